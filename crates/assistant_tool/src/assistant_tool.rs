@@ -1,3 +1,4 @@
+mod extension_tool;
 mod tool_registry;
 mod tool_working_set;
 
@@ -7,11 +8,13 @@ use anyhow::Result;
 use gpui::{AppContext, Task, WeakView, WindowContext};
 use workspace::Workspace;
 
+pub use crate::extension_tool::*;
 pub use crate::tool_registry::*;
 pub use crate::tool_working_set::*;
 
 pub fn init(cx: &mut AppContext) {
     ToolRegistry::default_global(cx);
+    extension_tool::init(cx);
 }
 
 /// A tool that can be used by a language model.
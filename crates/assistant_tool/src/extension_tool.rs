@@ -0,0 +1,97 @@
+use std::sync::Arc;
+
+use anyhow::Result;
+use extension::{Extension, ExtensionHostProxy, ExtensionToolProxy, ProjectDelegate};
+use gpui::{AppContext, Task, WeakView, WindowContext};
+use workspace::Workspace;
+
+use crate::{Tool, ToolRegistry};
+
+pub fn init(cx: &mut AppContext) {
+    let proxy = ExtensionHostProxy::default_global(cx);
+    proxy.register_tool_proxy(ToolRegistryProxy {
+        tool_registry: ToolRegistry::global(cx),
+    });
+}
+
+struct ToolRegistryProxy {
+    tool_registry: Arc<ToolRegistry>,
+}
+
+impl ExtensionToolProxy for ToolRegistryProxy {
+    fn register_tool(&self, extension: Arc<dyn Extension>, tool: extension::Tool) {
+        self.tool_registry
+            .register_tool(ExtensionTool::new(extension, tool));
+    }
+
+    fn unregister_tool(&self, tool_name: Arc<str>) {
+        self.tool_registry.unregister_tool_by_name(&tool_name);
+    }
+}
+
+struct ExtensionProject {
+    worktree_ids: Vec<u64>,
+}
+
+impl ProjectDelegate for ExtensionProject {
+    fn worktree_ids(&self) -> Vec<u64> {
+        self.worktree_ids.clone()
+    }
+}
+
+/// A [`Tool`] implemented by an extension.
+pub struct ExtensionTool {
+    extension: Arc<dyn Extension>,
+    tool: extension::Tool,
+}
+
+impl ExtensionTool {
+    pub fn new(extension: Arc<dyn Extension>, tool: extension::Tool) -> Self {
+        Self { extension, tool }
+    }
+}
+
+impl Tool for ExtensionTool {
+    fn name(&self) -> String {
+        self.tool.name.clone()
+    }
+
+    fn description(&self) -> String {
+        self.tool.description.clone()
+    }
+
+    fn input_schema(&self) -> serde_json::Value {
+        match &self.tool.input_schema {
+            serde_json::Value::Object(map) if !map.is_empty() => self.tool.input_schema.clone(),
+            _ => serde_json::json!({ "type": "object", "properties": {} }),
+        }
+    }
+
+    fn run(
+        self: Arc<Self>,
+        input: serde_json::Value,
+        workspace: WeakView<Workspace>,
+        cx: &mut WindowContext,
+    ) -> Task<Result<String>> {
+        let project = match workspace.update(cx, |workspace, cx| {
+            Arc::new(ExtensionProject {
+                worktree_ids: workspace
+                    .project()
+                    .read(cx)
+                    .visible_worktrees(cx)
+                    .map(|worktree| worktree.read(cx).id().to_proto())
+                    .collect(),
+            })
+        }) {
+            Ok(project) => project,
+            Err(error) => return Task::ready(Err(error)),
+        };
+
+        let tool = self.tool.clone();
+        cx.background_executor().spawn(async move {
+            self.extension
+                .run_tool(tool, input.to_string(), project)
+                .await
+        })
+    }
+}
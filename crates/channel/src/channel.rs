@@ -9,9 +9,12 @@ use std::sync::Arc;
 pub use channel_buffer::{ChannelBuffer, ChannelBufferEvent, ACKNOWLEDGE_DEBOUNCE_INTERVAL};
 pub use channel_chat::{
     mentions_to_proto, ChannelChat, ChannelChatEvent, ChannelMessage, ChannelMessageId,
-    MessageParams,
+    ChannelMessageReaction, ChannelMessageSnippet, MessageParams,
+};
+pub use channel_store::{
+    Channel, ChannelEvent, ChannelMembership, ChannelMessageSearch, ChannelMessageSearchResults,
+    ChannelStore,
 };
-pub use channel_store::{Channel, ChannelEvent, ChannelMembership, ChannelStore};
 
 #[cfg(test)]
 mod channel_store_tests;
@@ -41,6 +41,8 @@ pub struct MessageParams {
     pub text: String,
     pub mentions: Vec<(Range<usize>, UserId)>,
     pub reply_to_message_id: Option<u64>,
+    pub thread_id: Option<u64>,
+    pub snippet: Option<ChannelMessageSnippet>,
 }
 
 #[derive(Clone, Debug)]
@@ -53,6 +55,28 @@ pub struct ChannelMessage {
     pub mentions: Vec<(Range<usize>, UserId)>,
     pub reply_to_message_id: Option<u64>,
     pub edited_at: Option<OffsetDateTime>,
+    /// The id of the message that started the thread this message was posted in.
+    pub thread_id: Option<u64>,
+    pub reactions: Vec<ChannelMessageReaction>,
+    pub snippet: Option<ChannelMessageSnippet>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ChannelMessageReaction {
+    pub emoji: String,
+    pub user_ids: Vec<UserId>,
+}
+
+/// A range of lines from a file in a project, shared alongside a chat message.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ChannelMessageSnippet {
+    /// The path of the file, starting with the name of its worktree.
+    pub path: String,
+    pub start_row: u32,
+    pub end_row: u32,
+    /// The commit that was checked out when the snippet was shared, if the file is in a git repository.
+    pub commit_sha: Option<String>,
+    pub text: String,
 }
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
@@ -100,6 +124,7 @@ pub fn init(client: &AnyProtoClient) {
     client.add_model_message_handler(ChannelChat::handle_message_sent);
     client.add_model_message_handler(ChannelChat::handle_message_removed);
     client.add_model_message_handler(ChannelChat::handle_message_updated);
+    client.add_model_message_handler(ChannelChat::handle_reactions_updated);
 }
 
 impl ChannelChat {
@@ -173,7 +198,7 @@ impl ChannelChat {
         message: MessageParams,
         cx: &mut ModelContext<Self>,
     ) -> Result<Task<Result<u64>>> {
-        if message.text.trim().is_empty() {
+        if message.text.trim().is_empty() && message.snippet.is_none() {
             Err(anyhow!("message body can't be empty"))?;
         }
 
@@ -197,6 +222,9 @@ impl ChannelChat {
                     nonce,
                     reply_to_message_id: message.reply_to_message_id,
                     edited_at: None,
+                    thread_id: message.thread_id,
+                    reactions: Vec::new(),
+                    snippet: message.snippet.clone(),
                 },
                 &(),
             ),
@@ -215,6 +243,8 @@ impl ChannelChat {
                 nonce: Some(nonce.into()),
                 mentions: mentions_to_proto(&message.mentions),
                 reply_to_message_id: message.reply_to_message_id,
+                thread_id: message.thread_id,
+                snippet: message.snippet.map(|snippet| snippet.to_proto()),
             });
             let response = request.await?;
             drop(outgoing_message_guard);
@@ -274,6 +304,28 @@ impl ChannelChat {
         }))
     }
 
+    /// Adds the current user's reaction to a message, or removes it if they already reacted with
+    /// the same emoji.
+    pub fn toggle_reaction(
+        &mut self,
+        message_id: u64,
+        emoji: String,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<()>> {
+        let request = self.rpc.request(proto::ToggleChannelMessageReaction {
+            channel_id: self.channel_id.0,
+            message_id,
+            emoji,
+        });
+        cx.spawn(move |this, mut cx| async move {
+            let response = request.await?;
+            this.update(&mut cx, |this, cx| {
+                this.reactions_updated(message_id, reactions_from_proto(response.reactions), cx)
+            })?;
+            Ok(())
+        })
+    }
+
     pub fn load_more_messages(&mut self, cx: &mut ModelContext<Self>) -> Option<Task<Option<()>>> {
         if self.loaded_all_messages {
             return None;
@@ -319,6 +371,24 @@ impl ChannelChat {
         })
     }
 
+    /// Returns the index of a message, if it's already stored locally.
+    pub fn message_ix(&self, id: u64) -> Option<usize> {
+        let message_id = ChannelMessageId::Saved(id);
+        let mut cursor = self.messages.cursor::<(ChannelMessageId, Count)>(&());
+        cursor.seek(&message_id, Bias::Left, &());
+        cursor
+            .item()
+            .filter(|message| message.id == message_id)
+            .map(|_| cursor.start().1 .0)
+    }
+
+    /// Returns the loaded replies in the thread started by the given message, oldest first.
+    pub fn thread_replies(&self, root_id: u64) -> impl Iterator<Item = &ChannelMessage> {
+        self.messages
+            .iter()
+            .filter(move |message| message.thread_id == Some(root_id))
+    }
+
     /// Load all of the chat messages since a certain message id.
     ///
     /// For now, we always maintain a suffix of the channel's messages.
@@ -405,14 +475,11 @@ impl ChannelChat {
 
         let missing_ancestors = loaded_messages
             .iter()
-            .filter_map(|message| {
-                if let Some(ancestor_id) = message.reply_to_message_id {
-                    if !loaded_message_ids.contains(&ancestor_id) {
-                        return Some(ancestor_id);
-                    }
-                }
-                None
-            })
+            .flat_map(|message| [message.reply_to_message_id, message.thread_id])
+            .flatten()
+            .filter(|ancestor_id| !loaded_message_ids.contains(ancestor_id))
+            .collect::<HashSet<_>>()
+            .into_iter()
             .collect::<Vec<_>>();
 
         let loaded_ancestors = if missing_ancestors.is_empty() {
@@ -469,6 +536,8 @@ impl ChannelChat {
                         mentions: mentions_to_proto(&pending_message.mentions),
                         nonce: Some(pending_message.nonce.into()),
                         reply_to_message_id: pending_message.reply_to_message_id,
+                        thread_id: pending_message.thread_id,
+                        snippet: pending_message.snippet.map(|snippet| snippet.to_proto()),
                     });
                     let response = request.await?;
                     let message = ChannelMessage::from_proto(
@@ -586,8 +655,27 @@ impl ChannelChat {
         Ok(())
     }
 
+    async fn handle_reactions_updated(
+        this: Model<Self>,
+        message: TypedEnvelope<proto::ChannelMessageReactionsUpdated>,
+        mut cx: AsyncAppContext,
+    ) -> Result<()> {
+        this.update(&mut cx, |this, cx| {
+            this.reactions_updated(
+                message.payload.message_id,
+                reactions_from_proto(message.payload.reactions),
+                cx,
+            )
+        })?;
+        Ok(())
+    }
+
     fn insert_messages(&mut self, messages: SumTree<ChannelMessage>, cx: &mut ModelContext<Self>) {
         if let Some((first_message, last_message)) = messages.first().zip(messages.last()) {
+            let thread_ids = messages
+                .iter()
+                .filter_map(|message| message.thread_id)
+                .collect::<HashSet<_>>();
             let nonces = messages
                 .cursor::<()>(&())
                 .map(|m| m.nonce)
@@ -641,6 +729,16 @@ impl ChannelChat {
                 new_count,
             });
 
+            // Replies are displayed beneath the message that started their thread.
+            for thread_id in thread_ids {
+                if let Some(message_ix) = self.message_ix(thread_id) {
+                    cx.emit(ChannelChatEvent::UpdateMessage {
+                        message_id: ChannelMessageId::Saved(thread_id),
+                        message_ix,
+                    });
+                }
+            }
+
             cx.notify();
         }
     }
@@ -684,15 +782,39 @@ impl ChannelChat {
         mentions: Vec<(Range<usize>, u64)>,
         edited_at: Option<OffsetDateTime>,
         cx: &mut ModelContext<Self>,
+    ) {
+        self.edit_message(id, cx, |message| {
+            message.body = body;
+            message.mentions = mentions;
+            message.edited_at = edited_at;
+        });
+    }
+
+    fn reactions_updated(
+        &mut self,
+        id: u64,
+        reactions: Vec<ChannelMessageReaction>,
+        cx: &mut ModelContext<Self>,
+    ) {
+        self.edit_message(ChannelMessageId::Saved(id), cx, |message| {
+            message.reactions = reactions;
+        });
+    }
+
+    fn edit_message(
+        &mut self,
+        id: ChannelMessageId,
+        cx: &mut ModelContext<Self>,
+        edit: impl FnOnce(&mut ChannelMessage),
     ) {
         let mut cursor = self.messages.cursor::<ChannelMessageId>(&());
         let mut messages = cursor.slice(&id, Bias::Left, &());
         let ix = messages.summary().count;
 
         if let Some(mut message_to_update) = cursor.item().cloned() {
-            message_to_update.body = body;
-            message_to_update.mentions = mentions;
-            message_to_update.edited_at = edited_at;
+            if message_to_update.id == id {
+                edit(&mut message_to_update);
+            }
             messages.push(message_to_update, &());
             cursor.next(&());
         }
@@ -760,6 +882,9 @@ impl ChannelMessage {
                 .into(),
             reply_to_message_id: message.reply_to_message_id,
             edited_at,
+            thread_id: message.thread_id,
+            reactions: reactions_from_proto(message.reactions),
+            snippet: message.snippet.map(ChannelMessageSnippet::from_proto),
         })
     }
 
@@ -792,6 +917,38 @@ impl ChannelMessage {
     }
 }
 
+impl ChannelMessageSnippet {
+    pub fn from_proto(snippet: proto::ChatSnippet) -> Self {
+        Self {
+            path: snippet.path,
+            start_row: snippet.start_row,
+            end_row: snippet.end_row,
+            commit_sha: snippet.commit_sha,
+            text: snippet.text,
+        }
+    }
+
+    pub fn to_proto(&self) -> proto::ChatSnippet {
+        proto::ChatSnippet {
+            path: self.path.clone(),
+            start_row: self.start_row,
+            end_row: self.end_row,
+            commit_sha: self.commit_sha.clone(),
+            text: self.text.clone(),
+        }
+    }
+}
+
+fn reactions_from_proto(reactions: Vec<proto::ChatReaction>) -> Vec<ChannelMessageReaction> {
+    reactions
+        .into_iter()
+        .map(|reaction| ChannelMessageReaction {
+            emoji: reaction.emoji,
+            user_ids: reaction.user_ids,
+        })
+        .collect()
+}
+
 pub fn mentions_to_proto(mentions: &[(Range<usize>, UserId)]) -> Vec<proto::ChatMention> {
     mentions
         .iter()
@@ -862,6 +1019,8 @@ impl<'a> From<&'a str> for MessageParams {
             text: value.into(),
             mentions: Vec::new(),
             reply_to_message_id: None,
+            thread_id: None,
+            snippet: None,
         }
     }
 }
@@ -17,6 +17,7 @@ use rpc::{
 };
 use settings::Settings;
 use std::{mem, sync::Arc, time::Duration};
+use time::OffsetDateTime;
 use util::{maybe, ResultExt};
 
 pub const RECONNECT_TIMEOUT: Duration = Duration::from_secs(30);
@@ -59,6 +60,24 @@ pub struct Channel {
     pub parent_path: Vec<ChannelId>,
 }
 
+/// A full-text search over the chat history of the channels the current user belongs to.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ChannelMessageSearch {
+    pub query: String,
+    /// Restricts the search to a single channel.
+    pub channel_id: Option<ChannelId>,
+    pub sender_id: Option<UserId>,
+    pub sent_after: Option<OffsetDateTime>,
+    pub sent_before: Option<OffsetDateTime>,
+}
+
+pub struct ChannelMessageSearchResults {
+    /// The matching messages and the channels they were sent in, newest first.
+    pub messages: Vec<(ChannelId, ChannelMessage)>,
+    /// Whether there are no older matching messages.
+    pub done: bool,
+}
+
 #[derive(Default, Debug)]
 pub struct ChannelState {
     latest_chat_message: Option<u64>,
@@ -320,6 +339,38 @@ impl ChannelStore {
         )
     }
 
+    /// Searches the chat history, returning the matching messages older than
+    /// `before_message_id`.
+    pub fn search_messages(
+        &self,
+        search: &ChannelMessageSearch,
+        before_message_id: Option<u64>,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<ChannelMessageSearchResults>> {
+        let request = self.client.request(proto::SearchChannelMessages {
+            query: search.query.clone(),
+            channel_id: search.channel_id.map(|channel_id| channel_id.0),
+            sender_id: search.sender_id,
+            sent_after: search.sent_after.map(|t| t.unix_timestamp() as u64),
+            sent_before: search.sent_before.map(|t| t.unix_timestamp() as u64),
+            before_message_id,
+        });
+        let user_store = self.user_store.clone();
+        cx.spawn(|_, mut cx| async move {
+            let response = request.await?;
+            let (channel_ids, messages): (Vec<_>, Vec<_>) = response
+                .results
+                .into_iter()
+                .filter_map(|result| Some((ChannelId(result.channel_id), result.message?)))
+                .unzip();
+            let messages = ChannelMessage::from_proto_vec(messages, &user_store, &mut cx).await?;
+            Ok(ChannelMessageSearchResults {
+                messages: channel_ids.into_iter().zip(messages).collect(),
+                done: response.done,
+            })
+        })
+    }
+
     pub fn fetch_channel_messages(
         &self,
         message_ids: Vec<u64>,
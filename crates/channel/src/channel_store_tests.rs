@@ -187,6 +187,9 @@ async fn test_channel_messages(cx: &mut TestAppContext) {
                     nonce: Some(1.into()),
                     reply_to_message_id: None,
                     edited_at: None,
+                    reactions: vec![],
+                    thread_id: None,
+                    snippet: None,
                 },
                 proto::ChannelMessage {
                     id: 11,
@@ -197,6 +200,9 @@ async fn test_channel_messages(cx: &mut TestAppContext) {
                     nonce: Some(2.into()),
                     reply_to_message_id: None,
                     edited_at: None,
+                    reactions: vec![],
+                    thread_id: None,
+                    snippet: None,
                 },
             ],
             done: false,
@@ -246,6 +252,9 @@ async fn test_channel_messages(cx: &mut TestAppContext) {
             nonce: Some(3.into()),
             reply_to_message_id: None,
             edited_at: None,
+            reactions: vec![],
+            thread_id: None,
+            snippet: None,
         }),
     });
 
@@ -301,6 +310,9 @@ async fn test_channel_messages(cx: &mut TestAppContext) {
                     mentions: vec![],
                     reply_to_message_id: None,
                     edited_at: None,
+                    reactions: vec![],
+                    thread_id: None,
+                    snippet: None,
                 },
                 proto::ChannelMessage {
                     id: 9,
@@ -311,6 +323,9 @@ async fn test_channel_messages(cx: &mut TestAppContext) {
                     mentions: vec![],
                     reply_to_message_id: None,
                     edited_at: None,
+                    reactions: vec![],
+                    thread_id: None,
+                    snippet: None,
                 },
             ],
         },
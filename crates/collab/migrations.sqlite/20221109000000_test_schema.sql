@@ -225,10 +225,13 @@ CREATE TABLE IF NOT EXISTS "channel_messages" (
     "sent_at" TIMESTAMP,
     "edited_at" TIMESTAMP,
     "nonce" BLOB NOT NULL,
-    "reply_to_message_id" INTEGER DEFAULT NULL
+    "reply_to_message_id" INTEGER DEFAULT NULL,
+    "thread_id" INTEGER DEFAULT NULL REFERENCES channel_messages (id) ON DELETE CASCADE
 );
 CREATE INDEX "index_channel_messages_on_channel_id" ON "channel_messages" ("channel_id");
 CREATE UNIQUE INDEX "index_channel_messages_on_sender_id_nonce" ON "channel_messages" ("sender_id", "nonce");
+CREATE INDEX "index_channel_messages_on_thread_id" ON "channel_messages" ("thread_id");
+CREATE INDEX "index_channel_messages_on_sender_id_and_sent_at" ON "channel_messages" ("sender_id", "sent_at");
 
 CREATE TABLE "channel_message_mentions" (
     "message_id" INTEGER NOT NULL REFERENCES channel_messages (id) ON DELETE CASCADE,
@@ -238,6 +241,41 @@ CREATE TABLE "channel_message_mentions" (
     PRIMARY KEY(message_id, start_offset)
 );
 
+CREATE TABLE "channel_message_reactions" (
+    "message_id" INTEGER NOT NULL REFERENCES channel_messages (id) ON DELETE CASCADE,
+    "user_id" INTEGER NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+    "emoji" VARCHAR NOT NULL,
+    PRIMARY KEY(message_id, user_id, emoji)
+);
+
+CREATE TABLE "channel_message_snippets" (
+    "message_id" INTEGER PRIMARY KEY REFERENCES channel_messages (id) ON DELETE CASCADE,
+    "path" VARCHAR NOT NULL,
+    "start_row" INTEGER NOT NULL,
+    "end_row" INTEGER NOT NULL,
+    "commit_sha" VARCHAR,
+    "text" TEXT NOT NULL
+);
+
+CREATE TABLE "review_threads" (
+    "id" INTEGER PRIMARY KEY AUTOINCREMENT,
+    "channel_id" INTEGER NOT NULL REFERENCES channels (id) ON DELETE CASCADE,
+    "path" VARCHAR NOT NULL,
+    "start_row" INTEGER NOT NULL,
+    "end_row" INTEGER NOT NULL,
+    "resolved" BOOLEAN NOT NULL DEFAULT FALSE
+);
+CREATE INDEX "index_review_threads_on_channel_id" ON "review_threads" ("channel_id");
+
+CREATE TABLE "review_comments" (
+    "id" INTEGER PRIMARY KEY AUTOINCREMENT,
+    "thread_id" INTEGER NOT NULL REFERENCES review_threads (id) ON DELETE CASCADE,
+    "sender_id" INTEGER NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+    "body" TEXT NOT NULL,
+    "sent_at" TIMESTAMP NOT NULL
+);
+CREATE INDEX "index_review_comments_on_thread_id" ON "review_comments" ("thread_id");
+
 CREATE TABLE "channel_members" (
     "id" INTEGER PRIMARY KEY AUTOINCREMENT,
     "channel_id" INTEGER NOT NULL REFERENCES channels (id) ON DELETE CASCADE,
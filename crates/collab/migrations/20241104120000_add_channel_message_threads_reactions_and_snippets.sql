@@ -0,0 +1,18 @@
+ALTER TABLE channel_messages ADD thread_id INTEGER DEFAULT NULL REFERENCES channel_messages (id) ON DELETE CASCADE;
+CREATE INDEX "index_channel_messages_on_thread_id" ON "channel_messages" ("thread_id");
+
+CREATE TABLE "channel_message_reactions" (
+    "message_id" INTEGER NOT NULL REFERENCES channel_messages (id) ON DELETE CASCADE,
+    "user_id" INTEGER NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+    "emoji" VARCHAR NOT NULL,
+    PRIMARY KEY(message_id, user_id, emoji)
+);
+
+CREATE TABLE "channel_message_snippets" (
+    "message_id" INTEGER PRIMARY KEY REFERENCES channel_messages (id) ON DELETE CASCADE,
+    "path" VARCHAR NOT NULL,
+    "start_row" INTEGER NOT NULL,
+    "end_row" INTEGER NOT NULL,
+    "commit_sha" VARCHAR,
+    "text" TEXT NOT NULL
+);
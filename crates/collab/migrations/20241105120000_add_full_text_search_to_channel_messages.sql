@@ -0,0 +1,2 @@
+CREATE INDEX "index_channel_messages_on_body_tsvector" ON "channel_messages" USING GIN (to_tsvector('english', "body"));
+CREATE INDEX "index_channel_messages_on_sender_id_and_sent_at" ON "channel_messages" ("sender_id", "sent_at");
@@ -0,0 +1,18 @@
+CREATE TABLE "review_threads" (
+    "id" SERIAL PRIMARY KEY,
+    "channel_id" INTEGER NOT NULL REFERENCES channels (id) ON DELETE CASCADE,
+    "path" VARCHAR NOT NULL,
+    "start_row" INTEGER NOT NULL,
+    "end_row" INTEGER NOT NULL,
+    "resolved" BOOLEAN NOT NULL DEFAULT FALSE
+);
+CREATE INDEX "index_review_threads_on_channel_id" ON "review_threads" ("channel_id");
+
+CREATE TABLE "review_comments" (
+    "id" SERIAL PRIMARY KEY,
+    "thread_id" INTEGER NOT NULL REFERENCES review_threads (id) ON DELETE CASCADE,
+    "sender_id" INTEGER NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+    "body" TEXT NOT NULL,
+    "sent_at" TIMESTAMP NOT NULL
+);
+CREATE INDEX "index_review_comments_on_thread_id" ON "review_comments" ("thread_id");
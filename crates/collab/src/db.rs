@@ -473,6 +473,7 @@ pub type NotificationBatch = Vec<(UserId, proto::Notification)>;
 
 pub struct CreatedChannelMessage {
     pub message_id: MessageId,
+    pub thread_id: Option<MessageId>,
     pub participant_connection_ids: HashSet<ConnectionId>,
     pub notifications: NotificationBatch,
 }
@@ -482,11 +483,17 @@ pub struct UpdatedChannelMessage {
     pub participant_connection_ids: Vec<ConnectionId>,
     pub notifications: NotificationBatch,
     pub reply_to_message_id: Option<MessageId>,
+    pub thread_id: Option<MessageId>,
     pub timestamp: PrimitiveDateTime,
     pub deleted_mention_notification_ids: Vec<NotificationId>,
     pub updated_mention_notifications: Vec<rpc::proto::Notification>,
 }
 
+pub struct UpdatedChannelMessageReactions {
+    pub participant_connection_ids: Vec<ConnectionId>,
+    pub reactions: Vec<rpc::proto::ChatReaction>,
+}
+
 #[derive(Clone, Debug, PartialEq, Eq, FromQueryResult, Serialize, Deserialize)]
 pub struct Invite {
     pub email_address: String,
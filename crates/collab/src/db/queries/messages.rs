@@ -1,6 +1,6 @@
 use super::*;
 use rpc::Notification;
-use sea_orm::{SelectColumns, TryInsertResult};
+use sea_orm::{DbBackend, SelectColumns, TryInsertResult};
 use time::OffsetDateTime;
 use util::ResultExt;
 
@@ -143,6 +143,98 @@ impl Database {
         .await
     }
 
+    /// Returns the messages matching a full-text search query, newest first, from the specified
+    /// channel or from every channel the user belongs to.
+    pub async fn search_channel_messages(
+        &self,
+        user_id: UserId,
+        request: &proto::SearchChannelMessages,
+        limit: usize,
+    ) -> Result<Vec<proto::ChannelMessageSearchResult>> {
+        self.transaction(|tx| async move {
+            let channel_ids = if let Some(channel_id) = request.channel_id {
+                let channel = self
+                    .get_channel_internal(ChannelId::from_proto(channel_id), &tx)
+                    .await?;
+                self.check_user_is_channel_participant(&channel, user_id, &tx)
+                    .await?;
+                vec![channel.id]
+            } else {
+                self.get_user_channels(user_id, None, false, &tx)
+                    .await?
+                    .channels
+                    .into_iter()
+                    .map(|channel| channel.id)
+                    .collect()
+            };
+
+            let mut condition =
+                Condition::all().add(channel_message::Column::ChannelId.is_in(channel_ids));
+            if cfg!(any(test, feature = "sqlite"))
+                && self.pool.get_database_backend() == DbBackend::Sqlite
+            {
+                for word in request.query.split_whitespace() {
+                    let word = word.replace(['%', '_'], "").to_uppercase();
+                    condition = condition.add(Expr::cust_with_values(
+                        "UPPER(body) LIKE ?",
+                        [format!("%{word}%")],
+                    ));
+                }
+            } else {
+                condition = condition.add(Expr::cust_with_values(
+                    "to_tsvector('english', body) @@ websearch_to_tsquery('english', $1)",
+                    [request.query.clone()],
+                ));
+            }
+            if let Some(sender_id) = request.sender_id {
+                condition = condition
+                    .add(channel_message::Column::SenderId.eq(UserId::from_proto(sender_id)));
+            }
+            if let Some(sent_after) = request.sent_after {
+                let sent_after = OffsetDateTime::from_unix_timestamp(sent_after as i64)
+                    .map_err(|error| anyhow!(error))?;
+                condition = condition.add(channel_message::Column::SentAt.gte(
+                    time::PrimitiveDateTime::new(sent_after.date(), sent_after.time()),
+                ));
+            }
+            if let Some(sent_before) = request.sent_before {
+                let sent_before = OffsetDateTime::from_unix_timestamp(sent_before as i64)
+                    .map_err(|error| anyhow!(error))?;
+                condition = condition.add(channel_message::Column::SentAt.lt(
+                    time::PrimitiveDateTime::new(sent_before.date(), sent_before.time()),
+                ));
+            }
+            if let Some(before_message_id) = request.before_message_id {
+                condition = condition
+                    .add(channel_message::Column::Id.lt(MessageId::from_proto(before_message_id)));
+            }
+
+            let rows = channel_message::Entity::find()
+                .filter(condition)
+                .order_by_desc(channel_message::Column::Id)
+                .limit(limit as u64)
+                .all(&*tx)
+                .await?;
+            let channel_ids_by_message_id = rows
+                .iter()
+                .map(|row| (row.id.to_proto(), row.channel_id.to_proto()))
+                .collect::<HashMap<_, _>>();
+
+            let mut messages = self.load_channel_messages(rows, &tx).await?;
+            messages.reverse();
+            Ok(messages
+                .into_iter()
+                .filter_map(|message| {
+                    Some(proto::ChannelMessageSearchResult {
+                        channel_id: *channel_ids_by_message_id.get(&message.id)?,
+                        message: Some(message),
+                    })
+                })
+                .collect())
+        })
+        .await
+    }
+
     async fn load_channel_messages(
         &self,
         rows: Vec<channel_message::Model>,
@@ -166,11 +258,30 @@ impl Database {
                     edited_at: row
                         .edited_at
                         .map(|t| t.assume_utc().unix_timestamp() as u64),
+                    reactions: vec![],
+                    thread_id: row.thread_id.map(|id| id.to_proto()),
+                    snippet: None,
                 }
             })
             .collect::<Vec<_>>();
         messages.reverse();
 
+        let message_ids = messages.iter().map(|m| m.id).collect::<Vec<_>>();
+        let mut reactions = self
+            .channel_message_reactions(message_ids.iter().copied(), tx)
+            .await?;
+        let mut snippets = channel_message_snippet::Entity::find()
+            .filter(channel_message_snippet::Column::MessageId.is_in(message_ids))
+            .all(tx)
+            .await?
+            .into_iter()
+            .map(|snippet| (snippet.message_id.to_proto(), snippet_to_proto(snippet)))
+            .collect::<HashMap<_, _>>();
+        for message in &mut messages {
+            message.reactions = reactions.remove(&message.id).unwrap_or_default();
+            message.snippet = snippets.remove(&message.id);
+        }
+
         let mut mentions = channel_message_mention::Entity::find()
             .filter(channel_message_mention::Column::MessageId.is_in(messages.iter().map(|m| m.id)))
             .order_by_asc(channel_message_mention::Column::MessageId)
@@ -203,6 +314,36 @@ impl Database {
         Ok(messages)
     }
 
+    /// Returns the reactions to the given messages, grouped by emoji.
+    async fn channel_message_reactions(
+        &self,
+        message_ids: impl IntoIterator<Item = u64>,
+        tx: &DatabaseTransaction,
+    ) -> Result<HashMap<u64, Vec<proto::ChatReaction>>> {
+        let rows = channel_message_reaction::Entity::find()
+            .filter(channel_message_reaction::Column::MessageId.is_in(message_ids))
+            .order_by_asc(channel_message_reaction::Column::MessageId)
+            .order_by_asc(channel_message_reaction::Column::Emoji)
+            .order_by_asc(channel_message_reaction::Column::UserId)
+            .all(tx)
+            .await?;
+
+        let mut reactions = HashMap::<u64, Vec<proto::ChatReaction>>::default();
+        for row in rows {
+            let message_reactions = reactions.entry(row.message_id.to_proto()).or_default();
+            match message_reactions.last_mut() {
+                Some(reaction) if reaction.emoji == row.emoji => {
+                    reaction.user_ids.push(row.user_id.to_proto());
+                }
+                _ => message_reactions.push(proto::ChatReaction {
+                    emoji: row.emoji,
+                    user_ids: vec![row.user_id.to_proto()],
+                }),
+            }
+        }
+        Ok(reactions)
+    }
+
     fn format_mentions_to_entities(
         &self,
         message_id: MessageId,
@@ -239,12 +380,26 @@ impl Database {
         timestamp: OffsetDateTime,
         nonce: u128,
         reply_to_message_id: Option<MessageId>,
+        thread_id: Option<MessageId>,
+        snippet: Option<&proto::ChatSnippet>,
     ) -> Result<CreatedChannelMessage> {
         self.transaction(|tx| async move {
             let channel = self.get_channel_internal(channel_id, &tx).await?;
             self.check_user_is_channel_participant(&channel, user_id, &tx)
                 .await?;
 
+            // Replies to a reply in a thread belong to the thread of that reply.
+            let thread_id = if let Some(thread_id) = thread_id {
+                let thread_root = channel_message::Entity::find_by_id(thread_id)
+                    .filter(channel_message::Column::ChannelId.eq(channel_id))
+                    .one(&*tx)
+                    .await?
+                    .ok_or_else(|| anyhow!("no such thread"))?;
+                Some(thread_root.thread_id.unwrap_or(thread_root.id))
+            } else {
+                None
+            };
+
             let mut rows = channel_chat_participant::Entity::find()
                 .filter(channel_chat_participant::Column::ChannelId.eq(channel_id))
                 .stream(&*tx)
@@ -279,6 +434,7 @@ impl Database {
                 id: ActiveValue::NotSet,
                 reply_to_message_id: ActiveValue::Set(reply_to_message_id),
                 edited_at: ActiveValue::NotSet,
+                thread_id: ActiveValue::Set(thread_id),
             })
             .on_conflict(
                 OnConflict::columns([
@@ -307,6 +463,21 @@ impl Database {
                             .await?;
                     }
 
+                    if let Some(snippet) = snippet {
+                        channel_message_snippet::Entity::insert(
+                            channel_message_snippet::ActiveModel {
+                                message_id: ActiveValue::Set(message_id),
+                                path: ActiveValue::Set(snippet.path.clone()),
+                                start_row: ActiveValue::Set(snippet.start_row as i32),
+                                end_row: ActiveValue::Set(snippet.end_row as i32),
+                                commit_sha: ActiveValue::Set(snippet.commit_sha.clone()),
+                                text: ActiveValue::Set(snippet.text.clone()),
+                            },
+                        )
+                        .exec_without_returning(&*tx)
+                        .await?;
+                    }
+
                     for mentioned_user in mentioned_user_ids {
                         notifications.extend(
                             self.create_notification(
@@ -338,6 +509,7 @@ impl Database {
 
             Ok(CreatedChannelMessage {
                 message_id,
+                thread_id,
                 participant_connection_ids,
                 notifications,
             })
@@ -615,6 +787,7 @@ impl Database {
                 body: ActiveValue::Set(body.to_string()),
                 edited_at: ActiveValue::Set(Some(edited_at)),
                 reply_to_message_id: ActiveValue::Unchanged(channel_message.reply_to_message_id),
+                thread_id: ActiveValue::Unchanged(channel_message.thread_id),
                 id: ActiveValue::Unchanged(message_id),
                 channel_id: ActiveValue::Unchanged(channel_id),
                 sender_id: ActiveValue::Unchanged(user_id),
@@ -713,6 +886,7 @@ impl Database {
                 participant_connection_ids,
                 notifications,
                 reply_to_message_id: channel_message.reply_to_message_id,
+                thread_id: channel_message.thread_id,
                 timestamp: channel_message.sent_at,
                 deleted_mention_notification_ids: deleted_notification_ids
                     .into_iter()
@@ -722,4 +896,71 @@ impl Database {
         })
         .await
     }
+
+    /// Adds a user's reaction to a channel message, or removes it if they had already reacted
+    /// to the message with the same emoji.
+    pub async fn toggle_channel_message_reaction(
+        &self,
+        channel_id: ChannelId,
+        message_id: MessageId,
+        user_id: UserId,
+        emoji: &str,
+    ) -> Result<UpdatedChannelMessageReactions> {
+        self.transaction(|tx| async move {
+            let channel = self.get_channel_internal(channel_id, &tx).await?;
+            self.check_user_is_channel_participant(&channel, user_id, &tx)
+                .await?;
+
+            channel_message::Entity::find_by_id(message_id)
+                .filter(channel_message::Column::ChannelId.eq(channel_id))
+                .one(&*tx)
+                .await?
+                .ok_or_else(|| anyhow!("no such message"))?;
+
+            let result = channel_message_reaction::Entity::delete_many()
+                .filter(channel_message_reaction::Column::MessageId.eq(message_id))
+                .filter(channel_message_reaction::Column::UserId.eq(user_id))
+                .filter(channel_message_reaction::Column::Emoji.eq(emoji))
+                .exec(&*tx)
+                .await?;
+            if result.rows_affected == 0 {
+                channel_message_reaction::Entity::insert(channel_message_reaction::ActiveModel {
+                    message_id: ActiveValue::Set(message_id),
+                    user_id: ActiveValue::Set(user_id),
+                    emoji: ActiveValue::Set(emoji.to_string()),
+                })
+                .exec_without_returning(&*tx)
+                .await?;
+            }
+
+            let participant_connection_ids = channel_chat_participant::Entity::find()
+                .filter(channel_chat_participant::Column::ChannelId.eq(channel_id))
+                .all(&*tx)
+                .await?
+                .into_iter()
+                .map(|row| row.connection())
+                .collect();
+            let reactions = self
+                .channel_message_reactions([message_id.to_proto()], &tx)
+                .await?
+                .remove(&message_id.to_proto())
+                .unwrap_or_default();
+
+            Ok(UpdatedChannelMessageReactions {
+                participant_connection_ids,
+                reactions,
+            })
+        })
+        .await
+    }
+}
+
+fn snippet_to_proto(snippet: channel_message_snippet::Model) -> proto::ChatSnippet {
+    proto::ChatSnippet {
+        path: snippet.path,
+        start_row: snippet.start_row as u32,
+        end_row: snippet.end_row as u32,
+        commit_sha: snippet.commit_sha,
+        text: snippet.text,
+    }
 }
@@ -0,0 +1,287 @@
+use super::*;
+use crate::rpc::MAX_MESSAGE_LEN;
+use time::OffsetDateTime;
+
+impl Database {
+    /// Returns the review threads on the files of the given project, including those left in
+    /// earlier sessions of the channel the project is shared in.
+    pub async fn get_review_threads(
+        &self,
+        project_id: ProjectId,
+        connection_id: ConnectionId,
+    ) -> Result<Vec<proto::ReviewThread>> {
+        self.transaction(|tx| async move {
+            let (channel_id, root_names) = self
+                .review_thread_scope(project_id, connection_id, &tx)
+                .await?;
+            // Projects shared outside of channels can't be reviewed.
+            let Some(channel_id) = channel_id else {
+                return Ok(Vec::new());
+            };
+            let threads = review_thread::Entity::find()
+                .filter(review_thread::Column::ChannelId.eq(channel_id))
+                .order_by_asc(review_thread::Column::Id)
+                .all(&*tx)
+                .await?
+                .into_iter()
+                .filter(|thread| is_path_in_worktrees(&thread.path, &root_names))
+                .collect();
+            self.review_threads_to_proto(threads, &tx).await
+        })
+        .await
+    }
+
+    /// Starts a review thread on a range of lines of a file in the given project.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn create_review_thread(
+        &self,
+        project_id: ProjectId,
+        connection_id: ConnectionId,
+        user_id: UserId,
+        path: &str,
+        start_row: u32,
+        end_row: u32,
+        body: &str,
+        timestamp: OffsetDateTime,
+    ) -> Result<proto::ReviewThread> {
+        self.transaction(|tx| async move {
+            let (channel_id, root_names) = self
+                .review_thread_scope(project_id, connection_id, &tx)
+                .await?;
+            let channel_id = channel_id
+                .ok_or_else(|| anyhow!("review comments are only available in channel calls"))?;
+            if !is_path_in_worktrees(path, &root_names) {
+                Err(anyhow!("path is not in the project"))?;
+            }
+            if start_row > end_row {
+                Err(anyhow!("invalid row range"))?;
+            }
+
+            let thread = review_thread::ActiveModel {
+                channel_id: ActiveValue::Set(channel_id),
+                path: ActiveValue::Set(path.to_string()),
+                start_row: ActiveValue::Set(start_row as i32),
+                end_row: ActiveValue::Set(end_row as i32),
+                resolved: ActiveValue::Set(false),
+                ..Default::default()
+            }
+            .insert(&*tx)
+            .await?;
+            insert_review_comment(thread.id, user_id, body, timestamp, &tx).await?;
+            self.review_thread_to_proto(thread, &tx).await
+        })
+        .await
+    }
+
+    /// Adds a comment to the end of a review thread.
+    pub async fn add_review_comment(
+        &self,
+        project_id: ProjectId,
+        connection_id: ConnectionId,
+        user_id: UserId,
+        thread_id: ReviewThreadId,
+        body: &str,
+        timestamp: OffsetDateTime,
+    ) -> Result<proto::ReviewThread> {
+        self.transaction(|tx| async move {
+            let thread = self
+                .review_thread(project_id, connection_id, thread_id, &tx)
+                .await?;
+            insert_review_comment(thread.id, user_id, body, timestamp, &tx).await?;
+            self.review_thread_to_proto(thread, &tx).await
+        })
+        .await
+    }
+
+    /// Marks a review thread as resolved, or reopens it.
+    pub async fn resolve_review_thread(
+        &self,
+        project_id: ProjectId,
+        connection_id: ConnectionId,
+        thread_id: ReviewThreadId,
+        resolved: bool,
+    ) -> Result<proto::ReviewThread> {
+        self.transaction(|tx| async move {
+            let thread = self
+                .review_thread(project_id, connection_id, thread_id, &tx)
+                .await?;
+            let thread = review_thread::ActiveModel {
+                id: ActiveValue::Unchanged(thread.id),
+                resolved: ActiveValue::Set(resolved),
+                ..Default::default()
+            }
+            .update(&*tx)
+            .await?;
+            self.review_thread_to_proto(thread, &tx).await
+        })
+        .await
+    }
+
+    /// Updates the lines a review thread is attached to, after the file was edited. Only the host
+    /// can do this, as the rows are those of the file on its disk.
+    pub async fn move_review_thread(
+        &self,
+        project_id: ProjectId,
+        connection_id: ConnectionId,
+        thread_id: ReviewThreadId,
+        start_row: u32,
+        end_row: u32,
+    ) -> Result<proto::ReviewThread> {
+        self.transaction(|tx| async move {
+            if start_row > end_row {
+                Err(anyhow!("invalid row range"))?;
+            }
+            let project = project::Entity::find_by_id(project_id)
+                .one(&*tx)
+                .await?
+                .ok_or_else(|| anyhow!("no such project"))?;
+            if project.host_connection()? != connection_id {
+                Err(anyhow!("only the host can move review threads"))?;
+            }
+            let thread = self
+                .review_thread(project_id, connection_id, thread_id, &tx)
+                .await?;
+            let thread = review_thread::ActiveModel {
+                id: ActiveValue::Unchanged(thread.id),
+                start_row: ActiveValue::Set(start_row as i32),
+                end_row: ActiveValue::Set(end_row as i32),
+                ..Default::default()
+            }
+            .update(&*tx)
+            .await?;
+            self.review_thread_to_proto(thread, &tx).await
+        })
+        .await
+    }
+
+    /// Returns the channel whose review threads are visible in the given project, if it's shared
+    /// in a channel's call, and the root names of the project's worktrees, which the paths of
+    /// those threads start with.
+    async fn review_thread_scope(
+        &self,
+        project_id: ProjectId,
+        connection_id: ConnectionId,
+        tx: &DatabaseTransaction,
+    ) -> Result<(Option<ChannelId>, Vec<String>)> {
+        let (project, _) = self
+            .access_project(project_id, connection_id, Capability::ReadOnly, tx)
+            .await?;
+        let room_id = project
+            .room_id
+            .ok_or_else(|| anyhow!("project is not shared in a call"))?;
+        let room = room::Entity::find_by_id(room_id)
+            .one(tx)
+            .await?
+            .ok_or_else(|| anyhow!("no such room"))?;
+        let root_names = worktree::Entity::find()
+            .filter(worktree::Column::ProjectId.eq(project_id))
+            .all(tx)
+            .await?
+            .into_iter()
+            .map(|worktree| worktree.root_name)
+            .collect();
+        Ok((room.channel_id, root_names))
+    }
+
+    async fn review_thread(
+        &self,
+        project_id: ProjectId,
+        connection_id: ConnectionId,
+        thread_id: ReviewThreadId,
+        tx: &DatabaseTransaction,
+    ) -> Result<review_thread::Model> {
+        let (channel_id, root_names) = self
+            .review_thread_scope(project_id, connection_id, tx)
+            .await?;
+        Ok(review_thread::Entity::find_by_id(thread_id)
+            .one(tx)
+            .await?
+            .filter(|thread| {
+                Some(thread.channel_id) == channel_id
+                    && is_path_in_worktrees(&thread.path, &root_names)
+            })
+            .ok_or_else(|| anyhow!("no such thread"))?)
+    }
+
+    async fn review_thread_to_proto(
+        &self,
+        thread: review_thread::Model,
+        tx: &DatabaseTransaction,
+    ) -> Result<proto::ReviewThread> {
+        let mut threads = self.review_threads_to_proto(vec![thread], tx).await?;
+        Ok(threads.pop().ok_or_else(|| anyhow!("no such thread"))?)
+    }
+
+    async fn review_threads_to_proto(
+        &self,
+        threads: Vec<review_thread::Model>,
+        tx: &DatabaseTransaction,
+    ) -> Result<Vec<proto::ReviewThread>> {
+        let mut comments_by_thread =
+            HashMap::<ReviewThreadId, Vec<proto::ReviewComment>>::default();
+        let mut comments = review_comment::Entity::find()
+            .filter(review_comment::Column::ThreadId.is_in(threads.iter().map(|thread| thread.id)))
+            .order_by_asc(review_comment::Column::Id)
+            .stream(tx)
+            .await?;
+        while let Some(comment) = comments.next().await {
+            let comment = comment?;
+            comments_by_thread
+                .entry(comment.thread_id)
+                .or_default()
+                .push(proto::ReviewComment {
+                    id: comment.id.to_proto(),
+                    sender_id: comment.sender_id.to_proto(),
+                    body: comment.body,
+                    timestamp: comment.sent_at.assume_utc().unix_timestamp() as u64,
+                });
+        }
+
+        Ok(threads
+            .into_iter()
+            .map(|thread| proto::ReviewThread {
+                id: thread.id.to_proto(),
+                comments: comments_by_thread.remove(&thread.id).unwrap_or_default(),
+                path: thread.path,
+                start_row: thread.start_row as u32,
+                end_row: thread.end_row as u32,
+                resolved: thread.resolved,
+            })
+            .collect())
+    }
+}
+
+async fn insert_review_comment(
+    thread_id: ReviewThreadId,
+    sender_id: UserId,
+    body: &str,
+    timestamp: OffsetDateTime,
+    tx: &DatabaseTransaction,
+) -> Result<()> {
+    if body.trim().is_empty() {
+        Err(anyhow!("comment can't be blank"))?;
+    }
+    if body.len() > MAX_MESSAGE_LEN {
+        Err(anyhow!("comment is too long"))?;
+    }
+    let timestamp = timestamp.to_offset(time::UtcOffset::UTC);
+    review_comment::ActiveModel {
+        thread_id: ActiveValue::Set(thread_id),
+        sender_id: ActiveValue::Set(sender_id),
+        body: ActiveValue::Set(body.to_string()),
+        sent_at: ActiveValue::Set(PrimitiveDateTime::new(timestamp.date(), timestamp.time())),
+        ..Default::default()
+    }
+    .insert(tx)
+    .await?;
+    Ok(())
+}
+
+/// Review threads are stored with the full path of their file, starting with the root name of
+/// its worktree, so that they're found again when the project is shared in a later call.
+fn is_path_in_worktrees(path: &str, root_names: &[String]) -> bool {
+    root_names.iter().any(|root_name| {
+        path.strip_prefix(root_name.as_str())
+            .map_or(false, |rest| rest.starts_with('/'))
+    })
+}
@@ -11,6 +11,8 @@ pub mod channel_chat_participant;
 pub mod channel_member;
 pub mod channel_message;
 pub mod channel_message_mention;
+pub mod channel_message_reaction;
+pub mod channel_message_snippet;
 pub mod contact;
 pub mod contributor;
 pub mod embedding;
@@ -27,6 +29,8 @@ pub mod processed_stripe_event;
 pub mod project;
 pub mod project_collaborator;
 pub mod rate_buckets;
+pub mod review_comment;
+pub mod review_thread;
 pub mod room;
 pub mod room_participant;
 pub mod server;
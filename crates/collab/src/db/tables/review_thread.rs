@@ -0,0 +1,42 @@
+use crate::db::{ChannelId, ReviewThreadId};
+use sea_orm::entity::prelude::*;
+
+/// A thread of review comments left on a range of lines of a file, in a project shared in a
+/// channel.
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "review_threads")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: ReviewThreadId,
+    pub channel_id: ChannelId,
+    pub path: String,
+    pub start_row: i32,
+    pub end_row: i32,
+    pub resolved: bool,
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::channel::Entity",
+        from = "Column::ChannelId",
+        to = "super::channel::Column::Id"
+    )]
+    Channel,
+    #[sea_orm(has_many = "super::review_comment::Entity")]
+    Comment,
+}
+
+impl Related<super::channel::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Channel.def()
+    }
+}
+
+impl Related<super::review_comment::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Comment.def()
+    }
+}
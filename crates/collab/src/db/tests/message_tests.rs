@@ -33,6 +33,8 @@ async fn test_channel_message_retrieval(db: &Arc<Database>) {
                 OffsetDateTime::now_utc(),
                 i,
                 None,
+                None,
+                None,
             )
             .await
             .unwrap()
@@ -108,6 +110,8 @@ async fn test_channel_message_nonces(db: &Arc<Database>) {
             OffsetDateTime::now_utc(),
             100,
             None,
+            None,
+            None,
         )
         .await
         .unwrap()
@@ -121,6 +125,8 @@ async fn test_channel_message_nonces(db: &Arc<Database>) {
             OffsetDateTime::now_utc(),
             200,
             None,
+            None,
+            None,
         )
         .await
         .unwrap()
@@ -134,6 +140,8 @@ async fn test_channel_message_nonces(db: &Arc<Database>) {
             OffsetDateTime::now_utc(),
             100,
             None,
+            None,
+            None,
         )
         .await
         .unwrap()
@@ -147,6 +155,8 @@ async fn test_channel_message_nonces(db: &Arc<Database>) {
             OffsetDateTime::now_utc(),
             200,
             None,
+            None,
+            None,
         )
         .await
         .unwrap()
@@ -163,6 +173,8 @@ async fn test_channel_message_nonces(db: &Arc<Database>) {
             OffsetDateTime::now_utc(),
             100,
             None,
+            None,
+            None,
         )
         .await
         .unwrap()
@@ -245,6 +257,8 @@ async fn test_unseen_channel_messages(db: &Arc<Database>) {
             OffsetDateTime::now_utc(),
             1,
             None,
+            None,
+            None,
         )
         .await
         .unwrap();
@@ -258,6 +272,8 @@ async fn test_unseen_channel_messages(db: &Arc<Database>) {
             OffsetDateTime::now_utc(),
             2,
             None,
+            None,
+            None,
         )
         .await
         .unwrap();
@@ -271,6 +287,8 @@ async fn test_unseen_channel_messages(db: &Arc<Database>) {
             OffsetDateTime::now_utc(),
             3,
             None,
+            None,
+            None,
         )
         .await
         .unwrap()
@@ -289,6 +307,8 @@ async fn test_unseen_channel_messages(db: &Arc<Database>) {
             OffsetDateTime::now_utc(),
             4,
             None,
+            None,
+            None,
         )
         .await
         .unwrap()
@@ -356,6 +376,8 @@ async fn test_channel_message_mentions(db: &Arc<Database>) {
         OffsetDateTime::now_utc(),
         1,
         None,
+        None,
+        None,
     )
     .await
     .unwrap();
@@ -367,6 +389,8 @@ async fn test_channel_message_mentions(db: &Arc<Database>) {
         OffsetDateTime::now_utc(),
         2,
         None,
+        None,
+        None,
     )
     .await
     .unwrap();
@@ -378,6 +402,8 @@ async fn test_channel_message_mentions(db: &Arc<Database>) {
         OffsetDateTime::now_utc(),
         3,
         None,
+        None,
+        None,
     )
     .await
     .unwrap();
@@ -389,6 +415,8 @@ async fn test_channel_message_mentions(db: &Arc<Database>) {
         OffsetDateTime::now_utc(),
         4,
         None,
+        None,
+        None,
     )
     .await
     .unwrap();
@@ -419,3 +447,270 @@ async fn test_channel_message_mentions(db: &Arc<Database>) {
         ]
     );
 }
+
+test_both_dbs!(
+    test_channel_message_threads_reactions_and_snippets,
+    test_channel_message_threads_reactions_and_snippets_postgres,
+    test_channel_message_threads_reactions_and_snippets_sqlite
+);
+
+async fn test_channel_message_threads_reactions_and_snippets(db: &Arc<Database>) {
+    let user_a = new_test_user(db, "user_a@example.com").await;
+    let user_b = new_test_user(db, "user_b@example.com").await;
+    let channel = db.create_root_channel("channel", user_a).await.unwrap();
+    db.invite_channel_member(channel, user_b, user_a, ChannelRole::Member)
+        .await
+        .unwrap();
+    db.respond_to_channel_invite(channel, user_b, true)
+        .await
+        .unwrap();
+
+    let owner_id = db.create_server("test").await.unwrap().0 as u32;
+    db.join_channel_chat(channel, rpc::ConnectionId { owner_id, id: 0 }, user_a)
+        .await
+        .unwrap();
+    db.join_channel_chat(channel, rpc::ConnectionId { owner_id, id: 1 }, user_b)
+        .await
+        .unwrap();
+
+    let snippet = rpc::proto::ChatSnippet {
+        path: "zed/src/main.rs".into(),
+        start_row: 3,
+        end_row: 5,
+        commit_sha: Some("abc123".into()),
+        text: "fn main() {\n}\n".into(),
+    };
+    let root_id = db
+        .create_channel_message(
+            channel,
+            user_a,
+            "what does this do?",
+            &[],
+            OffsetDateTime::now_utc(),
+            1,
+            None,
+            None,
+            Some(&snippet),
+        )
+        .await
+        .unwrap()
+        .message_id;
+    let reply = db
+        .create_channel_message(
+            channel,
+            user_b,
+            "it starts the app",
+            &[],
+            OffsetDateTime::now_utc(),
+            2,
+            None,
+            Some(root_id),
+            None,
+        )
+        .await
+        .unwrap();
+    assert_eq!(reply.thread_id, Some(root_id));
+
+    // Replying to a reply in a thread adds the message to the same thread.
+    let nested_reply = db
+        .create_channel_message(
+            channel,
+            user_a,
+            "thanks",
+            &[],
+            OffsetDateTime::now_utc(),
+            3,
+            None,
+            Some(reply.message_id),
+            None,
+        )
+        .await
+        .unwrap();
+    assert_eq!(nested_reply.thread_id, Some(root_id));
+
+    let reactions = db
+        .toggle_channel_message_reaction(channel, root_id, user_a, "👍")
+        .await
+        .unwrap();
+    assert_eq!(reactions.participant_connection_ids.len(), 2);
+    db.toggle_channel_message_reaction(channel, root_id, user_b, "👍")
+        .await
+        .unwrap();
+    db.toggle_channel_message_reaction(channel, root_id, user_b, "🎉")
+        .await
+        .unwrap();
+    let reactions = db
+        .toggle_channel_message_reaction(channel, root_id, user_a, "👍")
+        .await
+        .unwrap()
+        .reactions;
+    let mut reactions = reactions
+        .iter()
+        .map(|reaction| (reaction.emoji.as_str(), reaction.user_ids.clone()))
+        .collect::<Vec<_>>();
+    reactions.sort();
+    assert_eq!(
+        reactions,
+        [
+            ("🎉", vec![user_b.to_proto()]),
+            ("👍", vec![user_b.to_proto()])
+        ]
+    );
+
+    let messages = db
+        .get_channel_messages(channel, user_b, 10, None)
+        .await
+        .unwrap();
+    assert_eq!(
+        messages
+            .iter()
+            .map(|message| (message.thread_id, message.reactions.len()))
+            .collect::<Vec<_>>(),
+        [
+            (None, 2),
+            (Some(root_id.to_proto()), 0),
+            (Some(root_id.to_proto()), 0),
+        ]
+    );
+    assert_eq!(messages[0].snippet.as_ref(), Some(&snippet));
+    assert_eq!(messages[1].snippet, None);
+}
+
+test_both_dbs!(
+    test_search_channel_messages,
+    test_search_channel_messages_postgres,
+    test_search_channel_messages_sqlite
+);
+
+async fn test_search_channel_messages(db: &Arc<Database>) {
+    let user_a = new_test_user(db, "user_a@example.com").await;
+    let user_b = new_test_user(db, "user_b@example.com").await;
+    let user_c = new_test_user(db, "user_c@example.com").await;
+    let channel_1 = db.create_root_channel("channel-1", user_a).await.unwrap();
+    let channel_2 = db.create_root_channel("channel-2", user_a).await.unwrap();
+    for (channel, user) in [(channel_1, user_c), (channel_2, user_b)] {
+        db.invite_channel_member(channel, user, user_a, ChannelRole::Member)
+            .await
+            .unwrap();
+        db.respond_to_channel_invite(channel, user, true)
+            .await
+            .unwrap();
+    }
+
+    let now = OffsetDateTime::now_utc();
+    for (nonce, (channel, sender, body)) in [
+        (channel_1, user_a, "the deploy failed"),
+        (channel_1, user_c, "time for lunch"),
+        (channel_1, user_a, "deploying a fix now"),
+        (channel_2, user_b, "Deploy went through"),
+    ]
+    .into_iter()
+    .enumerate()
+    {
+        db.create_channel_message(
+            channel,
+            sender,
+            body,
+            &[],
+            now,
+            nonce as u128,
+            None,
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+    }
+
+    let search = |user_id, request: rpc::proto::SearchChannelMessages| {
+        let db = db.clone();
+        async move {
+            db.search_channel_messages(user_id, &request, 10)
+                .await
+                .unwrap()
+                .into_iter()
+                .map(|result| result.message.unwrap().body)
+                .collect::<Vec<_>>()
+        }
+    };
+
+    // Results include every channel the user belongs to, newest first.
+    assert_eq!(
+        search(
+            user_a,
+            rpc::proto::SearchChannelMessages {
+                query: "deploy".into(),
+                ..Default::default()
+            }
+        )
+        .await,
+        [
+            "Deploy went through",
+            "deploying a fix now",
+            "the deploy failed"
+        ]
+    );
+    assert_eq!(
+        search(
+            user_c,
+            rpc::proto::SearchChannelMessages {
+                query: "deploy".into(),
+                ..Default::default()
+            }
+        )
+        .await,
+        ["deploying a fix now", "the deploy failed"]
+    );
+
+    // Results can be filtered by channel, sender and date.
+    assert_eq!(
+        search(
+            user_a,
+            rpc::proto::SearchChannelMessages {
+                query: "deploy".into(),
+                channel_id: Some(channel_2.to_proto()),
+                ..Default::default()
+            }
+        )
+        .await,
+        ["Deploy went through"]
+    );
+    assert_eq!(
+        search(
+            user_a,
+            rpc::proto::SearchChannelMessages {
+                query: "deploy".into(),
+                sender_id: Some(user_a.to_proto()),
+                ..Default::default()
+            }
+        )
+        .await,
+        ["deploying a fix now", "the deploy failed"]
+    );
+    assert_eq!(
+        search(
+            user_a,
+            rpc::proto::SearchChannelMessages {
+                query: "deploy".into(),
+                sent_after: Some(now.unix_timestamp() as u64 + 60),
+                ..Default::default()
+            }
+        )
+        .await,
+        Vec::<String>::new()
+    );
+
+    // Users can't search channels they don't belong to.
+    assert!(db
+        .search_channel_messages(
+            user_c,
+            &rpc::proto::SearchChannelMessages {
+                query: "deploy".into(),
+                channel_id: Some(channel_2.to_proto()),
+                ..Default::default()
+            },
+            10,
+        )
+        .await
+        .is_err());
+}
@@ -0,0 +1,143 @@
+use super::new_test_user;
+use crate::{
+    db::{ChannelRole, Database, ReviewThreadId, RoomId},
+    rpc::MAX_MESSAGE_LEN,
+    test_both_dbs,
+};
+use rpc::{proto, ConnectionId};
+use std::sync::Arc;
+use time::OffsetDateTime;
+
+test_both_dbs!(
+    test_review_threads,
+    test_review_threads_postgres,
+    test_review_threads_sqlite
+);
+
+async fn test_review_threads(db: &Arc<Database>) {
+    let owner_id = db.create_server("test").await.unwrap().0 as u32;
+    let connection = ConnectionId { owner_id, id: 1 };
+    let user = new_test_user(db, "user@example.com").await;
+    let guest = new_test_user(db, "guest@example.com").await;
+    let channel = db.create_root_channel("zed", user).await.unwrap();
+    db.invite_channel_member(channel, guest, user, ChannelRole::Member)
+        .await
+        .unwrap();
+    db.respond_to_channel_invite(channel, guest, true)
+        .await
+        .unwrap();
+
+    let (joined_room, _, _) = db.join_channel(channel, user, connection).await.unwrap();
+    let room_id = RoomId::from_proto(joined_room.room.id);
+    drop(joined_room);
+
+    let worktrees = [proto::WorktreeMetadata {
+        id: 1,
+        root_name: "zed".into(),
+        visible: true,
+        abs_path: "/code/zed".into(),
+    }];
+    let project_id = db
+        .share_project(room_id, connection, &worktrees, false)
+        .await
+        .unwrap()
+        .0;
+    let guest_connection = ConnectionId { owner_id, id: 2 };
+    db.join_channel(channel, guest, guest_connection)
+        .await
+        .unwrap();
+    db.join_project(project_id, guest_connection, guest)
+        .await
+        .unwrap();
+
+    let thread = db
+        .create_review_thread(
+            project_id,
+            connection,
+            user,
+            "zed/src/main.rs",
+            3,
+            5,
+            "Should this be async?",
+            OffsetDateTime::now_utc(),
+        )
+        .await
+        .unwrap();
+    assert_eq!((thread.start_row, thread.end_row), (3, 5));
+    assert_eq!(thread.comments.len(), 1);
+
+    // Threads can only be left on the files of the project.
+    assert!(db
+        .create_review_thread(
+            project_id,
+            connection,
+            user,
+            "zed-other/src/main.rs",
+            0,
+            0,
+            "Hello",
+            OffsetDateTime::now_utc(),
+        )
+        .await
+        .is_err());
+
+    let thread_id = ReviewThreadId::from_proto(thread.id);
+    db.add_review_comment(
+        project_id,
+        connection,
+        user,
+        thread_id,
+        "Not yet",
+        OffsetDateTime::now_utc(),
+    )
+    .await
+    .unwrap();
+    assert!(db
+        .add_review_comment(
+            project_id,
+            connection,
+            user,
+            thread_id,
+            "  ",
+            OffsetDateTime::now_utc()
+        )
+        .await
+        .is_err());
+    assert!(db
+        .add_review_comment(
+            project_id,
+            guest_connection,
+            guest,
+            thread_id,
+            &"a".repeat(MAX_MESSAGE_LEN + 1),
+            OffsetDateTime::now_utc()
+        )
+        .await
+        .is_err());
+
+    // Only the host moves threads, as the rows are those of the files on its disk.
+    assert!(db
+        .move_review_thread(project_id, guest_connection, thread_id, 7, 9)
+        .await
+        .is_err());
+    db.move_review_thread(project_id, connection, thread_id, 4, 6)
+        .await
+        .unwrap();
+    db.resolve_review_thread(project_id, connection, thread_id, true)
+        .await
+        .unwrap();
+
+    let threads = db.get_review_threads(project_id, connection).await.unwrap();
+    assert_eq!(threads.len(), 1);
+    assert_eq!(threads[0].path, "zed/src/main.rs");
+    assert_eq!((threads[0].start_row, threads[0].end_row), (4, 6));
+    assert!(threads[0].resolved);
+    assert_eq!(
+        threads[0]
+            .comments
+            .iter()
+            .map(|comment| comment.body.as_str())
+            .collect::<Vec<_>>(),
+        ["Should this be async?", "Not yet"]
+    );
+}
@@ -8,7 +8,8 @@ use crate::{
         self, BufferId, Capability, Channel, ChannelId, ChannelRole, ChannelsForUser,
         CreatedChannelMessage, Database, InviteMemberResult, MembershipUpdated, MessageId,
         NotificationId, Project, ProjectId, RejoinedProject, RemoveChannelMemberResult, ReplicaId,
-        RespondToChannelInvite, RoomId, ServerId, UpdatedChannelMessage, User, UserId,
+        RespondToChannelInvite, ReviewThreadId, RoomId, ServerId, UpdatedChannelMessage, User,
+        UserId,
     },
     executor::Executor,
     AppState, Config, Error, RateLimit, Result,
@@ -81,7 +82,10 @@ pub const RECONNECT_TIMEOUT: Duration = Duration::from_secs(30);
 pub const CLEANUP_TIMEOUT: Duration = Duration::from_secs(15);
 
 const MESSAGE_COUNT_PER_PAGE: usize = 100;
-const MAX_MESSAGE_LEN: usize = 1024;
+pub(crate) const MAX_MESSAGE_LEN: usize = 1024;
+const MAX_SNIPPET_LEN: usize = 16 * 1024;
+const MAX_REACTION_LEN: usize = 8;
+const MESSAGE_SEARCH_RESULTS_PER_PAGE: usize = 50;
 const NOTIFICATION_COUNT_PER_PAGE: usize = 50;
 
 type MessageHandler =
@@ -327,6 +331,8 @@ impl Server {
             .add_request_handler(forward_mutating_project_request::<proto::ReloadBuffers>)
             .add_request_handler(forward_mutating_project_request::<proto::FormatBuffers>)
             .add_request_handler(forward_mutating_project_request::<proto::CreateProjectEntry>)
+            .add_request_handler(forward_mutating_project_request::<proto::ReadProjectFile>)
+            .add_request_handler(forward_mutating_project_request::<proto::AppendToProjectFile>)
             .add_request_handler(forward_mutating_project_request::<proto::RenameProjectEntry>)
             .add_request_handler(forward_mutating_project_request::<proto::CopyProjectEntry>)
             .add_request_handler(forward_mutating_project_request::<proto::DeleteProjectEntry>)
@@ -344,6 +350,15 @@ impl Server {
             .add_message_handler(broadcast_project_message_from_host::<proto::BufferReloaded>)
             .add_message_handler(broadcast_project_message_from_host::<proto::BufferSaved>)
             .add_message_handler(broadcast_project_message_from_host::<proto::UpdateDiffBase>)
+            .add_message_handler(broadcast_project_message_from_host::<proto::ShareTerminal>)
+            .add_message_handler(broadcast_project_message_from_host::<proto::UnshareTerminal>)
+            .add_message_handler(broadcast_project_message_from_host::<proto::UpdateSharedTerminal>)
+            .add_request_handler(forward_mutating_project_request::<proto::SharedTerminalInput>)
+            .add_request_handler(get_review_threads)
+            .add_request_handler(create_review_thread)
+            .add_request_handler(add_review_comment)
+            .add_request_handler(resolve_review_thread)
+            .add_request_handler(move_review_thread)
             .add_request_handler(get_users)
             .add_request_handler(fuzzy_search_users)
             .add_request_handler(request_contact)
@@ -369,8 +384,10 @@ impl Server {
             .add_request_handler(send_channel_message)
             .add_request_handler(remove_channel_message)
             .add_request_handler(update_channel_message)
+            .add_request_handler(toggle_channel_message_reaction)
             .add_request_handler(get_channel_messages)
             .add_request_handler(get_channel_messages_by_id)
+            .add_request_handler(search_channel_messages)
             .add_request_handler(get_notifications)
             .add_request_handler(mark_notification_as_read)
             .add_request_handler(move_channel)
@@ -2286,6 +2303,152 @@ async fn broadcast_project_message_from_host<T: EntityMessage<Entity = ShareProj
     Ok(())
 }
 
+/// Get the review threads on the files of a shared project.
+async fn get_review_threads(
+    request: proto::GetReviewThreads,
+    response: Response<proto::GetReviewThreads>,
+    session: Session,
+) -> Result<()> {
+    let threads = session
+        .db()
+        .await
+        .get_review_threads(
+            ProjectId::from_proto(request.project_id),
+            session.connection_id,
+        )
+        .await?;
+    response.send(proto::GetReviewThreadsResponse { threads })?;
+    Ok(())
+}
+
+/// Start a review thread on some lines of a file in a shared project.
+async fn create_review_thread(
+    request: proto::CreateReviewThread,
+    response: Response<proto::CreateReviewThread>,
+    session: Session,
+) -> Result<()> {
+    if request.body.len() > MAX_MESSAGE_LEN {
+        return Err(anyhow!("comment is too long"))?;
+    }
+    let project_id = ProjectId::from_proto(request.project_id);
+    let thread = session
+        .db()
+        .await
+        .create_review_thread(
+            project_id,
+            session.connection_id,
+            session.user_id(),
+            &request.path,
+            request.start_row,
+            request.end_row,
+            &request.body,
+            OffsetDateTime::now_utc(),
+        )
+        .await?;
+    review_thread_updated(project_id, thread, response, &session).await
+}
+
+/// Reply to a review thread in a shared project.
+async fn add_review_comment(
+    request: proto::AddReviewComment,
+    response: Response<proto::AddReviewComment>,
+    session: Session,
+) -> Result<()> {
+    if request.body.len() > MAX_MESSAGE_LEN {
+        return Err(anyhow!("comment is too long"))?;
+    }
+    let project_id = ProjectId::from_proto(request.project_id);
+    let thread = session
+        .db()
+        .await
+        .add_review_comment(
+            project_id,
+            session.connection_id,
+            session.user_id(),
+            ReviewThreadId::from_proto(request.thread_id),
+            &request.body,
+            OffsetDateTime::now_utc(),
+        )
+        .await?;
+    review_thread_updated(project_id, thread, response, &session).await
+}
+
+/// Resolve or reopen a review thread in a shared project.
+async fn resolve_review_thread(
+    request: proto::ResolveReviewThread,
+    response: Response<proto::ResolveReviewThread>,
+    session: Session,
+) -> Result<()> {
+    let project_id = ProjectId::from_proto(request.project_id);
+    let thread = session
+        .db()
+        .await
+        .resolve_review_thread(
+            project_id,
+            session.connection_id,
+            ReviewThreadId::from_proto(request.thread_id),
+            request.resolved,
+        )
+        .await?;
+    review_thread_updated(project_id, thread, response, &session).await
+}
+
+/// Update the lines a review thread is attached to, after its file was edited on the host.
+async fn move_review_thread(
+    request: proto::MoveReviewThread,
+    response: Response<proto::MoveReviewThread>,
+    session: Session,
+) -> Result<()> {
+    let project_id = ProjectId::from_proto(request.project_id);
+    let thread = session
+        .db()
+        .await
+        .move_review_thread(
+            project_id,
+            session.connection_id,
+            ReviewThreadId::from_proto(request.thread_id),
+            request.start_row,
+            request.end_row,
+        )
+        .await?;
+    review_thread_updated(project_id, thread, response, &session).await
+}
+
+/// Send a review thread back to the participant that changed it, and to the
+/// other participants in the project.
+async fn review_thread_updated<T>(
+    project_id: ProjectId,
+    thread: proto::ReviewThread,
+    response: Response<T>,
+    session: &Session,
+) -> Result<()>
+where
+    T: RequestMessage<Response = proto::ReviewThreadResponse>,
+{
+    let project_connection_ids = session
+        .db()
+        .await
+        .project_connection_ids(project_id, session.connection_id, false)
+        .await?;
+    broadcast(
+        Some(session.connection_id),
+        project_connection_ids.iter().copied(),
+        |connection_id| {
+            session.peer.send(
+                connection_id,
+                proto::UpdateReviewThread {
+                    project_id: project_id.to_proto(),
+                    thread: Some(thread.clone()),
+                },
+            )
+        },
+    );
+    response.send(proto::ReviewThreadResponse {
+        thread: Some(thread),
+    })?;
+    Ok(())
+}
+
 /// Start following another user in a call.
 async fn follow(
     request: proto::Follow,
@@ -3346,9 +3509,17 @@ async fn send_channel_message(
     if body.len() > MAX_MESSAGE_LEN {
         return Err(anyhow!("message is too long"))?;
     }
-    if body.is_empty() {
+    if body.is_empty() && request.snippet.is_none() {
         return Err(anyhow!("message can't be blank"))?;
     }
+    if let Some(snippet) = &request.snippet {
+        if snippet.text.len() > MAX_SNIPPET_LEN {
+            return Err(anyhow!("snippet is too long"))?;
+        }
+        if snippet.start_row > snippet.end_row {
+            return Err(anyhow!("invalid snippet range"))?;
+        }
+    }
 
     // TODO: adjust mentions if body is trimmed
 
@@ -3360,6 +3531,7 @@ async fn send_channel_message(
     let channel_id = ChannelId::from_proto(request.channel_id);
     let CreatedChannelMessage {
         message_id,
+        thread_id,
         participant_connection_ids,
         notifications,
     } = session
@@ -3373,6 +3545,8 @@ async fn send_channel_message(
             timestamp,
             nonce.clone().into(),
             request.reply_to_message_id.map(MessageId::from_proto),
+            request.thread_id.map(MessageId::from_proto),
+            request.snippet.as_ref(),
         )
         .await?;
 
@@ -3385,6 +3559,9 @@ async fn send_channel_message(
         nonce: Some(nonce),
         reply_to_message_id: request.reply_to_message_id,
         edited_at: None,
+        reactions: Vec::new(),
+        thread_id: thread_id.map(|id| id.to_proto()),
+        snippet: request.snippet,
     };
     broadcast(
         Some(session.connection_id),
@@ -3479,6 +3656,7 @@ async fn update_channel_message(
         participant_connection_ids,
         notifications,
         reply_to_message_id,
+        thread_id,
         timestamp,
         deleted_mention_notification_ids,
         updated_mention_notifications,
@@ -3509,6 +3687,11 @@ async fn update_channel_message(
         nonce: Some(nonce),
         reply_to_message_id: reply_to_message_id.map(|id| id.to_proto()),
         edited_at: Some(updated_at.unix_timestamp() as u64),
+        // Editing a message doesn't change its reactions or snippet, so clients keep the ones
+        // they already have.
+        reactions: Vec::new(),
+        thread_id: thread_id.map(|id| id.to_proto()),
+        snippet: None,
     };
 
     response.send(proto::Ack {})?;
@@ -3553,6 +3736,68 @@ async fn update_channel_message(
     Ok(())
 }
 
+/// Add or remove a reaction to a channel message
+async fn toggle_channel_message_reaction(
+    request: proto::ToggleChannelMessageReaction,
+    response: Response<proto::ToggleChannelMessageReaction>,
+    session: Session,
+) -> Result<()> {
+    let emoji = request.emoji.trim();
+    if emoji.is_empty() || emoji.chars().count() > MAX_REACTION_LEN {
+        return Err(anyhow!("invalid reaction"))?;
+    }
+
+    let channel_id = ChannelId::from_proto(request.channel_id);
+    let message_id = MessageId::from_proto(request.message_id);
+    let db::UpdatedChannelMessageReactions {
+        participant_connection_ids,
+        reactions,
+    } = session
+        .db()
+        .await
+        .toggle_channel_message_reaction(channel_id, message_id, session.user_id(), emoji)
+        .await?;
+
+    broadcast(
+        Some(session.connection_id),
+        participant_connection_ids,
+        |connection| {
+            session.peer.send(
+                connection,
+                proto::ChannelMessageReactionsUpdated {
+                    channel_id: channel_id.to_proto(),
+                    message_id: message_id.to_proto(),
+                    reactions: reactions.clone(),
+                },
+            )
+        },
+    );
+    response.send(proto::ToggleChannelMessageReactionResponse { reactions })?;
+    Ok(())
+}
+
+/// Search the messages of the channels the user belongs to
+async fn search_channel_messages(
+    request: proto::SearchChannelMessages,
+    response: Response<proto::SearchChannelMessages>,
+    session: Session,
+) -> Result<()> {
+    if request.query.trim().is_empty() {
+        return Err(anyhow!("search query can't be blank"))?;
+    }
+
+    let results = session
+        .db()
+        .await
+        .search_channel_messages(session.user_id(), &request, MESSAGE_SEARCH_RESULTS_PER_PAGE)
+        .await?;
+    response.send(proto::SearchChannelMessagesResponse {
+        done: results.len() < MESSAGE_SEARCH_RESULTS_PER_PAGE,
+        results,
+    })?;
+    Ok(())
+}
+
 /// Mark a channel message as read
 async fn acknowledge_channel_message(
     request: proto::AckChannelMessage,
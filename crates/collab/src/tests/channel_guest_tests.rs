@@ -4,6 +4,8 @@ use chrono::Utc;
 use editor::Editor;
 use gpui::{BackgroundExecutor, TestAppContext};
 use rpc::proto;
+use serde_json::json;
+use std::path::Path;
 
 #[gpui::test]
 async fn test_channel_guests(
@@ -167,6 +169,91 @@ async fn test_channel_guest_promotion(cx_a: &mut TestAppContext, cx_b: &mut Test
         .is_err());
 }
 
+#[gpui::test]
+async fn test_channel_guest_file_reads(cx_a: &mut TestAppContext, cx_b: &mut TestAppContext) {
+    let mut server = TestServer::start(cx_a.executor()).await;
+    let client_a = server.create_client(cx_a, "user_a").await;
+    let client_b = server.create_client(cx_b, "user_b").await;
+    let active_call_a = cx_a.read(ActiveCall::global);
+
+    let channel_id = server
+        .make_public_channel("the-channel", &client_a, cx_a)
+        .await;
+
+    client_a
+        .fs()
+        .insert_tree(
+            "/a",
+            json!({
+                "1.txt": "one",
+                ".env": "SECRET=1",
+                ".git": { "config": "[core]" },
+            }),
+        )
+        .await;
+    let (project_a, _) = client_a.build_local_project("/a", cx_a).await;
+    active_call_a
+        .update(cx_a, |call, cx| call.join_channel(channel_id, cx))
+        .await
+        .unwrap();
+    active_call_a
+        .update(cx_a, |call, cx| call.share_project(project_a.clone(), cx))
+        .await
+        .unwrap();
+    cx_a.run_until_parked();
+
+    // Client B joins channel A as a guest
+    cx_b.update(|cx| workspace::join_channel(channel_id, client_b.app_state.clone(), None, cx))
+        .await
+        .unwrap();
+    cx_a.run_until_parked();
+
+    let project_b = cx_b
+        .read(ActiveCall::global)
+        .read_with(cx_b, |call, _| call.location().unwrap().upgrade().unwrap());
+    let worktree_b = project_b.read_with(cx_b, |project, cx| project.worktrees(cx).next().unwrap());
+    let read_file = |path: &'static str, cx: &mut TestAppContext| {
+        worktree_b.update(cx, |worktree, cx| {
+            worktree.read_file(Path::new(path), 0..1024, cx)
+        })
+    };
+
+    // Read-only guests can't read files.
+    assert!(project_b.read_with(cx_b, |project, cx| project.is_read_only(cx)));
+    assert!(read_file("1.txt", cx_b).await.is_err());
+
+    // B is promoted
+    active_call_a
+        .update(cx_a, |call, cx| {
+            call.room().unwrap().update(cx, |room, cx| {
+                room.set_participant_role(
+                    client_b.user_id().unwrap(),
+                    proto::ChannelRole::Member,
+                    cx,
+                )
+            })
+        })
+        .await
+        .unwrap();
+    cx_a.run_until_parked();
+
+    // Members can read the files that are shared with them, but not private or excluded ones.
+    assert!(project_b.read_with(cx_b, |project, cx| !project.is_read_only(cx)));
+    assert_eq!(read_file("1.txt", cx_b).await.unwrap(), b"one");
+    assert!(read_file(".env", cx_b).await.is_err());
+    assert!(read_file(".git/config", cx_b).await.is_err());
+    assert!(worktree_b
+        .update(cx_b, |worktree, cx| {
+            worktree.append_to_file(Path::new(".env"), b"SECRET=2".to_vec(), cx)
+        })
+        .await
+        .is_err());
+    assert_eq!(
+        client_a.fs().load("/a/.env".as_ref()).await.unwrap(),
+        "SECRET=1"
+    );
+}
+
 #[gpui::test]
 async fn test_channel_requires_zed_cla(cx_a: &mut TestAppContext, cx_b: &mut TestAppContext) {
     let mut server = TestServer::start(cx_a.executor()).await;
@@ -1,5 +1,8 @@
 use crate::{rpc::RECONNECT_TIMEOUT, tests::TestServer};
-use channel::{ChannelChat, ChannelMessageId, MessageParams};
+use channel::{
+    ChannelChat, ChannelMessageId, ChannelMessageReaction, ChannelMessageSearch,
+    ChannelMessageSnippet, MessageParams,
+};
 use collab_ui::chat_panel::ChatPanel;
 use gpui::{BackgroundExecutor, Model, TestAppContext};
 use rpc::Notification;
@@ -44,6 +47,8 @@ async fn test_basic_channel_messages(
                     text: "hi @user_c!".into(),
                     mentions: vec![(3..10, client_c.id())],
                     reply_to_message_id: None,
+                    thread_id: None,
+                    snippet: None,
                 },
                 cx,
             )
@@ -229,6 +234,8 @@ async fn test_remove_channel_message(
                     text: "two @user_b".to_string(),
                     mentions: vec![(4..12, client_b.id())],
                     reply_to_message_id: None,
+                    thread_id: None,
+                    snippet: None,
                 },
                 cx,
             )
@@ -476,6 +483,8 @@ async fn test_chat_replies(cx_a: &mut TestAppContext, cx_b: &mut TestAppContext)
                 MessageParams {
                     text: "reply".into(),
                     reply_to_message_id: Some(msg_id),
+                    thread_id: None,
+                    snippet: None,
                     mentions: Vec::new(),
                 },
                 cx,
@@ -498,6 +507,122 @@ async fn test_chat_replies(cx_a: &mut TestAppContext, cx_b: &mut TestAppContext)
     });
 }
 
+#[gpui::test]
+async fn test_chat_threads_and_reactions(cx_a: &mut TestAppContext, cx_b: &mut TestAppContext) {
+    let mut server = TestServer::start(cx_a.executor()).await;
+    let client_a = server.create_client(cx_a, "user_a").await;
+    let client_b = server.create_client(cx_b, "user_b").await;
+
+    let channel_id = server
+        .make_channel(
+            "the-channel",
+            None,
+            (&client_a, cx_a),
+            &mut [(&client_b, cx_b)],
+        )
+        .await;
+
+    let channel_chat_a = client_a
+        .channel_store()
+        .update(cx_a, |store, cx| store.open_channel_chat(channel_id, cx))
+        .await
+        .unwrap();
+    let channel_chat_b = client_b
+        .channel_store()
+        .update(cx_b, |store, cx| store.open_channel_chat(channel_id, cx))
+        .await
+        .unwrap();
+
+    let snippet = ChannelMessageSnippet {
+        path: "zed/src/main.rs".into(),
+        start_row: 0,
+        end_row: 1,
+        commit_sha: Some("abc123".into()),
+        text: "fn main() {\n}\n".into(),
+    };
+    let root_id = channel_chat_a
+        .update(cx_a, |c, cx| {
+            c.send_message(
+                MessageParams {
+                    text: String::new(),
+                    mentions: Vec::new(),
+                    reply_to_message_id: None,
+                    thread_id: None,
+                    snippet: Some(snippet.clone()),
+                },
+                cx,
+            )
+            .unwrap()
+        })
+        .await
+        .unwrap();
+
+    cx_a.run_until_parked();
+
+    channel_chat_b
+        .update(cx_b, |c, cx| {
+            c.send_message(
+                MessageParams {
+                    text: "looks good".into(),
+                    mentions: Vec::new(),
+                    reply_to_message_id: None,
+                    thread_id: Some(root_id),
+                    snippet: None,
+                },
+                cx,
+            )
+            .unwrap()
+        })
+        .await
+        .unwrap();
+    channel_chat_b
+        .update(cx_b, |c, cx| c.toggle_reaction(root_id, "🎉".into(), cx))
+        .await
+        .unwrap();
+
+    cx_a.run_until_parked();
+
+    channel_chat_a.update(cx_a, |channel_chat, _| {
+        let root = channel_chat.find_loaded_message(root_id).unwrap();
+        assert_eq!(root.snippet.as_ref(), Some(&snippet));
+        assert_eq!(
+            root.reactions,
+            [ChannelMessageReaction {
+                emoji: "🎉".into(),
+                user_ids: vec![client_b.user_id().unwrap()],
+            }]
+        );
+        assert_eq!(
+            channel_chat
+                .thread_replies(root_id)
+                .map(|message| message.body.as_str())
+                .collect::<Vec<_>>(),
+            ["looks good"]
+        );
+    });
+
+    channel_chat_a
+        .update(cx_a, |c, cx| c.toggle_reaction(root_id, "🎉".into(), cx))
+        .await
+        .unwrap();
+    channel_chat_b
+        .update(cx_b, |c, cx| c.toggle_reaction(root_id, "🎉".into(), cx))
+        .await
+        .unwrap();
+
+    cx_a.run_until_parked();
+
+    channel_chat_b.update(cx_b, |channel_chat, _| {
+        assert_eq!(
+            channel_chat.find_loaded_message(root_id).unwrap().reactions,
+            [ChannelMessageReaction {
+                emoji: "🎉".into(),
+                user_ids: vec![client_a.user_id().unwrap()],
+            }]
+        );
+    });
+}
+
 #[gpui::test]
 async fn test_chat_editing(cx_a: &mut TestAppContext, cx_b: &mut TestAppContext) {
     let mut server = TestServer::start(cx_a.executor()).await;
@@ -532,6 +657,8 @@ async fn test_chat_editing(cx_a: &mut TestAppContext, cx_b: &mut TestAppContext)
                 MessageParams {
                     text: "Initial message".into(),
                     reply_to_message_id: None,
+                    thread_id: None,
+                    snippet: None,
                     mentions: Vec::new(),
                 },
                 cx,
@@ -550,6 +677,8 @@ async fn test_chat_editing(cx_a: &mut TestAppContext, cx_b: &mut TestAppContext)
                 MessageParams {
                     text: "Updated body".into(),
                     reply_to_message_id: None,
+                    thread_id: None,
+                    snippet: None,
                     mentions: Vec::new(),
                 },
                 cx,
@@ -593,6 +722,8 @@ async fn test_chat_editing(cx_a: &mut TestAppContext, cx_b: &mut TestAppContext)
                 MessageParams {
                     text: "Updated body including a mention for @user_b".into(),
                     reply_to_message_id: None,
+                    thread_id: None,
+                    snippet: None,
                     mentions: vec![(37..45, client_b.id())],
                 },
                 cx,
@@ -639,6 +770,8 @@ async fn test_chat_editing(cx_a: &mut TestAppContext, cx_b: &mut TestAppContext)
                 MessageParams {
                     text: "Updated body v2 including a mention for @user_b".into(),
                     reply_to_message_id: None,
+                    thread_id: None,
+                    snippet: None,
                     mentions: vec![(37..45, client_b.id())],
                 },
                 cx,
@@ -693,6 +826,8 @@ async fn test_chat_editing(cx_a: &mut TestAppContext, cx_b: &mut TestAppContext)
                 MessageParams {
                     text: "Updated body without a mention".into(),
                     reply_to_message_id: None,
+                    thread_id: None,
+                    snippet: None,
                     mentions: vec![],
                 },
                 cx,
@@ -723,3 +858,62 @@ async fn test_chat_editing(cx_a: &mut TestAppContext, cx_b: &mut TestAppContext)
         assert_eq!(store.notification_count(), 1);
     });
 }
+
+#[gpui::test]
+async fn test_search_channel_messages(cx_a: &mut TestAppContext, cx_b: &mut TestAppContext) {
+    let mut server = TestServer::start(cx_a.executor()).await;
+    let client_a = server.create_client(cx_a, "user_a").await;
+    let client_b = server.create_client(cx_b, "user_b").await;
+
+    let channel_id = server
+        .make_channel(
+            "the-channel",
+            None,
+            (&client_a, cx_a),
+            &mut [(&client_b, cx_b)],
+        )
+        .await;
+
+    let channel_chat_a = client_a
+        .channel_store()
+        .update(cx_a, |store, cx| store.open_channel_chat(channel_id, cx))
+        .await
+        .unwrap();
+
+    for text in ["the deploy failed", "lunch?", "deploy is green again"] {
+        channel_chat_a
+            .update(cx_a, |c, cx| c.send_message(text.into(), cx).unwrap())
+            .await
+            .unwrap();
+    }
+
+    cx_a.run_until_parked();
+
+    let results = client_b
+        .channel_store()
+        .update(cx_b, |store, cx| {
+            store.search_messages(
+                &ChannelMessageSearch {
+                    query: "deploy".into(),
+                    ..Default::default()
+                },
+                None,
+                cx,
+            )
+        })
+        .await
+        .unwrap();
+    assert!(results.done);
+    assert_eq!(
+        results
+            .messages
+            .iter()
+            .map(|(channel_id, message)| (*channel_id, message.body.as_str()))
+            .collect::<Vec<_>>(),
+        [
+            (channel_id, "deploy is green again"),
+            (channel_id, "the deploy failed"),
+        ]
+    );
+    assert_eq!(results.messages[0].1.sender.github_login, "user_a");
+}
@@ -24,15 +24,18 @@ use language::{
     },
     tree_sitter_rust, tree_sitter_typescript, Diagnostic, DiagnosticEntry, FakeLspAdapter,
     Language, LanguageConfig, LanguageMatcher, LineEnding, OffsetRangeExt, Point, Rope,
+    ToPoint as _,
 };
 use lsp::LanguageServerId;
 use parking_lot::Mutex;
 use project::lsp_store::FormatTarget;
 use project::{
-    lsp_store::FormatTrigger, search::SearchQuery, search::SearchResult, DiagnosticSummary,
-    HoverBlockKind, Project, ProjectPath,
+    lsp_store::FormatTrigger, search::SearchQuery, search::SearchResult,
+    terminal_sharing::SharedTerminal, terminals::TerminalKind, DiagnosticSummary, HoverBlockKind,
+    Project, ProjectPath,
 };
 use rand::prelude::*;
+use rpc::proto;
 use serde_json::json;
 use settings::SettingsStore;
 use std::{
@@ -6738,3 +6741,218 @@ async fn test_remote_git_branches(
 
     assert_eq!(host_branch.as_ref(), "totally-new-branch");
 }
+
+#[gpui::test]
+async fn test_review_threads_on_reopened_buffers(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let client_a = server.create_client(cx_a, "user_a").await;
+    let channel_id = server
+        .make_channel("the-channel", None, (&client_a, cx_a), &mut [])
+        .await;
+    let active_call_a = cx_a.read(ActiveCall::global);
+    active_call_a
+        .update(cx_a, |call, cx| call.join_channel(channel_id, cx))
+        .await
+        .unwrap();
+
+    client_a
+        .fs()
+        .insert_tree("/a", json!({ "main.rs": "one\ntwo\nthree\n" }))
+        .await;
+    let (project_a, worktree_id) = client_a.build_local_project("/a", cx_a).await;
+    active_call_a
+        .update(cx_a, |call, cx| call.share_project(project_a.clone(), cx))
+        .await
+        .unwrap();
+    executor.run_until_parked();
+
+    let open_buffer = |cx: &mut TestAppContext| {
+        project_a.update(cx, |project, cx| {
+            project.open_buffer((worktree_id, "main.rs"), cx)
+        })
+    };
+    let thread_rows = |buffer: &Model<language::Buffer>, cx: &mut TestAppContext| {
+        let threads = project_a.update(cx, |project, cx| {
+            project.review_threads_for_buffer(buffer, cx)
+        });
+        buffer.read_with(cx, |buffer, _| {
+            threads
+                .into_iter()
+                .map(|(thread_id, range)| {
+                    (
+                        thread_id,
+                        range.start.to_point(buffer).row..=range.end.to_point(buffer).row,
+                    )
+                })
+                .collect::<Vec<_>>()
+        })
+    };
+
+    let buffer = open_buffer(cx_a).await.unwrap();
+    let thread_id = project_a
+        .update(cx_a, |project, cx| {
+            let snapshot = buffer.read(cx).snapshot();
+            let range =
+                snapshot.anchor_before(Point::new(1, 0))..snapshot.anchor_after(Point::new(1, 3));
+            project.create_review_thread(&buffer, range, "Rename this?".into(), cx)
+        })
+        .await
+        .unwrap();
+    assert_eq!(thread_rows(&buffer, cx_a), [(thread_id, 1..=1)]);
+
+    // Closing the file and opening it again creates a new buffer, where the thread is attached
+    // to the same lines.
+    let old_buffer_id = buffer.read_with(cx_a, |buffer, _| buffer.remote_id());
+    drop(buffer);
+    executor.run_until_parked();
+    let buffer = open_buffer(cx_a).await.unwrap();
+    assert_ne!(
+        buffer.read_with(cx_a, |buffer, _| buffer.remote_id()),
+        old_buffer_id
+    );
+    assert_eq!(thread_rows(&buffer, cx_a), [(thread_id, 1..=1)]);
+
+    // The thread moves along with edits to the new buffer, and keeps its lines once it's saved.
+    buffer.update(cx_a, |buffer, cx| buffer.edit([(0..0, "zero\n")], None, cx));
+    assert_eq!(thread_rows(&buffer, cx_a), [(thread_id, 2..=2)]);
+    project_a
+        .update(cx_a, |project, cx| project.save_buffer(buffer.clone(), cx))
+        .await
+        .unwrap();
+    executor.run_until_parked();
+    project_a.read_with(cx_a, |project, _| {
+        assert_eq!(project.review_thread(thread_id).unwrap().rows, 2..=2);
+    });
+}
+
+#[gpui::test]
+async fn test_shared_terminals(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+    cx_c: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let client_a = server.create_client(cx_a, "user_a").await;
+    let client_b = server.create_client(cx_b, "user_b").await;
+    let client_c = server.create_client(cx_c, "user_c").await;
+    server
+        .create_room(&mut [(&client_a, cx_a), (&client_b, cx_b), (&client_c, cx_c)])
+        .await;
+    let active_call_a = cx_a.read(ActiveCall::global);
+
+    // The host's terminal runs `cat`, which echoes back whatever is typed into it.
+    cx_a.update(|cx| {
+        terminal::init(cx);
+        SettingsStore::update_global(cx, |store, cx| {
+            store
+                .set_user_settings(r#"{"terminal": {"shell": {"program": "cat"}}}"#, cx)
+                .unwrap();
+        });
+    });
+
+    client_a
+        .fs()
+        .insert_tree("/a", json!({ "a.txt": "a-contents" }))
+        .await;
+    let (project_a, _) = client_a.build_local_project("/a", cx_a).await;
+    let project_id = active_call_a
+        .update(cx_a, |call, cx| call.share_project(project_a.clone(), cx))
+        .await
+        .unwrap();
+    let project_b = client_b.join_remote_project(project_id, cx_b).await;
+    let project_c = client_c.join_remote_project(project_id, cx_c).await;
+
+    executor.allow_parking();
+    let window_a = cx_a.add_empty_window().handle();
+    let terminal_a = project_a
+        .update(cx_a, |project, cx| {
+            project.create_terminal(TerminalKind::Shell(None), window_a, cx)
+        })
+        .await
+        .unwrap();
+    let terminal_id = project_a
+        .update(cx_a, |project, cx| project.share_terminal(&terminal_a, cx))
+        .unwrap();
+    executor.run_until_parked();
+
+    let terminal_b = project_b.read_with(cx_b, |project, _| {
+        project.remote_shared_terminals().next().unwrap().clone()
+    });
+    let terminal_c = project_c.read_with(cx_c, |project, _| {
+        project.remote_shared_terminals().next().unwrap().clone()
+    });
+    assert!(!terminal_b.read_with(cx_b, |terminal, _| terminal.can_write()));
+    assert!(!terminal_c.read_with(cx_c, |terminal, _| terminal.can_write()));
+
+    // Guests receive what is written to the host's terminal.
+    terminal_a.update(cx_a, |terminal, _| terminal.input("from-a\n".into()));
+    wait_for_shared_terminal_text(&executor, &terminal_b, cx_b, "from-a").await;
+    wait_for_shared_terminal_text(&executor, &terminal_c, cx_c, "from-a").await;
+
+    // Guests can't write to the terminal until the host allows them to.
+    let input = terminal_b.read_with(cx_b, |terminal, cx| {
+        terminal.input(b"from-b\n".to_vec(), cx)
+    });
+    assert!(input.await.is_err());
+
+    project_a.update(cx_a, |project, cx| {
+        project.set_shared_terminal_writer(terminal_id, client_b.peer_id().unwrap(), true, cx)
+    });
+    executor.run_until_parked();
+    assert!(terminal_b.read_with(cx_b, |terminal, _| terminal.can_write()));
+    assert!(!terminal_c.read_with(cx_c, |terminal, _| terminal.can_write()));
+
+    // The host rejects input from guests that weren't allowed to write, even when they bypass
+    // their replica's check.
+    let input = client_c.request(proto::SharedTerminalInput {
+        project_id,
+        terminal_id,
+        input: b"from-c\n".to_vec(),
+    });
+    assert!(input.await.is_err());
+
+    let input = terminal_b.read_with(cx_b, |terminal, cx| {
+        terminal.input(b"from-b\n".to_vec(), cx)
+    });
+    input.await.unwrap();
+    wait_for_shared_terminal_text(&executor, &terminal_b, cx_b, "from-b").await;
+    wait_for_shared_terminal_text(&executor, &terminal_c, cx_c, "from-b").await;
+    assert!(!shared_terminal_text(&terminal_c, cx_c).contains("from-c"));
+}
+
+fn shared_terminal_text(terminal: &Model<SharedTerminal>, cx: &TestAppContext) -> String {
+    terminal.read_with(cx, |terminal, _| {
+        terminal
+            .lines()
+            .iter()
+            .map(|line| line.iter().map(|run| run.text.as_str()).collect::<String>())
+            .collect::<Vec<_>>()
+            .join("\n")
+    })
+}
+
+/// Waits for the host's terminal process to output the given text and for it to be
+/// replicated to the guest.
+async fn wait_for_shared_terminal_text(
+    executor: &BackgroundExecutor,
+    terminal: &Model<SharedTerminal>,
+    cx: &TestAppContext,
+    text: &str,
+) {
+    for _ in 0..500 {
+        executor.advance_clock(Duration::from_millis(100));
+        executor.run_until_parked();
+        if shared_terminal_text(terminal, cx).contains(text) {
+            return;
+        }
+        std::thread::sleep(Duration::from_millis(10));
+    }
+    panic!(
+        "shared terminal never contained {text:?}, its text was {:?}",
+        shared_terminal_text(terminal, cx)
+    );
+}
@@ -1,20 +1,25 @@
 use crate::{collab_panel, ChatPanelSettings};
+use anyhow::anyhow;
 use anyhow::Result;
 use call::{room, ActiveCall};
-use channel::{ChannelChat, ChannelChatEvent, ChannelMessage, ChannelMessageId, ChannelStore};
-use client::{ChannelId, Client};
-use collections::HashMap;
+use channel::{
+    ChannelChat, ChannelChatEvent, ChannelMessage, ChannelMessageId, ChannelMessageReaction,
+    ChannelMessageSearch, ChannelMessageSearchResults, ChannelMessageSnippet, ChannelStore,
+};
+use client::{ChannelId, Client, UserStore};
+use collections::{HashMap, HashSet};
 use db::kvp::KEY_VALUE_STORE;
 use editor::{actions, Editor};
 use gpui::{
-    actions, div, list, prelude::*, px, Action, AppContext, AsyncWindowContext, ClipboardItem,
-    CursorStyle, DismissEvent, ElementId, EventEmitter, FocusHandle, FocusableView, FontWeight,
-    HighlightStyle, ListOffset, ListScrollEvent, ListState, Model, Render, Stateful, Subscription,
-    Task, View, ViewContext, VisualContext, WeakView,
+    actions, div, list, prelude::*, px, Action, AnyElement, AppContext, AsyncWindowContext,
+    ClipboardItem, CursorStyle, DismissEvent, ElementId, EventEmitter, FocusHandle, FocusableView,
+    FontWeight, HighlightStyle, ListOffset, ListScrollEvent, ListState, Model, Render, Stateful,
+    Subscription, Task, View, ViewContext, VisualContext, WeakView,
 };
 use language::LanguageRegistry;
 use menu::Confirm;
 use message_editor::MessageEditor;
+use message_search::{match_indices, parse_search_query, MessageSearch};
 use project::Fs;
 use rich_text::{Highlight, RichText};
 use serde::{Deserialize, Serialize};
@@ -22,8 +27,8 @@ use settings::Settings;
 use std::{sync::Arc, time::Duration};
 use time::{OffsetDateTime, UtcOffset};
 use ui::{
-    prelude::*, Avatar, Button, ContextMenu, IconButton, IconName, KeyBinding, Label, PopoverMenu,
-    Tab, TabBar, Tooltip,
+    prelude::*, Avatar, Button, ContextMenu, HighlightedLabel, IconButton, IconName, KeyBinding,
+    Label, PopoverMenu, Tab, TabBar, Tooltip,
 };
 use util::{ResultExt, TryFutureExt};
 use workspace::{
@@ -32,22 +37,34 @@ use workspace::{
 };
 
 mod message_editor;
+mod message_search;
+pub(crate) mod snippet;
 
 const MESSAGE_LOADING_THRESHOLD: usize = 50;
 const CHAT_PANEL_KEY: &str = "ChatPanel";
+const REACTIONS: [&str; 6] = ["👍", "❤️", "😄", "🎉", "👀", "🚀"];
+const MAX_SNIPPET_PREVIEW_LINES: usize = 12;
 
 pub fn init(cx: &mut AppContext) {
     cx.observe_new_views(|workspace: &mut Workspace, _| {
         workspace.register_action(|workspace, _: &ToggleFocus, cx| {
             workspace.toggle_panel_focus::<ChatPanel>(cx);
         });
+        workspace.register_action(ChatPanel::share_selection_to_chat);
+        workspace.register_action(|workspace, _: &SearchMessages, cx| {
+            if let Some(panel) = workspace.focus_panel::<ChatPanel>(cx) {
+                panel.update(cx, |panel, cx| panel.open_search(cx));
+            }
+        });
     })
     .detach();
 }
 
 pub struct ChatPanel {
     client: Arc<Client>,
+    workspace: WeakView<Workspace>,
     channel_store: Model<ChannelStore>,
+    user_store: Model<UserStore>,
     languages: Arc<LanguageRegistry>,
     message_list: ListState,
     active_chat: Option<(Model<ChannelChat>, Subscription)>,
@@ -64,6 +81,8 @@ pub struct ChatPanel {
     open_context_menu: Option<(u64, Subscription)>,
     highlighted_message: Option<(u64, Task<()>)>,
     last_acknowledged_message_id: Option<u64>,
+    expanded_threads: HashSet<u64>,
+    search: Option<MessageSearch>,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -71,7 +90,10 @@ struct SerializedChatPanel {
     width: Option<Pixels>,
 }
 
-actions!(chat_panel, [ToggleFocus]);
+actions!(
+    chat_panel,
+    [ToggleFocus, ShareSelectionToChat, SearchMessages]
+);
 
 impl ChatPanel {
     pub fn new(workspace: &mut Workspace, cx: &mut ViewContext<Workspace>) -> View<Self> {
@@ -80,6 +102,7 @@ impl ChatPanel {
         let channel_store = ChannelStore::global(cx);
         let user_store = workspace.app_state().user_store.clone();
         let languages = workspace.app_state().languages.clone();
+        let workspace_handle = cx.view().downgrade();
 
         let input_editor = cx.new_view(|cx| {
             MessageEditor::new(
@@ -115,7 +138,9 @@ impl ChatPanel {
             let mut this = Self {
                 fs,
                 client,
+                workspace: workspace_handle,
                 channel_store,
+                user_store,
                 languages,
                 message_list,
                 active_chat: Default::default(),
@@ -131,6 +156,8 @@ impl ChatPanel {
                 open_context_menu: None,
                 highlighted_message: None,
                 last_acknowledged_message_id: None,
+                expanded_threads: HashSet::default(),
+                search: None,
             };
 
             if let Some(channel_id) = ActiveCall::global(cx)
@@ -235,10 +262,12 @@ impl ChatPanel {
     fn set_active_chat(&mut self, chat: Model<ChannelChat>, cx: &mut ViewContext<Self>) {
         if self.active_chat.as_ref().map(|e| &e.0) != Some(&chat) {
             self.markdown_data.clear();
+            self.expanded_threads.clear();
             self.message_list.reset(chat.read(cx).message_count());
             self.message_editor.update(cx, |editor, cx| {
                 editor.set_channel_chat(chat.clone(), cx);
                 editor.clear_reply_to_message_id();
+                editor.clear_thread_id();
             });
             let subscription = cx.subscribe(&chat, Self::channel_did_change);
             self.active_chat = Some((chat, subscription));
@@ -380,7 +409,7 @@ impl ChatPanel {
         )
     }
 
-    fn render_message(&mut self, ix: usize, cx: &mut ViewContext<Self>) -> impl IntoElement {
+    fn render_message(&mut self, ix: usize, cx: &mut ViewContext<Self>) -> AnyElement {
         let active_chat = &self.active_chat.as_ref().unwrap().0;
         let (message, is_continuation_from_previous, is_admin) =
             active_chat.update(cx, |active_chat, cx| {
@@ -413,6 +442,14 @@ impl ChatPanel {
 
         let _is_pending = message.is_pending();
 
+        // Replies in a thread are displayed beneath the message that started it.
+        if message
+            .thread_id
+            .is_some_and(|thread_id| active_chat.read(cx).message_ix(thread_id).is_some())
+        {
+            return div().into_any_element();
+        }
+
         let belongs_to_user = Some(message.sender.id) == self.client.user_id();
         let can_delete_message = belongs_to_user || is_admin;
         let can_edit_message = belongs_to_user;
@@ -453,6 +490,15 @@ impl ChatPanel {
         };
 
         let reply_to_message_id = self.message_editor.read(cx).reply_to_message_id();
+        let thread_replies = message_id
+            .map(|id| {
+                active_chat
+                    .read(cx)
+                    .thread_replies(id)
+                    .cloned()
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
 
         v_flex()
             .w_full()
@@ -535,6 +581,17 @@ impl ChatPanel {
                         .when(self.has_open_menu(message_id), |el| {
                             el.bg(cx.theme().colors().element_selected)
                         })
+                    })
+                    .when_some(message.snippet.as_ref(), |el, snippet| {
+                        el.child(self.render_snippet(message.id, snippet, cx))
+                    })
+                    .when_some(message_id, |el, message_id| {
+                        el.when(!message.reactions.is_empty(), |el| {
+                            el.child(self.render_reactions(message_id, &message.reactions, cx))
+                        })
+                        .when(!thread_replies.is_empty(), |el| {
+                            el.child(self.render_thread(message_id, thread_replies, cx))
+                        })
                     }),
             )
             .when(
@@ -563,6 +620,241 @@ impl ChatPanel {
                 self.render_popover_buttons(cx, message_id, can_delete_message, can_edit_message)
                     .mt_neg_2p5(),
             )
+            .into_any_element()
+    }
+
+    fn render_snippet(
+        &self,
+        message_id: ChannelMessageId,
+        snippet: &ChannelMessageSnippet,
+        cx: &mut ViewContext<Self>,
+    ) -> impl IntoElement {
+        let element_id: ElementId = match message_id {
+            ChannelMessageId::Saved(id) => ("saved-message-snippet", id).into(),
+            ChannelMessageId::Pending(id) => ("pending-message-snippet", id).into(),
+        };
+        let location = if snippet.start_row == snippet.end_row {
+            format!("{}:{}", snippet.path, snippet.start_row + 1)
+        } else {
+            format!(
+                "{}:{}-{}",
+                snippet.path,
+                snippet.start_row + 1,
+                snippet.end_row + 1
+            )
+        };
+        let mut text = snippet
+            .text
+            .lines()
+            .take(MAX_SNIPPET_PREVIEW_LINES)
+            .collect::<Vec<_>>()
+            .join("\n");
+        if snippet.text.lines().count() > MAX_SNIPPET_PREVIEW_LINES {
+            text.push_str("\n…");
+        }
+        let workspace = self.workspace.clone();
+        let snippet = snippet.clone();
+
+        v_flex()
+            .id(element_id)
+            .mt_1()
+            .rounded_md()
+            .overflow_hidden()
+            .border_1()
+            .border_color(cx.theme().colors().border)
+            .bg(cx.theme().colors().editor_background)
+            .child(
+                h_flex()
+                    .px_1p5()
+                    .py_0p5()
+                    .gap_1()
+                    .border_b_1()
+                    .border_color(cx.theme().colors().border)
+                    .child(
+                        Icon::new(IconName::FileCode)
+                            .size(IconSize::XSmall)
+                            .color(Color::Muted),
+                    )
+                    .child(
+                        Label::new(location)
+                            .size(LabelSize::XSmall)
+                            .color(Color::Muted),
+                    )
+                    .when_some(snippet.commit_sha.as_ref(), |el, commit_sha| {
+                        el.child(
+                            Label::new(commit_sha.chars().take(7).collect::<String>())
+                                .size(LabelSize::XSmall)
+                                .color(Color::Disabled),
+                        )
+                    }),
+            )
+            .child(
+                div()
+                    .px_1p5()
+                    .py_1()
+                    .font_buffer(cx)
+                    .text_ui_xs(cx)
+                    .child(text),
+            )
+            .cursor(CursorStyle::PointingHand)
+            .tooltip(|cx| Tooltip::text("Open in Editor", cx))
+            .on_click(move |_, cx| {
+                workspace
+                    .update(cx, |workspace, cx| {
+                        snippet::open_snippet(workspace, &snippet, cx)
+                    })
+                    .ok();
+            })
+    }
+
+    fn render_reactions(
+        &self,
+        message_id: u64,
+        reactions: &[ChannelMessageReaction],
+        cx: &mut ViewContext<Self>,
+    ) -> impl IntoElement {
+        let current_user_id = self.client.user_id();
+        h_flex()
+            .flex_wrap()
+            .gap_1()
+            .mt_1()
+            .children(reactions.iter().map(|reaction| {
+                let emoji = reaction.emoji.clone();
+                let reacted =
+                    current_user_id.map_or(false, |user_id| reaction.user_ids.contains(&user_id));
+                Button::new(
+                    ElementId::Name(format!("reaction-{message_id}-{emoji}").into()),
+                    format!("{emoji} {}", reaction.user_ids.len()),
+                )
+                .style(ButtonStyle::Filled)
+                .label_size(LabelSize::XSmall)
+                .toggle_state(reacted)
+                .on_click(cx.listener(move |this, _, cx| {
+                    this.toggle_reaction(message_id, emoji.clone(), cx)
+                }))
+            }))
+    }
+
+    fn render_thread(
+        &mut self,
+        root_id: u64,
+        replies: Vec<ChannelMessage>,
+        cx: &mut ViewContext<Self>,
+    ) -> impl IntoElement {
+        let is_expanded = self.expanded_threads.contains(&root_id);
+        let label = if replies.len() == 1 {
+            "1 reply".to_string()
+        } else {
+            format!("{} replies", replies.len())
+        };
+        let replies = if is_expanded {
+            replies
+                .into_iter()
+                .map(|reply| self.render_thread_reply(reply, cx))
+                .collect()
+        } else {
+            Vec::new()
+        };
+
+        v_flex()
+            .mt_1()
+            .child(
+                Button::new(("toggle-thread", root_id), label)
+                    .icon(if is_expanded {
+                        IconName::ChevronDown
+                    } else {
+                        IconName::ChevronRight
+                    })
+                    .icon_position(IconPosition::Start)
+                    .icon_size(IconSize::XSmall)
+                    .icon_color(Color::Muted)
+                    .label_size(LabelSize::XSmall)
+                    .color(Color::Accent)
+                    .on_click(cx.listener(move |this, _, cx| this.toggle_thread(root_id, cx))),
+            )
+            .when(is_expanded, |el| {
+                el.child(
+                    v_flex()
+                        .ml_2()
+                        .pl_2()
+                        .gap_1()
+                        .border_l_2()
+                        .border_color(cx.theme().colors().border)
+                        .children(replies)
+                        .child(
+                            div().child(
+                                Button::new(("reply-in-thread", root_id), "Reply")
+                                    .label_size(LabelSize::XSmall)
+                                    .color(Color::Muted)
+                                    .on_click(cx.listener(move |this, _, cx| {
+                                        this.reply_in_thread(root_id, cx)
+                                    })),
+                            ),
+                        ),
+                )
+            })
+    }
+
+    fn render_thread_reply(
+        &mut self,
+        reply: ChannelMessage,
+        cx: &mut ViewContext<Self>,
+    ) -> AnyElement {
+        let element_id: ElementId = match reply.id {
+            ChannelMessageId::Saved(id) => ("saved-thread-reply", id).into(),
+            ChannelMessageId::Pending(id) => ("pending-thread-reply", id).into(),
+        };
+        let header = h_flex()
+            .gap_1()
+            .child(Avatar::new(reply.sender.avatar_uri.clone()).size(rems(0.8)))
+            .child(
+                Label::new(reply.sender.github_login.clone())
+                    .size(LabelSize::XSmall)
+                    .weight(FontWeight::BOLD),
+            )
+            .child(
+                Label::new(time_format::format_localized_timestamp(
+                    reply.timestamp,
+                    OffsetDateTime::now_utc(),
+                    self.local_timezone,
+                    time_format::TimestampFormat::EnhancedAbsolute,
+                ))
+                .size(LabelSize::XSmall)
+                .color(Color::Muted),
+            );
+        let snippet = reply
+            .snippet
+            .as_ref()
+            .map(|snippet| self.render_snippet(reply.id, snippet, cx));
+        let reactions = match reply.id {
+            ChannelMessageId::Saved(id) if !reply.reactions.is_empty() => {
+                Some(self.render_reactions(id, &reply.reactions, cx))
+            }
+            _ => None,
+        };
+        let text = self.markdown_data.entry(reply.id).or_insert_with(|| {
+            Self::render_markdown_with_mentions(
+                &self.languages,
+                self.client.id(),
+                &reply,
+                self.local_timezone,
+                cx,
+            )
+        });
+
+        v_flex()
+            .w_full()
+            .text_ui_sm(cx)
+            .child(header)
+            .child(
+                div()
+                    .w_full()
+                    .id(element_id)
+                    .child(text.element("body".into(), cx)),
+            )
+            .children(snippet)
+            .children(reactions)
+            .into_any_element()
     }
 
     fn has_open_menu(&self, message_id: Option<u64>) -> bool {
@@ -618,6 +910,23 @@ impl ChatPanel {
                             .tooltip(|cx| Tooltip::text("Reply", cx)),
                     ),
                 )
+                .child(
+                    self.render_popover_button(
+                        cx,
+                        div()
+                            .id("reply-in-thread")
+                            .child(
+                                IconButton::new(
+                                    ("reply-in-thread", message_id),
+                                    IconName::MessageBubbles,
+                                )
+                                .on_click(cx.listener(
+                                    move |this, _, cx| this.reply_in_thread(message_id, cx),
+                                )),
+                            )
+                            .tooltip(|cx| Tooltip::text("Reply in Thread", cx)),
+                    ),
+                )
             })
             .when_some(message_id, |el, message_id| {
                 el.when(can_edit_message, |el| {
@@ -702,25 +1011,37 @@ impl ChatPanel {
     ) -> View<ContextMenu> {
         let menu = {
             ContextMenu::build(cx, move |menu, cx| {
-                menu.entry(
-                    "Copy message text",
-                    None,
-                    cx.handler_for(this, move |this, cx| {
-                        if let Some(message) = this.active_chat().and_then(|active_chat| {
-                            active_chat.read(cx).find_loaded_message(message_id)
-                        }) {
-                            let text = message.body.clone();
-                            cx.write_to_clipboard(ClipboardItem::new_string(text))
-                        }
-                    }),
-                )
-                .when(can_delete_message, |menu| {
+                let menu = REACTIONS.into_iter().fold(menu, |menu, emoji| {
                     menu.entry(
-                        "Delete message",
+                        format!("React with {emoji}"),
                         None,
-                        cx.handler_for(this, move |this, cx| this.remove_message(message_id, cx)),
+                        cx.handler_for(this, move |this, cx| {
+                            this.toggle_reaction(message_id, emoji.to_string(), cx)
+                        }),
                     )
-                })
+                });
+                menu.separator()
+                    .entry(
+                        "Copy message text",
+                        None,
+                        cx.handler_for(this, move |this, cx| {
+                            if let Some(message) = this.active_chat().and_then(|active_chat| {
+                                active_chat.read(cx).find_loaded_message(message_id)
+                            }) {
+                                let text = message.body.clone();
+                                cx.write_to_clipboard(ClipboardItem::new_string(text))
+                            }
+                        }),
+                    )
+                    .when(can_delete_message, |menu| {
+                        menu.entry(
+                            "Delete message",
+                            None,
+                            cx.handler_for(this, move |this, cx| {
+                                this.remove_message(message_id, cx)
+                            }),
+                        )
+                    })
             })
         };
         this.update(cx, |this, cx| {
@@ -811,6 +1132,358 @@ impl ChatPanel {
         }
     }
 
+    fn toggle_reaction(&mut self, message_id: u64, emoji: String, cx: &mut ViewContext<Self>) {
+        if let Some((chat, _)) = self.active_chat.as_ref() {
+            chat.update(cx, |chat, cx| chat.toggle_reaction(message_id, emoji, cx))
+                .detach_and_log_err(cx);
+        }
+    }
+
+    fn toggle_thread(&mut self, root_id: u64, cx: &mut ViewContext<Self>) {
+        if !self.expanded_threads.remove(&root_id) {
+            self.expanded_threads.insert(root_id);
+        }
+        self.remeasure_message(root_id, cx);
+    }
+
+    fn reply_in_thread(&mut self, root_id: u64, cx: &mut ViewContext<Self>) {
+        self.cancel_edit_message(cx);
+        self.expanded_threads.insert(root_id);
+        self.message_editor.update(cx, |editor, cx| {
+            editor.clear_reply_to_message_id();
+            editor.set_thread_id(root_id);
+            editor.focus_handle(cx).focus(cx);
+        });
+        self.remeasure_message(root_id, cx);
+    }
+
+    fn remeasure_message(&mut self, message_id: u64, cx: &mut ViewContext<Self>) {
+        if let Some(ix) = self
+            .active_chat()
+            .and_then(|chat| chat.read(cx).message_ix(message_id))
+        {
+            self.message_list.splice(ix..ix + 1, 1);
+        }
+        cx.notify();
+    }
+
+    fn share_selection_to_chat(
+        workspace: &mut Workspace,
+        _: &ShareSelectionToChat,
+        cx: &mut ViewContext<Workspace>,
+    ) {
+        let Some(editor) = workspace.active_item_as::<Editor>(cx) else {
+            return;
+        };
+        let Some((mut snippet, commit_sha)) =
+            editor.update(cx, |editor, cx| snippet::snippet_for_selection(editor, cx))
+        else {
+            return;
+        };
+        let Some(panel) = workspace.focus_panel::<Self>(cx) else {
+            return;
+        };
+        cx.spawn(|_, mut cx| async move {
+            snippet.commit_sha = commit_sha.await;
+            panel.update(&mut cx, |panel, cx| {
+                panel.message_editor.update(cx, |editor, cx| {
+                    editor.set_snippet(snippet);
+                    editor.focus_handle(cx).focus(cx);
+                });
+                cx.notify();
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+
+    fn open_search(&mut self, cx: &mut ViewContext<Self>) {
+        let search = self.search.get_or_insert_with(|| {
+            MessageSearch::new(cx.new_view(|cx| {
+                let mut editor = Editor::single_line(cx);
+                editor.set_placeholder_text(
+                    "Search messages (from:@user in:#channel after:2024-01-31)",
+                    cx,
+                );
+                editor
+            }))
+        });
+        search.visible = true;
+        search.editor.focus_handle(cx).focus(cx);
+        cx.notify();
+    }
+
+    fn toggle_search(&mut self, cx: &mut ViewContext<Self>) {
+        match self.search.as_mut() {
+            Some(search) if search.visible => {
+                search.visible = false;
+                self.message_editor.focus_handle(cx).focus(cx);
+                cx.notify();
+            }
+            _ => self.open_search(cx),
+        }
+    }
+
+    fn search(&mut self, _: &Confirm, cx: &mut ViewContext<Self>) {
+        let Some(search) = self.search.as_mut() else {
+            return;
+        };
+        let query = search.editor.read(cx).text(cx);
+        search.results.clear();
+        search.done = true;
+        search.pending_search = None;
+        match parse_search_query(&query) {
+            Ok(query) if query.text.is_empty() => {
+                search.query = None;
+                search.error = Some("Enter some text to search for".into());
+            }
+            Ok(query) => {
+                search.query = Some(query);
+                search.error = None;
+                self.load_search_results(cx);
+            }
+            Err(error) => {
+                search.query = None;
+                search.error = Some(error.into());
+            }
+        }
+        cx.notify();
+    }
+
+    fn load_search_results(&mut self, cx: &mut ViewContext<Self>) {
+        let Some(search) = self.search.as_mut() else {
+            return;
+        };
+        let Some(query) = search.query.clone() else {
+            return;
+        };
+        let before_message_id = search
+            .results
+            .last()
+            .and_then(|(_, message)| message.id.into());
+        let channel_id = if let Some(channel_name) = &query.channel {
+            let channel = self
+                .channel_store
+                .read(cx)
+                .ordered_channels()
+                .find(|(_, channel)| channel.name.as_ref() == channel_name)
+                .map(|(_, channel)| channel.id);
+            if channel.is_none() {
+                search.error = Some(format!("No channel named #{channel_name}").into());
+                return;
+            }
+            channel
+        } else {
+            None
+        };
+
+        let user_store = self.user_store.clone();
+        let channel_store = self.channel_store.clone();
+        let local_timezone = self.local_timezone;
+        search.pending_search = Some(cx.spawn(|this, mut cx| async move {
+            let results: Result<ChannelMessageSearchResults> = async {
+                let sender_id = if let Some(login) = &query.sender {
+                    let users = user_store
+                        .update(&mut cx, |user_store, cx| {
+                            user_store.fuzzy_search_users(login.clone(), cx)
+                        })?
+                        .await?;
+                    let user = users
+                        .into_iter()
+                        .find(|user| user.github_login.eq_ignore_ascii_case(login))
+                        .ok_or_else(|| anyhow!("No user named @{login}"))?;
+                    Some(user.id)
+                } else {
+                    None
+                };
+                let search = ChannelMessageSearch {
+                    query: query.text.clone(),
+                    channel_id,
+                    sender_id,
+                    sent_after: query
+                        .sent_after
+                        .map(|date| date.midnight().assume_offset(local_timezone)),
+                    sent_before: query
+                        .sent_before
+                        .map(|date| date.midnight().assume_offset(local_timezone)),
+                };
+                channel_store
+                    .update(&mut cx, |store, cx| {
+                        store.search_messages(&search, before_message_id, cx)
+                    })?
+                    .await
+            }
+            .await;
+
+            this.update(&mut cx, |this, cx| {
+                if let Some(search) = this.search.as_mut() {
+                    search.pending_search = None;
+                    match results {
+                        Ok(results) => {
+                            search.results.extend(results.messages);
+                            search.done = results.done;
+                        }
+                        Err(error) => search.error = Some(error.to_string().into()),
+                    }
+                }
+                cx.notify();
+            })
+            .ok();
+        }));
+    }
+
+    fn jump_to_search_result(
+        &mut self,
+        channel_id: ChannelId,
+        message: &ChannelMessage,
+        cx: &mut ViewContext<Self>,
+    ) {
+        let ChannelMessageId::Saved(message_id) = message.id else {
+            return;
+        };
+        if let Some(search) = self.search.as_mut() {
+            search.visible = false;
+        }
+
+        // Replies in a thread are shown beneath the message that started it, so jump to that
+        // message and expand its thread once the channel's chat is open.
+        let thread_id = message.thread_id;
+        let select_channel =
+            self.select_channel(channel_id, Some(thread_id.unwrap_or(message_id)), cx);
+        cx.spawn(|this, mut cx| async move {
+            select_channel.await?;
+            if let Some(thread_id) = thread_id {
+                this.update(&mut cx, |this, cx| {
+                    this.expanded_threads.insert(thread_id);
+                    cx.notify();
+                })?;
+            }
+            anyhow::Ok(())
+        })
+        .detach_and_log_err(cx);
+        cx.notify();
+    }
+
+    fn render_search(&self, search: &MessageSearch, cx: &mut ViewContext<Self>) -> Div {
+        let query_text = search
+            .query
+            .as_ref()
+            .map(|query| query.text.clone())
+            .unwrap_or_default();
+        let channel_store = self.channel_store.read(cx);
+        let results = search
+            .results
+            .iter()
+            .filter_map(|(channel_id, message)| {
+                let ChannelMessageId::Saved(message_id) = message.id else {
+                    return None;
+                };
+                let channel_name = channel_store
+                    .channel_for_id(*channel_id)
+                    .map(|channel| channel.name.clone());
+                let body = message.body.replace('\n', " ");
+                let highlights = match_indices(&body, &query_text);
+                let channel_id = *channel_id;
+                let message = message.clone();
+                Some(
+                    v_flex()
+                        .id(("search-result", message_id))
+                        .p_1()
+                        .rounded_md()
+                        .cursor_pointer()
+                        .hover(|style| style.bg(cx.theme().colors().element_hover))
+                        .child(
+                            h_flex()
+                                .gap_1()
+                                .child(
+                                    Avatar::new(message.sender.avatar_uri.clone()).size(rems(0.8)),
+                                )
+                                .child(
+                                    Label::new(message.sender.github_login.clone())
+                                        .size(LabelSize::XSmall)
+                                        .weight(FontWeight::BOLD),
+                                )
+                                .when_some(channel_name, |el, channel_name| {
+                                    el.child(
+                                        Label::new(format!("#{channel_name}"))
+                                            .size(LabelSize::XSmall)
+                                            .color(Color::Muted),
+                                    )
+                                })
+                                .child(
+                                    Label::new(time_format::format_localized_timestamp(
+                                        message.timestamp,
+                                        OffsetDateTime::now_utc(),
+                                        self.local_timezone,
+                                        time_format::TimestampFormat::EnhancedAbsolute,
+                                    ))
+                                    .size(LabelSize::XSmall)
+                                    .color(Color::Muted),
+                                ),
+                        )
+                        .child(HighlightedLabel::new(body, highlights).size(LabelSize::Small))
+                        .on_click(cx.listener(move |this, _, cx| {
+                            this.jump_to_search_result(channel_id, &message, cx)
+                        })),
+                )
+            })
+            .collect::<Vec<_>>();
+        let is_searching = search.pending_search.is_some();
+        let found_nothing =
+            !is_searching && search.error.is_none() && search.query.is_some() && results.is_empty();
+
+        v_flex()
+            .size_full()
+            .child(
+                h_flex()
+                    .p_2()
+                    .gap_1()
+                    .border_b_1()
+                    .border_color(cx.theme().colors().border)
+                    .on_action(cx.listener(Self::search))
+                    .child(Icon::new(IconName::MagnifyingGlass).color(Color::Muted))
+                    .child(search.editor.clone()),
+            )
+            .when_some(search.error.clone(), |el, error| {
+                el.child(
+                    div()
+                        .px_2()
+                        .py_1()
+                        .child(Label::new(error).size(LabelSize::Small).color(Color::Error)),
+                )
+            })
+            .child(
+                v_flex()
+                    .id("search-results")
+                    .flex_1()
+                    .overflow_y_scroll()
+                    .px_1()
+                    .py_1()
+                    .gap_0p5()
+                    .children(results)
+                    .when(is_searching, |el| {
+                        el.child(
+                            Label::new("Searching…")
+                                .size(LabelSize::Small)
+                                .color(Color::Muted),
+                        )
+                    })
+                    .when(found_nothing, |el| {
+                        el.child(
+                            Label::new("No messages found")
+                                .size(LabelSize::Small)
+                                .color(Color::Muted),
+                        )
+                    })
+                    .when(!is_searching && !search.done, |el| {
+                        el.child(
+                            Button::new("load-more-search-results", "Load more results")
+                                .full_width()
+                                .on_click(cx.listener(|this, _, cx| this.load_search_results(cx))),
+                        )
+                    }),
+            )
+    }
+
     fn remove_message(&mut self, id: u64, cx: &mut ViewContext<Self>) {
         if let Some((chat, _)) = self.active_chat.as_ref() {
             chat.update(cx, |chat, cx| chat.remove_message(id, cx).detach())
@@ -896,6 +1569,16 @@ impl ChatPanel {
             .update(cx, |editor, _| editor.clear_reply_to_message_id());
     }
 
+    fn close_thread_preview(&mut self, cx: &mut ViewContext<Self>) {
+        self.message_editor
+            .update(cx, |editor, _| editor.clear_thread_id());
+    }
+
+    fn remove_snippet(&mut self, cx: &mut ViewContext<Self>) {
+        self.message_editor
+            .update(cx, |editor, _| editor.clear_snippet());
+    }
+
     fn cancel_edit_message(&mut self, cx: &mut ViewContext<Self>) {
         self.message_editor.update(cx, |editor, cx| {
             // only clear the editor input if we were editing a message
@@ -927,7 +1610,10 @@ impl Render for ChatPanel {
         let message_editor = self.message_editor.read(cx);
 
         let reply_to_message_id = message_editor.reply_to_message_id();
+        let thread_id = message_editor.thread_id();
+        let snippet = message_editor.snippet().cloned();
         let edit_message_id = message_editor.edit_message_id();
+        let is_searching = self.search.as_ref().map_or(false, |search| search.visible);
 
         v_flex()
             .key_context("ChatPanel")
@@ -936,24 +1622,33 @@ impl Render for ChatPanel {
             .on_action(cx.listener(Self::send))
             .child(
                 h_flex().child(
-                    TabBar::new("chat_header").child(
-                        h_flex()
-                            .w_full()
-                            .h(Tab::container_height(cx))
-                            .px_2()
-                            .child(Label::new(
-                                self.active_chat
-                                    .as_ref()
-                                    .and_then(|c| {
-                                        Some(format!("#{}", c.0.read(cx).channel(cx)?.name))
-                                    })
-                                    .unwrap_or("Chat".to_string()),
-                            )),
-                    ),
+                    TabBar::new("chat_header")
+                        .child(
+                            h_flex().w_full().h(Tab::container_height(cx)).px_2().child(
+                                Label::new(
+                                    self.active_chat
+                                        .as_ref()
+                                        .and_then(|c| {
+                                            Some(format!("#{}", c.0.read(cx).channel(cx)?.name))
+                                        })
+                                        .unwrap_or("Chat".to_string()),
+                                ),
+                            ),
+                        )
+                        .end_child(
+                            IconButton::new("toggle-message-search", IconName::MagnifyingGlass)
+                                .toggle_state(is_searching)
+                                .tooltip(|cx| {
+                                    Tooltip::for_action("Search Messages", &SearchMessages, cx)
+                                })
+                                .on_click(cx.listener(|this, _, cx| this.toggle_search(cx))),
+                        ),
                 ),
             )
             .child(div().flex_grow().px_2().map(|this| {
-                if self.active_chat.is_some() {
+                if let Some(search) = self.search.as_ref().filter(|search| search.visible) {
+                    this.px_0().child(self.render_search(search, cx))
+                } else if self.active_chat.is_some() {
                     this.child(list(self.message_list.clone()).size_full())
                 } else {
                     this.child(
@@ -1069,6 +1764,98 @@ impl Render for ChatPanel {
                     )
                 })
             })
+            .when_some(thread_id, |el, thread_id| {
+                let root_sender = self.active_chat().and_then(|active_chat| {
+                    active_chat
+                        .read(cx)
+                        .find_loaded_message(thread_id)
+                        .map(|message| message.sender.github_login.clone())
+                });
+
+                el.when_some(root_sender, |el, root_sender| {
+                    el.child(
+                        h_flex()
+                            .justify_between()
+                            .items_start()
+                            .py_1()
+                            .px_2()
+                            .border_t_1()
+                            .border_color(cx.theme().colors().border)
+                            .bg(cx.theme().colors().background)
+                            .child(
+                                h_flex()
+                                    .id(("thread-preview", thread_id))
+                                    .child(
+                                        Label::new("Replying in thread of ").size(LabelSize::Small),
+                                    )
+                                    .child(
+                                        Label::new(format!("@{root_sender}"))
+                                            .size(LabelSize::Small)
+                                            .weight(FontWeight::BOLD),
+                                    )
+                                    .when_some(channel_id, |this, channel_id| {
+                                        this.cursor_pointer().on_click(cx.listener(
+                                            move |chat_panel, _, cx| {
+                                                chat_panel
+                                                    .select_channel(
+                                                        channel_id,
+                                                        thread_id.into(),
+                                                        cx,
+                                                    )
+                                                    .detach_and_log_err(cx)
+                                            },
+                                        ))
+                                    }),
+                            )
+                            .child(
+                                IconButton::new("close-thread-preview", IconName::Close)
+                                    .shape(ui::IconButtonShape::Square)
+                                    .tooltip(|cx| Tooltip::text("Close thread", cx))
+                                    .on_click(cx.listener(move |this, _, cx| {
+                                        this.close_thread_preview(cx);
+                                    })),
+                            ),
+                    )
+                })
+            })
+            .when_some(snippet, |el, snippet| {
+                el.child(
+                    h_flex()
+                        .justify_between()
+                        .items_center()
+                        .py_1()
+                        .px_2()
+                        .border_t_1()
+                        .border_color(cx.theme().colors().border)
+                        .bg(cx.theme().colors().background)
+                        .child(
+                            h_flex()
+                                .gap_1()
+                                .child(
+                                    Icon::new(IconName::FileCode)
+                                        .size(IconSize::Small)
+                                        .color(Color::Muted),
+                                )
+                                .child(
+                                    Label::new(format!(
+                                        "{}:{}-{}",
+                                        snippet.path,
+                                        snippet.start_row + 1,
+                                        snippet.end_row + 1
+                                    ))
+                                    .size(LabelSize::Small),
+                                ),
+                        )
+                        .child(
+                            IconButton::new("remove-snippet", IconName::Close)
+                                .shape(ui::IconButtonShape::Square)
+                                .tooltip(|cx| Tooltip::text("Remove snippet", cx))
+                                .on_click(cx.listener(move |this, _, cx| {
+                                    this.remove_snippet(cx);
+                                })),
+                        ),
+                )
+            })
             .children(
                 Some(
                     h_flex()
@@ -1076,6 +1863,7 @@ impl Render for ChatPanel {
                         .on_action(cx.listener(|this, _: &actions::Cancel, cx| {
                             this.cancel_edit_message(cx);
                             this.close_reply_preview(cx);
+                            this.close_thread_preview(cx);
                         }))
                         .map(|el| el.child(self.message_editor.clone())),
                 )
@@ -1182,6 +1970,9 @@ mod tests {
             mentions: vec![(ranges[0].clone(), 101), (ranges[1].clone(), 102)],
             reply_to_message_id: None,
             edited_at: None,
+            thread_id: None,
+            reactions: Vec::new(),
+            snippet: None,
         };
 
         let message = ChatPanel::render_markdown_with_mentions(
@@ -1236,6 +2027,9 @@ mod tests {
             mentions: Vec::new(),
             reply_to_message_id: None,
             edited_at: None,
+            thread_id: None,
+            reactions: Vec::new(),
+            snippet: None,
         };
 
         let message = ChatPanel::render_markdown_with_mentions(
@@ -1283,6 +2077,9 @@ mod tests {
             mentions: Vec::new(),
             reply_to_message_id: None,
             edited_at: None,
+            thread_id: None,
+            reactions: Vec::new(),
+            snippet: None,
         };
 
         let message = ChatPanel::render_markdown_with_mentions(
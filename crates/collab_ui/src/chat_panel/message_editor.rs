@@ -1,5 +1,5 @@
 use anyhow::{Context, Result};
-use channel::{ChannelChat, ChannelStore, MessageParams};
+use channel::{ChannelChat, ChannelMessageSnippet, ChannelStore, MessageParams};
 use client::{UserId, UserStore};
 use collections::HashSet;
 use editor::{AnchorRangeExt, CompletionProvider, Editor, EditorElement, EditorStyle};
@@ -48,6 +48,8 @@ pub struct MessageEditor {
     mentions: Vec<UserId>,
     mentions_task: Option<Task<()>>,
     reply_to_message_id: Option<u64>,
+    thread_id: Option<u64>,
+    snippet: Option<ChannelMessageSnippet>,
     edit_message_id: Option<u64>,
 }
 
@@ -159,6 +161,8 @@ impl MessageEditor {
             mentions: Vec::new(),
             mentions_task: None,
             reply_to_message_id: None,
+            thread_id: None,
+            snippet: None,
             edit_message_id: None,
         }
     }
@@ -175,6 +179,30 @@ impl MessageEditor {
         self.reply_to_message_id = None;
     }
 
+    pub fn thread_id(&self) -> Option<u64> {
+        self.thread_id
+    }
+
+    pub fn set_thread_id(&mut self, thread_id: u64) {
+        self.thread_id = Some(thread_id);
+    }
+
+    pub fn clear_thread_id(&mut self) {
+        self.thread_id = None;
+    }
+
+    pub fn snippet(&self) -> Option<&ChannelMessageSnippet> {
+        self.snippet.as_ref()
+    }
+
+    pub fn set_snippet(&mut self, snippet: ChannelMessageSnippet) {
+        self.snippet = Some(snippet);
+    }
+
+    pub fn clear_snippet(&mut self) {
+        self.snippet = None;
+    }
+
     pub fn edit_message_id(&self) -> Option<u64> {
         self.edit_message_id
     }
@@ -221,11 +249,15 @@ impl MessageEditor {
             editor.clear(cx);
             self.mentions.clear();
             let reply_to_message_id = std::mem::take(&mut self.reply_to_message_id);
+            let thread_id = std::mem::take(&mut self.thread_id);
+            let snippet = std::mem::take(&mut self.snippet);
 
             MessageParams {
                 text,
                 mentions,
                 reply_to_message_id,
+                thread_id,
+                snippet,
             }
         })
     }
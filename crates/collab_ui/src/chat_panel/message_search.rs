@@ -0,0 +1,118 @@
+use channel::ChannelMessage;
+use client::ChannelId;
+use editor::Editor;
+use gpui::{SharedString, Task, View};
+use time::{macros::format_description, Date};
+
+/// The state of a search over the chat history, shown in place of the message list.
+pub(crate) struct MessageSearch {
+    pub editor: View<Editor>,
+    pub visible: bool,
+    pub query: Option<ParsedSearchQuery>,
+    pub results: Vec<(ChannelId, ChannelMessage)>,
+    pub done: bool,
+    pub error: Option<SharedString>,
+    pub pending_search: Option<Task<()>>,
+}
+
+impl MessageSearch {
+    pub fn new(editor: View<Editor>) -> Self {
+        Self {
+            editor,
+            visible: true,
+            query: None,
+            results: Vec::new(),
+            done: true,
+            error: None,
+            pending_search: None,
+        }
+    }
+}
+
+/// A search query, with the `from:`, `in:`, `after:` and `before:` filters separated from the
+/// text to search for.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub(crate) struct ParsedSearchQuery {
+    pub text: String,
+    pub sender: Option<String>,
+    pub channel: Option<String>,
+    pub sent_after: Option<Date>,
+    pub sent_before: Option<Date>,
+}
+
+pub(crate) fn parse_search_query(query: &str) -> Result<ParsedSearchQuery, String> {
+    let date_format = format_description!("[year]-[month]-[day]");
+    let parse_date =
+        |date: &str| Date::parse(date, date_format).map_err(|_| format!("Invalid date \"{date}\""));
+
+    let mut parsed = ParsedSearchQuery::default();
+    let mut words = Vec::new();
+    for word in query.split_whitespace() {
+        if let Some(sender) = word.strip_prefix("from:") {
+            parsed.sender = Some(sender.trim_start_matches('@').to_string());
+        } else if let Some(channel) = word.strip_prefix("in:") {
+            parsed.channel = Some(channel.trim_start_matches('#').to_string());
+        } else if let Some(date) = word.strip_prefix("after:") {
+            parsed.sent_after = Some(parse_date(date)?);
+        } else if let Some(date) = word.strip_prefix("before:") {
+            parsed.sent_before = Some(parse_date(date)?);
+        } else {
+            words.push(word);
+        }
+    }
+    parsed.text = words.join(" ");
+    Ok(parsed)
+}
+
+/// Returns the byte offsets of the characters in `text` that are part of an occurrence of one of
+/// the words in `query`, ignoring ASCII case.
+pub(crate) fn match_indices(text: &str, query: &str) -> Vec<usize> {
+    let lowercase_text = text.to_ascii_lowercase();
+    let mut indices = Vec::new();
+    for word in query.split_whitespace() {
+        let word = word.to_ascii_lowercase();
+        for (start, _) in lowercase_text.match_indices(&word) {
+            indices.extend(
+                text[start..start + word.len()]
+                    .char_indices()
+                    .map(|(ix, _)| start + ix),
+            );
+        }
+    }
+    indices.sort_unstable();
+    indices.dedup();
+    indices
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use time::Month;
+
+    #[test]
+    fn test_parse_search_query() {
+        assert_eq!(
+            parse_search_query("deploy from:@nathan in:#zed after:2024-10-01 failed").unwrap(),
+            ParsedSearchQuery {
+                text: "deploy failed".into(),
+                sender: Some("nathan".into()),
+                channel: Some("zed".into()),
+                sent_after: Some(Date::from_calendar_date(2024, Month::October, 1).unwrap()),
+                sent_before: None,
+            }
+        );
+        assert_eq!(
+            parse_search_query("before:yesterday").unwrap_err(),
+            "Invalid date \"yesterday\""
+        );
+    }
+
+    #[test]
+    fn test_match_indices() {
+        assert_eq!(
+            match_indices("Deploy the deploy", "deploy"),
+            [0, 1, 2, 3, 4, 5, 11, 12, 13, 14, 15, 16]
+        );
+        assert_eq!(match_indices("héllo wörld", "wö"), [7, 8]);
+    }
+}
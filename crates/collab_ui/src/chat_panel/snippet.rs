@@ -0,0 +1,98 @@
+use channel::ChannelMessageSnippet;
+use editor::{scroll::Autoscroll, Editor};
+use gpui::{Task, ViewContext};
+use language::Point;
+use project::{File, ProjectPath};
+use std::path::{Component, Path};
+use workspace::Workspace;
+
+/// Builds a snippet from the lines covered by the editor's newest selection, along with a task
+/// that resolves to the commit checked out in the file's repository, if any.
+pub(crate) fn snippet_for_selection(
+    editor: &mut Editor,
+    cx: &mut ViewContext<Editor>,
+) -> Option<(ChannelMessageSnippet, Task<Option<String>>)> {
+    let selection = editor.selections.newest::<Point>(cx);
+    let buffer = editor.buffer().read(cx).as_singleton()?;
+    let buffer = buffer.read(cx);
+    let file = File::from_dyn(buffer.file())?;
+
+    let start_row = selection.start.row;
+    let mut end_row = selection.end.row;
+    if end_row > start_row && selection.end.column == 0 {
+        end_row -= 1;
+    }
+    let range = Point::new(start_row, 0)..Point::new(end_row, buffer.line_len(end_row));
+    let text = buffer.text_for_range(range).collect::<String>();
+
+    let repo = file
+        .worktree
+        .read(cx)
+        .as_local()
+        .and_then(|worktree| worktree.local_git_repo(file.path()));
+    let commit_sha = cx
+        .background_executor()
+        .spawn(async move { repo?.head_sha() });
+
+    Some((
+        ChannelMessageSnippet {
+            path: file.full_path(cx).to_string_lossy().into_owned(),
+            start_row,
+            end_row,
+            commit_sha: None,
+            text,
+        },
+        commit_sha,
+    ))
+}
+
+/// Opens the file a snippet was shared from, if it belongs to one of the workspace's worktrees,
+/// and selects the snippet's first line.
+pub(crate) fn open_snippet(
+    workspace: &mut Workspace,
+    snippet: &ChannelMessageSnippet,
+    cx: &mut ViewContext<Workspace>,
+) {
+    open_file_at_row(workspace, Path::new(&snippet.path), snippet.start_row, cx);
+}
+
+/// Opens a file given its full path, starting with the root name of its worktree, and selects
+/// the start of the given row.
+pub(crate) fn open_file_at_row(
+    workspace: &mut Workspace,
+    full_path: &Path,
+    row: u32,
+    cx: &mut ViewContext<Workspace>,
+) {
+    let mut components = full_path.components();
+    let Some(Component::Normal(root_name)) = components.next() else {
+        return;
+    };
+    let Some(worktree) = workspace
+        .project()
+        .read(cx)
+        .visible_worktrees(cx)
+        .find(|worktree| worktree.read(cx).root_name() == root_name)
+    else {
+        return;
+    };
+    let project_path = ProjectPath {
+        worktree_id: worktree.read(cx).id(),
+        path: components.as_path().into(),
+    };
+
+    let open_task = workspace.open_path(project_path, None, true, cx);
+    cx.spawn(|_, mut cx| async move {
+        let item = open_task.await?;
+        if let Some(editor) = item.downcast::<Editor>() {
+            editor.update(&mut cx, |editor, cx| {
+                let point = Point::new(row, 0);
+                editor.change_selections(Some(Autoscroll::center()), cx, |selections| {
+                    selections.select_ranges([point..point])
+                });
+            })?;
+        }
+        anyhow::Ok(())
+    })
+    .detach_and_log_err(cx);
+}
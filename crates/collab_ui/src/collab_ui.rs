@@ -4,6 +4,7 @@ pub mod collab_panel;
 pub mod notification_panel;
 pub mod notifications;
 mod panel_settings;
+pub mod review_panel;
 
 use std::{rc::Rc, sync::Arc};
 
@@ -14,7 +15,7 @@ use gpui::{
 };
 use panel_settings::MessageEditorSettings;
 pub use panel_settings::{
-    ChatPanelSettings, CollaborationPanelSettings, NotificationPanelSettings,
+    ChatPanelSettings, CollaborationPanelSettings, NotificationPanelSettings, ReviewPanelSettings,
 };
 use release_channel::ReleaseChannel;
 use settings::Settings;
@@ -25,6 +26,7 @@ pub fn init(app_state: &Arc<AppState>, cx: &mut AppContext) {
     CollaborationPanelSettings::register(cx);
     ChatPanelSettings::register(cx);
     NotificationPanelSettings::register(cx);
+    ReviewPanelSettings::register(cx);
     MessageEditorSettings::register(cx);
 
     channel_view::init(cx);
@@ -32,6 +34,7 @@ pub fn init(app_state: &Arc<AppState>, cx: &mut AppContext) {
     collab_panel::init(cx);
     notification_panel::init(cx);
     notifications::init(app_state, cx);
+    review_panel::init(cx);
     title_bar::init(cx);
 }
 
@@ -0,0 +1,361 @@
+mod review_thread_view;
+
+use crate::{chat_panel::snippet::open_file_at_row, ReviewPanelSettings};
+use anyhow::Result;
+use client::UserStore;
+use db::kvp::KEY_VALUE_STORE;
+use gpui::{
+    actions, AppContext, AsyncWindowContext, EventEmitter, FocusHandle, FocusableView, Model,
+    Render, Subscription, Task, View, ViewContext, VisualContext, WeakView, WindowContext,
+};
+use project::{review_threads::ReviewThread, Fs, Project};
+use review_thread_view::ReviewThreadBlocks;
+use serde::{Deserialize, Serialize};
+use settings::{Settings, SettingsStore};
+use std::sync::Arc;
+use ui::{prelude::*, Avatar, Button, ListHeader, ListItem, ListItemSpacing, Tab};
+use util::{ResultExt, TryFutureExt};
+use workspace::{
+    dock::{DockPosition, Panel, PanelEvent},
+    Workspace,
+};
+
+const REVIEW_PANEL_KEY: &str = "ReviewPanel";
+
+actions!(review_panel, [ToggleFocus, StartReviewThread]);
+
+pub fn init(cx: &mut AppContext) {
+    cx.observe_new_views(|workspace: &mut Workspace, _| {
+        workspace.register_action(|workspace, _: &ToggleFocus, cx| {
+            workspace.toggle_panel_focus::<ReviewPanel>(cx);
+        });
+    })
+    .detach();
+    cx.observe_new_views(ReviewThreadBlocks::register).detach();
+}
+
+/// Lists the review threads on the files of the workspace's project, when it's shared in a
+/// channel's call.
+pub struct ReviewPanel {
+    fs: Arc<dyn Fs>,
+    project: Model<Project>,
+    user_store: Model<UserStore>,
+    workspace: WeakView<Workspace>,
+    width: Option<Pixels>,
+    show_resolved: bool,
+    focus_handle: FocusHandle,
+    pending_serialization: Task<Option<()>>,
+    subscriptions: Vec<Subscription>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct SerializedReviewPanel {
+    width: Option<Pixels>,
+}
+
+impl ReviewPanel {
+    pub fn new(workspace: &mut Workspace, cx: &mut ViewContext<Workspace>) -> View<Self> {
+        let fs = workspace.app_state().fs.clone();
+        let user_store = workspace.app_state().user_store.clone();
+        let project = workspace.project().clone();
+        let workspace_handle = workspace.weak_handle();
+
+        cx.new_view(|cx: &mut ViewContext<Self>| {
+            let mut this = Self {
+                fs,
+                project,
+                user_store,
+                workspace: workspace_handle,
+                width: None,
+                show_resolved: false,
+                focus_handle: cx.focus_handle(),
+                pending_serialization: Task::ready(None),
+                subscriptions: Vec::new(),
+            };
+
+            this.subscriptions.extend([
+                cx.subscribe(&this.project, |_, _, event, cx| match event {
+                    project::Event::ReviewThreadsChanged
+                    | project::Event::RemoteIdChanged(_)
+                    | project::Event::DisconnectedFromHost => cx.notify(),
+                    _ => {}
+                }),
+                cx.observe(&this.user_store, |_, _, cx| cx.notify()),
+                cx.observe_global::<SettingsStore>(|_: &mut Self, cx| cx.notify()),
+            ]);
+            this
+        })
+    }
+
+    pub fn load(
+        workspace: WeakView<Workspace>,
+        cx: AsyncWindowContext,
+    ) -> Task<Result<View<Self>>> {
+        cx.spawn(|mut cx| async move {
+            let serialized_panel = if let Some(panel) = cx
+                .background_executor()
+                .spawn(async move { KEY_VALUE_STORE.read_kvp(REVIEW_PANEL_KEY) })
+                .await
+                .log_err()
+                .flatten()
+            {
+                Some(serde_json::from_str::<SerializedReviewPanel>(&panel)?)
+            } else {
+                None
+            };
+
+            workspace.update(&mut cx, |workspace, cx| {
+                let panel = Self::new(workspace, cx);
+                if let Some(serialized_panel) = serialized_panel {
+                    panel.update(cx, |panel, cx| {
+                        panel.width = serialized_panel.width.map(|w| w.round());
+                        cx.notify();
+                    });
+                }
+                panel
+            })
+        })
+    }
+
+    fn serialize(&mut self, cx: &mut ViewContext<Self>) {
+        let width = self.width;
+        self.pending_serialization = cx.background_executor().spawn(
+            async move {
+                KEY_VALUE_STORE
+                    .write_kvp(
+                        REVIEW_PANEL_KEY.into(),
+                        serde_json::to_string(&SerializedReviewPanel { width })?,
+                    )
+                    .await?;
+                anyhow::Ok(())
+            }
+            .log_err(),
+        );
+    }
+
+    fn open_thread(&mut self, thread_id: u64, cx: &mut ViewContext<Self>) {
+        let Some(thread) = self.project.read(cx).review_thread(thread_id).cloned() else {
+            return;
+        };
+        self.workspace
+            .update(cx, |workspace, cx| {
+                open_file_at_row(workspace, &thread.path, *thread.rows.start(), cx)
+            })
+            .ok();
+    }
+
+    fn resolve_thread(&mut self, thread_id: u64, resolved: bool, cx: &mut ViewContext<Self>) {
+        self.project
+            .update(cx, |project, cx| {
+                project.resolve_review_thread(thread_id, resolved, cx)
+            })
+            .detach_and_log_err(cx);
+    }
+
+    fn render_thread(&self, thread: &ReviewThread, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let thread_id = thread.id;
+        let resolved = thread.resolved;
+        let first_comment = thread.comments.first();
+        let author = first_comment.and_then(|comment| {
+            self.user_store.update(cx, |user_store, cx| {
+                user_store.get_user_optimistic(comment.sender_id, cx)
+            })
+        });
+        let preview = first_comment
+            .and_then(|comment| comment.body.lines().next())
+            .unwrap_or_default()
+            .to_string();
+        let replies = thread.comments.len().saturating_sub(1);
+
+        ListItem::new(("review-thread", thread_id))
+            .spacing(ListItemSpacing::Sparse)
+            .start_slot::<Avatar>(author.map(|author| Avatar::new(author.avatar_uri.clone())))
+            .child(
+                v_flex()
+                    .overflow_hidden()
+                    .child(
+                        h_flex()
+                            .gap_1()
+                            .child(
+                                Label::new(format!(
+                                    "{}:{}",
+                                    thread.path.display(),
+                                    thread.rows.start() + 1
+                                ))
+                                .size(LabelSize::Small)
+                                .color(Color::Muted),
+                            )
+                            .when(replies > 0, |el| {
+                                el.child(
+                                    Label::new(if replies == 1 {
+                                        "1 reply".to_string()
+                                    } else {
+                                        format!("{replies} replies")
+                                    })
+                                    .size(LabelSize::Small)
+                                    .color(Color::Muted),
+                                )
+                            }),
+                    )
+                    .child(Label::new(preview).single_line()),
+            )
+            .end_slot(
+                Button::new(
+                    ("resolve-review-thread", thread_id),
+                    if resolved { "Reopen" } else { "Resolve" },
+                )
+                .label_size(LabelSize::Small)
+                .on_click(
+                    cx.listener(move |this, _, cx| this.resolve_thread(thread_id, !resolved, cx)),
+                ),
+            )
+            .on_click(cx.listener(move |this, _, cx| this.open_thread(thread_id, cx)))
+    }
+}
+
+impl Render for ReviewPanel {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let project = self.project.read(cx);
+        let is_shared = project.remote_id().is_some();
+        let (resolved, open): (Vec<_>, Vec<_>) = project
+            .review_threads()
+            .cloned()
+            .partition(|thread| thread.resolved);
+        let show_resolved = self.show_resolved;
+
+        v_flex()
+            .key_context("ReviewPanel")
+            .track_focus(&self.focus_handle)
+            .size_full()
+            .child(
+                h_flex()
+                    .justify_between()
+                    .px_2()
+                    .py_1()
+                    // Match the height of the tab bar so they line up.
+                    .h(Tab::container_height(cx))
+                    .border_b_1()
+                    .border_color(cx.theme().colors().border)
+                    .child(Label::new("Review"))
+                    .child(Icon::new(IconName::MessageBubbles)),
+            )
+            .map(|this| {
+                if !is_shared {
+                    this.child(
+                        v_flex().p_4().child(
+                            Label::new(
+                                "Share a project in a channel call to leave review comments.",
+                            )
+                            .color(Color::Muted)
+                            .size(LabelSize::Small),
+                        ),
+                    )
+                } else if open.is_empty() && resolved.is_empty() {
+                    this.child(
+                        v_flex().p_4().child(
+                            Label::new(
+                                "Select some lines and start a review thread to comment on them.",
+                            )
+                            .color(Color::Muted)
+                            .size(LabelSize::Small),
+                        ),
+                    )
+                } else {
+                    this.child(
+                        v_flex()
+                            .id("review-threads")
+                            .overflow_y_scroll()
+                            .flex_1()
+                            .px_1()
+                            .child(ListHeader::new(format!("Open ({})", open.len())))
+                            .children(
+                                open.iter()
+                                    .map(|thread| self.render_thread(thread, cx))
+                                    .collect::<Vec<_>>(),
+                            )
+                            .child(
+                                ListHeader::new(format!("Resolved ({})", resolved.len()))
+                                    .toggle(Some(show_resolved))
+                                    .on_toggle(cx.listener(|this, _, cx| {
+                                        this.show_resolved = !this.show_resolved;
+                                        cx.notify();
+                                    })),
+                            )
+                            .when(show_resolved, |el| {
+                                el.children(
+                                    resolved
+                                        .iter()
+                                        .map(|thread| self.render_thread(thread, cx))
+                                        .collect::<Vec<_>>(),
+                                )
+                            }),
+                    )
+                }
+            })
+    }
+}
+
+impl FocusableView for ReviewPanel {
+    fn focus_handle(&self, _: &AppContext) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl EventEmitter<PanelEvent> for ReviewPanel {}
+
+impl Panel for ReviewPanel {
+    fn persistent_name() -> &'static str {
+        "ReviewPanel"
+    }
+
+    fn position(&self, cx: &WindowContext) -> DockPosition {
+        ReviewPanelSettings::get_global(cx).dock
+    }
+
+    fn position_is_valid(&self, position: DockPosition) -> bool {
+        matches!(position, DockPosition::Left | DockPosition::Right)
+    }
+
+    fn set_position(&mut self, position: DockPosition, cx: &mut ViewContext<Self>) {
+        settings::update_settings_file::<ReviewPanelSettings>(
+            self.fs.clone(),
+            cx,
+            move |settings, _| settings.dock = Some(position),
+        );
+    }
+
+    fn size(&self, cx: &WindowContext) -> Pixels {
+        self.width
+            .unwrap_or_else(|| ReviewPanelSettings::get_global(cx).default_width)
+    }
+
+    fn set_size(&mut self, size: Option<Pixels>, cx: &mut ViewContext<Self>) {
+        self.width = size;
+        self.serialize(cx);
+        cx.notify();
+    }
+
+    fn icon(&self, cx: &WindowContext) -> Option<IconName> {
+        ReviewPanelSettings::get_global(cx)
+            .button
+            .then_some(IconName::MessageBubbles)
+    }
+
+    fn icon_tooltip(&self, _cx: &WindowContext) -> Option<&'static str> {
+        Some("Review Panel")
+    }
+
+    fn icon_label(&self, cx: &WindowContext) -> Option<String> {
+        let open_threads = self
+            .project
+            .read(cx)
+            .review_threads()
+            .filter(|thread| !thread.resolved)
+            .count();
+        (open_threads > 0).then(|| open_threads.to_string())
+    }
+
+    fn toggle_action(&self) -> Box<dyn gpui::Action> {
+        Box::new(ToggleFocus)
+    }
+}
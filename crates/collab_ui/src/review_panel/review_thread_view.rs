@@ -0,0 +1,492 @@
+use super::StartReviewThread;
+use client::UserStore;
+use collections::{HashMap, HashSet};
+use editor::{
+    actions::Cancel,
+    display_map::{BlockPlacement, BlockProperties, BlockStyle, CustomBlockId},
+    Editor, EditorMode,
+};
+use gpui::{
+    AppContext, EventEmitter, FocusHandle, FocusableView, FontWeight, Model, Render, Subscription,
+    Task, View, ViewContext, WeakView,
+};
+use language::{Anchor, Buffer, Point};
+use menu::Confirm;
+use project::{review_threads::ReviewThread, Project};
+use std::{ops::Range, sync::Arc};
+use time::{OffsetDateTime, UtcOffset};
+use ui::{prelude::*, Avatar, Button, Label};
+use util::ResultExt as _;
+
+/// A review thread shown below the lines it's attached to, or a draft of a new thread.
+pub struct ReviewThreadView {
+    project: Model<Project>,
+    user_store: Model<UserStore>,
+    target: ReviewThreadTarget,
+    reply_editor: View<Editor>,
+    local_timezone: UtcOffset,
+    pending_request: Option<Task<()>>,
+    error: Option<SharedString>,
+    _subscriptions: Vec<Subscription>,
+}
+
+enum ReviewThreadTarget {
+    Thread(u64),
+    Draft {
+        buffer: Model<Buffer>,
+        range: Range<Anchor>,
+    },
+}
+
+pub enum ReviewThreadViewEvent {
+    /// The draft was posted as a new thread, or discarded.
+    DraftDismissed,
+}
+
+impl EventEmitter<ReviewThreadViewEvent> for ReviewThreadView {}
+
+impl ReviewThreadView {
+    fn new(
+        project: Model<Project>,
+        target: ReviewThreadTarget,
+        cx: &mut ViewContext<Self>,
+    ) -> Self {
+        let user_store = project.read(cx).user_store();
+        let reply_editor = cx.new_view(|cx| {
+            let mut editor = Editor::single_line(cx);
+            let placeholder = match &target {
+                ReviewThreadTarget::Thread(_) => "Reply…",
+                ReviewThreadTarget::Draft { .. } => "Leave a review comment…",
+            };
+            editor.set_placeholder_text(placeholder, cx);
+            editor
+        });
+        let local_offset = chrono::Local::now().offset().local_minus_utc();
+        Self {
+            _subscriptions: vec![
+                cx.observe(&project, |_, _, cx| cx.notify()),
+                cx.observe(&user_store, |_, _, cx| cx.notify()),
+            ],
+            project,
+            user_store,
+            target,
+            reply_editor,
+            local_timezone: UtcOffset::from_whole_seconds(local_offset).unwrap(),
+            pending_request: None,
+            error: None,
+        }
+    }
+
+    fn thread<'a>(&self, cx: &'a AppContext) -> Option<&'a ReviewThread> {
+        match self.target {
+            ReviewThreadTarget::Thread(thread_id) => self.project.read(cx).review_thread(thread_id),
+            ReviewThreadTarget::Draft { .. } => None,
+        }
+    }
+
+    fn confirm(&mut self, _: &Confirm, cx: &mut ViewContext<Self>) {
+        let body = self.reply_editor.read(cx).text(cx);
+        if body.trim().is_empty() || self.pending_request.is_some() {
+            return;
+        }
+
+        let project = self.project.clone();
+        let request = match &self.target {
+            ReviewThreadTarget::Thread(thread_id) => project.update(cx, |project, cx| {
+                project.add_review_comment(*thread_id, body, cx)
+            }),
+            ReviewThreadTarget::Draft { buffer, range } => project.update(cx, |project, cx| {
+                let request = project.create_review_thread(buffer, range.clone(), body, cx);
+                cx.background_executor()
+                    .spawn(async move { request.await.map(|_| ()) })
+            }),
+        };
+        self.error = None;
+        self.pending_request = Some(cx.spawn(|this, mut cx| async move {
+            let result = request.await;
+            this.update(&mut cx, |this, cx| {
+                this.pending_request = None;
+                match result {
+                    Ok(()) => {
+                        this.reply_editor.update(cx, |editor, cx| editor.clear(cx));
+                        if matches!(this.target, ReviewThreadTarget::Draft { .. }) {
+                            cx.emit(ReviewThreadViewEvent::DraftDismissed);
+                        }
+                    }
+                    Err(error) => this.error = Some(error.to_string().into()),
+                }
+                cx.notify();
+            })
+            .ok();
+        }));
+        cx.notify();
+    }
+
+    fn cancel(&mut self, _: &Cancel, cx: &mut ViewContext<Self>) {
+        if matches!(self.target, ReviewThreadTarget::Draft { .. }) {
+            cx.emit(ReviewThreadViewEvent::DraftDismissed);
+        } else {
+            cx.propagate();
+        }
+    }
+
+    fn resolve(&mut self, resolved: bool, cx: &mut ViewContext<Self>) {
+        let ReviewThreadTarget::Thread(thread_id) = self.target else {
+            return;
+        };
+        self.project
+            .update(cx, |project, cx| {
+                project.resolve_review_thread(thread_id, resolved, cx)
+            })
+            .detach_and_log_err(cx);
+    }
+
+    /// The number of lines to reserve for the thread in the editor.
+    fn block_height(thread: Option<&ReviewThread>) -> u32 {
+        let comment_lines = thread.map_or(0, |thread| {
+            thread
+                .comments
+                .iter()
+                .map(|comment| 1 + comment.body.lines().count().max(1) as u32)
+                .sum()
+        });
+        comment_lines + 3
+    }
+}
+
+impl Render for ReviewThreadView {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let now = OffsetDateTime::now_utc();
+        let thread = self.thread(cx).cloned();
+        let comments = thread
+            .iter()
+            .flat_map(|thread| thread.comments.iter())
+            .map(|comment| {
+                let sender = self.user_store.update(cx, |user_store, cx| {
+                    user_store.get_user_optimistic(comment.sender_id, cx)
+                });
+                v_flex()
+                    .child(
+                        h_flex()
+                            .gap_1()
+                            .when_some(sender.clone(), |el, sender| {
+                                el.child(Avatar::new(sender.avatar_uri.clone()).size(rems(0.8)))
+                            })
+                            .child(
+                                Label::new(
+                                    sender
+                                        .map(|sender| sender.github_login.clone())
+                                        .unwrap_or_default(),
+                                )
+                                .size(LabelSize::Small)
+                                .weight(FontWeight::BOLD),
+                            )
+                            .child(
+                                Label::new(time_format::format_localized_timestamp(
+                                    comment.timestamp,
+                                    now,
+                                    self.local_timezone,
+                                    time_format::TimestampFormat::EnhancedAbsolute,
+                                ))
+                                .size(LabelSize::Small)
+                                .color(Color::Muted),
+                            ),
+                    )
+                    .child(Label::new(comment.body.clone()).size(LabelSize::Small))
+            })
+            .collect::<Vec<_>>();
+
+        v_flex()
+            .on_action(cx.listener(Self::confirm))
+            .on_action(cx.listener(Self::cancel))
+            .w_full()
+            .max_w(rems(40.))
+            .my_1()
+            .p_1()
+            .gap_1()
+            .rounded_md()
+            .border_1()
+            .border_color(cx.theme().colors().border)
+            .bg(cx.theme().colors().editor_subheader_background)
+            .children(comments)
+            .child(
+                h_flex()
+                    .gap_1()
+                    .child(
+                        div()
+                            .flex_1()
+                            .px_1()
+                            .rounded_sm()
+                            .bg(cx.theme().colors().editor_background)
+                            .child(self.reply_editor.clone()),
+                    )
+                    .when_some(thread, |el, thread| {
+                        let resolved = thread.resolved;
+                        el.child(
+                            Button::new(
+                                "resolve-review-thread",
+                                if resolved { "Reopen" } else { "Resolve" },
+                            )
+                            .label_size(LabelSize::Small)
+                            .on_click(cx.listener(move |this, _, cx| this.resolve(!resolved, cx))),
+                        )
+                    }),
+            )
+            .when_some(self.error.clone(), |el, error| {
+                el.child(Label::new(error).size(LabelSize::Small).color(Color::Error))
+            })
+    }
+}
+
+impl FocusableView for ReviewThreadView {
+    fn focus_handle(&self, cx: &AppContext) -> FocusHandle {
+        self.reply_editor.focus_handle(cx)
+    }
+}
+
+/// Shows the open review threads on an editor's file below the lines they're attached to.
+pub(crate) struct ReviewThreadBlocks {
+    editor: WeakView<Editor>,
+    project: Model<Project>,
+    buffer: Model<Buffer>,
+    blocks: HashMap<u64, ReviewThreadBlock>,
+    draft: Option<(CustomBlockId, View<ReviewThreadView>)>,
+    _subscription: Subscription,
+}
+
+struct ReviewThreadBlock {
+    id: CustomBlockId,
+    height: u32,
+    _view: View<ReviewThreadView>,
+}
+
+struct ReviewThreadsAddon {
+    _view: View<ReviewThreadBlocks>,
+}
+
+impl editor::Addon for ReviewThreadsAddon {
+    fn to_any(&self) -> &dyn std::any::Any {
+        self
+    }
+}
+
+impl ReviewThreadBlocks {
+    pub(crate) fn register(editor: &mut Editor, cx: &mut ViewContext<Editor>) {
+        if editor.mode() != EditorMode::Full {
+            return;
+        }
+        let Some(project) = editor.project().cloned() else {
+            return;
+        };
+        let Some(buffer) = editor.buffer().read(cx).as_singleton() else {
+            return;
+        };
+
+        let editor_handle = cx.view().downgrade();
+        let view = cx.new_view(|cx| {
+            let subscription = cx.subscribe(&project, |this, _, event, cx| match event {
+                project::Event::ReviewThreadsChanged
+                | project::Event::RemoteIdChanged(_)
+                | project::Event::DisconnectedFromHost => this.refresh(cx),
+                _ => {}
+            });
+            Self {
+                editor: editor_handle,
+                project,
+                buffer,
+                blocks: HashMap::default(),
+                draft: None,
+                _subscription: subscription,
+            }
+        });
+
+        let handle = view.downgrade();
+        editor
+            .register_action(move |_: &StartReviewThread, cx| {
+                handle
+                    .update(cx, |this, cx| this.start_thread(cx))
+                    .log_err();
+            })
+            .detach();
+        editor.register_addon(ReviewThreadsAddon {
+            _view: view.clone(),
+        });
+        cx.defer(move |_, cx| view.update(cx, |this, cx| this.refresh(cx)));
+    }
+
+    fn refresh(&mut self, cx: &mut ViewContext<Self>) {
+        let buffer = self.buffer.clone();
+        let threads = self.project.update(cx, |project, cx| {
+            project
+                .review_threads_for_buffer(&buffer, cx)
+                .into_iter()
+                .filter_map(|(thread_id, range)| {
+                    let thread = project.review_thread(thread_id)?;
+                    (!thread.resolved).then(|| (thread.clone(), range))
+                })
+                .collect::<Vec<_>>()
+        });
+
+        let project = self.project.clone();
+        let blocks = &mut self.blocks;
+        self.editor
+            .update(cx, |editor, cx| {
+                let snapshot = editor.buffer().read(cx).snapshot(cx);
+                let Some((excerpt_id, _, _)) = snapshot.as_singleton() else {
+                    return;
+                };
+                let excerpt_id = *excerpt_id;
+
+                let thread_ids = threads
+                    .iter()
+                    .map(|(thread, _)| thread.id)
+                    .collect::<HashSet<_>>();
+                let removed_blocks = blocks
+                    .iter()
+                    .filter(|(thread_id, _)| !thread_ids.contains(thread_id))
+                    .map(|(_, block)| block.id)
+                    .collect::<HashSet<_>>();
+                blocks.retain(|thread_id, _| thread_ids.contains(thread_id));
+                editor.remove_blocks(removed_blocks, None, cx);
+
+                let mut resized_blocks = HashMap::default();
+                let mut new_blocks = Vec::new();
+                for (thread, range) in threads {
+                    let height = ReviewThreadView::block_height(Some(&thread));
+                    if let Some(block) = blocks.get_mut(&thread.id) {
+                        if block.height != height {
+                            block.height = height;
+                            resized_blocks.insert(block.id, height);
+                        }
+                        continue;
+                    }
+                    let Some(position) = snapshot.anchor_in_excerpt(excerpt_id, range.end) else {
+                        continue;
+                    };
+                    let view = cx.new_view(|cx| {
+                        ReviewThreadView::new(
+                            project.clone(),
+                            ReviewThreadTarget::Thread(thread.id),
+                            cx,
+                        )
+                    });
+                    new_blocks.push((thread.id, position, height, view));
+                }
+                editor.resize_blocks(resized_blocks, None, cx);
+
+                let block_ids = editor.insert_blocks(
+                    new_blocks.iter().map(|(_, position, height, view)| {
+                        block_properties(*position, *height, view)
+                    }),
+                    None,
+                    cx,
+                );
+                for ((thread_id, _, height, view), id) in new_blocks.into_iter().zip(block_ids) {
+                    blocks.insert(
+                        thread_id,
+                        ReviewThreadBlock {
+                            id,
+                            height,
+                            _view: view,
+                        },
+                    );
+                }
+            })
+            .ok();
+    }
+
+    fn start_thread(&mut self, cx: &mut ViewContext<Self>) {
+        if self.project.read(cx).remote_id().is_none() {
+            return;
+        }
+        self.dismiss_draft(cx);
+
+        let Some(editor) = self.editor.upgrade() else {
+            return;
+        };
+        let selection = editor.update(cx, |editor, cx| editor.selections.newest::<Point>(cx));
+        let buffer = self.buffer.read(cx);
+        let start_row = selection.start.row;
+        let mut end_row = selection.end.row;
+        if end_row > start_row && selection.end.column == 0 {
+            end_row -= 1;
+        }
+        let range = buffer.anchor_before(Point::new(start_row, 0))
+            ..buffer.anchor_after(Point::new(end_row, buffer.line_len(end_row)));
+
+        let view = cx.new_view(|cx| {
+            ReviewThreadView::new(
+                self.project.clone(),
+                ReviewThreadTarget::Draft {
+                    buffer: self.buffer.clone(),
+                    range,
+                },
+                cx,
+            )
+        });
+        cx.subscribe(&view, |this, _, event, cx| match event {
+            ReviewThreadViewEvent::DraftDismissed => this.dismiss_draft(cx),
+        })
+        .detach();
+
+        let block_id = editor.update(cx, |editor, cx| {
+            let snapshot = editor.buffer().read(cx).snapshot(cx);
+            let position = snapshot.anchor_after(Point::new(end_row, 0));
+            editor
+                .insert_blocks(
+                    [block_properties(
+                        position,
+                        ReviewThreadView::block_height(None),
+                        &view,
+                    )],
+                    None,
+                    cx,
+                )
+                .pop()
+        });
+        if let Some(block_id) = block_id {
+            view.focus_handle(cx).focus(cx);
+            self.draft = Some((block_id, view));
+        }
+    }
+
+    fn dismiss_draft(&mut self, cx: &mut ViewContext<Self>) {
+        let Some((block_id, _)) = self.draft.take() else {
+            return;
+        };
+        self.editor
+            .update(cx, |editor, cx| {
+                editor.remove_blocks(HashSet::from_iter([block_id]), None, cx);
+                cx.focus_self();
+            })
+            .ok();
+    }
+}
+
+impl Render for ReviewThreadBlocks {
+    fn render(&mut self, _: &mut ViewContext<Self>) -> impl IntoElement {
+        gpui::Empty
+    }
+}
+
+fn block_properties(
+    position: editor::Anchor,
+    height: u32,
+    view: &View<ReviewThreadView>,
+) -> BlockProperties<editor::Anchor> {
+    let view = view.clone();
+    BlockProperties {
+        placement: BlockPlacement::Below(position),
+        height,
+        style: BlockStyle::Flex,
+        render: Arc::new(move |cx| {
+            div()
+                .block_mouse_down()
+                .pl(cx.gutter_dimensions.full_width())
+                .pr_4()
+                .child(view.clone())
+                .into_any_element()
+        }),
+        priority: 0,
+    }
+}
@@ -252,6 +252,7 @@ gpui::actions!(
         DuplicateLineDown,
         DuplicateLineUp,
         DuplicateSelection,
+        EnableFullEditing,
         ExpandAllHunkDiffs,
         ExpandMacroRecursively,
         FindAllReferences,
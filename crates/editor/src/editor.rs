@@ -29,6 +29,7 @@ mod hunk_diff;
 mod indent_guides;
 mod inlay_hint_cache;
 pub mod items;
+mod large_file_banner;
 mod linked_editing_ranges;
 mod lsp_ext;
 mod mouse_context_menu;
@@ -38,6 +39,7 @@ mod proposed_changes_editor;
 mod rust_analyzer_ext;
 pub mod scroll;
 mod selections_collection;
+mod snippet_variables;
 pub mod tasks;
 
 #[cfg(test)]
@@ -103,6 +105,7 @@ use language::{
     Point, Selection, SelectionGoal, TransactionId,
 };
 use language::{point_to_lsp, BufferRow, CharClassifier, Runnable, RunnableRange};
+pub use large_file_banner::LargeFileBanner;
 use linked_editing_ranges::refresh_linked_ranges;
 use mouse_context_menu::MouseContextMenu;
 pub use proposed_changes_editor::{
@@ -142,7 +145,7 @@ use selections_collection::{
 use serde::{Deserialize, Serialize};
 use settings::{update_settings_file, Settings, SettingsLocation, SettingsStore};
 use smallvec::SmallVec;
-use snippet::Snippet;
+use snippet::{Snippet, Transform};
 use std::{
     any::TypeId,
     borrow::Cow,
@@ -897,6 +900,15 @@ struct SnippetState {
     ranges: Vec<Vec<Range<Anchor>>>,
     active_index: usize,
     choices: Vec<Option<Vec<String>>>,
+    transforms: Vec<Vec<SnippetTransform>>,
+}
+
+/// A range of a snippet that mirrors a tabstop through a regex transform,
+/// updated when that tabstop is left.
+struct SnippetTransform {
+    source: Range<Anchor>,
+    target: Range<Anchor>,
+    transform: Transform,
 }
 
 #[doc(hidden)]
@@ -1521,6 +1533,10 @@ impl Editor {
         &self.buffer
     }
 
+    pub fn project(&self) -> Option<&Model<Project>> {
+        self.project.as_ref()
+    }
+
     pub fn workspace(&self) -> Option<View<Workspace>> {
         self.workspace.as_ref()?.0.upgrade()
     }
@@ -1726,6 +1742,22 @@ impl Editor {
         self.read_only = read_only;
     }
 
+    /// Returns the buffers in this editor that were opened in large-file mode.
+    pub fn large_file_buffers(&self, cx: &AppContext) -> Vec<Model<Buffer>> {
+        self.buffer
+            .read(cx)
+            .all_buffers()
+            .into_iter()
+            .filter(|buffer| buffer.read(cx).is_large_file())
+            .collect()
+    }
+
+    pub fn enable_full_editing(&mut self, _: &EnableFullEditing, cx: &mut ViewContext<Self>) {
+        for buffer in self.large_file_buffers(cx) {
+            buffer.update(cx, |buffer, cx| buffer.enable_full_editing(cx));
+        }
+    }
+
     pub fn set_use_autoclose(&mut self, autoclose: bool) {
         self.use_autoclose = autoclose;
     }
@@ -1871,8 +1903,17 @@ impl Editor {
         self.select_prev_state = None;
         self.select_larger_syntax_node_stack.clear();
         self.invalidate_autoclose_regions(&self.selections.disjoint_anchors(), buffer);
-        self.snippet_stack
+        let abandoned_snippets = self
+            .snippet_stack
             .invalidate(&self.selections.disjoint_anchors(), buffer);
+        if !abandoned_snippets.is_empty() {
+            // Mirrors of the tabstop being left still need to reflect its final text.
+            cx.defer(move |this, cx| {
+                for snippet in abandoned_snippets {
+                    this.apply_snippet_transforms(&snippet.transforms[snippet.active_index], cx);
+                }
+            });
+        }
         self.take_rename(false, cx);
 
         let new_cursor_position = self.selections.newest_anchor().head();
@@ -2474,7 +2515,8 @@ impl Editor {
             return true;
         }
 
-        if self.snippet_stack.pop().is_some() {
+        if let Some(snippet) = self.snippet_stack.pop() {
+            self.apply_snippet_transforms(&snippet.transforms[snippet.active_index], cx);
             return true;
         }
 
@@ -3531,6 +3573,9 @@ impl Editor {
             .filter(|(_, excerpt_visible_range, _)| !excerpt_visible_range.is_empty())
             .filter_map(|(buffer_handle, excerpt_visible_range, excerpt_id)| {
                 let buffer = buffer_handle.read(cx);
+                if buffer.is_large_file() {
+                    return None;
+                }
                 let buffer_file = project::File::from_dyn(buffer.file())?;
                 let buffer_worktree = project.worktree_for_id(buffer_file.worktree_id(cx), cx)?;
                 let worktree_entry = buffer_worktree
@@ -3800,7 +3845,13 @@ impl Editor {
         let text;
 
         if completion.is_snippet() {
-            snippet = Some(Snippet::parse(&completion.new_text).log_err()?);
+            let variables = self.snippet_variables(&buffer_handle, cx);
+            snippet = Some(
+                Snippet::parse_with_variables(&completion.new_text, &|name| {
+                    variables.get(name).cloned()
+                })
+                .log_err()?,
+            );
             text = snippet.as_ref().unwrap().text.clone();
         } else {
             snippet = None;
@@ -3889,11 +3940,14 @@ impl Editor {
         self.transact(cx, |this, cx| {
             if let Some(mut snippet) = snippet {
                 snippet.text = text.to_string();
-                for tabstop in snippet
-                    .tabstops
-                    .iter_mut()
-                    .flat_map(|tabstop| tabstop.ranges.iter_mut())
-                {
+                for tabstop in snippet.tabstops.iter_mut().flat_map(|tabstop| {
+                    tabstop.ranges.iter_mut().chain(
+                        tabstop
+                            .transformed_ranges
+                            .iter_mut()
+                            .map(|(range, _)| range),
+                    )
+                }) {
                     tabstop.start -= common_prefix_len as isize;
                     tabstop.end -= common_prefix_len as isize;
                 }
@@ -5060,6 +5114,7 @@ impl Editor {
             is_end_tabstop: bool,
             ranges: Vec<Range<T>>,
             choices: Option<Vec<String>>,
+            transforms: Vec<SnippetTransform>,
         }
 
         let tabstops = self.buffer.update(cx, |buffer, cx| {
@@ -5082,30 +5137,52 @@ impl Editor {
                     let is_end_tabstop = tabstop.ranges.first().map_or(false, |tabstop| {
                         tabstop.is_empty() && tabstop.start == snippet.text.len() as isize
                     });
+                    // Resolves a range of the snippet within each of the insertions.
+                    let resolve_range = |tabstop_range: Range<isize>| {
+                        let mut delta = 0_isize;
+                        insertion_ranges.iter().map(move |insertion_range| {
+                            let insertion_start = insertion_range.start as isize + delta;
+                            delta += snippet.text.len() as isize - insertion_range.len() as isize;
+
+                            let start = ((insertion_start + tabstop_range.start) as usize)
+                                .min(snapshot.len());
+                            let end = ((insertion_start + tabstop_range.end) as usize)
+                                .min(snapshot.len());
+                            snapshot.anchor_before(start)..snapshot.anchor_after(end)
+                        })
+                    };
                     let mut tabstop_ranges = tabstop
                         .ranges
                         .iter()
-                        .flat_map(|tabstop_range| {
-                            let mut delta = 0_isize;
-                            insertion_ranges.iter().map(move |insertion_range| {
-                                let insertion_start = insertion_range.start as isize + delta;
-                                delta +=
-                                    snippet.text.len() as isize - insertion_range.len() as isize;
-
-                                let start = ((insertion_start + tabstop_range.start) as usize)
-                                    .min(snapshot.len());
-                                let end = ((insertion_start + tabstop_range.end) as usize)
-                                    .min(snapshot.len());
-                                snapshot.anchor_before(start)..snapshot.anchor_after(end)
-                            })
-                        })
+                        .cloned()
+                        .flat_map(resolve_range)
                         .collect::<Vec<_>>();
+                    let transforms = tabstop
+                        .ranges
+                        .first()
+                        .map(|source_range| {
+                            tabstop
+                                .transformed_ranges
+                                .iter()
+                                .flat_map(|(target_range, transform)| {
+                                    resolve_range(source_range.clone())
+                                        .zip(resolve_range(target_range.clone()))
+                                        .map(|(source, target)| SnippetTransform {
+                                            source,
+                                            target,
+                                            transform: transform.clone(),
+                                        })
+                                })
+                                .collect::<Vec<_>>()
+                        })
+                        .unwrap_or_default();
                     tabstop_ranges.sort_unstable_by(|a, b| a.start.cmp(&b.start, snapshot));
 
                     Tabstop {
                         is_end_tabstop,
                         ranges: tabstop_ranges,
                         choices: tabstop.choices.clone(),
+                        transforms,
                     }
                 })
                 .collect::<Vec<_>>()
@@ -5129,15 +5206,16 @@ impl Editor {
                     .map(|tabstop| tabstop.choices.clone())
                     .collect();
 
-                let ranges = tabstops
+                let (ranges, transforms) = tabstops
                     .into_iter()
-                    .map(|tabstop| tabstop.ranges)
-                    .collect::<Vec<_>>();
+                    .map(|tabstop| (tabstop.ranges, tabstop.transforms))
+                    .unzip();
 
                 self.snippet_stack.push(SnippetState {
                     active_index: 0,
                     ranges,
                     choices,
+                    transforms,
                 });
             }
 
@@ -5190,6 +5268,13 @@ impl Editor {
 
     pub fn move_to_snippet_tabstop(&mut self, bias: Bias, cx: &mut ViewContext<Self>) -> bool {
         if let Some(mut snippet) = self.snippet_stack.pop() {
+            let can_move = match bias {
+                Bias::Left => snippet.active_index > 0,
+                Bias::Right => snippet.active_index + 1 < snippet.ranges.len(),
+            };
+            if can_move {
+                self.apply_snippet_transforms(&snippet.transforms[snippet.active_index], cx);
+            }
             match bias {
                 Bias::Left => {
                     if snippet.active_index > 0 {
@@ -5230,6 +5315,35 @@ impl Editor {
         false
     }
 
+    /// Rewrites the ranges mirroring a tabstop through a transform, based on the tabstop's
+    /// current text.
+    fn apply_snippet_transforms(
+        &mut self,
+        transforms: &[SnippetTransform],
+        cx: &mut ViewContext<Self>,
+    ) {
+        if transforms.is_empty() {
+            return;
+        }
+        self.buffer.update(cx, |buffer, cx| {
+            let snapshot = buffer.snapshot(cx);
+            let edits = transforms
+                .iter()
+                .filter_map(|transform| {
+                    let source_text = snapshot
+                        .text_for_range(transform.source.clone())
+                        .collect::<String>();
+                    let new_text = transform.transform.apply(&source_text);
+                    let target = transform.target.start.to_offset(&snapshot)
+                        ..transform.target.end.to_offset(&snapshot);
+                    let old_text = snapshot.text_for_range(target.clone()).collect::<String>();
+                    (old_text != new_text).then_some((target, new_text))
+                })
+                .collect::<Vec<_>>();
+            buffer.edit(edits, None, cx);
+        });
+    }
+
     pub fn clear(&mut self, cx: &mut ViewContext<Self>) {
         self.transact(cx, |this, cx| {
             this.select_all(&SelectAll, cx);
@@ -12185,6 +12299,15 @@ impl Editor {
                 cx.emit(EditorEvent::Reparsed(*buffer_id));
                 cx.notify();
             }
+            multi_buffer::Event::LargeFileModeChanged(buffer_id) => {
+                if let Some(project) = &self.project {
+                    if let Some(buffer) = multibuffer.read(cx).buffer(*buffer_id) {
+                        get_unstaged_changes_for_buffers(project, [buffer], cx);
+                    }
+                }
+                self.refresh_inlay_hints(InlayHintRefreshReason::RefreshRequested, cx);
+                cx.notify();
+            }
             multi_buffer::Event::DirtyChanged => cx.emit(EditorEvent::DirtyChanged),
             multi_buffer::Event::Saved => cx.emit(EditorEvent::Saved),
             multi_buffer::Event::FileHandleChanged | multi_buffer::Event::Reloaded => {
@@ -12862,6 +12985,9 @@ fn get_unstaged_changes_for_buffers(
     let mut tasks = Vec::new();
     project.update(cx, |project, cx| {
         for buffer in buffers {
+            if buffer.read(cx).is_large_file() {
+                continue;
+            }
             tasks.push(project.open_unstaged_changes(buffer.clone(), cx))
         }
     });
@@ -14366,10 +14492,12 @@ impl<T: ToPoint + ToOffset> SelectionExt for Selection<T> {
 }
 
 impl<T: InvalidationRegion> InvalidationStack<T> {
-    fn invalidate<S>(&mut self, selections: &[Selection<S>], buffer: &MultiBufferSnapshot)
+    /// Pops the regions that no longer contain all of the given selections, returning them.
+    fn invalidate<S>(&mut self, selections: &[Selection<S>], buffer: &MultiBufferSnapshot) -> Vec<T>
     where
         S: Clone + ToOffset,
     {
+        let mut invalidated = Vec::new();
         while let Some(region) = self.last() {
             let all_selections_inside_invalidation_ranges =
                 if selections.len() == region.ranges().len() {
@@ -14387,9 +14515,10 @@ impl<T: InvalidationRegion> InvalidationStack<T> {
             if all_selections_inside_invalidation_ranges {
                 break;
             } else {
-                self.pop();
+                invalidated.extend(self.pop());
             }
         }
+        invalidated
     }
 }
 
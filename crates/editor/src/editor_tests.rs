@@ -6830,6 +6830,96 @@ async fn test_snippets(cx: &mut gpui::TestAppContext) {
     });
 }
 
+#[gpui::test]
+async fn test_snippet_transforms(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |_| {});
+
+    let (text, insertion_ranges) = marked_text_ranges(
+        indoc! {"
+            ˇ
+            ˇ
+        "},
+        false,
+    );
+
+    let buffer = cx.update(|cx| MultiBuffer::build_simple(&text, cx));
+    let (editor, cx) = cx.add_window_view(|cx| build_editor(buffer, cx));
+
+    editor.update(cx, |editor, cx| {
+        let snippet = Snippet::parse("let ${1:foo} = ${1/(.*)/${1:/upcase}/};$0").unwrap();
+
+        editor
+            .insert_snippet(&insertion_ranges, snippet, cx)
+            .unwrap();
+
+        fn assert(editor: &mut Editor, cx: &mut ViewContext<Editor>, marked_text: &str) {
+            let (expected_text, selection_ranges) = marked_text_ranges(marked_text, false);
+            assert_eq!(editor.text(cx), expected_text);
+            assert_eq!(editor.selections.ranges::<usize>(cx), selection_ranges);
+        }
+
+        assert(
+            editor,
+            cx,
+            indoc! {"
+                let «foo» = FOO;
+                let «foo» = FOO;
+            "},
+        );
+
+        // Transforms are applied when leaving the tabstop.
+        editor.handle_input("my_var", cx);
+        assert(
+            editor,
+            cx,
+            indoc! {"
+                let my_varˇ = FOO;
+                let my_varˇ = FOO;
+            "},
+        );
+
+        assert!(editor.move_to_next_snippet_tabstop(cx));
+        assert(
+            editor,
+            cx,
+            indoc! {"
+                let my_var = MY_VAR;ˇ
+                let my_var = MY_VAR;ˇ
+            "},
+        );
+    });
+}
+
+#[gpui::test]
+async fn test_snippet_transforms_applied_when_snippet_ends(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |_| {});
+
+    let buffer = cx.update(|cx| MultiBuffer::build_simple("\n\n", cx));
+    let (editor, cx) = cx.add_window_view(|cx| build_editor(buffer, cx));
+
+    // Escape ends the snippet while a transformed tabstop is active.
+    editor.update(cx, |editor, cx| {
+        let snippet = Snippet::parse("let ${1:foo} = ${1/(.*)/${1:/upcase}/};$0").unwrap();
+        editor.insert_snippet(&[0..0], snippet, cx).unwrap();
+        editor.handle_input("bar", cx);
+        editor.cancel(&Cancel, cx);
+    });
+    editor.update(cx, |editor, cx| {
+        assert_eq!(editor.text(cx), "let bar = BAR;\n\n");
+    });
+
+    // Moving the cursor out of the tabstop ends the snippet too.
+    editor.update(cx, |editor, cx| {
+        let snippet = Snippet::parse("let ${1:foo} = ${1/(.*)/${1:/upcase}/};$0").unwrap();
+        editor.insert_snippet(&[15..15], snippet, cx).unwrap();
+        editor.handle_input("baz", cx);
+        editor.change_selections(None, cx, |s| s.select_ranges([0..0]));
+    });
+    editor.update(cx, |editor, cx| {
+        assert_eq!(editor.text(cx), "let bar = BAR;\nlet baz = BAZ;\n");
+    });
+}
+
 #[gpui::test]
 async fn test_document_format_during_save(cx: &mut gpui::TestAppContext) {
     init_test(cx, |_| {});
@@ -7314,6 +7404,93 @@ async fn test_range_format_during_save(cx: &mut gpui::TestAppContext) {
     save.await;
 }
 
+#[gpui::test]
+async fn test_format_modifications_during_save(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |settings| {
+        settings.defaults.format_on_save = Some(language_settings::FormatOnSave::Modifications);
+        settings.defaults.remove_trailing_whitespace_on_save = Some(true);
+    });
+
+    let staged_contents = "one  \ntwo\nthree\n".to_string();
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        "/dir",
+        json!({
+            ".git": {},
+            "file.rs": staged_contents.clone(),
+        }),
+    )
+    .await;
+    fs.set_index_for_repo(
+        "/dir/.git".as_ref(),
+        &[("file.rs".as_ref(), staged_contents)],
+    );
+
+    let project = Project::test(fs, ["/dir".as_ref()], cx).await;
+
+    let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+    language_registry.add(rust_lang());
+    let mut fake_servers = language_registry.register_fake_lsp(
+        "Rust",
+        FakeLspAdapter {
+            capabilities: lsp::ServerCapabilities {
+                document_formatting_provider: Some(lsp::OneOf::Left(true)),
+                document_range_formatting_provider: Some(lsp::OneOf::Left(true)),
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+    );
+
+    let buffer = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer("/dir/file.rs", cx)
+        })
+        .await
+        .unwrap();
+
+    let buffer = cx.new_model(|cx| MultiBuffer::singleton(buffer, cx));
+    let (editor, cx) =
+        cx.add_window_view(|cx| build_editor_with_project(project.clone(), buffer, cx));
+    editor.update(cx, |editor, cx| {
+        editor.set_text("one  \ntwo\nthree\nfour  \n", cx)
+    });
+    assert!(cx.read(|cx| editor.is_dirty(cx)));
+
+    cx.executor().start_waiting();
+    let fake_server = fake_servers.next().await.unwrap();
+
+    fake_server.handle_request::<lsp::request::Formatting, _, _>(move |_, _| async move {
+        panic!("the whole document should not be formatted");
+    });
+    let save = editor
+        .update(cx, |editor, cx| editor.save(true, project.clone(), cx))
+        .unwrap();
+    fake_server
+        .handle_request::<lsp::request::RangeFormatting, _, _>(move |params, _| async move {
+            // Only the added line is formatted, after its trailing whitespace was removed.
+            assert_eq!(
+                params.range,
+                lsp::Range::new(lsp::Position::new(3, 0), lsp::Position::new(3, 4))
+            );
+            Ok(Some(vec![lsp::TextEdit::new(
+                params.range,
+                "FOUR".to_string(),
+            )]))
+        })
+        .next()
+        .await;
+    cx.executor().start_waiting();
+    save.await;
+
+    // Trailing whitespace is only removed from the modified lines.
+    assert_eq!(
+        editor.update(cx, |editor, cx| editor.text(cx)),
+        "one  \ntwo\nthree\nFOUR\n"
+    );
+    assert!(!cx.read(|cx| editor.is_dirty(cx)));
+}
+
 #[gpui::test]
 async fn test_document_format_manual_trigger(cx: &mut gpui::TestAppContext) {
     init_test(cx, |settings| {
@@ -14362,6 +14539,100 @@ async fn test_multi_buffer_with_single_excerpt_folding(cx: &mut gpui::TestAppCon
     );
 }
 
+#[gpui::test]
+async fn test_enabling_full_editing_requests_diff_and_inlay_hints(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |settings| {
+        settings.defaults.inlay_hints = Some(InlayHintSettings {
+            enabled: true,
+            edit_debounce_ms: 0,
+            scroll_debounce_ms: 0,
+            show_type_hints: true,
+            show_parameter_hints: true,
+            show_other_hints: true,
+            show_background: false,
+        })
+    });
+    cx.update(|cx| {
+        SettingsStore::update_global(cx, |store, cx| {
+            store.update_user_settings::<project::WorktreeSettings>(cx, |settings| {
+                settings.large_file_threshold_mb = Some(0);
+            });
+        });
+    });
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        "/dir",
+        json!({
+            ".git": {},
+            "main.rs": "fn main() {}\n",
+        }),
+    )
+    .await;
+    fs.set_index_for_repo(
+        "/dir/.git".as_ref(),
+        &[("main.rs".as_ref(), "fn other() {}\n".to_string())],
+    );
+
+    let project = Project::test(fs, ["/dir".as_ref()], cx).await;
+    let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+    language_registry.add(rust_lang());
+    let hint_requests = Arc::new(AtomicUsize::new(0));
+    let mut fake_servers = language_registry.register_fake_lsp(
+        "Rust",
+        FakeLspAdapter {
+            capabilities: lsp::ServerCapabilities {
+                inlay_hint_provider: Some(lsp::OneOf::Left(true)),
+                ..Default::default()
+            },
+            initializer: Some(Box::new({
+                let hint_requests = hint_requests.clone();
+                move |fake_server| {
+                    let hint_requests = hint_requests.clone();
+                    fake_server.handle_request::<lsp::request::InlayHintRequest, _, _>(
+                        move |_, _| {
+                            hint_requests.fetch_add(1, atomic::Ordering::SeqCst);
+                            async move { Ok(Some(Vec::new())) }
+                        },
+                    );
+                }
+            })),
+            ..Default::default()
+        },
+    );
+
+    let buffer = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer("/dir/main.rs", cx)
+        })
+        .await
+        .unwrap();
+    let buffer_id = buffer.read_with(cx, |buffer, _| buffer.remote_id());
+    let multi_buffer = cx.new_model(|cx| MultiBuffer::singleton(buffer.clone(), cx));
+    let (editor, cx) =
+        cx.add_window_view(|cx| build_editor_with_project(project.clone(), multi_buffer, cx));
+    cx.executor().run_until_parked();
+
+    // Neither the diff nor inlay hints are requested for a large file.
+    editor.update(cx, |editor, cx| {
+        assert!(buffer.read(cx).is_large_file());
+        assert!(!editor.diff_map.diff_bases.contains_key(&buffer_id));
+    });
+    assert_eq!(hint_requests.load(atomic::Ordering::SeqCst), 0);
+
+    editor.update(cx, |editor, cx| {
+        editor.enable_full_editing(&EnableFullEditing, cx)
+    });
+    let _fake_server = fake_servers.next().await.unwrap();
+    cx.executor().run_until_parked();
+
+    editor.update(cx, |editor, cx| {
+        assert!(!buffer.read(cx).is_large_file());
+        assert!(editor.diff_map.diff_bases.contains_key(&buffer_id));
+    });
+    assert!(hint_requests.load(atomic::Ordering::SeqCst) > 0);
+}
+
 fn empty_range(row: usize, column: usize) -> Range<DisplayPoint> {
     let point = DisplayPoint::new(DisplayRow(row as u32), column as u32);
     point..point
@@ -219,6 +219,7 @@ impl EditorElement {
         register_action(view, cx, Editor::duplicate_line_up);
         register_action(view, cx, Editor::duplicate_line_down);
         register_action(view, cx, Editor::duplicate_selection);
+        register_action(view, cx, Editor::enable_full_editing);
         register_action(view, cx, Editor::move_line_up);
         register_action(view, cx, Editor::move_line_down);
         register_action(view, cx, Editor::transpose);
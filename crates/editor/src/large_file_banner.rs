@@ -0,0 +1,111 @@
+use gpui::{EventEmitter, Subscription, View};
+use ui::{prelude::*, Tooltip};
+use workspace::{item::ItemHandle, ToolbarItemEvent, ToolbarItemLocation, ToolbarItemView};
+
+use crate::{actions::EnableFullEditing, Editor};
+
+/// A banner shown above editors for files that were opened in large-file mode,
+/// which lets the user opt into full editing.
+pub struct LargeFileBanner {
+    editor: Option<View<Editor>>,
+    _subscription: Option<Subscription>,
+}
+
+impl Default for LargeFileBanner {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LargeFileBanner {
+    pub fn new() -> Self {
+        Self {
+            editor: None,
+            _subscription: None,
+        }
+    }
+
+    fn large_file_size(&self, cx: &AppContext) -> Option<usize> {
+        let editor = self.editor.as_ref()?.read(cx);
+        let buffers = editor.large_file_buffers(cx);
+        buffers.iter().map(|buffer| buffer.read(cx).len()).max()
+    }
+
+    fn toolbar_item_location(&self, cx: &AppContext) -> ToolbarItemLocation {
+        if self.large_file_size(cx).is_some() {
+            ToolbarItemLocation::Secondary
+        } else {
+            ToolbarItemLocation::Hidden
+        }
+    }
+}
+
+impl EventEmitter<ToolbarItemEvent> for LargeFileBanner {}
+
+impl ToolbarItemView for LargeFileBanner {
+    fn set_active_pane_item(
+        &mut self,
+        active_pane_item: Option<&dyn ItemHandle>,
+        cx: &mut ViewContext<Self>,
+    ) -> ToolbarItemLocation {
+        self.editor = active_pane_item.and_then(|item| item.downcast::<Editor>());
+        self._subscription = self.editor.as_ref().map(|editor| {
+            let multibuffer = editor.read(cx).buffer().clone();
+            cx.subscribe(&multibuffer, |this, _, event, cx| {
+                if let multi_buffer::Event::LargeFileModeChanged(_) = event {
+                    let location = this.toolbar_item_location(cx);
+                    cx.emit(ToolbarItemEvent::ChangeLocation(location));
+                    cx.notify();
+                }
+            })
+        });
+        self.toolbar_item_location(cx)
+    }
+}
+
+impl Render for LargeFileBanner {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let size_in_megabytes = self.large_file_size(cx).unwrap_or(0) as f64 / (1024. * 1024.);
+
+        h_flex()
+            .w_full()
+            .px_2()
+            .py_0p5()
+            .gap_2()
+            .justify_between()
+            .bg(cx.theme().status().warning_background)
+            .rounded_md()
+            .child(
+                h_flex()
+                    .gap_2()
+                    .child(
+                        Icon::new(IconName::Warning)
+                            .size(IconSize::Small)
+                            .color(Color::Warning),
+                    )
+                    .child(Label::new(format!(
+                        "This file is {size_in_megabytes:.0} MB, so it was opened read-only, \
+                        without syntax highlighting, language servers, git diffs or inlay hints."
+                    ))),
+            )
+            .child(
+                Button::new("enable-full-editing", "Enable Full Editing")
+                    .style(ButtonStyle::Filled)
+                    .tooltip(|cx| {
+                        Tooltip::with_meta(
+                            "Enable Full Editing",
+                            Some(&EnableFullEditing),
+                            "Editing and language features may be slow for this file",
+                            cx,
+                        )
+                    })
+                    .on_click(cx.listener(|this, _, cx| {
+                        if let Some(editor) = &this.editor {
+                            editor.update(cx, |editor, cx| {
+                                editor.enable_full_editing(&EnableFullEditing, cx)
+                            });
+                        }
+                    })),
+            )
+    }
+}
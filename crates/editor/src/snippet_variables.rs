@@ -0,0 +1,121 @@
+use std::path::Path;
+
+use chrono::Local;
+use collections::HashMap;
+use gpui::{Model, ViewContext};
+use language::{Buffer, Point};
+use multi_buffer::MultiBufferRow;
+use rand::Rng;
+
+use crate::Editor;
+
+const FILE_VARIABLES: &[&str] = &[
+    "TM_FILENAME",
+    "TM_FILENAME_BASE",
+    "TM_DIRECTORY",
+    "TM_FILEPATH",
+    "RELATIVE_FILEPATH",
+];
+
+impl Editor {
+    /// Resolves the variables that snippets may reference, such as `$TM_FILENAME` or `$CLIPBOARD`,
+    /// for a snippet inserted in `buffer` at the newest selection.
+    pub(crate) fn snippet_variables(
+        &self,
+        buffer: &Model<Buffer>,
+        cx: &mut ViewContext<Self>,
+    ) -> HashMap<&'static str, String> {
+        let mut variables = HashMap::default();
+        let snapshot = self.buffer.read(cx).snapshot(cx);
+        let selection = self.selections.newest::<Point>(cx);
+        let head = selection.head();
+
+        variables.insert(
+            "TM_SELECTED_TEXT",
+            snapshot.text_for_range(selection.range()).collect(),
+        );
+        let line_end = Point::new(head.row, snapshot.line_len(MultiBufferRow(head.row)));
+        variables.insert(
+            "TM_CURRENT_LINE",
+            snapshot
+                .text_for_range(Point::new(head.row, 0)..line_end)
+                .collect(),
+        );
+        let (word_range, _) = snapshot.surrounding_word(head, false);
+        variables.insert(
+            "TM_CURRENT_WORD",
+            snapshot.text_for_range(word_range).collect(),
+        );
+        variables.insert("TM_LINE_INDEX", head.row.to_string());
+        variables.insert("TM_LINE_NUMBER", (head.row + 1).to_string());
+
+        for name in FILE_VARIABLES {
+            variables.insert(name, String::new());
+        }
+        if let Some(file) = buffer.read(cx).file() {
+            let abs_path = match file.as_local() {
+                Some(file) => file.abs_path(cx),
+                None => file.full_path(cx),
+            };
+            let file_name = Path::new(file.file_name(cx));
+            variables.insert("TM_FILENAME", file_name.to_string_lossy().into_owned());
+            if let Some(stem) = file_name.file_stem() {
+                variables.insert("TM_FILENAME_BASE", stem.to_string_lossy().into_owned());
+            }
+            if let Some(directory) = abs_path.parent() {
+                variables.insert("TM_DIRECTORY", directory.to_string_lossy().into_owned());
+            }
+            variables.insert("TM_FILEPATH", abs_path.to_string_lossy().into_owned());
+            variables.insert(
+                "RELATIVE_FILEPATH",
+                file.path().to_string_lossy().into_owned(),
+            );
+        }
+
+        variables.insert(
+            "CLIPBOARD",
+            cx.read_from_clipboard()
+                .and_then(|item| item.text())
+                .unwrap_or_default(),
+        );
+
+        let now = Local::now();
+        for (name, format) in [
+            ("CURRENT_YEAR", "%Y"),
+            ("CURRENT_YEAR_SHORT", "%y"),
+            ("CURRENT_MONTH", "%m"),
+            ("CURRENT_MONTH_NAME", "%B"),
+            ("CURRENT_MONTH_NAME_SHORT", "%b"),
+            ("CURRENT_DATE", "%d"),
+            ("CURRENT_DAY_NAME", "%A"),
+            ("CURRENT_DAY_NAME_SHORT", "%a"),
+            ("CURRENT_HOUR", "%H"),
+            ("CURRENT_MINUTE", "%M"),
+            ("CURRENT_SECOND", "%S"),
+        ] {
+            variables.insert(name, now.format(format).to_string());
+        }
+        variables.insert("CURRENT_SECONDS_UNIX", now.timestamp().to_string());
+
+        let mut rng = rand::thread_rng();
+        variables.insert("UUID", uuid::Uuid::new_v4().to_string());
+        variables.insert("RANDOM", format!("{:06}", rng.gen_range(0..1_000_000)));
+        variables.insert("RANDOM_HEX", format!("{:06x}", rng.gen_range(0..0x1000000)));
+
+        let scope = snapshot.language_scope_at(head);
+        let line_comment = scope
+            .as_ref()
+            .and_then(|scope| scope.line_comment_prefixes().first())
+            .map(|prefix| prefix.trim_end().to_string());
+        let block_comment = scope
+            .as_ref()
+            .and_then(|scope| scope.block_comment_delimiters())
+            .map(|(start, end)| (start.trim_end().to_string(), end.trim_start().to_string()));
+        variables.insert("LINE_COMMENT", line_comment.unwrap_or_default());
+        let (block_comment_start, block_comment_end) = block_comment.unwrap_or_default();
+        variables.insert("BLOCK_COMMENT_START", block_comment_start);
+        variables.insert("BLOCK_COMMENT_END", block_comment_end);
+
+        variables
+    }
+}
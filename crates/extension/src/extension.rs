@@ -98,6 +98,21 @@ pub trait Extension: Send + Sync + 'static {
         worktree: Option<Arc<dyn WorktreeDelegate>>,
     ) -> Result<SlashCommandOutput>;
 
+    async fn task_templates(&self, language: LanguageName) -> Result<Vec<TaskTemplate>>;
+
+    async fn task_variables(
+        &self,
+        language: LanguageName,
+        variables: Vec<(String, String)>,
+    ) -> Result<Vec<(String, String)>>;
+
+    async fn run_tool(
+        &self,
+        tool: Tool,
+        input: String,
+        project: Arc<dyn ProjectDelegate>,
+    ) -> Result<String>;
+
     async fn context_server_command(
         &self,
         context_server_id: Arc<str>,
@@ -8,7 +8,7 @@ use language::{LanguageMatcher, LanguageName, LanguageServerBinaryStatus, Loaded
 use lsp::LanguageServerName;
 use parking_lot::RwLock;
 
-use crate::{Extension, SlashCommand};
+use crate::{Extension, SlashCommand, Tool};
 
 #[derive(Default)]
 struct GlobalExtensionHostProxy(Arc<ExtensionHostProxy>);
@@ -27,6 +27,7 @@ pub struct ExtensionHostProxy {
     language_server_proxy: RwLock<Option<Arc<dyn ExtensionLanguageServerProxy>>>,
     snippet_proxy: RwLock<Option<Arc<dyn ExtensionSnippetProxy>>>,
     slash_command_proxy: RwLock<Option<Arc<dyn ExtensionSlashCommandProxy>>>,
+    tool_proxy: RwLock<Option<Arc<dyn ExtensionToolProxy>>>,
     context_server_proxy: RwLock<Option<Arc<dyn ExtensionContextServerProxy>>>,
     indexed_docs_provider_proxy: RwLock<Option<Arc<dyn ExtensionIndexedDocsProviderProxy>>>,
 }
@@ -52,6 +53,7 @@ impl ExtensionHostProxy {
             language_server_proxy: RwLock::default(),
             snippet_proxy: RwLock::default(),
             slash_command_proxy: RwLock::default(),
+            tool_proxy: RwLock::default(),
             context_server_proxy: RwLock::default(),
             indexed_docs_provider_proxy: RwLock::default(),
         }
@@ -81,6 +83,10 @@ impl ExtensionHostProxy {
         self.slash_command_proxy.write().replace(Arc::new(proxy));
     }
 
+    pub fn register_tool_proxy(&self, proxy: impl ExtensionToolProxy) {
+        self.tool_proxy.write().replace(Arc::new(proxy));
+    }
+
     pub fn register_context_server_proxy(&self, proxy: impl ExtensionContextServerProxy) {
         self.context_server_proxy.write().replace(Arc::new(proxy));
     }
@@ -103,6 +109,23 @@ pub trait ExtensionThemeProxy: Send + Sync + 'static {
     fn load_user_theme(&self, theme_path: PathBuf, fs: Arc<dyn Fs>) -> Task<Result<()>>;
 
     fn reload_current_theme(&self, cx: &mut AppContext);
+
+    fn list_icon_theme_names(
+        &self,
+        icon_theme_path: PathBuf,
+        fs: Arc<dyn Fs>,
+    ) -> Task<Result<Vec<String>>>;
+
+    fn remove_icon_themes(&self, icon_themes: Vec<SharedString>);
+
+    fn load_icon_theme(
+        &self,
+        icon_theme_path: PathBuf,
+        icons_root_dir: PathBuf,
+        fs: Arc<dyn Fs>,
+    ) -> Task<Result<()>>;
+
+    fn reload_current_icon_theme(&self, cx: &mut AppContext);
 }
 
 impl ExtensionThemeProxy for ExtensionHostProxy {
@@ -137,6 +160,47 @@ impl ExtensionThemeProxy for ExtensionHostProxy {
 
         proxy.reload_current_theme(cx)
     }
+
+    fn list_icon_theme_names(
+        &self,
+        icon_theme_path: PathBuf,
+        fs: Arc<dyn Fs>,
+    ) -> Task<Result<Vec<String>>> {
+        let Some(proxy) = self.theme_proxy.read().clone() else {
+            return Task::ready(Ok(Vec::new()));
+        };
+
+        proxy.list_icon_theme_names(icon_theme_path, fs)
+    }
+
+    fn remove_icon_themes(&self, icon_themes: Vec<SharedString>) {
+        let Some(proxy) = self.theme_proxy.read().clone() else {
+            return;
+        };
+
+        proxy.remove_icon_themes(icon_themes)
+    }
+
+    fn load_icon_theme(
+        &self,
+        icon_theme_path: PathBuf,
+        icons_root_dir: PathBuf,
+        fs: Arc<dyn Fs>,
+    ) -> Task<Result<()>> {
+        let Some(proxy) = self.theme_proxy.read().clone() else {
+            return Task::ready(Ok(()));
+        };
+
+        proxy.load_icon_theme(icon_theme_path, icons_root_dir, fs)
+    }
+
+    fn reload_current_icon_theme(&self, cx: &mut AppContext) {
+        let Some(proxy) = self.theme_proxy.read().clone() else {
+            return;
+        };
+
+        proxy.reload_current_icon_theme(cx)
+    }
 }
 
 pub trait ExtensionGrammarProxy: Send + Sync + 'static {
@@ -287,6 +351,30 @@ impl ExtensionSlashCommandProxy for ExtensionHostProxy {
     }
 }
 
+pub trait ExtensionToolProxy: Send + Sync + 'static {
+    fn register_tool(&self, extension: Arc<dyn Extension>, tool: Tool);
+
+    fn unregister_tool(&self, tool_name: Arc<str>);
+}
+
+impl ExtensionToolProxy for ExtensionHostProxy {
+    fn register_tool(&self, extension: Arc<dyn Extension>, tool: Tool) {
+        let Some(proxy) = self.tool_proxy.read().clone() else {
+            return;
+        };
+
+        proxy.register_tool(extension, tool)
+    }
+
+    fn unregister_tool(&self, tool_name: Arc<str>) {
+        let Some(proxy) = self.tool_proxy.read().clone() else {
+            return;
+        };
+
+        proxy.unregister_tool(tool_name)
+    }
+}
+
 pub trait ExtensionContextServerProxy: Send + Sync + 'static {
     fn register_context_server(
         &self,
@@ -1,10 +1,14 @@
 mod lsp;
 mod slash_command;
+mod task;
+mod tool;
 
 use std::ops::Range;
 
 pub use lsp::*;
 pub use slash_command::*;
+pub use task::*;
+pub use tool::*;
 
 /// A list of environment variables.
 pub type EnvVars = Vec<(String, String)>;
@@ -0,0 +1,16 @@
+/// A template for a task contributed by an extension.
+#[derive(Debug, Clone)]
+pub struct TaskTemplate {
+    /// The human-readable name of the task to display in the UI.
+    pub label: String,
+    /// The command to run.
+    pub command: String,
+    /// The arguments to pass to the command.
+    pub args: Vec<String>,
+    /// The environment variables to set for the command.
+    pub env: Vec<(String, String)>,
+    /// The directory to run the command in.
+    pub cwd: Option<String>,
+    /// The runnable tags this task applies to.
+    pub tags: Vec<String>,
+}
@@ -0,0 +1,10 @@
+/// A tool that can be used by a language model in the Assistant.
+#[derive(Debug, Clone)]
+pub struct Tool {
+    /// The name of the tool.
+    pub name: String,
+    /// The description of the tool.
+    pub description: String,
+    /// The JSON schema that describes the tool's input.
+    pub input_schema: serde_json::Value,
+}
@@ -27,6 +27,8 @@ pub use wit::{
     zed::extension::slash_command::{
         SlashCommand, SlashCommandArgumentCompletion, SlashCommandOutput, SlashCommandOutputSection,
     },
+    zed::extension::task::TaskTemplate,
+    zed::extension::tool::Tool,
     CodeLabel, CodeLabelSpan, CodeLabelSpanLiteral, Command, DownloadedFileType, EnvVars,
     KeyValueStore, LanguageServerInstallationStatus, Project, Range, Worktree,
 };
@@ -129,6 +131,30 @@ pub trait Extension: Send + Sync {
         Err("`run_slash_command` not implemented".to_string())
     }
 
+    /// Returns the task templates for the given language.
+    fn language_task_templates(&self, _language_name: String) -> Result<Vec<TaskTemplate>, String> {
+        Ok(Vec::new())
+    }
+
+    /// Returns additional task variables for the given language, computed from
+    /// the variables of the current task context (e.g., `ZED_FILE` or `ZED_ROW`).
+    ///
+    /// The returned variables are available to task templates as `$ZED_CUSTOM_<name>`.
+    fn language_task_variables(
+        &self,
+        _language_name: String,
+        _variables: EnvVars,
+    ) -> Result<EnvVars, String> {
+        Ok(Vec::new())
+    }
+
+    /// Runs the provided tool with the given input, returning the output for the language model.
+    ///
+    /// The input is a JSON value matching the tool's input schema.
+    fn run_tool(&self, _tool: Tool, _input: String, _project: &Project) -> Result<String, String> {
+        Err("`run_tool` not implemented".to_string())
+    }
+
     /// Returns the command used to start a context server.
     fn context_server_command(
         &mut self,
@@ -195,7 +221,7 @@ mod wit {
 
     wit_bindgen::generate!({
         skip: ["init-extension"],
-        path: "./wit/since_v0.2.0",
+        path: "./wit/since_v0.3.0",
     });
 }
 
@@ -279,6 +305,21 @@ impl wit::Guest for Component {
         extension().run_slash_command(command, args, worktree)
     }
 
+    fn language_task_templates(language_name: String) -> Result<Vec<TaskTemplate>, String> {
+        extension().language_task_templates(language_name)
+    }
+
+    fn language_task_variables(
+        language_name: String,
+        variables: EnvVars,
+    ) -> Result<EnvVars, String> {
+        extension().language_task_variables(language_name, variables)
+    }
+
+    fn run_tool(tool: Tool, input: String, project: &Project) -> Result<String, String> {
+        extension().run_tool(tool, input, project)
+    }
+
     fn context_server_command(
         context_server_id: String,
         project: &Project,
@@ -0,0 +1,181 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{mpsc, Arc};
+
+use ::fs::RealFs;
+use anyhow::{anyhow, bail, Context as _, Result};
+use extension::{Extension as _, ExtensionHostProxy, ExtensionManifest};
+use extension_host::wasm_host::{WasmExtension, WasmHost};
+use gpui::AsyncAppContext;
+use http_client::HttpClient;
+use language::LanguageServerName;
+use node_runtime::NodeRuntime;
+use release_channel::AppVersion;
+use serde::Deserialize;
+
+use crate::labels::{check_labels, ExpectedLabel};
+
+/// A recorded set of inputs for an extension's exported functions, along with
+/// the labels the extension is expected to produce for them.
+///
+/// Fixtures are read from the `tests/fixtures` directory of the extension.
+#[derive(Debug, Deserialize)]
+struct Fixture {
+    language_server_id: String,
+    #[serde(default)]
+    labels_for_completions: Vec<CompletionFixture>,
+    #[serde(default)]
+    labels_for_symbols: Vec<SymbolFixture>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CompletionFixture {
+    completion: lsp::CompletionItem,
+    expected: Option<ExpectedLabel>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SymbolFixture {
+    name: String,
+    kind: lsp::SymbolKind,
+    expected: Option<ExpectedLabel>,
+}
+
+pub fn test_fixtures(
+    manifest: Arc<ExtensionManifest>,
+    extension_path: &Path,
+    work_dir: PathBuf,
+    http_client: Arc<dyn HttpClient>,
+) -> Result<()> {
+    let fixtures = load_fixtures(&extension_path.join("tests").join("fixtures"))?;
+    if fixtures.is_empty() {
+        return Ok(());
+    }
+    if manifest.lib.kind.is_none() {
+        bail!("extension has fixtures but does not provide a Wasm module");
+    }
+
+    let extension_path = extension_path.to_path_buf();
+    let (result_tx, result_rx) = mpsc::channel();
+    gpui::App::headless().run(move |cx| {
+        settings::init(cx);
+        release_channel::init(AppVersion::init(env!("CARGO_PKG_VERSION")), cx);
+        language::init(cx);
+
+        let wasm_host = WasmHost::new(
+            Arc::new(RealFs::default()),
+            http_client,
+            NodeRuntime::unavailable(),
+            Arc::new(ExtensionHostProxy::new()),
+            work_dir,
+            cx,
+        );
+
+        cx.spawn(|cx| async move {
+            let result = run_fixtures(fixtures, extension_path, &manifest, wasm_host, &cx).await;
+            result_tx.send(result).ok();
+            cx.update(|cx| cx.quit()).ok();
+        })
+        .detach();
+    });
+
+    result_rx
+        .recv()
+        .map_err(|_| anyhow!("fixtures did not finish running"))?
+}
+
+fn load_fixtures(fixtures_dir: &Path) -> Result<Vec<(PathBuf, Fixture)>> {
+    if !fixtures_dir.is_dir() {
+        return Ok(Vec::new());
+    }
+
+    let mut fixture_paths = fs::read_dir(fixtures_dir)?
+        .map(|entry| Ok(entry?.path()))
+        .collect::<Result<Vec<_>>>()?;
+    fixture_paths.retain(|path| path.extension() == Some("json".as_ref()));
+    fixture_paths.sort();
+
+    fixture_paths
+        .into_iter()
+        .map(|path| {
+            let content = fs::read_to_string(&path)?;
+            let fixture = serde_json::from_str(&content)
+                .with_context(|| format!("failed to parse fixture {}", path.display()))?;
+            Ok((path, fixture))
+        })
+        .collect()
+}
+
+async fn run_fixtures(
+    fixtures: Vec<(PathBuf, Fixture)>,
+    extension_path: PathBuf,
+    manifest: &Arc<ExtensionManifest>,
+    wasm_host: Arc<WasmHost>,
+    cx: &AsyncAppContext,
+) -> Result<()> {
+    let extension = WasmExtension::load(extension_path, manifest, wasm_host, cx)
+        .await
+        .context("failed to load extension")?;
+
+    let mut failures = Vec::new();
+    for (path, fixture) in fixtures {
+        let language_server_id = LanguageServerName(fixture.language_server_id.into());
+
+        let (completions, expected): (Vec<_>, Vec<_>) = fixture
+            .labels_for_completions
+            .into_iter()
+            .map(|fixture| {
+                (
+                    language_extension::lsp_completion_to_extension(fixture.completion),
+                    fixture.expected,
+                )
+            })
+            .unzip();
+        if !completions.is_empty() {
+            let labels = extension
+                .labels_for_completions(language_server_id.clone(), completions)
+                .await
+                .with_context(|| format!("labels-for-completions failed for {}", path.display()))?;
+            check_labels(
+                &path,
+                "labels_for_completions",
+                labels,
+                expected,
+                &mut failures,
+            );
+        }
+
+        let (symbols, expected): (Vec<_>, Vec<_>) = fixture
+            .labels_for_symbols
+            .into_iter()
+            .map(|fixture| {
+                (
+                    extension::Symbol {
+                        kind: language_extension::lsp_symbol_kind_to_extension(fixture.kind),
+                        name: fixture.name,
+                    },
+                    fixture.expected,
+                )
+            })
+            .unzip();
+        if !symbols.is_empty() {
+            let labels = extension
+                .labels_for_symbols(language_server_id, symbols)
+                .await
+                .with_context(|| format!("labels-for-symbols failed for {}", path.display()))?;
+            check_labels(&path, "labels_for_symbols", labels, expected, &mut failures);
+        }
+
+        log::info!("ran fixture {}", path.display());
+    }
+
+    if !failures.is_empty() {
+        bail!(
+            "{} fixture(s) failed:\n{}",
+            failures.len(),
+            failures.join("\n")
+        );
+    }
+
+    Ok(())
+}
@@ -0,0 +1,190 @@
+use std::path::Path;
+
+use anyhow::{anyhow, Result};
+use extension::{CodeLabel, CodeLabelSpan};
+use serde::Deserialize;
+
+/// The label that a fixture expects an extension to produce.
+#[derive(Debug, PartialEq, Deserialize)]
+pub struct ExpectedLabel {
+    pub text: String,
+    pub filter_text: String,
+}
+
+/// Compares the labels returned by `function` with the expected ones, recording a failure for
+/// each label that doesn't match.
+pub fn check_labels(
+    path: &Path,
+    function: &str,
+    labels: Vec<Option<CodeLabel>>,
+    expected: Vec<Option<ExpectedLabel>>,
+    failures: &mut Vec<String>,
+) {
+    if labels.len() != expected.len() {
+        failures.push(format!(
+            "{}: {function} returned {} labels, expected {}",
+            path.display(),
+            labels.len(),
+            expected.len()
+        ));
+        return;
+    }
+
+    for (ix, (label, expected)) in labels.into_iter().zip(expected).enumerate() {
+        let actual = label.map(|label| display_label(&label)).transpose();
+        match actual {
+            Ok(actual) if actual == expected => {}
+            Ok(actual) => failures.push(format!(
+                "{}: {function}[{ix}]: expected {expected:?}, got {actual:?}",
+                path.display()
+            )),
+            Err(error) => failures.push(format!(
+                "{}: {function}[{ix}]: invalid label: {error}",
+                path.display()
+            )),
+        }
+    }
+}
+
+/// Returns the text that would be displayed for the given label, and the part of it used for filtering.
+fn display_label(label: &CodeLabel) -> Result<ExpectedLabel> {
+    let mut text = String::new();
+    for span in &label.spans {
+        match span {
+            CodeLabelSpan::CodeRange(range) => text.push_str(
+                label
+                    .code
+                    .get(range.clone())
+                    .ok_or_else(|| anyhow!("span {range:?} is out of bounds of the code"))?,
+            ),
+            CodeLabelSpan::Literal(literal) => text.push_str(&literal.text),
+        }
+    }
+
+    let filter_text = text
+        .get(label.filter_range.clone())
+        .ok_or_else(|| anyhow!("filter range {:?} is out of bounds", label.filter_range))?
+        .to_string();
+    Ok(ExpectedLabel { text, filter_text })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use extension::CodeLabelSpanLiteral;
+
+    fn label(
+        code: &str,
+        spans: Vec<CodeLabelSpan>,
+        filter_range: std::ops::Range<usize>,
+    ) -> CodeLabel {
+        CodeLabel {
+            code: code.into(),
+            spans,
+            filter_range,
+        }
+    }
+
+    fn literal(text: &str) -> CodeLabelSpan {
+        CodeLabelSpan::Literal(CodeLabelSpanLiteral {
+            text: text.into(),
+            highlight_name: None,
+        })
+    }
+
+    fn expected(text: &str, filter_text: &str) -> ExpectedLabel {
+        ExpectedLabel {
+            text: text.into(),
+            filter_text: filter_text.into(),
+        }
+    }
+
+    #[test]
+    fn test_display_label() {
+        let code_label = label(
+            "fn len(&self) -> usize {}",
+            vec![
+                CodeLabelSpan::CodeRange(0..6),
+                literal("(…)"),
+                CodeLabelSpan::CodeRange(13..22),
+            ],
+            3..6,
+        );
+        assert_eq!(
+            display_label(&code_label).unwrap(),
+            expected("fn len(…) -> usize", "len")
+        );
+
+        let out_of_bounds_span = label("len", vec![CodeLabelSpan::CodeRange(0..10)], 0..3);
+        assert!(display_label(&out_of_bounds_span).is_err());
+
+        let out_of_bounds_filter = label("len", vec![CodeLabelSpan::CodeRange(0..3)], 0..4);
+        assert!(display_label(&out_of_bounds_filter).is_err());
+    }
+
+    #[test]
+    fn test_check_labels() {
+        let path = Path::new("tests/fixtures/rust.json");
+        let labels = || {
+            vec![
+                Some(label("len", vec![CodeLabelSpan::CodeRange(0..3)], 0..3)),
+                None,
+            ]
+        };
+
+        let mut failures = Vec::new();
+        check_labels(
+            path,
+            "labels_for_completions",
+            labels(),
+            vec![Some(expected("len", "len")), None],
+            &mut failures,
+        );
+        assert_eq!(failures, Vec::<String>::new());
+
+        check_labels(
+            path,
+            "labels_for_completions",
+            labels(),
+            vec![Some(expected("len", "le")), Some(expected("push", "push"))],
+            &mut failures,
+        );
+        assert_eq!(
+            failures,
+            [
+                r#"tests/fixtures/rust.json: labels_for_completions[0]: expected Some(ExpectedLabel { text: "len", filter_text: "le" }), got Some(ExpectedLabel { text: "len", filter_text: "len" })"#,
+                r#"tests/fixtures/rust.json: labels_for_completions[1]: expected Some(ExpectedLabel { text: "push", filter_text: "push" }), got None"#,
+            ]
+        );
+
+        failures.clear();
+        check_labels(
+            path,
+            "labels_for_symbols",
+            labels(),
+            vec![None],
+            &mut failures,
+        );
+        assert_eq!(
+            failures,
+            ["tests/fixtures/rust.json: labels_for_symbols returned 2 labels, expected 1"]
+        );
+
+        failures.clear();
+        check_labels(
+            path,
+            "labels_for_symbols",
+            vec![Some(label(
+                "len",
+                vec![CodeLabelSpan::CodeRange(2..5)],
+                0..1,
+            ))],
+            vec![None],
+            &mut failures,
+        );
+        assert_eq!(
+            failures,
+            ["tests/fixtures/rust.json: labels_for_symbols[0]: invalid label: span 2..5 is out of bounds of the code"]
+        );
+    }
+}
@@ -1,3 +1,8 @@
+#[cfg(feature = "fixtures")]
+mod fixtures;
+#[cfg(any(test, feature = "fixtures"))]
+mod labels;
+
 use std::{
     collections::HashMap,
     env, fs,
@@ -15,7 +20,7 @@ use extension::{
 };
 use language::LanguageConfig;
 use reqwest_client::ReqwestClient;
-use tree_sitter::{Language, Query, WasmStore};
+use tree_sitter::{Language, Node, Parser, Query, WasmStore};
 
 #[derive(Parser, Debug)]
 #[command(name = "zed-extension")]
@@ -24,11 +29,14 @@ struct Args {
     #[arg(long)]
     source_dir: PathBuf,
     /// The output directory to place the packaged extension.
-    #[arg(long)]
-    output_dir: PathBuf,
+    #[arg(long, required_unless_present = "test")]
+    output_dir: Option<PathBuf>,
     /// The path to a directory where build dependencies are downloaded
     #[arg(long)]
     scratch_dir: PathBuf,
+    /// Test the extension against its sample files and fixtures, instead of packaging it.
+    #[arg(long)]
+    test: bool,
 }
 
 #[tokio::main]
@@ -48,11 +56,6 @@ async fn main() -> Result<()> {
         .scratch_dir
         .canonicalize()
         .context("failed to canonicalize scratch_dir")?;
-    let output_dir = if args.output_dir.is_relative() {
-        env::current_dir()?.join(&args.output_dir)
-    } else {
-        args.output_dir
-    };
 
     log::info!("loading extension manifest");
     let mut manifest = ExtensionManifest::load(fs.clone(), &extension_path).await?;
@@ -67,7 +70,7 @@ async fn main() -> Result<()> {
     );
     let http_client = Arc::new(ReqwestClient::user_agent(&user_agent)?);
 
-    let builder = ExtensionBuilder::new(http_client, scratch_dir);
+    let builder = ExtensionBuilder::new(http_client.clone(), scratch_dir.clone());
     builder
         .compile_extension(
             &extension_path,
@@ -80,6 +83,37 @@ async fn main() -> Result<()> {
     let grammars = test_grammars(&manifest, &extension_path, &mut wasm_store)?;
     test_languages(&manifest, &extension_path, &grammars)?;
     test_themes(&manifest, &extension_path, fs.clone()).await?;
+    test_icon_themes(&manifest, &extension_path, fs.clone()).await?;
+
+    if args.test {
+        test_language_samples(&manifest, &extension_path, &grammars, wasm_store)?;
+        // Running the fixtures blocks this thread on gpui's event loop, so the Tokio runtime
+        // needs to move its other tasks, including the HTTP client's, to other workers.
+        #[cfg(feature = "fixtures")]
+        tokio::task::block_in_place(|| {
+            fixtures::test_fixtures(
+                Arc::new(manifest),
+                &extension_path,
+                scratch_dir.join("work"),
+                http_client,
+            )
+        })?;
+        #[cfg(not(feature = "fixtures"))]
+        if extension_path.join("tests").join("fixtures").is_dir() {
+            bail!("the extension has fixtures, but zed-extension was built without the `fixtures` feature");
+        }
+        log::info!("all extension tests passed");
+        return Ok(());
+    }
+
+    let output_dir = args
+        .output_dir
+        .ok_or_else(|| anyhow!("--output-dir is required when packaging an extension"))?;
+    let output_dir = if output_dir.is_relative() {
+        env::current_dir()?.join(&output_dir)
+    } else {
+        output_dir
+    };
 
     let archive_dir = output_dir.join("archive");
     fs::remove_dir_all(&archive_dir).ok();
@@ -167,6 +201,39 @@ async fn copy_extension_resources(
         }
     }
 
+    if !manifest.icon_themes.is_empty() {
+        let output_icon_themes_dir = output_dir.join("icon_themes");
+        fs::create_dir_all(&output_icon_themes_dir)?;
+        for icon_theme_path in &manifest.icon_themes {
+            fs::copy(
+                extension_path.join(icon_theme_path),
+                output_icon_themes_dir.join(
+                    icon_theme_path
+                        .file_name()
+                        .ok_or_else(|| anyhow!("invalid icon theme path"))?,
+                ),
+            )
+            .with_context(|| {
+                format!("failed to copy icon theme '{}'", icon_theme_path.display())
+            })?;
+        }
+
+        let icons_dir = extension_path.join("icons");
+        if icons_dir.is_dir() {
+            copy_recursive(
+                fs.as_ref(),
+                &icons_dir,
+                &output_dir.join("icons"),
+                CopyOptions {
+                    overwrite: true,
+                    ignore_if_exists: false,
+                },
+            )
+            .await
+            .context("failed to copy icons dir")?;
+        }
+    }
+
     if !manifest.languages.is_empty() {
         let output_languages_dir = output_dir.join("languages");
         fs::create_dir_all(&output_languages_dir)?;
@@ -249,7 +316,13 @@ fn test_languages(
                 })?;
 
                 let query_source = fs::read_to_string(&query_path)?;
-                let _query = Query::new(grammar, &query_source)?;
+                let _query = Query::new(grammar, &query_source).with_context(|| {
+                    format!(
+                        "query {} of language {} is invalid",
+                        query_path.display(),
+                        config.name
+                    )
+                })?;
             }
         }
 
@@ -259,6 +332,87 @@ fn test_languages(
     Ok(())
 }
 
+/// Parses each language's sample files, found in `tests/languages/<language>`,
+/// and checks that they parse without errors.
+fn test_language_samples(
+    manifest: &ExtensionManifest,
+    extension_path: &Path,
+    grammars: &HashMap<String, Language>,
+    wasm_store: WasmStore,
+) -> Result<()> {
+    let samples_dir = extension_path.join("tests").join("languages");
+    let mut parser = Parser::new();
+    parser.set_wasm_store(wasm_store)?;
+
+    for relative_language_dir in &manifest.languages {
+        let language_dir = extension_path.join(relative_language_dir);
+        let config_content = fs::read_to_string(language_dir.join("config.toml"))?;
+        let config: LanguageConfig = toml::from_str(&config_content)?;
+        let Some(grammar_name) = &config.grammar else {
+            continue;
+        };
+        let grammar = grammars
+            .get(grammar_name.as_ref())
+            .ok_or_else(|| anyhow!("grammar not found: '{grammar_name}'"))?;
+
+        let language_samples_dir = samples_dir.join(
+            relative_language_dir
+                .file_name()
+                .ok_or_else(|| anyhow!("invalid language path"))?,
+        );
+        if !language_samples_dir.is_dir() {
+            continue;
+        }
+
+        parser.set_language(grammar)?;
+        let mut sample_paths = fs::read_dir(&language_samples_dir)?
+            .map(|entry| Ok(entry?.path()))
+            .collect::<Result<Vec<_>>>()?;
+        sample_paths.retain(|path| path.is_file());
+        sample_paths.sort();
+
+        for sample_path in sample_paths {
+            let source = fs::read_to_string(&sample_path)?;
+            let tree = parser
+                .parse(&source, None)
+                .ok_or_else(|| anyhow!("failed to parse {}", sample_path.display()))?;
+            if let Some(node) = first_error_node(tree.root_node()) {
+                let position = node.start_position();
+                bail!(
+                    "{}:{}:{}: {} while parsing with grammar '{grammar_name}'",
+                    sample_path.display(),
+                    position.row + 1,
+                    position.column + 1,
+                    if node.is_missing() {
+                        format!("missing {}", node.kind())
+                    } else {
+                        "syntax error".to_string()
+                    }
+                );
+            }
+            log::info!("parsed sample {}", sample_path.display());
+        }
+    }
+
+    Ok(())
+}
+
+fn first_error_node(node: Node) -> Option<Node> {
+    if !node.has_error() {
+        return None;
+    }
+    if node.is_error() || node.is_missing() {
+        return Some(node);
+    }
+
+    let mut cursor = node.walk();
+    let children = node.children(&mut cursor).collect::<Vec<_>>();
+    children
+        .into_iter()
+        .find_map(first_error_node)
+        .or(Some(node))
+}
+
 async fn test_themes(
     manifest: &ExtensionManifest,
     extension_path: &Path,
@@ -272,3 +426,40 @@ async fn test_themes(
 
     Ok(())
 }
+
+async fn test_icon_themes(
+    manifest: &ExtensionManifest,
+    extension_path: &Path,
+    fs: Arc<dyn Fs>,
+) -> Result<()> {
+    for relative_icon_theme_path in &manifest.icon_themes {
+        let icon_theme_path = extension_path.join(relative_icon_theme_path);
+        let icon_theme_family = theme::read_icon_theme(&icon_theme_path, fs.clone()).await?;
+        for icon_theme in icon_theme_family.themes {
+            let icon_theme = theme::IconTheme::from_content(icon_theme, extension_path);
+            let icon_paths = icon_theme.file_icons.values().chain(
+                [&icon_theme.directory_icons, &icon_theme.chevron_icons]
+                    .into_iter()
+                    .chain(icon_theme.named_directory_icons.values())
+                    .flat_map(|icons| icons.collapsed.iter().chain(icons.expanded.iter())),
+            );
+            for icon_path in icon_paths {
+                if !Path::new(icon_path.as_ref()).starts_with(extension_path.join("icons")) {
+                    bail!(
+                        "icon '{icon_path}' in icon theme '{}' must be in the icons directory",
+                        icon_theme.name
+                    );
+                }
+                if !fs.is_file(Path::new(icon_path.as_ref())).await {
+                    bail!(
+                        "icon '{icon_path}' in icon theme '{}' does not exist",
+                        icon_theme.name
+                    );
+                }
+            }
+        }
+        log::info!("loaded icon theme family {}", icon_theme_family.name);
+    }
+
+    Ok(())
+}
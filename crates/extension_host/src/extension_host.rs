@@ -1,4 +1,5 @@
 pub mod extension_settings;
+mod extension_task_provider;
 pub mod headless_host;
 pub mod wasm_host;
 
@@ -15,8 +16,9 @@ pub use extension::ExtensionManifest;
 use extension::{
     ExtensionContextServerProxy, ExtensionGrammarProxy, ExtensionHostProxy,
     ExtensionIndexedDocsProviderProxy, ExtensionLanguageProxy, ExtensionLanguageServerProxy,
-    ExtensionSlashCommandProxy, ExtensionSnippetProxy, ExtensionThemeProxy,
+    ExtensionSlashCommandProxy, ExtensionSnippetProxy, ExtensionThemeProxy, ExtensionToolProxy,
 };
+use extension_task_provider::{ExtensionTaskProvider, ExtensionTasks};
 use fs::{Fs, RemoveOptions};
 use futures::{
     channel::{
@@ -142,6 +144,8 @@ impl Global for GlobalExtensionStore {}
 pub struct ExtensionIndex {
     pub extensions: BTreeMap<Arc<str>, ExtensionIndexEntry>,
     pub themes: BTreeMap<Arc<str>, ExtensionIndexThemeEntry>,
+    #[serde(default)]
+    pub icon_themes: BTreeMap<Arc<str>, ExtensionIndexIconThemeEntry>,
     pub languages: BTreeMap<LanguageName, ExtensionIndexLanguageEntry>,
 }
 
@@ -157,6 +161,12 @@ pub struct ExtensionIndexThemeEntry {
     pub path: PathBuf,
 }
 
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Debug, Deserialize, Serialize)]
+pub struct ExtensionIndexIconThemeEntry {
+    pub extension: Arc<str>,
+    pub path: PathBuf,
+}
+
 #[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Debug, Deserialize, Serialize)]
 pub struct ExtensionIndexLanguageEntry {
     pub extension: Arc<str>,
@@ -436,6 +446,23 @@ impl ExtensionStore {
             .filter_map(|(name, theme)| theme.extension.as_ref().eq(extension_id).then_some(name))
     }
 
+    /// Returns the names of icon themes provided by extensions.
+    pub fn extension_icon_themes<'a>(
+        &'a self,
+        extension_id: &'a str,
+    ) -> impl Iterator<Item = &'a Arc<str>> {
+        self.extension_index
+            .icon_themes
+            .iter()
+            .filter_map(|(name, icon_theme)| {
+                icon_theme
+                    .extension
+                    .as_ref()
+                    .eq(extension_id)
+                    .then_some(name)
+            })
+    }
+
     pub fn fetch_extensions(
         &self,
         search: Option<&str>,
@@ -1023,6 +1050,17 @@ impl ExtensionStore {
                 }
             })
             .collect::<Vec<_>>();
+        let icon_themes_to_remove = old_index
+            .icon_themes
+            .iter()
+            .filter_map(|(name, entry)| {
+                if extensions_to_unload.contains(&entry.extension) {
+                    Some(name.clone().into())
+                } else {
+                    None
+                }
+            })
+            .collect::<Vec<_>>();
         let languages_to_remove = old_index
             .languages
             .iter()
@@ -1040,6 +1078,9 @@ impl ExtensionStore {
                 continue;
             };
             grammars_to_remove.extend(extension.manifest.grammars.keys().cloned());
+            for tool_name in extension.manifest.tools.keys() {
+                self.proxy.unregister_tool(tool_name.clone());
+            }
             for (language_server_name, config) in extension.manifest.language_servers.iter() {
                 for language in config.languages() {
                     self.proxy
@@ -1051,6 +1092,7 @@ impl ExtensionStore {
         self.wasm_extensions
             .retain(|(extension, _)| !extensions_to_unload.contains(&extension.id));
         self.proxy.remove_user_themes(themes_to_remove);
+        self.proxy.remove_icon_themes(icon_themes_to_remove);
         self.proxy
             .remove_languages(&languages_to_remove, &grammars_to_remove);
 
@@ -1061,6 +1103,7 @@ impl ExtensionStore {
             .collect::<Vec<_>>();
         let mut grammars_to_add = Vec::new();
         let mut themes_to_add = Vec::new();
+        let mut icon_themes_to_add = Vec::new();
         let mut snippets_to_add = Vec::new();
         for extension_id in &extensions_to_load {
             let Some(extension) = new_index.extensions.get(extension_id) else {
@@ -1079,6 +1122,16 @@ impl ExtensionStore {
                 path.extend([Path::new(extension_id.as_ref()), theme_path.as_path()]);
                 path
             }));
+            icon_themes_to_add.extend(extension.manifest.icon_themes.iter().map(
+                |icon_theme_path| {
+                    let mut path = self.installed_dir.clone();
+                    path.extend([Path::new(extension_id.as_ref()), icon_theme_path.as_path()]);
+
+                    let icons_root_path = self.installed_dir.join(extension_id.as_ref());
+
+                    (path, icons_root_path)
+                },
+            ));
             snippets_to_add.extend(extension.manifest.snippets.iter().map(|snippets_path| {
                 let mut path = self.installed_dir.clone();
                 path.extend([Path::new(extension_id.as_ref()), snippets_path.as_path()]);
@@ -1088,12 +1141,36 @@ impl ExtensionStore {
 
         self.proxy.register_grammars(grammars_to_add);
 
+        let extension_tasks = extensions_to_load
+            .iter()
+            .filter(|extension_id| {
+                new_index
+                    .extensions
+                    .get(*extension_id)
+                    .map_or(false, |extension| {
+                        !extension.manifest.task_providers.is_empty()
+                    })
+            })
+            .map(|extension_id| (extension_id.clone(), Arc::new(ExtensionTasks::default())))
+            .collect::<HashMap<_, _>>();
+
         for (language_name, language) in languages_to_add {
             let mut language_path = self.installed_dir.clone();
             language_path.extend([
                 Path::new(language.extension.as_ref()),
                 language.path.as_path(),
             ]);
+            let tasks = new_index
+                .extensions
+                .get(&language.extension)
+                .filter(|extension| {
+                    extension
+                        .manifest
+                        .task_providers
+                        .contains_key(language_name)
+                })
+                .and_then(|_| extension_tasks.get(&language.extension).cloned());
+            let provider_language_name = language_name.clone();
             self.proxy.register_language(
                 language_name.clone(),
                 language.grammar.clone(),
@@ -1103,14 +1180,19 @@ impl ExtensionStore {
                     let config = std::fs::read_to_string(language_path.join("config.toml"))?;
                     let config: LanguageConfig = ::toml::from_str(&config)?;
                     let queries = load_plugin_queries(&language_path);
-                    let context_provider =
-                        std::fs::read_to_string(language_path.join("tasks.json"))
-                            .ok()
-                            .and_then(|contents| {
-                                let definitions =
-                                    serde_json_lenient::from_str(&contents).log_err()?;
-                                Some(Arc::new(ContextProviderWithTasks::new(definitions)) as Arc<_>)
-                            });
+                    let definitions = std::fs::read_to_string(language_path.join("tasks.json"))
+                        .ok()
+                        .and_then(|contents| serde_json_lenient::from_str(&contents).log_err());
+                    let context_provider = match tasks.clone() {
+                        Some(tasks) => Some(Arc::new(ExtensionTaskProvider::new(
+                            provider_language_name.clone(),
+                            definitions,
+                            tasks,
+                        )) as Arc<_>),
+                        None => definitions.map(|definitions| {
+                            Arc::new(ContextProviderWithTasks::new(definitions)) as Arc<_>
+                        }),
+                    };
 
                     Ok(LoadedLanguage {
                         config,
@@ -1147,6 +1229,13 @@ impl ExtensionStore {
                                 .log_err();
                         }
 
+                        for (icon_theme_path, icons_root_path) in icon_themes_to_add.into_iter() {
+                            proxy
+                                .load_icon_theme(icon_theme_path, icons_root_path, fs.clone())
+                                .await
+                                .log_err();
+                        }
+
                         for snippets_path in &snippets_to_add {
                             if let Some(snippets_contents) = fs.load(snippets_path).await.log_err()
                             {
@@ -1215,6 +1304,25 @@ impl ExtensionStore {
                         );
                     }
 
+                    for (tool_name, tool) in &manifest.tools {
+                        this.proxy.register_tool(
+                            extension.clone(),
+                            extension::Tool {
+                                name: tool_name.to_string(),
+                                description: tool.description.clone(),
+                                input_schema: serde_json::Value::Object(tool.input_schema.clone()),
+                            },
+                        );
+                    }
+
+                    if let Some(tasks) = extension_tasks.get(&manifest.id) {
+                        tasks.load(
+                            extension.clone(),
+                            manifest.task_providers.keys().cloned().collect(),
+                            cx.background_executor(),
+                        );
+                    }
+
                     for (id, _context_server_entry) in &manifest.context_servers {
                         this.proxy
                             .register_context_server(extension.clone(), id.clone(), cx);
@@ -1228,6 +1336,7 @@ impl ExtensionStore {
 
                 this.wasm_extensions.extend(wasm_extensions);
                 this.proxy.reload_current_theme(cx);
+                this.proxy.reload_current_icon_theme(cx);
             })
             .ok();
         })
@@ -1365,6 +1474,38 @@ impl ExtensionStore {
             }
         }
 
+        if let Ok(mut icon_theme_paths) = fs.read_dir(&extension_dir.join("icon_themes")).await {
+            while let Some(icon_theme_path) = icon_theme_paths.next().await {
+                let icon_theme_path = icon_theme_path?;
+                let Ok(relative_path) = icon_theme_path.strip_prefix(&extension_dir) else {
+                    continue;
+                };
+
+                let Some(icon_theme_families) = proxy
+                    .list_icon_theme_names(icon_theme_path.clone(), fs.clone())
+                    .await
+                    .log_err()
+                else {
+                    continue;
+                };
+
+                let relative_path = relative_path.to_path_buf();
+                if !extension_manifest.icon_themes.contains(&relative_path) {
+                    extension_manifest.icon_themes.push(relative_path.clone());
+                }
+
+                for icon_theme_name in icon_theme_families {
+                    index.icon_themes.insert(
+                        icon_theme_name.into(),
+                        ExtensionIndexIconThemeEntry {
+                            extension: extension_id.clone(),
+                            path: relative_path.clone(),
+                        },
+                    );
+                }
+            }
+        }
+
         let extension_wasm_path = extension_dir.join("extension.wasm");
         if fs.is_file(&extension_wasm_path).await {
             extension_manifest
@@ -4,17 +4,21 @@ use crate::{
     GrammarManifestEntry, SchemaVersion, RELOAD_DEBOUNCE_DURATION,
 };
 use async_compression::futures::bufread::GzipEncoder;
+use async_trait::async_trait;
 use collections::BTreeMap;
-use extension::ExtensionHostProxy;
+use extension::{Extension, ExtensionHostProxy, ExtensionToolProxy, ProjectDelegate, Tool};
 use fs::{FakeFs, Fs, RealFs};
 use futures::{io::BufReader, AsyncReadExt, StreamExt};
-use gpui::{Context, SemanticVersion, TestAppContext};
+use gpui::{AsyncAppContext, Context, SemanticVersion, TestAppContext};
 use http_client::{FakeHttpClient, Response};
-use language::{LanguageMatcher, LanguageRegistry, LanguageServerBinaryStatus};
+use language::{
+    Anchor, LanguageMatcher, LanguageName, LanguageRegistry, LanguageServerBinaryStatus,
+    LanguageToolchainStore, Location, Toolchain,
+};
 use lsp::LanguageServerName;
 use node_runtime::NodeRuntime;
 use parking_lot::Mutex;
-use project::{Project, DEFAULT_COMPLETION_CONTEXT};
+use project::{Project, WorktreeId, DEFAULT_COMPLETION_CONTEXT};
 use release_channel::AppVersion;
 use reqwest_client::ReqwestClient;
 use serde_json::json;
@@ -24,6 +28,7 @@ use std::{
     path::{Path, PathBuf},
     sync::Arc,
 };
+use task::{TaskVariables, VariableName};
 use theme::ThemeRegistry;
 use util::test::temp_tree;
 
@@ -149,6 +154,7 @@ async fn test_extension_store(cx: &mut TestAppContext) {
                         authors: Vec::new(),
                         repository: None,
                         themes: Default::default(),
+                        icon_themes: Vec::new(),
                         lib: Default::default(),
                         languages: vec!["languages/erb".into(), "languages/ruby".into()],
                         grammars: [
@@ -158,8 +164,10 @@ async fn test_extension_store(cx: &mut TestAppContext) {
                         .into_iter()
                         .collect(),
                         language_servers: BTreeMap::default(),
+                        task_providers: BTreeMap::default(),
                         context_servers: BTreeMap::default(),
                         slash_commands: BTreeMap::default(),
+                        tools: BTreeMap::default(),
                         indexed_docs_providers: BTreeMap::default(),
                         snippets: None,
                     }),
@@ -181,12 +189,15 @@ async fn test_extension_store(cx: &mut TestAppContext) {
                             "themes/monokai-pro.json".into(),
                             "themes/monokai.json".into(),
                         ],
+                        icon_themes: Vec::new(),
                         lib: Default::default(),
                         languages: Default::default(),
                         grammars: BTreeMap::default(),
                         language_servers: BTreeMap::default(),
+                        task_providers: BTreeMap::default(),
                         context_servers: BTreeMap::default(),
                         slash_commands: BTreeMap::default(),
+                        tools: BTreeMap::default(),
                         indexed_docs_providers: BTreeMap::default(),
                         snippets: None,
                     }),
@@ -258,6 +269,7 @@ async fn test_extension_store(cx: &mut TestAppContext) {
         ]
         .into_iter()
         .collect(),
+        icon_themes: BTreeMap::default(),
     };
 
     let proxy = Arc::new(ExtensionHostProxy::new());
@@ -344,12 +356,15 @@ async fn test_extension_store(cx: &mut TestAppContext) {
                 authors: vec![],
                 repository: None,
                 themes: vec!["themes/gruvbox.json".into()],
+                icon_themes: Vec::new(),
                 lib: Default::default(),
                 languages: Default::default(),
                 grammars: BTreeMap::default(),
                 language_servers: BTreeMap::default(),
+                task_providers: BTreeMap::default(),
                 context_servers: BTreeMap::default(),
                 slash_commands: BTreeMap::default(),
+                tools: BTreeMap::default(),
                 indexed_docs_providers: BTreeMap::default(),
                 snippets: None,
             }),
@@ -484,6 +499,8 @@ async fn test_extension_store_with_test_extension(cx: &mut TestAppContext) {
     theme_extension::init(proxy.clone(), theme_registry.clone(), cx.executor());
     let language_registry = project.read_with(cx, |project, _cx| project.languages().clone());
     language_extension::init(proxy.clone(), language_registry.clone());
+    let registered_tools = RegisteredTools::default();
+    proxy.register_tool_proxy(registered_tools.clone());
     let node_runtime = NodeRuntime::unavailable();
 
     let mut status_updates = language_registry.language_server_binary_statuses();
@@ -614,6 +631,21 @@ async fn test_extension_store_with_test_extension(cx: &mut TestAppContext) {
         .await
         .unwrap();
 
+    // The extension's tools are registered, and run in its Wasm module.
+    let (extension, tool) = registered_tools
+        .0
+        .lock()
+        .first()
+        .cloned()
+        .expect("the extension's tool should be registered");
+    assert_eq!(tool.name, "echo");
+    assert_eq!(tool.input_schema["properties"]["text"]["type"], "string");
+    let output = extension
+        .run_tool(tool, r#"{"text":"hi"}"#.into(), Arc::new(TestProject))
+        .await
+        .unwrap();
+    assert_eq!(output, r#"{"text":"hi"} (1 worktrees)"#);
+
     let mut fake_servers = language_registry.register_fake_language_server(
         LanguageServerName("gleam".into()),
         lsp::ServerCapabilities {
@@ -630,6 +662,46 @@ async fn test_extension_store_with_test_extension(cx: &mut TestAppContext) {
         .await
         .unwrap();
 
+    // The extension provides task templates for Gleam, and computes their variables.
+    cx.executor().run_until_parked();
+    let context_provider = buffer
+        .read_with(cx, |buffer, _| {
+            buffer
+                .language()
+                .and_then(|language| language.context_provider())
+        })
+        .expect("Gleam should have a context provider");
+    let templates = cx
+        .update(|cx| context_provider.associated_tasks(None, cx))
+        .unwrap();
+    assert_eq!(
+        templates
+            .0
+            .iter()
+            .map(|template| template.label.as_str())
+            .collect::<Vec<_>>(),
+        ["gleam run $ZED_CUSTOM_MODULE"]
+    );
+    let variables = cx
+        .update(|cx| {
+            context_provider.build_context(
+                &TaskVariables::from_iter([(VariableName::Stem, "test".to_string())]),
+                &Location {
+                    buffer: buffer.clone(),
+                    range: Anchor::MIN..Anchor::MIN,
+                },
+                None,
+                Arc::new(NoToolchains),
+                cx,
+            )
+        })
+        .await
+        .unwrap();
+    assert_eq!(
+        variables.get(&VariableName::Custom("MODULE".into())),
+        Some("test")
+    );
+
     let fake_server = fake_servers.next().await.unwrap();
     let expected_server_path =
         extensions_dir.join(format!("work/{test_extension_id}/gleam-v1.2.3/gleam"));
@@ -760,6 +832,43 @@ async fn test_extension_store_with_test_extension(cx: &mut TestAppContext) {
     assert!(fs.metadata(&expected_server_path).await.unwrap().is_none());
 }
 
+#[derive(Clone, Default)]
+struct RegisteredTools(Arc<Mutex<Vec<(Arc<dyn Extension>, Tool)>>>);
+
+impl ExtensionToolProxy for RegisteredTools {
+    fn register_tool(&self, extension: Arc<dyn Extension>, tool: Tool) {
+        self.0.lock().push((extension, tool));
+    }
+
+    fn unregister_tool(&self, tool_name: Arc<str>) {
+        self.0
+            .lock()
+            .retain(|(_, tool)| tool.name.as_str() != tool_name.as_ref());
+    }
+}
+
+struct TestProject;
+
+impl ProjectDelegate for TestProject {
+    fn worktree_ids(&self) -> Vec<u64> {
+        vec![1]
+    }
+}
+
+struct NoToolchains;
+
+#[async_trait(?Send)]
+impl LanguageToolchainStore for NoToolchains {
+    async fn active_toolchain(
+        self: Arc<Self>,
+        _: WorktreeId,
+        _: LanguageName,
+        _: &mut AsyncAppContext,
+    ) -> Option<Toolchain> {
+        None
+    }
+}
+
 fn init_test(cx: &mut TestAppContext) {
     cx.update(|cx| {
         let store = SettingsStore::test(cx);
@@ -0,0 +1,125 @@
+use std::borrow::Cow;
+use std::sync::Arc;
+
+use anyhow::Result;
+use collections::HashMap;
+use extension::Extension;
+use gpui::{AppContext, BackgroundExecutor, Task};
+use language::{ContextProvider, LanguageName, LanguageToolchainStore, Location};
+use parking_lot::RwLock;
+use task::{TaskTemplate, TaskTemplates, TaskVariables, VariableName};
+use util::ResultExt;
+
+/// The task state shared by all of the languages an extension provides tasks for.
+///
+/// Languages are loaded lazily, and may be loaded before the extension's Wasm module,
+/// so the extension and its task templates are filled in once the extension is loaded.
+#[derive(Default)]
+pub(crate) struct ExtensionTasks {
+    extension: RwLock<Option<Arc<dyn Extension>>>,
+    templates: RwLock<HashMap<LanguageName, TaskTemplates>>,
+}
+
+impl ExtensionTasks {
+    pub fn load(
+        self: &Arc<Self>,
+        extension: Arc<dyn Extension>,
+        languages: Vec<LanguageName>,
+        executor: &BackgroundExecutor,
+    ) {
+        *self.extension.write() = Some(extension.clone());
+
+        let this = self.clone();
+        executor
+            .spawn(async move {
+                for language in languages {
+                    let Some(templates) =
+                        extension.task_templates(language.clone()).await.log_err()
+                    else {
+                        continue;
+                    };
+
+                    let templates = templates.into_iter().map(task_template).collect();
+                    this.templates
+                        .write()
+                        .insert(language, TaskTemplates(templates));
+                }
+            })
+            .detach();
+    }
+}
+
+fn task_template(template: extension::TaskTemplate) -> TaskTemplate {
+    TaskTemplate {
+        label: template.label,
+        command: template.command,
+        args: template.args,
+        env: template.env.into_iter().collect(),
+        cwd: template.cwd,
+        tags: template.tags,
+        ..TaskTemplate::default()
+    }
+}
+
+/// A [`ContextProvider`] for a language whose tasks are provided by an extension.
+pub(crate) struct ExtensionTaskProvider {
+    language: LanguageName,
+    /// The templates from the language's `tasks.json`, if any.
+    static_templates: Option<TaskTemplates>,
+    tasks: Arc<ExtensionTasks>,
+}
+
+impl ExtensionTaskProvider {
+    pub fn new(
+        language: LanguageName,
+        static_templates: Option<TaskTemplates>,
+        tasks: Arc<ExtensionTasks>,
+    ) -> Self {
+        Self {
+            language,
+            static_templates,
+            tasks,
+        }
+    }
+}
+
+impl ContextProvider for ExtensionTaskProvider {
+    fn build_context(
+        &self,
+        variables: &TaskVariables,
+        _location: &Location,
+        _project_env: Option<HashMap<String, String>>,
+        _toolchains: Arc<dyn LanguageToolchainStore>,
+        cx: &mut AppContext,
+    ) -> Task<Result<TaskVariables>> {
+        let Some(extension) = self.tasks.extension.read().clone() else {
+            return Task::ready(Ok(TaskVariables::default()));
+        };
+
+        let language = self.language.clone();
+        let variables = variables
+            .clone()
+            .into_iter()
+            .map(|(name, value)| (name.to_string(), value))
+            .collect();
+        cx.background_executor().spawn(async move {
+            let variables = extension.task_variables(language, variables).await?;
+            Ok(variables
+                .into_iter()
+                .map(|(name, value)| (VariableName::Custom(Cow::Owned(name)), value))
+                .collect())
+        })
+    }
+
+    fn associated_tasks(
+        &self,
+        _: Option<Arc<dyn language::File>>,
+        _: &AppContext,
+    ) -> Option<TaskTemplates> {
+        let mut templates = self.static_templates.clone().unwrap_or_default();
+        if let Some(extension_templates) = self.tasks.templates.read().get(&self.language) {
+            templates.0.extend(extension_templates.0.iter().cloned());
+        }
+        Some(templates)
+    }
+}
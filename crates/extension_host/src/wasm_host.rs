@@ -5,7 +5,8 @@ use anyhow::{anyhow, bail, Context as _, Result};
 use async_trait::async_trait;
 use extension::{
     CodeLabel, Command, Completion, ExtensionHostProxy, KeyValueStoreDelegate, ProjectDelegate,
-    SlashCommand, SlashCommandArgumentCompletion, SlashCommandOutput, Symbol, WorktreeDelegate,
+    SlashCommand, SlashCommandArgumentCompletion, SlashCommandOutput, Symbol, TaskTemplate, Tool,
+    WorktreeDelegate,
 };
 use fs::{normalize_path, Fs};
 use futures::future::LocalBoxFuture;
@@ -237,6 +238,58 @@ impl extension::Extension for WasmExtension {
         .await
     }
 
+    async fn task_templates(&self, language: LanguageName) -> Result<Vec<TaskTemplate>> {
+        self.call(|extension, store| {
+            async move {
+                let templates = extension
+                    .call_language_task_templates(store, language.as_ref())
+                    .await?
+                    .map_err(|err| anyhow!("{err}"))?;
+                anyhow::Ok(templates.into_iter().map(Into::into).collect())
+            }
+            .boxed()
+        })
+        .await
+    }
+
+    async fn task_variables(
+        &self,
+        language: LanguageName,
+        variables: Vec<(String, String)>,
+    ) -> Result<Vec<(String, String)>> {
+        self.call(|extension, store| {
+            async move {
+                let variables = extension
+                    .call_language_task_variables(store, language.as_ref(), &variables)
+                    .await?
+                    .map_err(|err| anyhow!("{err}"))?;
+                anyhow::Ok(variables)
+            }
+            .boxed()
+        })
+        .await
+    }
+
+    async fn run_tool(
+        &self,
+        tool: Tool,
+        input: String,
+        project: Arc<dyn ProjectDelegate>,
+    ) -> Result<String> {
+        self.call(|extension, store| {
+            async move {
+                let project_resource = store.data_mut().table().push(project)?;
+                let output = extension
+                    .call_run_tool(store, &tool.into(), &input, project_resource)
+                    .await?
+                    .map_err(|err| anyhow!("{err}"))?;
+                anyhow::Ok(output)
+            }
+            .boxed()
+        })
+        .await
+    }
+
     async fn context_server_command(
         &self,
         context_server_id: Arc<str>,
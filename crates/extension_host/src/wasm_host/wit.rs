@@ -3,11 +3,12 @@ mod since_v0_0_4;
 mod since_v0_0_6;
 mod since_v0_1_0;
 mod since_v0_2_0;
+mod since_v0_3_0;
 use extension::{KeyValueStoreDelegate, WorktreeDelegate};
 use language::LanguageName;
 use lsp::LanguageServerName;
 use release_channel::ReleaseChannel;
-use since_v0_2_0 as latest;
+use since_v0_3_0 as latest;
 
 use super::{wasm_engine, WasmState};
 use anyhow::{anyhow, Context, Result};
@@ -25,6 +26,8 @@ pub use latest::{
         Completion, CompletionKind, CompletionLabelDetails, InsertTextFormat, Symbol, SymbolKind,
     },
     zed::extension::slash_command::{SlashCommandArgumentCompletion, SlashCommandOutput},
+    zed::extension::task::TaskTemplate,
+    zed::extension::tool::Tool,
     CodeLabel, CodeLabelSpan, Command, ExtensionProject, Range, SlashCommand,
 };
 pub use since_v0_0_4::LanguageServerConfig;
@@ -58,7 +61,7 @@ pub fn wasm_api_version_range(release_channel: ReleaseChannel) -> RangeInclusive
 
     let max_version = match release_channel {
         ReleaseChannel::Dev | ReleaseChannel::Nightly => latest::MAX_VERSION,
-        ReleaseChannel::Stable | ReleaseChannel::Preview => latest::MAX_VERSION,
+        ReleaseChannel::Stable | ReleaseChannel::Preview => since_v0_2_0::MAX_VERSION,
     };
 
     since_v0_0_1::MIN_VERSION..=max_version
@@ -88,6 +91,7 @@ pub fn authorize_access_to_unreleased_wasm_api_version(
 }
 
 pub enum Extension {
+    V030(since_v0_3_0::Extension),
     V020(since_v0_2_0::Extension),
     V010(since_v0_1_0::Extension),
     V006(since_v0_0_6::Extension),
@@ -102,14 +106,22 @@ impl Extension {
         version: SemanticVersion,
         component: &Component,
     ) -> Result<Self> {
-        // Note: The release channel can be used to stage a new version of the extension API.
-        let _ = release_channel;
-
         if version >= latest::MIN_VERSION {
+            authorize_access_to_unreleased_wasm_api_version(release_channel)?;
+
             let extension =
                 latest::Extension::instantiate_async(store, component, latest::linker())
                     .await
                     .context("failed to instantiate wasm extension")?;
+            Ok(Self::V030(extension))
+        } else if version >= since_v0_2_0::MIN_VERSION {
+            let extension = since_v0_2_0::Extension::instantiate_async(
+                store,
+                component,
+                since_v0_2_0::linker(),
+            )
+            .await
+            .context("failed to instantiate wasm extension")?;
             Ok(Self::V020(extension))
         } else if version >= since_v0_1_0::MIN_VERSION {
             let extension = since_v0_1_0::Extension::instantiate_async(
@@ -152,6 +164,7 @@ impl Extension {
 
     pub async fn call_init_extension(&self, store: &mut Store<WasmState>) -> Result<()> {
         match self {
+            Extension::V030(ext) => ext.call_init_extension(store).await,
             Extension::V020(ext) => ext.call_init_extension(store).await,
             Extension::V010(ext) => ext.call_init_extension(store).await,
             Extension::V006(ext) => ext.call_init_extension(store).await,
@@ -168,10 +181,14 @@ impl Extension {
         resource: Resource<Arc<dyn WorktreeDelegate>>,
     ) -> Result<Result<Command, String>> {
         match self {
-            Extension::V020(ext) => {
+            Extension::V030(ext) => {
                 ext.call_language_server_command(store, &language_server_id.0, resource)
                     .await
             }
+            Extension::V020(ext) => Ok(ext
+                .call_language_server_command(store, &language_server_id.0, resource)
+                .await?
+                .map(|command| command.into())),
             Extension::V010(ext) => Ok(ext
                 .call_language_server_command(store, &language_server_id.0, resource)
                 .await?
@@ -214,6 +231,14 @@ impl Extension {
         resource: Resource<Arc<dyn WorktreeDelegate>>,
     ) -> Result<Result<Option<String>, String>> {
         match self {
+            Extension::V030(ext) => {
+                ext.call_language_server_initialization_options(
+                    store,
+                    &language_server_id.0,
+                    resource,
+                )
+                .await
+            }
             Extension::V020(ext) => {
                 ext.call_language_server_initialization_options(
                     store,
@@ -271,6 +296,14 @@ impl Extension {
         resource: Resource<Arc<dyn WorktreeDelegate>>,
     ) -> Result<Result<Option<String>, String>> {
         match self {
+            Extension::V030(ext) => {
+                ext.call_language_server_workspace_configuration(
+                    store,
+                    &language_server_id.0,
+                    resource,
+                )
+                .await
+            }
             Extension::V020(ext) => {
                 ext.call_language_server_workspace_configuration(
                     store,
@@ -306,10 +339,19 @@ impl Extension {
         completions: Vec<latest::Completion>,
     ) -> Result<Result<Vec<Option<CodeLabel>>, String>> {
         match self {
-            Extension::V020(ext) => {
+            Extension::V030(ext) => {
                 ext.call_labels_for_completions(store, &language_server_id.0, &completions)
                     .await
             }
+            Extension::V020(ext) => Ok(ext
+                .call_labels_for_completions(store, &language_server_id.0, &completions)
+                .await?
+                .map(|labels| {
+                    labels
+                        .into_iter()
+                        .map(|label| label.map(Into::into))
+                        .collect()
+                })),
             Extension::V010(ext) => Ok(ext
                 .call_labels_for_completions(
                     store,
@@ -347,10 +389,19 @@ impl Extension {
         symbols: Vec<latest::Symbol>,
     ) -> Result<Result<Vec<Option<CodeLabel>>, String>> {
         match self {
-            Extension::V020(ext) => {
+            Extension::V030(ext) => {
                 ext.call_labels_for_symbols(store, &language_server_id.0, &symbols)
                     .await
             }
+            Extension::V020(ext) => Ok(ext
+                .call_labels_for_symbols(store, &language_server_id.0, &symbols)
+                .await?
+                .map(|labels| {
+                    labels
+                        .into_iter()
+                        .map(|label| label.map(Into::into))
+                        .collect()
+                })),
             Extension::V010(ext) => Ok(ext
                 .call_labels_for_symbols(
                     store,
@@ -388,6 +439,10 @@ impl Extension {
         arguments: &[String],
     ) -> Result<Result<Vec<SlashCommandArgumentCompletion>, String>> {
         match self {
+            Extension::V030(ext) => {
+                ext.call_complete_slash_command_argument(store, command, arguments)
+                    .await
+            }
             Extension::V020(ext) => {
                 ext.call_complete_slash_command_argument(store, command, arguments)
                     .await
@@ -408,6 +463,10 @@ impl Extension {
         resource: Option<Resource<Arc<dyn WorktreeDelegate>>>,
     ) -> Result<Result<SlashCommandOutput, String>> {
         match self {
+            Extension::V030(ext) => {
+                ext.call_run_slash_command(store, command, arguments, resource)
+                    .await
+            }
             Extension::V020(ext) => {
                 ext.call_run_slash_command(store, command, arguments, resource)
                     .await
@@ -422,6 +481,57 @@ impl Extension {
         }
     }
 
+    pub async fn call_language_task_templates(
+        &self,
+        store: &mut Store<WasmState>,
+        language_name: &str,
+    ) -> Result<Result<Vec<TaskTemplate>, String>> {
+        match self {
+            Extension::V030(ext) => ext.call_language_task_templates(store, language_name).await,
+            Extension::V001(_)
+            | Extension::V004(_)
+            | Extension::V006(_)
+            | Extension::V010(_)
+            | Extension::V020(_) => Ok(Ok(Vec::new())),
+        }
+    }
+
+    pub async fn call_language_task_variables(
+        &self,
+        store: &mut Store<WasmState>,
+        language_name: &str,
+        variables: &[(String, String)],
+    ) -> Result<Result<Vec<(String, String)>, String>> {
+        match self {
+            Extension::V030(ext) => {
+                ext.call_language_task_variables(store, language_name, variables)
+                    .await
+            }
+            Extension::V001(_)
+            | Extension::V004(_)
+            | Extension::V006(_)
+            | Extension::V010(_)
+            | Extension::V020(_) => Ok(Ok(Vec::new())),
+        }
+    }
+
+    pub async fn call_run_tool(
+        &self,
+        store: &mut Store<WasmState>,
+        tool: &Tool,
+        input: &str,
+        project: Resource<ExtensionProject>,
+    ) -> Result<Result<String, String>> {
+        match self {
+            Extension::V030(ext) => ext.call_run_tool(store, tool, input, project).await,
+            Extension::V001(_)
+            | Extension::V004(_)
+            | Extension::V006(_)
+            | Extension::V010(_)
+            | Extension::V020(_) => Err(anyhow!("`run_tool` not available prior to v0.3.0")),
+        }
+    }
+
     pub async fn call_context_server_command(
         &self,
         store: &mut Store<WasmState>,
@@ -429,10 +539,14 @@ impl Extension {
         project: Resource<ExtensionProject>,
     ) -> Result<Result<Command, String>> {
         match self {
-            Extension::V020(ext) => {
+            Extension::V030(ext) => {
                 ext.call_context_server_command(store, &context_server_id, project)
                     .await
             }
+            Extension::V020(ext) => Ok(ext
+                .call_context_server_command(store, &context_server_id, project)
+                .await?
+                .map(|command| command.into())),
             Extension::V001(_) | Extension::V004(_) | Extension::V006(_) | Extension::V010(_) => {
                 Err(anyhow!(
                     "`context_server_command` not available prior to v0.2.0"
@@ -447,6 +561,7 @@ impl Extension {
         provider: &str,
     ) -> Result<Result<Vec<String>, String>> {
         match self {
+            Extension::V030(ext) => ext.call_suggest_docs_packages(store, provider).await,
             Extension::V020(ext) => ext.call_suggest_docs_packages(store, provider).await,
             Extension::V010(ext) => ext.call_suggest_docs_packages(store, provider).await,
             Extension::V001(_) | Extension::V004(_) | Extension::V006(_) => Err(anyhow!(
@@ -463,6 +578,10 @@ impl Extension {
         kv_store: Resource<Arc<dyn KeyValueStoreDelegate>>,
     ) -> Result<Result<(), String>> {
         match self {
+            Extension::V030(ext) => {
+                ext.call_index_docs(store, provider, package_name, kv_store)
+                    .await
+            }
             Extension::V020(ext) => {
                 ext.call_index_docs(store, provider, package_name, kv_store)
                     .await
@@ -487,3 +606,27 @@ impl<T> ToWasmtimeResult<T> for Result<T> {
         Ok(self.map_err(|error| error.to_string()))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_v0_3_0_is_only_supported_on_development_channels() {
+        let v0_2_0 = SemanticVersion::new(0, 2, 0);
+        let v0_3_0 = SemanticVersion::new(0, 3, 0);
+
+        for channel in [ReleaseChannel::Dev, ReleaseChannel::Nightly] {
+            assert!(is_supported_wasm_api_version(channel, v0_2_0));
+            assert!(is_supported_wasm_api_version(channel, v0_3_0));
+        }
+        for channel in [ReleaseChannel::Preview, ReleaseChannel::Stable] {
+            assert!(is_supported_wasm_api_version(channel, v0_2_0));
+            assert!(!is_supported_wasm_api_version(channel, v0_3_0));
+        }
+        assert!(!is_supported_wasm_api_version(
+            ReleaseChannel::Dev,
+            SemanticVersion::new(0, 4, 0)
+        ));
+    }
+}
@@ -4,6 +4,8 @@ use collections::HashMap;
 
 use gpui::{AppContext, AssetSource, Global, SharedString};
 use serde_derive::Deserialize;
+use settings::SettingsStore;
+use theme::{IconTheme, ThemeSettings};
 use util::{maybe, paths::PathExt};
 
 #[derive(Deserialize, Debug)]
@@ -48,7 +50,19 @@ impl FileIcons {
             })
     }
 
+    /// Returns the active icon theme, if one other than the bundled file icons is selected.
+    fn active_icon_theme(cx: &AppContext) -> Option<&IconTheme> {
+        cx.try_global::<SettingsStore>()?
+            .try_get::<ThemeSettings>(None)?
+            .active_icon_theme
+            .as_deref()
+    }
+
     pub fn get_icon(path: &Path, cx: &AppContext) -> Option<SharedString> {
+        if let Some(icon) = Self::active_icon_theme(cx).and_then(|theme| theme.file_icon(path)) {
+            return Some(icon);
+        }
+
         let this = cx.try_global::<Self>()?;
 
         // FIXME: Associate a type with the languages and have the file's language
@@ -73,7 +87,13 @@ impl FileIcons {
             .map(|type_config| type_config.icon.clone())
     }
 
-    pub fn get_folder_icon(expanded: bool, cx: &AppContext) -> Option<SharedString> {
+    pub fn get_folder_icon(expanded: bool, path: &Path, cx: &AppContext) -> Option<SharedString> {
+        if let Some(icon) =
+            Self::active_icon_theme(cx).and_then(|theme| theme.directory_icon(path, expanded))
+        {
+            return Some(icon);
+        }
+
         let this = cx.try_global::<Self>()?;
 
         let key = if expanded {
@@ -86,6 +106,12 @@ impl FileIcons {
     }
 
     pub fn get_chevron_icon(expanded: bool, cx: &AppContext) -> Option<SharedString> {
+        if let Some(icon) =
+            Self::active_icon_theme(cx).and_then(|theme| theme.chevron_icon(expanded))
+        {
+            return Some(icon);
+        }
+
         let this = cx.try_global::<Self>()?;
 
         let key = if expanded {
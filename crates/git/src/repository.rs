@@ -30,6 +30,12 @@ pub trait GitRepository: Send + Sync {
     /// Note that for symlink entries, this will return the contents of the symlink, not the target.
     fn load_index_text(&self, relative_file_path: &Path) -> Option<String>;
 
+    /// Loads a git repository entry's contents as bytes, as they are staged in the index.
+    fn load_index_bytes(&self, relative_file_path: &Path) -> Option<Vec<u8>>;
+
+    /// Loads a git repository entry's contents as bytes, as of the HEAD commit.
+    fn load_head_bytes(&self, relative_file_path: &Path) -> Option<Vec<u8>>;
+
     /// Returns the URL of the remote with the given name.
     fn remote_url(&self, name: &str) -> Option<String>;
     fn branch_name(&self) -> Option<String>;
@@ -79,6 +85,21 @@ impl RealGitRepository {
 // https://git-scm.com/book/en/v2/Git-Internals-Git-Objects
 const GIT_MODE_SYMLINK: u32 = 0o120000;
 
+fn load_index_blob(repo: &git2::Repository, relative_file_path: &Path) -> Result<Option<Vec<u8>>> {
+    const STAGE_NORMAL: i32 = 0;
+    let index = repo.index()?;
+
+    // This check is required because index.get_path() unwraps internally :(
+    check_path_to_repo_path_errors(relative_file_path)?;
+
+    let oid = match index.get_path(relative_file_path, STAGE_NORMAL) {
+        Some(entry) if entry.mode != GIT_MODE_SYMLINK => entry.id,
+        _ => return Ok(None),
+    };
+
+    Ok(Some(repo.find_blob(oid)?.content().to_owned()))
+}
+
 impl GitRepository for RealGitRepository {
     fn reload_index(&self) {
         if let Ok(mut index) = self.repository.lock().index() {
@@ -93,18 +114,9 @@ impl GitRepository for RealGitRepository {
 
     fn load_index_text(&self, relative_file_path: &Path) -> Option<String> {
         fn logic(repo: &git2::Repository, relative_file_path: &Path) -> Result<Option<String>> {
-            const STAGE_NORMAL: i32 = 0;
-            let index = repo.index()?;
-
-            // This check is required because index.get_path() unwraps internally :(
-            check_path_to_repo_path_errors(relative_file_path)?;
-
-            let oid = match index.get_path(relative_file_path, STAGE_NORMAL) {
-                Some(entry) if entry.mode != GIT_MODE_SYMLINK => entry.id,
-                _ => return Ok(None),
+            let Some(content) = load_index_blob(repo, relative_file_path)? else {
+                return Ok(None);
             };
-
-            let content = repo.find_blob(oid)?.content().to_owned();
             Ok(Some(String::from_utf8(content)?))
         }
 
@@ -115,6 +127,43 @@ impl GitRepository for RealGitRepository {
         None
     }
 
+    fn load_index_bytes(&self, relative_file_path: &Path) -> Option<Vec<u8>> {
+        match load_index_blob(&self.repository.lock(), relative_file_path) {
+            Ok(value) => value,
+            Err(err) => {
+                log::error!("Error loading index contents: {:?}", err);
+                None
+            }
+        }
+    }
+
+    fn load_head_bytes(&self, relative_file_path: &Path) -> Option<Vec<u8>> {
+        fn logic(repo: &git2::Repository, relative_file_path: &Path) -> Result<Option<Vec<u8>>> {
+            check_path_to_repo_path_errors(relative_file_path)?;
+
+            let tree = repo.head()?.peel_to_tree()?;
+            let entry = match tree.get_path(relative_file_path) {
+                Ok(entry) => entry,
+                Err(err) if err.code() == git2::ErrorCode::NotFound => return Ok(None),
+                Err(err) => return Err(err.into()),
+            };
+            if entry.filemode() == GIT_MODE_SYMLINK as i32 {
+                return Ok(None);
+            }
+
+            let content = repo.find_blob(entry.id())?.content().to_owned();
+            Ok(Some(content))
+        }
+
+        match logic(&self.repository.lock(), relative_file_path) {
+            Ok(value) => value,
+            Err(err) => {
+                log::error!("Error loading HEAD contents: {:?}", err);
+                None
+            }
+        }
+    }
+
     fn remote_url(&self, name: &str) -> Option<String> {
         let repo = self.repository.lock();
         let remote = repo.find_remote(name).ok()?;
@@ -237,6 +286,7 @@ pub struct FakeGitRepositoryState {
     pub dot_git_dir: PathBuf,
     pub event_emitter: smol::channel::Sender<PathBuf>,
     pub index_contents: HashMap<PathBuf, String>,
+    pub head_contents: HashMap<PathBuf, String>,
     pub blames: HashMap<PathBuf, Blame>,
     pub worktree_statuses: HashMap<RepoPath, GitFileStatus>,
     pub current_branch_name: Option<String>,
@@ -255,6 +305,7 @@ impl FakeGitRepositoryState {
             dot_git_dir,
             event_emitter,
             index_contents: Default::default(),
+            head_contents: Default::default(),
             blames: Default::default(),
             worktree_statuses: Default::default(),
             current_branch_name: Default::default(),
@@ -271,6 +322,22 @@ impl GitRepository for FakeGitRepository {
         state.index_contents.get(path).cloned()
     }
 
+    fn load_index_bytes(&self, path: &Path) -> Option<Vec<u8>> {
+        let state = self.state.lock();
+        state
+            .index_contents
+            .get(path)
+            .map(|content| content.clone().into_bytes())
+    }
+
+    fn load_head_bytes(&self, path: &Path) -> Option<Vec<u8>> {
+        let state = self.state.lock();
+        state
+            .head_contents
+            .get(path)
+            .map(|content| content.clone().into_bytes())
+    }
+
     fn remote_url(&self, _name: &str) -> Option<String> {
         None
     }
@@ -15,6 +15,7 @@ use util::{ResultExt, TryFutureExt};
 
 use db::kvp::KEY_VALUE_STORE;
 use gpui::*;
+use image_viewer::is_diffable_image;
 use project::{Entry, EntryKind, Fs, Project, ProjectEntryId, WorktreeId};
 use serde::{Deserialize, Serialize};
 use settings::Settings as _;
@@ -54,6 +55,7 @@ struct EntryDetails {
     filename: String,
     display_name: String,
     path: Arc<Path>,
+    abs_path: PathBuf,
     kind: EntryKind,
     depth: usize,
     is_expanded: bool,
@@ -368,6 +370,7 @@ impl GitPanel {
                         kind: entry.kind,
                         is_expanded,
                         path: entry.path.clone(),
+                        abs_path: snapshot.abs_path().join(&entry.path),
                         status,
                         depth,
                     };
@@ -659,6 +662,8 @@ impl GitPanel {
         let id = id.to_proto() as usize;
         let checkbox_id = ElementId::Name(format!("checkbox_{}", id).into());
         let is_staged = ToggleState::Selected;
+        let has_image_diff =
+            !details.is_dir() && details.status.is_some() && is_diffable_image(&details.path);
 
         h_flex()
             .id(id)
@@ -677,6 +682,19 @@ impl GitPanel {
                 this.child(git_status_icon(status))
             })
             .child(h_flex().gap_1p5().child(details.display_name.clone()))
+            .when(has_image_diff, |this| {
+                let abs_path = details.abs_path.clone();
+                this.cursor_pointer()
+                    .tooltip(|cx| Tooltip::text("Open Image Diff", cx))
+                    .on_click(move |_, cx| {
+                        cx.dispatch_action(
+                            workspace::OpenImageDiff {
+                                abs_path: abs_path.clone(),
+                            }
+                            .boxed_clone(),
+                        )
+                    })
+            })
     }
 }
 
@@ -255,7 +255,7 @@ impl Settings for LineIndicatorFormat {
         sources: SettingsSources<Self::FileContent>,
         _: &mut AppContext,
     ) -> anyhow::Result<Self> {
-        let format = [sources.release_channel, sources.user]
+        let format = [sources.profile, sources.release_channel, sources.user]
             .into_iter()
             .find_map(|value| value.copied().flatten())
             .unwrap_or(sources.default.ok_or_else(Self::missing_default)?);
@@ -0,0 +1,633 @@
+use crate::{Bounds, ClickListener, FocusId, GlobalElementId, Pixels, SharedString};
+use collections::FxHashMap;
+use smallvec::SmallVec;
+use std::{
+    fmt::{self, Write as _},
+    hash::{Hash, Hasher},
+    ops::Range,
+};
+
+/// The semantic role of an element, as reported to assistive technologies.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum AccessibilityRole {
+    /// A container with no semantics of its own, used to group related elements.
+    Group,
+    /// A clickable button.
+    Button,
+    /// A button that toggles between a pressed and an unpressed state.
+    ToggleButton,
+    /// A checkbox.
+    Checkbox,
+    /// A radio button, one of a mutually exclusive set.
+    RadioButton,
+    /// A modal or non-modal dialog.
+    Dialog,
+    /// A section heading.
+    Heading,
+    /// An image or icon.
+    Image,
+    /// Static text.
+    Label,
+    /// A hyperlink.
+    Link,
+    /// A list of items.
+    List,
+    /// An item in a [AccessibilityRole::List].
+    ListItem,
+    /// A menu, such as a context menu.
+    Menu,
+    /// An item in a [AccessibilityRole::Menu].
+    MenuItem,
+    /// A progress indicator.
+    ProgressBar,
+    /// A scrollable region.
+    ScrollArea,
+    /// A separator between groups of elements.
+    Separator,
+    /// A control for picking a value from a range.
+    Slider,
+    /// A tab in a [AccessibilityRole::TabList].
+    Tab,
+    /// A list of tabs.
+    TabList,
+    /// An editable text field.
+    TextInput,
+    /// A toolbar.
+    Toolbar,
+    /// A tooltip.
+    Tooltip,
+    /// A hierarchical list of items.
+    Tree,
+    /// An item in a [AccessibilityRole::Tree].
+    TreeItem,
+}
+
+impl AccessibilityRole {
+    /// A human readable name for this role, used when printing accessibility trees.
+    pub fn name(&self) -> &'static str {
+        match self {
+            AccessibilityRole::Group => "group",
+            AccessibilityRole::Button => "button",
+            AccessibilityRole::ToggleButton => "toggle button",
+            AccessibilityRole::Checkbox => "checkbox",
+            AccessibilityRole::RadioButton => "radio button",
+            AccessibilityRole::Dialog => "dialog",
+            AccessibilityRole::Heading => "heading",
+            AccessibilityRole::Image => "image",
+            AccessibilityRole::Label => "label",
+            AccessibilityRole::Link => "link",
+            AccessibilityRole::List => "list",
+            AccessibilityRole::ListItem => "list item",
+            AccessibilityRole::Menu => "menu",
+            AccessibilityRole::MenuItem => "menu item",
+            AccessibilityRole::ProgressBar => "progress bar",
+            AccessibilityRole::ScrollArea => "scroll area",
+            AccessibilityRole::Separator => "separator",
+            AccessibilityRole::Slider => "slider",
+            AccessibilityRole::Tab => "tab",
+            AccessibilityRole::TabList => "tab list",
+            AccessibilityRole::TextInput => "text input",
+            AccessibilityRole::Toolbar => "toolbar",
+            AccessibilityRole::Tooltip => "tooltip",
+            AccessibilityRole::Tree => "tree",
+            AccessibilityRole::TreeItem => "tree item",
+        }
+    }
+}
+
+/// An action that assistive technologies can perform on an element.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum AccessibilityAction {
+    /// Activate the element by invoking its click handlers.
+    Click,
+    /// Move keyboard focus to the element.
+    Focus,
+    /// Expand a collapsed element, such as a tree item. Delivered to the element's click handlers.
+    Expand,
+    /// Collapse an expanded element. Delivered to the element's click handlers.
+    Collapse,
+}
+
+impl AccessibilityAction {
+    /// A stable name for this action, as reported to assistive technologies.
+    pub fn name(&self) -> &'static str {
+        match self {
+            AccessibilityAction::Click => "click",
+            AccessibilityAction::Focus => "focus",
+            AccessibilityAction::Expand => "expand",
+            AccessibilityAction::Collapse => "collapse",
+        }
+    }
+}
+
+/// The accessibility information attached to an element through
+/// [crate::InteractiveElement::accessibility_role] and related methods.
+#[derive(Clone, Debug, PartialEq)]
+pub struct AccessibilityProperties {
+    /// The role of the element.
+    pub role: AccessibilityRole,
+    /// The name announced for the element, e.g. a button's label.
+    pub name: Option<SharedString>,
+    /// The current value of the element, e.g. the contents of a text input.
+    pub value: Option<SharedString>,
+    /// Whether the element is toggled, checked or selected.
+    pub selected: Option<bool>,
+    /// Whether the element is expanded, for elements that can be collapsed.
+    pub expanded: Option<bool>,
+    /// Whether the element is disabled.
+    pub disabled: bool,
+    /// The actions that can be performed on the element, in addition to the ones
+    /// implied by its interactivity.
+    pub actions: SmallVec<[AccessibilityAction; 2]>,
+}
+
+impl AccessibilityProperties {
+    /// Create accessibility properties with the given role.
+    pub fn new(role: AccessibilityRole) -> Self {
+        Self {
+            role,
+            name: None,
+            value: None,
+            selected: None,
+            expanded: None,
+            disabled: false,
+            actions: SmallVec::new(),
+        }
+    }
+}
+
+/// Uniquely identifies a node in an [AccessibilityTree]. Nodes for elements
+/// with an [crate::ElementId] keep the same id across frames.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct AccessibilityNodeId(pub u64);
+
+/// A node in the accessibility tree of a window.
+#[derive(Clone, Debug, PartialEq)]
+pub struct AccessibilityNode {
+    /// The id of this node.
+    pub id: AccessibilityNodeId,
+    /// The parent of this node, or `None` if it's a root.
+    pub parent: Option<AccessibilityNodeId>,
+    /// The children of this node, in paint order.
+    pub children: Vec<AccessibilityNodeId>,
+    /// The semantic properties of this node.
+    pub properties: AccessibilityProperties,
+    /// The window-relative bounds of the element.
+    pub bounds: Bounds<Pixels>,
+    /// Whether the element can receive keyboard focus.
+    pub focusable: bool,
+    /// Whether the element is currently focused.
+    pub focused: bool,
+    /// Every action that can be performed on this node.
+    pub actions: SmallVec<[AccessibilityAction; 2]>,
+    pub(crate) focus_id: Option<FocusId>,
+}
+
+#[derive(Clone, Debug)]
+pub(crate) enum AccessibilityOperation {
+    StartNode(AccessibilityNodeRequest),
+    EndNode,
+}
+
+#[derive(Clone)]
+pub(crate) struct AccessibilityNodeRequest {
+    pub(crate) stable_id: Option<u64>,
+    pub(crate) properties: AccessibilityProperties,
+    pub(crate) bounds: Bounds<Pixels>,
+    pub(crate) focus_id: Option<FocusId>,
+    /// The element's own click listeners, which activating the node invokes directly rather
+    /// than simulating a click that could land on an element drawn on top of it.
+    pub(crate) click_listeners: Vec<ClickListener>,
+}
+
+impl fmt::Debug for AccessibilityNodeRequest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AccessibilityNodeRequest")
+            .field("stable_id", &self.stable_id)
+            .field("properties", &self.properties)
+            .field("bounds", &self.bounds)
+            .field("focus_id", &self.focus_id)
+            .field("click_listeners", &self.click_listeners.len())
+            .finish()
+    }
+}
+
+impl AccessibilityNodeRequest {
+    pub(crate) fn new(
+        global_id: Option<&GlobalElementId>,
+        properties: AccessibilityProperties,
+        bounds: Bounds<Pixels>,
+        focus_id: Option<FocusId>,
+        click_listeners: Vec<ClickListener>,
+    ) -> Self {
+        Self {
+            stable_id: global_id.map(|global_id| {
+                let mut hasher = collections::FxHasher::default();
+                global_id.hash(&mut hasher);
+                hasher.finish()
+            }),
+            properties,
+            bounds,
+            focus_id,
+            click_listeners,
+        }
+    }
+}
+
+/// Records the accessibility nodes painted during a frame, so that the ranges
+/// painted by cached views can be replayed into the next frame like the [crate::Scene].
+#[derive(Default)]
+pub(crate) struct AccessibilityOperations {
+    operations: Vec<AccessibilityOperation>,
+}
+
+impl AccessibilityOperations {
+    pub(crate) fn clear(&mut self) {
+        self.operations.clear();
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.operations.len()
+    }
+
+    pub(crate) fn start_node(&mut self, request: AccessibilityNodeRequest) {
+        self.operations
+            .push(AccessibilityOperation::StartNode(request));
+    }
+
+    pub(crate) fn end_node(&mut self) {
+        self.operations.push(AccessibilityOperation::EndNode);
+    }
+
+    pub(crate) fn replay(&mut self, range: Range<usize>, previous: &AccessibilityOperations) {
+        self.operations
+            .extend(previous.operations[range].iter().cloned());
+    }
+
+    /// Build a tree out of the recorded operations.
+    pub(crate) fn build_tree(&self, focus: Option<FocusId>) -> AccessibilityTree {
+        self.build(focus).0
+    }
+
+    /// Returns the click listeners of the element that painted the node with the given id.
+    pub(crate) fn click_listeners(&self, node_id: AccessibilityNodeId) -> Vec<ClickListener> {
+        let (_, requests) = self.build(None);
+        requests
+            .get(&node_id)
+            .and_then(|ix| match &self.operations[*ix] {
+                AccessibilityOperation::StartNode(request) => Some(request.click_listeners.clone()),
+                AccessibilityOperation::EndNode => None,
+            })
+            .unwrap_or_default()
+    }
+
+    /// Builds the tree, along with the index of the operation that started each node.
+    fn build(
+        &self,
+        focus: Option<FocusId>,
+    ) -> (AccessibilityTree, FxHashMap<AccessibilityNodeId, usize>) {
+        let mut tree = AccessibilityTree::default();
+        let mut requests = FxHashMap::default();
+        let mut stack: Vec<AccessibilityNodeId> = Vec::new();
+        for (ix, operation) in self.operations.iter().enumerate() {
+            match operation {
+                AccessibilityOperation::StartNode(request) => {
+                    let parent = stack.last().copied();
+                    let sibling_index = match parent {
+                        Some(parent) => tree.nodes[&parent].children.len(),
+                        None => tree.roots.len(),
+                    };
+                    let mut id = AccessibilityNodeId(request.stable_id.unwrap_or_else(|| {
+                        let mut hasher = collections::FxHasher::default();
+                        parent.hash(&mut hasher);
+                        sibling_index.hash(&mut hasher);
+                        hasher.finish()
+                    }));
+                    // Elements can share an id when they're rendered in several places,
+                    // so disambiguate them by their position instead.
+                    while tree.nodes.contains_key(&id) {
+                        id.0 = id.0.wrapping_mul(31).wrapping_add(sibling_index as u64 + 1);
+                    }
+
+                    let mut actions = SmallVec::new();
+                    if !request.click_listeners.is_empty() {
+                        actions.push(AccessibilityAction::Click);
+                    }
+                    if request.focus_id.is_some() {
+                        actions.push(AccessibilityAction::Focus);
+                    }
+                    for action in &request.properties.actions {
+                        if !actions.contains(action) {
+                            actions.push(*action);
+                        }
+                    }
+
+                    tree.nodes.insert(
+                        id,
+                        AccessibilityNode {
+                            id,
+                            parent,
+                            children: Vec::new(),
+                            properties: request.properties.clone(),
+                            bounds: request.bounds,
+                            focusable: request.focus_id.is_some(),
+                            focused: request.focus_id.is_some() && request.focus_id == focus,
+                            actions,
+                            focus_id: request.focus_id,
+                        },
+                    );
+                    requests.insert(id, ix);
+                    match parent {
+                        Some(parent) => tree.nodes.get_mut(&parent).unwrap().children.push(id),
+                        None => tree.roots.push(id),
+                    }
+                    stack.push(id);
+                }
+                AccessibilityOperation::EndNode => {
+                    stack.pop();
+                }
+            }
+        }
+        (tree, requests)
+    }
+}
+
+/// The accessibility tree of a window, built from the elements painted in a frame.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct AccessibilityTree {
+    roots: Vec<AccessibilityNodeId>,
+    nodes: FxHashMap<AccessibilityNodeId, AccessibilityNode>,
+}
+
+impl AccessibilityTree {
+    /// The top-level nodes of the tree, in paint order.
+    pub fn roots(&self) -> impl Iterator<Item = &AccessibilityNode> {
+        self.roots.iter().map(|id| &self.nodes[id])
+    }
+
+    /// Look up a node by its id.
+    pub fn node(&self, id: AccessibilityNodeId) -> Option<&AccessibilityNode> {
+        self.nodes.get(&id)
+    }
+
+    /// The children of the given node, in paint order.
+    pub fn children(&self, id: AccessibilityNodeId) -> impl Iterator<Item = &AccessibilityNode> {
+        self.nodes
+            .get(&id)
+            .into_iter()
+            .flat_map(|node| node.children.iter().map(|child| &self.nodes[child]))
+    }
+
+    /// Iterate over every node in the tree, in no particular order.
+    pub fn nodes(&self) -> impl Iterator<Item = &AccessibilityNode> {
+        self.nodes.values()
+    }
+
+    /// The number of nodes in the tree.
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Whether the tree has no nodes.
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// Find the first node, in depth-first order, with the given role and name.
+    pub fn find(&self, role: AccessibilityRole, name: &str) -> Option<&AccessibilityNode> {
+        let mut stack = self.roots.iter().rev().copied().collect::<Vec<_>>();
+        while let Some(id) = stack.pop() {
+            let node = &self.nodes[&id];
+            if node.properties.role == role && node.properties.name.as_deref() == Some(name) {
+                return Some(node);
+            }
+            stack.extend(node.children.iter().rev().copied());
+        }
+        None
+    }
+
+    fn write_node(
+        &self,
+        f: &mut fmt::Formatter,
+        id: AccessibilityNodeId,
+        depth: usize,
+    ) -> fmt::Result {
+        let node = &self.nodes[&id];
+        let properties = &node.properties;
+        f.write_str(&"    ".repeat(depth))?;
+        f.write_str(properties.role.name())?;
+        if let Some(name) = &properties.name {
+            write!(f, " {:?}", name.as_ref())?;
+        }
+        if let Some(value) = &properties.value {
+            write!(f, " = {:?}", value.as_ref())?;
+        }
+        let mut states = String::new();
+        let mut push_state = |state: &str| {
+            if !states.is_empty() {
+                states.push_str(", ");
+            }
+            states.push_str(state);
+        };
+        if node.focused {
+            push_state("focused");
+        } else if node.focusable {
+            push_state("focusable");
+        }
+        match properties.selected {
+            Some(true) => push_state("selected"),
+            Some(false) => push_state("not selected"),
+            None => {}
+        }
+        match properties.expanded {
+            Some(true) => push_state("expanded"),
+            Some(false) => push_state("collapsed"),
+            None => {}
+        }
+        if properties.disabled {
+            push_state("disabled");
+        }
+        if !states.is_empty() {
+            write!(f, " ({states})")?;
+        }
+        f.write_char('\n')?;
+        for child in &node.children {
+            self.write_node(f, *child, depth + 1)?;
+        }
+        Ok(())
+    }
+}
+
+/// Prints the tree as an indented outline, one node per line, which makes it
+/// convenient to assert against in tests.
+impl fmt::Display for AccessibilityTree {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for root in &self.roots {
+            self.write_node(f, *root, 0)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        self as gpui, div, px, FocusHandle, InteractiveElement, IntoElement, ParentElement, Render,
+        StatefulInteractiveElement, Styled, TestAppContext, ViewContext,
+    };
+    use std::rc::Rc;
+
+    fn request(
+        role: AccessibilityRole,
+        name: &str,
+        stable_id: Option<u64>,
+    ) -> AccessibilityNodeRequest {
+        let mut properties = AccessibilityProperties::new(role);
+        properties.name = Some(name.to_string().into());
+        AccessibilityNodeRequest {
+            stable_id,
+            properties,
+            bounds: Bounds::default(),
+            focus_id: None,
+            click_listeners: if role == AccessibilityRole::Button {
+                vec![Rc::new(|_, _| {})]
+            } else {
+                Vec::new()
+            },
+        }
+    }
+
+    #[test]
+    fn test_build_tree() {
+        let mut operations = AccessibilityOperations::default();
+        operations.start_node(request(AccessibilityRole::Toolbar, "Tools", Some(1)));
+        operations.start_node(request(AccessibilityRole::Button, "Save", None));
+        operations.end_node();
+        operations.start_node(request(AccessibilityRole::Button, "Open", None));
+        operations.end_node();
+        operations.end_node();
+        operations.start_node(request(AccessibilityRole::Label, "Ready", None));
+        operations.end_node();
+
+        let tree = operations.build_tree(None);
+        assert_eq!(
+            tree.to_string(),
+            concat!(
+                "toolbar \"Tools\"\n",
+                "    button \"Save\"\n",
+                "    button \"Open\"\n",
+                "label \"Ready\"\n",
+            )
+        );
+
+        let toolbar = tree.roots().next().unwrap();
+        assert_eq!(toolbar.id, AccessibilityNodeId(1));
+        let save = tree.find(AccessibilityRole::Button, "Save").unwrap();
+        assert_eq!(save.parent, Some(toolbar.id));
+        assert_eq!(save.actions.as_slice(), &[AccessibilityAction::Click]);
+
+        // Replaying the operations yields the same ids, so that assistive
+        // technologies can keep track of nodes across frames.
+        let mut next_operations = AccessibilityOperations::default();
+        next_operations.replay(0..operations.len(), &operations);
+        assert_eq!(next_operations.build_tree(None), tree);
+    }
+
+    struct Toolbar {
+        clicks: usize,
+        focus_handle: FocusHandle,
+    }
+
+    impl Render for Toolbar {
+        fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+            div()
+                .id("toolbar")
+                .accessibility_role(AccessibilityRole::Toolbar)
+                .accessibility_name("Tools")
+                .child(
+                    div()
+                        .id("save")
+                        .size_10()
+                        .accessibility_role(AccessibilityRole::Button)
+                        .accessibility_name("Save")
+                        .track_focus(&self.focus_handle)
+                        .on_click(cx.listener(|this, _, _| this.clicks += 1)),
+                )
+                // Elements without a role don't appear in the tree.
+                .child(div().child("Ready"))
+        }
+    }
+
+    #[gpui::test]
+    fn test_element_accessibility(cx: &mut TestAppContext) {
+        let (view, cx) = cx.add_window_view(|cx| Toolbar {
+            clicks: 0,
+            focus_handle: cx.focus_handle(),
+        });
+
+        let tree = cx.accessibility_tree();
+        assert_eq!(
+            tree.to_string(),
+            concat!("toolbar \"Tools\"\n", "    button \"Save\" (focusable)\n")
+        );
+
+        let save = tree.find(AccessibilityRole::Button, "Save").unwrap().id;
+        cx.update(|cx| cx.perform_accessibility_action(save, AccessibilityAction::Focus));
+        assert_eq!(
+            cx.accessibility_tree().to_string(),
+            concat!("toolbar \"Tools\"\n", "    button \"Save\" (focused)\n")
+        );
+
+        cx.update(|cx| cx.perform_accessibility_action(save, AccessibilityAction::Click));
+        view.update(cx, |view, _| assert_eq!(view.clicks, 1));
+    }
+
+    struct CoveredButton {
+        button_clicks: usize,
+        overlay_clicks: usize,
+    }
+
+    impl Render for CoveredButton {
+        fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+            div()
+                .size_full()
+                .child(
+                    div()
+                        .id("button")
+                        .size(px(20.))
+                        .accessibility_role(AccessibilityRole::Button)
+                        .accessibility_name("Run")
+                        .on_click(cx.listener(|this, _, _| this.button_clicks += 1)),
+                )
+                .child(
+                    div()
+                        .id("overlay")
+                        .absolute()
+                        .top_0()
+                        .left_0()
+                        .size(px(40.))
+                        .on_click(cx.listener(|this, _, _| this.overlay_clicks += 1)),
+                )
+        }
+    }
+
+    #[gpui::test]
+    fn test_click_action_ignores_overlapping_elements(cx: &mut TestAppContext) {
+        let (view, cx) = cx.add_window_view(|_| CoveredButton {
+            button_clicks: 0,
+            overlay_clicks: 0,
+        });
+
+        let run = cx
+            .accessibility_tree()
+            .find(AccessibilityRole::Button, "Run")
+            .unwrap()
+            .id;
+        cx.update(|cx| cx.perform_accessibility_action(run, AccessibilityAction::Click));
+        view.update(cx, |view, _| {
+            assert_eq!(view.button_clicks, 1);
+            assert_eq!(view.overlay_clicks, 0);
+        });
+    }
+}
@@ -1,11 +1,11 @@
 use crate::{
-    Action, AnyView, AnyWindowHandle, AppCell, AppContext, AsyncAppContext, AvailableSpace,
-    BackgroundExecutor, BorrowAppContext, Bounds, ClipboardItem, Context, DrawPhase, Drawable,
-    Element, Empty, Entity, EventEmitter, ForegroundExecutor, Global, InputEvent, Keystroke, Model,
-    ModelContext, Modifiers, ModifiersChangedEvent, MouseButton, MouseDownEvent, MouseMoveEvent,
-    MouseUpEvent, Pixels, Platform, Point, Render, Result, Size, Task, TestDispatcher,
-    TestPlatform, TestScreenCaptureSource, TestWindow, TextSystem, View, ViewContext,
-    VisualContext, WindowBounds, WindowContext, WindowHandle, WindowOptions,
+    AccessibilityTree, Action, AnyView, AnyWindowHandle, AppCell, AppContext, AsyncAppContext,
+    AvailableSpace, BackgroundExecutor, BorrowAppContext, Bounds, ClipboardItem, Context,
+    DrawPhase, Drawable, Element, Empty, Entity, EventEmitter, ForegroundExecutor, Global,
+    InputEvent, Keystroke, Model, ModelContext, Modifiers, ModifiersChangedEvent, MouseButton,
+    MouseDownEvent, MouseMoveEvent, MouseUpEvent, Pixels, Platform, Point, Render, Result, Size,
+    Task, TestDispatcher, TestPlatform, TestScreenCaptureSource, TestWindow, TextSystem, View,
+    ViewContext, VisualContext, WindowBounds, WindowContext, WindowHandle, WindowOptions,
 };
 use anyhow::{anyhow, bail};
 use futures::{channel::oneshot, Stream, StreamExt};
@@ -779,6 +779,46 @@ impl VisualTestContext {
         self.simulate_window_resize(self.window, size)
     }
 
+    /// Renders the window's current frame into an image using the test platform's
+    /// software rasterizer. The window is redrawn first if it's dirty.
+    pub fn render_to_image(&mut self) -> image::RgbaImage {
+        let test_window = self.cx.test_window(self.window);
+        self.update(|cx| {
+            if cx.window.dirty.get() {
+                cx.draw();
+            }
+            test_window.rasterize(&cx.window.rendered_frame.scene)
+        })
+    }
+
+    /// Renders the window and compares it against the golden PNG at the given path.
+    /// See [crate::assert_image_matches_snapshot] for how goldens are updated.
+    pub fn assert_snapshot(&mut self, path: impl AsRef<std::path::Path>) {
+        let image = self.render_to_image();
+        crate::assert_image_matches_snapshot(&image, path);
+    }
+
+    /// Like [Self::assert_snapshot], but with a custom tolerance, e.g. for goldens containing text.
+    pub fn assert_snapshot_with_tolerance(
+        &mut self,
+        path: impl AsRef<std::path::Path>,
+        tolerance: crate::SnapshotTolerance,
+    ) {
+        let image = self.render_to_image();
+        crate::assert_image_matches_snapshot_with_tolerance(&image, path, tolerance);
+    }
+
+    /// Returns the accessibility tree of the window's current frame, redrawing
+    /// the window first if it's dirty.
+    pub fn accessibility_tree(&mut self) -> AccessibilityTree {
+        self.update(|cx| {
+            if cx.window.dirty.get() {
+                cx.draw();
+            }
+            cx.accessibility_tree()
+        })
+    }
+
     /// debug_bounds returns the bounds of the element with the given selector.
     pub fn debug_bounds(&mut self, selector: &'static str) -> Option<Bounds<Pixels>> {
         self.update(|cx| cx.window.rendered_frame.debug_bounds.get(selector).copied())
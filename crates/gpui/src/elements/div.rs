@@ -16,7 +16,8 @@
 //! constructed by combining these two systems into an all-in-one element.
 
 use crate::{
-    point, px, size, Action, AnyDrag, AnyElement, AnyTooltip, AnyView, AppContext, Bounds,
+    point, px, size, AccessibilityAction, AccessibilityNodeRequest, AccessibilityProperties,
+    AccessibilityRole, Action, AnyDrag, AnyElement, AnyTooltip, AnyView, AppContext, Bounds,
     ClickEvent, DispatchPhase, Element, ElementId, FocusHandle, Global, GlobalElementId, Hitbox,
     HitboxId, IntoElement, IsZero, KeyContext, KeyDownEvent, KeyUpEvent, LayoutId,
     ModifiersChangedEvent, MouseButton, MouseDownEvent, MouseMoveEvent, MouseUpEvent,
@@ -438,7 +439,7 @@ impl Interactivity {
         Self: Sized,
     {
         self.click_listeners
-            .push(Box::new(move |event, cx| listener(event, cx)));
+            .push(Rc::new(move |event, cx| listener(event, cx)));
     }
 
     /// On drag initiation, this callback will be used to create a new view to render the dragged value for a
@@ -545,6 +546,77 @@ pub trait InteractiveElement: Sized {
         Stateful { element: self }
     }
 
+    /// Expose this element to assistive technologies with the given role.
+    fn accessibility_role(mut self, role: AccessibilityRole) -> Self {
+        match self.interactivity().accessibility.as_mut() {
+            Some(accessibility) => accessibility.role = role,
+            None => {
+                self.interactivity().accessibility =
+                    Some(Box::new(AccessibilityProperties::new(role)))
+            }
+        }
+        self
+    }
+
+    /// Set the name assistive technologies announce for this element, such as a button's label.
+    /// Has no effect unless a role was assigned with [InteractiveElement::accessibility_role].
+    fn accessibility_name(mut self, name: impl Into<SharedString>) -> Self {
+        if let Some(accessibility) = self.interactivity().accessibility.as_mut() {
+            accessibility.name = Some(name.into());
+        }
+        self
+    }
+
+    /// Set the value assistive technologies announce for this element, such as the
+    /// contents of a text input.
+    /// Has no effect unless a role was assigned with [InteractiveElement::accessibility_role].
+    fn accessibility_value(mut self, value: impl Into<SharedString>) -> Self {
+        if let Some(accessibility) = self.interactivity().accessibility.as_mut() {
+            accessibility.value = Some(value.into());
+        }
+        self
+    }
+
+    /// Report whether this element is toggled, checked or selected.
+    /// Has no effect unless a role was assigned with [InteractiveElement::accessibility_role].
+    fn accessibility_selected(mut self, selected: bool) -> Self {
+        if let Some(accessibility) = self.interactivity().accessibility.as_mut() {
+            accessibility.selected = Some(selected);
+        }
+        self
+    }
+
+    /// Report whether this element is expanded. Expanded elements can be collapsed by
+    /// assistive technologies and vice versa.
+    /// Has no effect unless a role was assigned with [InteractiveElement::accessibility_role].
+    fn accessibility_expanded(mut self, expanded: bool) -> Self {
+        if let Some(accessibility) = self.interactivity().accessibility.as_mut() {
+            accessibility.expanded = Some(expanded);
+            let action = if expanded {
+                AccessibilityAction::Collapse
+            } else {
+                AccessibilityAction::Expand
+            };
+            accessibility.actions.retain(|existing| {
+                !matches!(
+                    existing,
+                    AccessibilityAction::Expand | AccessibilityAction::Collapse
+                )
+            });
+            accessibility.actions.push(action);
+        }
+        self
+    }
+
+    /// Report this element as disabled to assistive technologies.
+    /// Has no effect unless a role was assigned with [InteractiveElement::accessibility_role].
+    fn accessibility_disabled(mut self, disabled: bool) -> Self {
+        if let Some(accessibility) = self.interactivity().accessibility.as_mut() {
+            accessibility.disabled = disabled;
+        }
+        self
+    }
+
     /// Track the focus state of the given focus handle on this element.
     /// If the focus handle is focused by the application, this element will
     /// apply its focused styles.
@@ -1064,7 +1136,7 @@ pub(crate) type MouseMoveListener =
 pub(crate) type ScrollWheelListener =
     Box<dyn Fn(&ScrollWheelEvent, DispatchPhase, &Hitbox, &mut WindowContext) + 'static>;
 
-pub(crate) type ClickListener = Box<dyn Fn(&ClickEvent, &mut WindowContext) + 'static>;
+pub(crate) type ClickListener = Rc<dyn Fn(&ClickEvent, &mut WindowContext) + 'static>;
 
 pub(crate) type DragListener =
     Box<dyn Fn(&dyn Any, Point<Pixels>, &mut WindowContext) -> AnyView + 'static>;
@@ -1303,6 +1375,7 @@ pub struct Interactivity {
     pub(crate) hover_listener: Option<Box<dyn Fn(&bool, &mut WindowContext)>>,
     pub(crate) tooltip_builder: Option<TooltipBuilder>,
     pub(crate) occlude_mouse: bool,
+    pub(crate) accessibility: Option<Box<AccessibilityProperties>>,
 
     #[cfg(debug_assertions)]
     pub(crate) location: Option<core::panic::Location<'static>>,
@@ -1525,44 +1598,56 @@ impl Interactivity {
                     return ((), element_state);
                 }
 
-                cx.with_element_opacity(style.opacity, |cx| {
-                    style.paint(bounds, cx, |cx: &mut WindowContext| {
-                        cx.with_text_style(style.text_style().cloned(), |cx| {
-                            cx.with_content_mask(
-                                style.overflow_mask(bounds, cx.rem_size()),
-                                |cx| {
-                                    if let Some(hitbox) = hitbox {
-                                        #[cfg(debug_assertions)]
-                                        self.paint_debug_info(global_id, hitbox, &style, cx);
-
-                                        if !cx.has_active_drag() {
-                                            if let Some(mouse_cursor) = style.mouse_cursor {
-                                                cx.set_cursor_style(mouse_cursor, hitbox);
+                let accessibility_node = self.accessibility.as_ref().map(|properties| {
+                    AccessibilityNodeRequest::new(
+                        global_id,
+                        properties.as_ref().clone(),
+                        bounds,
+                        self.tracked_focus_handle.as_ref().map(|handle| handle.id),
+                        self.click_listeners.clone(),
+                    )
+                });
+
+                cx.with_accessibility_node(accessibility_node, |cx| {
+                    cx.with_element_opacity(style.opacity, |cx| {
+                        style.paint(bounds, cx, |cx: &mut WindowContext| {
+                            cx.with_text_style(style.text_style().cloned(), |cx| {
+                                cx.with_content_mask(
+                                    style.overflow_mask(bounds, cx.rem_size()),
+                                    |cx| {
+                                        if let Some(hitbox) = hitbox {
+                                            #[cfg(debug_assertions)]
+                                            self.paint_debug_info(global_id, hitbox, &style, cx);
+
+                                            if !cx.has_active_drag() {
+                                                if let Some(mouse_cursor) = style.mouse_cursor {
+                                                    cx.set_cursor_style(mouse_cursor, hitbox);
+                                                }
                                             }
-                                        }
 
-                                        if let Some(group) = self.group.clone() {
-                                            GroupHitboxes::push(group, hitbox.id, cx);
-                                        }
+                                            if let Some(group) = self.group.clone() {
+                                                GroupHitboxes::push(group, hitbox.id, cx);
+                                            }
 
-                                        self.paint_mouse_listeners(
-                                            hitbox,
-                                            element_state.as_mut(),
-                                            cx,
-                                        );
-                                        self.paint_scroll_listener(hitbox, &style, cx);
-                                    }
+                                            self.paint_mouse_listeners(
+                                                hitbox,
+                                                element_state.as_mut(),
+                                                cx,
+                                            );
+                                            self.paint_scroll_listener(hitbox, &style, cx);
+                                        }
 
-                                    self.paint_keyboard_listeners(cx);
-                                    f(&style, cx);
+                                        self.paint_keyboard_listeners(cx);
+                                        f(&style, cx);
 
-                                    if hitbox.is_some() {
-                                        if let Some(group) = self.group.as_ref() {
-                                            GroupHitboxes::pop(group, cx);
+                                        if hitbox.is_some() {
+                                            if let Some(group) = self.group.as_ref() {
+                                                GroupHitboxes::pop(group, cx);
+                                            }
                                         }
-                                    }
-                                },
-                            );
+                                    },
+                                );
+                            });
                         });
                     });
                 });
@@ -125,6 +125,7 @@ impl<F: Fn(&mut WindowContext) -> Option<Result<Arc<RenderImage>, ImageCacheErro
 /// The style of an image element.
 pub struct ImageStyle {
     grayscale: bool,
+    pixelated: bool,
     object_fit: ObjectFit,
     loading: Option<Box<dyn Fn() -> AnyElement>>,
     fallback: Option<Box<dyn Fn() -> AnyElement>>,
@@ -134,6 +135,7 @@ impl Default for ImageStyle {
     fn default() -> Self {
         Self {
             grayscale: false,
+            pixelated: false,
             object_fit: ObjectFit::Contain,
             loading: None,
             fallback: None,
@@ -152,6 +154,13 @@ pub trait StyledImage: Sized {
         self
     }
 
+    /// Set the image to be scaled with nearest-neighbor filtering, so that its
+    /// pixels stay sharp when it is scaled up.
+    fn pixelated(mut self, pixelated: bool) -> Self {
+        self.image_style().pixelated = pixelated;
+        self
+    }
+
     /// Set the object fit for the image.
     fn object_fit(mut self, object_fit: ObjectFit) -> Self {
         self.image_style().object_fit = object_fit;
@@ -412,6 +421,7 @@ impl Element for Img {
                         data.clone(),
                         layout_state.frame_index,
                         self.style.grayscale,
+                        self.style.pixelated,
                     )
                     .log_err();
                 } else if let Some(replacement) = &mut layout_state.replacement {
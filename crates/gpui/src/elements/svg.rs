@@ -23,6 +23,9 @@ pub fn svg() -> Svg {
 
 impl Svg {
     /// Set the path to the SVG file for this element.
+    ///
+    /// Absolute paths are read from the file system, while all other paths
+    /// are loaded from the application's [`AssetSource`](crate::AssetSource).
     pub fn path(mut self, path: impl Into<SharedString>) -> Self {
         self.path = Some(path.into());
         self
@@ -27,11 +27,12 @@ mod test;
 mod windows;
 
 use crate::{
-    point, Action, AnyWindowHandle, AsyncWindowContext, BackgroundExecutor, Bounds, DevicePixels,
-    DispatchEventResult, Font, FontId, FontMetrics, FontRun, ForegroundExecutor, GPUSpecs, GlyphId,
-    ImageSource, Keymap, LineLayout, Pixels, PlatformInput, Point, RenderGlyphParams, RenderImage,
-    RenderImageParams, RenderSvgParams, ScaledPixels, Scene, SharedString, Size, SvgRenderer,
-    SvgSize, Task, TaskLabel, WindowContext, DEFAULT_WINDOW_SIZE,
+    point, AccessibilityAction, AccessibilityNodeId, AccessibilityTree, Action, AnyWindowHandle,
+    AsyncWindowContext, BackgroundExecutor, Bounds, DevicePixels, DispatchEventResult, Font,
+    FontId, FontMetrics, FontRun, ForegroundExecutor, GPUSpecs, GlyphId, ImageSource, Keymap,
+    LineLayout, Pixels, PlatformInput, Point, RenderGlyphParams, RenderImage, RenderImageParams,
+    RenderSvgParams, ScaledPixels, Scene, SharedString, Size, SvgRenderer, SvgSize, Task,
+    TaskLabel, WindowContext, DEFAULT_WINDOW_SIZE,
 };
 use anyhow::{anyhow, Result};
 use async_task::Runnable;
@@ -412,6 +413,17 @@ pub(crate) trait PlatformWindow: HasWindowHandle + HasDisplayHandle {
     fn completed_frame(&self) {}
     fn sprite_atlas(&self) -> Arc<dyn PlatformAtlas>;
 
+    // Accessibility
+    fn accessibility_enabled(&self) -> bool {
+        false
+    }
+    fn update_accessibility_tree(&self, _tree: AccessibilityTree) {}
+    fn on_accessibility_action(
+        &self,
+        _callback: Box<dyn FnMut(AccessibilityNodeId, AccessibilityAction)>,
+    ) {
+    }
+
     // macOS specific methods
     fn set_edited(&mut self, _edited: bool) {}
     fn show_character_palette(&self) {}
@@ -744,7 +744,8 @@ fn fs_mono_sprite(input: MonoSpriteVarying) -> @location(0) vec4<f32> {
 struct PolychromeSprite {
     order: u32,
     pad: u32,
-    grayscale: u32,
+    // The `grayscale` and `pixelated` flags, one per byte.
+    flags: u32,
     opacity: f32,
     bounds: Bounds,
     content_mask: Bounds,
@@ -785,9 +786,14 @@ fn fs_poly_sprite(input: PolySpriteVarying) -> @location(0) vec4<f32> {
     let distance = quad_sdf(input.position.xy, sprite.bounds, sprite.corner_radii);
 
     var color = sample;
-    if ((sprite.grayscale & 0xFFu) != 0u) {
+    if ((sprite.flags & 0xFF00u) != 0u) {
+        let atlas_size = vec2<f32>(textureDimensions(t_sprite, 0));
+        let texel = vec2<i32>(floor(input.tile_position * atlas_size));
+        color = textureLoad(t_sprite, texel, 0);
+    }
+    if ((sprite.flags & 0xFFu) != 0u) {
         let grayscale = dot(color.rgb, GRAYSCALE_FACTORS);
-        color = vec4<f32>(vec3<f32>(grayscale), sample.a);
+        color = vec4<f32>(vec3<f32>(grayscale), color.a);
     }
     return blend_color(color, sprite.opacity * saturate(0.5 - distance));
 }
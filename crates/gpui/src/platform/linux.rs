@@ -1,3 +1,5 @@
+#[cfg(any(feature = "wayland", feature = "x11"))]
+mod accessibility;
 mod dispatcher;
 mod headless;
 mod platform;
@@ -0,0 +1,901 @@
+//! Exposes the accessibility trees of GPUI windows to assistive technologies through [AT-SPI].
+//!
+//! When the session reports that assistive technologies are enabled, we connect to the
+//! accessibility bus, embed an application object into the registry and publish one object
+//! per window and per [AccessibilityNode]. Actions requested by screen readers are sent back
+//! to the main thread through a [calloop] event source.
+//!
+//! [AT-SPI]: https://gitlab.gnome.org/GNOME/at-spi2-core
+
+use std::{
+    cell::RefCell,
+    collections::{BTreeMap, HashMap, HashSet},
+    rc::Rc,
+    sync::Arc,
+};
+
+use calloop::channel::Channel;
+use calloop::{EventSource, Poll, PostAction, Readiness, Token, TokenFactory};
+use parking_lot::Mutex;
+use smol::stream::StreamExt;
+use util::ResultExt;
+use zbus::zvariant::{ObjectPath, OwnedObjectPath, OwnedValue, Value};
+
+use crate::{
+    AccessibilityAction, AccessibilityNode, AccessibilityNodeId, AccessibilityRole,
+    AccessibilityTree, BackgroundExecutor,
+};
+
+const ROOT_PATH: &str = "/org/a11y/atspi/accessible/root";
+const NULL_PATH: &str = "/org/a11y/atspi/null";
+
+/// An action requested by an assistive technology, to be performed on the main thread.
+pub(crate) struct AccessibilityRequest {
+    window_id: u64,
+    node_id: AccessibilityNodeId,
+    action: AccessibilityAction,
+}
+
+type ActionCallback = Box<dyn FnMut(AccessibilityNodeId, AccessibilityAction)>;
+
+/// The main thread's handle to the AT-SPI bridge, shared by a client and its windows.
+#[derive(Clone)]
+pub(crate) struct AtspiBridge {
+    shared: Arc<Mutex<SharedState>>,
+    action_callbacks: Rc<RefCell<HashMap<u64, ActionCallback>>>,
+}
+
+/// The state read by the D-Bus object server, which runs on a background thread.
+struct SharedState {
+    enabled: bool,
+    bus_name: String,
+    desktop: Option<(String, OwnedObjectPath)>,
+    app_id: i32,
+    windows: BTreeMap<u64, WindowEntry>,
+    sync: smol::channel::Sender<()>,
+    requests: calloop::channel::Sender<AccessibilityRequest>,
+}
+
+#[derive(Default)]
+struct WindowEntry {
+    title: String,
+    tree: AccessibilityTree,
+}
+
+impl AtspiBridge {
+    pub fn new(executor: &BackgroundExecutor) -> (Self, AtspiEventSource) {
+        let (requests, channel) = calloop::channel::channel();
+        let (sync, sync_events) = smol::channel::unbounded();
+        let shared = Arc::new(Mutex::new(SharedState {
+            enabled: false,
+            bus_name: String::new(),
+            desktop: None,
+            app_id: 0,
+            windows: BTreeMap::new(),
+            sync,
+            requests,
+        }));
+
+        executor
+            .spawn({
+                let shared = shared.clone();
+                async move { serve(shared, sync_events).await.log_err() }
+            })
+            .detach();
+
+        let bridge = Self {
+            shared,
+            action_callbacks: Rc::default(),
+        };
+        (bridge, AtspiEventSource { channel })
+    }
+
+    /// Whether an assistive technology is listening, and windows should publish their trees.
+    pub fn is_enabled(&self) -> bool {
+        self.shared.lock().enabled
+    }
+
+    pub fn set_window_title(&self, window_id: u64, title: &str) {
+        let mut shared = self.shared.lock();
+        shared.windows.entry(window_id).or_default().title = title.to_string();
+        shared.sync.try_send(()).ok();
+    }
+
+    pub fn update_window(&self, window_id: u64, tree: AccessibilityTree) {
+        let mut shared = self.shared.lock();
+        shared.windows.entry(window_id).or_default().tree = tree;
+        shared.sync.try_send(()).ok();
+    }
+
+    pub fn remove_window(&self, window_id: u64) {
+        self.action_callbacks.borrow_mut().remove(&window_id);
+        let mut shared = self.shared.lock();
+        shared.windows.remove(&window_id);
+        shared.sync.try_send(()).ok();
+    }
+
+    pub fn on_action(&self, window_id: u64, callback: ActionCallback) {
+        self.action_callbacks
+            .borrow_mut()
+            .insert(window_id, callback);
+    }
+
+    pub fn handle_request(&self, request: AccessibilityRequest) {
+        // The callback may cause the window to be closed, so don't hold the borrow while running it.
+        let Some(mut callback) = self
+            .action_callbacks
+            .borrow_mut()
+            .remove(&request.window_id)
+        else {
+            return;
+        };
+        callback(request.node_id, request.action);
+        if self.shared.lock().windows.contains_key(&request.window_id) {
+            self.action_callbacks
+                .borrow_mut()
+                .entry(request.window_id)
+                .or_insert(callback);
+        }
+    }
+}
+
+async fn serve(
+    shared: Arc<Mutex<SharedState>>,
+    mut sync_events: smol::channel::Receiver<()>,
+) -> anyhow::Result<()> {
+    let session = zbus::Connection::session().await?;
+    let is_enabled = session
+        .call_method(
+            Some("org.a11y.Bus"),
+            "/org/a11y/bus",
+            Some("org.freedesktop.DBus.Properties"),
+            "Get",
+            &("org.a11y.Status", "IsEnabled"),
+        )
+        .await?
+        .body()
+        .deserialize::<OwnedValue>()
+        .ok()
+        .and_then(|value| bool::try_from(value).ok())
+        .unwrap_or(false);
+    if !is_enabled {
+        return Ok(());
+    }
+
+    let address = session
+        .call_method(
+            Some("org.a11y.Bus"),
+            "/org/a11y/bus",
+            Some("org.a11y.Bus"),
+            "GetAddress",
+            &(),
+        )
+        .await?
+        .body()
+        .deserialize::<String>()?;
+    let connection = zbus::connection::Builder::address(address.as_str())?
+        .build()
+        .await?;
+    let bus_name = connection
+        .unique_name()
+        .map(|name| name.to_string())
+        .unwrap_or_default();
+    shared.lock().bus_name = bus_name.clone();
+
+    let root = AccessibleObject {
+        shared: shared.clone(),
+        target: Target::Root,
+    };
+    connection
+        .object_server()
+        .at(ROOT_PATH, AccessibleInterface(root.clone()))
+        .await?;
+    connection
+        .object_server()
+        .at(ROOT_PATH, ApplicationInterface(root))
+        .await?;
+
+    let desktop = connection
+        .call_method(
+            Some("org.a11y.atspi.Registry"),
+            ROOT_PATH,
+            Some("org.a11y.atspi.Socket"),
+            "Embed",
+            &((bus_name, ObjectPath::from_static_str_unchecked(ROOT_PATH)),),
+        )
+        .await?
+        .body()
+        .deserialize::<(String, OwnedObjectPath)>()?;
+    {
+        let mut shared = shared.lock();
+        shared.desktop = Some(desktop);
+        shared.enabled = true;
+    }
+
+    let mut published = HashMap::<String, Target>::default();
+    let mut focused_path = None;
+    loop {
+        // Coalesce bursts of updates into a single pass over the trees.
+        while sync_events.try_recv().is_ok() {}
+
+        let (targets, focused) = {
+            let shared = shared.lock();
+            let mut targets = HashMap::default();
+            let mut focused = None;
+            for (window_id, window) in &shared.windows {
+                targets.insert(window_path(*window_id), Target::Window(*window_id));
+                for node in window.tree.nodes() {
+                    let path = node_path(*window_id, node.id);
+                    if node.focused {
+                        focused = Some(path.clone());
+                    }
+                    targets.insert(path, Target::Node(*window_id, node.id));
+                }
+            }
+            (targets, focused)
+        };
+
+        let object_server = connection.object_server();
+        let stale_paths = published
+            .keys()
+            .filter(|path| !targets.contains_key(*path))
+            .cloned()
+            .collect::<HashSet<_>>();
+        for path in stale_paths {
+            object_server
+                .remove::<AccessibleInterface, _>(path.as_str())
+                .await
+                .log_err();
+            object_server
+                .remove::<ComponentInterface, _>(path.as_str())
+                .await
+                .ok();
+            object_server
+                .remove::<ActionInterface, _>(path.as_str())
+                .await
+                .ok();
+            published.remove(&path);
+        }
+        for (path, target) in targets {
+            if published.contains_key(&path) {
+                continue;
+            }
+            let object = AccessibleObject {
+                shared: shared.clone(),
+                target,
+            };
+            object_server
+                .at(path.as_str(), AccessibleInterface(object.clone()))
+                .await
+                .log_err();
+            object_server
+                .at(path.as_str(), ComponentInterface(object.clone()))
+                .await
+                .log_err();
+            if matches!(target, Target::Node(..)) {
+                object_server
+                    .at(path.as_str(), ActionInterface(object))
+                    .await
+                    .log_err();
+            }
+            published.insert(path, target);
+        }
+
+        if focused != focused_path {
+            if let Some(path) = focused.as_deref() {
+                connection
+                    .emit_signal(
+                        None::<zbus::names::BusName>,
+                        path,
+                        "org.a11y.atspi.Event.Object",
+                        "StateChanged",
+                        &(
+                            "focused",
+                            1i32,
+                            0i32,
+                            Value::from(0i32),
+                            HashMap::<String, Value>::new(),
+                        ),
+                    )
+                    .await
+                    .log_err();
+            }
+            focused_path = focused;
+        }
+
+        if sync_events.next().await.is_none() {
+            break;
+        }
+    }
+
+    Ok(())
+}
+
+fn window_path(window_id: u64) -> String {
+    format!("/org/a11y/atspi/accessible/w{window_id}")
+}
+
+fn node_path(window_id: u64, node_id: AccessibilityNodeId) -> String {
+    format!("/org/a11y/atspi/accessible/w{window_id}_n{}", node_id.0)
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Target {
+    Root,
+    Window(u64),
+    Node(u64, AccessibilityNodeId),
+}
+
+#[derive(Clone)]
+struct AccessibleObject {
+    shared: Arc<Mutex<SharedState>>,
+    target: Target,
+}
+
+type ObjectRef = (String, OwnedObjectPath);
+
+impl AccessibleObject {
+    fn object_ref(shared: &SharedState, path: String) -> ObjectRef {
+        (
+            shared.bus_name.clone(),
+            ObjectPath::from_string_unchecked(path).into(),
+        )
+    }
+
+    fn null_ref() -> ObjectRef {
+        (
+            String::new(),
+            ObjectPath::from_static_str_unchecked(NULL_PATH).into(),
+        )
+    }
+
+    fn with_node<R>(&self, f: impl FnOnce(&AccessibilityNode) -> R) -> Option<R> {
+        let Target::Node(window_id, node_id) = self.target else {
+            return None;
+        };
+        let shared = self.shared.lock();
+        let node = shared.windows.get(&window_id)?.tree.node(node_id)?;
+        Some(f(node))
+    }
+
+    fn name(&self) -> String {
+        let shared = self.shared.lock();
+        match self.target {
+            Target::Root => "Zed".to_string(),
+            Target::Window(window_id) => shared
+                .windows
+                .get(&window_id)
+                .map(|window| window.title.clone())
+                .unwrap_or_default(),
+            Target::Node(window_id, node_id) => shared
+                .windows
+                .get(&window_id)
+                .and_then(|window| window.tree.node(node_id))
+                .and_then(|node| node.properties.name.as_ref())
+                .map(|name| name.to_string())
+                .unwrap_or_default(),
+        }
+    }
+
+    fn parent(&self) -> ObjectRef {
+        let shared = self.shared.lock();
+        match self.target {
+            Target::Root => shared.desktop.clone().unwrap_or_else(Self::null_ref),
+            Target::Window(_) => Self::object_ref(&shared, ROOT_PATH.to_string()),
+            Target::Node(window_id, node_id) => {
+                let parent = shared
+                    .windows
+                    .get(&window_id)
+                    .and_then(|window| window.tree.node(node_id))
+                    .and_then(|node| node.parent);
+                match parent {
+                    Some(parent) => Self::object_ref(&shared, node_path(window_id, parent)),
+                    None => Self::object_ref(&shared, window_path(window_id)),
+                }
+            }
+        }
+    }
+
+    fn children(&self) -> Vec<ObjectRef> {
+        let shared = self.shared.lock();
+        match self.target {
+            Target::Root => shared
+                .windows
+                .keys()
+                .map(|window_id| Self::object_ref(&shared, window_path(*window_id)))
+                .collect(),
+            Target::Window(window_id) => shared
+                .windows
+                .get(&window_id)
+                .into_iter()
+                .flat_map(|window| window.tree.roots())
+                .map(|node| Self::object_ref(&shared, node_path(window_id, node.id)))
+                .collect(),
+            Target::Node(window_id, node_id) => shared
+                .windows
+                .get(&window_id)
+                .into_iter()
+                .flat_map(|window| window.tree.children(node_id))
+                .map(|node| Self::object_ref(&shared, node_path(window_id, node.id)))
+                .collect(),
+        }
+    }
+
+    fn role(&self) -> Role {
+        match self.target {
+            Target::Root => Role::Application,
+            Target::Window(_) => Role::Frame,
+            Target::Node(..) => self
+                .with_node(|node| Role::from(node.properties.role))
+                .unwrap_or(Role::Invalid),
+        }
+    }
+
+    fn states(&self) -> Vec<u32> {
+        let mut states = StateSet::default();
+        match self.target {
+            Target::Root => {}
+            Target::Window(_) => {
+                states.insert(State::Enabled);
+                states.insert(State::Sensitive);
+                states.insert(State::Showing);
+                states.insert(State::Visible);
+            }
+            Target::Node(..) => {
+                self.with_node(|node| {
+                    if !node.properties.disabled {
+                        states.insert(State::Enabled);
+                        states.insert(State::Sensitive);
+                    }
+                    states.insert(State::Showing);
+                    states.insert(State::Visible);
+                    if node.focusable {
+                        states.insert(State::Focusable);
+                    }
+                    if node.focused {
+                        states.insert(State::Focused);
+                    }
+                    if let Some(selected) = node.properties.selected {
+                        states.insert(State::Checkable);
+                        if selected {
+                            states.insert(State::Checked);
+                            states.insert(State::Selected);
+                        }
+                    }
+                    if let Some(expanded) = node.properties.expanded {
+                        states.insert(State::Expandable);
+                        states.insert(if expanded {
+                            State::Expanded
+                        } else {
+                            State::Collapsed
+                        });
+                    }
+                });
+            }
+        }
+        states.0.to_vec()
+    }
+
+    fn request(&self, action: AccessibilityAction) -> bool {
+        let Target::Node(window_id, node_id) = self.target else {
+            return false;
+        };
+        let shared = self.shared.lock();
+        shared
+            .requests
+            .send(AccessibilityRequest {
+                window_id,
+                node_id,
+                action,
+            })
+            .is_ok()
+    }
+}
+
+struct AccessibleInterface(AccessibleObject);
+
+#[zbus::interface(name = "org.a11y.atspi.Accessible")]
+impl AccessibleInterface {
+    #[zbus(property)]
+    fn name(&self) -> String {
+        self.0.name()
+    }
+
+    #[zbus(property)]
+    fn description(&self) -> String {
+        self.0
+            .with_node(|node| {
+                node.properties
+                    .value
+                    .as_ref()
+                    .map(|value| value.to_string())
+            })
+            .flatten()
+            .unwrap_or_default()
+    }
+
+    #[zbus(property)]
+    fn parent(&self) -> ObjectRef {
+        self.0.parent()
+    }
+
+    #[zbus(property)]
+    fn child_count(&self) -> i32 {
+        self.0.children().len() as i32
+    }
+
+    #[zbus(property)]
+    fn locale(&self) -> String {
+        String::new()
+    }
+
+    #[zbus(property)]
+    fn accessible_id(&self) -> String {
+        match self.0.target {
+            Target::Root => String::new(),
+            Target::Window(window_id) => format!("w{window_id}"),
+            Target::Node(window_id, node_id) => format!("w{window_id}_n{}", node_id.0),
+        }
+    }
+
+    fn get_child_at_index(&self, index: i32) -> ObjectRef {
+        usize::try_from(index)
+            .ok()
+            .and_then(|index| self.0.children().into_iter().nth(index))
+            .unwrap_or_else(AccessibleObject::null_ref)
+    }
+
+    fn get_children(&self) -> Vec<ObjectRef> {
+        self.0.children()
+    }
+
+    fn get_index_in_parent(&self) -> i32 {
+        let shared = self.0.shared.lock();
+        let index = match self.0.target {
+            Target::Root => None,
+            Target::Window(window_id) => shared.windows.keys().position(|id| *id == window_id),
+            Target::Node(window_id, node_id) => shared.windows.get(&window_id).and_then(|window| {
+                let node = window.tree.node(node_id)?;
+                match node.parent {
+                    Some(parent) => window
+                        .tree
+                        .node(parent)?
+                        .children
+                        .iter()
+                        .position(|id| *id == node_id),
+                    None => window.tree.roots().position(|root| root.id == node_id),
+                }
+            }),
+        };
+        index.map_or(-1, |index| index as i32)
+    }
+
+    fn get_relation_set(&self) -> Vec<(u32, Vec<ObjectRef>)> {
+        Vec::new()
+    }
+
+    fn get_role(&self) -> u32 {
+        self.0.role() as u32
+    }
+
+    fn get_role_name(&self) -> String {
+        match self.0.target {
+            Target::Root => "application".to_string(),
+            Target::Window(_) => "frame".to_string(),
+            Target::Node(..) => self
+                .0
+                .with_node(|node| node.properties.role.name().to_string())
+                .unwrap_or_default(),
+        }
+    }
+
+    fn get_localized_role_name(&self) -> String {
+        self.get_role_name()
+    }
+
+    fn get_state(&self) -> Vec<u32> {
+        self.0.states()
+    }
+
+    fn get_attributes(&self) -> HashMap<String, String> {
+        HashMap::from_iter([("toolkit".to_string(), "GPUI".to_string())])
+    }
+
+    fn get_application(&self) -> ObjectRef {
+        let shared = self.0.shared.lock();
+        AccessibleObject::object_ref(&shared, ROOT_PATH.to_string())
+    }
+
+    fn get_interfaces(&self) -> Vec<String> {
+        let mut interfaces = vec!["org.a11y.atspi.Accessible".to_string()];
+        match self.0.target {
+            Target::Root => interfaces.push("org.a11y.atspi.Application".to_string()),
+            Target::Window(_) => interfaces.push("org.a11y.atspi.Component".to_string()),
+            Target::Node(..) => {
+                interfaces.push("org.a11y.atspi.Component".to_string());
+                interfaces.push("org.a11y.atspi.Action".to_string());
+            }
+        }
+        interfaces
+    }
+}
+
+struct ApplicationInterface(AccessibleObject);
+
+#[zbus::interface(name = "org.a11y.atspi.Application")]
+impl ApplicationInterface {
+    #[zbus(property)]
+    fn toolkit_name(&self) -> String {
+        "GPUI".to_string()
+    }
+
+    #[zbus(property)]
+    fn version(&self) -> String {
+        env!("CARGO_PKG_VERSION").to_string()
+    }
+
+    #[zbus(property)]
+    fn atspi_version(&self) -> String {
+        "2.1".to_string()
+    }
+
+    #[zbus(property)]
+    fn id(&self) -> i32 {
+        self.0.shared.lock().app_id
+    }
+
+    #[zbus(property)]
+    fn set_id(&mut self, id: i32) {
+        self.0.shared.lock().app_id = id;
+    }
+}
+
+struct ComponentInterface(AccessibleObject);
+
+#[zbus::interface(name = "org.a11y.atspi.Component")]
+impl ComponentInterface {
+    /// Returns the extents of the object relative to its window. Screen coordinates
+    /// aren't available on Wayland, so all coordinate types are treated as window-relative.
+    fn get_extents(&self, _coord_type: u32) -> (i32, i32, i32, i32) {
+        self.0
+            .with_node(|node| {
+                (
+                    node.bounds.origin.x.0 as i32,
+                    node.bounds.origin.y.0 as i32,
+                    node.bounds.size.width.0 as i32,
+                    node.bounds.size.height.0 as i32,
+                )
+            })
+            .unwrap_or_default()
+    }
+
+    fn get_position(&self, coord_type: u32) -> (i32, i32) {
+        let (x, y, _, _) = self.get_extents(coord_type);
+        (x, y)
+    }
+
+    fn get_size(&self) -> (i32, i32) {
+        let (_, _, width, height) = self.get_extents(0);
+        (width, height)
+    }
+
+    fn contains(&self, x: i32, y: i32, coord_type: u32) -> bool {
+        let (left, top, width, height) = self.get_extents(coord_type);
+        x >= left && x < left + width && y >= top && y < top + height
+    }
+
+    fn get_layer(&self) -> u32 {
+        // ATSPI_LAYER_WIDGET
+        3
+    }
+
+    fn grab_focus(&self) -> bool {
+        self.0.request(AccessibilityAction::Focus)
+    }
+}
+
+struct ActionInterface(AccessibleObject);
+
+impl ActionInterface {
+    fn actions(&self) -> Vec<AccessibilityAction> {
+        self.0
+            .with_node(|node| node.actions.to_vec())
+            .unwrap_or_default()
+    }
+}
+
+#[zbus::interface(name = "org.a11y.atspi.Action")]
+impl ActionInterface {
+    #[zbus(property)]
+    fn n_actions(&self) -> i32 {
+        self.actions().len() as i32
+    }
+
+    fn get_name(&self, index: i32) -> String {
+        usize::try_from(index)
+            .ok()
+            .and_then(|index| self.actions().get(index).copied())
+            .map(|action| action.name().to_string())
+            .unwrap_or_default()
+    }
+
+    fn get_localized_name(&self, index: i32) -> String {
+        self.get_name(index)
+    }
+
+    fn get_description(&self, _index: i32) -> String {
+        String::new()
+    }
+
+    fn get_key_binding(&self, _index: i32) -> String {
+        String::new()
+    }
+
+    fn get_actions(&self) -> Vec<(String, String, String)> {
+        self.actions()
+            .into_iter()
+            .map(|action| (action.name().to_string(), String::new(), String::new()))
+            .collect()
+    }
+
+    fn do_action(&self, index: i32) -> bool {
+        let Some(action) = usize::try_from(index)
+            .ok()
+            .and_then(|index| self.actions().get(index).copied())
+        else {
+            return false;
+        };
+        self.0.request(action)
+    }
+}
+
+/// The subset of `AtspiRole` values that GPUI roles map onto.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(u32)]
+enum Role {
+    Invalid = 0,
+    CheckBox = 7,
+    Dialog = 16,
+    Frame = 23,
+    Image = 27,
+    Label = 29,
+    List = 31,
+    ListItem = 32,
+    Menu = 33,
+    MenuItem = 35,
+    PageTab = 37,
+    PageTabList = 38,
+    Panel = 39,
+    ProgressBar = 42,
+    PushButton = 43,
+    RadioButton = 44,
+    ScrollPane = 49,
+    Separator = 50,
+    Slider = 51,
+    ToggleButton = 62,
+    ToolBar = 63,
+    ToolTip = 64,
+    Tree = 65,
+    Application = 75,
+    Entry = 79,
+    Heading = 83,
+    Link = 88,
+    TreeItem = 91,
+}
+
+impl From<AccessibilityRole> for Role {
+    fn from(role: AccessibilityRole) -> Self {
+        match role {
+            AccessibilityRole::Group => Role::Panel,
+            AccessibilityRole::Button => Role::PushButton,
+            AccessibilityRole::ToggleButton => Role::ToggleButton,
+            AccessibilityRole::Checkbox => Role::CheckBox,
+            AccessibilityRole::RadioButton => Role::RadioButton,
+            AccessibilityRole::Dialog => Role::Dialog,
+            AccessibilityRole::Heading => Role::Heading,
+            AccessibilityRole::Image => Role::Image,
+            AccessibilityRole::Label => Role::Label,
+            AccessibilityRole::Link => Role::Link,
+            AccessibilityRole::List => Role::List,
+            AccessibilityRole::ListItem => Role::ListItem,
+            AccessibilityRole::Menu => Role::Menu,
+            AccessibilityRole::MenuItem => Role::MenuItem,
+            AccessibilityRole::ProgressBar => Role::ProgressBar,
+            AccessibilityRole::ScrollArea => Role::ScrollPane,
+            AccessibilityRole::Separator => Role::Separator,
+            AccessibilityRole::Slider => Role::Slider,
+            AccessibilityRole::Tab => Role::PageTab,
+            AccessibilityRole::TabList => Role::PageTabList,
+            AccessibilityRole::TextInput => Role::Entry,
+            AccessibilityRole::Toolbar => Role::ToolBar,
+            AccessibilityRole::Tooltip => Role::ToolTip,
+            AccessibilityRole::Tree => Role::Tree,
+            AccessibilityRole::TreeItem => Role::TreeItem,
+        }
+    }
+}
+
+/// The subset of `AtspiStateType` values that GPUI reports.
+#[derive(Clone, Copy)]
+#[repr(u32)]
+enum State {
+    Checked = 4,
+    Collapsed = 5,
+    Enabled = 8,
+    Expandable = 9,
+    Expanded = 10,
+    Focusable = 11,
+    Focused = 12,
+    Selected = 23,
+    Sensitive = 24,
+    Showing = 25,
+    Visible = 30,
+    Checkable = 41,
+}
+
+/// AT-SPI states are transmitted as a 64-bit set, split into two 32-bit words.
+#[derive(Default)]
+struct StateSet([u32; 2]);
+
+impl StateSet {
+    fn insert(&mut self, state: State) {
+        let bit = state as u32;
+        self.0[(bit / 32) as usize] |= 1 << (bit % 32);
+    }
+}
+
+/// A [calloop] event source that delivers accessibility actions to the main thread.
+pub(crate) struct AtspiEventSource {
+    channel: Channel<AccessibilityRequest>,
+}
+
+impl EventSource for AtspiEventSource {
+    type Event = AccessibilityRequest;
+    type Metadata = ();
+    type Ret = ();
+    type Error = anyhow::Error;
+
+    fn process_events<F>(
+        &mut self,
+        readiness: Readiness,
+        token: Token,
+        mut callback: F,
+    ) -> Result<PostAction, Self::Error>
+    where
+        F: FnMut(Self::Event, &mut Self::Metadata) -> Self::Ret,
+    {
+        self.channel.process_events(readiness, token, |evt, _| {
+            if let calloop::channel::Event::Msg(msg) = evt {
+                (callback)(msg, &mut ())
+            }
+        })?;
+
+        Ok(PostAction::Continue)
+    }
+
+    fn register(
+        &mut self,
+        poll: &mut Poll,
+        token_factory: &mut TokenFactory,
+    ) -> calloop::Result<()> {
+        self.channel.register(poll, token_factory)?;
+
+        Ok(())
+    }
+
+    fn reregister(
+        &mut self,
+        poll: &mut Poll,
+        token_factory: &mut TokenFactory,
+    ) -> calloop::Result<()> {
+        self.channel.reregister(poll, token_factory)?;
+
+        Ok(())
+    }
+
+    fn unregister(&mut self, poll: &mut Poll) -> calloop::Result<()> {
+        self.channel.unregister(poll)?;
+
+        Ok(())
+    }
+}
@@ -64,6 +64,7 @@ use xkbcommon::xkb::{self, Keycode, KEYMAP_COMPILE_NO_FLAGS};
 
 use super::display::WaylandDisplay;
 use super::window::{ImeInput, WaylandWindowStatePtr};
+use crate::platform::linux::accessibility::AtspiBridge;
 use crate::platform::linux::wayland::clipboard::{
     Clipboard, DataOffer, FILE_LIST_MIME_TYPE, TEXT_MIME_TYPE,
 };
@@ -110,6 +111,7 @@ pub struct Globals {
     pub decoration_manager: Option<zxdg_decoration_manager_v1::ZxdgDecorationManagerV1>,
     pub blur_manager: Option<org_kde_kwin_blur_manager::OrgKdeKwinBlurManager>,
     pub text_input_manager: Option<zwp_text_input_manager_v3::ZwpTextInputManagerV3>,
+    pub accessibility: AtspiBridge,
     pub executor: ForegroundExecutor,
 }
 
@@ -119,6 +121,7 @@ impl Globals {
         executor: ForegroundExecutor,
         qh: QueueHandle<WaylandClientStatePtr>,
         seat: wl_seat::WlSeat,
+        accessibility: AtspiBridge,
     ) -> Self {
         Globals {
             activation: globals.bind(&qh, 1..=1, ()).ok(),
@@ -147,6 +150,7 @@ impl Globals {
             decoration_manager: globals.bind(&qh, 1..=1, ()).ok(),
             blur_manager: globals.bind(&qh, 1..=1, ()).ok(),
             text_input_manager: globals.bind(&qh, 1..=1, ()).ok(),
+            accessibility,
             executor,
             qh,
         }
@@ -459,12 +463,23 @@ impl WaylandClient {
             })
             .unwrap();
 
+        let (accessibility, accessibility_events) = AtspiBridge::new(&common.background_executor);
+        handle
+            .insert_source(accessibility_events, {
+                let accessibility = accessibility.clone();
+                move |request, _, _: &mut WaylandClientStatePtr| {
+                    accessibility.handle_request(request);
+                }
+            })
+            .unwrap();
+
         let seat = seat.unwrap();
         let globals = Globals::new(
             globals,
             common.foreground_executor.clone(),
             qh.clone(),
             seat.clone(),
+            accessibility,
         );
 
         let data_device = globals
@@ -25,10 +25,11 @@ use crate::platform::linux::wayland::serial::SerialKind;
 use crate::platform::{PlatformAtlas, PlatformInputHandler, PlatformWindow};
 use crate::scene::Scene;
 use crate::{
-    px, size, AnyWindowHandle, Bounds, Decorations, GPUSpecs, Globals, Modifiers, Output, Pixels,
-    PlatformDisplay, PlatformInput, Point, PromptLevel, RequestFrameOptions, ResizeEdge,
-    ScaledPixels, Size, Tiling, WaylandClientStatePtr, WindowAppearance,
-    WindowBackgroundAppearance, WindowBounds, WindowControls, WindowDecorations, WindowParams,
+    px, size, AccessibilityAction, AccessibilityNodeId, AccessibilityTree, AnyWindowHandle, Bounds,
+    Decorations, GPUSpecs, Globals, Modifiers, Output, Pixels, PlatformDisplay, PlatformInput,
+    Point, PromptLevel, RequestFrameOptions, ResizeEdge, ScaledPixels, Size, Tiling,
+    WaylandClientStatePtr, WindowAppearance, WindowBackgroundAppearance, WindowBounds,
+    WindowControls, WindowDecorations, WindowParams,
 };
 
 #[derive(Default)]
@@ -240,6 +241,10 @@ impl Drop for WaylandWindow {
         }
         state.xdg_surface.destroy();
         state.surface.destroy();
+        state
+            .globals
+            .accessibility
+            .remove_window(state.handle.window_id().as_u64());
 
         let state_ptr = self.0.clone();
         state
@@ -865,7 +870,12 @@ impl PlatformWindow for WaylandWindow {
     }
 
     fn set_title(&mut self, title: &str) {
-        self.borrow().toplevel.set_title(title.to_string());
+        let state = self.borrow();
+        state.toplevel.set_title(title.to_string());
+        state
+            .globals
+            .accessibility
+            .set_window_title(state.handle.window_id().as_u64(), title);
     }
 
     fn set_app_id(&mut self, app_id: &str) {
@@ -942,6 +952,29 @@ impl PlatformWindow for WaylandWindow {
         self.0.callbacks.borrow_mut().appearance_changed = Some(callback);
     }
 
+    fn accessibility_enabled(&self) -> bool {
+        self.borrow().globals.accessibility.is_enabled()
+    }
+
+    fn update_accessibility_tree(&self, tree: AccessibilityTree) {
+        let state = self.borrow();
+        state
+            .globals
+            .accessibility
+            .update_window(state.handle.window_id().as_u64(), tree);
+    }
+
+    fn on_accessibility_action(
+        &self,
+        callback: Box<dyn FnMut(AccessibilityNodeId, AccessibilityAction)>,
+    ) {
+        let state = self.borrow();
+        state
+            .globals
+            .accessibility
+            .on_action(state.handle.window_id().as_u64(), callback);
+    }
+
     fn draw(&self, scene: &Scene) {
         let mut state = self.borrow_mut();
         state.renderer.draw(scene);
@@ -49,6 +49,7 @@ use super::{
 };
 use super::{X11Display, X11WindowStatePtr, XcbAtoms};
 use super::{XimCallbackEvent, XimHandler};
+use crate::platform::linux::accessibility::AtspiBridge;
 use crate::platform::linux::platform::{DOUBLE_CLICK_INTERVAL, SCROLL_LINES};
 use crate::platform::linux::xdg_desktop_portal::{Event as XDPEvent, XDPEventSource};
 use crate::platform::linux::{
@@ -167,6 +168,7 @@ pub struct X11ClientState {
     pub(crate) x_root_index: usize,
     pub(crate) _resource_database: Database,
     pub(crate) atoms: XcbAtoms,
+    pub(crate) accessibility: AtspiBridge,
     pub(crate) windows: HashMap<xproto::Window, WindowRef>,
     pub(crate) mouse_focused_window: Option<xproto::Window>,
     pub(crate) keyboard_focused_window: Option<xproto::Window>,
@@ -419,6 +421,16 @@ impl X11Client {
             })
             .unwrap();
 
+        let (accessibility, accessibility_events) = AtspiBridge::new(&common.background_executor);
+        handle
+            .insert_source(accessibility_events, {
+                let accessibility = accessibility.clone();
+                move |request, _, _: &mut X11Client| {
+                    accessibility.handle_request(request);
+                }
+            })
+            .unwrap();
+
         X11Client(Rc::new(RefCell::new(X11ClientState {
             modifiers: Modifiers::default(),
             event_loop: Some(event_loop),
@@ -437,6 +449,7 @@ impl X11Client {
             x_root_index,
             _resource_database: resource_database,
             atoms,
+            accessibility,
             windows: HashMap::default(),
             mouse_focused_window: None,
             keyboard_focused_window: None,
@@ -1307,6 +1320,7 @@ impl LinuxClient for X11Client {
             &state.atoms,
             state.scale_factor,
             state.common.appearance,
+            state.accessibility.clone(),
         )?;
         state
             .xcb_connection
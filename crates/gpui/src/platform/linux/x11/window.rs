@@ -2,11 +2,13 @@ use anyhow::{anyhow, Context};
 
 use crate::{
     platform::blade::{BladeRenderer, BladeSurfaceConfig},
-    px, size, AnyWindowHandle, Bounds, Decorations, DevicePixels, ForegroundExecutor, GPUSpecs,
-    Modifiers, Pixels, PlatformAtlas, PlatformDisplay, PlatformInput, PlatformInputHandler,
-    PlatformWindow, Point, PromptLevel, RequestFrameOptions, ResizeEdge, ScaledPixels, Scene, Size,
-    Tiling, WindowAppearance, WindowBackgroundAppearance, WindowBounds, WindowDecorations,
-    WindowKind, WindowParams, X11ClientStatePtr,
+    platform::linux::accessibility::AtspiBridge,
+    px, size, AccessibilityAction, AccessibilityNodeId, AccessibilityTree, AnyWindowHandle, Bounds,
+    Decorations, DevicePixels, ForegroundExecutor, GPUSpecs, Modifiers, Pixels, PlatformAtlas,
+    PlatformDisplay, PlatformInput, PlatformInputHandler, PlatformWindow, Point, PromptLevel,
+    RequestFrameOptions, ResizeEdge, ScaledPixels, Scene, Size, Tiling, WindowAppearance,
+    WindowBackgroundAppearance, WindowBounds, WindowDecorations, WindowKind, WindowParams,
+    X11ClientStatePtr,
 };
 
 use blade_graphics as gpu;
@@ -266,6 +268,7 @@ pub struct X11WindowState {
     edge_constraints: Option<EdgeConstraints>,
     pub handle: AnyWindowHandle,
     last_insets: [u32; 4],
+    accessibility: AtspiBridge,
 }
 
 impl X11WindowState {
@@ -366,6 +369,7 @@ impl X11WindowState {
         atoms: &XcbAtoms,
         scale_factor: f32,
         appearance: WindowAppearance,
+        accessibility: AtspiBridge,
     ) -> anyhow::Result<Self> {
         let x_screen_index = params
             .display_id
@@ -617,6 +621,7 @@ impl X11WindowState {
                 edge_constraints: None,
                 counter_id: sync_request_counter,
                 last_sync_counter: None,
+                accessibility,
             })
         });
 
@@ -669,6 +674,9 @@ impl Drop for X11Window {
     fn drop(&mut self) {
         let mut state = self.0.state.borrow_mut();
         state.renderer.destroy();
+        state
+            .accessibility
+            .remove_window(state.handle.window_id().as_u64());
 
         let destroy_x_window = maybe!({
             check_reply(
@@ -724,6 +732,7 @@ impl X11Window {
         atoms: &XcbAtoms,
         scale_factor: f32,
         appearance: WindowAppearance,
+        accessibility: AtspiBridge,
     ) -> anyhow::Result<Self> {
         let ptr = X11WindowStatePtr {
             state: Rc::new(RefCell::new(X11WindowState::new(
@@ -738,6 +747,7 @@ impl X11Window {
                 atoms,
                 scale_factor,
                 appearance,
+                accessibility,
             )?)),
             callbacks: Rc::new(RefCell::new(Callbacks::default())),
             xcb: xcb.clone(),
@@ -1229,6 +1239,11 @@ impl PlatformWindow for X11Window {
         )
         .unwrap();
         self.flush().unwrap();
+
+        let state = self.0.state.borrow();
+        state
+            .accessibility
+            .set_window_title(state.handle.window_id().as_u64(), title);
     }
 
     fn set_app_id(&mut self, app_id: &str) {
@@ -1348,6 +1363,27 @@ impl PlatformWindow for X11Window {
         self.0.callbacks.borrow_mut().appearance_changed = Some(callback);
     }
 
+    fn accessibility_enabled(&self) -> bool {
+        self.0.state.borrow().accessibility.is_enabled()
+    }
+
+    fn update_accessibility_tree(&self, tree: AccessibilityTree) {
+        let state = self.0.state.borrow();
+        state
+            .accessibility
+            .update_window(state.handle.window_id().as_u64(), tree);
+    }
+
+    fn on_accessibility_action(
+        &self,
+        callback: Box<dyn FnMut(AccessibilityNodeId, AccessibilityAction)>,
+    ) {
+        let state = self.0.state.borrow();
+        state
+            .accessibility
+            .on_action(state.handle.window_id().as_u64(), callback);
+    }
+
     fn draw(&self, scene: &Scene) {
         let mut inner = self.0.state.borrow_mut();
         inner.renderer.draw(scene);
@@ -398,8 +398,13 @@ fragment float4 polychrome_sprite_fragment(
   PolychromeSprite sprite = sprites[input.sprite_id];
   constexpr sampler atlas_texture_sampler(mag_filter::linear,
                                           min_filter::linear);
+  constexpr sampler pixelated_atlas_texture_sampler(mag_filter::nearest,
+                                                    min_filter::linear);
   float4 sample =
-      atlas_texture.sample(atlas_texture_sampler, input.tile_position);
+      sprite.pixelated
+          ? atlas_texture.sample(pixelated_atlas_texture_sampler,
+                                 input.tile_position)
+          : atlas_texture.sample(atlas_texture_sampler, input.tile_position);
   float distance =
       quad_sdf(input.position.xy, sprite.bounds, sprite.corner_radii);
 
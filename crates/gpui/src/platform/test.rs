@@ -1,6 +1,7 @@
 mod dispatcher;
 mod display;
 mod platform;
+mod rasterizer;
 mod window;
 
 pub(crate) use dispatcher::*;
@@ -0,0 +1,732 @@
+//! A CPU rasterizer for [Scene]s, used by the test platform to render windows
+//! into images so that tests can compare them against golden snapshots.
+//!
+//! The output isn't meant to be pixel-identical to the GPU renderers, only
+//! deterministic and close enough that visual regressions show up in a diff.
+
+use crate::{
+    color::BackgroundTag, AtlasTextureId, AtlasTextureKind, Background, Bounds, Corners,
+    DevicePixels, Edges, Hsla, MonochromeSprite, Path, PathVertex, PolychromeSprite,
+    PrimitiveBatch, Quad, Rgba, ScaledPixels, Scene, Shadow, Size, TestAtlas, Underline,
+};
+use image::RgbaImage;
+
+/// Renders the given scene into an RGBA image of the given size, reading sprite
+/// contents from the test atlas.
+pub(crate) fn rasterize_scene(
+    scene: &Scene,
+    size: Size<DevicePixels>,
+    atlas: &TestAtlas,
+) -> RgbaImage {
+    let mut canvas = Canvas::new(size.width.0.max(0) as u32, size.height.0.max(0) as u32);
+    for batch in scene.batches() {
+        match batch {
+            PrimitiveBatch::Shadows(shadows) => {
+                for shadow in shadows {
+                    canvas.draw_shadow(shadow);
+                }
+            }
+            PrimitiveBatch::Quads(quads) => {
+                for quad in quads {
+                    canvas.draw_quad(quad);
+                }
+            }
+            PrimitiveBatch::Paths(paths) => {
+                for path in paths {
+                    canvas.draw_path(path);
+                }
+            }
+            PrimitiveBatch::Underlines(underlines) => {
+                for underline in underlines {
+                    canvas.draw_underline(underline);
+                }
+            }
+            PrimitiveBatch::MonochromeSprites {
+                texture_id,
+                sprites,
+            } => {
+                for sprite in sprites {
+                    canvas.draw_monochrome_sprite(sprite, texture_id, atlas);
+                }
+            }
+            PrimitiveBatch::PolychromeSprites {
+                texture_id,
+                sprites,
+            } => {
+                for sprite in sprites {
+                    canvas.draw_polychrome_sprite(sprite, texture_id, atlas);
+                }
+            }
+            // Surfaces are backed by platform video buffers, which the test
+            // platform never produces.
+            PrimitiveBatch::Surfaces(_) => {}
+        }
+    }
+    canvas.into_image()
+}
+
+/// A premultiplied-alpha floating point framebuffer.
+struct Canvas {
+    width: u32,
+    height: u32,
+    pixels: Vec<[f32; 4]>,
+}
+
+/// The range of device pixels covered by some bounds, clipped to a mask and the canvas.
+struct PixelRange {
+    x: std::ops::Range<u32>,
+    y: std::ops::Range<u32>,
+}
+
+impl Canvas {
+    fn new(width: u32, height: u32) -> Self {
+        Self {
+            width,
+            height,
+            pixels: vec![[0.; 4]; (width * height) as usize],
+        }
+    }
+
+    fn into_image(self) -> RgbaImage {
+        let mut image = RgbaImage::new(self.width, self.height);
+        for (pixel, [r, g, b, a]) in image.pixels_mut().zip(self.pixels) {
+            let unpremultiply = |channel: f32| {
+                if a > 0. {
+                    to_u8(channel / a)
+                } else {
+                    0
+                }
+            };
+            pixel.0 = [
+                unpremultiply(r),
+                unpremultiply(g),
+                unpremultiply(b),
+                to_u8(a),
+            ];
+        }
+        image
+    }
+
+    fn pixel_range(
+        &self,
+        bounds: &Bounds<ScaledPixels>,
+        mask: &Bounds<ScaledPixels>,
+    ) -> Option<PixelRange> {
+        let clipped = bounds.intersect(mask);
+        if clipped.is_empty() {
+            return None;
+        }
+        let clamp_x = |value: f32| (value.max(0.) as u32).min(self.width);
+        let clamp_y = |value: f32| (value.max(0.) as u32).min(self.height);
+        let range = PixelRange {
+            x: clamp_x(clipped.left().0.floor())..clamp_x(clipped.right().0.ceil()),
+            y: clamp_y(clipped.top().0.floor())..clamp_y(clipped.bottom().0.ceil()),
+        };
+        (!range.x.is_empty() && !range.y.is_empty()).then_some(range)
+    }
+
+    /// Composites a straight-alpha color over the pixel at the given position,
+    /// scaled by the given coverage.
+    fn blend(&mut self, x: u32, y: u32, color: Rgba, coverage: f32) {
+        let alpha = color.a * coverage.clamp(0., 1.);
+        if alpha <= 0. {
+            return;
+        }
+        let pixel = &mut self.pixels[(y * self.width + x) as usize];
+        let inverse = 1. - alpha;
+        pixel[0] = color.r * alpha + pixel[0] * inverse;
+        pixel[1] = color.g * alpha + pixel[1] * inverse;
+        pixel[2] = color.b * alpha + pixel[2] * inverse;
+        pixel[3] = alpha + pixel[3] * inverse;
+    }
+
+    fn draw_quad(&mut self, quad: &Quad) {
+        let Some(range) = self.pixel_range(&quad.bounds, &quad.content_mask.bounds) else {
+            return;
+        };
+        let inner_bounds = inset_bounds(&quad.bounds, &quad.border_widths);
+        let inner_radii = inset_corner_radii(&quad.corner_radii, &quad.border_widths);
+        let has_border = quad.border_widths.top.0 > 0.
+            || quad.border_widths.right.0 > 0.
+            || quad.border_widths.bottom.0 > 0.
+            || quad.border_widths.left.0 > 0.;
+        let border_color = quad.border_color.to_rgb();
+
+        for y in range.y.clone() {
+            for x in range.x.clone() {
+                let (px, py) = (x as f32 + 0.5, y as f32 + 0.5);
+                let outer_distance =
+                    rounded_rect_distance(px, py, &quad.bounds, &quad.corner_radii);
+                let coverage = (0.5 - outer_distance).clamp(0., 1.);
+                if coverage <= 0. {
+                    continue;
+                }
+
+                let background = background_color_at(&quad.background, &quad.bounds, px, py);
+                if has_border {
+                    let inner_coverage = if inner_bounds.is_empty() {
+                        0.
+                    } else {
+                        let inner_distance =
+                            rounded_rect_distance(px, py, &inner_bounds, &inner_radii);
+                        (0.5 - inner_distance).clamp(0., 1.)
+                    };
+                    self.blend(x, y, background, coverage * inner_coverage);
+                    self.blend(x, y, border_color, coverage * (1. - inner_coverage));
+                } else {
+                    self.blend(x, y, background, coverage);
+                }
+            }
+        }
+    }
+
+    fn draw_shadow(&mut self, shadow: &Shadow) {
+        let blur_radius = shadow.blur_radius.0.max(0.);
+        let mut visible_bounds = shadow.bounds;
+        visible_bounds.origin.x -= ScaledPixels(blur_radius * 3.);
+        visible_bounds.origin.y -= ScaledPixels(blur_radius * 3.);
+        visible_bounds.size.width += ScaledPixels(blur_radius * 6.);
+        visible_bounds.size.height += ScaledPixels(blur_radius * 6.);
+        let Some(range) = self.pixel_range(&visible_bounds, &shadow.content_mask.bounds) else {
+            return;
+        };
+
+        let color = shadow.color.to_rgb();
+        let sigma = blur_radius / 2.;
+        for y in range.y.clone() {
+            for x in range.x.clone() {
+                let (px, py) = (x as f32 + 0.5, y as f32 + 0.5);
+                let distance = rounded_rect_distance(px, py, &shadow.bounds, &shadow.corner_radii);
+                let coverage = if sigma > 0. {
+                    0.5 - 0.5 * erf(distance / (sigma * std::f32::consts::SQRT_2))
+                } else {
+                    (0.5 - distance).clamp(0., 1.)
+                };
+                self.blend(x, y, color, coverage);
+            }
+        }
+    }
+
+    fn draw_underline(&mut self, underline: &Underline) {
+        let Some(range) = self.pixel_range(&underline.bounds, &underline.content_mask.bounds)
+        else {
+            return;
+        };
+
+        let color = underline.color.to_rgb();
+        let thickness = underline.thickness.0.max(1.);
+        let origin = underline.bounds.origin;
+        let height = underline.bounds.size.height.0;
+        for y in range.y.clone() {
+            for x in range.x.clone() {
+                let (px, py) = (x as f32 + 0.5, y as f32 + 0.5);
+                let coverage = if underline.wavy {
+                    let frequency = std::f32::consts::PI * 3. * thickness / 8.;
+                    let amplitude = (height - thickness) / 2.;
+                    let center = origin.y.0 + height / 2.;
+                    let wave = center + ((px - origin.x.0) / height * frequency).sin() * amplitude;
+                    (thickness / 2. + 0.5 - (py - wave).abs()).clamp(0., 1.)
+                } else if py - origin.y.0 <= thickness {
+                    1.
+                } else {
+                    0.
+                };
+                self.blend(x, y, color, coverage);
+            }
+        }
+    }
+
+    fn draw_path(&mut self, path: &Path<ScaledPixels>) {
+        let Some(range) = self.pixel_range(&path.bounds, &path.content_mask.bounds) else {
+            return;
+        };
+
+        // Like the GPU path shaders, fill the pixels covered by an odd number of the path's
+        // triangles, so that the overlapping triangles of concave paths cancel out.
+        let width = range.x.len();
+        let mut coverage_counts = vec![0u32; width * range.y.len()];
+        for triangle in path.vertices.chunks_exact(3) {
+            let [a, mut b, mut c] = [&triangle[0], &triangle[1], &triangle[2]];
+            let position = |vertex: &PathVertex<ScaledPixels>| {
+                (vertex.xy_position.x.0, vertex.xy_position.y.0)
+            };
+            let mut area = edge_function(position(a), position(b), position(c));
+            if area == 0. {
+                continue;
+            }
+            if area < 0. {
+                std::mem::swap(&mut b, &mut c);
+                area = -area;
+            }
+            let edges = [
+                (position(b), position(c)),
+                (position(c), position(a)),
+                (position(a), position(b)),
+            ];
+
+            for (row, y) in range.y.clone().enumerate() {
+                for (column, x) in range.x.clone().enumerate() {
+                    let sample = (x as f32 + 0.5, y as f32 + 0.5);
+                    // Samples on an edge are only covered by the triangle whose top or left edge
+                    // it is, so that they aren't counted twice by the triangles sharing it.
+                    let weights = edges.map(|(from, to)| edge_function(from, to, sample) / area);
+                    if edges.iter().zip(weights).any(|(&(from, to), weight)| {
+                        weight < 0. || (weight == 0. && !is_top_left_edge(from, to))
+                    }) {
+                        continue;
+                    }
+
+                    // Curve triangles use the Loop-Blinn parameterization, where
+                    // the filled side satisfies s² - t <= 0.
+                    let [w0, w1, w2] = weights;
+                    let s = w0 * a.st_position.x + w1 * b.st_position.x + w2 * c.st_position.x;
+                    let t = w0 * a.st_position.y + w1 * b.st_position.y + w2 * c.st_position.y;
+                    if s * s - t <= 0. {
+                        coverage_counts[row * width + column] += 1;
+                    }
+                }
+            }
+        }
+
+        for (row, y) in range.y.clone().enumerate() {
+            for (column, x) in range.x.clone().enumerate() {
+                if coverage_counts[row * width + column] % 2 == 1 {
+                    let color = background_color_at(
+                        &path.color,
+                        &path.bounds,
+                        x as f32 + 0.5,
+                        y as f32 + 0.5,
+                    );
+                    self.blend(x, y, color, 1.);
+                }
+            }
+        }
+    }
+
+    fn draw_monochrome_sprite(
+        &mut self,
+        sprite: &MonochromeSprite,
+        texture_id: AtlasTextureId,
+        atlas: &TestAtlas,
+    ) {
+        let Some(range) = self.pixel_range(&sprite.bounds, &sprite.content_mask.bounds) else {
+            return;
+        };
+        let Some(texture) = atlas.texture(texture_id) else {
+            return;
+        };
+
+        let color = sprite.color.to_rgb();
+        let matrix = sprite.transformation;
+        let [[a, b], [c, d]] = matrix.rotation_scale;
+        let determinant = a * d - b * c;
+        if determinant == 0. {
+            return;
+        }
+
+        for y in range.y.clone() {
+            for x in range.x.clone() {
+                // Map the device pixel back into the untransformed sprite bounds.
+                let dx = x as f32 + 0.5 - matrix.translation[0];
+                let dy = y as f32 + 0.5 - matrix.translation[1];
+                let px = (d * dx - b * dy) / determinant;
+                let py = (a * dy - c * dx) / determinant;
+                let Some(alpha) =
+                    texture.sample(&sprite.bounds, px, py, AtlasTextureKind::Monochrome)
+                else {
+                    continue;
+                };
+                self.blend(x, y, color, alpha[3]);
+            }
+        }
+    }
+
+    fn draw_polychrome_sprite(
+        &mut self,
+        sprite: &PolychromeSprite,
+        texture_id: AtlasTextureId,
+        atlas: &TestAtlas,
+    ) {
+        let Some(range) = self.pixel_range(&sprite.bounds, &sprite.content_mask.bounds) else {
+            return;
+        };
+        let Some(texture) = atlas.texture(texture_id) else {
+            return;
+        };
+
+        for y in range.y.clone() {
+            for x in range.x.clone() {
+                let (px, py) = (x as f32 + 0.5, y as f32 + 0.5);
+                let distance = rounded_rect_distance(px, py, &sprite.bounds, &sprite.corner_radii);
+                let coverage = (0.5 - distance).clamp(0., 1.);
+                if coverage <= 0. {
+                    continue;
+                }
+                let Some([r, g, b, a]) =
+                    texture.sample(&sprite.bounds, px, py, AtlasTextureKind::Polychrome)
+                else {
+                    continue;
+                };
+                let mut color = Rgba { r, g, b, a };
+                if sprite.grayscale {
+                    let luminance = 0.2126 * r + 0.7152 * g + 0.0722 * b;
+                    color = Rgba {
+                        r: luminance,
+                        g: luminance,
+                        b: luminance,
+                        a,
+                    };
+                }
+                self.blend(x, y, color, coverage * sprite.opacity);
+            }
+        }
+    }
+}
+
+/// The contents of a tile stored in the [TestAtlas].
+pub(crate) struct TestAtlasTexture {
+    pub(crate) kind: AtlasTextureKind,
+    pub(crate) size: Size<DevicePixels>,
+    pub(crate) bytes: Vec<u8>,
+}
+
+impl TestAtlasTexture {
+    /// Samples the texel under the given point, stretching the texture over the
+    /// given bounds with nearest-neighbor filtering. Returns straight RGBA.
+    fn sample(
+        &self,
+        bounds: &Bounds<ScaledPixels>,
+        x: f32,
+        y: f32,
+        expected_kind: AtlasTextureKind,
+    ) -> Option<[f32; 4]> {
+        if self.kind != expected_kind || bounds.size.width.0 <= 0. || bounds.size.height.0 <= 0. {
+            return None;
+        }
+        let u = (x - bounds.origin.x.0) / bounds.size.width.0;
+        let v = (y - bounds.origin.y.0) / bounds.size.height.0;
+        if !(0. ..1.).contains(&u) || !(0. ..1.).contains(&v) {
+            return None;
+        }
+
+        let width = self.size.width.0 as usize;
+        let height = self.size.height.0 as usize;
+        let column = ((u * width as f32) as usize).min(width.saturating_sub(1));
+        let row = ((v * height as f32) as usize).min(height.saturating_sub(1));
+        match self.kind {
+            AtlasTextureKind::Monochrome | AtlasTextureKind::Path => {
+                let alpha = *self.bytes.get(row * width + column)?;
+                Some([1., 1., 1., alpha as f32 / 255.])
+            }
+            AtlasTextureKind::Polychrome => {
+                let offset = (row * width + column) * 4;
+                let [b, g, r, a] = self.bytes.get(offset..offset + 4)? else {
+                    return None;
+                };
+                Some([
+                    *r as f32 / 255.,
+                    *g as f32 / 255.,
+                    *b as f32 / 255.,
+                    *a as f32 / 255.,
+                ])
+            }
+        }
+    }
+}
+
+/// Twice the signed area of the triangle `from`, `to`, `point`, which is positive when `point` is
+/// to the right of the edge from `from` to `to`, in a coordinate space where y points down.
+fn edge_function(from: (f32, f32), to: (f32, f32), point: (f32, f32)) -> f32 {
+    (to.0 - from.0) * (point.1 - from.1) - (to.1 - from.1) * (point.0 - from.0)
+}
+
+/// Whether the edge of a triangle with a positive area is its top or one of its left edges,
+/// following the top-left rule used by GPUs to assign samples on shared edges to one triangle.
+fn is_top_left_edge(from: (f32, f32), to: (f32, f32)) -> bool {
+    let (dx, dy) = (to.0 - from.0, to.1 - from.1);
+    (dy == 0. && dx > 0.) || dy < 0.
+}
+
+fn background_color_at(
+    background: &Background,
+    bounds: &Bounds<ScaledPixels>,
+    x: f32,
+    y: f32,
+) -> Rgba {
+    match background.tag {
+        BackgroundTag::Solid => background.solid.to_rgb(),
+        BackgroundTag::LinearGradient => {
+            // An angle of 0 points to the top, and increasing angles rotate clockwise.
+            let radians = background.angle.to_radians();
+            let direction = (radians.sin(), -radians.cos());
+            let center = bounds.center();
+            let half_width = bounds.size.width.0 / 2.;
+            let half_height = bounds.size.height.0 / 2.;
+            let half_length = (half_width * direction.0).abs() + (half_height * direction.1).abs();
+            let projection = (x - center.x.0) * direction.0 + (y - center.y.0) * direction.1;
+            let position = if half_length > 0. {
+                (projection / half_length + 1.) / 2.
+            } else {
+                0.
+            };
+
+            let [from, to] = background.colors;
+            let span = to.percentage - from.percentage;
+            let t = if span > 0. {
+                ((position - from.percentage) / span).clamp(0., 1.)
+            } else if position < from.percentage {
+                0.
+            } else {
+                1.
+            };
+            mix(from.color, to.color, t)
+        }
+    }
+}
+
+fn mix(from: Hsla, to: Hsla, t: f32) -> Rgba {
+    let from = from.to_rgb();
+    let to = to.to_rgb();
+    Rgba {
+        r: from.r + (to.r - from.r) * t,
+        g: from.g + (to.g - from.g) * t,
+        b: from.b + (to.b - from.b) * t,
+        a: from.a + (to.a - from.a) * t,
+    }
+}
+
+/// Signed distance from the point to the edge of the rounded rectangle,
+/// negative inside.
+fn rounded_rect_distance(
+    x: f32,
+    y: f32,
+    bounds: &Bounds<ScaledPixels>,
+    corner_radii: &Corners<ScaledPixels>,
+) -> f32 {
+    let center = bounds.center();
+    let half_width = bounds.size.width.0 / 2.;
+    let half_height = bounds.size.height.0 / 2.;
+    let dx = x - center.x.0;
+    let dy = y - center.y.0;
+    let radius = match (dx < 0., dy < 0.) {
+        (true, true) => corner_radii.top_left.0,
+        (false, true) => corner_radii.top_right.0,
+        (false, false) => corner_radii.bottom_right.0,
+        (true, false) => corner_radii.bottom_left.0,
+    }
+    .min(half_width)
+    .min(half_height)
+    .max(0.);
+
+    let qx = dx.abs() - half_width + radius;
+    let qy = dy.abs() - half_height + radius;
+    let outside = (qx.max(0.).powi(2) + qy.max(0.).powi(2)).sqrt();
+    let inside = qx.max(qy).min(0.);
+    outside + inside - radius
+}
+
+fn inset_bounds(
+    bounds: &Bounds<ScaledPixels>,
+    insets: &Edges<ScaledPixels>,
+) -> Bounds<ScaledPixels> {
+    let mut inset = *bounds;
+    inset.origin.x += insets.left;
+    inset.origin.y += insets.top;
+    inset.size.width = ScaledPixels((bounds.size.width - insets.left - insets.right).0.max(0.));
+    inset.size.height = ScaledPixels((bounds.size.height - insets.top - insets.bottom).0.max(0.));
+    inset
+}
+
+fn inset_corner_radii(
+    radii: &Corners<ScaledPixels>,
+    insets: &Edges<ScaledPixels>,
+) -> Corners<ScaledPixels> {
+    let inset = |radius: ScaledPixels, a: ScaledPixels, b: ScaledPixels| {
+        ScaledPixels((radius.0 - a.0.max(b.0)).max(0.))
+    };
+    Corners {
+        top_left: inset(radii.top_left, insets.top, insets.left),
+        top_right: inset(radii.top_right, insets.top, insets.right),
+        bottom_right: inset(radii.bottom_right, insets.bottom, insets.right),
+        bottom_left: inset(radii.bottom_left, insets.bottom, insets.left),
+    }
+}
+
+/// Abramowitz and Stegun approximation of the error function.
+fn erf(x: f32) -> f32 {
+    let sign = x.signum();
+    let x = x.abs();
+    let t = 1. / (1. + 0.3275911 * x);
+    let polynomial =
+        t * (0.2548296 + t * (-0.28449672 + t * (1.4214138 + t * (-1.4531521 + t * 1.0614054))));
+    sign * (1. - polynomial * (-x * x).exp())
+}
+
+fn to_u8(channel: f32) -> u8 {
+    (channel.clamp(0., 1.) * 255.).round() as u8
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        black, blue, div, point, px, red, size, white, ContentMask, IntoElement, ParentElement,
+        Point, Render, Styled, TestAppContext, ViewContext,
+    };
+
+    fn scaled_bounds(x: f32, y: f32, width: f32, height: f32) -> Bounds<ScaledPixels> {
+        Bounds {
+            origin: point(ScaledPixels(x), ScaledPixels(y)),
+            size: size(ScaledPixels(width), ScaledPixels(height)),
+        }
+    }
+
+    #[test]
+    fn test_rasterize_quads() {
+        let mut scene = Scene::default();
+        let full_mask = ContentMask {
+            bounds: scaled_bounds(0., 0., 10., 10.),
+        };
+        scene.insert_primitive(Quad {
+            order: 0,
+            pad: 0,
+            bounds: scaled_bounds(2., 2., 4., 4.),
+            content_mask: full_mask.clone(),
+            background: Hsla::red().into(),
+            border_color: Hsla::blue(),
+            corner_radii: Corners::default(),
+            border_widths: Edges {
+                top: ScaledPixels(1.),
+                right: ScaledPixels(1.),
+                bottom: ScaledPixels(1.),
+                left: ScaledPixels(1.),
+            },
+        });
+        // Clipped by its content mask to the left half of the window.
+        scene.insert_primitive(Quad {
+            order: 0,
+            pad: 0,
+            bounds: scaled_bounds(0., 8., 10., 2.),
+            content_mask: ContentMask {
+                bounds: scaled_bounds(0., 0., 5., 10.),
+            },
+            background: Hsla::black().into(),
+            border_color: Hsla::default(),
+            corner_radii: Corners::default(),
+            border_widths: Edges::default(),
+        });
+        scene.finish();
+
+        let image = rasterize_scene(
+            &scene,
+            size(DevicePixels(10), DevicePixels(10)),
+            &TestAtlas::new(),
+        );
+        assert_eq!(image.get_pixel(0, 0).0, [0, 0, 0, 0]);
+        assert_eq!(image.get_pixel(2, 2).0, [0, 0, 255, 255]);
+        assert_eq!(image.get_pixel(3, 3).0, [255, 0, 0, 255]);
+        assert_eq!(image.get_pixel(5, 5).0, [0, 0, 255, 255]);
+        assert_eq!(image.get_pixel(1, 9).0, [0, 0, 0, 255]);
+        assert_eq!(image.get_pixel(7, 9).0, [0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn test_rasterize_paths() {
+        let mut path = Path::new(point(px(0.), px(0.)));
+        path.line_to(point(px(8.), px(0.)));
+        path.line_to(point(px(0.), px(8.)));
+        path.content_mask = ContentMask {
+            bounds: Bounds {
+                origin: Point::default(),
+                size: size(px(8.), px(8.)),
+            },
+        };
+        path.color = Hsla::black().into();
+
+        let mut scene = Scene::default();
+        scene.insert_primitive(path.scale(1.));
+        scene.finish();
+
+        let image = rasterize_scene(
+            &scene,
+            size(DevicePixels(8), DevicePixels(8)),
+            &TestAtlas::new(),
+        );
+        assert_eq!(image.get_pixel(1, 1).0, [0, 0, 0, 255]);
+        assert_eq!(image.get_pixel(6, 6).0, [0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn test_rasterize_concave_path() {
+        // An L-shape, whose triangles fanning out from the start overlap in the notch.
+        let mut path = Path::new(point(px(11.), px(1.)));
+        for (x, y) in [
+            (11., 5.),
+            (5., 5.),
+            (5., 11.),
+            (1., 11.),
+            (1., 1.),
+            (11., 1.),
+        ] {
+            path.line_to(point(px(x), px(y)));
+        }
+        path.content_mask = ContentMask {
+            bounds: Bounds {
+                origin: Point::default(),
+                size: size(px(12.), px(12.)),
+            },
+        };
+        path.color = Hsla::black().into();
+
+        let mut scene = Scene::default();
+        scene.insert_primitive(path.scale(1.));
+        scene.finish();
+
+        let image = rasterize_scene(
+            &scene,
+            size(DevicePixels(12), DevicePixels(12)),
+            &TestAtlas::new(),
+        );
+        assert_eq!(image.get_pixel(2, 9).0, [0, 0, 0, 255]);
+        assert_eq!(image.get_pixel(9, 2).0, [0, 0, 0, 255]);
+        assert_eq!(image.get_pixel(6, 6).0, [0, 0, 0, 0]);
+        crate::assert_image_matches_snapshot(
+            &image,
+            concat!(
+                env!("CARGO_MANIFEST_DIR"),
+                "/src/platform/test/snapshots/concave_path.png"
+            ),
+        );
+    }
+
+    struct Squares;
+
+    impl Render for Squares {
+        fn render(&mut self, _cx: &mut ViewContext<Self>) -> impl IntoElement {
+            div()
+                .flex()
+                .size_full()
+                .p(px(4.))
+                .gap(px(4.))
+                .bg(white())
+                .child(
+                    div()
+                        .size(px(12.))
+                        .bg(red())
+                        .border_2()
+                        .border_color(blue()),
+                )
+                .child(div().size(px(12.)).bg(black()))
+        }
+    }
+
+    #[crate::test]
+    fn test_window_snapshot(cx: &mut TestAppContext) {
+        let (_, cx) = cx.add_window_view(|_| Squares);
+        cx.simulate_resize(size(px(36.), px(20.)));
+        cx.assert_snapshot(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/src/platform/test/snapshots/squares.png"
+        ));
+    }
+}
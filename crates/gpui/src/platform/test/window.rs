@@ -1,10 +1,12 @@
+use super::rasterizer::{rasterize_scene, TestAtlasTexture};
 use crate::{
-    AnyWindowHandle, AtlasKey, AtlasTextureId, AtlasTile, Bounds, DispatchEventResult, GPUSpecs,
-    Pixels, PlatformAtlas, PlatformDisplay, PlatformInput, PlatformInputHandler, PlatformWindow,
-    Point, RequestFrameOptions, ScaledPixels, Size, TestPlatform, TileId, WindowAppearance,
-    WindowBackgroundAppearance, WindowBounds, WindowParams,
+    AnyWindowHandle, AtlasKey, AtlasTextureId, AtlasTile, Bounds, DevicePixels,
+    DispatchEventResult, GPUSpecs, Pixels, PlatformAtlas, PlatformDisplay, PlatformInput,
+    PlatformInputHandler, PlatformWindow, Point, RequestFrameOptions, ScaledPixels, Scene, Size,
+    TestPlatform, TileId, WindowAppearance, WindowBackgroundAppearance, WindowBounds, WindowParams,
 };
 use collections::HashMap;
+use image::RgbaImage;
 use parking_lot::Mutex;
 use raw_window_handle::{HasDisplayHandle, HasWindowHandle};
 use std::{
@@ -19,7 +21,7 @@ pub(crate) struct TestWindowState {
     pub(crate) title: Option<String>,
     pub(crate) edited: bool,
     platform: Weak<TestPlatform>,
-    sprite_atlas: Arc<dyn PlatformAtlas>,
+    sprite_atlas: Arc<TestAtlas>,
     pub(crate) should_close_handler: Option<Box<dyn FnMut() -> bool>>,
     input_callback: Option<Box<dyn FnMut(PlatformInput) -> DispatchEventResult>>,
     active_status_change_callback: Option<Box<dyn FnMut(bool)>>,
@@ -87,6 +89,20 @@ impl TestWindow {
         self.0.lock().resize_callback = Some(callback);
     }
 
+    /// Rasterizes the given scene on the CPU at this window's device size.
+    pub(crate) fn rasterize(&self, scene: &Scene) -> RgbaImage {
+        let scale_factor = self.scale_factor();
+        let (size, atlas) = {
+            let lock = self.0.lock();
+            (lock.bounds.size, lock.sprite_atlas.clone())
+        };
+        let device_size = Size {
+            width: DevicePixels((size.width.0 * scale_factor).ceil() as i32),
+            height: DevicePixels((size.height.0 * scale_factor).ceil() as i32),
+        };
+        rasterize_scene(scene, device_size, &atlas)
+    }
+
     pub(crate) fn simulate_active_status_change(&self, active: bool) {
         let mut lock = self.0.lock();
         let Some(mut callback) = lock.active_status_change_callback.take() else {
@@ -284,6 +300,7 @@ impl PlatformWindow for TestWindow {
 pub(crate) struct TestAtlasState {
     next_id: u32,
     tiles: HashMap<AtlasKey, AtlasTile>,
+    textures: HashMap<AtlasTextureId, Arc<TestAtlasTexture>>,
 }
 
 pub(crate) struct TestAtlas(Mutex<TestAtlasState>);
@@ -293,8 +310,14 @@ impl TestAtlas {
         TestAtlas(Mutex::new(TestAtlasState {
             next_id: 0,
             tiles: HashMap::default(),
+            textures: HashMap::default(),
         }))
     }
+
+    /// Returns the contents of the texture with the given id, if it's still in the atlas.
+    pub(crate) fn texture(&self, texture_id: AtlasTextureId) -> Option<Arc<TestAtlasTexture>> {
+        self.0.lock().textures.get(&texture_id).cloned()
+    }
 }
 
 impl PlatformAtlas for TestAtlas {
@@ -311,7 +334,7 @@ impl PlatformAtlas for TestAtlas {
         }
         drop(state);
 
-        let Some((size, _)) = build()? else {
+        let Some((size, bytes)) = build()? else {
             return Ok(None);
         };
 
@@ -320,14 +343,23 @@ impl PlatformAtlas for TestAtlas {
         let texture_id = state.next_id;
         state.next_id += 1;
         let tile_id = state.next_id;
+        let texture_id = AtlasTextureId {
+            index: texture_id,
+            kind: key.texture_kind(),
+        };
 
+        state.textures.insert(
+            texture_id,
+            Arc::new(TestAtlasTexture {
+                kind: texture_id.kind,
+                size,
+                bytes: bytes.into_owned(),
+            }),
+        );
         state.tiles.insert(
             key.clone(),
             crate::AtlasTile {
-                texture_id: AtlasTextureId {
-                    index: texture_id,
-                    kind: crate::AtlasTextureKind::Path,
-                },
+                texture_id,
                 tile_id: TileId(tile_id),
                 padding: 0,
                 bounds: crate::Bounds {
@@ -342,6 +374,8 @@ impl PlatformAtlas for TestAtlas {
 
     fn remove(&self, key: &AtlasKey) {
         let mut state = self.0.lock();
-        state.tiles.remove(key);
+        if let Some(tile) = state.tiles.remove(key) {
+            state.textures.remove(&tile.texture_id);
+        }
     }
 }
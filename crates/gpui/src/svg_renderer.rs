@@ -1,7 +1,7 @@
 use crate::{AssetSource, DevicePixels, IsZero, Result, SharedString, Size};
 use anyhow::anyhow;
 use resvg::tiny_skia::Pixmap;
-use std::{hash::Hash, sync::Arc};
+use std::{borrow::Cow, hash::Hash, path::Path, sync::Arc};
 
 #[derive(Clone, PartialEq, Hash, Eq)]
 pub(crate) struct RenderSvgParams {
@@ -30,8 +30,14 @@ impl SvgRenderer {
         }
 
         // Load the tree.
-        let Some(bytes) = self.asset_source.load(&params.path)? else {
-            return Ok(None);
+        let path = Path::new(params.path.as_ref());
+        let bytes = if path.is_absolute() {
+            Cow::Owned(std::fs::read(path)?)
+        } else {
+            let Some(bytes) = self.asset_source.load(&params.path)? else {
+                return Ok(None);
+            };
+            bytes
         };
 
         let pixmap = self.render_pixmap(&bytes, SvgSize::Size(params.size))?;
@@ -27,11 +27,13 @@
 //! ```
 use crate::{Entity, Subscription, TestAppContext, TestDispatcher};
 use futures::StreamExt as _;
+use image::RgbaImage;
 use rand::prelude::*;
 use smol::channel;
 use std::{
     env,
     panic::{self, RefUnwindSafe},
+    path::Path,
 };
 
 /// Run the given test function with the configured parameters.
@@ -111,3 +113,141 @@ pub fn observe<T: 'static>(entity: &impl Entity<T>, cx: &mut TestAppContext) ->
 
     Observation { rx, _subscription }
 }
+
+/// How far an image rendered by the test platform may stray from its golden snapshot.
+///
+/// Anti-aliasing and glyph rasterization differ slightly between platforms, so snapshots are
+/// compared per channel rather than for exact equality. Goldens containing text are still
+/// rendered by the platform's text system and usually need a looser tolerance than the default.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SnapshotTolerance {
+    /// The largest difference in any channel for which two pixels are still considered equal.
+    pub max_channel_difference: u8,
+    /// The fraction of pixels, between 0 and 1, that may differ by more than
+    /// `max_channel_difference` before the comparison fails.
+    pub max_differing_pixels: f32,
+}
+
+impl Default for SnapshotTolerance {
+    fn default() -> Self {
+        Self {
+            max_channel_difference: 8,
+            max_differing_pixels: 0.001,
+        }
+    }
+}
+
+/// Compares an image rendered by the test platform against the golden PNG at the given path,
+/// using the default [SnapshotTolerance].
+///
+/// When the `UPDATE_SNAPSHOTS` environment variable is set, or the golden doesn't exist yet
+/// outside of CI, the golden is (re)written instead. On mismatch, the rendered image is written
+/// next to the golden with an `.actual.png` extension so the two can be compared.
+pub fn assert_image_matches_snapshot(image: &RgbaImage, path: impl AsRef<Path>) {
+    assert_image_matches_snapshot_with_tolerance(image, path, SnapshotTolerance::default());
+}
+
+/// Like [assert_image_matches_snapshot], but with a custom tolerance.
+pub fn assert_image_matches_snapshot_with_tolerance(
+    image: &RgbaImage,
+    path: impl AsRef<Path>,
+    tolerance: SnapshotTolerance,
+) {
+    let path = path.as_ref();
+    let update = env::var("UPDATE_SNAPSHOTS").map_or(false, |value| !value.is_empty());
+    if update || (!path.exists() && env::var("CI").is_err()) {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).expect("failed to create snapshot directory");
+        }
+        image
+            .save(path)
+            .unwrap_or_else(|error| panic!("failed to write snapshot {path:?}: {error}"));
+        return;
+    }
+
+    let expected = image::open(path)
+        .unwrap_or_else(|error| panic!("failed to read snapshot {path:?}: {error}"))
+        .into_rgba8();
+    let actual_path = path.with_extension("actual.png");
+    let mismatch = image_mismatch(&expected, image, tolerance);
+
+    if let Some(mismatch) = mismatch {
+        image.save(&actual_path).ok();
+        panic!(
+            "rendered image doesn't match snapshot {path:?}: {mismatch}. \
+             The rendered image was written to {actual_path:?}; \
+             rerun with UPDATE_SNAPSHOTS=1 to accept it."
+        );
+    } else {
+        std::fs::remove_file(&actual_path).ok();
+    }
+}
+
+/// Describes how the actual image differs from the expected one, if by more than the tolerance.
+fn image_mismatch(
+    expected: &RgbaImage,
+    actual: &RgbaImage,
+    tolerance: SnapshotTolerance,
+) -> Option<String> {
+    if expected.dimensions() != actual.dimensions() {
+        return Some(format!(
+            "expected size {:?}, got {:?}",
+            expected.dimensions(),
+            actual.dimensions()
+        ));
+    }
+
+    let mut differing_pixels = 0;
+    let mut max_difference = 0;
+    for (expected, actual) in expected.pixels().zip(actual.pixels()) {
+        let difference = expected
+            .0
+            .iter()
+            .zip(actual.0.iter())
+            .map(|(expected, actual)| expected.abs_diff(*actual))
+            .max()
+            .unwrap_or(0);
+        if difference > tolerance.max_channel_difference {
+            differing_pixels += 1;
+            max_difference = max_difference.max(difference);
+        }
+    }
+
+    let pixel_count = (expected.width() as usize * expected.height() as usize).max(1);
+    let allowed_pixels = (pixel_count as f32 * tolerance.max_differing_pixels) as usize;
+    (differing_pixels > allowed_pixels).then(|| {
+        format!(
+            "{differing_pixels} pixels differ by up to {max_difference} \
+             (at most {allowed_pixels} may differ by more than {})",
+            tolerance.max_channel_difference
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::Rgba;
+
+    #[test]
+    fn test_image_mismatch_tolerance() {
+        let expected = RgbaImage::from_pixel(10, 10, Rgba([100, 100, 100, 255]));
+        let tolerance = SnapshotTolerance {
+            max_channel_difference: 4,
+            max_differing_pixels: 0.01,
+        };
+
+        let mut actual = RgbaImage::from_pixel(10, 10, Rgba([104, 96, 100, 255]));
+        assert_eq!(image_mismatch(&expected, &actual, tolerance), None);
+
+        // A single pixel out of a hundred may differ by more than the per-channel tolerance.
+        actual.put_pixel(0, 0, Rgba([0, 0, 0, 255]));
+        assert_eq!(image_mismatch(&expected, &actual, tolerance), None);
+
+        actual.put_pixel(1, 0, Rgba([100, 100, 110, 255]));
+        assert!(image_mismatch(&expected, &actual, tolerance).is_some());
+
+        let smaller = RgbaImage::from_pixel(5, 10, Rgba([100, 100, 100, 255]));
+        assert!(image_mismatch(&expected, &smaller, tolerance).is_some());
+    }
+}
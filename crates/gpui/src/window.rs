@@ -1,20 +1,21 @@
 use crate::{
-    point, prelude::*, px, size, transparent_black, Action, AnyDrag, AnyElement, AnyTooltip,
-    AnyView, AppContext, Arena, Asset, AsyncWindowContext, AvailableSpace, Background, Bounds,
-    BoxShadow, Context, Corners, CursorStyle, Decorations, DevicePixels, DispatchActionListener,
-    DispatchNodeId, DispatchTree, DisplayId, Edges, Effect, Entity, EntityId, EventEmitter,
-    FileDropEvent, Flatten, FontId, GPUSpecs, Global, GlobalElementId, GlyphId, Hsla, InputHandler,
-    IsZero, KeyBinding, KeyContext, KeyDownEvent, KeyEvent, Keystroke, KeystrokeEvent,
-    KeystrokeObserver, LayoutId, LineLayoutIndex, Model, ModelContext, Modifiers,
-    ModifiersChangedEvent, MonochromeSprite, MouseButton, MouseEvent, MouseMoveEvent, MouseUpEvent,
-    Path, Pixels, PlatformAtlas, PlatformDisplay, PlatformInput, PlatformInputHandler,
-    PlatformWindow, Point, PolychromeSprite, PromptLevel, Quad, Render, RenderGlyphParams,
-    RenderImage, RenderImageParams, RenderSvgParams, Replay, ResizeEdge, ScaledPixels, Scene,
-    Shadow, SharedString, Size, StrikethroughStyle, Style, SubscriberSet, Subscription,
-    TaffyLayoutEngine, Task, TextStyle, TextStyleRefinement, TransformationMatrix, Underline,
-    UnderlineStyle, View, VisualContext, WeakView, WindowAppearance, WindowBackgroundAppearance,
-    WindowBounds, WindowControls, WindowDecorations, WindowOptions, WindowParams, WindowTextSystem,
-    SUBPIXEL_VARIANTS,
+    point, prelude::*, px, size, transparent_black, AccessibilityAction, AccessibilityNodeId,
+    AccessibilityNodeRequest, AccessibilityOperations, AccessibilityTree, Action, AnyDrag,
+    AnyElement, AnyTooltip, AnyView, AppContext, Arena, Asset, AsyncWindowContext, AvailableSpace,
+    Background, Bounds, BoxShadow, ClickEvent, Context, Corners, CursorStyle, Decorations,
+    DevicePixels, DispatchActionListener, DispatchNodeId, DispatchTree, DisplayId, Edges, Effect,
+    Entity, EntityId, EventEmitter, FileDropEvent, Flatten, FontId, GPUSpecs, Global,
+    GlobalElementId, GlyphId, Hsla, InputHandler, IsZero, KeyBinding, KeyContext, KeyDownEvent,
+    KeyEvent, Keystroke, KeystrokeEvent, KeystrokeObserver, LayoutId, LineLayoutIndex, Model,
+    ModelContext, Modifiers, ModifiersChangedEvent, MonochromeSprite, MouseButton, MouseDownEvent,
+    MouseEvent, MouseMoveEvent, MouseUpEvent, Path, Pixels, PlatformAtlas, PlatformDisplay,
+    PlatformInput, PlatformInputHandler, PlatformWindow, Point, PolychromeSprite, PromptLevel,
+    Quad, Render, RenderGlyphParams, RenderImage, RenderImageParams, RenderSvgParams, Replay,
+    ResizeEdge, ScaledPixels, Scene, Shadow, SharedString, Size, StrikethroughStyle, Style,
+    SubscriberSet, Subscription, TaffyLayoutEngine, Task, TextStyle, TextStyleRefinement,
+    TransformationMatrix, Underline, UnderlineStyle, View, VisualContext, WeakView,
+    WindowAppearance, WindowBackgroundAppearance, WindowBounds, WindowControls, WindowDecorations,
+    WindowOptions, WindowParams, WindowTextSystem, SUBPIXEL_VARIANTS,
 };
 use anyhow::{anyhow, Context as _, Result};
 use collections::{FxHashMap, FxHashSet};
@@ -403,6 +404,7 @@ pub(crate) struct Frame {
     pub(crate) mouse_listeners: Vec<Option<AnyMouseListener>>,
     pub(crate) dispatch_tree: DispatchTree,
     pub(crate) scene: Scene,
+    pub(crate) accessibility: AccessibilityOperations,
     pub(crate) hitboxes: Vec<Hitbox>,
     pub(crate) deferred_draws: Vec<DeferredDraw>,
     pub(crate) input_handlers: Vec<Option<PlatformInputHandler>>,
@@ -425,6 +427,7 @@ pub(crate) struct PrepaintStateIndex {
 #[derive(Clone, Default)]
 pub(crate) struct PaintIndex {
     scene_index: usize,
+    accessibility_index: usize,
     mouse_listeners_index: usize,
     input_handlers_index: usize,
     cursor_styles_index: usize,
@@ -442,6 +445,7 @@ impl Frame {
             mouse_listeners: Vec::new(),
             dispatch_tree,
             scene: Scene::default(),
+            accessibility: AccessibilityOperations::default(),
             hitboxes: Vec::new(),
             deferred_draws: Vec::new(),
             input_handlers: Vec::new(),
@@ -459,6 +463,7 @@ impl Frame {
         self.mouse_listeners.clear();
         self.dispatch_tree.clear();
         self.scene.clear();
+        self.accessibility.clear();
         self.input_handlers.clear();
         self.tooltip_requests.clear();
         self.cursor_styles.clear();
@@ -779,6 +784,16 @@ impl Window {
                     .unwrap_or(DispatchEventResult::default())
             })
         });
+        platform_window.on_accessibility_action({
+            let mut cx = cx.to_async();
+            Box::new(move |node_id, action| {
+                handle
+                    .update(&mut cx, |_, cx| {
+                        cx.perform_accessibility_action(node_id, action)
+                    })
+                    .log_err();
+            })
+        });
 
         if let Some(app_id) = app_id {
             platform_window.set_app_id(&app_id);
@@ -1489,6 +1504,11 @@ impl<'a> WindowContext<'a> {
                 .retain(&(), |listener| listener(&event, self));
         }
 
+        if self.window.platform_window.accessibility_enabled() {
+            let tree = self.accessibility_tree();
+            self.window.platform_window.update_accessibility_tree(tree);
+        }
+
         self.reset_cursor_style();
         self.window.refreshing = false;
         self.window.draw_phase = DrawPhase::None;
@@ -1727,6 +1747,7 @@ impl<'a> WindowContext<'a> {
     pub(crate) fn paint_index(&self) -> PaintIndex {
         PaintIndex {
             scene_index: self.window.next_frame.scene.len(),
+            accessibility_index: self.window.next_frame.accessibility.len(),
             mouse_listeners_index: self.window.next_frame.mouse_listeners.len(),
             input_handlers_index: self.window.next_frame.input_handlers.len(),
             cursor_styles_index: self.window.next_frame.cursor_styles.len(),
@@ -1775,6 +1796,98 @@ impl<'a> WindowContext<'a> {
             range.start.scene_index..range.end.scene_index,
             &window.rendered_frame.scene,
         );
+        window.next_frame.accessibility.replay(
+            range.start.accessibility_index..range.end.accessibility_index,
+            &window.rendered_frame.accessibility,
+        );
+    }
+
+    /// Invoke the given function with the given accessibility node pushed, so that
+    /// the nodes painted by the function become its children. This method should
+    /// only be called as part of the paint phase of element drawing.
+    pub(crate) fn with_accessibility_node<R>(
+        &mut self,
+        request: Option<AccessibilityNodeRequest>,
+        f: impl FnOnce(&mut Self) -> R,
+    ) -> R {
+        debug_assert_eq!(
+            self.window.draw_phase,
+            DrawPhase::Paint,
+            "this method can only be called during paint"
+        );
+
+        let Some(request) = request else {
+            return f(self);
+        };
+        self.window.next_frame.accessibility.start_node(request);
+        let result = f(self);
+        self.window.next_frame.accessibility.end_node();
+        result
+    }
+
+    /// Returns the accessibility tree of the most recently rendered frame.
+    pub fn accessibility_tree(&self) -> AccessibilityTree {
+        self.window
+            .rendered_frame
+            .accessibility
+            .build_tree(self.window.focus)
+    }
+
+    /// Perform an action requested by an assistive technology on the node with the given id.
+    /// Clicks, and actions that toggle an element, invoke the click handlers of the element
+    /// that painted the node.
+    pub fn perform_accessibility_action(
+        &mut self,
+        node_id: AccessibilityNodeId,
+        action: AccessibilityAction,
+    ) {
+        let tree = self.accessibility_tree();
+        let Some(node) = tree.node(node_id) else {
+            return;
+        };
+        if !node.actions.contains(&action) {
+            return;
+        }
+
+        match action {
+            AccessibilityAction::Focus => {
+                if let Some(handle) = node
+                    .focus_id
+                    .and_then(|id| FocusHandle::for_id(id, &self.window.focus_handles))
+                {
+                    self.focus(&handle);
+                }
+            }
+            AccessibilityAction::Click
+            | AccessibilityAction::Expand
+            | AccessibilityAction::Collapse => {
+                let position = node.bounds.center();
+                let modifiers = self.modifiers();
+                let event = ClickEvent {
+                    down: MouseDownEvent {
+                        button: MouseButton::Left,
+                        position,
+                        modifiers,
+                        click_count: 1,
+                        first_mouse: false,
+                    },
+                    up: MouseUpEvent {
+                        button: MouseButton::Left,
+                        position,
+                        modifiers,
+                        click_count: 1,
+                    },
+                };
+                let listeners = self
+                    .window
+                    .rendered_frame
+                    .accessibility
+                    .click_listeners(node_id);
+                for listener in listeners {
+                    listener(&event, self);
+                }
+            }
+        }
     }
 
     /// Push a text style onto the stack, and call a function with that style active.
@@ -2545,6 +2658,7 @@ impl<'a> WindowContext<'a> {
                     order: 0,
                     pad: 0,
                     grayscale: false,
+                    pixelated: false,
                     bounds,
                     corner_radii: Default::default(),
                     content_mask,
@@ -2625,6 +2739,7 @@ impl<'a> WindowContext<'a> {
         data: Arc<RenderImage>,
         frame_index: usize,
         grayscale: bool,
+        pixelated: bool,
     ) -> Result<()> {
         debug_assert_eq!(
             self.window.draw_phase,
@@ -2663,6 +2778,7 @@ impl<'a> WindowContext<'a> {
                 order: 0,
                 pad: 0,
                 grayscale,
+                pixelated,
                 bounds,
                 content_mask,
                 corner_radii,
@@ -0,0 +1,605 @@
+use std::path::Path;
+use std::sync::Arc;
+
+use anyhow::Result;
+use gpui::{
+    canvas, img, size, AnyElement, AppContext, Bounds, EventEmitter, FocusHandle, FocusableView,
+    ImageSource, Model, MouseButton, MouseDownEvent, MouseMoveEvent, ObjectFit, Point, Render,
+    RenderImage, Task, View, VisualContext,
+};
+use project::{image_store::create_gpui_image, Project, ProjectPath};
+use ui::prelude::*;
+use util::ResultExt;
+use workspace::{
+    item::{BreadcrumbText, Item, ItemEvent, TabContentParams},
+    OpenImageDiff, ToolbarItemLocation, Workspace,
+};
+
+use crate::{image_bounds, paint_checkerboard, Zoom};
+
+/// The file extensions of the images that can be compared.
+const DIFFABLE_IMAGE_EXTENSIONS: &[&str] = &[
+    "png", "jpg", "jpeg", "webp", "gif", "bmp", "tif", "tiff", "svg",
+];
+
+/// Returns whether the file at the given path is an image that can be shown in an [`ImageDiffView`].
+pub fn is_diffable_image(path: &Path) -> bool {
+    path.extension()
+        .and_then(|extension| extension.to_str())
+        .map_or(false, |extension| {
+            DIFFABLE_IMAGE_EXTENSIONS.contains(&extension.to_lowercase().as_str())
+        })
+}
+
+pub fn init(cx: &mut AppContext) {
+    cx.observe_new_views(|workspace: &mut Workspace, _| {
+        workspace.register_action(ImageDiffView::deploy);
+    })
+    .detach();
+}
+
+/// The version of an image that the working copy is compared against.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DiffBase {
+    Head,
+    Index,
+}
+
+impl DiffBase {
+    fn label(self) -> &'static str {
+        match self {
+            DiffBase::Head => "HEAD",
+            DiffBase::Index => "Index",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ImageDiffMode {
+    /// The two images are shown next to each other.
+    SideBySide,
+    /// The images are overlaid, with a divider that reveals one or the other.
+    Swipe,
+    /// The working copy is blended over the base image.
+    OnionSkin,
+}
+
+enum DiffImage {
+    Loading,
+    /// The image does not exist in this version, e.g. because it was added or deleted.
+    Missing,
+    Loaded(Arc<RenderImage>),
+    Error(SharedString),
+}
+
+impl DiffImage {
+    fn image(&self) -> Option<&Arc<RenderImage>> {
+        match self {
+            DiffImage::Loaded(image) => Some(image),
+            _ => None,
+        }
+    }
+}
+
+/// Compares the working copy of an image with its version in git.
+pub struct ImageDiffView {
+    project: Model<Project>,
+    project_path: ProjectPath,
+    focus_handle: FocusHandle,
+    base: DiffBase,
+    mode: ImageDiffMode,
+    base_image: DiffImage,
+    working_image: DiffImage,
+    /// The position of the swipe divider, or the opacity of the working copy in onion-skin mode,
+    /// from `0.0` to `1.0`.
+    blend: f32,
+    /// The bounds of the area that the images are shown in, as of the last frame.
+    viewport: Bounds<Pixels>,
+    dragging: bool,
+    load_task: Task<()>,
+}
+
+impl ImageDiffView {
+    fn deploy(workspace: &mut Workspace, action: &OpenImageDiff, cx: &mut ViewContext<Workspace>) {
+        let project = workspace.project().clone();
+        let Some(project_path) = project.read(cx).find_project_path(&action.abs_path, cx) else {
+            return;
+        };
+
+        let existing = workspace
+            .items_of_type::<ImageDiffView>(cx)
+            .find(|view| view.read(cx).project_path == project_path);
+        if let Some(existing) = existing {
+            workspace.activate_item(&existing, true, true, cx);
+            return;
+        }
+
+        let view = cx.new_view(|cx| ImageDiffView::new(project, project_path, cx));
+        workspace.add_item_to_active_pane(Box::new(view), None, true, cx);
+    }
+
+    pub fn new(
+        project: Model<Project>,
+        project_path: ProjectPath,
+        cx: &mut ViewContext<Self>,
+    ) -> Self {
+        cx.subscribe(&project, |this, _, event, cx| match event {
+            project::Event::WorktreeUpdatedEntries(worktree_id, changes)
+                if *worktree_id == this.project_path.worktree_id
+                    && changes
+                        .iter()
+                        .any(|(path, _, _)| *path == this.project_path.path) =>
+            {
+                this.load(cx);
+            }
+            project::Event::WorktreeUpdatedGitRepositories(worktree_id)
+                if *worktree_id == this.project_path.worktree_id =>
+            {
+                this.load(cx);
+            }
+            _ => {}
+        })
+        .detach();
+
+        let mut this = Self {
+            project,
+            project_path,
+            focus_handle: cx.focus_handle(),
+            base: DiffBase::Head,
+            mode: ImageDiffMode::SideBySide,
+            base_image: DiffImage::Loading,
+            working_image: DiffImage::Loading,
+            blend: 0.5,
+            viewport: Bounds::default(),
+            dragging: false,
+            load_task: Task::ready(()),
+        };
+        this.load(cx);
+        this
+    }
+
+    fn load(&mut self, cx: &mut ViewContext<Self>) {
+        let Some(worktree) = self
+            .project
+            .read(cx)
+            .worktree_for_id(self.project_path.worktree_id, cx)
+        else {
+            return;
+        };
+
+        let path = self.project_path.path.clone();
+        let base_bytes = match self.base {
+            DiffBase::Head => worktree.read(cx).load_committed_binary_file(&path, cx),
+            DiffBase::Index => worktree.read(cx).load_staged_binary_file(&path, cx),
+        };
+        let working_bytes = if worktree.read(cx).entry_for_path(&path).is_some() {
+            Some(worktree.update(cx, |worktree, cx| worktree.load_binary_file(&path, cx)))
+        } else {
+            None
+        };
+        let svg_renderer = cx.svg_renderer();
+
+        self.load_task = cx.spawn(|this, mut cx| async move {
+            let base_bytes = base_bytes.await;
+            let working_bytes = match working_bytes {
+                Some(task) => task.await.map(|file| Some(file.content)),
+                None => Ok(None),
+            };
+
+            let (base_image, working_image) = cx
+                .background_executor()
+                .spawn(async move {
+                    let decode = |bytes: Result<Option<Vec<u8>>>| {
+                        let image = match bytes {
+                            Ok(Some(bytes)) => create_gpui_image(bytes)
+                                .and_then(|image| image.to_image_data(svg_renderer.clone())),
+                            Ok(None) => return DiffImage::Missing,
+                            Err(error) => Err(error),
+                        };
+                        match image {
+                            Ok(image) => DiffImage::Loaded(image),
+                            Err(error) => DiffImage::Error(error.to_string().into()),
+                        }
+                    };
+                    (decode(base_bytes), decode(working_bytes))
+                })
+                .await;
+
+            this.update(&mut cx, |this, cx| {
+                this.base_image = base_image;
+                this.working_image = working_image;
+                cx.emit(ItemEvent::UpdateBreadcrumbs);
+                cx.notify();
+            })
+            .log_err();
+        });
+    }
+
+    fn set_base(&mut self, base: DiffBase, cx: &mut ViewContext<Self>) {
+        if self.base != base {
+            self.base = base;
+            self.base_image = DiffImage::Loading;
+            self.load(cx);
+        }
+    }
+
+    fn set_mode(&mut self, mode: ImageDiffMode, cx: &mut ViewContext<Self>) {
+        self.mode = mode;
+        self.blend = 0.5;
+        cx.notify();
+    }
+
+    fn update_blend(&mut self, position: Point<Pixels>, cx: &mut ViewContext<Self>) {
+        if self.viewport.size.width > px(0.) {
+            self.blend =
+                ((position.x - self.viewport.left()) / self.viewport.size.width).clamp(0., 1.);
+            cx.notify();
+        }
+    }
+
+    fn file_name(&self) -> SharedString {
+        self.project_path
+            .path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default()
+            .into()
+    }
+
+    fn render_mode_button(
+        &self,
+        id: &'static str,
+        label: &'static str,
+        mode: ImageDiffMode,
+        cx: &mut ViewContext<Self>,
+    ) -> impl IntoElement {
+        Button::new(id, label)
+            .style(ButtonStyle::Subtle)
+            .label_size(LabelSize::Small)
+            .toggle_state(self.mode == mode)
+            .on_click(cx.listener(move |this, _, cx| this.set_mode(mode, cx)))
+    }
+
+    fn render_base_button(&self, base: DiffBase, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        Button::new(base.label(), base.label())
+            .style(ButtonStyle::Subtle)
+            .label_size(LabelSize::Small)
+            .toggle_state(self.base == base)
+            .on_click(cx.listener(move |this, _, cx| this.set_base(base, cx)))
+    }
+
+    fn render_header(&self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let hint = match self.mode {
+            ImageDiffMode::SideBySide => None,
+            ImageDiffMode::Swipe => Some("Drag to move the divider".to_string()),
+            ImageDiffMode::OnionSkin => Some(format!(
+                "Drag to blend · Working copy {:.0}%",
+                self.blend * 100.
+            )),
+        };
+
+        h_flex()
+            .flex_none()
+            .justify_between()
+            .gap_2()
+            .px_2()
+            .py_1()
+            .border_b_1()
+            .border_color(cx.theme().colors().border)
+            .child(
+                h_flex()
+                    .gap_1()
+                    .child(self.render_mode_button(
+                        "side-by-side",
+                        "Side by Side",
+                        ImageDiffMode::SideBySide,
+                        cx,
+                    ))
+                    .child(self.render_mode_button("swipe", "Swipe", ImageDiffMode::Swipe, cx))
+                    .child(self.render_mode_button(
+                        "onion-skin",
+                        "Onion Skin",
+                        ImageDiffMode::OnionSkin,
+                        cx,
+                    ))
+                    .children(
+                        hint.map(|hint| {
+                            Label::new(hint).size(LabelSize::Small).color(Color::Muted)
+                        }),
+                    ),
+            )
+            .child(
+                h_flex()
+                    .gap_1()
+                    .child(
+                        Label::new("Compare with")
+                            .size(LabelSize::Small)
+                            .color(Color::Muted),
+                    )
+                    .child(self.render_base_button(DiffBase::Head, cx))
+                    .child(self.render_base_button(DiffBase::Index, cx)),
+            )
+    }
+
+    fn render_side_by_side(&self) -> impl IntoElement {
+        let labeled = |label: SharedString, image: &DiffImage| {
+            v_flex()
+                .flex_1()
+                .h_full()
+                .child(
+                    div()
+                        .px_2()
+                        .py_1()
+                        .child(Label::new(label).size(LabelSize::Small).color(Color::Muted)),
+                )
+                .child(
+                    div()
+                        .relative()
+                        .flex_1()
+                        .overflow_hidden()
+                        .child(render_layer(image, true)),
+                )
+        };
+
+        h_flex()
+            .size_full()
+            .gap_2()
+            .child(labeled(self.base.label().into(), &self.base_image))
+            .child(labeled("Working Copy".into(), &self.working_image))
+    }
+
+    fn render_swipe(&self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let viewport_width = self.viewport.size.width;
+        div()
+            .relative()
+            .size_full()
+            .child(render_layer(&self.working_image, true))
+            // The base image is revealed to the left of the divider.
+            .child(
+                div()
+                    .absolute()
+                    .top_0()
+                    .left_0()
+                    .h_full()
+                    .w(relative(self.blend))
+                    .overflow_hidden()
+                    .child(
+                        div()
+                            .absolute()
+                            .top_0()
+                            .left_0()
+                            .h_full()
+                            .w(viewport_width)
+                            .child(render_layer(&self.base_image, true)),
+                    ),
+            )
+            .child(
+                div()
+                    .absolute()
+                    .top_0()
+                    .h_full()
+                    .left(relative(self.blend))
+                    .w(px(2.))
+                    .bg(cx.theme().colors().border_focused),
+            )
+    }
+
+    fn render_onion_skin(&self) -> impl IntoElement {
+        div()
+            .relative()
+            .size_full()
+            .child(render_layer(&self.base_image, true))
+            .child(
+                div()
+                    .absolute()
+                    .top_0()
+                    .left_0()
+                    .size_full()
+                    .opacity(self.blend)
+                    .child(render_layer(&self.working_image, false)),
+            )
+    }
+}
+
+/// Renders an image scaled down to fit its container, with a checkerboard behind it if `checkerboard` is true.
+fn render_layer(image: &DiffImage, checkerboard: bool) -> AnyElement {
+    let content = match image {
+        DiffImage::Loaded(image) => {
+            let image_size = image.size(0);
+            let image_size = size(image_size.width.0 as f32, image_size.height.0 as f32);
+            let background = checkerboard.then(|| {
+                canvas(
+                    |_, _| (),
+                    move |viewport, _, cx| {
+                        let image_bounds =
+                            image_bounds(viewport, image_size, Zoom::Fit, Point::default());
+                        paint_checkerboard(image_bounds, viewport, cx);
+                    },
+                )
+                .absolute()
+                .size_full()
+            });
+            div()
+                .size_full()
+                .children(background)
+                .child(
+                    img(ImageSource::Render(image.clone()))
+                        .size_full()
+                        .object_fit(ObjectFit::ScaleDown),
+                )
+                .into_any_element()
+        }
+        DiffImage::Loading => render_message("Loading…".into()),
+        DiffImage::Missing => render_message("No image in this version".into()),
+        DiffImage::Error(error) => render_message(format!("Failed to load image: {error}").into()),
+    };
+
+    div()
+        .absolute()
+        .top_0()
+        .left_0()
+        .size_full()
+        .child(content)
+        .into_any_element()
+}
+
+fn render_message(message: SharedString) -> AnyElement {
+    div()
+        .size_full()
+        .flex()
+        .justify_center()
+        .items_center()
+        .child(Label::new(message).color(Color::Muted))
+        .into_any_element()
+}
+
+impl EventEmitter<ItemEvent> for ImageDiffView {}
+
+impl FocusableView for ImageDiffView {
+    fn focus_handle(&self, _: &AppContext) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Item for ImageDiffView {
+    type Event = ItemEvent;
+
+    fn to_item_events(event: &Self::Event, mut f: impl FnMut(ItemEvent)) {
+        f(*event)
+    }
+
+    fn tab_content(&self, params: TabContentParams, _: &WindowContext) -> AnyElement {
+        Label::new(format!("{} ({})", self.file_name(), self.base.label()))
+            .single_line()
+            .color(params.text_color())
+            .into_any_element()
+    }
+
+    fn tab_icon(&self, _: &WindowContext) -> Option<Icon> {
+        Some(Icon::new(IconName::Diff))
+    }
+
+    fn tab_tooltip_text(&self, _: &AppContext) -> Option<SharedString> {
+        Some(
+            format!(
+                "{} ({} ↔ Working Copy)",
+                self.project_path.path.to_string_lossy(),
+                self.base.label()
+            )
+            .into(),
+        )
+    }
+
+    fn telemetry_event_text(&self) -> Option<&'static str> {
+        Some("Image Diff Opened")
+    }
+
+    fn breadcrumb_location(&self, _: &AppContext) -> ToolbarItemLocation {
+        ToolbarItemLocation::PrimaryLeft
+    }
+
+    fn breadcrumbs(&self, _: &theme::Theme, _: &AppContext) -> Option<Vec<BreadcrumbText>> {
+        let dimensions = |image: &DiffImage| {
+            image.image().map_or_else(
+                || "—".to_string(),
+                |image| {
+                    let size = image.size(0);
+                    format!("{} × {}", size.width.0, size.height.0)
+                },
+            )
+        };
+        Some(vec![BreadcrumbText {
+            text: format!(
+                "{} · {}: {} · Working Copy: {}",
+                self.project_path.path.to_string_lossy(),
+                self.base.label(),
+                dimensions(&self.base_image),
+                dimensions(&self.working_image)
+            ),
+            highlights: None,
+            font: None,
+        }])
+    }
+
+    fn clone_on_split(
+        &self,
+        _workspace_id: Option<workspace::WorkspaceId>,
+        cx: &mut ViewContext<Self>,
+    ) -> Option<View<Self>> {
+        let project = self.project.clone();
+        let project_path = self.project_path.clone();
+        Some(cx.new_view(|cx| Self::new(project, project_path, cx)))
+    }
+}
+
+impl Render for ImageDiffView {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let view = cx.view().downgrade();
+        let viewport_observer = canvas(
+            move |bounds, cx| {
+                view.update(cx, |this, _| this.viewport = bounds).ok();
+            },
+            |_, _, _| {},
+        )
+        .absolute()
+        .size_full();
+
+        let content = match self.mode {
+            ImageDiffMode::SideBySide => self.render_side_by_side().into_any_element(),
+            ImageDiffMode::Swipe => self.render_swipe(cx).into_any_element(),
+            ImageDiffMode::OnionSkin => self.render_onion_skin().into_any_element(),
+        };
+        let blends = self.mode != ImageDiffMode::SideBySide;
+
+        v_flex()
+            .key_context("ImageDiffView")
+            .track_focus(&self.focus_handle)
+            .size_full()
+            .child(self.render_header(cx))
+            .child(
+                div()
+                    .id("image-diff")
+                    .relative()
+                    .flex_1()
+                    .w_full()
+                    .overflow_hidden()
+                    .when(blends, |this| {
+                        this.cursor_col_resize()
+                            .on_mouse_down(
+                                MouseButton::Left,
+                                cx.listener(|this, event: &MouseDownEvent, cx| {
+                                    this.dragging = true;
+                                    this.update_blend(event.position, cx);
+                                }),
+                            )
+                            .on_mouse_move(cx.listener(|this, event: &MouseMoveEvent, cx| {
+                                if this.dragging && event.pressed_button == Some(MouseButton::Left)
+                                {
+                                    this.update_blend(event.position, cx);
+                                } else {
+                                    this.dragging = false;
+                                }
+                            }))
+                            .on_mouse_up(
+                                MouseButton::Left,
+                                cx.listener(|this, _, _| this.dragging = false),
+                            )
+                    })
+                    .child(viewport_observer)
+                    .child(content),
+            )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_diffable_image() {
+        assert!(is_diffable_image(Path::new("assets/logo.png")));
+        assert!(is_diffable_image(Path::new("icons/Check.SVG")));
+        assert!(!is_diffable_image(Path::new("src/main.rs")));
+        assert!(!is_diffable_image(Path::new("Makefile")));
+    }
+}
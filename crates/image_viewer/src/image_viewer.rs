@@ -1,12 +1,19 @@
+mod image_diff_view;
+
 use std::path::PathBuf;
+use std::sync::Arc;
 
 use anyhow::Context as _;
 use editor::items::entry_git_aware_label_color;
 use gpui::{
-    canvas, div, fill, img, opaque_grey, point, size, AnyElement, AppContext, Bounds, EventEmitter,
-    FocusHandle, FocusableView, InteractiveElement, IntoElement, Model, ObjectFit, ParentElement,
-    Render, Styled, Task, View, ViewContext, VisualContext, WeakView, WindowContext,
+    actions, canvas, div, fill, img, opaque_grey, point, relative, size, AnyElement, AppContext,
+    Bounds, ClipboardItem, CursorStyle, EventEmitter, FocusHandle, FocusableView, Hsla,
+    ImageFormat, ImageSource, InteractiveElement, IntoElement, Model, MouseButton, MouseDownEvent,
+    MouseMoveEvent, MouseUpEvent, ObjectFit, ParentElement, Point, Render, RenderImage, Rgba,
+    ScrollWheelEvent, Size, StatefulInteractiveElement, Styled, Task, View, ViewContext,
+    VisualContext, WeakView, WindowContext,
 };
+use human_bytes::human_bytes;
 use persistence::IMAGE_VIEWER;
 use theme::Theme;
 use ui::prelude::*;
@@ -20,12 +27,149 @@ use workspace::{
     ItemId, ItemSettings, ToolbarItemLocation, Workspace, WorkspaceId,
 };
 
+actions!(image_viewer, [ZoomIn, ZoomOut, ZoomToFit, ActualSize]);
+
+pub use image_diff_view::{is_diffable_image, ImageDiffView};
+
 const IMAGE_VIEWER_KIND: &str = "ImageView";
 
+const MIN_ZOOM: f32 = 0.05;
+const MAX_ZOOM: f32 = 64.;
+const ZOOM_STEP: f32 = 1.25;
+/// How much a pixel of scrolling changes the zoom level.
+const SCROLL_ZOOM_SENSITIVITY: f32 = 0.005;
+/// The zoom level at and above which images are rendered with nearest-neighbor
+/// filtering, so that individual pixels can be told apart.
+const PIXELATED_ZOOM: f32 = 4.;
+/// How far the mouse has to move before a click becomes a drag.
+const DRAG_THRESHOLD: f32 = 3.;
+const CHECKERBOARD_SQUARE_SIZE: f32 = 16.;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum Zoom {
+    /// Scale the image down to fit the viewport, without scaling it up.
+    Fit,
+    /// Scale the image by a fixed factor, where `1.0` shows it at its actual size.
+    Scale(f32),
+}
+
+impl Zoom {
+    fn scale(self, viewport: Size<Pixels>, image_size: Size<f32>) -> f32 {
+        match self {
+            Zoom::Scale(scale) => scale,
+            Zoom::Fit => {
+                if viewport.width <= px(0.)
+                    || viewport.height <= px(0.)
+                    || image_size.width <= 0.
+                    || image_size.height <= 0.
+                {
+                    return 1.;
+                }
+                (viewport.width.0 / image_size.width)
+                    .min(viewport.height.0 / image_size.height)
+                    .min(1.)
+            }
+        }
+    }
+}
+
+/// Returns the bounds of an image with the given size, zoom and pan within the viewport.
+pub(crate) fn image_bounds(
+    viewport: Bounds<Pixels>,
+    image_size: Size<f32>,
+    zoom: Zoom,
+    pan: Point<Pixels>,
+) -> Bounds<Pixels> {
+    let scale = zoom.scale(viewport.size, image_size);
+    let size = size(px(image_size.width * scale), px(image_size.height * scale));
+    let origin = viewport.center() + pan - point(size.width * 0.5, size.height * 0.5);
+    Bounds { origin, size }
+}
+
+/// Paints a checkerboard behind the visible part of an image, so that its transparent parts are visible.
+pub(crate) fn paint_checkerboard(
+    image_bounds: Bounds<Pixels>,
+    viewport: Bounds<Pixels>,
+    cx: &mut WindowContext,
+) {
+    let visible_bounds = image_bounds.intersect(&viewport);
+    if visible_bounds.size.width <= px(0.) || visible_bounds.size.height <= px(0.) {
+        return;
+    }
+
+    cx.paint_quad(fill(visible_bounds, opaque_grey(0.7, 1.)));
+
+    // Only the squares that are visible are painted, keeping them aligned to the image.
+    let square_size = px(CHECKERBOARD_SQUARE_SIZE);
+    let first_column = ((visible_bounds.left() - image_bounds.left()) / square_size).floor() as i64;
+    let first_row = ((visible_bounds.top() - image_bounds.top()) / square_size).floor() as i64;
+    let mut row = first_row;
+    while image_bounds.top() + square_size * (row as f32) < visible_bounds.bottom() {
+        let mut column = first_column + (first_column + row).rem_euclid(2);
+        while image_bounds.left() + square_size * (column as f32) < visible_bounds.right() {
+            let square = Bounds::new(
+                image_bounds.origin + point(square_size * column as f32, square_size * row as f32),
+                size(square_size, square_size),
+            );
+            cx.paint_quad(fill(
+                square.intersect(&visible_bounds),
+                opaque_grey(0.6, 1.),
+            ));
+            column += 2;
+        }
+        row += 1;
+    }
+}
+
+struct Drag {
+    start_position: Point<Pixels>,
+    start_pan: Point<Pixels>,
+    moved: bool,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct HoveredPixel {
+    x: u32,
+    y: u32,
+    /// The color of the pixel, as RGBA.
+    color: [u8; 4],
+}
+
+fn color_hex([r, g, b, a]: [u8; 4]) -> String {
+    if a == u8::MAX {
+        format!("#{r:02x}{g:02x}{b:02x}")
+    } else {
+        format!("#{r:02x}{g:02x}{b:02x}{a:02x}")
+    }
+}
+
+fn image_format_name(format: ImageFormat) -> &'static str {
+    match format {
+        ImageFormat::Png => "PNG",
+        ImageFormat::Jpeg => "JPEG",
+        ImageFormat::Webp => "WebP",
+        ImageFormat::Gif => "GIF",
+        ImageFormat::Svg => "SVG",
+        ImageFormat::Bmp => "BMP",
+        ImageFormat::Tiff => "TIFF",
+    }
+}
+
 pub struct ImageView {
     image_item: Model<ImageItem>,
     project: Model<Project>,
     focus_handle: FocusHandle,
+    /// The decoded image, used to render it and to inspect its pixels.
+    image_data: Option<Arc<RenderImage>>,
+    load_error: Option<SharedString>,
+    load_task: Task<()>,
+    zoom: Zoom,
+    /// The offset of the image's center from the center of the viewport.
+    pan: Point<Pixels>,
+    /// The bounds of the viewport, as of the last frame.
+    viewport: Bounds<Pixels>,
+    drag: Option<Drag>,
+    hovered_pixel: Option<HoveredPixel>,
 }
 
 impl ImageView {
@@ -35,11 +179,198 @@ impl ImageView {
         cx: &mut ViewContext<Self>,
     ) -> Self {
         cx.subscribe(&image_item, Self::on_image_event).detach();
-        Self {
+        let mut this = Self {
             image_item,
             project,
             focus_handle: cx.focus_handle(),
+            image_data: None,
+            load_error: None,
+            load_task: Task::ready(()),
+            zoom: Zoom::Fit,
+            pan: Point::default(),
+            viewport: Bounds::default(),
+            drag: None,
+            hovered_pixel: None,
+        };
+        this.load_image(cx);
+        this
+    }
+
+    fn load_image(&mut self, cx: &mut ViewContext<Self>) {
+        let image = self.image_item.read(cx).image.clone();
+        let svg_renderer = cx.svg_renderer();
+        let image_data = cx
+            .background_executor()
+            .spawn(async move { image.to_image_data(svg_renderer) });
+        self.load_task = cx.spawn(|this, mut cx| async move {
+            let image_data = image_data.await;
+            this.update(&mut cx, |this, cx| {
+                match image_data {
+                    Ok(image_data) => {
+                        this.image_data = Some(image_data);
+                        this.load_error = None;
+                    }
+                    Err(error) => {
+                        this.image_data = None;
+                        this.load_error = Some(error.to_string().into());
+                    }
+                }
+                this.hovered_pixel = None;
+                cx.emit(ImageViewEvent::TitleChanged);
+                cx.notify();
+            })
+            .ok();
+        });
+    }
+
+    fn image_size(&self) -> Option<Size<f32>> {
+        let size = self.image_data.as_ref()?.size(0);
+        Some(Size {
+            width: size.width.0 as f32,
+            height: size.height.0 as f32,
+        })
+    }
+
+    fn scale(&self) -> f32 {
+        self.image_size().map_or(1., |image_size| {
+            self.zoom.scale(self.viewport.size, image_size)
+        })
+    }
+
+    fn image_bounds(&self) -> Option<Bounds<Pixels>> {
+        Some(image_bounds(
+            self.viewport,
+            self.image_size()?,
+            self.zoom,
+            self.pan,
+        ))
+    }
+
+    /// Sets the zoom level, keeping the part of the image under `anchor` in place.
+    fn zoom_around(&mut self, scale: f32, anchor: Point<Pixels>, cx: &mut ViewContext<Self>) {
+        let (Some(image_size), Some(bounds)) = (self.image_size(), self.image_bounds()) else {
+            return;
+        };
+        let old_scale = self.scale();
+        let scale = scale.clamp(MIN_ZOOM, MAX_ZOOM);
+        let image_point = (anchor - bounds.origin).map(|coordinate| coordinate.0 / old_scale);
+        let origin = point(
+            anchor.x - px(image_point.x * scale),
+            anchor.y - px(image_point.y * scale),
+        );
+        let size = size(px(image_size.width * scale), px(image_size.height * scale));
+
+        self.zoom = Zoom::Scale(scale);
+        self.pan = origin - self.viewport.center() + point(size.width * 0.5, size.height * 0.5);
+        cx.notify();
+    }
+
+    fn zoom_in(&mut self, _: &ZoomIn, cx: &mut ViewContext<Self>) {
+        self.zoom_around(self.scale() * ZOOM_STEP, self.viewport.center(), cx);
+    }
+
+    fn zoom_out(&mut self, _: &ZoomOut, cx: &mut ViewContext<Self>) {
+        self.zoom_around(self.scale() / ZOOM_STEP, self.viewport.center(), cx);
+    }
+
+    fn zoom_to_fit(&mut self, _: &ZoomToFit, cx: &mut ViewContext<Self>) {
+        self.zoom = Zoom::Fit;
+        self.pan = Point::default();
+        cx.notify();
+    }
+
+    fn actual_size(&mut self, _: &ActualSize, cx: &mut ViewContext<Self>) {
+        self.zoom_around(1., self.viewport.center(), cx);
+    }
+
+    fn handle_scroll_wheel(&mut self, event: &ScrollWheelEvent, cx: &mut ViewContext<Self>) {
+        let delta = event.delta.pixel_delta(cx.line_height());
+        let factor = (-delta.y.0 * SCROLL_ZOOM_SENSITIVITY).exp();
+        self.zoom_around(self.scale() * factor, event.position, cx);
+        self.hovered_pixel = self.pixel_at(event.position);
+    }
+
+    fn handle_mouse_down(&mut self, event: &MouseDownEvent, cx: &mut ViewContext<Self>) {
+        cx.focus(&self.focus_handle);
+        self.drag = Some(Drag {
+            start_position: event.position,
+            start_pan: self.pan,
+            moved: false,
+        });
+    }
+
+    fn handle_mouse_move(&mut self, event: &MouseMoveEvent, cx: &mut ViewContext<Self>) {
+        if event.pressed_button == Some(MouseButton::Left) {
+            let scale = self.scale();
+            if let Some(drag) = self.drag.as_mut() {
+                let delta = event.position - drag.start_position;
+                if delta.x.abs() > px(DRAG_THRESHOLD) || delta.y.abs() > px(DRAG_THRESHOLD) {
+                    drag.moved = true;
+                }
+                if drag.moved {
+                    // Panning keeps the current scale, even if the image was fit to the viewport.
+                    self.zoom = Zoom::Scale(scale);
+                    self.pan = drag.start_pan + delta;
+                }
+            }
+        } else {
+            self.drag = None;
+        }
+
+        self.hovered_pixel = self.pixel_at(event.position);
+        cx.notify();
+    }
+
+    fn handle_mouse_up(&mut self, event: &MouseUpEvent, cx: &mut ViewContext<Self>) {
+        let Some(drag) = self.drag.take() else {
+            return;
+        };
+        if !drag.moved {
+            if let Some(pixel) = self.pixel_at(event.position) {
+                cx.write_to_clipboard(ClipboardItem::new_string(color_hex(pixel.color)));
+            }
         }
+        cx.notify();
+    }
+
+    /// Returns the pixel of the image under the given position.
+    fn pixel_at(&self, position: Point<Pixels>) -> Option<HoveredPixel> {
+        let image_data = self.image_data.as_ref()?;
+        let bounds = self.image_bounds()?;
+        if !self.viewport.contains(&position) || !bounds.contains(&position) {
+            return None;
+        }
+
+        let scale = self.scale();
+        let size = image_data.size(0);
+        let (width, height) = (size.width.0 as u32, size.height.0 as u32);
+        let offset = position - bounds.origin;
+        let x = ((offset.x.0 / scale) as u32).min(width.saturating_sub(1));
+        let y = ((offset.y.0 / scale) as u32).min(height.saturating_sub(1));
+
+        // Decoded images are stored as BGRA.
+        let index = (y as usize * width as usize + x as usize) * 4;
+        let [b, g, r, a] = image_data.as_bytes(0)?.get(index..index + 4)? else {
+            return None;
+        };
+        Some(HoveredPixel {
+            x,
+            y,
+            color: [*r, *g, *b, *a],
+        })
+    }
+
+    /// Returns a description of the image's dimensions, format and file size.
+    fn image_metadata(&self, cx: &AppContext) -> String {
+        let image = &self.image_item.read(cx).image;
+        let mut metadata = Vec::new();
+        if let Some(image_data) = &self.image_data {
+            let size = image_data.size(0);
+            metadata.push(format!("{} × {}", size.width.0, size.height.0));
+        }
+        metadata.push(image_format_name(image.format).to_string());
+        metadata.push(human_bytes(image.bytes.len() as f64));
+        metadata.join(" · ")
     }
 
     fn on_image_event(
@@ -49,10 +380,13 @@ impl ImageView {
         cx: &mut ViewContext<Self>,
     ) {
         match event {
-            ImageItemEvent::FileHandleChanged | ImageItemEvent::Reloaded => {
+            ImageItemEvent::FileHandleChanged => {
                 cx.emit(ImageViewEvent::TitleChanged);
                 cx.notify();
             }
+            ImageItemEvent::Reloaded => {
+                self.load_image(cx);
+            }
             ImageItemEvent::ReloadNeeded => {}
         }
     }
@@ -136,7 +470,11 @@ impl Item for ImageView {
     }
 
     fn breadcrumbs(&self, _theme: &Theme, cx: &AppContext) -> Option<Vec<BreadcrumbText>> {
-        let text = breadcrumbs_text_for_image(self.project.read(cx), self.image_item.read(cx), cx);
+        let text = format!(
+            "{} · {}",
+            breadcrumbs_text_for_image(self.project.read(cx), self.image_item.read(cx), cx),
+            self.image_metadata(cx)
+        );
         Some(vec![BreadcrumbText {
             text,
             highlights: None,
@@ -152,10 +490,11 @@ impl Item for ImageView {
     where
         Self: Sized,
     {
-        Some(cx.new_view(|cx| Self {
-            image_item: self.image_item.clone(),
-            project: self.project.clone(),
-            focus_handle: cx.focus_handle(),
+        Some(cx.new_view(|cx| {
+            let mut view = Self::new(self.image_item.clone(), self.project.clone(), cx);
+            view.zoom = self.zoom;
+            view.pan = self.pan;
+            view
         }))
     }
 }
@@ -256,70 +595,143 @@ impl FocusableView for ImageView {
 
 impl Render for ImageView {
     fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
-        let image = self.image_item.read(cx).image.clone();
-        let checkered_background = |bounds: Bounds<Pixels>, _, cx: &mut WindowContext| {
-            let square_size = 32.0;
-
-            let start_y = bounds.origin.y.0;
-            let height = bounds.size.height.0;
-            let start_x = bounds.origin.x.0;
-            let width = bounds.size.width.0;
-
-            let mut y = start_y;
-            let mut x = start_x;
-            let mut color_swapper = true;
-            // draw checkerboard pattern
-            while y <= start_y + height {
-                // Keeping track of the grid in order to be resilient to resizing
-                let start_swap = color_swapper;
-                while x <= start_x + width {
-                    let rect =
-                        Bounds::new(point(px(x), px(y)), size(px(square_size), px(square_size)));
-
-                    let color = if color_swapper {
-                        opaque_grey(0.6, 0.4)
-                    } else {
-                        opaque_grey(0.7, 0.4)
-                    };
-
-                    cx.paint_quad(fill(rect, color));
-                    color_swapper = !color_swapper;
-                    x += square_size;
+        let view = cx.view().downgrade();
+        let image_size = self.image_size();
+        let zoom = self.zoom;
+        let pan = self.pan;
+        let scale = self.scale();
+
+        // Records the viewport's bounds, and paints the checkerboard behind the image.
+        let checkered_background = canvas(
+            move |bounds, cx| {
+                view.update(cx, |this, _| this.viewport = bounds).ok();
+            },
+            move |viewport, _, cx| {
+                let Some(image_size) = image_size else {
+                    return;
+                };
+                paint_checkerboard(image_bounds(viewport, image_size, zoom, pan), viewport, cx);
+            },
+        )
+        .size_full()
+        .absolute()
+        .top_0()
+        .left_0();
+
+        let image = self.image_data.clone().map(|image_data| {
+            let image = img(ImageSource::Render(image_data)).pixelated(scale >= PIXELATED_ZOOM);
+            match self.zoom {
+                Zoom::Fit => div()
+                    .size_full()
+                    .child(image.size_full().object_fit(ObjectFit::ScaleDown)),
+                Zoom::Scale(_) => {
+                    let image_size = image_size.unwrap_or_default();
+                    let width = px(image_size.width * scale);
+                    let height = px(image_size.height * scale);
+                    // Position the image relative to the center of the viewport.
+                    div()
+                        .absolute()
+                        .left(relative(0.5))
+                        .top(relative(0.5))
+                        .child(
+                            image
+                                .absolute()
+                                .left(self.pan.x - width * 0.5)
+                                .top(self.pan.y - height * 0.5)
+                                .w(width)
+                                .h(height)
+                                .object_fit(ObjectFit::Fill),
+                        )
                 }
-                x = start_x;
-                color_swapper = !start_swap;
-                y += square_size;
             }
+        });
+
+        let cursor = if self.drag.as_ref().map_or(false, |drag| drag.moved) {
+            CursorStyle::ClosedHand
+        } else if self.hovered_pixel.is_some() {
+            CursorStyle::Crosshair
+        } else {
+            CursorStyle::Arrow
         };
 
-        let checkered_background = canvas(|_, _| (), checkered_background)
-            .border_2()
-            .border_color(cx.theme().styles.colors.border)
-            .size_full()
+        let status = h_flex()
             .absolute()
-            .top_0()
-            .left_0();
+            .bottom_2()
+            .left_2()
+            .gap_2()
+            .px_2()
+            .py_1()
+            .rounded_md()
+            .border_1()
+            .border_color(cx.theme().colors().border)
+            .bg(cx.theme().colors().elevated_surface_background)
+            .child(
+                Label::new(format!("{:.0}%", scale * 100.))
+                    .size(LabelSize::Small)
+                    .color(Color::Muted),
+            )
+            .when_some(self.hovered_pixel, |this, pixel| {
+                let [r, g, b, a] = pixel.color;
+                let color = Hsla::from(Rgba {
+                    r: r as f32 / 255.,
+                    g: g as f32 / 255.,
+                    b: b as f32 / 255.,
+                    a: a as f32 / 255.,
+                });
+                this.child(
+                    Label::new(format!("{}, {}", pixel.x, pixel.y))
+                        .size(LabelSize::Small)
+                        .color(Color::Muted),
+                )
+                .child(
+                    div()
+                        .size_3()
+                        .rounded_sm()
+                        .border_1()
+                        .border_color(cx.theme().colors().border)
+                        .bg(color),
+                )
+                .child(Label::new(color_hex(pixel.color)).size(LabelSize::Small))
+            });
 
         div()
+            .id("image-viewer")
+            .key_context("ImageViewer")
             .track_focus(&self.focus_handle(cx))
+            .on_action(cx.listener(Self::zoom_in))
+            .on_action(cx.listener(Self::zoom_out))
+            .on_action(cx.listener(Self::zoom_to_fit))
+            .on_action(cx.listener(Self::actual_size))
+            .on_scroll_wheel(cx.listener(Self::handle_scroll_wheel))
+            .on_mouse_down(MouseButton::Left, cx.listener(Self::handle_mouse_down))
+            .on_mouse_move(cx.listener(Self::handle_mouse_move))
+            .on_mouse_up(MouseButton::Left, cx.listener(Self::handle_mouse_up))
+            .on_hover(cx.listener(|this, hovered, cx| {
+                if !hovered {
+                    this.hovered_pixel = None;
+                    cx.notify();
+                }
+            }))
+            .cursor(cursor)
+            .relative()
             .size_full()
+            .overflow_hidden()
             .child(checkered_background)
-            .child(
-                div()
-                    .flex()
-                    .justify_center()
-                    .items_center()
-                    .w_full()
-                    // TODO: In browser based Tailwind & Flex this would be h-screen and we'd use w-full
-                    .h_full()
-                    .child(
-                        img(image)
-                            .object_fit(ObjectFit::ScaleDown)
-                            .max_w_full()
-                            .max_h_full()
-                            .id("img"),
-                    ),
-            )
+            .children(image)
+            .when_some(self.load_error.clone(), |this, error| {
+                this.child(
+                    div()
+                        .size_full()
+                        .flex()
+                        .justify_center()
+                        .items_center()
+                        .child(
+                            Label::new(format!("Failed to load image: {error}"))
+                                .color(Color::Error),
+                        ),
+                )
+            })
+            .child(status)
     }
 }
 
@@ -340,7 +752,8 @@ impl ProjectItem for ImageView {
 
 pub fn init(cx: &mut AppContext) {
     workspace::register_project_item::<ImageView>(cx);
-    workspace::register_serializable_item::<ImageView>(cx)
+    workspace::register_serializable_item::<ImageView>(cx);
+    image_diff_view::init(cx);
 }
 
 mod persistence {
@@ -423,3 +836,22 @@ mod persistence {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_color_hex() {
+        assert_eq!(color_hex([0x12, 0xab, 0xff, 0xff]), "#12abff");
+        assert_eq!(color_hex([0, 0, 0, 0x80]), "#00000080");
+    }
+
+    #[test]
+    fn test_fit_zoom_only_scales_down() {
+        let viewport = size(px(400.), px(300.));
+        assert_eq!(Zoom::Fit.scale(viewport, size(100., 100.)), 1.);
+        assert_eq!(Zoom::Fit.scale(viewport, size(800., 300.)), 0.5);
+        assert_eq!(Zoom::Scale(8.).scale(viewport, size(800., 300.)), 8.);
+    }
+}
@@ -123,6 +123,9 @@ pub struct Buffer {
     completion_triggers_timestamp: clock::Lamport,
     deferred_ops: OperationQueue<Operation>,
     capability: Capability,
+    /// When the buffer is in large-file mode, in which it isn't parsed, the capability
+    /// it regains once full editing is enabled.
+    large_file: Option<Capability>,
     has_conflict: bool,
     /// Memoize calls to has_changes_since(saved_version).
     /// The contents of a cell are (self.version, has_changes) at the time of a last call.
@@ -345,6 +348,8 @@ pub enum BufferEvent {
     DiagnosticsUpdated,
     /// The buffer gained or lost editing capabilities.
     CapabilityChanged,
+    /// The buffer entered or left large-file mode.
+    LargeFileModeChanged,
     /// The buffer was explicitly requested to close.
     Closed,
     /// The buffer was discarded when closing.
@@ -657,6 +662,9 @@ impl Buffer {
             .with_context(|| anyhow!("Could not deserialize buffer_id"))?;
         let buffer = TextBuffer::new(replica_id, buffer_id, message.base_text);
         let mut this = Self::build(buffer, file, capability);
+        if message.large_file {
+            this = this.with_large_file_mode();
+        }
         this.text.set_line_ending(proto::deserialize_line_ending(
             rpc::proto::LineEnding::from_i32(message.line_ending)
                 .ok_or_else(|| anyhow!("missing line_ending"))?,
@@ -675,6 +683,7 @@ impl Buffer {
             line_ending: proto::serialize_line_ending(self.line_ending()) as i32,
             saved_version: proto::serialize_version(&self.saved_version),
             saved_mtime: self.saved_mtime.map(|time| time.into()),
+            large_file: self.is_large_file(),
         }
     }
 
@@ -744,6 +753,32 @@ impl Buffer {
         self.capability == Capability::ReadOnly
     }
 
+    /// Whether this buffer was opened in large-file mode.
+    ///
+    /// Large files aren't parsed, registered with language servers, diffed against
+    /// git or queried for inlay hints, and are read-only until full editing is enabled.
+    pub fn is_large_file(&self) -> bool {
+        self.large_file.is_some()
+    }
+
+    /// Puts the buffer in large-file mode, returning the buffer.
+    pub fn with_large_file_mode(mut self) -> Self {
+        self.large_file = Some(self.capability);
+        self.capability = Capability::ReadOnly;
+        self
+    }
+
+    /// Leaves large-file mode, restoring the buffer's capability and parsing it.
+    pub fn enable_full_editing(&mut self, cx: &mut ModelContext<Self>) {
+        let Some(capability) = self.large_file.take() else {
+            return;
+        };
+        self.set_capability(capability, cx);
+        self.reparse(cx);
+        cx.emit(BufferEvent::LargeFileModeChanged);
+        cx.notify();
+    }
+
     /// Builds a [`Buffer`] with the given underlying [`TextBuffer`], diff base, [`File`] and [`Capability`].
     pub fn build(buffer: TextBuffer, file: Option<Arc<dyn File>>, capability: Capability) -> Self {
         let saved_mtime = file.as_ref().and_then(|file| file.disk_state().mtime());
@@ -761,6 +796,7 @@ impl Buffer {
             branch_state: None,
             file,
             capability,
+            large_file: None,
             syntax_map,
             parsing_in_background: false,
             non_text_state_update_count: 0,
@@ -962,6 +998,11 @@ impl Buffer {
 
     /// Assign the buffer a new [`Capability`].
     pub fn set_capability(&mut self, capability: Capability, cx: &mut ModelContext<Self>) {
+        // Large files stay read-only until full editing is enabled.
+        if let Some(large_file_capability) = self.large_file.as_mut() {
+            *large_file_capability = capability;
+            return;
+        }
         self.capability = capability;
         cx.emit(BufferEvent::CapabilityChanged)
     }
@@ -1149,7 +1190,7 @@ impl Buffer {
     /// for the same buffer, we only initiate a new parse if we are not already
     /// parsing in the background.
     pub fn reparse(&mut self, cx: &mut ModelContext<Self>) {
-        if self.parsing_in_background {
+        if self.parsing_in_background || self.large_file.is_some() {
             return;
         }
         let language = if let Some(language) = self.language.clone() {
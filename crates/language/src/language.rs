@@ -372,6 +372,18 @@ pub trait LspAdapter: 'static + Send + Sync {
         None
     }
 
+    /// Adjusts the binary the language server is started with to the toolchain that is active
+    /// in the worktree, e.g. to run a server written in JavaScript with the selected Node.js.
+    async fn binary_for_toolchain(
+        &self,
+        binary: LanguageServerBinary,
+        _: &dyn LspAdapterDelegate,
+        _: Arc<dyn LanguageToolchainStore>,
+        _: &mut AsyncAppContext,
+    ) -> LanguageServerBinary {
+        binary
+    }
+
     async fn fetch_latest_server_version(
         &self,
         delegate: &dyn LspAdapterDelegate,
@@ -440,6 +440,9 @@ pub enum FormatOnSave {
     On,
     /// Files should not be formatted on save.
     Off,
+    /// Only the lines that differ from the git index should be formatted on save,
+    /// using the `formatter` setting.
+    Modifications,
     List(FormatterList),
 }
 
@@ -464,6 +467,7 @@ impl JsonSchema for FormatOnSave {
             enum_values: Some(vec![
                 Value::String("on".into()),
                 Value::String("off".into()),
+                Value::String("modifications".into()),
                 Value::String("prettier".into()),
                 Value::String("language_server".into()),
             ]),
@@ -490,6 +494,7 @@ impl Serialize for FormatOnSave {
         match self {
             Self::On => serializer.serialize_str("on"),
             Self::Off => serializer.serialize_str("off"),
+            Self::Modifications => serializer.serialize_str("modifications"),
             Self::List(list) => list.serialize(serializer),
         }
     }
@@ -516,6 +521,8 @@ impl<'de> Deserialize<'de> for FormatOnSave {
                     Ok(Self::Value::On)
                 } else if v == "off" {
                     Ok(Self::Value::Off)
+                } else if v == "modifications" {
+                    Ok(Self::Value::Modifications)
                 } else if v == "language_server" {
                     Ok(Self::Value::List(FormatterList(
                         Formatter::LanguageServer { name: None }.into(),
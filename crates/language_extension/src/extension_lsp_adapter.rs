@@ -390,7 +390,7 @@ fn build_code_label(
     })
 }
 
-fn lsp_completion_to_extension(value: lsp::CompletionItem) -> extension::Completion {
+pub fn lsp_completion_to_extension(value: lsp::CompletionItem) -> extension::Completion {
     extension::Completion {
         label: value.label,
         label_details: value
@@ -456,7 +456,7 @@ fn lsp_insert_text_format_to_extension(
     }
 }
 
-fn lsp_symbol_kind_to_extension(value: lsp::SymbolKind) -> extension::SymbolKind {
+pub fn lsp_symbol_kind_to_extension(value: lsp::SymbolKind) -> extension::SymbolKind {
     match value {
         lsp::SymbolKind::FILE => extension::SymbolKind::File,
         lsp::SymbolKind::MODULE => extension::SymbolKind::Module,
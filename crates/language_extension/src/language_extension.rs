@@ -1,5 +1,7 @@
 mod extension_lsp_adapter;
 
+pub use extension_lsp_adapter::{lsp_completion_to_extension, lsp_symbol_kind_to_extension};
+
 use std::path::PathBuf;
 use std::sync::Arc;
 
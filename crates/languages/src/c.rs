@@ -1,15 +1,29 @@
 use anyhow::{anyhow, bail, Context, Result};
 use async_trait::async_trait;
+use collections::HashMap;
 use futures::StreamExt;
-use gpui::AsyncAppContext;
+use gpui::{AppContext, AsyncAppContext, Task};
 use http_client::github::{latest_github_release, GitHubLspBinaryVersion};
 pub use language::*;
 use lsp::{InitializeParams, LanguageServerBinary, LanguageServerName};
-use serde_json::json;
+use project::MTime;
+use serde_json::{json, Value};
 use smol::fs::{self, File};
-use std::{any::Any, env::consts, path::PathBuf, sync::Arc};
+use std::{
+    any::Any,
+    borrow::Cow,
+    env::consts,
+    path::PathBuf,
+    sync::{Arc, Mutex},
+};
+use task::{TaskTemplates, TaskVariables, VariableName};
 use util::{fs::remove_matching, maybe, merge_json_value_into, ResultExt};
 
+use crate::cmake::{
+    test_regex, CMakeProject, CMAKE_TEST_NAME_TASK_VARIABLE, CMAKE_TEST_REGEX_TASK_VARIABLE,
+    LISTS_FILE_NAME,
+};
+
 pub struct CLspAdapter;
 
 impl CLspAdapter {
@@ -259,6 +273,20 @@ impl super::LspAdapter for CLspAdapter {
         })
     }
 
+    async fn initialization_options(
+        self: Arc<Self>,
+        delegate: &Arc<dyn LspAdapterDelegate>,
+    ) -> Result<Option<Value>> {
+        // clangd only looks for `compile_commands.json` next to the sources and in their `build`
+        // subdirectories, which misses the build directories of CMake presets.
+        let root = delegate.worktree_root_path().to_path_buf();
+        let compilation_database_dir =
+            smol::unblock(move || CMakeProject::find(&root, &root)?.compilation_database_dir())
+                .await;
+        Ok(compilation_database_dir
+            .map(|dir| json!({ "compilationDatabasePath": dir.to_string_lossy() })))
+    }
+
     fn prepare_initialize_params(
         &self,
         mut original: InitializeParams,
@@ -280,6 +308,106 @@ impl super::LspAdapter for CLspAdapter {
     }
 }
 
+/// Provides the tasks of the CMake project a C or C++ file belongs to, and runs its GoogleTest
+/// and Catch2 test cases.
+#[derive(Default)]
+pub(super) struct CContextProvider {
+    /// The CMake projects of local worktrees, by source directory. They are loaded in the
+    /// background, so that tasks can be listed without reading files on the main thread.
+    cmake_projects: Arc<Mutex<HashMap<PathBuf, CachedCMakeProject>>>,
+}
+
+#[derive(Default)]
+struct CachedCMakeProject {
+    project: Option<Arc<CMakeProject>>,
+    /// The modification times of the project's files, as the worktree saw them once the project
+    /// was loaded. The project is reloaded when they change.
+    mtimes: Option<Vec<Option<MTime>>>,
+    is_loading: bool,
+}
+
+impl ContextProvider for CContextProvider {
+    fn build_context(
+        &self,
+        variables: &TaskVariables,
+        _: &Location,
+        _: Option<HashMap<String, String>>,
+        _: Arc<dyn LanguageToolchainStore>,
+        _: &mut AppContext,
+    ) -> Task<Result<TaskVariables>> {
+        let capture =
+            |name: &'static str| variables.get(&VariableName::Custom(Cow::Borrowed(name)));
+        let mut context = TaskVariables::default();
+        if let Some((test_name, regex)) = test_regex(
+            capture("_gtest_suite"),
+            capture("_gtest_name"),
+            capture("_catch2_name"),
+        ) {
+            context.insert(CMAKE_TEST_NAME_TASK_VARIABLE, test_name);
+            context.insert(CMAKE_TEST_REGEX_TASK_VARIABLE, regex);
+        }
+        Task::ready(Ok(context))
+    }
+
+    fn associated_tasks(
+        &self,
+        file: Option<Arc<dyn language::File>>,
+        cx: &AppContext,
+    ) -> Option<TaskTemplates> {
+        let file = project::File::from_dyn(file.as_ref())?;
+        let worktree = file.worktree.read(cx);
+        let source_dir = file
+            .path
+            .ancestors()
+            .skip(1)
+            .filter(|dir| worktree.entry_for_path(dir.join(LISTS_FILE_NAME)).is_some())
+            .last()?;
+        let source_dir = worktree.absolutize(source_dir).ok()?;
+        if !worktree.is_local() {
+            // The files of remote worktrees can't be read from here, so their presets and
+            // targets are unknown.
+            return Some(CMakeProject::new(source_dir).task_templates());
+        }
+
+        let mut cmake_projects = self.cmake_projects.lock().unwrap();
+        let cached = cmake_projects.entry(source_dir.clone()).or_default();
+        if let Some(project) = cached.project.clone() {
+            let mtimes = project
+                .sources
+                .iter()
+                .map(|path| {
+                    let path = path.strip_prefix(worktree.abs_path()).ok()?;
+                    worktree.entry_for_path(path)?.mtime
+                })
+                .collect::<Vec<_>>();
+            if *cached.mtimes.get_or_insert_with(|| mtimes.clone()) == mtimes {
+                return Some(project.task_templates());
+            }
+        }
+
+        if !cached.is_loading {
+            cached.is_loading = true;
+            let cmake_projects = self.cmake_projects.clone();
+            let source_dir = source_dir.clone();
+            cx.background_executor()
+                .spawn(async move {
+                    let project = CMakeProject::load(source_dir.clone());
+                    let mut cmake_projects = cmake_projects.lock().unwrap();
+                    let cached = cmake_projects.entry(source_dir).or_default();
+                    cached.project = Some(Arc::new(project));
+                    cached.mtimes = None;
+                    cached.is_loading = false;
+                })
+                .detach();
+        }
+        // Until the project is (re)loaded, its tasks are based on what it was last loaded with.
+        Some(match &cached.project {
+            Some(project) => project.task_templates(),
+            None => CMakeProject::new(source_dir).task_templates(),
+        })
+    }
+}
+
 async fn get_cached_server_binary(container_dir: PathBuf) -> Option<LanguageServerBinary> {
     maybe!(async {
         let mut last_clangd_dir = None;
@@ -312,9 +440,10 @@ async fn get_cached_server_binary(container_dir: PathBuf) -> Option<LanguageServ
 #[cfg(test)]
 mod tests {
     use gpui::{BorrowAppContext, Context, TestAppContext};
-    use language::{language_settings::AllLanguageSettings, AutoindentMode, Buffer};
+    use language::{language_settings::AllLanguageSettings, Anchor, AutoindentMode, Buffer};
     use settings::SettingsStore;
     use std::num::NonZeroU32;
+    use unindent::Unindent;
 
     #[gpui::test]
     async fn test_c_autoindent(cx: &mut TestAppContext) {
@@ -355,4 +484,72 @@ mod tests {
             buffer
         });
     }
+
+    #[gpui::test]
+    async fn test_cpp_runnables(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let test_settings = SettingsStore::test(cx);
+            cx.set_global(test_settings);
+            language::init(cx);
+        });
+        let language = crate::language("cpp", tree_sitter_cpp::LANGUAGE.into());
+        let text = r#"
+            TEST(Parser, Empty) {}
+
+            namespace demo {
+            TEST_CASE("vectors can be sized", "[vector]") {
+                REQUIRE(v.size() == 5);
+            }
+            }
+
+            SCENARIO("resizing") {}
+        "#
+        .unindent();
+
+        let buffer = cx.new_model(|cx| Buffer::local(text, cx).with_language(language, cx));
+        cx.executor().run_until_parked();
+        let runnables = buffer.read_with(cx, |buffer, _| {
+            buffer
+                .snapshot()
+                .runnable_ranges(Anchor::MIN..Anchor::MAX)
+                .map(|runnable| {
+                    let mut names = runnable
+                        .extra_captures
+                        .into_iter()
+                        .filter(|(name, _)| name.ends_with("_suite") || name.ends_with("_name"))
+                        .collect::<Vec<_>>();
+                    names.sort();
+                    let tags = runnable
+                        .runnable
+                        .tags
+                        .iter()
+                        .map(|tag| tag.0.to_string())
+                        .collect::<Vec<_>>();
+                    (tags, names)
+                })
+                .collect::<Vec<_>>()
+        });
+
+        let capture = |name: &str, text: &str| (name.to_owned(), text.to_owned());
+        assert_eq!(
+            runnables,
+            [
+                (
+                    vec!["cpp-gtest".to_owned()],
+                    vec![
+                        capture("_gtest_name", "Empty"),
+                        capture("_gtest_suite", "Parser")
+                    ]
+                ),
+                (
+                    vec!["cpp-catch2".to_owned()],
+                    vec![capture("_catch2_name", r#""vectors can be sized""#)]
+                ),
+                (
+                    vec!["cpp-catch2".to_owned()],
+                    vec![capture("_catch2_name", r#""resizing""#)]
+                ),
+            ]
+        );
+    }
 }
@@ -0,0 +1,573 @@
+use std::{
+    borrow::Cow,
+    collections::BTreeSet,
+    path::{Path, PathBuf},
+    sync::LazyLock,
+};
+
+use collections::HashSet;
+use regex::Regex;
+use serde::Deserialize;
+use task::{TaskTemplate, TaskTemplates, VariableName};
+use util::ResultExt;
+
+pub(crate) const LISTS_FILE_NAME: &str = "CMakeLists.txt";
+const PRESETS_FILE_NAMES: [&str; 2] = ["CMakePresets.json", "CMakeUserPresets.json"];
+const COMPILATION_DATABASE_FILE_NAME: &str = "compile_commands.json";
+/// The build directory used when the project has no presets.
+const DEFAULT_BUILD_DIR: &str = "build";
+/// Makes CMake generate the `compile_commands.json` clangd reads.
+const EXPORT_COMPILE_COMMANDS: &str = "-DCMAKE_EXPORT_COMPILE_COMMANDS=ON";
+
+/// The name of the GoogleTest or Catch2 test case to run.
+pub(crate) const CMAKE_TEST_NAME_TASK_VARIABLE: VariableName =
+    VariableName::Custom(Cow::Borrowed("CMAKE_TEST_NAME"));
+/// The `ctest -R` regex selecting the tests of the test case to run.
+pub(crate) const CMAKE_TEST_REGEX_TASK_VARIABLE: VariableName =
+    VariableName::Custom(Cow::Borrowed("CMAKE_TEST_REGEX"));
+
+/// A CMake project, as described by its `CMakeLists.txt` and presets files.
+#[derive(Debug, Default)]
+pub(crate) struct CMakeProject {
+    pub source_dir: PathBuf,
+    pub presets: CMakePresets,
+    pub targets: BTreeSet<String>,
+    /// The lists and presets files the project was loaded from, including the presets files
+    /// that don't exist.
+    pub sources: Vec<PathBuf>,
+}
+
+impl CMakeProject {
+    /// Loads the outermost CMake project containing `path`, without leaving `root`.
+    pub fn find(root: &Path, path: &Path) -> Option<Self> {
+        let source_dir = path
+            .ancestors()
+            .take_while(|dir| dir.starts_with(root))
+            .filter(|dir| dir.join(LISTS_FILE_NAME).is_file())
+            .last()?
+            .to_path_buf();
+        Some(Self::load(source_dir))
+    }
+
+    /// A project whose files haven't been read, which has no presets or targets.
+    pub fn new(source_dir: PathBuf) -> Self {
+        Self {
+            source_dir,
+            ..Self::default()
+        }
+    }
+
+    pub fn load(source_dir: PathBuf) -> Self {
+        let mut presets = CMakePresets::default();
+        let mut sources = Vec::new();
+        for file_name in PRESETS_FILE_NAMES {
+            let path = source_dir.join(file_name);
+            let contents = std::fs::read_to_string(&path);
+            sources.push(path);
+            let Ok(contents) = contents else {
+                continue;
+            };
+            if let Some(file_presets) = serde_json::from_str::<CMakePresets>(&contents).log_err() {
+                presets.extend(file_presets);
+            }
+        }
+        let (targets, lists_files) = targets(&source_dir);
+        sources.extend(lists_files);
+        Self {
+            source_dir,
+            presets,
+            targets,
+            sources,
+        }
+    }
+
+    /// The build directories of the configure presets that can be selected.
+    pub fn preset_build_dirs(&self) -> Vec<(&str, PathBuf)> {
+        self.presets
+            .configure_presets
+            .iter()
+            .filter(|preset| !preset.hidden)
+            .filter_map(|preset| {
+                let binary_dir = self.presets.binary_dir(preset)?;
+                Some((preset.name.as_str(), self.expand(&binary_dir, &preset.name)))
+            })
+            .collect()
+    }
+
+    /// Returns the directory with the `compile_commands.json` that was generated most recently.
+    pub fn compilation_database_dir(&self) -> Option<PathBuf> {
+        if self
+            .source_dir
+            .join(COMPILATION_DATABASE_FILE_NAME)
+            .is_file()
+        {
+            return Some(self.source_dir.clone());
+        }
+
+        let mut candidates = self
+            .preset_build_dirs()
+            .into_iter()
+            .map(|(_, dir)| dir)
+            .collect::<Vec<_>>();
+        if let Ok(entries) = std::fs::read_dir(&self.source_dir) {
+            for entry in entries.flatten() {
+                let file_name = entry.file_name();
+                let file_name = file_name.to_string_lossy();
+                if file_name.starts_with("build") || file_name.starts_with("cmake-build-") {
+                    candidates.push(entry.path());
+                }
+            }
+        }
+        if let Ok(entries) = std::fs::read_dir(self.source_dir.join("out/build")) {
+            candidates.extend(entries.flatten().map(|entry| entry.path()));
+        }
+
+        candidates
+            .into_iter()
+            .filter_map(|dir| {
+                let modified = std::fs::metadata(dir.join(COMPILATION_DATABASE_FILE_NAME))
+                    .and_then(|metadata| metadata.modified())
+                    .ok()?;
+                Some((modified, dir))
+            })
+            .max_by_key(|(modified, _)| *modified)
+            .map(|(_, dir)| dir)
+    }
+
+    /// Returns the tasks that configure the project, build it or one of its targets, and run
+    /// its tests, for each of its presets.
+    pub fn task_templates(&self) -> TaskTemplates {
+        let cwd = self.source_dir.to_string_lossy().into_owned();
+        let build_dirs = if self.presets.configure_presets.is_empty() {
+            vec![(String::new(), DEFAULT_BUILD_DIR.to_owned())]
+        } else {
+            self.preset_build_dirs()
+                .into_iter()
+                .map(|(name, dir)| (format!(" (preset {name})"), self.relative_path(&dir)))
+                .collect()
+        };
+
+        let configures = if self.presets.configure_presets.is_empty() {
+            vec![(
+                String::new(),
+                vec![
+                    "-S".to_owned(),
+                    ".".to_owned(),
+                    "-B".to_owned(),
+                    DEFAULT_BUILD_DIR.to_owned(),
+                ],
+            )]
+        } else {
+            self.presets
+                .configure_presets
+                .iter()
+                .filter(|preset| !preset.hidden)
+                .map(|preset| {
+                    (
+                        format!(" (preset {})", preset.name),
+                        vec!["--preset".to_owned(), preset.name.clone()],
+                    )
+                })
+                .collect()
+        };
+        let builds = if self.presets.build_presets.is_empty() {
+            build_dirs
+                .iter()
+                .map(|(suffix, dir)| (suffix.clone(), vec!["--build".to_owned(), dir.clone()]))
+                .collect::<Vec<_>>()
+        } else {
+            visible_presets(&self.presets.build_presets)
+                .map(|name| {
+                    (
+                        format!(" (preset {name})"),
+                        vec!["--build".to_owned(), "--preset".to_owned(), name.to_owned()],
+                    )
+                })
+                .collect()
+        };
+        let tests = if self.presets.test_presets.is_empty() {
+            build_dirs
+                .iter()
+                .map(|(suffix, dir)| (suffix.clone(), vec!["--test-dir".to_owned(), dir.clone()]))
+                .collect::<Vec<_>>()
+        } else {
+            visible_presets(&self.presets.test_presets)
+                .map(|name| {
+                    (
+                        format!(" (preset {name})"),
+                        vec!["--preset".to_owned(), name.to_owned()],
+                    )
+                })
+                .collect()
+        };
+
+        let template = |label: String, command: &str, args: Vec<String>| TaskTemplate {
+            label,
+            command: command.to_owned(),
+            args,
+            cwd: Some(cwd.clone()),
+            ..TaskTemplate::default()
+        };
+        let mut templates = Vec::new();
+        for (suffix, mut args) in configures {
+            args.push(EXPORT_COMPILE_COMMANDS.to_owned());
+            templates.push(template(format!("cmake configure{suffix}"), "cmake", args));
+        }
+        for (suffix, args) in &builds {
+            templates.push(template(
+                format!("cmake build{suffix}"),
+                "cmake",
+                args.clone(),
+            ));
+            for target in &self.targets {
+                let mut args = args.clone();
+                args.extend(["--target".to_owned(), target.clone()]);
+                templates.push(template(
+                    format!("cmake build {target}{suffix}"),
+                    "cmake",
+                    args,
+                ));
+            }
+        }
+        for (suffix, args) in &tests {
+            let mut args = args.clone();
+            args.push("--output-on-failure".to_owned());
+            templates.push(template(format!("ctest{suffix}"), "ctest", args));
+        }
+
+        // Test cases are run with the first preset, after building what they need.
+        if let Some(((_, build_args), (_, test_args))) = builds.first().zip(tests.first()) {
+            templates.push(TaskTemplate {
+                label: format!("ctest {}", CMAKE_TEST_NAME_TASK_VARIABLE.template_value()),
+                command: format!(
+                    "cmake {} && ctest {} --output-on-failure -R",
+                    build_args.join(" "),
+                    test_args.join(" ")
+                ),
+                args: vec![format!(
+                    "\"{}\"",
+                    CMAKE_TEST_REGEX_TASK_VARIABLE.template_value()
+                )],
+                cwd: Some(cwd.clone()),
+                tags: vec!["cpp-gtest".to_owned(), "cpp-catch2".to_owned()],
+                ..TaskTemplate::default()
+            });
+        }
+        TaskTemplates(templates)
+    }
+
+    fn relative_path(&self, path: &Path) -> String {
+        path.strip_prefix(&self.source_dir)
+            .unwrap_or(path)
+            .to_string_lossy()
+            .into_owned()
+    }
+
+    /// Expands the macros of a preset `binaryDir`, which is relative to the source directory.
+    fn expand(&self, binary_dir: &str, preset_name: &str) -> PathBuf {
+        let source_dir = self.source_dir.to_string_lossy();
+        let source_parent_dir = self
+            .source_dir
+            .parent()
+            .map(|dir| dir.to_string_lossy())
+            .unwrap_or_default();
+        let source_dir_name = self
+            .source_dir
+            .file_name()
+            .map(|name| name.to_string_lossy())
+            .unwrap_or_default();
+        let expanded = binary_dir
+            .replace("${sourceDir}", &source_dir)
+            .replace("${sourceParentDir}", &source_parent_dir)
+            .replace("${sourceDirName}", &source_dir_name)
+            .replace("${presetName}", preset_name);
+        self.source_dir.join(expanded)
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct CMakePresets {
+    #[serde(default)]
+    pub configure_presets: Vec<ConfigurePreset>,
+    #[serde(default)]
+    pub build_presets: Vec<Preset>,
+    #[serde(default)]
+    pub test_presets: Vec<Preset>,
+}
+
+impl CMakePresets {
+    fn extend(&mut self, other: CMakePresets) {
+        self.configure_presets.extend(other.configure_presets);
+        self.build_presets.extend(other.build_presets);
+        self.test_presets.extend(other.test_presets);
+    }
+
+    /// Resolves the `binaryDir` of a configure preset, following the presets it inherits from.
+    fn binary_dir(&self, preset: &ConfigurePreset) -> Option<String> {
+        let mut visited = HashSet::default();
+        let mut pending = vec![preset];
+        while let Some(preset) = pending.pop() {
+            if !visited.insert(preset.name.as_str()) {
+                continue;
+            }
+            if let Some(binary_dir) = &preset.binary_dir {
+                return Some(binary_dir.clone());
+            }
+            // The first preset inherited from takes precedence.
+            for parent in preset.inherits.names().rev() {
+                if let Some(parent) = self
+                    .configure_presets
+                    .iter()
+                    .find(|candidate| candidate.name == parent)
+                {
+                    pending.push(parent);
+                }
+            }
+        }
+        None
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ConfigurePreset {
+    pub name: String,
+    #[serde(default)]
+    pub hidden: bool,
+    #[serde(default)]
+    pub inherits: Inherits,
+    pub binary_dir: Option<String>,
+}
+
+/// A build or test preset.
+#[derive(Debug, Deserialize)]
+pub(crate) struct Preset {
+    pub name: String,
+    #[serde(default)]
+    pub hidden: bool,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(untagged)]
+pub(crate) enum Inherits {
+    #[default]
+    None,
+    One(String),
+    Many(Vec<String>),
+}
+
+impl Inherits {
+    fn names(&self) -> impl DoubleEndedIterator<Item = &str> {
+        let names: &[String] = match self {
+            Inherits::None => &[],
+            Inherits::One(name) => std::slice::from_ref(name),
+            Inherits::Many(names) => names,
+        };
+        names.iter().map(String::as_str)
+    }
+}
+
+fn visible_presets(presets: &[Preset]) -> impl Iterator<Item = &str> {
+    presets
+        .iter()
+        .filter(|preset| !preset.hidden)
+        .map(|preset| preset.name.as_str())
+}
+
+static TARGET_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)\badd_(?:executable|library)\s*\(\s*([\w.+-]+)([^)]*)").unwrap()
+});
+static SUBDIRECTORY_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"(?i)\badd_subdirectory\s*\(\s*"?([\w./+-]+)"?"#).unwrap());
+
+/// Collects the targets defined by the `CMakeLists.txt` of the project and of the
+/// subdirectories it adds, along with the `CMakeLists.txt` files that were read.
+fn targets(source_dir: &Path) -> (BTreeSet<String>, Vec<PathBuf>) {
+    let mut targets = BTreeSet::new();
+    let mut lists_files = Vec::new();
+    let mut visited = HashSet::default();
+    let mut pending = vec![source_dir.to_path_buf()];
+    while let Some(dir) = pending.pop() {
+        if !visited.insert(dir.clone()) {
+            continue;
+        }
+        let lists_file = dir.join(LISTS_FILE_NAME);
+        let Ok(contents) = std::fs::read_to_string(&lists_file) else {
+            continue;
+        };
+        lists_files.push(lists_file);
+        let (dir_targets, subdirectories) = parse_lists_file(&contents);
+        targets.extend(dir_targets);
+        pending.extend(
+            subdirectories
+                .into_iter()
+                .map(|subdirectory| dir.join(subdirectory))
+                .filter(|subdirectory| subdirectory.starts_with(source_dir)),
+        );
+    }
+    (targets, lists_files)
+}
+
+/// Returns the targets a `CMakeLists.txt` builds, along with the subdirectories it adds.
+fn parse_lists_file(contents: &str) -> (Vec<String>, Vec<String>) {
+    let contents = contents
+        .lines()
+        .map(|line| line.split_once('#').map_or(line, |(code, _)| code))
+        .collect::<Vec<_>>()
+        .join("\n");
+    let targets = TARGET_REGEX
+        .captures_iter(&contents)
+        .filter(|captures| {
+            // Imported and alias targets are not built by the project.
+            !captures[2].split_whitespace().any(|argument| {
+                argument.eq_ignore_ascii_case("IMPORTED") || argument.eq_ignore_ascii_case("ALIAS")
+            })
+        })
+        .map(|captures| captures[1].to_owned())
+        .collect();
+    let subdirectories = SUBDIRECTORY_REGEX
+        .captures_iter(&contents)
+        .map(|captures| captures[1].to_owned())
+        .collect();
+    (targets, subdirectories)
+}
+
+/// Builds the name and the `ctest -R` regex of the tests registered for a GoogleTest or Catch2
+/// test case by `gtest_discover_tests` and `catch_discover_tests`.
+pub(crate) fn test_regex(
+    gtest_suite: Option<&str>,
+    gtest_name: Option<&str>,
+    catch2_name: Option<&str>,
+) -> Option<(String, String)> {
+    if let (Some(suite), Some(name)) = (gtest_suite, gtest_name) {
+        let test_name = format!("{suite}.{name}");
+        // Parameterized tests are registered as `Instantiation/Suite.Name/Parameter`.
+        let regex = format!("(^|/){}(/|$)", regex::escape(&test_name));
+        return Some((test_name, regex));
+    }
+    let name = catch2_name?.strip_prefix('"')?.strip_suffix('"')?;
+    Some((name.to_owned(), format!("^{}$", regex::escape(name))))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_lists_file() {
+        let (targets, subdirectories) = parse_lists_file(
+            r#"
+            cmake_minimum_required(VERSION 3.20)
+            project(demo CXX)
+            add_library(core STATIC src/core.cpp)
+            add_library(demo::core ALIAS core)
+            add_library(zlib SHARED IMPORTED)
+            # add_executable(disabled main.cpp)
+            add_executable(demo-app
+                src/main.cpp)
+            ADD_SUBDIRECTORY(tests)
+            add_subdirectory("third_party/fmt" EXCLUDE_FROM_ALL)
+            "#,
+        );
+        assert_eq!(targets, ["core", "demo-app"]);
+        assert_eq!(subdirectories, ["tests", "third_party/fmt"]);
+    }
+
+    #[test]
+    fn test_preset_build_dirs() {
+        let presets: CMakePresets = serde_json::from_str(
+            r#"{
+                "version": 3,
+                "configurePresets": [
+                    { "name": "base", "hidden": true, "binaryDir": "${sourceDir}/out/${presetName}" },
+                    { "name": "debug", "inherits": "base" },
+                    { "name": "release", "inherits": ["release-base", "base"] },
+                    { "name": "release-base", "hidden": true, "binaryDir": "build-release" },
+                    { "name": "no-dir" }
+                ],
+                "buildPresets": [{ "name": "debug", "configurePreset": "debug" }]
+            }"#,
+        )
+        .unwrap();
+        let project = CMakeProject {
+            source_dir: PathBuf::from("/src/demo"),
+            presets,
+            targets: BTreeSet::new(),
+            sources: Vec::new(),
+        };
+        assert_eq!(
+            project.preset_build_dirs(),
+            [
+                ("debug", PathBuf::from("/src/demo/out/debug")),
+                ("release", PathBuf::from("/src/demo/build-release")),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_task_templates() {
+        let project = CMakeProject {
+            source_dir: PathBuf::from("/src/demo"),
+            presets: CMakePresets::default(),
+            targets: BTreeSet::from_iter(["app".to_owned()]),
+            sources: Vec::new(),
+        };
+        let templates = project.task_templates().0;
+        assert_eq!(
+            templates
+                .iter()
+                .map(|template| (
+                    template.label.as_str(),
+                    template.command.as_str(),
+                    template.args.join(" ")
+                ))
+                .collect::<Vec<_>>(),
+            [
+                (
+                    "cmake configure",
+                    "cmake",
+                    "-S . -B build -DCMAKE_EXPORT_COMPILE_COMMANDS=ON".to_owned()
+                ),
+                ("cmake build", "cmake", "--build build".to_owned()),
+                (
+                    "cmake build app",
+                    "cmake",
+                    "--build build --target app".to_owned()
+                ),
+                (
+                    "ctest",
+                    "ctest",
+                    "--test-dir build --output-on-failure".to_owned()
+                ),
+                (
+                    "ctest $ZED_CUSTOM_CMAKE_TEST_NAME",
+                    "cmake --build build && ctest --test-dir build --output-on-failure -R",
+                    "\"$ZED_CUSTOM_CMAKE_TEST_REGEX\"".to_owned()
+                ),
+            ]
+        );
+        assert!(templates
+            .iter()
+            .all(|template| template.cwd.as_deref() == Some("/src/demo")));
+    }
+
+    #[test]
+    fn test_test_regex() {
+        assert_eq!(
+            test_regex(Some("Parser"), Some("Empty"), None),
+            Some((
+                "Parser.Empty".to_owned(),
+                r"(^|/)Parser\.Empty(/|$)".to_owned()
+            ))
+        );
+        assert_eq!(
+            test_regex(None, None, Some(r#""vectors (resize)""#)),
+            Some((
+                "vectors (resize)".to_owned(),
+                r"^vectors \(resize\)$".to_owned()
+            ))
+        );
+        assert_eq!(test_regex(None, Some("Empty"), None), None);
+    }
+}
@@ -0,0 +1,34 @@
+; GoogleTest test cases, such as `TEST(Suite, Name)` and `TEST_F(Fixture, Name)`
+(
+  (function_definition
+    !type
+    declarator: (function_declarator
+      declarator: (identifier) @_gtest_macro
+      parameters: (parameter_list
+        .
+        (parameter_declaration
+          type: (_) @_gtest_suite)
+        .
+        (parameter_declaration
+          type: (_) @run @_gtest_name)
+        .)
+    )
+  ) @_cpp-gtest
+  (#match? @_gtest_macro "^(TEST|TEST_F|TEST_P|TYPED_TEST|TYPED_TEST_P)$")
+  (#set! tag cpp-gtest)
+)
+
+; Catch2 test cases, such as `TEST_CASE("name", "[tag]")` and `SCENARIO("name")`, which parse as a
+; call missing its semicolon, followed by a block
+(
+  (expression_statement
+    (call_expression
+      function: (identifier) @run @_catch2_macro
+      arguments: (argument_list
+        .
+        (string_literal) @_catch2_name))) @_cpp-catch2
+  .
+  (compound_statement)
+  (#match? @_catch2_macro "^(TEST_CASE|SCENARIO)$")
+  (#set! tag cpp-catch2)
+)
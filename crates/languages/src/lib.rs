@@ -12,13 +12,21 @@ use std::{str, sync::Arc};
 use typescript::typescript_task_context;
 use util::{asset_str, ResultExt};
 
-use crate::{bash::bash_task_context, go::GoContextProvider, rust::RustContextProvider};
+use crate::{
+    bash::bash_task_context,
+    c::CContextProvider,
+    go::GoContextProvider,
+    node::NodeToolchainProvider,
+    rust::{RustContextProvider, RustToolchainProvider},
+};
 
 mod bash;
 mod c;
+mod cmake;
 mod css;
 mod go;
 mod json;
+mod node;
 mod python;
 mod rust;
 mod tailwind;
@@ -141,8 +149,16 @@ pub fn init(languages: Arc<LanguageRegistry>, node_runtime: NodeRuntime, cx: &mu
         };
     }
     language!("bash", Vec::new(), bash_task_context());
-    language!("c", vec![Arc::new(c::CLspAdapter) as Arc<dyn LspAdapter>]);
-    language!("cpp", vec![Arc::new(c::CLspAdapter)]);
+    language!(
+        "c",
+        vec![Arc::new(c::CLspAdapter) as Arc<dyn LspAdapter>],
+        CContextProvider::default()
+    );
+    language!(
+        "cpp",
+        vec![Arc::new(c::CLspAdapter)],
+        CContextProvider::default()
+    );
     language!(
         "css",
         vec![Arc::new(css::CssLspAdapter::new(node_runtime.clone())),]
@@ -189,7 +205,8 @@ pub fn init(languages: Arc<LanguageRegistry>, node_runtime: NodeRuntime, cx: &mu
     language!(
         "rust",
         vec![Arc::new(rust::RustLspAdapter)],
-        RustContextProvider
+        RustContextProvider::default(),
+        Arc::new(RustToolchainProvider) as Arc<dyn ToolchainLister>
     );
     language!(
         "tsx",
@@ -197,7 +214,8 @@ pub fn init(languages: Arc<LanguageRegistry>, node_runtime: NodeRuntime, cx: &mu
             Arc::new(typescript::TypeScriptLspAdapter::new(node_runtime.clone())),
             Arc::new(vtsls::VtslsLspAdapter::new(node_runtime.clone()))
         ],
-        typescript_task_context()
+        typescript_task_context(),
+        Arc::new(NodeToolchainProvider::new("TSX")) as Arc<dyn ToolchainLister>
     );
     language!(
         "typescript",
@@ -205,7 +223,8 @@ pub fn init(languages: Arc<LanguageRegistry>, node_runtime: NodeRuntime, cx: &mu
             Arc::new(typescript::TypeScriptLspAdapter::new(node_runtime.clone())),
             Arc::new(vtsls::VtslsLspAdapter::new(node_runtime.clone()))
         ],
-        typescript_task_context()
+        typescript_task_context(),
+        Arc::new(NodeToolchainProvider::new("TypeScript")) as Arc<dyn ToolchainLister>
     );
     language!(
         "javascript",
@@ -213,7 +232,8 @@ pub fn init(languages: Arc<LanguageRegistry>, node_runtime: NodeRuntime, cx: &mu
             Arc::new(typescript::TypeScriptLspAdapter::new(node_runtime.clone())),
             Arc::new(vtsls::VtslsLspAdapter::new(node_runtime.clone()))
         ],
-        typescript_task_context()
+        typescript_task_context(),
+        Arc::new(NodeToolchainProvider::new("JavaScript")) as Arc<dyn ToolchainLister>
     );
     language!(
         "jsdoc",
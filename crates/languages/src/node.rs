@@ -0,0 +1,446 @@
+use std::{
+    borrow::Cow,
+    ffi::OsString,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use anyhow::{Context as _, Result};
+use async_trait::async_trait;
+use collections::HashMap;
+use gpui::{AppContext, AsyncAppContext, SharedString, Task};
+use language::{
+    ContextProvider, LanguageName, LanguageToolchainStore, Location, LspAdapterDelegate, Toolchain,
+    ToolchainList, ToolchainLister,
+};
+use lsp::LanguageServerBinary;
+use node_runtime::NodeRuntime;
+use project::toolchain_store::active_node_toolchain;
+use semver::{Version, VersionReq};
+use serde::Serialize;
+use serde_json::json;
+use task::{TaskTemplates, TaskVariables, VariableName};
+use util::ResultExt;
+
+#[cfg(not(windows))]
+const NODE_BINARY: &str = "bin/node";
+#[cfg(windows)]
+const NODE_BINARY: &str = "node.exe";
+
+#[cfg(not(windows))]
+const NODE_EXECUTABLE: &str = "node";
+#[cfg(windows)]
+const NODE_EXECUTABLE: &str = "node.exe";
+
+/// The `PATH` tasks run with, starting with the directory of the selected Node.js version.
+const NODE_PATH_TASK_VARIABLE: VariableName =
+    VariableName::Custom(Cow::Borrowed("NODE_TOOLCHAIN_PATH"));
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum NodeVersionManager {
+    Nvm,
+    Fnm,
+    Volta,
+    Asdf,
+    System,
+}
+
+impl NodeVersionManager {
+    fn name(&self) -> &'static str {
+        match self {
+            NodeVersionManager::Nvm => "nvm",
+            NodeVersionManager::Fnm => "fnm",
+            NodeVersionManager::Volta => "volta",
+            NodeVersionManager::Asdf => "asdf",
+            NodeVersionManager::System => "system",
+        }
+    }
+}
+
+#[derive(Debug)]
+struct NodeInstallation {
+    version: Version,
+    node: PathBuf,
+    manager: NodeVersionManager,
+}
+
+/// Lists the Node.js versions installed with nvm, fnm, volta and asdf, along with the one on
+/// the `PATH`. The version pinned by the project in `.nvmrc`, `.node-version` or the `engines`
+/// of its `package.json` is the default one.
+pub(crate) struct NodeToolchainProvider {
+    language_name: LanguageName,
+}
+
+impl NodeToolchainProvider {
+    pub(crate) fn new(language_name: &str) -> Self {
+        Self {
+            language_name: LanguageName::new(language_name),
+        }
+    }
+}
+
+#[async_trait]
+impl ToolchainLister for NodeToolchainProvider {
+    async fn list(
+        &self,
+        worktree_root: PathBuf,
+        project_env: Option<HashMap<String, String>>,
+    ) -> ToolchainList {
+        let env = project_env.unwrap_or_default();
+        let mut installations = managed_installations(&env);
+        if let Some(system_installation) = system_installation(&env).await {
+            installations.push(system_installation);
+        }
+        installations.sort_by(|lhs, rhs| {
+            rhs.version
+                .cmp(&lhs.version)
+                .then_with(|| lhs.manager.cmp(&rhs.manager))
+        });
+        installations.dedup_by(|lhs, rhs| lhs.node == rhs.node);
+
+        let pinned = pinned_versions(&worktree_root);
+        let default = pinned
+            .as_ref()
+            .and_then(|requirements| {
+                installations.iter().position(|installation| {
+                    requirements
+                        .iter()
+                        .any(|requirement| requirement.matches(&installation.version))
+                })
+            })
+            .or_else(|| {
+                installations
+                    .iter()
+                    .position(|installation| installation.manager == NodeVersionManager::System)
+            });
+
+        let toolchains = installations
+            .into_iter()
+            .filter_map(|installation| {
+                Some(Toolchain {
+                    name: format!(
+                        "Node.js {} ({})",
+                        installation.version,
+                        installation.manager.name()
+                    )
+                    .into(),
+                    path: installation.node.to_str()?.to_owned().into(),
+                    language_name: self.language_name.clone(),
+                    as_json: json!({
+                        "version": installation.version.to_string(),
+                        "manager": installation.manager,
+                    }),
+                })
+            })
+            .collect();
+        ToolchainList {
+            toolchains,
+            default,
+            groups: Default::default(),
+        }
+    }
+
+    fn term(&self) -> SharedString {
+        SharedString::new_static("Node.js Version")
+    }
+}
+
+fn env_path(env: &HashMap<String, String>, name: &str) -> Option<PathBuf> {
+    env.get(name)
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os(name).map(PathBuf::from))
+}
+
+/// Finds the Node.js versions installed by the version managers, in their default locations
+/// unless the environment points somewhere else.
+fn managed_installations(env: &HashMap<String, String>) -> Vec<NodeInstallation> {
+    let home = util::paths::home_dir();
+    let fnm_dirs = match env_path(env, "FNM_DIR") {
+        Some(fnm_dir) => vec![fnm_dir],
+        None => vec![
+            env_path(env, "XDG_DATA_HOME")
+                .unwrap_or_else(|| home.join(".local/share"))
+                .join("fnm"),
+            home.join("Library/Application Support/fnm"),
+            home.join(".fnm"),
+        ],
+    };
+
+    let mut versions_dirs = vec![
+        (
+            NodeVersionManager::Nvm,
+            env_path(env, "NVM_DIR")
+                .unwrap_or_else(|| home.join(".nvm"))
+                .join("versions/node"),
+            Path::new(NODE_BINARY).to_path_buf(),
+        ),
+        (
+            NodeVersionManager::Volta,
+            env_path(env, "VOLTA_HOME")
+                .unwrap_or_else(|| home.join(".volta"))
+                .join("tools/image/node"),
+            Path::new(NODE_BINARY).to_path_buf(),
+        ),
+        (
+            NodeVersionManager::Asdf,
+            env_path(env, "ASDF_DATA_DIR")
+                .unwrap_or_else(|| home.join(".asdf"))
+                .join("installs/nodejs"),
+            Path::new(NODE_BINARY).to_path_buf(),
+        ),
+    ];
+    versions_dirs.extend(fnm_dirs.into_iter().map(|fnm_dir| {
+        (
+            NodeVersionManager::Fnm,
+            fnm_dir.join("node-versions"),
+            Path::new("installation").join(NODE_BINARY),
+        )
+    }));
+
+    let mut installations = Vec::new();
+    for (manager, versions_dir, node_binary) in versions_dirs {
+        let Ok(entries) = std::fs::read_dir(&versions_dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let Some(version) = entry.file_name().to_str().and_then(parse_node_version) else {
+                continue;
+            };
+            let node = entry.path().join(&node_binary);
+            if node.is_file() {
+                installations.push(NodeInstallation {
+                    version,
+                    node,
+                    manager,
+                });
+            }
+        }
+    }
+    installations
+}
+
+/// The Node.js found on the `PATH` of the project.
+async fn system_installation(env: &HashMap<String, String>) -> Option<NodeInstallation> {
+    let path = env
+        .get("PATH")
+        .map(OsString::from)
+        .or_else(|| std::env::var_os("PATH"))?;
+    let node = std::env::split_paths(&path)
+        .map(|dir| dir.join(NODE_EXECUTABLE))
+        .find(|node| node.is_file())?;
+    let output = util::command::new_smol_command(&node)
+        .arg("--version")
+        .output()
+        .await
+        .log_err()?;
+    let version = parse_node_version(String::from_utf8_lossy(&output.stdout).trim())?;
+    Some(NodeInstallation {
+        version,
+        node,
+        manager: NodeVersionManager::System,
+    })
+}
+
+fn parse_node_version(version: &str) -> Option<Version> {
+    Version::parse(version.strip_prefix('v').unwrap_or(version)).ok()
+}
+
+/// Returns the requirements the project puts on the Node.js version, any of which is enough.
+/// `.nvmrc` and `.node-version` take precedence over the `engines` of `package.json`.
+fn pinned_versions(worktree_root: &Path) -> Option<Vec<VersionReq>> {
+    [".nvmrc", ".node-version"]
+        .into_iter()
+        .find_map(|file_name| {
+            let contents = std::fs::read_to_string(worktree_root.join(file_name)).ok()?;
+            parse_version_file(&contents).map(|requirement| vec![requirement])
+        })
+        .or_else(|| {
+            let package_json = std::fs::read_to_string(worktree_root.join("package.json")).ok()?;
+            let package_json: serde_json::Value = serde_json::from_str(&package_json).ok()?;
+            parse_engines_range(package_json.get("engines")?.get("node")?.as_str()?)
+        })
+}
+
+/// Parses the version in a `.nvmrc` or `.node-version` file, such as `18`, `v18.17` or
+/// `18.17.0`, which stand for the newest matching version. Aliases such as `lts/*` are not
+/// supported, as resolving them needs the list of releases.
+fn parse_version_file(contents: &str) -> Option<VersionReq> {
+    let version = contents.lines().next()?.trim();
+    let version = version.strip_prefix('v').unwrap_or(version);
+    if !version.starts_with(|c: char| c.is_ascii_digit()) {
+        return None;
+    }
+    VersionReq::parse(&format!("={version}")).ok()
+}
+
+/// Parses an npm version range, such as `>=18 <21 || ^16.14`, into the requirements of its
+/// alternatives.
+fn parse_engines_range(range: &str) -> Option<Vec<VersionReq>> {
+    let requirements = range
+        .split("||")
+        .map(|alternative| {
+            let mut comparators = Vec::new();
+            let mut pending_operator = String::new();
+            for token in alternative.split_whitespace() {
+                if token.chars().all(|c| "<>=~^".contains(c)) {
+                    pending_operator.push_str(token);
+                } else {
+                    comparators.push(format!("{}{token}", std::mem::take(&mut pending_operator)));
+                }
+            }
+            if comparators.is_empty() {
+                VersionReq::parse("*")
+            } else {
+                VersionReq::parse(&comparators.join(", "))
+            }
+        })
+        .collect::<Result<Vec<_>, _>>()
+        .ok()?;
+    (!requirements.is_empty()).then_some(requirements)
+}
+
+fn prepend_to_path(dir: &Path, path: Option<&str>) -> Option<String> {
+    let paths = std::iter::once(dir.to_path_buf()).chain(
+        path.into_iter()
+            .flat_map(|path| std::env::split_paths(path).collect::<Vec<_>>()),
+    );
+    std::env::join_paths(paths).log_err()?.into_string().ok()
+}
+
+/// Runs a language server written in JavaScript with the Node.js version selected in the worktree.
+pub(crate) async fn binary_for_node_toolchain(
+    mut binary: LanguageServerBinary,
+    node: &NodeRuntime,
+    delegate: &dyn LspAdapterDelegate,
+    toolchains: Arc<dyn LanguageToolchainStore>,
+    cx: &mut AsyncAppContext,
+) -> LanguageServerBinary {
+    let Some(toolchain) = active_node_toolchain(toolchains, delegate.worktree_id(), None, cx).await
+    else {
+        return binary;
+    };
+    let toolchain_node = PathBuf::from(toolchain.path.as_ref());
+    if node.binary_path().await.ok().as_ref() == Some(&binary.path) {
+        binary.path = toolchain_node.clone();
+    }
+
+    // Servers installed outside of Zed start Node.js through their shebang.
+    if let Some(bin_dir) = toolchain_node.parent() {
+        let mut env = match binary.env.take() {
+            Some(env) => env,
+            None => delegate.shell_env().await,
+        };
+        if let Some(path) = prepend_to_path(bin_dir, env.get("PATH").map(String::as_str)) {
+            env.insert("PATH".to_owned(), path);
+        }
+        binary.env = Some(env);
+    }
+    binary
+}
+
+/// Provides tasks that run with the Node.js version selected in the worktree.
+pub(crate) struct NodeContextProvider {
+    templates: TaskTemplates,
+}
+
+impl NodeContextProvider {
+    pub(crate) fn new(mut templates: TaskTemplates) -> Self {
+        for template in &mut templates.0 {
+            template
+                .env
+                .insert("PATH".to_owned(), NODE_PATH_TASK_VARIABLE.template_value());
+        }
+        Self { templates }
+    }
+}
+
+impl ContextProvider for NodeContextProvider {
+    fn build_context(
+        &self,
+        _: &TaskVariables,
+        location: &Location,
+        project_env: Option<HashMap<String, String>>,
+        toolchains: Arc<dyn LanguageToolchainStore>,
+        cx: &mut AppContext,
+    ) -> Task<Result<TaskVariables>> {
+        let buffer = location.buffer.read(cx);
+        let worktree_id = buffer.file().map(|file| file.worktree_id(cx));
+        let language_name = buffer.language().map(|language| language.name());
+        cx.spawn(|mut cx| async move {
+            let toolchain = match worktree_id {
+                Some(worktree_id) => {
+                    active_node_toolchain(toolchains, worktree_id, language_name, &mut cx).await
+                }
+                None => None,
+            };
+            let path = project_env
+                .and_then(|mut env| env.remove("PATH"))
+                .or_else(|| std::env::var("PATH").ok())
+                .unwrap_or_default();
+            let path = match toolchain
+                .as_ref()
+                .and_then(|toolchain| Path::new(toolchain.path.as_ref()).parent())
+            {
+                Some(bin_dir) => {
+                    prepend_to_path(bin_dir, Some(&path)).context("building the PATH of tasks")?
+                }
+                None => path,
+            };
+            Ok(TaskVariables::from_iter([(
+                NODE_PATH_TASK_VARIABLE.clone(),
+                path,
+            )]))
+        })
+    }
+
+    fn associated_tasks(
+        &self,
+        _: Option<Arc<dyn language::File>>,
+        _: &AppContext,
+    ) -> Option<TaskTemplates> {
+        Some(self.templates.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_version_file() {
+        let matches = |contents: &str, version: &str| {
+            parse_version_file(contents)
+                .unwrap()
+                .matches(&Version::parse(version).unwrap())
+        };
+        assert!(matches("18\n", "18.20.4"));
+        assert!(!matches("18\n", "20.1.0"));
+        assert!(matches("v18.17", "18.17.1"));
+        assert!(!matches("v18.17", "18.18.0"));
+        assert!(matches("20.11.1", "20.11.1"));
+        assert!(!matches("20.11.1", "20.11.2"));
+        assert!(parse_version_file("lts/*").is_none());
+        assert!(parse_version_file("").is_none());
+    }
+
+    #[test]
+    fn test_parse_engines_range() {
+        let matches = |range: &str, version: &str| {
+            let version = Version::parse(version).unwrap();
+            parse_engines_range(range)
+                .unwrap()
+                .iter()
+                .any(|requirement| requirement.matches(&version))
+        };
+        assert!(matches(">=18", "22.0.0"));
+        assert!(!matches(">=18", "16.20.0"));
+        assert!(matches(">= 16 < 20", "18.0.0"));
+        assert!(!matches(">= 16 < 20", "20.0.0"));
+        assert!(matches("^16.14 || >=18", "16.20.0"));
+        assert!(matches("^16.14 || >=18", "21.0.0"));
+        assert!(!matches("^16.14 || >=18", "17.0.0"));
+        assert!(matches("18.x", "18.3.0"));
+        assert!(parse_engines_range("not a range").is_none());
+    }
+}
@@ -1,27 +1,37 @@
-use anyhow::{anyhow, Context, Result};
+use anyhow::{anyhow, ensure, Context, Result};
 use async_compression::futures::bufread::GzipDecoder;
 use async_trait::async_trait;
 use collections::HashMap;
-use futures::{io::BufReader, StreamExt};
-use gpui::{AppContext, AsyncAppContext, Task};
+use futures::{
+    future::{FutureExt as _, Shared},
+    io::BufReader,
+    StreamExt,
+};
+use gpui::{AppContext, AsyncAppContext, SharedString, Task};
 use http_client::github::AssetKind;
 use http_client::github::{latest_github_release, GitHubLspBinaryVersion};
 pub use language::*;
 use lsp::{LanguageServerBinary, LanguageServerName};
+use project::lsp_store::language_server_settings;
 use regex::Regex;
+use serde_json::{json, Value};
+use settings::WorktreeId;
 use smol::fs::{self};
 use std::fmt::Display;
 use std::{
     any::Any,
     borrow::Cow,
     path::{Path, PathBuf},
-    sync::{Arc, LazyLock},
+    sync::{Arc, LazyLock, Mutex},
 };
 use task::{TaskTemplate, TaskTemplates, TaskVariables, VariableName};
 use util::{fs::remove_matching, maybe, ResultExt};
 
 use crate::language_settings::language_settings;
 
+/// The environment variable rustup reads the toolchain to use from.
+const RUSTUP_TOOLCHAIN_ENV: &str = "RUSTUP_TOOLCHAIN";
+
 pub struct RustLspAdapter;
 
 #[cfg(target_os = "macos")]
@@ -77,11 +87,23 @@ impl LspAdapter for RustLspAdapter {
     async fn check_if_user_installed(
         &self,
         delegate: &dyn LspAdapterDelegate,
-        _: Arc<dyn LanguageToolchainStore>,
-        _: &AsyncAppContext,
+        toolchains: Arc<dyn LanguageToolchainStore>,
+        cx: &AsyncAppContext,
     ) -> Option<LanguageServerBinary> {
         let path = delegate.which("rust-analyzer".as_ref()).await?;
-        let env = delegate.shell_env().await;
+        let mut env = delegate.shell_env().await;
+
+        // When rust-analyzer is a rustup proxy, this makes it run the selected toolchain's server.
+        if let Some(toolchain) = toolchains
+            .active_toolchain(
+                delegate.worktree_id(),
+                LanguageName::new("Rust"),
+                &mut cx.clone(),
+            )
+            .await
+        {
+            env.insert(RUSTUP_TOOLCHAIN_ENV.to_owned(), toolchain.name.to_string());
+        }
 
         // It is surprisingly common for ~/.cargo/bin/rust-analyzer to be a symlink to
         // /usr/bin/rust-analyzer that fails when you run it; so we need to test it.
@@ -243,6 +265,60 @@ impl LspAdapter for RustLspAdapter {
         }
     }
 
+    async fn workspace_configuration(
+        self: Arc<Self>,
+        delegate: &Arc<dyn LspAdapterDelegate>,
+        toolchains: Arc<dyn LanguageToolchainStore>,
+        cx: &mut AsyncAppContext,
+    ) -> Result<Value> {
+        let toolchain = toolchains
+            .active_toolchain(delegate.worktree_id(), LanguageName::new("Rust"), cx)
+            .await;
+        cx.update(move |cx| {
+            let settings = language_server_settings(delegate.as_ref(), &Self::SERVER_NAME, cx);
+            let mut user_settings = settings
+                .and_then(|settings| settings.settings.clone())
+                .unwrap_or_default();
+            let Some(toolchain) = toolchain else {
+                return user_settings;
+            };
+
+            // rust-analyzer replaces its initialization options with the configuration it pulls,
+            // so start from them unless the user configured the section explicitly.
+            let mut config = user_settings
+                .get("rust-analyzer")
+                .or_else(|| settings.and_then(|settings| settings.initialization_options.as_ref()))
+                .filter(|config| config.is_object())
+                .cloned()
+                .unwrap_or_else(|| json!({}));
+            if let Some(extra_env) = config
+                .as_object_mut()
+                .and_then(|config| {
+                    config
+                        .entry("cargo")
+                        .or_insert_with(|| json!({}))
+                        .as_object_mut()
+                })
+                .and_then(|cargo| {
+                    cargo
+                        .entry("extraEnv")
+                        .or_insert_with(|| json!({}))
+                        .as_object_mut()
+                })
+            {
+                extra_env
+                    .entry(RUSTUP_TOOLCHAIN_ENV)
+                    .or_insert_with(|| Value::String(toolchain.name.to_string()));
+            }
+
+            if !user_settings.is_object() {
+                user_settings = json!({});
+            }
+            user_settings["rust-analyzer"] = config;
+            user_settings
+        })
+    }
+
     async fn label_for_completion(
         &self,
         completion: &lsp::CompletionItem,
@@ -436,7 +512,12 @@ impl LspAdapter for RustLspAdapter {
     }
 }
 
-pub(crate) struct RustContextProvider;
+#[derive(Default)]
+pub(crate) struct RustContextProvider {
+    /// The toolchain rustup resolves in each worktree, so that rustup is run once per worktree
+    /// rather than every time a task context is built.
+    rustup_toolchains: Arc<Mutex<HashMap<WorktreeId, Shared<Task<Option<String>>>>>>,
+}
 
 const RUST_PACKAGE_TASK_VARIABLE: VariableName =
     VariableName::Custom(Cow::Borrowed("RUST_PACKAGE"));
@@ -452,53 +533,106 @@ const RUST_BIN_KIND_TASK_VARIABLE: VariableName =
 const RUST_MAIN_FUNCTION_TASK_VARIABLE: VariableName =
     VariableName::Custom(Cow::Borrowed("_rust_main_function_end"));
 
+/// The toolchain cargo tasks are run with: the one selected for the worktree, or the one rustup
+/// resolves. It is empty when neither is known, which rustup treats like an unset `RUSTUP_TOOLCHAIN`.
+const RUST_TOOLCHAIN_TASK_VARIABLE: VariableName =
+    VariableName::Custom(Cow::Borrowed("RUST_TOOLCHAIN"));
+
+fn package_task_variables(
+    task_variables: &TaskVariables,
+    local_abs_path: Option<&Path>,
+    project_env: Option<&HashMap<String, String>>,
+) -> TaskVariables {
+    let is_main_function = task_variables
+        .get(&RUST_MAIN_FUNCTION_TASK_VARIABLE)
+        .is_some();
+
+    if is_main_function {
+        if let Some(target) = local_abs_path
+            .and_then(|path| package_name_and_bin_name_from_abs_path(path, project_env))
+        {
+            return TaskVariables::from_iter([
+                (RUST_PACKAGE_TASK_VARIABLE.clone(), target.package_name),
+                (RUST_BIN_NAME_TASK_VARIABLE.clone(), target.target_name),
+                (
+                    RUST_BIN_KIND_TASK_VARIABLE.clone(),
+                    target.target_kind.to_string(),
+                ),
+            ]);
+        }
+    }
+
+    if let Some(package_name) = local_abs_path
+        .and_then(|local_abs_path| local_abs_path.parent())
+        .and_then(|path| human_readable_package_name(path, project_env))
+    {
+        return TaskVariables::from_iter([(RUST_PACKAGE_TASK_VARIABLE.clone(), package_name)]);
+    }
+
+    TaskVariables::default()
+}
+
 impl ContextProvider for RustContextProvider {
     fn build_context(
         &self,
         task_variables: &TaskVariables,
         location: &Location,
         project_env: Option<HashMap<String, String>>,
-        _: Arc<dyn LanguageToolchainStore>,
+        toolchains: Arc<dyn LanguageToolchainStore>,
         cx: &mut gpui::AppContext,
     ) -> Task<Result<TaskVariables>> {
-        let local_abs_path = location
-            .buffer
-            .read(cx)
-            .file()
-            .and_then(|file| Some(file.as_local()?.abs_path(cx)));
-
-        let local_abs_path = local_abs_path.as_deref();
-
-        let is_main_function = task_variables
-            .get(&RUST_MAIN_FUNCTION_TASK_VARIABLE)
-            .is_some();
-
-        if is_main_function {
-            if let Some(target) = local_abs_path.and_then(|path| {
-                package_name_and_bin_name_from_abs_path(path, project_env.as_ref())
-            }) {
-                return Task::ready(Ok(TaskVariables::from_iter([
-                    (RUST_PACKAGE_TASK_VARIABLE.clone(), target.package_name),
-                    (RUST_BIN_NAME_TASK_VARIABLE.clone(), target.target_name),
-                    (
-                        RUST_BIN_KIND_TASK_VARIABLE.clone(),
-                        target.target_kind.to_string(),
-                    ),
-                ])));
-            }
-        }
+        let file = location.buffer.read(cx).file().cloned();
+        let worktree_id = file.as_ref().map(|file| file.worktree_id(cx));
+        let local_abs_path = file.and_then(|file| Some(file.as_local()?.abs_path(cx)));
+        let mut variables = package_task_variables(
+            task_variables,
+            local_abs_path.as_deref(),
+            project_env.as_ref(),
+        );
 
-        if let Some(package_name) = local_abs_path
-            .and_then(|local_abs_path| local_abs_path.parent())
-            .and_then(|path| human_readable_package_name(path, project_env.as_ref()))
-        {
-            return Task::ready(Ok(TaskVariables::from_iter([(
-                RUST_PACKAGE_TASK_VARIABLE.clone(),
-                package_name,
-            )])));
-        }
+        let directory = local_abs_path
+            .as_deref()
+            .and_then(Path::parent)
+            .map(Path::to_path_buf);
+        let rustup_toolchains = self.rustup_toolchains.clone();
+        cx.spawn(move |mut cx| async move {
+            let Some(worktree_id) = worktree_id else {
+                variables.insert(RUST_TOOLCHAIN_TASK_VARIABLE.clone(), String::new());
+                return Ok(variables);
+            };
 
-        Task::ready(Ok(TaskVariables::default()))
+            let toolchain = match toolchains
+                .active_toolchain(worktree_id, LanguageName::new("Rust"), &mut cx)
+                .await
+            {
+                Some(toolchain) => Some(toolchain.name.to_string()),
+                None => {
+                    let rustup_toolchain = rustup_toolchains
+                        .lock()
+                        .unwrap()
+                        .entry(worktree_id)
+                        .or_insert_with(|| {
+                            cx.background_executor()
+                                .spawn(async move {
+                                    active_rustup_toolchain(
+                                        directory.as_deref(),
+                                        project_env.as_ref(),
+                                    )
+                                    .await
+                                    .log_err()
+                                })
+                                .shared()
+                        })
+                        .clone();
+                    rustup_toolchain.await
+                }
+            };
+            variables.insert(
+                RUST_TOOLCHAIN_TASK_VARIABLE.clone(),
+                toolchain.unwrap_or_default(),
+            );
+            Ok(variables)
+        })
     }
 
     fn associated_tasks(
@@ -517,7 +651,7 @@ impl ContextProvider for RustContextProvider {
         } else {
             vec!["run".into()]
         };
-        Some(TaskTemplates(vec![
+        let mut templates = vec![
             TaskTemplate {
                 label: format!(
                     "cargo check -p {}",
@@ -622,10 +756,225 @@ impl ContextProvider for RustContextProvider {
                 cwd: Some("$ZED_DIRNAME".to_owned()),
                 ..TaskTemplate::default()
             },
-        ]))
+        ];
+        for template in &mut templates {
+            template.env.insert(
+                RUSTUP_TOOLCHAIN_ENV.to_owned(),
+                RUST_TOOLCHAIN_TASK_VARIABLE.template_value(),
+            );
+        }
+        Some(TaskTemplates(templates))
+    }
+}
+
+/// Lists the toolchains installed with rustup, along with the one pinned by the
+/// `rust-toolchain.toml` of the worktree, if it is not installed yet.
+pub(crate) struct RustToolchainProvider;
+
+#[async_trait]
+impl ToolchainLister for RustToolchainProvider {
+    async fn list(
+        &self,
+        worktree_root: PathBuf,
+        project_env: Option<HashMap<String, String>>,
+    ) -> ToolchainList {
+        let installed = installed_rustup_toolchains(&worktree_root, project_env.as_ref())
+            .await
+            .log_err()
+            .unwrap_or_default();
+        let pinned = pinned_toolchain(&worktree_root);
+
+        let mut toolchains = Vec::with_capacity(installed.len() + 1);
+        let mut default = None;
+        let mut installed_default = None;
+        if let Some((toolchain_file, channel)) = &pinned {
+            if !installed
+                .iter()
+                .any(|toolchain| toolchain_matches_channel(&toolchain.name, channel))
+            {
+                default = Some(0);
+                toolchains.push(Toolchain {
+                    name: channel.clone().into(),
+                    path: toolchain_file.to_string_lossy().into_owned().into(),
+                    language_name: LanguageName::new("Rust"),
+                    as_json: json!({ "name": channel, "pinned": true }),
+                });
+            }
+        }
+        for toolchain in installed {
+            let is_pinned = pinned.as_ref().map_or(false, |(_, channel)| {
+                toolchain_matches_channel(&toolchain.name, channel)
+            });
+            if default.is_none() && is_pinned {
+                default = Some(toolchains.len());
+            }
+            if toolchain.is_default {
+                installed_default = Some(toolchains.len());
+            }
+            toolchains.push(Toolchain {
+                path: toolchain
+                    .path
+                    .as_ref()
+                    .map_or_else(
+                        || toolchain.name.clone(),
+                        |path| path.to_string_lossy().into_owned(),
+                    )
+                    .into(),
+                as_json: json!({ "name": toolchain.name, "pinned": is_pinned }),
+                name: toolchain.name.into(),
+                language_name: LanguageName::new("Rust"),
+            });
+        }
+
+        ToolchainList {
+            toolchains,
+            // rustup prefers the pinned toolchain over its default one.
+            default: default.or(installed_default),
+            groups: Default::default(),
+        }
+    }
+
+    fn term(&self) -> SharedString {
+        SharedString::new_static("Toolchain")
     }
 }
 
+/// A toolchain installed with rustup.
+#[derive(Debug, PartialEq)]
+struct RustupToolchain {
+    name: String,
+    path: Option<PathBuf>,
+    is_default: bool,
+}
+
+async fn installed_rustup_toolchains(
+    directory: &Path,
+    project_env: Option<&HashMap<String, String>>,
+) -> Result<Vec<RustupToolchain>> {
+    let mut command = util::command::new_smol_command("rustup");
+    if let Some(envs) = project_env {
+        command.envs(envs);
+    }
+    let output = command
+        .current_dir(directory)
+        .args(["toolchain", "list", "--verbose"])
+        .output()
+        .await
+        .context("running rustup")?;
+    ensure!(
+        output.status.success(),
+        "rustup toolchain list failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    Ok(parse_rustup_toolchains(&String::from_utf8_lossy(
+        &output.stdout,
+    )))
+}
+
+/// Parses the output of `rustup toolchain list --verbose`, whose lines look like
+/// `stable-x86_64-unknown-linux-gnu (active, default) /home/me/.rustup/toolchains/stable-x86_64-unknown-linux-gnu`.
+fn parse_rustup_toolchains(output: &str) -> Vec<RustupToolchain> {
+    output
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && *line != "no installed toolchains")
+        .map(|line| {
+            let (name, rest) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+            let rest = rest.trim();
+            let (annotations, path) =
+                match rest.strip_prefix('(').and_then(|rest| rest.split_once(')')) {
+                    Some((annotations, path)) => (annotations, path.trim()),
+                    None => ("", rest),
+                };
+            RustupToolchain {
+                name: name.to_owned(),
+                path: (!path.is_empty()).then(|| PathBuf::from(path)),
+                is_default: annotations
+                    .split(',')
+                    .any(|annotation| annotation.trim() == "default"),
+            }
+        })
+        .collect()
+}
+
+/// The name of the toolchain rustup uses in the given directory, taking overrides into account.
+async fn active_rustup_toolchain(
+    directory: Option<&Path>,
+    project_env: Option<&HashMap<String, String>>,
+) -> Result<String> {
+    let mut command = util::command::new_smol_command("rustup");
+    if let Some(envs) = project_env {
+        command.envs(envs);
+    }
+    if let Some(directory) = directory {
+        command.current_dir(directory);
+    }
+    let output = command
+        .args(["show", "active-toolchain"])
+        .output()
+        .await
+        .context("running rustup")?;
+    ensure!(
+        output.status.success(),
+        "rustup show active-toolchain failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    String::from_utf8_lossy(&output.stdout)
+        .split_whitespace()
+        .next()
+        .map(ToOwned::to_owned)
+        .context("no active rustup toolchain")
+}
+
+/// Finds the toolchain file that applies to the given directory, the same way rustup does,
+/// and returns its path along with the channel it pins.
+fn pinned_toolchain(directory: &Path) -> Option<(PathBuf, String)> {
+    directory.ancestors().find_map(|directory| {
+        ["rust-toolchain", "rust-toolchain.toml"]
+            .into_iter()
+            .find_map(|file_name| {
+                let path = directory.join(file_name);
+                let contents = std::fs::read_to_string(&path).ok()?;
+                Some((path, contents))
+            })
+            .and_then(|(path, contents)| Some((path, parse_toolchain_file(&contents)?)))
+    })
+}
+
+fn parse_toolchain_file(contents: &str) -> Option<String> {
+    #[derive(serde::Deserialize)]
+    struct ToolchainFile {
+        toolchain: ToolchainSection,
+    }
+
+    #[derive(serde::Deserialize)]
+    struct ToolchainSection {
+        channel: Option<String>,
+    }
+
+    if let Ok(file) = toml::from_str::<ToolchainFile>(contents) {
+        return file.toolchain.channel;
+    }
+
+    // Legacy toolchain files only contain the name of the channel.
+    let channel = contents.trim();
+    (!channel.is_empty() && !channel.contains(char::is_whitespace)).then(|| channel.to_owned())
+}
+
+/// Whether an installed toolchain, such as `nightly-x86_64-unknown-linux-gnu`, is the one
+/// rustup resolves a channel to. `nightly` does not match `nightly-2024-10-01-x86_64-unknown-linux-gnu`.
+fn toolchain_matches_channel(toolchain_name: &str, channel: &str) -> bool {
+    if toolchain_name == channel {
+        return true;
+    }
+    toolchain_name
+        .strip_prefix(channel)
+        .and_then(|host| host.strip_prefix('-'))
+        .map_or(false, |host| {
+            !host.starts_with(|c: char| c.is_ascii_digit())
+        })
+}
+
 /// Part of the data structure of Cargo metadata
 #[derive(serde::Deserialize)]
 struct CargoMetadata {
@@ -1158,4 +1507,76 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn test_parse_rustup_toolchains() {
+        let output = "\
+stable-x86_64-unknown-linux-gnu (active, default) /home/me/.rustup/toolchains/stable-x86_64-unknown-linux-gnu
+nightly-2024-10-01-x86_64-unknown-linux-gnu /home/me/.rustup/toolchains/nightly-2024-10-01-x86_64-unknown-linux-gnu
+1.75.0-x86_64-unknown-linux-gnu (override)
+";
+        assert_eq!(
+            parse_rustup_toolchains(output),
+            vec![
+                RustupToolchain {
+                    name: "stable-x86_64-unknown-linux-gnu".into(),
+                    path: Some(
+                        "/home/me/.rustup/toolchains/stable-x86_64-unknown-linux-gnu".into()
+                    ),
+                    is_default: true,
+                },
+                RustupToolchain {
+                    name: "nightly-2024-10-01-x86_64-unknown-linux-gnu".into(),
+                    path: Some(
+                        "/home/me/.rustup/toolchains/nightly-2024-10-01-x86_64-unknown-linux-gnu"
+                            .into()
+                    ),
+                    is_default: false,
+                },
+                RustupToolchain {
+                    name: "1.75.0-x86_64-unknown-linux-gnu".into(),
+                    path: None,
+                    is_default: false,
+                },
+            ]
+        );
+        assert_eq!(parse_rustup_toolchains("no installed toolchains\n"), vec![]);
+    }
+
+    #[test]
+    fn test_parse_toolchain_file() {
+        assert_eq!(
+            parse_toolchain_file(
+                "[toolchain]\nchannel = \"nightly-2024-10-01\"\ncomponents = [\"rustfmt\"]\n"
+            ),
+            Some("nightly-2024-10-01".to_owned())
+        );
+        assert_eq!(
+            parse_toolchain_file("[toolchain]\npath = \"/opt/rust\"\n"),
+            None
+        );
+        assert_eq!(parse_toolchain_file("1.75.0\n"), Some("1.75.0".to_owned()));
+        assert_eq!(parse_toolchain_file(""), None);
+    }
+
+    #[test]
+    fn test_toolchain_matches_channel() {
+        assert!(toolchain_matches_channel(
+            "nightly-x86_64-unknown-linux-gnu",
+            "nightly"
+        ));
+        assert!(toolchain_matches_channel(
+            "nightly-2024-10-01-x86_64-unknown-linux-gnu",
+            "nightly-2024-10-01"
+        ));
+        assert!(toolchain_matches_channel("1.75.0", "1.75.0"));
+        assert!(!toolchain_matches_channel(
+            "nightly-2024-10-01-x86_64-unknown-linux-gnu",
+            "nightly"
+        ));
+        assert!(!toolchain_matches_channel(
+            "1.75.0-x86_64-unknown-linux-gnu",
+            "1.75"
+        ));
+    }
 }
@@ -9,7 +9,6 @@ use language::{LanguageToolchainStore, LspAdapter, LspAdapterDelegate};
 use lsp::{CodeActionKind, LanguageServerBinary, LanguageServerName};
 use node_runtime::NodeRuntime;
 use project::lsp_store::language_server_settings;
-use project::ContextProviderWithTasks;
 use serde_json::{json, Value};
 use smol::{fs, io::BufReader, stream::StreamExt};
 use std::{
@@ -21,8 +20,10 @@ use std::{
 use task::{TaskTemplate, TaskTemplates, VariableName};
 use util::{fs::remove_matching, maybe, ResultExt};
 
-pub(super) fn typescript_task_context() -> ContextProviderWithTasks {
-    ContextProviderWithTasks::new(TaskTemplates(vec![
+use crate::node::{binary_for_node_toolchain, NodeContextProvider};
+
+pub(super) fn typescript_task_context() -> NodeContextProvider {
+    NodeContextProvider::new(TaskTemplates(vec![
         TaskTemplate {
             label: "jest file test".to_owned(),
             command: "npx jest".to_owned(),
@@ -101,6 +102,16 @@ impl LspAdapter for TypeScriptLspAdapter {
         Self::SERVER_NAME.clone()
     }
 
+    async fn binary_for_toolchain(
+        &self,
+        binary: LanguageServerBinary,
+        delegate: &dyn LspAdapterDelegate,
+        toolchains: Arc<dyn LanguageToolchainStore>,
+        cx: &mut AsyncAppContext,
+    ) -> LanguageServerBinary {
+        binary_for_node_toolchain(binary, &self.node, delegate, toolchains, cx).await
+    }
+
     async fn fetch_latest_server_version(
         &self,
         _: &dyn LspAdapterDelegate,
@@ -407,6 +418,16 @@ impl LspAdapter for EsLintLspAdapter {
         Self::SERVER_NAME.clone()
     }
 
+    async fn binary_for_toolchain(
+        &self,
+        binary: LanguageServerBinary,
+        delegate: &dyn LspAdapterDelegate,
+        toolchains: Arc<dyn LanguageToolchainStore>,
+        cx: &mut AsyncAppContext,
+    ) -> LanguageServerBinary {
+        binary_for_node_toolchain(binary, &self.node, delegate, toolchains, cx).await
+    }
+
     async fn fetch_latest_server_version(
         &self,
         _delegate: &dyn LspAdapterDelegate,
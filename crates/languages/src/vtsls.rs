@@ -15,6 +15,8 @@ use std::{
 };
 use util::{maybe, merge_json_value_into, ResultExt};
 
+use crate::node::binary_for_node_toolchain;
+
 fn typescript_server_binary_arguments(server_path: &Path) -> Vec<OsString> {
     vec![server_path.into(), "--stdio".into()]
 }
@@ -89,6 +91,16 @@ impl LspAdapter for VtslsLspAdapter {
         })
     }
 
+    async fn binary_for_toolchain(
+        &self,
+        binary: LanguageServerBinary,
+        delegate: &dyn LspAdapterDelegate,
+        toolchains: Arc<dyn LanguageToolchainStore>,
+        cx: &mut AsyncAppContext,
+    ) -> LanguageServerBinary {
+        binary_for_node_toolchain(binary, &self.node, delegate, toolchains, cx).await
+    }
+
     async fn fetch_server_binary(
         &self,
         latest_version: Box<dyn 'static + Send + Any>,
@@ -0,0 +1,308 @@
+use fuzzy::{match_strings, StringMatch, StringMatchCandidate};
+use gpui::{
+    actions, AnyElement, AppContext, DismissEvent, EventEmitter, FocusHandle, FocusableView,
+    Render, Task, View, ViewContext, VisualContext, WeakView,
+};
+use picker::{Picker, PickerDelegate};
+use std::sync::Arc;
+use ui::{prelude::*, HighlightedLabel, KeyBinding, ListItem, ListItemSpacing, Tooltip};
+use util::ResultExt;
+use workspace::{
+    notifications::DetachAndPromptErr, LayoutSource, ModalView, NamedLayout, Workspace,
+};
+
+actions!(layout_selector, [Toggle]);
+
+pub fn init(cx: &mut AppContext) {
+    cx.observe_new_views(LayoutSelector::register).detach();
+}
+
+pub struct LayoutSelector {
+    picker: View<Picker<LayoutSelectorDelegate>>,
+}
+
+impl LayoutSelector {
+    fn register(workspace: &mut Workspace, _: &mut ViewContext<Workspace>) {
+        workspace.register_action(|workspace, _: &Toggle, cx| {
+            Self::toggle(workspace, cx);
+        });
+    }
+
+    fn toggle(workspace: &mut Workspace, cx: &mut ViewContext<Workspace>) {
+        let layouts = workspace.layouts(cx);
+        cx.spawn(|workspace, mut cx| async move {
+            let layouts = layouts.await?;
+            workspace.update(&mut cx, |workspace, cx| {
+                let weak_workspace = cx.view().downgrade();
+                workspace.toggle_modal(cx, |cx| Self::new(weak_workspace, layouts, cx));
+            })
+        })
+        .detach_and_prompt_err("Failed to load layouts", cx, |_, _| None);
+    }
+
+    fn new(
+        workspace: WeakView<Workspace>,
+        layouts: Vec<NamedLayout>,
+        cx: &mut ViewContext<Self>,
+    ) -> Self {
+        let delegate = LayoutSelectorDelegate::new(workspace, layouts, cx.view().downgrade());
+        let picker = cx.new_view(|cx| Picker::uniform_list(delegate, cx));
+        Self { picker }
+    }
+}
+
+impl ModalView for LayoutSelector {}
+
+impl EventEmitter<DismissEvent> for LayoutSelector {}
+
+impl FocusableView for LayoutSelector {
+    fn focus_handle(&self, cx: &AppContext) -> FocusHandle {
+        self.picker.focus_handle(cx)
+    }
+}
+
+impl Render for LayoutSelector {
+    fn render(&mut self, _: &mut ViewContext<Self>) -> impl IntoElement {
+        v_flex().w(rems(34.)).child(self.picker.clone())
+    }
+}
+
+enum LayoutEntry {
+    Layout(StringMatch),
+    Save { name: String },
+}
+
+pub struct LayoutSelectorDelegate {
+    layout_selector: WeakView<LayoutSelector>,
+    workspace: WeakView<Workspace>,
+    layouts: Vec<NamedLayout>,
+    matches: Vec<LayoutEntry>,
+    selected_index: usize,
+}
+
+impl LayoutSelectorDelegate {
+    fn new(
+        workspace: WeakView<Workspace>,
+        layouts: Vec<NamedLayout>,
+        layout_selector: WeakView<LayoutSelector>,
+    ) -> Self {
+        Self {
+            layout_selector,
+            workspace,
+            layouts,
+            matches: Vec::new(),
+            selected_index: 0,
+        }
+    }
+
+    fn delete_layout(&mut self, ix: usize, cx: &mut ViewContext<Picker<Self>>) {
+        let Some(LayoutEntry::Layout(layout_match)) = self.matches.get(ix) else {
+            return;
+        };
+        let name = self.layouts[layout_match.candidate_id].name.clone();
+        let Some(delete) = self
+            .workspace
+            .update(cx, |workspace, cx| {
+                workspace.delete_layout(name.to_string(), cx)
+            })
+            .log_err()
+        else {
+            return;
+        };
+        cx.spawn(|picker, mut cx| async move {
+            delete.await?;
+            let layouts = picker
+                .update(&mut cx, |picker, cx| {
+                    picker
+                        .delegate
+                        .workspace
+                        .update(cx, |workspace, cx| workspace.layouts(cx))
+                })??
+                .await?;
+            picker.update(&mut cx, |picker, cx| {
+                picker.delegate.layouts = layouts;
+                picker.delegate.selected_index = ix.saturating_sub(1);
+                picker.refresh(cx);
+            })
+        })
+        .detach_and_prompt_err("Failed to delete layout", cx, |_, _| None);
+    }
+}
+
+impl PickerDelegate for LayoutSelectorDelegate {
+    type ListItem = ListItem;
+
+    fn placeholder_text(&self, _: &mut WindowContext) -> Arc<str> {
+        "Switch to a layout, or type a name to save the current one...".into()
+    }
+
+    fn match_count(&self) -> usize {
+        self.matches.len()
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(&mut self, ix: usize, _: &mut ViewContext<Picker<Self>>) {
+        self.selected_index = ix;
+    }
+
+    fn update_matches(&mut self, query: String, cx: &mut ViewContext<Picker<Self>>) -> Task<()> {
+        let candidates = self
+            .layouts
+            .iter()
+            .enumerate()
+            .map(|(id, layout)| StringMatchCandidate::new(id, &layout.name))
+            .collect::<Vec<_>>();
+        let exact_match = self
+            .layouts
+            .iter()
+            .any(|layout| layout.name.as_ref() == query.trim());
+
+        cx.spawn(|picker, mut cx| async move {
+            let matches = if query.is_empty() {
+                candidates
+                    .into_iter()
+                    .map(|candidate| StringMatch {
+                        candidate_id: candidate.id,
+                        string: candidate.string,
+                        positions: Vec::new(),
+                        score: 0.0,
+                    })
+                    .collect()
+            } else {
+                match_strings(
+                    &candidates,
+                    &query,
+                    false,
+                    100,
+                    &Default::default(),
+                    cx.background_executor().clone(),
+                )
+                .await
+            };
+
+            picker
+                .update(&mut cx, |picker, _| {
+                    let delegate = &mut picker.delegate;
+                    delegate.matches = matches.into_iter().map(LayoutEntry::Layout).collect();
+                    if !query.trim().is_empty() && !exact_match {
+                        delegate.matches.push(LayoutEntry::Save {
+                            name: query.trim().to_string(),
+                        });
+                    }
+                    delegate.selected_index = delegate
+                        .selected_index
+                        .min(delegate.matches.len().saturating_sub(1));
+                })
+                .log_err();
+        })
+    }
+
+    fn confirm(&mut self, secondary: bool, cx: &mut ViewContext<Picker<Self>>) {
+        let Some(entry) = self.matches.get(self.selected_index) else {
+            return;
+        };
+        let task = match entry {
+            LayoutEntry::Layout(layout_match) => {
+                let layout = self.layouts[layout_match.candidate_id].clone();
+                self.workspace.update(cx, |workspace, cx| {
+                    if secondary {
+                        workspace.share_layout(layout.name.to_string(), layout.layout, cx)
+                    } else {
+                        workspace.apply_layout(layout.layout, cx)
+                    }
+                })
+            }
+            LayoutEntry::Save { name } => {
+                let name = name.clone();
+                self.workspace.update(cx, |workspace, cx| {
+                    workspace.save_layout(name, secondary, cx)
+                })
+            }
+        };
+        if let Some(task) = task.log_err() {
+            task.detach_and_prompt_err("Failed to update layout", cx, |_, _| None);
+        }
+        self.dismissed(cx);
+    }
+
+    fn dismissed(&mut self, cx: &mut ViewContext<Picker<Self>>) {
+        self.layout_selector
+            .update(cx, |_, cx| cx.emit(DismissEvent))
+            .log_err();
+    }
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        cx: &mut ViewContext<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        let entry = self.matches.get(ix)?;
+        let item = ListItem::new(ix)
+            .inset(true)
+            .spacing(ListItemSpacing::Sparse)
+            .toggle_state(selected);
+
+        Some(match entry {
+            LayoutEntry::Layout(layout_match) => {
+                let layout = &self.layouts[layout_match.candidate_id];
+                item.child(
+                    h_flex()
+                        .gap_2()
+                        .child(HighlightedLabel::new(
+                            layout_match.string.clone(),
+                            layout_match.positions.clone(),
+                        ))
+                        .when(layout.source == LayoutSource::Project, |this| {
+                            this.child(
+                                Label::new("project")
+                                    .size(LabelSize::Small)
+                                    .color(Color::Muted),
+                            )
+                        }),
+                )
+                .when(layout.source == LayoutSource::Local, |item| {
+                    let delete_button = IconButton::new("delete", IconName::Close)
+                        .icon_size(IconSize::Small)
+                        .on_click(cx.listener(move |picker, _, cx| {
+                            cx.stop_propagation();
+                            cx.prevent_default();
+                            picker.delegate.delete_layout(ix, cx);
+                        }))
+                        .tooltip(|cx| Tooltip::text("Delete Layout", cx))
+                        .into_any_element();
+                    if selected {
+                        item.end_slot::<AnyElement>(delete_button)
+                    } else {
+                        item.end_hover_slot::<AnyElement>(delete_button)
+                    }
+                })
+            }
+            LayoutEntry::Save { name } => {
+                item.child(Label::new(format!("Save current layout as \"{name}\"")))
+            }
+        })
+    }
+
+    fn render_footer(&self, cx: &mut ViewContext<Picker<Self>>) -> Option<AnyElement> {
+        let label = match self.matches.get(self.selected_index)? {
+            LayoutEntry::Layout(_) => "Share the selected layout with the project",
+            LayoutEntry::Save { .. } => "Save to the project's .zed/layouts.json instead",
+        };
+        Some(
+            h_flex()
+                .w_full()
+                .p_2()
+                .gap_2()
+                .justify_between()
+                .border_t_1()
+                .border_color(cx.theme().colors().border_variant)
+                .child(Label::new(label).size(LabelSize::Small).color(Color::Muted))
+                .children(KeyBinding::for_action(&menu::SecondaryConfirm, cx))
+                .into_any_element(),
+        )
+    }
+}
@@ -4,6 +4,8 @@ use gpui::{
 use language::HighlightId;
 use std::{fmt::Display, ops::Range, path::PathBuf};
 
+use crate::{math::MathNode, mermaid::MermaidDiagram};
+
 #[derive(Debug)]
 #[cfg_attr(test, derive(PartialEq))]
 pub enum ParsedMarkdownElement {
@@ -12,9 +14,12 @@ pub enum ParsedMarkdownElement {
     Table(ParsedMarkdownTable),
     BlockQuote(ParsedMarkdownBlockQuote),
     CodeBlock(ParsedMarkdownCodeBlock),
+    /// A fenced code block in the `mermaid` language.
+    MermaidDiagram(ParsedMarkdownMermaidDiagram),
     /// A paragraph of text and other inline elements.
     Paragraph(MarkdownParagraph),
     HorizontalRule(Range<usize>),
+    FootnoteDefinition(ParsedMarkdownFootnoteDefinition),
 }
 
 impl ParsedMarkdownElement {
@@ -25,17 +30,58 @@ impl ParsedMarkdownElement {
             Self::Table(table) => table.source_range.clone(),
             Self::BlockQuote(block_quote) => block_quote.source_range.clone(),
             Self::CodeBlock(code_block) => code_block.source_range.clone(),
+            Self::MermaidDiagram(diagram) => diagram.source_range.clone(),
             Self::Paragraph(text) => match text.get(0)? {
                 MarkdownParagraphChunk::Text(t) => t.source_range.clone(),
                 MarkdownParagraphChunk::Image(image) => image.source_range.clone(),
+                MarkdownParagraphChunk::Math(math) => math.source_range.clone(),
             },
             Self::HorizontalRule(range) => range.clone(),
+            Self::FootnoteDefinition(definition) => definition.source_range.clone(),
         })
     }
 
     pub fn is_list_item(&self) -> bool {
         matches!(self, Self::ListItem(_))
     }
+
+    /// Returns whether this element contains a reference to the footnote with the given label.
+    pub fn references_footnote(&self, label: &str) -> bool {
+        let is_reference = |link: &Option<Link>| match link {
+            Some(Link::Footnote { label: reference }) => reference.as_ref() == label,
+            _ => false,
+        };
+        let paragraph_references_footnote = |paragraph: &MarkdownParagraph| {
+            paragraph.iter().any(|chunk| match chunk {
+                MarkdownParagraphChunk::Text(text) => {
+                    text.regions.iter().any(|region| is_reference(&region.link))
+                }
+                MarkdownParagraphChunk::Image(_) | MarkdownParagraphChunk::Math(_) => false,
+            })
+        };
+
+        match self {
+            Self::Paragraph(paragraph) => paragraph_references_footnote(paragraph),
+            Self::Heading(heading) => paragraph_references_footnote(&heading.contents),
+            Self::Table(table) => std::iter::once(&table.header)
+                .chain(&table.body)
+                .flat_map(|row| &row.children)
+                .any(paragraph_references_footnote),
+            Self::ListItem(list_item) => list_item
+                .content
+                .iter()
+                .any(|child| child.references_footnote(label)),
+            Self::BlockQuote(block_quote) => block_quote
+                .children
+                .iter()
+                .any(|child| child.references_footnote(label)),
+            Self::FootnoteDefinition(definition) => definition
+                .children
+                .iter()
+                .any(|child| child.references_footnote(label)),
+            Self::CodeBlock(_) | Self::MermaidDiagram(_) | Self::HorizontalRule(_) => false,
+        }
+    }
 }
 
 pub type MarkdownParagraph = Vec<MarkdownParagraphChunk>;
@@ -45,6 +91,8 @@ pub type MarkdownParagraph = Vec<MarkdownParagraphChunk>;
 pub enum MarkdownParagraphChunk {
     Text(ParsedMarkdownText),
     Image(Image),
+    /// Math in display mode, such as `$$x^2$$`, which is typeset on its own line.
+    Math(ParsedMarkdownMath),
 }
 
 #[derive(Debug)]
@@ -80,6 +128,34 @@ pub struct ParsedMarkdownCodeBlock {
     pub highlights: Option<Vec<(Range<usize>, HighlightId)>>,
 }
 
+#[derive(Debug)]
+#[cfg_attr(test, derive(PartialEq))]
+pub struct ParsedMarkdownMermaidDiagram {
+    pub source_range: Range<usize>,
+    pub contents: SharedString,
+    /// The parsed diagram, or the reason it couldn't be parsed.
+    pub diagram: Result<MermaidDiagram, SharedString>,
+}
+
+#[derive(Debug)]
+#[cfg_attr(test, derive(PartialEq))]
+pub struct ParsedMarkdownMath {
+    pub source_range: Range<usize>,
+    /// The TeX source of the expression.
+    pub contents: SharedString,
+    pub expression: MathNode,
+}
+
+#[derive(Debug)]
+#[cfg_attr(test, derive(PartialEq))]
+pub struct ParsedMarkdownFootnoteDefinition {
+    pub source_range: Range<usize>,
+    pub label: SharedString,
+    /// The number that the footnote is displayed with, in order of the first reference to it.
+    pub number: usize,
+    pub children: Vec<ParsedMarkdownElement>,
+}
+
 #[derive(Debug)]
 #[cfg_attr(test, derive(PartialEq))]
 pub struct ParsedMarkdownHeading {
@@ -246,6 +322,11 @@ pub enum Link {
         /// The absolute path to the item.
         path: PathBuf,
     },
+    /// A reference to a footnote in the same document.
+    Footnote {
+        /// The label of the footnote, as in `[^label]`.
+        label: SharedString,
+    },
 }
 
 impl Link {
@@ -279,6 +360,7 @@ impl Display for Link {
         match self {
             Link::Web { url } => write!(f, "{}", url),
             Link::Path { display_path, .. } => write!(f, "{}", display_path.display()),
+            Link::Footnote { label } => write!(f, "[^{}]", label),
         }
     }
 }
@@ -1,4 +1,5 @@
 use crate::markdown_elements::*;
+use crate::{math, mermaid};
 use async_recursion::async_recursion;
 use collections::FxHashMap;
 use gpui::FontWeight;
@@ -34,6 +35,8 @@ struct MarkdownParser<'a> {
     parsed: Vec<ParsedMarkdownElement>,
     file_location_directory: Option<PathBuf>,
     language_registry: Option<Arc<LanguageRegistry>>,
+    /// The numbers assigned to footnotes, in order of their first reference.
+    footnote_numbers: FxHashMap<String, usize>,
 }
 
 struct MarkdownListItem {
@@ -62,9 +65,18 @@ impl<'a> MarkdownParser<'a> {
             language_registry,
             cursor: 0,
             parsed: vec![],
+            footnote_numbers: FxHashMap::default(),
         }
     }
 
+    fn footnote_number(&mut self, label: &str) -> usize {
+        let next_number = self.footnote_numbers.len() + 1;
+        *self
+            .footnote_numbers
+            .entry(label.to_string())
+            .or_insert(next_number)
+    }
+
     fn eof(&self) -> bool {
         if self.tokens.is_empty() {
             return true;
@@ -101,6 +113,8 @@ impl<'a> MarkdownParser<'a> {
             | Event::Code(_)
             | Event::Html(_)
             | Event::FootnoteReference(_)
+            | Event::InlineMath(_)
+            | Event::DisplayMath(_)
             | Event::Start(Tag::Link { .. })
             | Event::Start(Tag::Emphasis)
             | Event::Start(Tag::Strong)
@@ -172,7 +186,32 @@ impl<'a> MarkdownParser<'a> {
                     self.cursor += 1;
 
                     let code_block = self.parse_code_block(language).await;
-                    Some(vec![ParsedMarkdownElement::CodeBlock(code_block)])
+                    let block =
+                        match code_block.language.as_deref() {
+                            Some("mermaid") => ParsedMarkdownElement::MermaidDiagram(
+                                ParsedMarkdownMermaidDiagram {
+                                    diagram: mermaid::parse(&code_block.contents)
+                                        .map_err(|error| error.to_string().into()),
+                                    source_range: code_block.source_range,
+                                    contents: code_block.contents,
+                                },
+                            ),
+                            Some("math") => ParsedMarkdownElement::Paragraph(vec![
+                                MarkdownParagraphChunk::Math(ParsedMarkdownMath {
+                                    expression: math::parse(&code_block.contents),
+                                    source_range: code_block.source_range,
+                                    contents: code_block.contents,
+                                }),
+                            ]),
+                            _ => ParsedMarkdownElement::CodeBlock(code_block),
+                        };
+                    Some(vec![block])
+                }
+                Tag::FootnoteDefinition(label) => {
+                    let label = label.to_string();
+                    self.cursor += 1;
+                    let definition = self.parse_footnote_definition(label).await;
+                    Some(vec![ParsedMarkdownElement::FootnoteDefinition(definition)])
                 }
                 _ => None,
             },
@@ -214,7 +253,8 @@ impl<'a> MarkdownParser<'a> {
                 break;
             }
 
-            let (current, _) = self.current().unwrap();
+            let (current, event_range) = self.current().unwrap();
+            let event_range = event_range.clone();
             let prev_len = text.len();
             match current {
                 Event::SoftBreak => {
@@ -356,6 +396,50 @@ impl<'a> MarkdownParser<'a> {
                         link: link.clone(),
                     });
                 }
+                Event::InlineMath(tex) => {
+                    text.push_str(&math::parse(tex).to_unicode());
+                    highlights.push((
+                        prev_len..text.len(),
+                        MarkdownHighlight::Style(MarkdownHighlightStyle {
+                            italic: true,
+                            weight: if bold_depth > 0 {
+                                FontWeight::BOLD
+                            } else {
+                                FontWeight::default()
+                            },
+                            ..Default::default()
+                        }),
+                    ));
+                }
+                Event::DisplayMath(tex) => {
+                    let math = ParsedMarkdownMath {
+                        source_range: event_range,
+                        contents: tex.to_string().into(),
+                        expression: math::parse(tex),
+                    };
+                    if !text.is_empty() {
+                        markdown_text_like.push(MarkdownParagraphChunk::Text(ParsedMarkdownText {
+                            source_range: source_range.clone(),
+                            contents: std::mem::take(&mut text),
+                            highlights: std::mem::take(&mut highlights),
+                            region_ranges: std::mem::take(&mut region_ranges),
+                            regions: std::mem::take(&mut regions),
+                        }));
+                    }
+                    markdown_text_like.push(MarkdownParagraphChunk::Math(math));
+                }
+                Event::FootnoteReference(label) => {
+                    let label = label.to_string();
+                    let number = self.footnote_number(&label);
+                    text.push_str(&math::to_superscript(&number.to_string()).unwrap_or_default());
+                    region_ranges.push(prev_len..text.len());
+                    regions.push(ParsedRegion {
+                        code: false,
+                        link: Some(Link::Footnote {
+                            label: label.into(),
+                        }),
+                    });
+                }
                 Event::Start(tag) => match tag {
                     Tag::Emphasis => italic_depth += 1,
                     Tag::Strong => bold_depth += 1,
@@ -700,6 +784,37 @@ impl<'a> MarkdownParser<'a> {
         }
     }
 
+    #[async_recursion]
+    async fn parse_footnote_definition(
+        &mut self,
+        label: String,
+    ) -> ParsedMarkdownFootnoteDefinition {
+        let (_event, source_range) = self.previous().unwrap();
+        let source_range = source_range.clone();
+        let number = self.footnote_number(&label);
+        let mut children = Vec::new();
+
+        while !self.eof() {
+            if let Some((Event::End(TagEnd::FootnoteDefinition), _)) = self.current() {
+                self.cursor += 1;
+                break;
+            }
+
+            if let Some(block) = self.parse_block().await {
+                children.extend(block);
+            } else {
+                self.cursor += 1;
+            }
+        }
+
+        ParsedMarkdownFootnoteDefinition {
+            source_range,
+            label: label.into(),
+            number,
+            children,
+        }
+    }
+
     async fn parse_code_block(&mut self, language: Option<String>) -> ParsedMarkdownCodeBlock {
         let (_event, source_range) = self.previous().unwrap();
         let source_range = source_range.clone();
@@ -1329,6 +1444,82 @@ fn main() {
         );
     }
 
+    #[gpui::test]
+    async fn test_mermaid_diagram() {
+        let parsed = parse("```mermaid\ngraph TD\nA --> B\n```\n").await;
+
+        let ParsedMarkdownElement::MermaidDiagram(diagram) = &parsed.children[0] else {
+            panic!("expected a mermaid diagram, got {:?}", parsed.children);
+        };
+        assert_eq!(diagram.contents.as_ref(), "graph TD\nA --> B");
+        assert!(matches!(
+            diagram.diagram,
+            Ok(mermaid::MermaidDiagram::Flowchart(_))
+        ));
+
+        let parsed = parse("```mermaid\ngantt\n```\n").await;
+        let ParsedMarkdownElement::MermaidDiagram(diagram) = &parsed.children[0] else {
+            panic!("expected a mermaid diagram, got {:?}", parsed.children);
+        };
+        assert!(diagram.diagram.is_err());
+    }
+
+    #[gpui::test]
+    async fn test_math() {
+        let parsed = parse("Euler: $e^{i\\pi} = -1$\n\n$$\\frac{1}{2}$$").await;
+
+        assert_eq!(parsed.children[0], p("Euler: e^(iπ) = −1", 0..23));
+        assert_eq!(
+            parsed.children[1],
+            ParsedMarkdownElement::Paragraph(vec![MarkdownParagraphChunk::Math(
+                ParsedMarkdownMath {
+                    source_range: 24..39,
+                    contents: "\\frac{1}{2}".into(),
+                    expression: math::parse("\\frac{1}{2}"),
+                }
+            )])
+        );
+    }
+
+    #[gpui::test]
+    async fn test_footnotes() {
+        let parsed = parse("First[^b] and second[^a].\n\n[^a]: Note A.\n\n[^b]: Note B.\n").await;
+
+        let ParsedMarkdownElement::Paragraph(paragraph) = &parsed.children[0] else {
+            panic!("expected a paragraph, got {:?}", parsed.children);
+        };
+        let MarkdownParagraphChunk::Text(text) = &paragraph[0] else {
+            panic!("expected text, got {paragraph:?}");
+        };
+        assert_eq!(text.contents, "First¹ and second².");
+        assert_eq!(
+            text.regions,
+            vec![
+                ParsedRegion {
+                    code: false,
+                    link: Some(Link::Footnote { label: "b".into() }),
+                },
+                ParsedRegion {
+                    code: false,
+                    link: Some(Link::Footnote { label: "a".into() }),
+                },
+            ]
+        );
+
+        let definitions = parsed.children[1..]
+            .iter()
+            .map(|child| match child {
+                ParsedMarkdownElement::FootnoteDefinition(definition) => {
+                    (definition.label.to_string(), definition.number)
+                }
+                child => panic!("expected a footnote definition, got {child:?}"),
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(definitions, vec![("a".into(), 2), ("b".into(), 1)]);
+        assert!(parsed.children[0].references_footnote("a"));
+        assert!(!parsed.children[1].references_footnote("a"));
+    }
+
     fn rust_lang() -> Arc<Language> {
         Arc::new(Language::new(
             LanguageConfig {
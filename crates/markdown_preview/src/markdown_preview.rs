@@ -5,6 +5,8 @@ pub mod markdown_elements;
 pub mod markdown_parser;
 pub mod markdown_preview_view;
 pub mod markdown_renderer;
+pub mod math;
+pub mod mermaid;
 
 actions!(markdown, [OpenPreview, OpenPreviewToTheSide]);
 
@@ -20,7 +20,7 @@ use crate::OpenPreviewToTheSide;
 use crate::{
     markdown_elements::ParsedMarkdown,
     markdown_parser::parse_markdown,
-    markdown_renderer::{render_markdown_block, RenderContext},
+    markdown_renderer::{render_markdown_block, FootnoteTarget, RenderContext},
     OpenPreview,
 };
 
@@ -177,6 +177,14 @@ impl MarkdownPreviewView {
                                                 }
                                             })
                                         }
+                                    })
+                                    .with_footnote_clicked_callback({
+                                        let view = view.clone();
+                                        move |target, cx| {
+                                            view.update(cx, |view, cx| {
+                                                view.reveal_footnote(target, cx);
+                                            })
+                                        }
                                     });
                             let block = contents.children.get(ix).unwrap();
                             let rendered_block = render_markdown_block(block, &mut render_cx);
@@ -440,6 +448,26 @@ impl MarkdownPreviewView {
         block_index.unwrap_or_default()
     }
 
+    fn reveal_footnote(&mut self, target: &FootnoteTarget, cx: &mut ViewContext<Self>) {
+        let Some(contents) = &self.contents else {
+            return;
+        };
+
+        let block_index = contents.children.iter().position(|block| match target {
+            FootnoteTarget::Definition(label) => matches!(
+                block,
+                ParsedMarkdownElement::FootnoteDefinition(definition) if definition.label == *label
+            ),
+            FootnoteTarget::Reference(label) => block.references_footnote(label),
+        });
+
+        if let Some(block_index) = block_index {
+            self.selected_block = block_index;
+            self.list_state.scroll_to_reveal_item(block_index);
+            cx.notify();
+        }
+    }
+
     fn should_apply_padding_between(
         current_block: &ParsedMarkdownElement,
         next_block: Option<&ParsedMarkdownElement>,
@@ -1,14 +1,19 @@
-use crate::markdown_elements::{
-    HeadingLevel, Link, MarkdownParagraph, MarkdownParagraphChunk, ParsedMarkdown,
-    ParsedMarkdownBlockQuote, ParsedMarkdownCodeBlock, ParsedMarkdownElement,
-    ParsedMarkdownHeading, ParsedMarkdownListItem, ParsedMarkdownListItemType, ParsedMarkdownTable,
-    ParsedMarkdownTableAlignment, ParsedMarkdownTableRow,
+use crate::{
+    markdown_elements::{
+        HeadingLevel, Link, MarkdownParagraph, MarkdownParagraphChunk, ParsedMarkdown,
+        ParsedMarkdownBlockQuote, ParsedMarkdownCodeBlock, ParsedMarkdownElement,
+        ParsedMarkdownFootnoteDefinition, ParsedMarkdownHeading, ParsedMarkdownListItem,
+        ParsedMarkdownListItemType, ParsedMarkdownMath, ParsedMarkdownMermaidDiagram,
+        ParsedMarkdownTable, ParsedMarkdownTableAlignment, ParsedMarkdownTableRow,
+    },
+    math::{Accent, MathNode},
+    mermaid::{self, ArrowHead, BoxStyle, LayoutLine, NodeShape, Stroke, TextMetrics},
 };
 use gpui::{
-    div, img, px, rems, AbsoluteLength, AnyElement, ClipboardItem, DefiniteLength, Div, Element,
-    ElementId, HighlightStyle, Hsla, ImageSource, InteractiveText, IntoElement, Keystroke, Length,
-    Modifiers, ParentElement, Render, Resource, SharedString, Styled, StyledText, TextStyle, View,
-    WeakView, WindowContext,
+    canvas, div, img, point, px, rems, AbsoluteLength, AnyElement, Bounds, ClipboardItem,
+    DefiniteLength, Div, Element, ElementId, HighlightStyle, Hsla, ImageSource, InteractiveText,
+    IntoElement, Keystroke, Length, Modifiers, ParentElement, Path, Pixels, Point, Render,
+    Resource, SharedString, Styled, StyledText, TextStyle, View, WeakView, WindowContext,
 };
 use settings::Settings;
 use std::{
@@ -20,12 +25,22 @@ use theme::{ActiveTheme, SyntaxTheme, ThemeSettings};
 use ui::{
     h_flex, relative, tooltip_container, v_flex, Checkbox, Clickable, Color, FluentBuilder,
     IconButton, IconName, IconSize, InteractiveElement, Label, LabelCommon, LabelSize, LinkPreview,
-    StatefulInteractiveElement, StyledExt, StyledImage, ToggleState, ViewContext, VisibleOnHover,
-    VisualContext as _,
+    StatefulInteractiveElement, StyledExt, StyledImage, ToggleState, Tooltip, ViewContext,
+    VisibleOnHover, VisualContext as _,
 };
 use workspace::Workspace;
 
 type CheckboxClickedCallback = Arc<Box<dyn Fn(bool, Range<usize>, &mut WindowContext)>>;
+type FootnoteClickedCallback = Arc<Box<dyn Fn(&FootnoteTarget, &mut WindowContext)>>;
+
+/// The block that should be revealed when a footnote link is clicked.
+#[derive(Debug, Clone, PartialEq)]
+pub enum FootnoteTarget {
+    /// The definition of the footnote with the given label.
+    Definition(SharedString),
+    /// The first block that references the footnote with the given label.
+    Reference(SharedString),
+}
 
 #[derive(Clone)]
 pub struct RenderContext {
@@ -39,9 +54,14 @@ pub struct RenderContext {
     text_muted_color: Hsla,
     code_block_background_color: Hsla,
     code_span_background_color: Hsla,
+    diagram_node_background_color: Hsla,
+    diagram_note_background_color: Hsla,
+    diagram_text_metrics: TextMetrics,
+    math_font_size: Pixels,
     syntax_theme: Arc<SyntaxTheme>,
     indent: usize,
     checkbox_clicked_callback: Option<CheckboxClickedCallback>,
+    footnote_clicked_callback: Option<FootnoteClickedCallback>,
 }
 
 impl RenderContext {
@@ -53,20 +73,38 @@ impl RenderContext {
         let mut buffer_text_style = cx.text_style();
         buffer_text_style.font_family = buffer_font_family.clone();
 
+        let rem_size = cx.rem_size();
+        let text_style = cx.text_style();
+        let buffer_font_size = buffer_text_style.font_size.to_pixels(rem_size);
+        let buffer_font_id = cx.text_system().resolve_font(&buffer_text_style.font());
+        let char_width = cx
+            .text_system()
+            .advance(buffer_font_id, buffer_font_size, 'm')
+            .map_or(buffer_font_size * 0.6, |advance| advance.width);
+        let diagram_text_metrics = TextMetrics {
+            char_width: char_width.0,
+            line_height: buffer_text_style.line_height_in_pixels(rem_size).0,
+        };
+
         RenderContext {
             workspace,
             next_id: 0,
             indent: 0,
             buffer_font_family,
             buffer_text_style,
-            text_style: cx.text_style(),
+            math_font_size: text_style.font_size.to_pixels(rem_size) * 1.1,
+            text_style,
             syntax_theme: theme.syntax().clone(),
             border_color: theme.colors().border,
             text_color: theme.colors().text,
             text_muted_color: theme.colors().text_muted,
             code_block_background_color: theme.colors().surface_background,
             code_span_background_color: theme.colors().editor_document_highlight_read_background,
+            diagram_node_background_color: theme.colors().element_background,
+            diagram_note_background_color: theme.status().warning_background,
+            diagram_text_metrics,
             checkbox_clicked_callback: None,
+            footnote_clicked_callback: None,
         }
     }
 
@@ -78,6 +116,14 @@ impl RenderContext {
         self
     }
 
+    pub fn with_footnote_clicked_callback(
+        mut self,
+        callback: impl Fn(&FootnoteTarget, &mut WindowContext) + 'static,
+    ) -> Self {
+        self.footnote_clicked_callback = Some(Arc::new(Box::new(callback)));
+        self
+    }
+
     fn next_id(&mut self, span: &Range<usize>) -> ElementId {
         let id = format!("markdown-{}-{}-{}", self.next_id, span.start, span.end);
         self.next_id += 1;
@@ -131,6 +177,8 @@ pub fn render_markdown_block(block: &ParsedMarkdownElement, cx: &mut RenderConte
         BlockQuote(block_quote) => render_markdown_block_quote(block_quote, cx),
         CodeBlock(code_block) => render_markdown_code_block(code_block, cx),
         HorizontalRule(_) => render_markdown_rule(cx),
+        MermaidDiagram(diagram) => render_markdown_mermaid_diagram(diagram, cx),
+        FootnoteDefinition(definition) => render_markdown_footnote_definition(definition, cx),
     }
 }
 
@@ -234,6 +282,7 @@ fn paragraph_len(paragraphs: &MarkdownParagraph) -> usize {
             MarkdownParagraphChunk::Text(text) => text.contents.len(),
             // TODO: Scale column width based on image size
             MarkdownParagraphChunk::Image(_) => 1,
+            MarkdownParagraphChunk::Math(math) => math.contents.len(),
         })
         .sum()
 }
@@ -420,6 +469,7 @@ fn render_markdown_text(parsed_new: &MarkdownParagraph, cx: &mut RenderContext)
     let workspace_clone = cx.workspace.clone();
     let code_span_bg_color = cx.code_span_background_color;
     let text_style = cx.text_style.clone();
+    let footnote_clicked_callback = cx.footnote_clicked_callback.clone();
 
     for parsed_region in parsed_new {
         match parsed_region {
@@ -457,6 +507,7 @@ fn render_markdown_text(parsed_new: &MarkdownParagraph, cx: &mut RenderContext)
                     }
                 }
                 let workspace = workspace_clone.clone();
+                let footnote_clicked_callback = footnote_clicked_callback.clone();
                 let element = div()
                     .child(
                         InteractiveText::new(
@@ -470,6 +521,9 @@ fn render_markdown_text(parsed_new: &MarkdownParagraph, cx: &mut RenderContext)
                             move |idx, cx| {
                                 for (ix, range) in link_ranges.iter().enumerate() {
                                     if range.contains(&idx) {
+                                        if let Link::Footnote { .. } = &links[ix] {
+                                            return None;
+                                        }
                                         return Some(LinkPreview::new(&links[ix].to_string(), cx));
                                     }
                                 }
@@ -489,6 +543,14 @@ fn render_markdown_text(parsed_new: &MarkdownParagraph, cx: &mut RenderContext)
                                         });
                                     }
                                 }
+                                Link::Footnote { label } => {
+                                    if let Some(callback) = &footnote_clicked_callback {
+                                        callback(
+                                            &FootnoteTarget::Definition(label.clone()),
+                                            window_cx,
+                                        );
+                                    }
+                                }
                             },
                         ),
                     )
@@ -500,6 +562,7 @@ fn render_markdown_text(parsed_new: &MarkdownParagraph, cx: &mut RenderContext)
                 let image_resource = match image.link.clone() {
                     Link::Web { url } => Resource::Uri(url.into()),
                     Link::Path { path, .. } => Resource::Path(Arc::from(path)),
+                    Link::Footnote { .. } => continue,
                 };
 
                 let element_id = cx.next_id(&image.source_range);
@@ -540,6 +603,7 @@ fn render_markdown_text(parsed_new: &MarkdownParagraph, cx: &mut RenderContext)
                                             });
                                         }
                                     }
+                                    Link::Footnote { .. } => {}
                                 }
                             }
                         }
@@ -547,12 +611,649 @@ fn render_markdown_text(parsed_new: &MarkdownParagraph, cx: &mut RenderContext)
                     .into_any();
                 any_element.push(image_element);
             }
+
+            MarkdownParagraphChunk::Math(math) => {
+                any_element.push(render_markdown_math(math, cx));
+            }
         }
     }
 
     any_element
 }
 
+fn render_markdown_mermaid_diagram(
+    parsed: &ParsedMarkdownMermaidDiagram,
+    cx: &mut RenderContext,
+) -> AnyElement {
+    let diagram = match &parsed.diagram {
+        Ok(diagram) => diagram,
+        Err(error) => {
+            return cx
+                .with_common_p(v_flex())
+                .gap_1()
+                .child(
+                    div()
+                        .font_family(cx.buffer_font_family.clone())
+                        .px_3()
+                        .py_3()
+                        .bg(cx.code_block_background_color)
+                        .rounded_md()
+                        .child(parsed.contents.clone()),
+                )
+                .child(
+                    Label::new(format!("Failed to render diagram: {error}"))
+                        .size(LabelSize::Small)
+                        .color(Color::Error),
+                )
+                .into_any();
+        }
+    };
+
+    let metrics = cx.diagram_text_metrics;
+    let layout = diagram.layout(metrics);
+    let line_color = cx.text_muted_color;
+    let border_color = cx.border_color;
+    let node_background_color = cx.diagram_node_background_color;
+
+    let diamonds: Vec<Bounds<f32>> = layout
+        .boxes
+        .iter()
+        .filter(|layout_box| layout_box.shape == NodeShape::Diamond)
+        .map(|layout_box| layout_box.bounds)
+        .collect();
+
+    let boxes = layout.boxes.iter().map(|layout_box| {
+        let bounds = layout_box.bounds;
+        let lines = mermaid::label_lines(&layout_box.label)
+            .into_iter()
+            .map(|line| div().child(line.to_string()));
+        let element = v_flex()
+            .absolute()
+            .left(px(bounds.origin.x))
+            .top(px(bounds.origin.y))
+            .w(px(bounds.size.width))
+            .h(px(bounds.size.height));
+
+        match layout_box.style {
+            BoxStyle::Frame => element
+                .border_1()
+                .border_color(border_color)
+                .child(div().px_1().text_color(cx.text_muted_color).children(lines)),
+            BoxStyle::Note => element
+                .justify_center()
+                .items_center()
+                .border_1()
+                .border_color(border_color)
+                .bg(cx.diagram_note_background_color)
+                .children(lines),
+            BoxStyle::Node => {
+                let element = element.justify_center().items_center().children(lines);
+                match layout_box.shape {
+                    NodeShape::Diamond => element,
+                    NodeShape::Rectangle => element
+                        .border_1()
+                        .border_color(border_color)
+                        .bg(node_background_color),
+                    NodeShape::Rounded => element
+                        .border_1()
+                        .border_color(border_color)
+                        .bg(node_background_color)
+                        .rounded_md(),
+                    NodeShape::Stadium | NodeShape::Circle => element
+                        .border_1()
+                        .border_color(border_color)
+                        .bg(node_background_color)
+                        .rounded_full(),
+                }
+            }
+        }
+    });
+
+    let labels = layout.labels.iter().map(|label| {
+        let size = metrics.text_size(&label.text);
+        v_flex()
+            .absolute()
+            .items_center()
+            .left(px(label.center.x - size.width / 2.))
+            .top(px(label.center.y - size.height / 2.))
+            .bg(cx.code_block_background_color)
+            .children(
+                mermaid::label_lines(&label.text)
+                    .into_iter()
+                    .map(|line| div().child(line.to_string())),
+            )
+    });
+
+    let lines = layout.lines;
+    let lines_canvas = canvas(
+        |_, _| {},
+        move |bounds, _, cx| {
+            for line in &lines {
+                paint_diagram_line(line, bounds.origin, line_color, cx);
+            }
+            for diamond in &diamonds {
+                paint_diamond(
+                    *diamond,
+                    bounds.origin,
+                    border_color,
+                    node_background_color,
+                    cx,
+                );
+            }
+        },
+    )
+    .absolute()
+    .size_full();
+
+    let diagram = div()
+        .relative()
+        .flex_none()
+        .w(px(layout.size.width))
+        .h(px(layout.size.height))
+        .font_family(cx.buffer_font_family.clone())
+        .text_size(cx.buffer_text_style.font_size)
+        .line_height(px(metrics.line_height))
+        .text_color(cx.text_color)
+        .child(lines_canvas)
+        .children(boxes)
+        .children(labels);
+
+    let element_id = cx.next_id(&parsed.source_range);
+    cx.with_common_p(div())
+        .child(
+            h_flex()
+                .id(element_id)
+                .overflow_x_scroll()
+                .justify_center()
+                .px_3()
+                .py_3()
+                .bg(cx.code_block_background_color)
+                .rounded_md()
+                .child(diagram),
+        )
+        .into_any()
+}
+
+const DIAGRAM_DASH_LENGTH: f32 = 4.;
+const DIAGRAM_DASH_GAP: f32 = 3.;
+
+fn paint_diagram_line(
+    line: &LayoutLine,
+    origin: Point<Pixels>,
+    color: Hsla,
+    cx: &mut WindowContext,
+) {
+    let width = match line.stroke {
+        Stroke::Thick => 2.5,
+        Stroke::Solid | Stroke::Dotted => 1.,
+    };
+
+    for segment in line.points.windows(2) {
+        let (start, end) = (segment[0], segment[1]);
+        if line.stroke == Stroke::Dotted {
+            let length = distance(start, end);
+            let mut offset = 0.;
+            while offset < length {
+                let dash_end = (offset + DIAGRAM_DASH_LENGTH).min(length);
+                paint_segment(
+                    lerp(start, end, offset / length),
+                    lerp(start, end, dash_end / length),
+                    width,
+                    origin,
+                    color,
+                    cx,
+                );
+                offset += DIAGRAM_DASH_LENGTH + DIAGRAM_DASH_GAP;
+            }
+        } else {
+            paint_segment(start, end, width, origin, color, cx);
+        }
+    }
+
+    let [.., before, tip] = line.points.as_slice() else {
+        return;
+    };
+    let length = distance(*before, *tip);
+    if length == 0. {
+        return;
+    }
+    let direction = point((tip.x - before.x) / length, (tip.y - before.y) / length);
+    let normal = point(-direction.y, direction.x);
+    let half = mermaid::ARROW_SIZE / 2.;
+    let base = point(
+        tip.x - direction.x * mermaid::ARROW_SIZE,
+        tip.y - direction.y * mermaid::ARROW_SIZE,
+    );
+    let left = point(base.x + normal.x * half, base.y + normal.y * half);
+    let right = point(base.x - normal.x * half, base.y - normal.y * half);
+
+    match line.arrow {
+        ArrowHead::None => {}
+        ArrowHead::Filled => {
+            let mut path = Path::new(to_diagram_pixels(*tip, origin));
+            path.line_to(to_diagram_pixels(left, origin));
+            path.line_to(to_diagram_pixels(right, origin));
+            cx.paint_path(path, color);
+        }
+        ArrowHead::Open => {
+            paint_segment(left, *tip, width, origin, color, cx);
+            paint_segment(right, *tip, width, origin, color, cx);
+        }
+        ArrowHead::Cross => {
+            let center = lerp(base, *tip, 0.5);
+            let offset = point(
+                (direction.x + normal.x) * half / 2.,
+                (direction.y + normal.y) * half / 2.,
+            );
+            let other_offset = point(
+                (direction.x - normal.x) * half / 2.,
+                (direction.y - normal.y) * half / 2.,
+            );
+            paint_segment(
+                point(center.x - offset.x, center.y - offset.y),
+                point(center.x + offset.x, center.y + offset.y),
+                width,
+                origin,
+                color,
+                cx,
+            );
+            paint_segment(
+                point(center.x - other_offset.x, center.y - other_offset.y),
+                point(center.x + other_offset.x, center.y + other_offset.y),
+                width,
+                origin,
+                color,
+                cx,
+            );
+        }
+    }
+}
+
+fn paint_diamond(
+    bounds: Bounds<f32>,
+    origin: Point<Pixels>,
+    border_color: Hsla,
+    background_color: Hsla,
+    cx: &mut WindowContext,
+) {
+    let center = bounds.center();
+    let half_width = bounds.size.width / 2.;
+    let half_height = bounds.size.height / 2.;
+    paint_rhombus(center, half_width, half_height, origin, border_color, cx);
+
+    // Offset each edge of the rhombus inwards by the width of its border.
+    let border_width = 1.;
+    let hypotenuse = half_width.hypot(half_height);
+    paint_rhombus(
+        center,
+        half_width - border_width * hypotenuse / half_height,
+        half_height - border_width * hypotenuse / half_width,
+        origin,
+        background_color,
+        cx,
+    );
+}
+
+fn paint_rhombus(
+    center: Point<f32>,
+    half_width: f32,
+    half_height: f32,
+    origin: Point<Pixels>,
+    color: Hsla,
+    cx: &mut WindowContext,
+) {
+    let mut path = Path::new(to_diagram_pixels(
+        point(center.x, center.y - half_height),
+        origin,
+    ));
+    path.line_to(to_diagram_pixels(
+        point(center.x + half_width, center.y),
+        origin,
+    ));
+    path.line_to(to_diagram_pixels(
+        point(center.x, center.y + half_height),
+        origin,
+    ));
+    path.line_to(to_diagram_pixels(
+        point(center.x - half_width, center.y),
+        origin,
+    ));
+    cx.paint_path(path, color);
+}
+
+fn paint_segment(
+    start: Point<f32>,
+    end: Point<f32>,
+    width: f32,
+    origin: Point<Pixels>,
+    color: Hsla,
+    cx: &mut WindowContext,
+) {
+    let length = distance(start, end);
+    if length == 0. {
+        return;
+    }
+    let scale = width / 2. / length;
+    let normal = point(-(end.y - start.y) * scale, (end.x - start.x) * scale);
+
+    let mut path = Path::new(to_diagram_pixels(
+        point(start.x + normal.x, start.y + normal.y),
+        origin,
+    ));
+    path.line_to(to_diagram_pixels(
+        point(end.x + normal.x, end.y + normal.y),
+        origin,
+    ));
+    path.line_to(to_diagram_pixels(
+        point(end.x - normal.x, end.y - normal.y),
+        origin,
+    ));
+    path.line_to(to_diagram_pixels(
+        point(start.x - normal.x, start.y - normal.y),
+        origin,
+    ));
+    cx.paint_path(path, color);
+}
+
+fn distance(a: Point<f32>, b: Point<f32>) -> f32 {
+    (b.x - a.x).hypot(b.y - a.y)
+}
+
+fn lerp(a: Point<f32>, b: Point<f32>, t: f32) -> Point<f32> {
+    point(a.x + (b.x - a.x) * t, a.y + (b.y - a.y) * t)
+}
+
+fn to_diagram_pixels(position: Point<f32>, origin: Point<Pixels>) -> Point<Pixels> {
+    point(origin.x + px(position.x), origin.y + px(position.y))
+}
+
+const MIN_MATH_FONT_SIZE: Pixels = px(8.);
+
+fn render_markdown_math(parsed: &ParsedMarkdownMath, cx: &mut RenderContext) -> AnyElement {
+    h_flex()
+        .id(cx.next_id(&parsed.source_range))
+        .w_full()
+        .justify_center()
+        .py_2()
+        .overflow_x_scroll()
+        .text_color(cx.text_color)
+        .child(render_math_node(
+            &parsed.expression,
+            cx.math_font_size,
+            cx.text_color,
+        ))
+        .into_any()
+}
+
+fn render_math_node(node: &MathNode, font_size: Pixels, color: Hsla) -> AnyElement {
+    let script_size = (font_size * 0.7).max(MIN_MATH_FONT_SIZE);
+
+    match node {
+        MathNode::Identifier(text) => math_text(text, font_size).italic().into_any(),
+        MathNode::Number(text) | MathNode::Symbol(text) | MathNode::Text(text) => {
+            math_text(text, font_size).into_any()
+        }
+        MathNode::Binary(text) => math_text(text, font_size).mx(font_size * 0.22).into_any(),
+        MathNode::Relation(text) => math_text(text, font_size).mx(font_size * 0.28).into_any(),
+        MathNode::Space(width) => div().flex_none().w(font_size * *width).into_any(),
+        MathNode::Row(children) => h_flex()
+            .children(
+                children
+                    .iter()
+                    .map(|child| render_math_node(child, font_size, color)),
+            )
+            .into_any(),
+        MathNode::Fraction {
+            numerator,
+            denominator,
+        } => {
+            let part_size = (font_size * 0.9).max(MIN_MATH_FONT_SIZE);
+            v_flex()
+                .items_center()
+                .mx(font_size * 0.1)
+                .child(render_math_node(numerator, part_size, color))
+                .child(div().w_full().h(px(1.)).my(px(2.)).bg(color))
+                .child(render_math_node(denominator, part_size, color))
+                .into_any()
+        }
+        MathNode::Root { radicand, index } => h_flex()
+            .children(index.as_ref().map(|index| {
+                v_flex()
+                    .child(render_math_node(index, script_size, color))
+                    .child(div().h(font_size * 0.4))
+            }))
+            .child(math_text("√", font_size * math_height(radicand)))
+            .child(
+                div()
+                    .border_t_1()
+                    .border_color(color)
+                    .child(render_math_node(radicand, font_size, color)),
+            )
+            .into_any(),
+        MathNode::Scripts {
+            base,
+            subscript,
+            superscript,
+        } => h_flex()
+            .child(render_math_node(base, font_size, color))
+            .child(render_math_scripts(
+                subscript.as_deref(),
+                superscript.as_deref(),
+                script_size,
+                color,
+            ))
+            .into_any(),
+        MathNode::LargeOperator {
+            symbol,
+            lower,
+            upper,
+        } => {
+            let is_word = symbol.chars().all(|c| c.is_alphabetic() || c == ' ');
+            let symbol_size = if is_word { font_size } else { font_size * 1.5 };
+            let symbol_element = math_text(symbol, symbol_size);
+
+            // Integrals place their limits beside the symbol rather than above and below it.
+            if symbol.starts_with(['∫', '∬', '∭', '∮']) {
+                h_flex()
+                    .mr(font_size * 0.15)
+                    .child(symbol_element)
+                    .child(render_math_scripts(
+                        lower.as_deref(),
+                        upper.as_deref(),
+                        script_size,
+                        color,
+                    ))
+                    .into_any()
+            } else {
+                v_flex()
+                    .items_center()
+                    .mx(font_size * 0.15)
+                    .children(
+                        upper
+                            .as_ref()
+                            .map(|upper| render_math_node(upper, script_size, color)),
+                    )
+                    .child(symbol_element)
+                    .children(
+                        lower
+                            .as_ref()
+                            .map(|lower| render_math_node(lower, script_size, color)),
+                    )
+                    .into_any()
+            }
+        }
+        MathNode::Delimited { open, body, close } => {
+            let delimiter_size = font_size * math_height(body);
+            h_flex()
+                .when(!open.is_empty(), |this| {
+                    this.child(math_text(open, delimiter_size))
+                })
+                .child(render_math_node(body, font_size, color))
+                .when(!close.is_empty(), |this| {
+                    this.child(math_text(close, delimiter_size))
+                })
+                .into_any()
+        }
+        MathNode::Matrix { open, rows, close } => {
+            let delimiter_size = font_size * math_height(node);
+            let column_count = rows.iter().map(Vec::len).max().unwrap_or(0);
+            let columns = (0..column_count).map(|column| {
+                let cells = rows.iter().map(|row| match row.get(column) {
+                    Some(cell) => h_flex()
+                        .h(font_size * 1.25 * math_height(cell))
+                        .child(render_math_node(cell, font_size, color)),
+                    None => h_flex().h(font_size * 1.25),
+                });
+                // Environments with a single delimiter, such as `cases`, are left-aligned.
+                let column = v_flex().gap(font_size * 0.3).children(cells);
+                if close.is_empty() {
+                    column.items_start()
+                } else {
+                    column.items_center()
+                }
+            });
+
+            h_flex()
+                .when(!open.is_empty(), |this| {
+                    this.child(math_text(open, delimiter_size))
+                })
+                .child(
+                    h_flex()
+                        .items_start()
+                        .mx(font_size * 0.2)
+                        .gap(font_size * 0.8)
+                        .children(columns),
+                )
+                .when(!close.is_empty(), |this| {
+                    this.child(math_text(close, delimiter_size))
+                })
+                .into_any()
+        }
+        MathNode::Accent {
+            base,
+            accent: Accent::Overline,
+        } => div()
+            .border_t_1()
+            .border_color(color)
+            .child(render_math_node(base, font_size, color))
+            .into_any(),
+        MathNode::Accent { base, accent } => v_flex()
+            .items_center()
+            .child(
+                div()
+                    .text_size(script_size)
+                    .line_height(font_size * 0.4)
+                    .child(accent.symbol()),
+            )
+            .child(render_math_node(base, font_size, color))
+            .into_any(),
+    }
+}
+
+fn render_math_scripts(
+    subscript: Option<&MathNode>,
+    superscript: Option<&MathNode>,
+    script_size: Pixels,
+    color: Hsla,
+) -> Div {
+    let render_script = |script: Option<&MathNode>| match script {
+        Some(script) => render_math_node(script, script_size, color),
+        None => div().h(script_size * 0.6).into_any(),
+    };
+
+    v_flex()
+        .child(render_script(superscript))
+        .child(render_script(subscript))
+}
+
+fn math_text(text: &str, font_size: Pixels) -> Div {
+    div()
+        .flex_none()
+        .text_size(font_size)
+        .line_height(font_size * 1.25)
+        .child(text.to_string())
+}
+
+/// Estimates the height of an expression relative to a line of text, so that
+/// delimiters and radicals can be scaled to fit around it.
+fn math_height(node: &MathNode) -> f32 {
+    match node {
+        MathNode::Row(children) => children.iter().map(math_height).fold(1., f32::max),
+        MathNode::Fraction {
+            numerator,
+            denominator,
+        } => 0.9 * (math_height(numerator) + math_height(denominator)),
+        MathNode::Root { radicand, .. } => math_height(radicand),
+        MathNode::Scripts {
+            base,
+            subscript,
+            superscript,
+        } => {
+            let scripts = subscript.is_some() as u8 + superscript.is_some() as u8;
+            math_height(base) + 0.3 * scripts as f32
+        }
+        MathNode::LargeOperator { lower, upper, .. } => {
+            let limits = lower.is_some() as u8 + upper.is_some() as u8;
+            1.5 + 0.7 * limits as f32
+        }
+        MathNode::Delimited { body, .. } => math_height(body),
+        MathNode::Matrix { rows, .. } => {
+            let height: f32 = rows
+                .iter()
+                .map(|row| row.iter().map(math_height).fold(1., f32::max))
+                .sum();
+            height * 1.1
+        }
+        MathNode::Accent { base, .. } => math_height(base) + 0.3,
+        MathNode::Identifier(_)
+        | MathNode::Number(_)
+        | MathNode::Symbol(_)
+        | MathNode::Binary(_)
+        | MathNode::Relation(_)
+        | MathNode::Text(_)
+        | MathNode::Space(_) => 1.,
+    }
+}
+
+fn render_markdown_footnote_definition(
+    parsed: &ParsedMarkdownFootnoteDefinition,
+    cx: &mut RenderContext,
+) -> AnyElement {
+    let children: Vec<AnyElement> = parsed
+        .children
+        .iter()
+        .map(|child| render_markdown_block(child, cx))
+        .collect();
+
+    let text_color = cx.text_color;
+    let back_link = div()
+        .id(cx.next_id(&parsed.source_range))
+        .cursor_pointer()
+        .text_color(cx.text_muted_color)
+        .hover(move |style| style.text_color(text_color))
+        .child("↩")
+        .tooltip(|cx| Tooltip::text("Back to Reference", cx))
+        .when_some(cx.footnote_clicked_callback.clone(), |this, callback| {
+            let label = parsed.label.clone();
+            this.on_click(move |_, cx| {
+                callback(&FootnoteTarget::Reference(label.clone()), cx);
+            })
+        });
+
+    cx.with_common_p(h_flex())
+        .items_start()
+        .gap_2()
+        .child(
+            div()
+                .flex_none()
+                .text_color(cx.text_muted_color)
+                .child(format!("{}.", parsed.number)),
+        )
+        .child(div().w_full().children(children))
+        .child(back_link)
+        .into_any()
+}
+
 fn render_markdown_rule(cx: &mut RenderContext) -> AnyElement {
     let rule = div().w_full().h(px(2.)).bg(cx.border_color);
     div().pt_3().pb_3().child(rule).into_any()
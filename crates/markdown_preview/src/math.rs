@@ -0,0 +1,1005 @@
+//! A parser for the subset of TeX math that's commonly used in Markdown documents,
+//! such as `$e^{i\pi} + 1 = 0$`.
+//!
+//! Parsing never fails: unknown commands are kept as text, and unbalanced braces
+//! are closed at the end of the input, so that the preview can always show something.
+
+use std::iter::Peekable;
+use std::str::Chars;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum MathNode {
+    /// A variable, which is rendered in italics.
+    Identifier(String),
+    Number(String),
+    /// An ordinary symbol, such as a parenthesis or `∞`.
+    Symbol(String),
+    /// A binary operator, such as `+`, which is surrounded by a medium space.
+    Binary(String),
+    /// A relation, such as `=`, which is surrounded by a thick space.
+    Relation(String),
+    /// Upright text, such as the contents of `\text{…}` or a function name like `sin`.
+    Text(String),
+    /// Horizontal space, in ems.
+    Space(f32),
+    Row(Vec<MathNode>),
+    Fraction {
+        numerator: Box<MathNode>,
+        denominator: Box<MathNode>,
+    },
+    Root {
+        radicand: Box<MathNode>,
+        index: Option<Box<MathNode>>,
+    },
+    Scripts {
+        base: Box<MathNode>,
+        subscript: Option<Box<MathNode>>,
+        superscript: Option<Box<MathNode>>,
+    },
+    /// A large operator, such as `∑`, whose limits are placed above and below it.
+    LargeOperator {
+        symbol: String,
+        lower: Option<Box<MathNode>>,
+        upper: Option<Box<MathNode>>,
+    },
+    /// Delimiters that stretch to the height of their contents, from `\left` and `\right`.
+    Delimited {
+        open: String,
+        body: Box<MathNode>,
+        close: String,
+    },
+    /// A matrix or another environment that lays out cells in a grid, such as `cases`.
+    Matrix {
+        open: String,
+        rows: Vec<Vec<MathNode>>,
+        close: String,
+    },
+    Accent {
+        base: Box<MathNode>,
+        accent: Accent,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Accent {
+    Hat,
+    Bar,
+    Vector,
+    Dot,
+    DoubleDot,
+    Tilde,
+    Overline,
+}
+
+impl Accent {
+    /// The symbol drawn above the accented expression.
+    pub fn symbol(self) -> &'static str {
+        match self {
+            Accent::Hat => "^",
+            Accent::Bar | Accent::Overline => "‾",
+            Accent::Vector => "→",
+            Accent::Dot => "˙",
+            Accent::DoubleDot => "¨",
+            Accent::Tilde => "~",
+        }
+    }
+
+    fn combining_char(self) -> char {
+        match self {
+            Accent::Hat => '\u{302}',
+            Accent::Bar => '\u{304}',
+            Accent::Vector => '\u{20d7}',
+            Accent::Dot => '\u{307}',
+            Accent::DoubleDot => '\u{308}',
+            Accent::Tilde => '\u{303}',
+            Accent::Overline => '\u{305}',
+        }
+    }
+}
+
+/// Parses a TeX math expression.
+pub fn parse(source: &str) -> MathNode {
+    let mut parser = MathParser {
+        tokens: tokenize(source),
+        cursor: 0,
+    };
+    parser.parse_row(&|_| false)
+}
+
+impl MathNode {
+    /// Returns an approximation of this expression in plain Unicode text, for math that
+    /// appears inline with other text.
+    pub fn to_unicode(&self) -> String {
+        let mut text = String::new();
+        self.write_unicode(&mut text);
+        text.trim().to_string()
+    }
+
+    fn write_unicode(&self, text: &mut String) {
+        match self {
+            MathNode::Identifier(s)
+            | MathNode::Number(s)
+            | MathNode::Symbol(s)
+            | MathNode::Text(s) => text.push_str(s),
+            MathNode::Binary(s) | MathNode::Relation(s) => {
+                text.push(' ');
+                text.push_str(s);
+                text.push(' ');
+            }
+            MathNode::Space(width) => {
+                if *width >= 0.5 {
+                    text.push(' ');
+                } else if *width > 0. {
+                    text.push('\u{2009}');
+                }
+            }
+            MathNode::Row(children) => {
+                for child in children {
+                    child.write_unicode(text);
+                }
+            }
+            MathNode::Fraction {
+                numerator,
+                denominator,
+            } => {
+                numerator.write_unicode_grouped(text);
+                text.push('/');
+                denominator.write_unicode_grouped(text);
+            }
+            MathNode::Root { radicand, index } => {
+                match index.as_deref().map(MathNode::to_unicode).as_deref() {
+                    Some("3") => text.push('∛'),
+                    Some("4") => text.push('∜'),
+                    Some(index) => {
+                        text.push_str(&to_superscript(index).unwrap_or_default());
+                        text.push('√');
+                    }
+                    None => text.push('√'),
+                }
+                radicand.write_unicode_grouped(text);
+            }
+            MathNode::Scripts {
+                base,
+                subscript,
+                superscript,
+            } => {
+                base.write_unicode(text);
+                write_scripts(text, subscript.as_deref(), superscript.as_deref());
+            }
+            MathNode::LargeOperator {
+                symbol,
+                lower,
+                upper,
+            } => {
+                text.push_str(symbol);
+                write_scripts(text, lower.as_deref(), upper.as_deref());
+                text.push(' ');
+            }
+            MathNode::Delimited { open, body, close } => {
+                text.push_str(open);
+                body.write_unicode(text);
+                text.push_str(close);
+            }
+            MathNode::Matrix { open, rows, close } => {
+                text.push_str(if open.is_empty() { "[" } else { open });
+                for (ix, row) in rows.iter().enumerate() {
+                    if ix > 0 {
+                        text.push_str("; ");
+                    }
+                    for (ix, cell) in row.iter().enumerate() {
+                        if ix > 0 {
+                            text.push(' ');
+                        }
+                        text.push_str(&cell.to_unicode());
+                    }
+                }
+                text.push_str(if open.is_empty() { "]" } else { close });
+            }
+            MathNode::Accent { base, accent } => {
+                let base = base.to_unicode();
+                text.push_str(&base);
+                if base.chars().count() == 1 {
+                    text.push(accent.combining_char());
+                }
+            }
+        }
+    }
+
+    /// Writes this node, surrounded by parentheses if it consists of more than one term.
+    fn write_unicode_grouped(&self, text: &mut String) {
+        let is_simple = match self {
+            MathNode::Row(children) => children.len() <= 1,
+            MathNode::Fraction { .. } => false,
+            _ => true,
+        };
+        if is_simple {
+            self.write_unicode(text);
+        } else {
+            text.push('(');
+            text.push_str(&self.to_unicode());
+            text.push(')');
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        matches!(self, MathNode::Row(children) if children.is_empty())
+    }
+}
+
+fn write_scripts(text: &mut String, subscript: Option<&MathNode>, superscript: Option<&MathNode>) {
+    if let Some(subscript) = subscript {
+        let subscript = subscript.to_unicode();
+        match to_subscript(&subscript) {
+            Some(subscript) => text.push_str(&subscript),
+            None => {
+                text.push_str("_(");
+                text.push_str(&subscript);
+                text.push(')');
+            }
+        }
+    }
+    if let Some(superscript) = superscript {
+        let superscript = superscript.to_unicode();
+        match to_superscript(&superscript) {
+            Some(superscript) => text.push_str(&superscript),
+            None => {
+                text.push_str("^(");
+                text.push_str(&superscript);
+                text.push(')');
+            }
+        }
+    }
+}
+
+/// Converts the text to Unicode superscript characters, if they all have one.
+pub fn to_superscript(text: &str) -> Option<String> {
+    text.chars()
+        .map(|c| {
+            Some(match c {
+                '0' => '⁰',
+                '1' => '¹',
+                '2' => '²',
+                '3' => '³',
+                '4' => '⁴',
+                '5' => '⁵',
+                '6' => '⁶',
+                '7' => '⁷',
+                '8' => '⁸',
+                '9' => '⁹',
+                '+' => '⁺',
+                '-' | '−' => '⁻',
+                '=' => '⁼',
+                '(' => '⁽',
+                ')' => '⁾',
+                'i' => 'ⁱ',
+                'n' => 'ⁿ',
+                'T' => 'ᵀ',
+                '′' => '′',
+                '*' | '∗' => '*',
+                _ => return None,
+            })
+        })
+        .collect()
+}
+
+/// Converts the text to Unicode subscript characters, if they all have one.
+pub fn to_subscript(text: &str) -> Option<String> {
+    text.chars()
+        .map(|c| {
+            Some(match c {
+                '0' => '₀',
+                '1' => '₁',
+                '2' => '₂',
+                '3' => '₃',
+                '4' => '₄',
+                '5' => '₅',
+                '6' => '₆',
+                '7' => '₇',
+                '8' => '₈',
+                '9' => '₉',
+                '+' => '₊',
+                '-' | '−' => '₋',
+                '=' => '₌',
+                '(' => '₍',
+                ')' => '₎',
+                'a' => 'ₐ',
+                'e' => 'ₑ',
+                'i' => 'ᵢ',
+                'j' => 'ⱼ',
+                'k' => 'ₖ',
+                'n' => 'ₙ',
+                'x' => 'ₓ',
+                _ => return None,
+            })
+        })
+        .collect()
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Command(String),
+    Char(char),
+    OpenBrace,
+    CloseBrace,
+    Superscript,
+    Subscript,
+    Ampersand,
+}
+
+fn tokenize(source: &str) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    let mut chars = source.chars().peekable();
+    while let Some(c) = chars.next() {
+        tokens.push(match c {
+            '\\' => Token::Command(read_command(&mut chars)),
+            '{' => Token::OpenBrace,
+            '}' => Token::CloseBrace,
+            '^' => Token::Superscript,
+            '_' => Token::Subscript,
+            '&' => Token::Ampersand,
+            '%' => {
+                // A comment extends to the end of the line.
+                for c in chars.by_ref() {
+                    if c == '\n' {
+                        break;
+                    }
+                }
+                continue;
+            }
+            c if c.is_whitespace() => continue,
+            c => Token::Char(c),
+        });
+    }
+    tokens
+}
+
+fn read_command(chars: &mut Peekable<Chars>) -> String {
+    let mut name = String::new();
+    while let Some(&c) = chars.peek() {
+        if c.is_ascii_alphabetic() {
+            name.push(c);
+            chars.next();
+        } else {
+            break;
+        }
+    }
+    if name.is_empty() {
+        if let Some(c) = chars.next() {
+            name.push(c);
+        }
+    }
+    name
+}
+
+struct MathParser {
+    tokens: Vec<Token>,
+    cursor: usize,
+}
+
+impl MathParser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.cursor)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.cursor).cloned();
+        self.cursor += 1;
+        token
+    }
+
+    /// Parses nodes until the end of the input, or until `is_end` returns true
+    /// for the next token, which is left unconsumed.
+    fn parse_row(&mut self, is_end: &dyn Fn(&Token) -> bool) -> MathNode {
+        let mut children = Vec::new();
+        while let Some(token) = self.peek() {
+            if is_end(token) {
+                break;
+            }
+            if *token == Token::CloseBrace {
+                // An unbalanced closing brace.
+                self.cursor += 1;
+                continue;
+            }
+            let Some(node) = self.parse_atom() else {
+                continue;
+            };
+            let node = self.parse_scripts(node);
+
+            // A binary operator at the start of an expression, or after another
+            // operator, is a sign rather than an operation.
+            let node = match node {
+                MathNode::Binary(symbol)
+                    if matches!(
+                        children.last(),
+                        None | Some(MathNode::Binary(_) | MathNode::Relation(_))
+                    ) =>
+                {
+                    MathNode::Symbol(symbol)
+                }
+                node => node,
+            };
+            children.push(node);
+        }
+        if children.len() == 1 {
+            children.pop().unwrap()
+        } else {
+            MathNode::Row(children)
+        }
+    }
+
+    fn parse_scripts(&mut self, base: MathNode) -> MathNode {
+        let mut subscript = None;
+        let mut superscript = None;
+        loop {
+            match self.peek() {
+                Some(Token::Superscript) => {
+                    self.cursor += 1;
+                    superscript = Some(Box::new(self.parse_argument()));
+                }
+                Some(Token::Subscript) => {
+                    self.cursor += 1;
+                    subscript = Some(Box::new(self.parse_argument()));
+                }
+                Some(Token::Char('\'')) => {
+                    self.cursor += 1;
+                    let prime = MathNode::Symbol("′".into());
+                    superscript = Some(Box::new(match superscript.take() {
+                        Some(existing) => append(*existing, prime),
+                        None => prime,
+                    }));
+                }
+                _ => break,
+            }
+        }
+
+        if subscript.is_none() && superscript.is_none() {
+            return base;
+        }
+        match base {
+            MathNode::LargeOperator {
+                symbol,
+                lower: None,
+                upper: None,
+            } => MathNode::LargeOperator {
+                symbol,
+                lower: subscript,
+                upper: superscript,
+            },
+            base => MathNode::Scripts {
+                base: Box::new(base),
+                subscript,
+                superscript,
+            },
+        }
+    }
+
+    /// Parses the argument of a command or script: either a group in braces, or a single token.
+    fn parse_argument(&mut self) -> MathNode {
+        match self.peek() {
+            Some(Token::OpenBrace) => {
+                self.cursor += 1;
+                self.parse_group()
+            }
+            Some(Token::Char(c)) if c.is_ascii_digit() => {
+                // Unlike in a row, only one digit is taken as the argument.
+                let c = *c;
+                self.cursor += 1;
+                MathNode::Number(c.to_string())
+            }
+            Some(_) => self.parse_atom().unwrap_or(MathNode::Row(Vec::new())),
+            None => MathNode::Row(Vec::new()),
+        }
+    }
+
+    /// Parses the rest of a group whose opening brace has been consumed.
+    fn parse_group(&mut self) -> MathNode {
+        let group = self.parse_row(&|token| *token == Token::CloseBrace);
+        self.cursor += 1;
+        group
+    }
+
+    /// Reads the raw text of a group in braces, for commands such as `\text`.
+    fn parse_text_argument(&mut self) -> String {
+        if self.peek() != Some(&Token::OpenBrace) {
+            return match self.next() {
+                Some(Token::Char(c)) => c.to_string(),
+                _ => String::new(),
+            };
+        }
+        self.cursor += 1;
+
+        let mut text = String::new();
+        let mut depth = 0;
+        while let Some(token) = self.next() {
+            match token {
+                Token::OpenBrace => depth += 1,
+                Token::CloseBrace if depth == 0 => break,
+                Token::CloseBrace => depth -= 1,
+                Token::Char(c) => text.push(c),
+                Token::Command(command) => match command.as_str() {
+                    " " | "," | ";" | "quad" => text.push(' '),
+                    command if command.chars().all(|c| c.is_ascii_alphabetic()) => {}
+                    command => text.push_str(command),
+                },
+                Token::Superscript => text.push('^'),
+                Token::Subscript => text.push('_'),
+                Token::Ampersand => text.push('&'),
+            }
+        }
+        text
+    }
+
+    fn parse_atom(&mut self) -> Option<MathNode> {
+        let token = self.next()?;
+        Some(match token {
+            Token::OpenBrace => self.parse_group(),
+            Token::CloseBrace => return None,
+            // Scripts without a base are attached to an empty one.
+            Token::Superscript | Token::Subscript => {
+                self.cursor -= 1;
+                self.parse_scripts(MathNode::Row(Vec::new()))
+            }
+            Token::Ampersand => return None,
+            Token::Char(c) if c.is_ascii_digit() || c == '.' => {
+                let mut number = c.to_string();
+                while let Some(Token::Char(c)) = self.peek() {
+                    if c.is_ascii_digit() || *c == '.' {
+                        number.push(*c);
+                        self.cursor += 1;
+                    } else {
+                        break;
+                    }
+                }
+                MathNode::Number(number)
+            }
+            Token::Char(c) if c.is_alphabetic() => MathNode::Identifier(c.to_string()),
+            Token::Char(c) => char_node(c),
+            Token::Command(command) => self.parse_command(&command),
+        })
+    }
+
+    fn parse_command(&mut self, command: &str) -> MathNode {
+        match command {
+            "frac" | "dfrac" | "tfrac" | "cfrac" => {
+                let numerator = self.parse_argument();
+                let denominator = self.parse_argument();
+                MathNode::Fraction {
+                    numerator: Box::new(numerator),
+                    denominator: Box::new(denominator),
+                }
+            }
+            "binom" => {
+                let top = self.parse_argument();
+                let bottom = self.parse_argument();
+                MathNode::Matrix {
+                    open: "(".into(),
+                    rows: vec![vec![top], vec![bottom]],
+                    close: ")".into(),
+                }
+            }
+            "sqrt" => {
+                let index = if self.peek() == Some(&Token::Char('[')) {
+                    self.cursor += 1;
+                    let index = self.parse_row(&|token| *token == Token::Char(']'));
+                    self.cursor += 1;
+                    Some(Box::new(index))
+                } else {
+                    None
+                };
+                MathNode::Root {
+                    radicand: Box::new(self.parse_argument()),
+                    index,
+                }
+            }
+            "text" | "textrm" | "textup" | "textit" | "textbf" | "mbox" | "mathrm"
+            | "operatorname" => MathNode::Text(self.parse_text_argument()),
+            "mathbb" => MathNode::Identifier(
+                self.parse_text_argument()
+                    .chars()
+                    .map(double_struck)
+                    .collect(),
+            ),
+            "mathbf" | "boldsymbol" | "mathit" | "mathcal" | "mathsf" | "mathtt"
+            | "displaystyle" | "textstyle" => self.parse_argument(),
+            "left" => {
+                let open = self.parse_delimiter();
+                let body = self.parse_row(&|token| *token == Token::Command("right".into()));
+                self.cursor += 1;
+                let close = self.parse_delimiter();
+                MathNode::Delimited {
+                    open,
+                    body: Box::new(body),
+                    close,
+                }
+            }
+            // Sizing commands are approximated by the delimiter alone.
+            "big" | "Big" | "bigg" | "Bigg" | "bigl" | "bigr" | "Bigl" | "Bigr" => {
+                MathNode::Symbol(self.parse_delimiter())
+            }
+            "begin" => self.parse_environment(),
+            "hat" | "widehat" => self.parse_accent(Accent::Hat),
+            "bar" => self.parse_accent(Accent::Bar),
+            "vec" | "overrightarrow" => self.parse_accent(Accent::Vector),
+            "dot" => self.parse_accent(Accent::Dot),
+            "ddot" => self.parse_accent(Accent::DoubleDot),
+            "tilde" | "widetilde" => self.parse_accent(Accent::Tilde),
+            "overline" => self.parse_accent(Accent::Overline),
+            "," | ">" => MathNode::Space(0.17),
+            ":" => MathNode::Space(0.22),
+            ";" => MathNode::Space(0.28),
+            " " => MathNode::Space(0.33),
+            "quad" => MathNode::Space(1.),
+            "qquad" => MathNode::Space(2.),
+            "!" => MathNode::Space(0.),
+            // A line break outside of an environment.
+            "\\" => MathNode::Space(1.),
+            "{" | "}" | "|" | "%" | "$" | "#" | "&" | "_" => MathNode::Symbol(match command {
+                "|" => "‖".into(),
+                command => command.into(),
+            }),
+            command => {
+                if let Some(symbol) = large_operator(command) {
+                    MathNode::LargeOperator {
+                        symbol: symbol.into(),
+                        lower: None,
+                        upper: None,
+                    }
+                } else if FUNCTIONS.contains(&command) {
+                    MathNode::Text(command.into())
+                } else if let Some(node) = symbol(command) {
+                    node
+                } else {
+                    MathNode::Text(format!("\\{command}"))
+                }
+            }
+        }
+    }
+
+    fn parse_accent(&mut self, accent: Accent) -> MathNode {
+        MathNode::Accent {
+            base: Box::new(self.parse_argument()),
+            accent,
+        }
+    }
+
+    /// Parses the delimiter following a command such as `\left`, where `.` is an empty delimiter.
+    fn parse_delimiter(&mut self) -> String {
+        match self.next() {
+            Some(Token::Char('.')) | None => String::new(),
+            Some(Token::Char(c)) => c.to_string(),
+            Some(Token::Command(command)) => match command.as_str() {
+                "{" | "lbrace" => "{".into(),
+                "}" | "rbrace" => "}".into(),
+                "|" | "Vert" => "‖".into(),
+                "vert" => "|".into(),
+                "langle" => "⟨".into(),
+                "rangle" => "⟩".into(),
+                "lfloor" => "⌊".into(),
+                "rfloor" => "⌋".into(),
+                "lceil" => "⌈".into(),
+                "rceil" => "⌉".into(),
+                _ => String::new(),
+            },
+            Some(_) => String::new(),
+        }
+    }
+
+    fn parse_environment(&mut self) -> MathNode {
+        let name = self.parse_text_argument();
+        let (open, close) = match name.as_str() {
+            "pmatrix" => ("(", ")"),
+            "bmatrix" => ("[", "]"),
+            "Bmatrix" => ("{", "}"),
+            "vmatrix" => ("|", "|"),
+            "Vmatrix" => ("‖", "‖"),
+            "cases" => ("{", ""),
+            _ => ("", ""),
+        };
+        if name == "array" {
+            // Skip the column specification.
+            self.parse_text_argument();
+        }
+
+        let is_cell_end = |token: &Token| {
+            matches!(token, Token::Ampersand)
+                || matches!(token, Token::Command(command) if command == "\\" || command == "end")
+        };
+        let mut rows = Vec::new();
+        let mut row = Vec::new();
+        loop {
+            let cell = self.parse_row(&is_cell_end);
+            match self.next() {
+                Some(Token::Ampersand) => row.push(cell),
+                Some(Token::Command(command)) if command == "\\" => {
+                    row.push(cell);
+                    rows.push(std::mem::take(&mut row));
+                }
+                _ => {
+                    // Ignore the empty row produced by a trailing line break.
+                    if !cell.is_empty() || !row.is_empty() {
+                        row.push(cell);
+                        rows.push(row);
+                    }
+                    self.parse_text_argument();
+                    break;
+                }
+            }
+        }
+
+        MathNode::Matrix {
+            open: open.into(),
+            rows,
+            close: close.into(),
+        }
+    }
+}
+
+fn append(node: MathNode, next: MathNode) -> MathNode {
+    match node {
+        MathNode::Row(mut children) => {
+            children.push(next);
+            MathNode::Row(children)
+        }
+        node => MathNode::Row(vec![node, next]),
+    }
+}
+
+fn char_node(c: char) -> MathNode {
+    match c {
+        '+' => MathNode::Binary("+".into()),
+        '-' => MathNode::Binary("−".into()),
+        '*' => MathNode::Binary("∗".into()),
+        '/' => MathNode::Symbol("/".into()),
+        '=' | '<' | '>' | ':' => MathNode::Relation(c.to_string()),
+        c => MathNode::Symbol(c.to_string()),
+    }
+}
+
+fn double_struck(c: char) -> char {
+    match c {
+        'C' => 'ℂ',
+        'H' => 'ℍ',
+        'N' => 'ℕ',
+        'P' => 'ℙ',
+        'Q' => 'ℚ',
+        'R' => 'ℝ',
+        'Z' => 'ℤ',
+        'A'..='Z' => char::from_u32('𝔸' as u32 + (c as u32 - 'A' as u32)).unwrap_or(c),
+        c => c,
+    }
+}
+
+const FUNCTIONS: &[&str] = &[
+    "sin", "cos", "tan", "cot", "sec", "csc", "arcsin", "arccos", "arctan", "sinh", "cosh", "tanh",
+    "log", "ln", "lg", "exp", "det", "dim", "ker", "deg", "arg", "gcd", "Pr", "mod", "bmod",
+];
+
+fn large_operator(command: &str) -> Option<&'static str> {
+    Some(match command {
+        "sum" => "∑",
+        "prod" => "∏",
+        "coprod" => "∐",
+        "int" => "∫",
+        "iint" => "∬",
+        "iiint" => "∭",
+        "oint" => "∮",
+        "bigcup" => "⋃",
+        "bigcap" => "⋂",
+        "bigoplus" => "⨁",
+        "bigotimes" => "⨂",
+        "lim" => "lim",
+        "limsup" => "lim sup",
+        "liminf" => "lim inf",
+        "max" => "max",
+        "min" => "min",
+        "sup" => "sup",
+        "inf" => "inf",
+        "argmax" => "arg max",
+        "argmin" => "arg min",
+        _ => return None,
+    })
+}
+
+fn symbol(command: &str) -> Option<MathNode> {
+    let identifier = |s: &str| Some(MathNode::Identifier(s.into()));
+    let symbol = |s: &str| Some(MathNode::Symbol(s.into()));
+    let binary = |s: &str| Some(MathNode::Binary(s.into()));
+    let relation = |s: &str| Some(MathNode::Relation(s.into()));
+    match command {
+        "alpha" => identifier("α"),
+        "beta" => identifier("β"),
+        "gamma" => identifier("γ"),
+        "delta" => identifier("δ"),
+        "epsilon" => identifier("ϵ"),
+        "varepsilon" => identifier("ε"),
+        "zeta" => identifier("ζ"),
+        "eta" => identifier("η"),
+        "theta" => identifier("θ"),
+        "vartheta" => identifier("ϑ"),
+        "iota" => identifier("ι"),
+        "kappa" => identifier("κ"),
+        "lambda" => identifier("λ"),
+        "mu" => identifier("μ"),
+        "nu" => identifier("ν"),
+        "xi" => identifier("ξ"),
+        "pi" => identifier("π"),
+        "varpi" => identifier("ϖ"),
+        "rho" => identifier("ρ"),
+        "varrho" => identifier("ϱ"),
+        "sigma" => identifier("σ"),
+        "varsigma" => identifier("ς"),
+        "tau" => identifier("τ"),
+        "upsilon" => identifier("υ"),
+        "phi" => identifier("ϕ"),
+        "varphi" => identifier("φ"),
+        "chi" => identifier("χ"),
+        "psi" => identifier("ψ"),
+        "omega" => identifier("ω"),
+        "Gamma" => symbol("Γ"),
+        "Delta" => symbol("Δ"),
+        "Theta" => symbol("Θ"),
+        "Lambda" => symbol("Λ"),
+        "Xi" => symbol("Ξ"),
+        "Pi" => symbol("Π"),
+        "Sigma" => symbol("Σ"),
+        "Upsilon" => symbol("Υ"),
+        "Phi" => symbol("Φ"),
+        "Psi" => symbol("Ψ"),
+        "Omega" => symbol("Ω"),
+        "ell" => identifier("ℓ"),
+        "hbar" => identifier("ℏ"),
+        "infty" => symbol("∞"),
+        "partial" => symbol("∂"),
+        "nabla" => symbol("∇"),
+        "forall" => symbol("∀"),
+        "exists" => symbol("∃"),
+        "nexists" => symbol("∄"),
+        "emptyset" | "varnothing" => symbol("∅"),
+        "neg" | "lnot" => symbol("¬"),
+        "angle" => symbol("∠"),
+        "prime" => symbol("′"),
+        "Re" => symbol("ℜ"),
+        "Im" => symbol("ℑ"),
+        "aleph" => symbol("ℵ"),
+        "dots" | "ldots" => symbol("…"),
+        "cdots" => symbol("⋯"),
+        "vdots" => symbol("⋮"),
+        "ddots" => symbol("⋱"),
+        "langle" => symbol("⟨"),
+        "rangle" => symbol("⟩"),
+        "lfloor" => symbol("⌊"),
+        "rfloor" => symbol("⌋"),
+        "lceil" => symbol("⌈"),
+        "rceil" => symbol("⌉"),
+        "lbrace" => symbol("{"),
+        "rbrace" => symbol("}"),
+        "vert" | "mid" => relation("|"),
+        "Vert" => symbol("‖"),
+        "pm" => binary("±"),
+        "mp" => binary("∓"),
+        "times" => binary("×"),
+        "div" => binary("÷"),
+        "cdot" => binary("·"),
+        "ast" => binary("∗"),
+        "star" => binary("⋆"),
+        "circ" => binary("∘"),
+        "bullet" => binary("∙"),
+        "oplus" => binary("⊕"),
+        "ominus" => binary("⊖"),
+        "otimes" => binary("⊗"),
+        "cup" => binary("∪"),
+        "cap" => binary("∩"),
+        "setminus" => binary("∖"),
+        "wedge" | "land" => binary("∧"),
+        "vee" | "lor" => binary("∨"),
+        "leq" | "le" => relation("≤"),
+        "geq" | "ge" => relation("≥"),
+        "neq" | "ne" => relation("≠"),
+        "ll" => relation("≪"),
+        "gg" => relation("≫"),
+        "approx" => relation("≈"),
+        "sim" => relation("∼"),
+        "simeq" => relation("≃"),
+        "cong" => relation("≅"),
+        "equiv" => relation("≡"),
+        "propto" => relation("∝"),
+        "in" => relation("∈"),
+        "notin" => relation("∉"),
+        "ni" => relation("∋"),
+        "subset" => relation("⊂"),
+        "supset" => relation("⊃"),
+        "subseteq" => relation("⊆"),
+        "supseteq" => relation("⊇"),
+        "perp" => relation("⊥"),
+        "parallel" => relation("∥"),
+        "to" | "rightarrow" => relation("→"),
+        "gets" | "leftarrow" => relation("←"),
+        "leftrightarrow" => relation("↔"),
+        "Rightarrow" | "implies" => relation("⇒"),
+        "Leftarrow" => relation("⇐"),
+        "Leftrightarrow" | "iff" => relation("⇔"),
+        "mapsto" => relation("↦"),
+        "uparrow" => relation("↑"),
+        "downarrow" => relation("↓"),
+        "longrightarrow" => relation("⟶"),
+        "longleftarrow" => relation("⟵"),
+        "Longrightarrow" => relation("⟹"),
+        "vdash" => relation("⊢"),
+        "models" => relation("⊨"),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_scripts_and_fractions() {
+        assert_eq!(
+            parse("x^2"),
+            MathNode::Scripts {
+                base: Box::new(MathNode::Identifier("x".into())),
+                subscript: None,
+                superscript: Some(Box::new(MathNode::Number("2".into()))),
+            }
+        );
+        assert_eq!(
+            parse(r"\frac{1}{n}"),
+            MathNode::Fraction {
+                numerator: Box::new(MathNode::Number("1".into())),
+                denominator: Box::new(MathNode::Identifier("n".into())),
+            }
+        );
+        assert_eq!(
+            parse(r"\sum_{i=1}^n"),
+            MathNode::LargeOperator {
+                symbol: "∑".into(),
+                lower: Some(Box::new(MathNode::Row(vec![
+                    MathNode::Identifier("i".into()),
+                    MathNode::Relation("=".into()),
+                    MathNode::Number("1".into()),
+                ]))),
+                upper: Some(Box::new(MathNode::Identifier("n".into()))),
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_matrix() {
+        assert_eq!(
+            parse(r"\begin{pmatrix} a & b \\ c & d \\ \end{pmatrix}"),
+            MathNode::Matrix {
+                open: "(".into(),
+                rows: vec![
+                    vec![
+                        MathNode::Identifier("a".into()),
+                        MathNode::Identifier("b".into())
+                    ],
+                    vec![
+                        MathNode::Identifier("c".into()),
+                        MathNode::Identifier("d".into())
+                    ],
+                ],
+                close: ")".into(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_to_unicode() {
+        assert_eq!(parse(r"e^{i\pi} + 1 = 0").to_unicode(), "e^(iπ) + 1 = 0");
+        assert_eq!(parse(r"x_1^2 + x_2^2").to_unicode(), "x₁² + x₂²");
+        assert_eq!(parse(r"\frac{a+b}{2}").to_unicode(), "(a + b)/2");
+        assert_eq!(parse(r"\sqrt{x} \leq \alpha").to_unicode(), "√x ≤ α");
+        assert_eq!(parse(r"-x").to_unicode(), "−x");
+        assert_eq!(parse(r"\mathbb{R}^n").to_unicode(), "ℝⁿ");
+    }
+
+    #[test]
+    fn test_unbalanced_input() {
+        assert_eq!(parse(r"\frac{1}{"), parse(r"\frac{1}{}"));
+        assert_eq!(parse("x}"), MathNode::Identifier("x".into()));
+        assert_eq!(parse(r"\unknown").to_unicode(), r"\unknown");
+    }
+}
@@ -0,0 +1,1321 @@
+//! A parser and layout engine for the subset of [Mermaid](https://mermaid.js.org) used in
+//! design documents: flowcharts and sequence diagrams.
+//!
+//! Diagrams are laid out with a fixed-width font, so that the size of each label can be
+//! determined from its length.
+
+use anyhow::{anyhow, bail, Result};
+use collections::HashMap;
+use gpui::{point, size, Bounds, Point, Size};
+
+const NODE_PADDING: Size<f32> = Size {
+    width: 16.,
+    height: 8.,
+};
+const NODE_GAP: f32 = 32.;
+const RANK_GAP: f32 = 48.;
+const PARTICIPANT_GAP: f32 = 40.;
+const MESSAGE_GAP: f32 = 12.;
+/// The length of an arrowhead at the end of a line.
+pub const ARROW_SIZE: f32 = 8.;
+const ORDERING_PASSES: usize = 4;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum MermaidDiagram {
+    Flowchart(Flowchart),
+    Sequence(SequenceDiagram),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FlowDirection {
+    TopToBottom,
+    BottomToTop,
+    LeftToRight,
+    RightToLeft,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Flowchart {
+    pub direction: FlowDirection,
+    pub nodes: Vec<FlowNode>,
+    pub edges: Vec<FlowEdge>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FlowNode {
+    pub id: String,
+    pub label: String,
+    pub shape: NodeShape,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NodeShape {
+    Rectangle,
+    Rounded,
+    Stadium,
+    Circle,
+    Diamond,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FlowEdge {
+    pub from: usize,
+    pub to: usize,
+    pub label: Option<String>,
+    pub stroke: Stroke,
+    pub arrow: ArrowHead,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stroke {
+    Solid,
+    Dotted,
+    Thick,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArrowHead {
+    None,
+    Filled,
+    Open,
+    Cross,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SequenceDiagram {
+    pub participants: Vec<Participant>,
+    pub events: Vec<SequenceEvent>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Participant {
+    pub id: String,
+    pub label: String,
+    pub is_actor: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SequenceEvent {
+    Message {
+        from: usize,
+        to: usize,
+        text: String,
+        stroke: Stroke,
+        arrow: ArrowHead,
+    },
+    Note {
+        placement: NotePlacement,
+        text: String,
+    },
+    /// The start of a block such as `loop` or `alt`.
+    BlockStart {
+        kind: String,
+        label: String,
+    },
+    /// A divider between sections of a block, such as `else`.
+    BlockSection {
+        kind: String,
+        label: String,
+    },
+    BlockEnd,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NotePlacement {
+    LeftOf(usize),
+    RightOf(usize),
+    Over(usize, usize),
+}
+
+/// The metrics of the font that a diagram's labels are rendered in.
+#[derive(Debug, Clone, Copy)]
+pub struct TextMetrics {
+    pub char_width: f32,
+    pub line_height: f32,
+}
+
+impl TextMetrics {
+    /// The size of a label, which may span several lines.
+    pub fn text_size(&self, text: &str) -> Size<f32> {
+        let lines = label_lines(text);
+        let max_chars = lines
+            .iter()
+            .map(|line| line.chars().count())
+            .max()
+            .unwrap_or(0);
+        size(
+            max_chars as f32 * self.char_width,
+            lines.len().max(1) as f32 * self.line_height,
+        )
+    }
+}
+
+/// A diagram that has been laid out, with coordinates relative to its top-left corner.
+#[derive(Debug, Default)]
+pub struct DiagramLayout {
+    pub size: Size<f32>,
+    pub boxes: Vec<LayoutBox>,
+    pub lines: Vec<LayoutLine>,
+    pub labels: Vec<LayoutLabel>,
+}
+
+#[derive(Debug)]
+pub struct LayoutBox {
+    pub bounds: Bounds<f32>,
+    pub shape: NodeShape,
+    pub style: BoxStyle,
+    pub label: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BoxStyle {
+    Node,
+    Note,
+    /// A frame around a block of a sequence diagram, whose label is drawn in its top-left corner.
+    Frame,
+}
+
+#[derive(Debug)]
+pub struct LayoutLine {
+    pub points: Vec<Point<f32>>,
+    pub stroke: Stroke,
+    pub arrow: ArrowHead,
+}
+
+#[derive(Debug)]
+pub struct LayoutLabel {
+    pub center: Point<f32>,
+    pub text: String,
+}
+
+/// Splits a label into lines, which are separated by `<br>` tags.
+pub fn label_lines(label: &str) -> Vec<&str> {
+    let mut lines = Vec::new();
+    let mut rest = label;
+    while let Some(ix) = rest.find("<br") {
+        lines.push(rest[..ix].trim());
+        rest = &rest[ix..];
+        rest = rest.find('>').map_or("", |end| &rest[end + 1..]);
+    }
+    lines.push(rest.trim());
+    lines
+}
+
+pub fn parse(source: &str) -> Result<MermaidDiagram> {
+    let mut statements = statements(source);
+    let header = statements
+        .next()
+        .ok_or_else(|| anyhow!("the diagram is empty"))?;
+    let mut header_words = header.split_whitespace();
+    match header_words.next() {
+        Some("graph" | "flowchart") => {
+            let direction = match header_words.next() {
+                None | Some("TD" | "TB") => FlowDirection::TopToBottom,
+                Some("BT") => FlowDirection::BottomToTop,
+                Some("LR") => FlowDirection::LeftToRight,
+                Some("RL") => FlowDirection::RightToLeft,
+                Some(direction) => bail!("unknown flowchart direction {direction:?}"),
+            };
+            parse_flowchart(direction, statements).map(MermaidDiagram::Flowchart)
+        }
+        Some("sequenceDiagram") => parse_sequence_diagram(statements).map(MermaidDiagram::Sequence),
+        Some(kind) => bail!("unsupported diagram type {kind:?}"),
+        None => bail!("the diagram is empty"),
+    }
+}
+
+/// Returns the non-empty statements of the diagram, without comments.
+fn statements(source: &str) -> impl Iterator<Item = &str> {
+    source
+        .lines()
+        .filter(|line| !line.trim_start().starts_with("%%"))
+        .flat_map(|line| line.split(';'))
+        .map(str::trim)
+        .filter(|statement| !statement.is_empty())
+}
+
+impl MermaidDiagram {
+    pub fn layout(&self, metrics: TextMetrics) -> DiagramLayout {
+        match self {
+            MermaidDiagram::Flowchart(flowchart) => flowchart.layout(metrics),
+            MermaidDiagram::Sequence(diagram) => diagram.layout(metrics),
+        }
+    }
+}
+
+fn parse_flowchart<'a>(
+    direction: FlowDirection,
+    statements: impl Iterator<Item = &'a str>,
+) -> Result<Flowchart> {
+    let mut flowchart = Flowchart {
+        direction,
+        nodes: Vec::new(),
+        edges: Vec::new(),
+    };
+    let mut node_ids = HashMap::default();
+
+    for statement in statements {
+        let first_word = statement.split_whitespace().next().unwrap_or_default();
+        if matches!(
+            first_word,
+            "subgraph"
+                | "end"
+                | "direction"
+                | "classDef"
+                | "class"
+                | "style"
+                | "linkStyle"
+                | "click"
+        ) {
+            continue;
+        }
+
+        let mut parser = StatementParser::new(statement);
+        let mut previous = parser.parse_node_group(&mut flowchart, &mut node_ids)?;
+        loop {
+            parser.skip_whitespace();
+            if parser.is_done() {
+                break;
+            }
+            let link = parser.parse_link()?;
+            let next = parser.parse_node_group(&mut flowchart, &mut node_ids)?;
+            for &from in &previous {
+                for &to in &next {
+                    flowchart.edges.push(FlowEdge {
+                        from,
+                        to,
+                        label: link.label.clone(),
+                        stroke: link.stroke,
+                        arrow: link.arrow,
+                    });
+                }
+            }
+            previous = next;
+        }
+    }
+
+    Ok(flowchart)
+}
+
+struct Link {
+    label: Option<String>,
+    stroke: Stroke,
+    arrow: ArrowHead,
+}
+
+struct StatementParser<'a> {
+    statement: &'a str,
+    offset: usize,
+}
+
+impl<'a> StatementParser<'a> {
+    fn new(statement: &'a str) -> Self {
+        Self {
+            statement,
+            offset: 0,
+        }
+    }
+
+    fn rest(&self) -> &'a str {
+        &self.statement[self.offset..]
+    }
+
+    fn is_done(&self) -> bool {
+        self.offset >= self.statement.len()
+    }
+
+    fn skip_whitespace(&mut self) {
+        let rest = self.rest();
+        self.offset += rest.len() - rest.trim_start().len();
+    }
+
+    /// Parses one or more nodes separated by `&`, returning their indices.
+    fn parse_node_group(
+        &mut self,
+        flowchart: &mut Flowchart,
+        node_ids: &mut HashMap<String, usize>,
+    ) -> Result<Vec<usize>> {
+        let mut nodes = vec![self.parse_node(flowchart, node_ids)?];
+        loop {
+            self.skip_whitespace();
+            if !self.rest().starts_with('&') {
+                return Ok(nodes);
+            }
+            self.offset += 1;
+            nodes.push(self.parse_node(flowchart, node_ids)?);
+        }
+    }
+
+    fn parse_node(
+        &mut self,
+        flowchart: &mut Flowchart,
+        node_ids: &mut HashMap<String, usize>,
+    ) -> Result<usize> {
+        self.skip_whitespace();
+        let rest = self.rest();
+        let id_len = rest
+            .find(|c: char| !(c.is_alphanumeric() || c == '_' || c == '-' || c == '.'))
+            .unwrap_or(rest.len());
+        // Dashes can be part of a node id, but not the start of a link.
+        let id_len = rest[..id_len]
+            .find("--")
+            .or_else(|| rest[..id_len].find(".-"))
+            .unwrap_or(id_len);
+        let id = rest[..id_len].trim_end_matches(['-', '.']);
+        if id.is_empty() {
+            bail!("expected a node in {:?}", self.statement);
+        }
+        self.offset += id.len();
+
+        const SHAPES: &[(&str, &str, NodeShape)] = &[
+            ("((", "))", NodeShape::Circle),
+            ("([", "])", NodeShape::Stadium),
+            ("{{", "}}", NodeShape::Diamond),
+            ("[", "]", NodeShape::Rectangle),
+            ("(", ")", NodeShape::Rounded),
+            ("{", "}", NodeShape::Diamond),
+        ];
+        let mut declared = None;
+        let rest = self.rest();
+        for (open, close, shape) in SHAPES {
+            if let Some(after_open) = rest.strip_prefix(open) {
+                let end = after_open
+                    .find(close)
+                    .ok_or_else(|| anyhow!("unclosed {open:?} in {:?}", self.statement))?;
+                let label = after_open[..end].trim().trim_matches('"');
+                declared = Some((label.to_string(), *shape));
+                self.offset += open.len() + end + close.len();
+                break;
+            }
+        }
+
+        // Skip a class assignment, such as `A:::important`.
+        if let Some(class) = self.rest().strip_prefix(":::") {
+            let len = class.find(char::is_whitespace).unwrap_or(class.len());
+            self.offset += 3 + len;
+        }
+
+        let ix = *node_ids.entry(id.to_string()).or_insert_with(|| {
+            flowchart.nodes.push(FlowNode {
+                id: id.to_string(),
+                label: id.to_string(),
+                shape: NodeShape::Rectangle,
+            });
+            flowchart.nodes.len() - 1
+        });
+        if let Some((label, shape)) = declared {
+            flowchart.nodes[ix].label = label;
+            flowchart.nodes[ix].shape = shape;
+        }
+        Ok(ix)
+    }
+
+    fn parse_link(&mut self) -> Result<Link> {
+        let operator = self.parse_link_operator();
+        if operator.is_empty() {
+            bail!("expected a link in {:?}", self.statement);
+        }
+
+        let (operator, label) = if matches!(operator, "--" | "==" | "-.") {
+            // A label between the start and the end of the link, as in `A -- text --> B`.
+            let rest = self.rest();
+            let end = ["-->", "---", "==>", "===", ".->", ".-", "--o", "--x"]
+                .iter()
+                .filter_map(|end| rest.find(end))
+                .min()
+                .ok_or_else(|| anyhow!("unterminated link in {:?}", self.statement))?;
+            let label = rest[..end].trim().trim_matches('"').to_string();
+            self.offset += end;
+            let end_operator = self.parse_link_operator();
+            (end_operator, Some(label))
+        } else {
+            (operator, None)
+        };
+
+        self.skip_whitespace();
+        let label = if let Some(after_pipe) = self.rest().strip_prefix('|') {
+            let end = after_pipe
+                .find('|')
+                .ok_or_else(|| anyhow!("unclosed link label in {:?}", self.statement))?;
+            let label = after_pipe[..end].trim().trim_matches('"').to_string();
+            self.offset += end + 2;
+            Some(label)
+        } else {
+            label
+        };
+
+        let stroke = if operator.contains('.') {
+            Stroke::Dotted
+        } else if operator.contains('=') {
+            Stroke::Thick
+        } else {
+            Stroke::Solid
+        };
+        let arrow = if operator.ends_with('>') {
+            ArrowHead::Filled
+        } else if operator.ends_with('o') {
+            ArrowHead::Open
+        } else if operator.ends_with('x') {
+            ArrowHead::Cross
+        } else {
+            ArrowHead::None
+        };
+
+        Ok(Link {
+            label: label.filter(|label| !label.is_empty()),
+            stroke,
+            arrow,
+        })
+    }
+
+    fn parse_link_operator(&mut self) -> &'a str {
+        self.skip_whitespace();
+        let rest = self.rest();
+        let mut len = rest
+            .find(|c: char| !matches!(c, '-' | '=' | '.' | '<' | '>'))
+            .unwrap_or(rest.len());
+        // Links can end in `o` or `x` instead of an arrow, as in `A --o B`.
+        let after = &rest[len..];
+        if len >= 2
+            && (after.starts_with('o') || after.starts_with('x'))
+            && after[1..].starts_with(char::is_whitespace)
+        {
+            len += 1;
+        }
+        self.offset += len;
+        &rest[..len]
+    }
+}
+
+fn parse_sequence_diagram<'a>(
+    statements: impl Iterator<Item = &'a str>,
+) -> Result<SequenceDiagram> {
+    let mut diagram = SequenceDiagram {
+        participants: Vec::new(),
+        events: Vec::new(),
+    };
+    let mut autonumber = None;
+
+    fn participant(diagram: &mut SequenceDiagram, id: &str) -> usize {
+        let id = id.trim();
+        if let Some(ix) = diagram.participants.iter().position(|p| p.id == id) {
+            return ix;
+        }
+        diagram.participants.push(Participant {
+            id: id.to_string(),
+            label: id.to_string(),
+            is_actor: false,
+        });
+        diagram.participants.len() - 1
+    }
+
+    for statement in statements {
+        let (first_word, rest) = statement
+            .split_once(char::is_whitespace)
+            .map_or((statement, ""), |(word, rest)| (word, rest.trim()));
+        match first_word {
+            "participant" | "actor" => {
+                let (id, label) = rest.split_once(" as ").unwrap_or((rest, rest));
+                let ix = participant(&mut diagram, id);
+                diagram.participants[ix].label = label.trim().to_string();
+                diagram.participants[ix].is_actor = first_word == "actor";
+            }
+            "autonumber" => autonumber = Some(1),
+            "title" | "activate" | "deactivate" | "box" => {}
+            "loop" | "alt" | "opt" | "par" | "critical" | "break" | "rect" => {
+                diagram.events.push(SequenceEvent::BlockStart {
+                    kind: first_word.to_string(),
+                    label: rest.to_string(),
+                })
+            }
+            "else" | "and" | "option" => diagram.events.push(SequenceEvent::BlockSection {
+                kind: first_word.to_string(),
+                label: rest.to_string(),
+            }),
+            "end" => diagram.events.push(SequenceEvent::BlockEnd),
+            "Note" | "note" => {
+                let (placement, text) = rest
+                    .split_once(':')
+                    .ok_or_else(|| anyhow!("expected a ':' in {statement:?}"))?;
+                let placement = placement.trim();
+                let placement = if let Some(id) = placement.strip_prefix("left of ") {
+                    NotePlacement::LeftOf(participant(&mut diagram, id))
+                } else if let Some(id) = placement.strip_prefix("right of ") {
+                    NotePlacement::RightOf(participant(&mut diagram, id))
+                } else if let Some(ids) = placement.strip_prefix("over ") {
+                    let (first, last) = ids.split_once(',').unwrap_or((ids, ids));
+                    let first = participant(&mut diagram, first);
+                    let last = participant(&mut diagram, last);
+                    NotePlacement::Over(first.min(last), first.max(last))
+                } else {
+                    bail!("unknown note placement in {statement:?}");
+                };
+                diagram.events.push(SequenceEvent::Note {
+                    placement,
+                    text: text.trim().to_string(),
+                });
+            }
+            _ => {
+                let (arrow_text, text) = statement
+                    .split_once(':')
+                    .ok_or_else(|| anyhow!("unknown statement {statement:?}"))?;
+                const ARROWS: &[(&str, Stroke, ArrowHead)] = &[
+                    ("-->>", Stroke::Dotted, ArrowHead::Filled),
+                    ("->>", Stroke::Solid, ArrowHead::Filled),
+                    ("--x", Stroke::Dotted, ArrowHead::Cross),
+                    ("-x", Stroke::Solid, ArrowHead::Cross),
+                    ("--)", Stroke::Dotted, ArrowHead::Open),
+                    ("-)", Stroke::Solid, ArrowHead::Open),
+                    ("-->", Stroke::Dotted, ArrowHead::None),
+                    ("->", Stroke::Solid, ArrowHead::None),
+                ];
+                let (from, to, stroke, arrow) = ARROWS
+                    .iter()
+                    .find_map(|(arrow, stroke, head)| {
+                        let (from, to) = arrow_text.split_once(arrow)?;
+                        Some((from, to, *stroke, *head))
+                    })
+                    .ok_or_else(|| anyhow!("unknown statement {statement:?}"))?;
+                // Activation markers, as in `A->>+B`, aren't shown.
+                let to = to.trim().trim_start_matches(['+', '-']);
+                let from = participant(&mut diagram, from);
+                let to = participant(&mut diagram, to);
+                let mut text = text.trim().to_string();
+                if let Some(number) = autonumber.as_mut() {
+                    text = format!("{number}. {text}");
+                    *number += 1;
+                }
+                diagram.events.push(SequenceEvent::Message {
+                    from,
+                    to,
+                    text,
+                    stroke,
+                    arrow,
+                });
+            }
+        }
+    }
+
+    if diagram.participants.is_empty() {
+        bail!("the sequence diagram has no participants");
+    }
+    Ok(diagram)
+}
+
+impl Flowchart {
+    fn layout(&self, metrics: TextMetrics) -> DiagramLayout {
+        let node_sizes = self
+            .nodes
+            .iter()
+            .map(|node| {
+                let text = metrics.text_size(&node.label);
+                let padded = size(
+                    text.width + 2. * NODE_PADDING.width,
+                    text.height + 2. * NODE_PADDING.height,
+                );
+                match node.shape {
+                    NodeShape::Diamond => size(padded.width * 1.5, padded.height * 1.5),
+                    NodeShape::Circle => {
+                        let diameter = padded.width.max(padded.height);
+                        size(diameter, diameter)
+                    }
+                    _ => padded,
+                }
+            })
+            .collect::<Vec<_>>();
+
+        let ranks = self.ranks();
+        let order = self.order_within_ranks(&ranks);
+
+        // Lay the diagram out from top to bottom, and then transform the coordinates
+        // for the other directions.
+        let is_horizontal = matches!(
+            self.direction,
+            FlowDirection::LeftToRight | FlowDirection::RightToLeft
+        );
+        let main_size = |size: Size<f32>| {
+            if is_horizontal {
+                size.width
+            } else {
+                size.height
+            }
+        };
+        let cross_size = |size: Size<f32>| {
+            if is_horizontal {
+                size.height
+            } else {
+                size.width
+            }
+        };
+
+        let has_labels = self.edges.iter().any(|edge| edge.label.is_some());
+        let rank_gap = if has_labels {
+            RANK_GAP + metrics.line_height
+        } else {
+            RANK_GAP
+        };
+
+        let rank_extents = order
+            .iter()
+            .map(|rank| {
+                let main = rank
+                    .iter()
+                    .map(|&ix| main_size(node_sizes[ix]))
+                    .fold(0., f32::max);
+                let cross = rank
+                    .iter()
+                    .map(|&ix| cross_size(node_sizes[ix]))
+                    .sum::<f32>()
+                    + NODE_GAP * rank.len().saturating_sub(1) as f32;
+                (main, cross)
+            })
+            .collect::<Vec<_>>();
+        let total_cross = rank_extents
+            .iter()
+            .map(|(_, cross)| *cross)
+            .fold(0., f32::max);
+
+        let mut centers = vec![point(0., 0.); self.nodes.len()];
+        let mut main_offset = 0.;
+        for (rank, (main, cross)) in order.iter().zip(&rank_extents) {
+            let mut cross_offset = (total_cross - cross) / 2.;
+            for &ix in rank {
+                let node_cross = cross_size(node_sizes[ix]);
+                // (cross, main) coordinates, which are swapped below for horizontal layouts.
+                centers[ix] = point(cross_offset + node_cross / 2., main_offset + main / 2.);
+                cross_offset += node_cross + NODE_GAP;
+            }
+            main_offset += main + rank_gap;
+        }
+        let total_main = (main_offset - rank_gap).max(0.);
+
+        for center in &mut centers {
+            let (cross, main) = (center.x, center.y);
+            *center = match self.direction {
+                FlowDirection::TopToBottom => point(cross, main),
+                FlowDirection::BottomToTop => point(cross, total_main - main),
+                FlowDirection::LeftToRight => point(main, cross),
+                FlowDirection::RightToLeft => point(total_main - main, cross),
+            };
+        }
+        let diagram_size = if is_horizontal {
+            size(total_main, total_cross)
+        } else {
+            size(total_cross, total_main)
+        };
+
+        let node_bounds = centers
+            .iter()
+            .zip(&node_sizes)
+            .map(|(center, size)| {
+                Bounds::new(
+                    point(center.x - size.width / 2., center.y - size.height / 2.),
+                    *size,
+                )
+            })
+            .collect::<Vec<_>>();
+
+        let mut layout = DiagramLayout {
+            size: diagram_size,
+            ..Default::default()
+        };
+        for (node, bounds) in self.nodes.iter().zip(&node_bounds) {
+            layout.boxes.push(LayoutBox {
+                bounds: *bounds,
+                shape: node.shape,
+                style: BoxStyle::Node,
+                label: node.label.clone(),
+            });
+        }
+        for edge in &self.edges {
+            if edge.from == edge.to {
+                continue;
+            }
+            let from_center = centers[edge.from];
+            let to_center = centers[edge.to];
+            let start = clip_to_node(
+                from_center,
+                to_center,
+                &node_bounds[edge.from],
+                self.nodes[edge.from].shape,
+            );
+            let end = clip_to_node(
+                to_center,
+                from_center,
+                &node_bounds[edge.to],
+                self.nodes[edge.to].shape,
+            );
+            if let Some(label) = &edge.label {
+                layout.labels.push(LayoutLabel {
+                    center: point((start.x + end.x) / 2., (start.y + end.y) / 2.),
+                    text: label.clone(),
+                });
+            }
+            layout.lines.push(LayoutLine {
+                points: vec![start, end],
+                stroke: edge.stroke,
+                arrow: edge.arrow,
+            });
+        }
+        layout
+    }
+
+    /// Assigns each node to a rank, such that edges point to higher ranks, ignoring
+    /// the edges that close cycles.
+    fn ranks(&self) -> Vec<usize> {
+        let mut outgoing = vec![Vec::new(); self.nodes.len()];
+        for edge in &self.edges {
+            if edge.from != edge.to {
+                outgoing[edge.from].push(edge.to);
+            }
+        }
+
+        // Find a topological order with a depth-first search, which skips the edges
+        // pointing back to a node that's still being visited.
+        #[derive(Clone, Copy, PartialEq)]
+        enum Visit {
+            New,
+            InProgress,
+            Done,
+        }
+        let mut visits = vec![Visit::New; self.nodes.len()];
+        let mut postorder = Vec::with_capacity(self.nodes.len());
+        let mut forward_edges = vec![Vec::new(); self.nodes.len()];
+        for root in 0..self.nodes.len() {
+            if visits[root] != Visit::New {
+                continue;
+            }
+            let mut stack = vec![(root, 0)];
+            visits[root] = Visit::InProgress;
+            while let Some((node, next_child)) = stack.pop() {
+                if let Some(&child) = outgoing[node].get(next_child) {
+                    stack.push((node, next_child + 1));
+                    match visits[child] {
+                        Visit::New => {
+                            forward_edges[node].push(child);
+                            visits[child] = Visit::InProgress;
+                            stack.push((child, 0));
+                        }
+                        Visit::Done => forward_edges[node].push(child),
+                        Visit::InProgress => {}
+                    }
+                } else {
+                    visits[node] = Visit::Done;
+                    postorder.push(node);
+                }
+            }
+        }
+
+        let mut ranks = vec![0; self.nodes.len()];
+        for &node in postorder.iter().rev() {
+            for &child in &forward_edges[node] {
+                ranks[child] = ranks[child].max(ranks[node] + 1);
+            }
+        }
+        ranks
+    }
+
+    /// Orders the nodes in each rank to reduce the number of crossing edges, by repeatedly
+    /// moving each node towards the average position of its neighbors.
+    fn order_within_ranks(&self, ranks: &[usize]) -> Vec<Vec<usize>> {
+        let rank_count = ranks.iter().copied().max().map_or(0, |max| max + 1);
+        let mut order = vec![Vec::new(); rank_count];
+        for (node, &rank) in ranks.iter().enumerate() {
+            order[rank].push(node);
+        }
+
+        let mut positions = vec![0.; self.nodes.len()];
+        let update_positions = |order: &Vec<Vec<usize>>, positions: &mut Vec<f32>| {
+            for rank in order {
+                for (position, &node) in rank.iter().enumerate() {
+                    positions[node] = position as f32;
+                }
+            }
+        };
+        update_positions(&order, &mut positions);
+
+        for pass in 0..ORDERING_PASSES {
+            let downward = pass % 2 == 0;
+            let rank_indices: Vec<usize> = if downward {
+                (1..rank_count).collect()
+            } else {
+                (0..rank_count.saturating_sub(1)).rev().collect()
+            };
+            for rank_ix in rank_indices {
+                let neighbor_rank = if downward { rank_ix - 1 } else { rank_ix + 1 };
+                let mut weights = order[rank_ix]
+                    .iter()
+                    .map(|&node| {
+                        let neighbors = self
+                            .edges
+                            .iter()
+                            .filter_map(|edge| {
+                                let neighbor = if edge.to == node {
+                                    edge.from
+                                } else if edge.from == node {
+                                    edge.to
+                                } else {
+                                    return None;
+                                };
+                                (ranks[neighbor] == neighbor_rank).then_some(positions[neighbor])
+                            })
+                            .collect::<Vec<_>>();
+                        let weight = if neighbors.is_empty() {
+                            positions[node]
+                        } else {
+                            neighbors.iter().sum::<f32>() / neighbors.len() as f32
+                        };
+                        (node, weight)
+                    })
+                    .collect::<Vec<_>>();
+                weights.sort_by(|a, b| a.1.total_cmp(&b.1));
+                order[rank_ix] = weights.into_iter().map(|(node, _)| node).collect();
+                update_positions(&order, &mut positions);
+            }
+        }
+        order
+    }
+}
+
+/// Returns the point where the line from the node's center towards `toward` leaves the node.
+fn clip_to_node(
+    center: Point<f32>,
+    toward: Point<f32>,
+    bounds: &Bounds<f32>,
+    shape: NodeShape,
+) -> Point<f32> {
+    let dx = toward.x - center.x;
+    let dy = toward.y - center.y;
+    if dx == 0. && dy == 0. {
+        return center;
+    }
+    let half_width = bounds.size.width / 2.;
+    let half_height = bounds.size.height / 2.;
+    let scale = match shape {
+        NodeShape::Diamond => 1. / (dx.abs() / half_width + dy.abs() / half_height),
+        NodeShape::Circle => half_width / (dx * dx + dy * dy).sqrt(),
+        _ => {
+            let scale_x = if dx == 0. {
+                f32::INFINITY
+            } else {
+                half_width / dx.abs()
+            };
+            let scale_y = if dy == 0. {
+                f32::INFINITY
+            } else {
+                half_height / dy.abs()
+            };
+            scale_x.min(scale_y)
+        }
+    };
+    point(center.x + dx * scale, center.y + dy * scale)
+}
+
+impl SequenceDiagram {
+    fn layout(&self, metrics: TextMetrics) -> DiagramLayout {
+        let participant_sizes = self
+            .participants
+            .iter()
+            .map(|participant| {
+                let text = metrics.text_size(&participant.label);
+                size(
+                    text.width + 2. * NODE_PADDING.width,
+                    text.height + 2. * NODE_PADDING.height,
+                )
+            })
+            .collect::<Vec<_>>();
+
+        // The minimum distance between the lifelines of each pair of adjacent participants.
+        let mut gaps = participant_sizes
+            .windows(2)
+            .map(|pair| (pair[0].width + pair[1].width) / 2. + PARTICIPANT_GAP)
+            .collect::<Vec<_>>();
+        let widen = |gaps: &mut Vec<f32>, first: usize, last: usize, required: f32| {
+            if first >= last || last > gaps.len() {
+                return;
+            }
+            let current = gaps[first..last].iter().sum::<f32>();
+            if current < required {
+                let extra = (required - current) / (last - first) as f32;
+                for gap in &mut gaps[first..last] {
+                    *gap += extra;
+                }
+            }
+        };
+        for event in &self.events {
+            match event {
+                SequenceEvent::Message { from, to, text, .. } => {
+                    let text_width = metrics.text_size(text).width + 2. * MESSAGE_GAP;
+                    if from == to {
+                        widen(&mut gaps, *from, *from + 1, text_width + 2. * MESSAGE_GAP);
+                    } else {
+                        widen(&mut gaps, *from.min(to), *from.max(to), text_width);
+                    }
+                }
+                SequenceEvent::Note {
+                    placement: NotePlacement::RightOf(ix),
+                    text,
+                } => {
+                    let width = metrics.text_size(text).width + 2. * NODE_PADDING.width;
+                    widen(&mut gaps, *ix, ix + 1, width + 2. * MESSAGE_GAP);
+                }
+                SequenceEvent::Note {
+                    placement: NotePlacement::LeftOf(ix),
+                    text,
+                } if *ix > 0 => {
+                    let width = metrics.text_size(text).width + 2. * NODE_PADDING.width;
+                    widen(&mut gaps, ix - 1, *ix, width + 2. * MESSAGE_GAP);
+                }
+                _ => {}
+            }
+        }
+
+        // Leave room for notes to the left of the first participant and to the right of the last.
+        let note_width = |placement_matches: &dyn Fn(&NotePlacement) -> bool| {
+            self.events
+                .iter()
+                .filter_map(|event| match event {
+                    SequenceEvent::Note { placement, text } if placement_matches(placement) => {
+                        Some(metrics.text_size(text).width + 2. * NODE_PADDING.width + MESSAGE_GAP)
+                    }
+                    _ => None,
+                })
+                .fold(0., f32::max)
+        };
+        let last = self.participants.len() - 1;
+        let left_margin = (participant_sizes[0].width / 2.).max(note_width(&|placement| {
+            *placement == NotePlacement::LeftOf(0)
+        }));
+        let right_margin = (participant_sizes[last].width / 2.).max(note_width(&|placement| {
+            *placement == NotePlacement::RightOf(last)
+        }));
+
+        let mut lifeline_x = Vec::with_capacity(self.participants.len());
+        let mut x = left_margin;
+        lifeline_x.push(x);
+        for gap in &gaps {
+            x += gap;
+            lifeline_x.push(x);
+        }
+        let width = x + right_margin;
+
+        let header_height = participant_sizes
+            .iter()
+            .map(|size| size.height)
+            .fold(0., f32::max);
+
+        let mut layout = DiagramLayout::default();
+        let mut y = header_height + MESSAGE_GAP;
+        let mut open_blocks: Vec<(f32, String)> = Vec::new();
+        for event in &self.events {
+            match event {
+                SequenceEvent::Message {
+                    from,
+                    to,
+                    text,
+                    stroke,
+                    arrow,
+                } => {
+                    let text_height = metrics.text_size(text).height;
+                    let label_y = y + text_height / 2.;
+                    y += text_height + MESSAGE_GAP / 2.;
+                    let from_x = lifeline_x[*from];
+                    if from == to {
+                        let loop_width = 2. * MESSAGE_GAP + ARROW_SIZE;
+                        layout.labels.push(LayoutLabel {
+                            center: point(
+                                from_x + loop_width + metrics.text_size(text).width / 2. + 4.,
+                                y + MESSAGE_GAP,
+                            ),
+                            text: text.clone(),
+                        });
+                        layout.lines.push(LayoutLine {
+                            points: vec![
+                                point(from_x, y),
+                                point(from_x + loop_width, y),
+                                point(from_x + loop_width, y + 2. * MESSAGE_GAP),
+                                point(from_x, y + 2. * MESSAGE_GAP),
+                            ],
+                            stroke: *stroke,
+                            arrow: *arrow,
+                        });
+                        y += 2. * MESSAGE_GAP;
+                    } else {
+                        let to_x = lifeline_x[*to];
+                        layout.labels.push(LayoutLabel {
+                            center: point((from_x + to_x) / 2., label_y),
+                            text: text.clone(),
+                        });
+                        layout.lines.push(LayoutLine {
+                            points: vec![point(from_x, y), point(to_x, y)],
+                            stroke: *stroke,
+                            arrow: *arrow,
+                        });
+                    }
+                    y += MESSAGE_GAP;
+                }
+                SequenceEvent::Note { placement, text } => {
+                    let text_size = metrics.text_size(text);
+                    let note_size = size(
+                        text_size.width + 2. * NODE_PADDING.width,
+                        text_size.height + 2. * NODE_PADDING.height,
+                    );
+                    let (left, note_width) = match placement {
+                        NotePlacement::LeftOf(ix) => (
+                            lifeline_x[*ix] - MESSAGE_GAP - note_size.width,
+                            note_size.width,
+                        ),
+                        NotePlacement::RightOf(ix) => {
+                            (lifeline_x[*ix] + MESSAGE_GAP, note_size.width)
+                        }
+                        NotePlacement::Over(first, last) => {
+                            let center = (lifeline_x[*first] + lifeline_x[*last]) / 2.;
+                            let width = note_size
+                                .width
+                                .max(lifeline_x[*last] - lifeline_x[*first] + 2. * MESSAGE_GAP);
+                            (center - width / 2., width)
+                        }
+                    };
+                    layout.boxes.push(LayoutBox {
+                        bounds: Bounds::new(point(left, y), size(note_width, note_size.height)),
+                        shape: NodeShape::Rectangle,
+                        style: BoxStyle::Note,
+                        label: text.clone(),
+                    });
+                    y += note_size.height + MESSAGE_GAP;
+                }
+                SequenceEvent::BlockStart { kind, label } => {
+                    open_blocks.push((y, format!("{kind} {label}").trim().to_string()));
+                    y += metrics.line_height + MESSAGE_GAP;
+                }
+                SequenceEvent::BlockSection { label, .. } => {
+                    layout.lines.push(LayoutLine {
+                        points: vec![point(0., y), point(width, y)],
+                        stroke: Stroke::Dotted,
+                        arrow: ArrowHead::None,
+                    });
+                    if !label.is_empty() {
+                        let text = format!("[{label}]");
+                        layout.labels.push(LayoutLabel {
+                            center: point(width / 2., y + metrics.line_height / 2. + 2.),
+                            text,
+                        });
+                    }
+                    y += metrics.line_height + MESSAGE_GAP;
+                }
+                SequenceEvent::BlockEnd => {
+                    if let Some((top, label)) = open_blocks.pop() {
+                        let inset = open_blocks.len() as f32 * 4.;
+                        layout.boxes.push(LayoutBox {
+                            bounds: Bounds::new(
+                                point(inset, top),
+                                size(width - 2. * inset, y - top),
+                            ),
+                            shape: NodeShape::Rectangle,
+                            style: BoxStyle::Frame,
+                            label,
+                        });
+                        y += MESSAGE_GAP;
+                    }
+                }
+            }
+        }
+        let footer_top = y;
+
+        for ((participant, participant_size), x) in self
+            .participants
+            .iter()
+            .zip(&participant_sizes)
+            .zip(&lifeline_x)
+        {
+            let shape = if participant.is_actor {
+                NodeShape::Stadium
+            } else {
+                NodeShape::Rectangle
+            };
+            for top in [0., footer_top] {
+                layout.boxes.push(LayoutBox {
+                    bounds: Bounds::new(
+                        point(x - participant_size.width / 2., top),
+                        size(participant_size.width, header_height),
+                    ),
+                    shape,
+                    style: BoxStyle::Node,
+                    label: participant.label.clone(),
+                });
+            }
+            layout.lines.insert(
+                0,
+                LayoutLine {
+                    points: vec![point(*x, header_height), point(*x, footer_top)],
+                    stroke: Stroke::Dotted,
+                    arrow: ArrowHead::None,
+                },
+            );
+        }
+
+        layout.size = size(width, footer_top + header_height);
+        layout
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    const METRICS: TextMetrics = TextMetrics {
+        char_width: 8.,
+        line_height: 16.,
+    };
+
+    fn flowchart(source: &str) -> Flowchart {
+        match parse(source).unwrap() {
+            MermaidDiagram::Flowchart(flowchart) => flowchart,
+            diagram => panic!("expected a flowchart, got {diagram:?}"),
+        }
+    }
+
+    #[test]
+    fn test_parse_flowchart() {
+        let flowchart = flowchart(
+            "graph LR\n\
+             %% A comment\n\
+             A[Start] --> B{Is it?}\n\
+             B -->|Yes| C(Done) & D\n\
+             B -- No --> A; C -.-> D",
+        );
+        assert_eq!(flowchart.direction, FlowDirection::LeftToRight);
+        assert_eq!(
+            flowchart
+                .nodes
+                .iter()
+                .map(|node| (node.id.as_str(), node.label.as_str(), node.shape))
+                .collect::<Vec<_>>(),
+            vec![
+                ("A", "Start", NodeShape::Rectangle),
+                ("B", "Is it?", NodeShape::Diamond),
+                ("C", "Done", NodeShape::Rounded),
+                ("D", "D", NodeShape::Rectangle),
+            ]
+        );
+        assert_eq!(
+            flowchart
+                .edges
+                .iter()
+                .map(|edge| (edge.from, edge.to, edge.label.as_deref(), edge.stroke))
+                .collect::<Vec<_>>(),
+            vec![
+                (0, 1, None, Stroke::Solid),
+                (1, 2, Some("Yes"), Stroke::Solid),
+                (1, 3, Some("Yes"), Stroke::Solid),
+                (1, 0, Some("No"), Stroke::Solid),
+                (2, 3, None, Stroke::Dotted),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_sequence_diagram() {
+        let MermaidDiagram::Sequence(diagram) = parse(
+            "sequenceDiagram\n\
+             participant A as Alice\n\
+             actor B\n\
+             autonumber\n\
+             A->>+B: Hello\n\
+             loop Every minute\n\
+             B-->>A: Hi\n\
+             end\n\
+             Note over A,B: Done",
+        )
+        .unwrap() else {
+            panic!("expected a sequence diagram");
+        };
+        assert_eq!(
+            diagram.participants,
+            vec![
+                Participant {
+                    id: "A".into(),
+                    label: "Alice".into(),
+                    is_actor: false,
+                },
+                Participant {
+                    id: "B".into(),
+                    label: "B".into(),
+                    is_actor: true,
+                },
+            ]
+        );
+        assert_eq!(
+            diagram.events,
+            vec![
+                SequenceEvent::Message {
+                    from: 0,
+                    to: 1,
+                    text: "1. Hello".into(),
+                    stroke: Stroke::Solid,
+                    arrow: ArrowHead::Filled,
+                },
+                SequenceEvent::BlockStart {
+                    kind: "loop".into(),
+                    label: "Every minute".into(),
+                },
+                SequenceEvent::Message {
+                    from: 1,
+                    to: 0,
+                    text: "2. Hi".into(),
+                    stroke: Stroke::Dotted,
+                    arrow: ArrowHead::Filled,
+                },
+                SequenceEvent::BlockEnd,
+                SequenceEvent::Note {
+                    placement: NotePlacement::Over(0, 1),
+                    text: "Done".into(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(parse("").is_err());
+        assert!(parse("pie title Pets").is_err());
+        assert!(parse("graph TD\nA[Start --> B").is_err());
+    }
+
+    #[test]
+    fn test_flowchart_layout() {
+        let layout =
+            flowchart("graph TD\nA --> B\nA --> C\nB --> D\nC --> D\nD --> A").layout(METRICS);
+        let centers = layout
+            .boxes
+            .iter()
+            .map(|node| node.bounds.center())
+            .collect::<Vec<_>>();
+
+        // Each node is below its parents, ignoring the edge that closes the cycle.
+        assert!(centers[0].y < centers[1].y);
+        assert_eq!(centers[1].y, centers[2].y);
+        assert!(centers[1].y < centers[3].y);
+        assert!(centers[1].x < centers[2].x);
+
+        // Edges start and end at the boundaries of the nodes.
+        let first_edge = &layout.lines[0];
+        assert_eq!(first_edge.points[0].y, layout.boxes[0].bounds.bottom());
+        assert_eq!(first_edge.points[1].y, layout.boxes[1].bounds.top());
+
+        for node in &layout.boxes {
+            assert!(node.bounds.left() >= 0. && node.bounds.right() <= layout.size.width);
+            assert!(node.bounds.top() >= 0. && node.bounds.bottom() <= layout.size.height);
+        }
+    }
+
+    #[test]
+    fn test_label_lines() {
+        assert_eq!(
+            label_lines("one<br>two<br/>three"),
+            vec!["one", "two", "three"]
+        );
+        assert_eq!(label_lines("one"), vec!["one"]);
+    }
+}
@@ -1976,7 +1976,7 @@ impl OutlinePanel {
                 let color =
                     entry_git_aware_label_color(entry.git_status, entry.is_ignored, is_active);
                 let icon = if settings.folder_icons {
-                    FileIcons::get_folder_icon(is_expanded, cx)
+                    FileIcons::get_folder_icon(is_expanded, &entry.path, cx)
                 } else {
                     FileIcons::get_chevron_icon(is_expanded, cx)
                 }
@@ -2067,7 +2067,10 @@ impl OutlinePanel {
             let git_status = dir_entries.first().and_then(|entry| entry.git_status);
             let color = entry_git_aware_label_color(git_status, is_ignored, is_active);
             let icon = if settings.folder_icons {
-                FileIcons::get_folder_icon(is_expanded, cx)
+                let path = dir_entries
+                    .last()
+                    .map_or(Path::new(""), |entry| entry.path.as_ref());
+                FileIcons::get_folder_icon(is_expanded, path, cx)
             } else {
                 FileIcons::get_chevron_icon(is_expanded, cx)
             }
@@ -299,6 +299,11 @@ pub fn local_tasks_file_relative_path() -> &'static Path {
     Path::new(".zed/tasks.json")
 }
 
+/// Returns the relative path to a `layouts.json` file within a project.
+pub fn local_layouts_file_relative_path() -> &'static Path {
+    Path::new(".zed/layouts.json")
+}
+
 /// Returns the relative path to a `.vscode/tasks.json` file within a project.
 pub fn local_vscode_tasks_file_relative_path() -> &'static Path {
     Path::new(".vscode/tasks.json")
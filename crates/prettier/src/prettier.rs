@@ -8,7 +8,7 @@ use node_runtime::NodeRuntime;
 use paths::default_prettier_dir;
 use serde::{Deserialize, Serialize};
 use std::{
-    ops::ControlFlow,
+    ops::{ControlFlow, Range},
     path::{Path, PathBuf},
     sync::Arc,
 };
@@ -235,6 +235,7 @@ impl Prettier {
         _: LanguageServerId,
         prettier_dir: PathBuf,
         _: NodeRuntime,
+        _: Option<PathBuf>,
         _: AsyncAppContext,
     ) -> anyhow::Result<Self> {
         Ok(Self::Test(TestPrettier {
@@ -243,11 +244,13 @@ impl Prettier {
         }))
     }
 
+    /// Starts the prettier server with the given Node.js binary, or else the one of `node`.
     #[cfg(not(any(test, feature = "test-support")))]
     pub async fn start(
         server_id: LanguageServerId,
         prettier_dir: PathBuf,
         node: NodeRuntime,
+        node_binary: Option<PathBuf>,
         cx: AsyncAppContext,
     ) -> anyhow::Result<Self> {
         use lsp::{LanguageServerBinary, LanguageServerName};
@@ -263,9 +266,14 @@ impl Prettier {
             "no prettier server package found at {prettier_server:?}"
         );
 
-        let node_path = executor
-            .spawn(async move { node.binary_path().await })
-            .await?;
+        let node_path = match node_binary {
+            Some(node_binary) => node_binary,
+            None => {
+                executor
+                    .spawn(async move { node.binary_path().await })
+                    .await?
+            }
+        };
         let server_name = LanguageServerName("prettier".into());
         let server_binary = LanguageServerBinary {
             path: node_path,
@@ -293,11 +301,13 @@ impl Prettier {
         }))
     }
 
+    /// Formats the buffer, or only the part of it within the given byte `range`, if one is given.
     pub async fn format(
         &self,
         buffer: &Model<Buffer>,
         buffer_path: Option<PathBuf>,
         ignore_dir: Option<PathBuf>,
+        range: Option<Range<usize>>,
         cx: &mut AsyncAppContext,
     ) -> anyhow::Result<Diff> {
         match self {
@@ -425,6 +435,12 @@ impl Prettier {
                             ignore_path,
                         );
 
+                        // Prettier expects the range in UTF-16 code units, as JavaScript strings are indexed.
+                        let range = range.map(|range| {
+                            buffer.offset_to_offset_utf16(range.start).0
+                                ..buffer.offset_to_offset_utf16(range.end).0
+                        });
+
                         anyhow::Ok(FormatParams {
                             text: buffer.text(),
                             options: FormatOptions {
@@ -433,6 +449,8 @@ impl Prettier {
                                 path: buffer_path,
                                 prettier_options,
                                 ignore_path,
+                                range_start: range.as_ref().map(|range| range.start),
+                                range_end: range.map(|range| range.end),
                             },
                         })
                     })?
@@ -554,6 +572,10 @@ struct FormatOptions {
     path: Option<PathBuf>,
     prettier_options: Option<HashMap<String, serde_json::Value>>,
     ignore_path: Option<PathBuf>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    range_start: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    range_end: Option<usize>,
 }
 
 #[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
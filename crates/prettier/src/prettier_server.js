@@ -230,6 +230,13 @@ async function handleMessage(message, prettier) {
       parser: params.options.parser,
       filepath: params.options.filepath,
     };
+    if (
+      typeof params.options.rangeStart === "number" &&
+      typeof params.options.rangeEnd === "number"
+    ) {
+      options.rangeStart = params.options.rangeStart;
+      options.rangeEnd = params.options.rangeEnd;
+    }
     process.stderr.write(
       `Resolved config: ${JSON.stringify(resolvedConfig)}, will format file '${
         params.options.filepath || ""
@@ -792,10 +792,18 @@ impl LocalBufferStore {
                 let loaded = load_file.await?;
                 let text_buffer = cx
                     .background_executor()
-                    .spawn(async move { text::Buffer::new(0, buffer_id, loaded.text) })
+                    .spawn(async move {
+                        text::Buffer::new_normalized(0, buffer_id, loaded.line_ending, loaded.text)
+                    })
                     .await;
                 cx.insert_model(reservation, |_| {
-                    Buffer::build(text_buffer, Some(loaded.file), Capability::ReadWrite)
+                    let buffer =
+                        Buffer::build(text_buffer, Some(loaded.file), Capability::ReadWrite);
+                    if loaded.is_large {
+                        buffer.with_large_file_mode()
+                    } else {
+                        buffer
+                    }
                 })
             })
         });
@@ -1048,6 +1056,20 @@ impl BufferStore {
             .spawn(async move { task.await.map_err(|e| anyhow!("{e}")) })
     }
 
+    /// Loads the text of the given buffer's file as it is staged in the git index.
+    pub fn load_staged_text(
+        &self,
+        buffer: &Model<Buffer>,
+        cx: &AppContext,
+    ) -> Task<Result<Option<String>>> {
+        match &self.state {
+            BufferStoreState::Local(this) => this.load_staged_text(buffer, cx),
+            BufferStoreState::Remote(this) => {
+                this.load_staged_text(buffer.read(cx).remote_id(), cx)
+            }
+        }
+    }
+
     #[cfg(any(test, feature = "test-support"))]
     pub fn set_change_set(&mut self, buffer_id: BufferId, change_set: Model<BufferChangeSet>) {
         self.loading_change_sets
@@ -602,24 +602,32 @@ impl LocalImageStore {
     }
 }
 
-fn create_gpui_image(content: Vec<u8>) -> anyhow::Result<Arc<gpui::Image>> {
-    let format = image::guess_format(&content)?;
+/// Creates an image from the given file contents, detecting its format from the contents.
+pub fn create_gpui_image(content: Vec<u8>) -> anyhow::Result<Arc<gpui::Image>> {
+    let format = match image::guess_format(&content) {
+        Ok(image::ImageFormat::Png) => gpui::ImageFormat::Png,
+        Ok(image::ImageFormat::Jpeg) => gpui::ImageFormat::Jpeg,
+        Ok(image::ImageFormat::WebP) => gpui::ImageFormat::Webp,
+        Ok(image::ImageFormat::Gif) => gpui::ImageFormat::Gif,
+        Ok(image::ImageFormat::Bmp) => gpui::ImageFormat::Bmp,
+        Ok(image::ImageFormat::Tiff) => gpui::ImageFormat::Tiff,
+        _ if is_svg(&content) => gpui::ImageFormat::Svg,
+        _ => Err(anyhow::anyhow!("Image format not supported"))?,
+    };
 
     Ok(Arc::new(gpui::Image {
         id: hash(&content),
-        format: match format {
-            image::ImageFormat::Png => gpui::ImageFormat::Png,
-            image::ImageFormat::Jpeg => gpui::ImageFormat::Jpeg,
-            image::ImageFormat::WebP => gpui::ImageFormat::Webp,
-            image::ImageFormat::Gif => gpui::ImageFormat::Gif,
-            image::ImageFormat::Bmp => gpui::ImageFormat::Bmp,
-            image::ImageFormat::Tiff => gpui::ImageFormat::Tiff,
-            _ => Err(anyhow::anyhow!("Image format not supported"))?,
-        },
+        format,
         bytes: content,
     }))
 }
 
+fn is_svg(content: &[u8]) -> bool {
+    let text = String::from_utf8_lossy(&content[..content.len().min(1024)]);
+    let text = text.trim_start();
+    text.starts_with("<svg") || (text.starts_with("<?xml") && text.contains("<svg"))
+}
+
 impl ImageStoreImpl for Model<RemoteImageStore> {
     fn open_image(
         &self,
@@ -7,7 +7,7 @@ use crate::{
     prettier_store::{self, PrettierStore, PrettierStoreEvent},
     project_settings::{LspSettings, ProjectSettings},
     relativize_path, resolve_path,
-    toolchain_store::{EmptyToolchainStore, ToolchainStoreEvent},
+    toolchain_store::{EmptyToolchainStore, ToolchainStoreEvent, NODE_TOOLCHAIN_LANGUAGES},
     worktree_store::{WorktreeStore, WorktreeStoreEvent},
     yarn::YarnPathStore,
     CodeAction, Completion, CoreCompletion, Hover, InlayHint, ProjectItem as _, ProjectPath,
@@ -23,6 +23,7 @@ use futures::{
     stream::FuturesUnordered,
     AsyncWriteExt, Future, FutureExt, StreamExt,
 };
+use git::diff::BufferDiff;
 use globset::{Glob, GlobBuilder, GlobMatcher, GlobSet, GlobSetBuilder};
 use gpui::{
     AppContext, AsyncAppContext, Context, Entity, EventEmitter, Model, ModelContext, PromptLevel,
@@ -39,8 +40,8 @@ use language::{
     range_from_lsp, Bias, Buffer, BufferSnapshot, CachedLspAdapter, CodeLabel, Diagnostic,
     DiagnosticEntry, DiagnosticSet, Diff, Documentation, File as _, Language, LanguageName,
     LanguageRegistry, LanguageServerBinaryStatus, LanguageToolchainStore, LocalFile, LspAdapter,
-    LspAdapterDelegate, Patch, PointUtf16, TextBufferSnapshot, ToOffset, ToPointUtf16, Transaction,
-    Unclipped,
+    LspAdapterDelegate, OffsetRangeExt, Patch, PointUtf16, TextBufferSnapshot, ToOffset, ToPoint,
+    ToPointUtf16, Transaction, Unclipped,
 };
 use lsp::{
     notification::DidRenameFiles, CodeActionKind, CompletionContext, DiagnosticSeverity,
@@ -77,7 +78,7 @@ use std::{
     sync::Arc,
     time::{Duration, Instant},
 };
-use text::{Anchor, BufferId, LineEnding, Point, Selection};
+use text::{Anchor, BufferId, LineEnding, Point, Selection, SelectionGoal};
 use util::{
     debug_panic, defer, maybe, merge_json_value_into, post_inc, ResultExt, TryFutureExt as _,
 };
@@ -478,7 +479,7 @@ impl LocalLspStore {
                 .clone()
                 .get_language_server_command(
                     delegate.clone(),
-                    toolchains,
+                    toolchains.clone(),
                     lsp_binary_options,
                     &mut cx,
                 )
@@ -490,6 +491,10 @@ impl LocalLspStore {
             if let Some(arguments) = settings.and_then(|b| b.arguments) {
                 binary.arguments = arguments.into_iter().map(Into::into).collect();
             }
+            let mut binary = adapter
+                .adapter
+                .binary_for_toolchain(binary, delegate.as_ref(), toolchains, &mut cx)
+                .await;
 
             let mut shell_env = delegate.shell_env().await;
             shell_env.extend(binary.env.unwrap_or_default());
@@ -1137,14 +1142,34 @@ impl LocalLspStore {
             let remove_trailing_whitespace = settings.remove_trailing_whitespace_on_save;
             let ensure_final_newline = settings.ensure_final_newline_on_save;
 
+            // When only modified lines are formatted on save, find the ranges that differ from
+            // the git index. Buffers without a staged version are formatted entirely.
+            let modified_ranges = if trigger == FormatTrigger::Save
+                && settings.format_on_save == FormatOnSave::Modifications
+            {
+                Self::modified_ranges(&lsp_store, &buffer.handle, &mut cx).await?
+            } else {
+                None
+            };
+
             // First, format buffer's whitespace according to the settings.
             let trailing_whitespace_diff = if remove_trailing_whitespace {
-                Some(
-                    buffer
-                        .handle
-                        .update(&mut cx, |b, cx| b.remove_trailing_whitespace(cx))?
-                        .await,
-                )
+                let modified_offset_ranges = buffer.handle.read_with(&cx, |buffer, _| {
+                    modified_ranges.as_ref().map(|ranges| {
+                        ranges
+                            .iter()
+                            .map(|range| range.to_offset(buffer))
+                            .collect::<Vec<_>>()
+                    })
+                })?;
+                let mut diff = buffer
+                    .handle
+                    .update(&mut cx, |b, cx| b.remove_trailing_whitespace(cx))?
+                    .await;
+                if let Some(ranges) = modified_offset_ranges {
+                    retain_edits_intersecting(&mut diff.edits, &ranges);
+                }
+                Some(diff)
             } else {
                 None
             };
@@ -1265,6 +1290,72 @@ impl LocalLspStore {
                                     }
                                 }
                             }
+                            FormatOnSave::Modifications => {
+                                let formatters = match &settings.formatter {
+                                    SelectedFormatter::Auto => {
+                                        if prettier_settings.allowed {
+                                            vec![Formatter::Prettier]
+                                        } else {
+                                            vec![Formatter::LanguageServer { name: None }]
+                                        }
+                                    }
+                                    SelectedFormatter::List(formatters) => {
+                                        formatters.as_ref().to_vec()
+                                    }
+                                };
+                                let target = match &modified_ranges {
+                                    Some(ranges) => buffer.handle.read_with(&cx, |buffer, _| {
+                                        FormatTarget::Ranges(
+                                            ranges
+                                                .iter()
+                                                .map(|range| Selection {
+                                                    id: 0,
+                                                    start: range.start.to_point(buffer),
+                                                    end: range.end.to_point(buffer),
+                                                    reversed: false,
+                                                    goal: SelectionGoal::None,
+                                                })
+                                                .collect(),
+                                        )
+                                    })?,
+                                    None => FormatTarget::Buffer,
+                                };
+
+                                for formatter in &formatters {
+                                    if let FormatTarget::Ranges(selections) = &target {
+                                        if selections.is_empty() {
+                                            break;
+                                        }
+                                        if !Self::formatter_supports_ranges(
+                                            formatter,
+                                            primary_language_server.as_ref(),
+                                            &adapters_and_servers,
+                                        ) {
+                                            log::info!(
+                                                "skipping formatter {formatter:?} on save, as it cannot format only the modified lines"
+                                            );
+                                            continue;
+                                        }
+                                    }
+
+                                    let diff = Self::perform_format(
+                                        formatter,
+                                        &target,
+                                        server_and_buffer,
+                                        lsp_store.clone(),
+                                        buffer,
+                                        &settings,
+                                        &adapters_and_servers,
+                                        push_to_history,
+                                        &mut project_transaction,
+                                        &mut cx,
+                                    )
+                                    .await?;
+                                    if let Some(op) = diff {
+                                        format_operations.push(op);
+                                    }
+                                }
+                            }
                             FormatOnSave::List(formatters) => {
                                 for formatter in formatters.as_ref() {
                                     let diff = Self::perform_format(
@@ -1406,6 +1497,72 @@ impl LocalLspStore {
         Ok(project_transaction)
     }
 
+    /// Returns the ranges of the buffer that differ from its staged text in the git index,
+    /// or `None` if there is no staged version of the buffer's file.
+    async fn modified_ranges(
+        lsp_store: &WeakModel<LspStore>,
+        buffer: &Model<Buffer>,
+        cx: &mut AsyncAppContext,
+    ) -> Result<Option<Vec<Range<Anchor>>>> {
+        let staged_text = lsp_store
+            .update(cx, |lsp_store, cx| {
+                lsp_store.buffer_store.read(cx).load_staged_text(buffer, cx)
+            })?
+            .await
+            .ok()
+            .flatten();
+        let Some(mut staged_text) = staged_text else {
+            return Ok(None);
+        };
+        LineEnding::normalize(&mut staged_text);
+
+        let snapshot = buffer.read_with(cx, |buffer, _| buffer.text_snapshot())?;
+        let ranges = cx
+            .background_executor()
+            .spawn(async move {
+                let diff = BufferDiff::build(&staged_text, &snapshot).await;
+                diff.hunks_intersecting_range(Anchor::MIN..Anchor::MAX, &snapshot)
+                    .filter(|hunk| !hunk.row_range.is_empty())
+                    .map(|hunk| {
+                        let last_row = hunk.row_range.end - 1;
+                        let start = Point::new(hunk.row_range.start, 0);
+                        let end = Point::new(last_row, snapshot.line_len(last_row));
+                        snapshot.anchor_before(start)..snapshot.anchor_after(end)
+                    })
+                    .collect()
+            })
+            .await;
+        Ok(Some(ranges))
+    }
+
+    fn formatter_supports_ranges(
+        formatter: &Formatter,
+        primary_language_server: Option<&Arc<LanguageServer>>,
+        adapters_and_servers: &[(Arc<CachedLspAdapter>, Arc<LanguageServer>)],
+    ) -> bool {
+        match formatter {
+            Formatter::Prettier => true,
+            Formatter::LanguageServer { name } => {
+                let language_server = match name {
+                    Some(name) => adapters_and_servers
+                        .iter()
+                        .find_map(|(adapter, server)| {
+                            adapter.name.0.as_ref().eq(name.as_str()).then_some(server)
+                        })
+                        .or(primary_language_server),
+                    None => primary_language_server,
+                };
+                language_server.map_or(false, |server| {
+                    matches!(
+                        server.capabilities().document_range_formatting_provider,
+                        Some(ref provider) if *provider != OneOf::Left(false)
+                    )
+                })
+            }
+            Formatter::External { .. } | Formatter::CodeActions(_) => false,
+        }
+    }
+
     #[allow(clippy::too_many_arguments)]
     async fn perform_format(
         formatter: &Formatter,
@@ -1468,9 +1625,42 @@ impl LocalLspStore {
                 let prettier = lsp_store.update(cx, |lsp_store, _cx| {
                     lsp_store.prettier_store().unwrap().downgrade()
                 })?;
-                prettier_store::format_with_prettier(&prettier, &buffer.handle, cx)
-                    .await
-                    .transpose()?
+                // Prettier formats a single range, so format the range spanning all of the
+                // selections and keep only the edits that intersect one of them.
+                let ranges = match format_target.as_selections() {
+                    Some(selections) => Some(buffer.handle.read_with(cx, |buffer, _| {
+                        selections
+                            .iter()
+                            .map(|selection| {
+                                let start = buffer.clip_point(selection.start, Bias::Left);
+                                let end = buffer.clip_point(selection.end, Bias::Right);
+                                start.to_offset(buffer)..end.to_offset(buffer)
+                            })
+                            .sorted_by_key(|range| range.start)
+                            .collect::<Vec<_>>()
+                    })?),
+                    None => None,
+                };
+                let spanning_range = ranges.as_ref().and_then(|ranges| {
+                    let start = ranges.iter().map(|range| range.start).min()?;
+                    let end = ranges.iter().map(|range| range.end).max()?;
+                    Some(start..end)
+                });
+                let operation = prettier_store::format_with_prettier(
+                    &prettier,
+                    &buffer.handle,
+                    spanning_range,
+                    cx,
+                )
+                .await
+                .transpose()?;
+                match (operation, ranges) {
+                    (Some(FormatOperation::Prettier(mut diff)), Some(ranges)) => {
+                        retain_edits_intersecting(&mut diff.edits, &ranges);
+                        Some(FormatOperation::Prettier(diff))
+                    }
+                    (operation, _) => operation,
+                }
             }
             Formatter::External { command, arguments } => {
                 Self::format_via_external_command(buffer, command, arguments.as_deref(), cx)
@@ -1876,6 +2066,9 @@ impl LocalLspStore {
     ) {
         let buffer = buffer_handle.read(cx);
         let buffer_id = buffer.remote_id();
+        if buffer.is_large_file() {
+            return;
+        }
 
         let Some(file) = File::from_dyn(buffer.file()) else {
             return;
@@ -3130,15 +3323,57 @@ impl LspStore {
         &mut self,
         _: Model<ToolchainStore>,
         event: &ToolchainStoreEvent,
-        _: &mut ModelContext<Self>,
+        cx: &mut ModelContext<Self>,
     ) {
         match event {
-            ToolchainStoreEvent::ToolchainActivated { .. } => {
-                self.request_workspace_config_refresh()
+            ToolchainStoreEvent::ToolchainActivated {
+                worktree_id,
+                language_name,
+            } => {
+                self.request_workspace_config_refresh();
+                if NODE_TOOLCHAIN_LANGUAGES.contains(&language_name.0.as_ref()) {
+                    self.restart_for_node_toolchain(*worktree_id, cx);
+                }
             }
         }
     }
 
+    /// Language servers and prettier instances run on Node.js pick its version when they start,
+    /// so restart the ones of the worktree when another one is selected.
+    ///
+    /// The Node.js version selected for one of the [`NODE_TOOLCHAIN_LANGUAGES`] is also used for
+    /// the others that have none selected, so the running servers of all of them are restarted.
+    fn restart_for_node_toolchain(&mut self, worktree_id: WorktreeId, cx: &mut ModelContext<Self>) {
+        let Some(local) = self.as_local() else {
+            return;
+        };
+        local.prettier_store.update(cx, |prettier_store, cx| {
+            prettier_store.stop_project_prettiers(worktree_id, cx)
+        });
+
+        let languages = NODE_TOOLCHAIN_LANGUAGES
+            .into_iter()
+            .map(LanguageName::new)
+            .filter(|language| {
+                self.languages.lsp_adapters(language).iter().any(|adapter| {
+                    local
+                        .language_server_ids
+                        .contains_key(&(worktree_id, adapter.name.clone()))
+                })
+            })
+            .collect::<Vec<_>>();
+        if languages.is_empty() {
+            return;
+        }
+        if let Some(worktree) = self
+            .worktree_store
+            .read(cx)
+            .worktree_for_id(worktree_id, cx)
+        {
+            self.restart_local_language_servers(worktree, languages, cx);
+        }
+    }
+
     fn request_workspace_config_refresh(&mut self) {
         *self._maintain_workspace_config.1.borrow_mut() = ();
     }
@@ -3162,6 +3397,19 @@ impl LspStore {
                 self.on_buffer_saved(buffer, cx);
             }
 
+            language::BufferEvent::LargeFileModeChanged => {
+                self.detect_language_for_buffer(&buffer, cx);
+                if let Some(local) = self.as_local_mut() {
+                    local.initialize_buffer(&buffer, cx);
+                    if local
+                        .registered_buffers
+                        .contains_key(&buffer.read(cx).remote_id())
+                    {
+                        local.register_buffer_with_language_servers(&buffer, cx);
+                    }
+                }
+            }
+
             _ => {}
         }
     }
@@ -3325,6 +3573,9 @@ impl LspStore {
     ) -> Option<language::AvailableLanguage> {
         // If the buffer has a language, set it and start the language server if we haven't already.
         let buffer = buffer_handle.read(cx);
+        if buffer.is_large_file() {
+            return None;
+        }
         let file = buffer.file()?;
 
         let content = buffer.as_rope();
@@ -3647,7 +3898,7 @@ impl LspStore {
 
         // Restart all language servers with changed initialization options.
         for (worktree, language) in language_servers_to_restart {
-            self.restart_local_language_servers(worktree, language, cx);
+            self.restart_local_language_servers(worktree, vec![language], cx);
         }
 
         cx.notify();
@@ -7137,7 +7388,7 @@ impl LspStore {
                 .collect();
 
             for (worktree, language) in language_server_lookup_info {
-                self.restart_local_language_servers(worktree, language, cx);
+                self.restart_local_language_servers(worktree, vec![language], cx);
             }
         }
     }
@@ -7145,20 +7396,26 @@ impl LspStore {
     fn restart_local_language_servers(
         &mut self,
         worktree: Model<Worktree>,
-        language: LanguageName,
+        languages: Vec<LanguageName>,
         cx: &mut ModelContext<Self>,
     ) {
         let worktree_id = worktree.read(cx).id();
 
-        let stop_tasks = self
-            .languages
-            .clone()
-            .lsp_adapters(&language)
-            .iter()
-            .map(|adapter| {
+        // A language server can be used for several of the languages, so stop each one once.
+        let mut adapter_names = Vec::<LanguageServerName>::new();
+        for language in &languages {
+            for adapter in self.languages.lsp_adapters(language) {
+                if !adapter_names.contains(&adapter.name) {
+                    adapter_names.push(adapter.name.clone());
+                }
+            }
+        }
+        let stop_tasks = adapter_names
+            .into_iter()
+            .map(|adapter_name| {
                 let stop_task =
-                    self.stop_local_language_server(worktree_id, adapter.name.clone(), cx);
-                (stop_task, adapter.name.clone())
+                    self.stop_local_language_server(worktree_id, adapter_name.clone(), cx);
+                (stop_task, adapter_name)
             })
             .collect::<Vec<_>>();
         if stop_tasks.is_empty() {
@@ -7179,7 +7436,9 @@ impl LspStore {
                 let local = this.as_local_mut().unwrap();
                 // Restart the language server for the given worktree.
                 //
-                local.start_language_servers(&worktree, language.clone(), cx);
+                for language in languages {
+                    local.start_language_servers(&worktree, language, cx);
+                }
 
                 // Lookup new server ids and set them for each of the orphaned worktrees
                 for (affected_worktree_id, language_server_name) in affected_worktrees {
@@ -7843,6 +8102,16 @@ impl LspStore {
 
 impl EventEmitter<LspStoreEvent> for LspStore {}
 
+/// Drops the edits that don't intersect any of the given sorted, non-overlapping ranges.
+fn retain_edits_intersecting<T>(edits: &mut Vec<(Range<usize>, T)>, ranges: &[Range<usize>]) {
+    edits.retain(|(edit_range, _)| {
+        let ix = ranges.partition_point(|range| range.end < edit_range.start);
+        ranges
+            .get(ix)
+            .map_or(false, |range| range.start <= edit_range.end)
+    });
+}
+
 fn remove_empty_hover_blocks(mut hover: Hover) -> Option<Hover> {
     hover
         .contents
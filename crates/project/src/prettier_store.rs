@@ -1,5 +1,5 @@
 use std::{
-    ops::ControlFlow,
+    ops::{ControlFlow, Range},
     path::{Path, PathBuf},
     sync::Arc,
 };
@@ -15,7 +15,7 @@ use futures::{
 use gpui::{AsyncAppContext, EventEmitter, Model, ModelContext, Task, WeakModel};
 use language::{
     language_settings::{Formatter, LanguageSettings, SelectedFormatter},
-    Buffer, LanguageRegistry, LocalFile,
+    Buffer, LanguageRegistry, LanguageToolchainStore, LocalFile,
 };
 use lsp::{LanguageServer, LanguageServerId, LanguageServerName};
 use node_runtime::NodeRuntime;
@@ -25,8 +25,8 @@ use smol::stream::StreamExt;
 use util::{ResultExt, TryFutureExt};
 
 use crate::{
-    lsp_store::WorktreeId, worktree_store::WorktreeStore, File, PathChange, ProjectEntryId,
-    Worktree,
+    lsp_store::WorktreeId, toolchain_store::active_node_toolchain, worktree_store::WorktreeStore,
+    File, PathChange, ProjectEntryId, Worktree,
 };
 
 pub struct PrettierStore {
@@ -34,6 +34,7 @@ pub struct PrettierStore {
     fs: Arc<dyn Fs>,
     languages: Arc<LanguageRegistry>,
     worktree_store: Model<WorktreeStore>,
+    toolchains: Arc<dyn LanguageToolchainStore>,
     default_prettier: DefaultPrettier,
     prettiers_per_worktree: HashMap<WorktreeId, HashSet<Option<PathBuf>>>,
     prettier_ignores_per_worktree: HashMap<WorktreeId, HashSet<PathBuf>>,
@@ -57,6 +58,7 @@ impl PrettierStore {
         fs: Arc<dyn Fs>,
         languages: Arc<LanguageRegistry>,
         worktree_store: Model<WorktreeStore>,
+        toolchains: Arc<dyn LanguageToolchainStore>,
         _: &mut ModelContext<Self>,
     ) -> Self {
         Self {
@@ -64,6 +66,7 @@ impl PrettierStore {
             fs,
             languages,
             worktree_store,
+            toolchains,
             default_prettier: DefaultPrettier::default(),
             prettiers_per_worktree: HashMap::default(),
             prettier_ignores_per_worktree: HashMap::default(),
@@ -102,6 +105,48 @@ impl PrettierStore {
         .detach();
     }
 
+    /// Stops the prettier instances installed in the given worktree, so that they start again
+    /// with the Node.js version that is selected now, the next time they are needed.
+    pub(crate) fn stop_project_prettiers(
+        &mut self,
+        worktree_id: WorktreeId,
+        cx: &mut ModelContext<Self>,
+    ) {
+        let mut stopped_prettiers = FuturesUnordered::new();
+        let prettier_dirs = self
+            .prettiers_per_worktree
+            .get(&worktree_id)
+            .into_iter()
+            .flatten()
+            .flatten();
+        for prettier_dir in prettier_dirs {
+            let Some(prettier_instance) = self.prettier_instances.get_mut(prettier_dir) else {
+                continue;
+            };
+            prettier_instance.attempt = 0;
+            if let Some(prettier_task) = prettier_instance.prettier.take() {
+                stopped_prettiers.push(async move {
+                    let prettier = prettier_task.await.ok()?;
+                    Some(prettier.server()?.server_id())
+                });
+            }
+        }
+        cx.spawn(|prettier_store, mut cx| async move {
+            while let Some(prettier_server_id) = stopped_prettiers.next().await {
+                if let Some(prettier_server_id) = prettier_server_id {
+                    prettier_store
+                        .update(&mut cx, |_, cx| {
+                            cx.emit(PrettierStoreEvent::LanguageServerRemoved(
+                                prettier_server_id,
+                            ));
+                        })
+                        .ok();
+                }
+            }
+        })
+        .detach();
+    }
+
     fn prettier_instance_for_buffer(
         &mut self,
         buffer: &Model<Buffer>,
@@ -281,15 +326,30 @@ impl PrettierStore {
     ) -> PrettierTask {
         cx.spawn(|prettier_store, mut cx| async move {
             log::info!("Starting prettier at path {prettier_dir:?}");
-            let new_server_id = prettier_store.update(&mut cx, |prettier_store, _| {
-                prettier_store.languages.next_language_server_id()
-            })?;
+            let (new_server_id, toolchains) =
+                prettier_store.update(&mut cx, |prettier_store, _| {
+                    (
+                        prettier_store.languages.next_language_server_id(),
+                        prettier_store.toolchains.clone(),
+                    )
+                })?;
+            // The default prettier is shared by all projects, so only those installed in a
+            // project run with the Node.js version selected for it.
+            let node_binary = match worktree_id {
+                Some(worktree_id) if prettier_dir != default_prettier_dir().as_path() => {
+                    active_node_toolchain(toolchains, worktree_id, None, &mut cx)
+                        .await
+                        .map(|toolchain| PathBuf::from(toolchain.path.as_ref()))
+                }
+                _ => None,
+            };
 
-            let new_prettier = Prettier::start(new_server_id, prettier_dir, node, cx.clone())
-                .await
-                .context("default prettier spawn")
-                .map(Arc::new)
-                .map_err(Arc::new)?;
+            let new_prettier =
+                Prettier::start(new_server_id, prettier_dir, node, node_binary, cx.clone())
+                    .await
+                    .context("default prettier spawn")
+                    .map(Arc::new)
+                    .map_err(Arc::new)?;
             Self::register_new_prettier(
                 &prettier_store,
                 &new_prettier,
@@ -707,6 +767,7 @@ pub fn prettier_plugins_for_language(
 pub(super) async fn format_with_prettier(
     prettier_store: &WeakModel<PrettierStore>,
     buffer: &Model<Buffer>,
+    range: Option<Range<usize>>,
     cx: &mut AsyncAppContext,
 ) -> Option<Result<crate::lsp_store::FormatOperation>> {
     let prettier_instance = prettier_store
@@ -740,7 +801,7 @@ pub(super) async fn format_with_prettier(
                 .flatten();
 
             let format_result = prettier
-                .format(buffer, buffer_path, ignore_dir, cx)
+                .format(buffer, buffer_path, ignore_dir, range, cx)
                 .await
                 .map(crate::lsp_store::FormatOperation::Prettier)
                 .with_context(|| format!("{} failed to format buffer", prettier_description));
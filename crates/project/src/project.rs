@@ -8,9 +8,11 @@ pub mod lsp_ext_command;
 pub mod lsp_store;
 pub mod prettier_store;
 pub mod project_settings;
+pub mod review_threads;
 pub mod search;
 mod task_inventory;
 pub mod task_store;
+pub mod terminal_sharing;
 pub mod terminals;
 pub mod toolchain_store;
 pub mod worktree_store;
@@ -61,6 +63,7 @@ use parking_lot::Mutex;
 pub use prettier_store::PrettierStore;
 use project_settings::{ProjectSettings, SettingsObserver, SettingsObserverEvent};
 use remote::{SshConnectionOptions, SshRemoteClient};
+use review_threads::ReviewThreads;
 use rpc::{
     proto::{LanguageServerPromptResponse, SSH_PROJECT_ID},
     AnyProtoClient, ErrorCode,
@@ -82,6 +85,7 @@ use std::{
     time::Duration,
 };
 use task_store::TaskStore;
+use terminal_sharing::SharedTerminal;
 use terminals::Terminals;
 use text::{Anchor, BufferId};
 use toolchain_store::EmptyToolchainStore;
@@ -160,6 +164,7 @@ pub struct Project {
     git_diff_debouncer: DebouncedDelay<Self>,
     remotely_created_models: Arc<Mutex<RemotelyCreatedModels>>,
     terminals: Terminals,
+    review_threads: ReviewThreads,
     node: Option<NodeRuntime>,
     search_history: SearchHistory,
     search_included_history: SearchHistory,
@@ -274,6 +279,8 @@ pub enum Event {
     RefreshInlayHints,
     RevealInProjectPanel(ProjectEntryId),
     SnippetEdit(BufferId, Vec<(lsp::Range, Snippet)>),
+    TerminalShared(Model<SharedTerminal>),
+    ReviewThreadsChanged,
 }
 
 #[derive(Clone, Debug, Eq, PartialEq, Hash, PartialOrd, Ord)]
@@ -584,6 +591,11 @@ impl Project {
         client.add_model_request_handler(Self::handle_open_buffer_by_path);
         client.add_model_request_handler(Self::handle_open_new_buffer);
         client.add_model_message_handler(Self::handle_create_buffer_for_peer);
+        client.add_model_message_handler(Self::handle_share_terminal);
+        client.add_model_message_handler(Self::handle_unshare_terminal);
+        client.add_model_message_handler(Self::handle_update_shared_terminal);
+        client.add_model_request_handler(Self::handle_shared_terminal_input);
+        client.add_model_message_handler(Self::handle_update_review_thread);
 
         client.add_model_request_handler(WorktreeStore::handle_rename_project_entry);
 
@@ -621,22 +633,22 @@ impl Project {
             cx.subscribe(&image_store, Self::on_image_store_event)
                 .detach();
 
-            let prettier_store = cx.new_model(|cx| {
-                PrettierStore::new(
-                    node.clone(),
-                    fs.clone(),
+            let environment = ProjectEnvironment::new(&worktree_store, env, cx);
+            let toolchain_store = cx.new_model(|cx| {
+                ToolchainStore::local(
                     languages.clone(),
                     worktree_store.clone(),
+                    environment.clone(),
                     cx,
                 )
             });
-
-            let environment = ProjectEnvironment::new(&worktree_store, env, cx);
-            let toolchain_store = cx.new_model(|cx| {
-                ToolchainStore::local(
+            let prettier_store = cx.new_model(|cx| {
+                PrettierStore::new(
+                    node.clone(),
+                    fs.clone(),
                     languages.clone(),
                     worktree_store.clone(),
-                    environment.clone(),
+                    toolchain_store.read(cx).as_language_toolchain_store(),
                     cx,
                 )
             });
@@ -699,9 +711,8 @@ impl Project {
                 ssh_client: None,
                 buffers_needing_diff: Default::default(),
                 git_diff_debouncer: DebouncedDelay::new(),
-                terminals: Terminals {
-                    local_handles: Vec::new(),
-                },
+                terminals: Terminals::default(),
+                review_threads: ReviewThreads::default(),
                 node: Some(node),
                 search_history: Self::new_search_history(),
                 environment,
@@ -832,9 +843,8 @@ impl Project {
                 ssh_client: Some(ssh.clone()),
                 buffers_needing_diff: Default::default(),
                 git_diff_debouncer: DebouncedDelay::new(),
-                terminals: Terminals {
-                    local_handles: Vec::new(),
-                },
+                terminals: Terminals::default(),
+                review_threads: ReviewThreads::default(),
                 node: Some(node),
                 search_history: Self::new_search_history(),
                 environment,
@@ -1037,9 +1047,8 @@ impl Project {
                 },
                 buffers_needing_diff: Default::default(),
                 git_diff_debouncer: DebouncedDelay::new(),
-                terminals: Terminals {
-                    local_handles: Vec::new(),
-                },
+                terminals: Terminals::default(),
+                review_threads: ReviewThreads::default(),
                 node: None,
                 search_history: Self::new_search_history(),
                 search_included_history: Self::new_search_history(),
@@ -1087,6 +1096,7 @@ impl Project {
         this.update(&mut cx, |this, cx| {
             this.set_collaborators_from_proto(response.payload.collaborators, cx)?;
             this.client_subscriptions.extend(subscriptions);
+            this.load_review_threads(cx);
             anyhow::Ok(())
         })??;
 
@@ -1472,7 +1482,7 @@ impl Project {
             ))));
         };
         worktree.update(cx, |worktree, cx| {
-            worktree.create_entry(project_path.path, is_directory, cx)
+            worktree.create_entry(project_path.path, is_directory, None, cx)
         })
     }
 
@@ -1604,6 +1614,7 @@ impl Project {
         self.client_state = ProjectClientState::Shared {
             remote_id: project_id,
         };
+        self.load_review_threads(cx);
 
         cx.emit(Event::RemoteIdChanged(Some(project_id)));
         cx.notify();
@@ -1684,6 +1695,8 @@ impl Project {
             self.settings_observer.update(cx, |settings_observer, cx| {
                 settings_observer.unshared(cx);
             });
+            self.stop_sharing_terminals();
+            self.clear_review_threads();
 
             self.client
                 .send(proto::UnshareProject {
@@ -1740,6 +1753,8 @@ impl Project {
             });
             self.lsp_store
                 .update(cx, |lsp_store, _cx| lsp_store.disconnected_from_host());
+            self.close_remote_shared_terminals(cx);
+            self.clear_review_threads();
         }
     }
 
@@ -3643,6 +3658,9 @@ impl Project {
             cx.emit(Event::CollaboratorJoined(collaborator.peer_id));
             this.collaborators
                 .insert(collaborator.peer_id, collaborator);
+            if this.is_local() {
+                this.resend_shared_terminals(cx);
+            }
             cx.notify();
         })?;
 
@@ -3714,6 +3732,7 @@ impl Project {
                 }
             });
 
+            this.forget_shared_terminal_writer(peer_id);
             cx.emit(Event::CollaboratorLeft(peer_id));
             cx.notify();
             Ok(())
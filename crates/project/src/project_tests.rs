@@ -8,7 +8,7 @@ use language::{
     language_settings::{language_settings, AllLanguageSettings, LanguageSettingsContent},
     tree_sitter_rust, tree_sitter_typescript, Diagnostic, DiagnosticEntry, DiagnosticSet,
     DiskState, FakeLspAdapter, LanguageConfig, LanguageMatcher, LanguageName, LineEnding,
-    OffsetRangeExt, Point, ToPoint,
+    OffsetRangeExt, Point, ToPoint, Toolchain,
 };
 use lsp::{
     notification::DidRenameFiles, DiagnosticSeverity, DocumentChanges, FileOperationFilter,
@@ -779,6 +779,147 @@ async fn test_managing_language_servers(cx: &mut gpui::TestAppContext) {
     );
 }
 
+#[gpui::test]
+async fn test_large_file_mode(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+    cx.update(|cx| {
+        SettingsStore::update_global(cx, |settings, cx| {
+            settings.update_user_settings::<WorktreeSettings>(cx, |settings| {
+                settings.large_file_threshold_mb = Some(1);
+            });
+        });
+    });
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        "/the-root",
+        json!({
+            "small.rs": "const A: i32 = 1;",
+            "large.rs": "const A: i32 = 1;\n".repeat(70_000),
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs.clone(), ["/the-root".as_ref()], cx).await;
+    let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+    language_registry.add(rust_lang());
+    let mut fake_rust_servers =
+        language_registry.register_fake_lsp("Rust", FakeLspAdapter::default());
+
+    let (small_buffer, _small_handle) = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer_with_lsp("/the-root/small.rs", cx)
+        })
+        .await
+        .unwrap();
+    let (large_buffer, _large_handle) = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer_with_lsp("/the-root/large.rs", cx)
+        })
+        .await
+        .unwrap();
+    cx.executor().run_until_parked();
+
+    small_buffer.update(cx, |buffer, _| {
+        assert!(!buffer.is_large_file());
+        assert!(!buffer.read_only());
+        assert_eq!(buffer.language().map(|l| l.name()), Some("Rust".into()));
+    });
+    large_buffer.update(cx, |buffer, cx| {
+        assert!(buffer.is_large_file());
+        assert!(buffer.read_only());
+        assert_eq!(buffer.language().map(|l| l.name()), None);
+        assert_eq!(buffer.text(), "const A: i32 = 1;\n".repeat(70_000));
+
+        // Remote replicas of the buffer are also in large-file mode.
+        let replica =
+            Buffer::from_proto(1, Capability::ReadWrite, buffer.to_proto(cx), None).unwrap();
+        assert!(replica.is_large_file());
+        assert!(replica.read_only());
+    });
+
+    // Only the small file is opened in the language server.
+    let mut fake_rust_server = fake_rust_servers.next().await.unwrap();
+    assert_eq!(
+        fake_rust_server
+            .receive_notification::<lsp::notification::DidOpenTextDocument>()
+            .await
+            .text_document
+            .uri,
+        lsp::Url::from_file_path("/the-root/small.rs").unwrap(),
+    );
+
+    // Enabling full editing makes the file editable and detects its language.
+    large_buffer.update(cx, |buffer, cx| buffer.enable_full_editing(cx));
+    cx.executor().run_until_parked();
+    large_buffer.update(cx, |buffer, _| {
+        assert!(!buffer.is_large_file());
+        assert!(!buffer.read_only());
+        assert_eq!(buffer.language().map(|l| l.name()), Some("Rust".into()));
+    });
+    assert_eq!(
+        fake_rust_server
+            .receive_notification::<lsp::notification::DidOpenTextDocument>()
+            .await
+            .text_document
+            .uri,
+        lsp::Url::from_file_path("/the-root/large.rs").unwrap(),
+    );
+}
+
+#[gpui::test]
+async fn test_toolchain_activation_events(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree("/dir", json!({ "a.ts": "" })).await;
+    let project = Project::test(fs, ["/dir".as_ref()], cx).await;
+    let worktree_id = project.update(cx, |project, cx| {
+        project.worktrees(cx).next().unwrap().read(cx).id()
+    });
+
+    let activated_languages = Arc::new(Mutex::new(Vec::new()));
+    let toolchain_store =
+        project.read_with(cx, |project, _| project.toolchain_store.clone().unwrap());
+    cx.update(|cx| {
+        let activated_languages = activated_languages.clone();
+        cx.subscribe(&toolchain_store, move |_, event, _| {
+            let crate::toolchain_store::ToolchainStoreEvent::ToolchainActivated {
+                language_name,
+                ..
+            } = event;
+            activated_languages.lock().push(language_name.clone());
+        })
+        .detach();
+    });
+
+    let node = |name: &str| Toolchain {
+        name: name.to_string().into(),
+        path: format!("/nvm/{name}/bin/node").into(),
+        language_name: LanguageName::new("TypeScript"),
+        as_json: json!({}),
+    };
+
+    // Activating the toolchain that is already active doesn't emit an event.
+    for toolchain in [node("20"), node("20"), node("22")] {
+        project
+            .update(cx, |project, cx| {
+                project.activate_toolchain(worktree_id, toolchain, cx)
+            })
+            .await
+            .unwrap();
+    }
+    cx.executor().run_until_parked();
+
+    assert_eq!(
+        *activated_languages.lock(),
+        [
+            LanguageName::new("TypeScript"),
+            LanguageName::new("TypeScript")
+        ]
+    );
+}
+
 #[gpui::test]
 async fn test_reporting_fs_changes_to_language_servers(cx: &mut gpui::TestAppContext) {
     init_test(cx);
@@ -0,0 +1,306 @@
+use crate::{Event, File, Project};
+use anyhow::{anyhow, Context as _, Result};
+use client::{proto, TypedEnvelope};
+use collections::{BTreeMap, HashMap};
+use gpui::{AsyncAppContext, Model, ModelContext, Subscription, Task};
+use language::{Buffer, BufferEvent, Point};
+use std::{
+    ops::{Range, RangeInclusive},
+    path::PathBuf,
+};
+use text::{Anchor, BufferId, ToPoint as _};
+use time::OffsetDateTime;
+use util::ResultExt as _;
+
+/// A thread of review comments left on a range of lines of a file in a shared project.
+///
+/// Threads are stored by collab for the channel the project is shared in, so they're still there
+/// when the project is shared again in a later call.
+#[derive(Clone, Debug)]
+pub struct ReviewThread {
+    pub id: u64,
+    /// The full path of the file, starting with the root name of its worktree.
+    pub path: PathBuf,
+    /// The rows the thread was attached to when the file was last saved.
+    pub rows: RangeInclusive<u32>,
+    pub resolved: bool,
+    pub comments: Vec<ReviewComment>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ReviewComment {
+    pub id: u64,
+    pub sender_id: u64,
+    pub body: String,
+    pub timestamp: OffsetDateTime,
+}
+
+#[derive(Default)]
+pub struct ReviewThreads {
+    threads: BTreeMap<u64, ReviewThread>,
+    /// The ranges threads are attached to in the buffers of their files that are open, which
+    /// move along with edits to those buffers. They're dropped along with their buffer, as a
+    /// file that is opened again gets a new buffer.
+    anchors: HashMap<(u64, BufferId), Range<Anchor>>,
+    buffer_subscriptions: HashMap<BufferId, [Subscription; 2]>,
+    load_task: Option<Task<()>>,
+}
+
+impl ReviewThread {
+    fn from_proto(thread: proto::ReviewThread) -> Self {
+        Self {
+            id: thread.id,
+            path: PathBuf::from(thread.path),
+            rows: thread.start_row..=thread.end_row,
+            resolved: thread.resolved,
+            comments: thread
+                .comments
+                .into_iter()
+                .map(|comment| ReviewComment {
+                    id: comment.id,
+                    sender_id: comment.sender_id,
+                    body: comment.body,
+                    timestamp: OffsetDateTime::from_unix_timestamp(comment.timestamp as i64)
+                        .unwrap_or(OffsetDateTime::UNIX_EPOCH),
+                })
+                .collect(),
+        }
+    }
+}
+
+impl Project {
+    /// Returns the review threads on the files of this project, oldest first.
+    pub fn review_threads(&self) -> impl Iterator<Item = &ReviewThread> {
+        self.review_threads.threads.values()
+    }
+
+    pub fn review_thread(&self, thread_id: u64) -> Option<&ReviewThread> {
+        self.review_threads.threads.get(&thread_id)
+    }
+
+    /// Returns the review threads on the file of the given buffer, along with the ranges of the
+    /// buffer they're attached to.
+    pub fn review_threads_for_buffer(
+        &mut self,
+        buffer: &Model<Buffer>,
+        cx: &mut ModelContext<Self>,
+    ) -> Vec<(u64, Range<Anchor>)> {
+        let Some(path) = buffer_full_path(buffer, cx) else {
+            return Vec::new();
+        };
+        let buffer_id = buffer.read(cx).remote_id();
+        let snapshot = buffer.read(cx).text_snapshot();
+
+        let mut threads = Vec::new();
+        for thread in self.review_threads.threads.values() {
+            if thread.path != path {
+                continue;
+            }
+            let range = self
+                .review_threads
+                .anchors
+                .entry((thread.id, buffer_id))
+                .or_insert_with(|| {
+                    let max_row = snapshot.max_point().row;
+                    let start_row = (*thread.rows.start()).min(max_row);
+                    let end_row = (*thread.rows.end()).clamp(start_row, max_row);
+                    snapshot.anchor_before(Point::new(start_row, 0))
+                        ..snapshot.anchor_after(Point::new(end_row, snapshot.line_len(end_row)))
+                });
+            threads.push((thread.id, range.clone()));
+        }
+
+        if !threads.is_empty() {
+            self.watch_review_thread_buffer(buffer, cx);
+        }
+        threads
+    }
+
+    fn watch_review_thread_buffer(&mut self, buffer: &Model<Buffer>, cx: &mut ModelContext<Self>) {
+        let buffer_id = buffer.read(cx).remote_id();
+        self.review_threads
+            .buffer_subscriptions
+            .entry(buffer_id)
+            .or_insert_with(|| {
+                [
+                    cx.subscribe(buffer, |this, buffer, event, cx| {
+                        if *event == BufferEvent::Saved {
+                            this.move_review_threads(&buffer, cx);
+                        }
+                    }),
+                    cx.observe_release(buffer, move |this, _, _| {
+                        this.review_threads
+                            .anchors
+                            .retain(|(_, anchor_buffer_id), _| *anchor_buffer_id != buffer_id);
+                        this.review_threads.buffer_subscriptions.remove(&buffer_id);
+                    }),
+                ]
+            });
+    }
+
+    /// Starts a review thread on the lines of the given range of a buffer.
+    pub fn create_review_thread(
+        &mut self,
+        buffer: &Model<Buffer>,
+        range: Range<Anchor>,
+        body: String,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<u64>> {
+        let Some(project_id) = self.remote_id() else {
+            return Task::ready(Err(anyhow!("only shared projects can be reviewed")));
+        };
+        let Some(path) = buffer_full_path(buffer, cx) else {
+            return Task::ready(Err(anyhow!("buffer has no file")));
+        };
+        let snapshot = buffer.read(cx).text_snapshot();
+        let start_row = range.start.to_point(&snapshot).row;
+        let end_row = range.end.to_point(&snapshot).row;
+        let request = self.client.request(proto::CreateReviewThread {
+            project_id,
+            path: path.to_string_lossy().into_owned(),
+            start_row,
+            end_row,
+            body,
+        });
+        let buffer = buffer.downgrade();
+        cx.spawn(|this, mut cx| async move {
+            let thread = request.await?.thread.context("invalid thread")?;
+            this.update(&mut cx, |this, cx| {
+                let thread_id = thread.id;
+                if let Some(buffer) = buffer.upgrade() {
+                    let buffer_id = buffer.read(cx).remote_id();
+                    this.review_threads
+                        .anchors
+                        .insert((thread_id, buffer_id), range);
+                    this.watch_review_thread_buffer(&buffer, cx);
+                }
+                this.insert_review_thread(thread, cx);
+                thread_id
+            })
+        })
+    }
+
+    /// Adds a comment to the end of the given review thread.
+    pub fn add_review_comment(
+        &mut self,
+        thread_id: u64,
+        body: String,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<()>> {
+        let Some(project_id) = self.remote_id() else {
+            return Task::ready(Err(anyhow!("project is not shared")));
+        };
+        let request = self.client.request(proto::AddReviewComment {
+            project_id,
+            thread_id,
+            body,
+        });
+        cx.spawn(|this, mut cx| async move {
+            let thread = request.await?.thread.context("invalid thread")?;
+            this.update(&mut cx, |this, cx| this.insert_review_thread(thread, cx))
+        })
+    }
+
+    /// Marks the given review thread as resolved, or reopens it.
+    pub fn resolve_review_thread(
+        &mut self,
+        thread_id: u64,
+        resolved: bool,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<()>> {
+        let Some(project_id) = self.remote_id() else {
+            return Task::ready(Err(anyhow!("project is not shared")));
+        };
+        let request = self.client.request(proto::ResolveReviewThread {
+            project_id,
+            thread_id,
+            resolved,
+        });
+        cx.spawn(|this, mut cx| async move {
+            let thread = request.await?.thread.context("invalid thread")?;
+            this.update(&mut cx, |this, cx| this.insert_review_thread(thread, cx))
+        })
+    }
+
+    /// Fetches the review threads on the files of this project, if it's shared.
+    pub fn load_review_threads(&mut self, cx: &mut ModelContext<Self>) {
+        let Some(project_id) = self.remote_id() else {
+            return;
+        };
+        let request = self.client.request(proto::GetReviewThreads { project_id });
+        self.review_threads.load_task = Some(cx.spawn(|this, mut cx| async move {
+            if let Some(response) = request.await.log_err() {
+                this.update(&mut cx, |this, cx| {
+                    this.review_threads.threads = response
+                        .threads
+                        .into_iter()
+                        .map(|thread| (thread.id, ReviewThread::from_proto(thread)))
+                        .collect();
+                    this.review_threads.anchors.clear();
+                    this.review_threads.buffer_subscriptions.clear();
+                    cx.emit(Event::ReviewThreadsChanged);
+                    cx.notify();
+                })
+                .ok();
+            }
+        }));
+    }
+
+    pub(crate) fn clear_review_threads(&mut self) {
+        self.review_threads = ReviewThreads::default();
+    }
+
+    fn insert_review_thread(&mut self, thread: proto::ReviewThread, cx: &mut ModelContext<Self>) {
+        let thread = ReviewThread::from_proto(thread);
+        self.review_threads.threads.insert(thread.id, thread);
+        cx.emit(Event::ReviewThreadsChanged);
+        cx.notify();
+    }
+
+    /// Stores the rows the threads on a buffer ended up on after it was edited, so that they're
+    /// attached to the same lines when the project is shared again. Only the host does this,
+    /// as the saved file is the one on its disk.
+    fn move_review_threads(&mut self, buffer: &Model<Buffer>, cx: &mut ModelContext<Self>) {
+        let Some(project_id) = self.remote_id().filter(|_| self.is_local()) else {
+            return;
+        };
+        let buffer_id = buffer.read(cx).remote_id();
+        let snapshot = buffer.read(cx).text_snapshot();
+        for ((thread_id, anchor_buffer_id), range) in &self.review_threads.anchors {
+            if *anchor_buffer_id != buffer_id {
+                continue;
+            }
+            let Some(thread) = self.review_threads.threads.get_mut(thread_id) else {
+                continue;
+            };
+            let rows = range.start.to_point(&snapshot).row..=range.end.to_point(&snapshot).row;
+            if rows == thread.rows {
+                continue;
+            }
+            thread.rows = rows.clone();
+            let request = self.client.request(proto::MoveReviewThread {
+                project_id,
+                thread_id: *thread_id,
+                start_row: *rows.start(),
+                end_row: *rows.end(),
+            });
+            cx.background_executor()
+                .spawn(async move { request.await.log_err() })
+                .detach();
+        }
+    }
+
+    pub(crate) async fn handle_update_review_thread(
+        this: Model<Self>,
+        envelope: TypedEnvelope<proto::UpdateReviewThread>,
+        mut cx: AsyncAppContext,
+    ) -> Result<()> {
+        let thread = envelope.payload.thread.context("invalid thread")?;
+        this.update(&mut cx, |this, cx| this.insert_review_thread(thread, cx))
+    }
+}
+
+fn buffer_full_path(buffer: &Model<Buffer>, cx: &ModelContext<Project>) -> Option<PathBuf> {
+    let file = File::from_dyn(buffer.read(cx).file())?;
+    Some(file.full_path(cx))
+}
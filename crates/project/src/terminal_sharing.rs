@@ -0,0 +1,691 @@
+use crate::{Event, Project, ProjectClientState};
+use anyhow::{anyhow, Context as _, Result};
+use client::{proto, TypedEnvelope};
+use collections::HashSet;
+use gpui::{
+    AppContext, AsyncAppContext, Entity, EventEmitter, Model, ModelContext, Subscription, Task,
+    WeakModel,
+};
+use rpc::AnyProtoClient;
+use std::time::Duration;
+use terminal::{
+    alacritty_terminal::{
+        term::cell::{Cell, Flags},
+        vte::ansi::{Color as AnsiColor, NamedColor, Rgb},
+    },
+    Terminal,
+};
+use util::{post_inc, ResultExt as _};
+
+/// How many lines of a shared terminal's scrollback are replicated to guests.
+const MAX_SHARED_SCROLLBACK_LINES: usize = 1000;
+
+/// How often guests are sent the changes made to a shared terminal.
+const SHARED_TERMINAL_UPDATE_INTERVAL: Duration = Duration::from_millis(50);
+
+/// A terminal of the host that is shared with the guests of the project.
+pub(crate) struct HostedTerminal {
+    terminal: WeakModel<Terminal>,
+    writers: HashSet<proto::PeerId>,
+    sent_lines: Vec<proto::SharedTerminalLine>,
+    /// The absolute index of the first of the `sent_lines`, which increases as lines are
+    /// scrolled past the replicated part of the scrollback.
+    sent_first_line: u64,
+    sent_cursor: Option<proto::SharedTerminalCursor>,
+    pending_update: Option<Task<()>>,
+    _subscriptions: [Subscription; 2],
+}
+
+/// A read-only replica of a terminal that the host of a project shared with its guests.
+pub struct SharedTerminal {
+    id: u64,
+    project_id: u64,
+    title: String,
+    lines: Vec<SharedTerminalLine>,
+    /// The absolute index of the first of the `lines`.
+    first_line: u64,
+    cursor: Option<(usize, usize)>,
+    can_write: bool,
+    is_closed: bool,
+    client: AnyProtoClient,
+}
+
+pub type SharedTerminalLine = Vec<SharedTerminalRun>;
+
+/// A run of cells with the same style, in a line of a [`SharedTerminal`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct SharedTerminalRun {
+    pub text: String,
+    pub foreground: AnsiColor,
+    pub background: AnsiColor,
+    pub flags: Flags,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SharedTerminalEvent {
+    Updated,
+    Closed,
+}
+
+impl EventEmitter<SharedTerminalEvent> for SharedTerminal {}
+
+impl SharedTerminal {
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    pub fn title(&self) -> &str {
+        &self.title
+    }
+
+    /// The lines of the terminal, starting with the replicated part of its scrollback.
+    pub fn lines(&self) -> &[SharedTerminalLine] {
+        &self.lines
+    }
+
+    /// The line and column of the host's cursor, if it is shown.
+    pub fn cursor(&self) -> Option<(usize, usize)> {
+        self.cursor
+    }
+
+    /// Whether the host allowed the local participant to type into the terminal.
+    pub fn can_write(&self) -> bool {
+        self.can_write && !self.is_closed
+    }
+
+    /// Whether the host stopped sharing the terminal.
+    pub fn is_closed(&self) -> bool {
+        self.is_closed
+    }
+
+    /// Sends input to the host's terminal, as if it was typed there.
+    pub fn input(&self, input: Vec<u8>, cx: &AppContext) -> Task<Result<()>> {
+        if !self.can_write() {
+            return Task::ready(Err(anyhow!("not allowed to write to this terminal")));
+        }
+        let request = self.client.request(proto::SharedTerminalInput {
+            project_id: self.project_id,
+            terminal_id: self.id,
+            input,
+        });
+        cx.background_executor().spawn(async move {
+            request.await?;
+            Ok(())
+        })
+    }
+
+    fn apply_update(&mut self, update: proto::UpdateSharedTerminal, cx: &mut ModelContext<Self>) {
+        let removed_lines = update.first_line.saturating_sub(self.first_line) as usize;
+        self.lines.drain(..removed_lines.min(self.lines.len()));
+        self.first_line = update.first_line;
+        self.lines
+            .truncate(update.start_line.saturating_sub(update.first_line) as usize);
+        self.lines
+            .extend(update.lines.into_iter().map(line_from_proto));
+        self.cursor = update
+            .cursor
+            .map(|cursor| (cursor.line as usize, cursor.column as usize));
+        cx.emit(SharedTerminalEvent::Updated);
+        cx.notify();
+    }
+
+    fn close(&mut self, cx: &mut ModelContext<Self>) {
+        self.is_closed = true;
+        cx.emit(SharedTerminalEvent::Closed);
+        cx.notify();
+    }
+}
+
+impl Project {
+    /// Shares the given terminal with the guests of this project, which must be shared.
+    /// Guests can only see the terminal until they are allowed to write to it, see
+    /// [`Self::set_shared_terminal_writer`].
+    pub fn share_terminal(
+        &mut self,
+        terminal: &Model<Terminal>,
+        cx: &mut ModelContext<Self>,
+    ) -> Result<u64> {
+        if !matches!(self.client_state, ProjectClientState::Shared { .. }) {
+            return Err(anyhow!("only shared projects can share terminals"));
+        }
+        if let Some(terminal_id) = self.shared_terminal_id(terminal) {
+            return Ok(terminal_id);
+        }
+
+        let terminal_id = post_inc(&mut self.terminals.next_shared_terminal_id);
+        let subscriptions = [
+            cx.subscribe(terminal, move |this, _, event, cx| match event {
+                terminal::Event::Wakeup => this.schedule_shared_terminal_update(terminal_id, cx),
+                terminal::Event::TitleChanged => this.send_share_terminal(terminal_id, cx),
+                terminal::Event::CloseTerminal => this.unshare_terminal(terminal_id, cx),
+                _ => {}
+            }),
+            cx.observe_release(terminal, move |this, _, cx| {
+                this.unshare_terminal(terminal_id, cx)
+            }),
+        ];
+        self.terminals.hosted.insert(
+            terminal_id,
+            HostedTerminal {
+                terminal: terminal.downgrade(),
+                writers: HashSet::default(),
+                sent_lines: Vec::new(),
+                sent_first_line: 0,
+                sent_cursor: None,
+                pending_update: None,
+                _subscriptions: subscriptions,
+            },
+        );
+        self.send_share_terminal(terminal_id, cx);
+        self.send_shared_terminal_update(terminal_id, cx);
+        cx.notify();
+        Ok(terminal_id)
+    }
+
+    /// Stops sharing the terminal with the given id with the guests of this project.
+    pub fn unshare_terminal(&mut self, terminal_id: u64, cx: &mut ModelContext<Self>) {
+        if self.terminals.hosted.remove(&terminal_id).is_none() {
+            return;
+        }
+        if let Some(project_id) = self.remote_id() {
+            self.client
+                .send(proto::UnshareTerminal {
+                    project_id,
+                    terminal_id,
+                })
+                .log_err();
+        }
+        cx.notify();
+    }
+
+    /// Returns the id under which the given terminal is shared, if it is.
+    pub fn shared_terminal_id(&self, terminal: &Model<Terminal>) -> Option<u64> {
+        self.terminals.hosted.iter().find_map(|(id, hosted)| {
+            (hosted.terminal.entity_id() == terminal.entity_id()).then_some(*id)
+        })
+    }
+
+    /// Returns whether the given guest is allowed to write to the shared terminal.
+    pub fn is_shared_terminal_writer(&self, terminal_id: u64, peer_id: proto::PeerId) -> bool {
+        self.terminals
+            .hosted
+            .get(&terminal_id)
+            .map_or(false, |hosted| hosted.writers.contains(&peer_id))
+    }
+
+    /// Allows or disallows the given guest to write to the shared terminal.
+    pub fn set_shared_terminal_writer(
+        &mut self,
+        terminal_id: u64,
+        peer_id: proto::PeerId,
+        can_write: bool,
+        cx: &mut ModelContext<Self>,
+    ) {
+        let Some(hosted) = self.terminals.hosted.get_mut(&terminal_id) else {
+            return;
+        };
+        let changed = if can_write {
+            hosted.writers.insert(peer_id)
+        } else {
+            hosted.writers.remove(&peer_id)
+        };
+        if changed {
+            self.send_share_terminal(terminal_id, cx);
+            cx.notify();
+        }
+    }
+
+    /// The terminals that the host of this project shared with the local participant.
+    pub fn remote_shared_terminals(&self) -> impl Iterator<Item = &Model<SharedTerminal>> {
+        self.terminals.remote.values()
+    }
+
+    fn send_share_terminal(&mut self, terminal_id: u64, cx: &mut ModelContext<Self>) {
+        let Some(project_id) = self.remote_id() else {
+            return;
+        };
+        let Some(hosted) = self.terminals.hosted.get(&terminal_id) else {
+            return;
+        };
+        let Some(terminal) = hosted.terminal.upgrade() else {
+            return;
+        };
+        self.client
+            .send(proto::ShareTerminal {
+                project_id,
+                terminal_id,
+                title: terminal.read(cx).title(true),
+                writers: hosted.writers.iter().copied().collect(),
+            })
+            .log_err();
+    }
+
+    fn schedule_shared_terminal_update(&mut self, terminal_id: u64, cx: &mut ModelContext<Self>) {
+        let Some(hosted) = self.terminals.hosted.get_mut(&terminal_id) else {
+            return;
+        };
+        if hosted.pending_update.is_some() {
+            return;
+        }
+        hosted.pending_update = Some(cx.spawn(|this, mut cx| async move {
+            cx.background_executor()
+                .timer(SHARED_TERMINAL_UPDATE_INTERVAL)
+                .await;
+            this.update(&mut cx, |this, cx| {
+                if let Some(hosted) = this.terminals.hosted.get_mut(&terminal_id) {
+                    hosted.pending_update.take();
+                }
+                this.send_shared_terminal_update(terminal_id, cx);
+            })
+            .ok();
+        }));
+    }
+
+    /// Sends guests the lines of the shared terminal that changed since the last update.
+    ///
+    /// Lines are identified by their absolute index, so that lines that scrolled up, once the
+    /// replicated part of the scrollback is full, don't need to be sent again.
+    fn send_shared_terminal_update(&mut self, terminal_id: u64, cx: &mut ModelContext<Self>) {
+        let Some(project_id) = self.remote_id() else {
+            return;
+        };
+        let Some(hosted) = self.terminals.hosted.get_mut(&terminal_id) else {
+            return;
+        };
+
+        let Some(terminal) = hosted.terminal.upgrade() else {
+            return;
+        };
+        let snapshot = terminal.read(cx).grid_snapshot(MAX_SHARED_SCROLLBACK_LINES);
+        let lines = snapshot
+            .lines
+            .iter()
+            .map(|cells| line_to_proto(cells))
+            .collect::<Vec<_>>();
+        let cursor = snapshot
+            .cursor
+            .map(|(line, column)| proto::SharedTerminalCursor {
+                line: line as u32,
+                column: column as u32,
+            });
+
+        let removed_lines = removed_line_count(&hosted.sent_lines, &lines);
+        let kept_lines = &hosted.sent_lines[removed_lines..];
+        let start_line = kept_lines
+            .iter()
+            .zip(&lines)
+            .take_while(|(sent_line, line)| sent_line == line)
+            .count();
+        if removed_lines == 0
+            && start_line == lines.len()
+            && start_line == kept_lines.len()
+            && cursor == hosted.sent_cursor
+        {
+            return;
+        }
+
+        let first_line = hosted.sent_first_line + removed_lines as u64;
+        self.client
+            .send(proto::UpdateSharedTerminal {
+                project_id,
+                terminal_id,
+                first_line,
+                start_line: first_line + start_line as u64,
+                lines: lines[start_line..].to_vec(),
+                cursor: cursor.clone(),
+            })
+            .log_err();
+        hosted.sent_lines = lines;
+        hosted.sent_first_line = first_line;
+        hosted.sent_cursor = cursor;
+    }
+
+    /// Sends the full state of the shared terminals, so that guests that just joined
+    /// the project can replicate them.
+    pub(crate) fn resend_shared_terminals(&mut self, cx: &mut ModelContext<Self>) {
+        let terminal_ids = self.terminals.hosted.keys().copied().collect::<Vec<_>>();
+        for terminal_id in terminal_ids {
+            if let Some(hosted) = self.terminals.hosted.get_mut(&terminal_id) {
+                hosted.sent_lines.clear();
+                hosted.sent_cursor = None;
+            }
+            self.send_share_terminal(terminal_id, cx);
+            self.send_shared_terminal_update(terminal_id, cx);
+        }
+    }
+
+    pub(crate) fn forget_shared_terminal_writer(&mut self, peer_id: proto::PeerId) {
+        for hosted in self.terminals.hosted.values_mut() {
+            hosted.writers.remove(&peer_id);
+        }
+    }
+
+    pub(crate) fn stop_sharing_terminals(&mut self) {
+        self.terminals.hosted.clear();
+    }
+
+    pub(crate) fn close_remote_shared_terminals(&mut self, cx: &mut AppContext) {
+        for (_, terminal) in self.terminals.remote.drain() {
+            terminal.update(cx, |terminal, cx| terminal.close(cx));
+        }
+    }
+
+    pub(crate) async fn handle_share_terminal(
+        this: Model<Self>,
+        envelope: TypedEnvelope<proto::ShareTerminal>,
+        mut cx: AsyncAppContext,
+    ) -> Result<()> {
+        this.update(&mut cx, |this, cx| {
+            let payload = envelope.payload;
+            let can_write = this
+                .client
+                .peer_id()
+                .map_or(false, |peer_id| payload.writers.contains(&peer_id));
+            if let Some(terminal) = this.terminals.remote.get(&payload.terminal_id) {
+                terminal.update(cx, |terminal, cx| {
+                    terminal.title = payload.title;
+                    terminal.can_write = can_write;
+                    cx.emit(SharedTerminalEvent::Updated);
+                    cx.notify();
+                });
+            } else {
+                let client: AnyProtoClient = this.client.clone().into();
+                let terminal = cx.new_model(|_| SharedTerminal {
+                    id: payload.terminal_id,
+                    project_id: payload.project_id,
+                    title: payload.title,
+                    lines: Vec::new(),
+                    first_line: 0,
+                    cursor: None,
+                    can_write,
+                    is_closed: false,
+                    client,
+                });
+                this.terminals
+                    .remote
+                    .insert(payload.terminal_id, terminal.clone());
+                cx.emit(Event::TerminalShared(terminal));
+            }
+            cx.notify();
+        })
+    }
+
+    pub(crate) async fn handle_unshare_terminal(
+        this: Model<Self>,
+        envelope: TypedEnvelope<proto::UnshareTerminal>,
+        mut cx: AsyncAppContext,
+    ) -> Result<()> {
+        this.update(&mut cx, |this, cx| {
+            if let Some(terminal) = this.terminals.remote.remove(&envelope.payload.terminal_id) {
+                terminal.update(cx, |terminal, cx| terminal.close(cx));
+            }
+            cx.notify();
+        })
+    }
+
+    pub(crate) async fn handle_update_shared_terminal(
+        this: Model<Self>,
+        envelope: TypedEnvelope<proto::UpdateSharedTerminal>,
+        mut cx: AsyncAppContext,
+    ) -> Result<()> {
+        this.update(&mut cx, |this, cx| {
+            let terminal = this
+                .terminals
+                .remote
+                .get(&envelope.payload.terminal_id)
+                .cloned()
+                .context("received update for unknown shared terminal")?;
+            terminal.update(cx, |terminal, cx| {
+                terminal.apply_update(envelope.payload, cx)
+            });
+            Ok(())
+        })?
+    }
+
+    pub(crate) async fn handle_shared_terminal_input(
+        this: Model<Self>,
+        envelope: TypedEnvelope<proto::SharedTerminalInput>,
+        mut cx: AsyncAppContext,
+    ) -> Result<proto::Ack> {
+        let sender_id = envelope.original_sender_id()?;
+        this.update(&mut cx, |this, cx| {
+            let hosted = this
+                .terminals
+                .hosted
+                .get(&envelope.payload.terminal_id)
+                .context("terminal is not shared")?;
+            anyhow::ensure!(
+                hosted.writers.contains(&sender_id),
+                "guest is not allowed to write to this terminal"
+            );
+            let terminal = hosted.terminal.upgrade().context("terminal was closed")?;
+            terminal.update(cx, |terminal, _| {
+                terminal.input_bytes(envelope.payload.input)
+            });
+            Ok(proto::Ack {})
+        })?
+    }
+}
+
+/// Returns how many lines were removed from the top of the terminal since `sent_lines` were
+/// sent, because they were scrolled past the replicated part of the scrollback.
+///
+/// Lines aren't numbered by the terminal, so this picks the number of removed lines that leaves
+/// the most sent lines in place. Any number gives the right result, but sends more lines if it's
+/// wrong.
+fn removed_line_count<T: PartialEq>(sent_lines: &[T], lines: &[T]) -> usize {
+    let mut removed_lines = 0;
+    let mut max_kept_lines = 0;
+    for removed in 0..sent_lines.len() {
+        if sent_lines.len() - removed <= max_kept_lines {
+            break;
+        }
+        let kept_lines = sent_lines[removed..]
+            .iter()
+            .zip(lines)
+            .take_while(|(sent_line, line)| sent_line == line)
+            .count();
+        if kept_lines > max_kept_lines {
+            removed_lines = removed;
+            max_kept_lines = kept_lines;
+        }
+    }
+    removed_lines
+}
+
+fn line_to_proto(cells: &[Cell]) -> proto::SharedTerminalLine {
+    let mut runs = Vec::<proto::SharedTerminalRun>::new();
+    for cell in cells {
+        if cell
+            .flags
+            .intersects(Flags::WIDE_CHAR_SPACER | Flags::LEADING_WIDE_CHAR_SPACER)
+        {
+            continue;
+        }
+
+        let (foreground, background) = if cell.flags.contains(Flags::INVERSE) {
+            (cell.bg, cell.fg)
+        } else {
+            (cell.fg, cell.bg)
+        };
+        let run = proto::SharedTerminalRun {
+            text: String::new(),
+            foreground: Some(color_to_proto(foreground)),
+            background: Some(color_to_proto(background)),
+            bold: cell.flags.contains(Flags::BOLD),
+            italic: cell.flags.contains(Flags::ITALIC),
+            underline: cell.flags.intersects(Flags::ALL_UNDERLINES),
+            strikethrough: cell.flags.contains(Flags::STRIKEOUT),
+            dim: cell.flags.contains(Flags::DIM),
+        };
+
+        let last_run = match runs.last_mut() {
+            Some(last_run) if has_same_style(last_run, &run) => last_run,
+            _ => {
+                runs.push(run);
+                runs.last_mut().unwrap()
+            }
+        };
+        last_run.text.push(cell.c);
+        if let Some(zerowidth) = cell.zerowidth() {
+            last_run.text.extend(zerowidth);
+        }
+    }
+    proto::SharedTerminalLine { runs }
+}
+
+fn has_same_style(a: &proto::SharedTerminalRun, b: &proto::SharedTerminalRun) -> bool {
+    a.foreground == b.foreground
+        && a.background == b.background
+        && a.bold == b.bold
+        && a.italic == b.italic
+        && a.underline == b.underline
+        && a.strikethrough == b.strikethrough
+        && a.dim == b.dim
+}
+
+fn line_from_proto(line: proto::SharedTerminalLine) -> SharedTerminalLine {
+    line.runs
+        .into_iter()
+        .map(|run| {
+            let mut flags = Flags::empty();
+            flags.set(Flags::BOLD, run.bold);
+            flags.set(Flags::ITALIC, run.italic);
+            flags.set(Flags::UNDERLINE, run.underline);
+            flags.set(Flags::STRIKEOUT, run.strikethrough);
+            flags.set(Flags::DIM, run.dim);
+            SharedTerminalRun {
+                text: run.text,
+                foreground: run
+                    .foreground
+                    .and_then(color_from_proto)
+                    .unwrap_or(AnsiColor::Named(NamedColor::Foreground)),
+                background: run
+                    .background
+                    .and_then(color_from_proto)
+                    .unwrap_or(AnsiColor::Named(NamedColor::Background)),
+                flags,
+            }
+        })
+        .collect()
+}
+
+fn color_to_proto(color: AnsiColor) -> proto::TerminalColor {
+    let variant = match color {
+        AnsiColor::Named(color) => proto::terminal_color::Variant::Named(color as u32),
+        AnsiColor::Indexed(index) => proto::terminal_color::Variant::Indexed(index as u32),
+        AnsiColor::Spec(rgb) => proto::terminal_color::Variant::Rgb(
+            (rgb.r as u32) << 16 | (rgb.g as u32) << 8 | rgb.b as u32,
+        ),
+    };
+    proto::TerminalColor {
+        variant: Some(variant),
+    }
+}
+
+fn color_from_proto(color: proto::TerminalColor) -> Option<AnsiColor> {
+    Some(match color.variant? {
+        proto::terminal_color::Variant::Named(index) => {
+            AnsiColor::Named(named_color_from_index(index)?)
+        }
+        proto::terminal_color::Variant::Indexed(index) => AnsiColor::Indexed(index as u8),
+        proto::terminal_color::Variant::Rgb(rgb) => AnsiColor::Spec(Rgb {
+            r: (rgb >> 16) as u8,
+            g: (rgb >> 8) as u8,
+            b: rgb as u8,
+        }),
+    })
+}
+
+fn named_color_from_index(index: u32) -> Option<NamedColor> {
+    const NAMED_COLORS: [NamedColor; 29] = [
+        NamedColor::Black,
+        NamedColor::Red,
+        NamedColor::Green,
+        NamedColor::Yellow,
+        NamedColor::Blue,
+        NamedColor::Magenta,
+        NamedColor::Cyan,
+        NamedColor::White,
+        NamedColor::BrightBlack,
+        NamedColor::BrightRed,
+        NamedColor::BrightGreen,
+        NamedColor::BrightYellow,
+        NamedColor::BrightBlue,
+        NamedColor::BrightMagenta,
+        NamedColor::BrightCyan,
+        NamedColor::BrightWhite,
+        NamedColor::Foreground,
+        NamedColor::Background,
+        NamedColor::Cursor,
+        NamedColor::DimBlack,
+        NamedColor::DimRed,
+        NamedColor::DimGreen,
+        NamedColor::DimYellow,
+        NamedColor::DimBlue,
+        NamedColor::DimMagenta,
+        NamedColor::DimCyan,
+        NamedColor::DimWhite,
+        NamedColor::BrightForeground,
+        NamedColor::DimForeground,
+    ];
+    NAMED_COLORS
+        .into_iter()
+        .find(|color| *color as u32 == index)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_terminal_color_round_trip() {
+        for color in [
+            AnsiColor::Named(NamedColor::Black),
+            AnsiColor::Named(NamedColor::Background),
+            AnsiColor::Named(NamedColor::DimForeground),
+            AnsiColor::Indexed(214),
+            AnsiColor::Spec(Rgb {
+                r: 12,
+                g: 34,
+                b: 255,
+            }),
+        ] {
+            assert_eq!(color_from_proto(color_to_proto(color)), Some(color));
+        }
+    }
+
+    #[test]
+    fn test_line_to_proto_merges_cells_with_same_style() {
+        let mut cells = "ab c".chars().map(cell).collect::<Vec<_>>();
+        cells[3].flags.insert(Flags::BOLD);
+
+        let line = line_from_proto(line_to_proto(&cells));
+        assert_eq!(
+            line.iter()
+                .map(|run| (run.text.as_str(), run.flags))
+                .collect::<Vec<_>>(),
+            [("ab ", Flags::empty()), ("c", Flags::BOLD)]
+        );
+    }
+
+    #[test]
+    fn test_removed_line_count() {
+        // Lines were appended while the scrollback wasn't full.
+        assert_eq!(removed_line_count(&[1, 2, 3], &[1, 2, 3, 4]), 0);
+        // Lines scrolled out of the full scrollback, and the last line changed.
+        assert_eq!(removed_line_count(&[1, 2, 3, 4, 5], &[3, 4, 6, 7, 8]), 2);
+        // Repeated lines are kept in place.
+        assert_eq!(removed_line_count(&[0, 0, 0, 1], &[0, 0, 0, 1, 2]), 0);
+        // Everything changed, e.g. because the terminal was cleared.
+        assert_eq!(removed_line_count(&[1, 2, 3], &[4, 5]), 0);
+        assert_eq!(removed_line_count::<u8>(&[], &[1]), 0);
+    }
+
+    fn cell(c: char) -> Cell {
+        Cell {
+            c,
+            ..Default::default()
+        }
+    }
+}
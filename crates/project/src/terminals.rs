@@ -1,4 +1,7 @@
-use crate::Project;
+use crate::{
+    terminal_sharing::{HostedTerminal, SharedTerminal},
+    Project,
+};
 use anyhow::{Context as _, Result};
 use collections::HashMap;
 use gpui::{AnyWindowHandle, AppContext, Context, Entity, Model, ModelContext, Task, WeakModel};
@@ -23,8 +26,12 @@ use util::ResultExt;
 // #[cfg(target_os = "macos")]
 // use std::os::unix::ffi::OsStrExt;
 
+#[derive(Default)]
 pub struct Terminals {
     pub(crate) local_handles: Vec<WeakModel<terminal::Terminal>>,
+    pub(crate) next_shared_terminal_id: u64,
+    pub(crate) hosted: HashMap<u64, HostedTerminal>,
+    pub(crate) remote: HashMap<u64, Model<SharedTerminal>>,
 }
 
 /// Terminals are opened either for the users shell, or to run a task.
@@ -39,8 +46,14 @@ pub enum TerminalKind {
 
 /// SshCommand describes how to connect to a remote server
 #[derive(Debug, Clone, PartialEq, Eq)]
-pub struct SshCommand {
-    arguments: Vec<String>,
+pub enum SshCommand {
+    /// Run commands with `ssh` and these arguments.
+    Ssh { arguments: Vec<String> },
+    /// Run commands by appending them to this program and arguments, such as `docker exec`.
+    Exec {
+        program: String,
+        arguments: Vec<String>,
+    },
 }
 
 impl Project {
@@ -67,10 +80,19 @@ impl Project {
     fn ssh_details(&self, cx: &AppContext) -> Option<(String, SshCommand)> {
         if let Some(ssh_client) = &self.ssh_client {
             let ssh_client = ssh_client.read(cx);
+            if let Some((program, arguments)) = ssh_client.exec_command() {
+                let connection_options = ssh_client.connection_options();
+                return Some((
+                    connection_options
+                        .nickname
+                        .unwrap_or(connection_options.host),
+                    SshCommand::Exec { program, arguments },
+                ));
+            }
             if let Some(args) = ssh_client.ssh_args() {
                 return Some((
                     ssh_client.connection_options().host.clone(),
-                    SshCommand { arguments: args },
+                    SshCommand::Ssh { arguments: args },
                 ));
             }
         }
@@ -473,14 +495,23 @@ pub fn wrap_for_ssh(
     } else {
         format!("cd; {env_changes} {to_run}")
     };
-    let shell_invocation = format!("sh -c {}", shlex::try_quote(&commands).unwrap());
+    match ssh_command {
+        SshCommand::Ssh { arguments } => {
+            let shell_invocation = format!("sh -c {}", shlex::try_quote(&commands).unwrap());
 
-    let program = "ssh".to_string();
-    let mut args = ssh_command.arguments.clone();
+            let program = "ssh".to_string();
+            let mut args = arguments.clone();
 
-    args.push("-t".to_string());
-    args.push(shell_invocation);
-    (program, args)
+            args.push("-t".to_string());
+            args.push(shell_invocation);
+            (program, args)
+        }
+        SshCommand::Exec { program, arguments } => {
+            let mut args = arguments.clone();
+            args.extend(["sh".to_string(), "-c".to_string(), commands]);
+            (program.clone(), args)
+        }
+    }
 }
 
 fn add_environment_path(env: &mut HashMap<String, String>, new_path: &Path) -> Result<()> {
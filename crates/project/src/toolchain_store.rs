@@ -246,6 +246,34 @@ impl language::LanguageToolchainStore for RemoteStore {
     }
 }
 
+/// The languages whose toolchains are Node.js versions.
+pub const NODE_TOOLCHAIN_LANGUAGES: [&str; 3] = ["TypeScript", "TSX", "JavaScript"];
+
+/// Returns the Node.js version that tools running on Node.js, such as language servers,
+/// prettier and tasks, should use in the given worktree: the one selected for `language`,
+/// or else the first one selected for a language of [`NODE_TOOLCHAIN_LANGUAGES`].
+pub async fn active_node_toolchain(
+    toolchains: Arc<dyn LanguageToolchainStore>,
+    worktree_id: WorktreeId,
+    language: Option<LanguageName>,
+    cx: &mut AsyncAppContext,
+) -> Option<Toolchain> {
+    let languages = language
+        .filter(|language| NODE_TOOLCHAIN_LANGUAGES.contains(&language.0.as_ref()))
+        .into_iter()
+        .chain(NODE_TOOLCHAIN_LANGUAGES.into_iter().map(LanguageName::new));
+    for language in languages {
+        if let Some(toolchain) = toolchains
+            .clone()
+            .active_toolchain(worktree_id, language, cx)
+            .await
+        {
+            return Some(toolchain);
+        }
+    }
+    None
+}
+
 pub(crate) struct EmptyToolchainStore;
 #[async_trait(?Send)]
 impl language::LanguageToolchainStore for EmptyToolchainStore {
@@ -263,7 +291,10 @@ struct RemoteStore(WeakModel<RemoteToolchainStore>);
 
 #[derive(Clone)]
 pub(crate) enum ToolchainStoreEvent {
-    ToolchainActivated,
+    ToolchainActivated {
+        worktree_id: WorktreeId,
+        language_name: LanguageName,
+    },
 }
 
 impl EventEmitter<ToolchainStoreEvent> for LocalToolchainStore {}
@@ -277,11 +308,18 @@ impl LocalToolchainStore {
     ) -> Task<Option<()>> {
         cx.spawn(move |this, mut cx| async move {
             this.update(&mut cx, |this, cx| {
-                this.active_toolchains.insert(
-                    (worktree_id, toolchain.language_name.clone()),
-                    toolchain.clone(),
-                );
-                cx.emit(ToolchainStoreEvent::ToolchainActivated);
+                let language_name = toolchain.language_name.clone();
+                let previous_toolchain = this
+                    .active_toolchains
+                    .insert((worktree_id, language_name.clone()), toolchain.clone());
+                // Activating the toolchain that is already active, e.g. when it is restored or
+                // picked automatically, mustn't restart the language servers that use it.
+                if previous_toolchain.as_ref() != Some(&toolchain) {
+                    cx.emit(ToolchainStoreEvent::ToolchainActivated {
+                        worktree_id,
+                        language_name,
+                    });
+                }
             })
             .ok();
             Some(())
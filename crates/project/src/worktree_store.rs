@@ -69,6 +69,8 @@ impl EventEmitter<WorktreeStoreEvent> for WorktreeStore {}
 impl WorktreeStore {
     pub fn init(client: &AnyProtoClient) {
         client.add_model_request_handler(Self::handle_create_project_entry);
+        client.add_model_request_handler(Self::handle_read_project_file);
+        client.add_model_request_handler(Self::handle_append_to_project_file);
         client.add_model_request_handler(Self::handle_copy_project_entry);
         client.add_model_request_handler(Self::handle_delete_project_entry);
         client.add_model_request_handler(Self::handle_expand_project_entry);
@@ -1015,6 +1017,52 @@ impl WorktreeStore {
         Worktree::handle_create_entry(worktree, envelope.payload, cx).await
     }
 
+    pub async fn handle_read_project_file(
+        this: Model<Self>,
+        envelope: TypedEnvelope<proto::ReadProjectFile>,
+        mut cx: AsyncAppContext,
+    ) -> Result<proto::ReadProjectFileResponse> {
+        let worktree = this.update(&mut cx, |this, cx| {
+            let worktree_id = WorktreeId::from_proto(envelope.payload.worktree_id);
+            let worktree = this
+                .worktree_for_id(worktree_id, cx)
+                .ok_or_else(|| anyhow!("worktree not found"))?;
+            if envelope.original_sender_id.is_some() {
+                Self::check_file_is_shared(worktree.read(cx), &envelope.payload.path)?;
+            }
+            anyhow::Ok(worktree)
+        })??;
+        Worktree::handle_read_file(worktree, envelope.payload, cx).await
+    }
+
+    pub async fn handle_append_to_project_file(
+        this: Model<Self>,
+        envelope: TypedEnvelope<proto::AppendToProjectFile>,
+        mut cx: AsyncAppContext,
+    ) -> Result<proto::Ack> {
+        let worktree = this.update(&mut cx, |this, cx| {
+            let worktree_id = WorktreeId::from_proto(envelope.payload.worktree_id);
+            let worktree = this
+                .worktree_for_id(worktree_id, cx)
+                .ok_or_else(|| anyhow!("worktree not found"))?;
+            if envelope.original_sender_id.is_some() {
+                Self::check_file_is_shared(worktree.read(cx), &envelope.payload.path)?;
+            }
+            anyhow::Ok(worktree)
+        })??;
+        Worktree::handle_append_to_file(worktree, envelope.payload, cx).await
+    }
+
+    /// Requests forwarded from collaborators, rather than sent by the project's own client over
+    /// SSH, may only access the files that are shared with them: private files, and files that
+    /// are excluded from the worktree, such as the contents of `.git`, aren't.
+    fn check_file_is_shared(worktree: &Worktree, path: &str) -> Result<()> {
+        match worktree.entry_for_path(path) {
+            Some(entry) if entry.is_file() && !entry.is_private => Ok(()),
+            _ => Err(anyhow!("{path:?} is not shared with collaborators")),
+        }
+    }
+
     pub async fn handle_rename_project_entry(
         this: Model<super::Project>,
         envelope: TypedEnvelope<proto::RenameProjectEntry>,
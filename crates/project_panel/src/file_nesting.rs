@@ -0,0 +1,342 @@
+use collections::HashMap;
+use git::repository::GitFileStatus;
+use project::{Entry, ProjectEntryId};
+use std::{collections::HashSet, path::Path};
+
+use crate::{project_panel_settings::FileNestingSettings, NEW_ENTRY_ID};
+
+const CAPTURE: &str = "${capture}";
+
+/// Files nested under a parent file in the project panel.
+#[derive(Debug, Default)]
+pub(crate) struct NestedFiles {
+    pub(crate) children: Vec<ProjectEntryId>,
+    /// The strongest git status among the parent and its nested files.
+    pub(crate) git_status: Option<GitFileStatus>,
+}
+
+/// The outcome of applying nesting rules to a worktree's visible entries.
+#[derive(Debug, Default)]
+pub(crate) struct FileNests {
+    pub(crate) nested_files: HashMap<ProjectEntryId, NestedFiles>,
+    pub(crate) nest_parents: HashMap<ProjectEntryId, ProjectEntryId>,
+}
+
+struct NestingRule {
+    parent: String,
+    children: Vec<String>,
+}
+
+pub(crate) struct FileNestingRules {
+    rules: Vec<NestingRule>,
+}
+
+impl FileNestingRules {
+    pub(crate) fn new(settings: &FileNestingSettings) -> Option<Self> {
+        if !settings.enabled {
+            return None;
+        }
+        let rules = settings
+            .patterns
+            .iter()
+            .filter(|(parent, children)| parent.matches('*').count() <= 1 && !children.is_empty())
+            .map(|(parent, children)| NestingRule {
+                parent: parent.clone(),
+                children: children.clone(),
+            })
+            .collect::<Vec<_>>();
+        (!rules.is_empty()).then_some(Self { rules })
+    }
+
+    /// Returns, for each of the sibling files called `names`, the index of the sibling it should
+    /// be nested under.
+    ///
+    /// Rather than matching every pair of siblings, the siblings matching each rule's parent
+    /// pattern are indexed by the text captured by its `*`, and each file only looks up the
+    /// captures that its name could have been derived from.
+    fn parent_indices(&self, names: &[&str]) -> Vec<Option<usize>> {
+        let rule_parents = self
+            .rules
+            .iter()
+            .map(|rule| {
+                let mut parents = RuleParents::default();
+                for (ix, name) in names.iter().enumerate() {
+                    if let Some(captured) = capture(&rule.parent, name) {
+                        parents.by_capture.entry(captured).or_insert(ix);
+                        parents.in_order.push(ix);
+                    }
+                }
+                parents
+            })
+            .collect::<Vec<_>>();
+
+        names
+            .iter()
+            .enumerate()
+            .map(|(ix, name)| {
+                self.rules
+                    .iter()
+                    .zip(&rule_parents)
+                    .find_map(|(rule, parents)| {
+                        rule.children
+                            .iter()
+                            .filter_map(|child| parents.parent_for(child, ix, name))
+                            .min()
+                    })
+            })
+            .collect()
+    }
+
+    /// Moves every nested file of `entries` right after its parent file, dropping the
+    /// nested files of parents that aren't in `expanded_nest_ids`.
+    ///
+    /// `entries` are expected to be sorted, so that siblings appear in display order.
+    pub(crate) fn nest_entries(
+        &self,
+        entries: &mut Vec<Entry>,
+        expanded_nest_ids: &HashSet<ProjectEntryId>,
+        nests: &mut FileNests,
+    ) {
+        let mut files_by_directory = HashMap::<&Path, Vec<usize>>::default();
+        for (ix, entry) in entries.iter().enumerate() {
+            if entry.is_file() && entry.id != NEW_ENTRY_ID {
+                if let Some(directory) = entry.path.parent() {
+                    files_by_directory.entry(directory).or_default().push(ix);
+                }
+            }
+        }
+
+        let mut parent_ixs = HashMap::<usize, usize>::default();
+        for file_ixs in files_by_directory.values() {
+            if file_ixs.len() < 2 {
+                continue;
+            }
+            let names = file_ixs
+                .iter()
+                .map(|ix| file_name(&entries[*ix]))
+                .collect::<Vec<_>>();
+            for (file_ix, parent_position) in file_ixs.iter().zip(self.parent_indices(&names)) {
+                if let Some(parent_position) = parent_position {
+                    parent_ixs.insert(*file_ix, file_ixs[parent_position]);
+                }
+            }
+        }
+        if parent_ixs.is_empty() {
+            return;
+        }
+
+        // Nest every file under its outermost parent, so that nesting is only one level deep.
+        let mut root_parent_ixs = HashMap::<usize, usize>::default();
+        for &file_ix in parent_ixs.keys() {
+            let mut parent_ix = parent_ixs[&file_ix];
+            let mut steps = 0;
+            while let Some(&grandparent_ix) = parent_ixs.get(&parent_ix) {
+                parent_ix = grandparent_ix;
+                steps += 1;
+                if steps > parent_ixs.len() {
+                    break;
+                }
+            }
+            if parent_ix != file_ix && !parent_ixs.contains_key(&parent_ix) {
+                root_parent_ixs.insert(file_ix, parent_ix);
+            }
+        }
+
+        let mut children_by_parent = HashMap::<usize, Vec<usize>>::default();
+        for (&file_ix, &parent_ix) in &root_parent_ixs {
+            children_by_parent
+                .entry(parent_ix)
+                .or_default()
+                .push(file_ix);
+        }
+
+        let old_entries = std::mem::take(entries);
+        let mut taken = old_entries.into_iter().map(Some).collect::<Vec<_>>();
+        for ix in 0..taken.len() {
+            if root_parent_ixs.contains_key(&ix) {
+                continue;
+            }
+            let Some(entry) = taken[ix].take() else {
+                continue;
+            };
+            let Some(mut child_ixs) = children_by_parent.remove(&ix) else {
+                entries.push(entry);
+                continue;
+            };
+            child_ixs.sort_unstable();
+
+            let parent_id = entry.id;
+            let is_expanded = expanded_nest_ids.contains(&parent_id);
+            let mut nested = NestedFiles {
+                children: Vec::with_capacity(child_ixs.len()),
+                git_status: entry.git_status,
+            };
+            entries.push(entry);
+            for child_ix in child_ixs {
+                let Some(child) = taken[child_ix].take() else {
+                    continue;
+                };
+                nested.children.push(child.id);
+                nested.git_status = strongest_git_status(nested.git_status, child.git_status);
+                nests.nest_parents.insert(child.id, parent_id);
+                if is_expanded {
+                    entries.push(child);
+                }
+            }
+            nests.nested_files.insert(parent_id, nested);
+        }
+    }
+}
+
+/// The siblings whose names match a rule's parent pattern.
+#[derive(Default)]
+struct RuleParents<'a> {
+    /// The first sibling for each text captured by the pattern's `*`.
+    by_capture: HashMap<&'a str, usize>,
+    in_order: Vec<usize>,
+}
+
+impl RuleParents<'_> {
+    /// Returns the first of these siblings that the file called `name`, at index `ix` among
+    /// them, matches the child pattern of.
+    fn parent_for(&self, child: &str, ix: usize, name: &str) -> Option<usize> {
+        let Some((before, after)) = child.split_once(CAPTURE) else {
+            // Without a capture, the child pattern applies to the files of every parent.
+            return glob_matches(child, name)
+                .then(|| self.in_order.iter().copied().find(|parent| *parent != ix))
+                .flatten();
+        };
+        let boundaries = name
+            .char_indices()
+            .map(|(offset, _)| offset)
+            .chain([name.len()])
+            .collect::<Vec<_>>();
+        let starts = boundaries
+            .iter()
+            .copied()
+            .filter(|start| glob_matches(before, &name[..*start]));
+        starts
+            .flat_map(|start| {
+                boundaries
+                    .iter()
+                    .copied()
+                    .filter(move |end| *end >= start)
+                    .map(move |end| &name[start..end])
+            })
+            .filter_map(|captured| {
+                let parent = *self.by_capture.get(captured)?;
+                (parent != ix && glob_matches(&child.replace(CAPTURE, captured), name))
+                    .then_some(parent)
+            })
+            .min()
+    }
+}
+
+fn file_name(entry: &Entry) -> &str {
+    entry
+        .path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .unwrap_or_default()
+}
+
+fn strongest_git_status(
+    a: Option<GitFileStatus>,
+    b: Option<GitFileStatus>,
+) -> Option<GitFileStatus> {
+    let rank = |status: Option<GitFileStatus>| match status {
+        Some(GitFileStatus::Conflict) => 3,
+        Some(GitFileStatus::Modified) => 2,
+        Some(GitFileStatus::Added) => 1,
+        None => 0,
+    };
+    if rank(b) > rank(a) {
+        b
+    } else {
+        a
+    }
+}
+
+/// Matches `name` against a pattern with at most one `*`, returning the text matched by it.
+fn capture<'a>(pattern: &str, name: &'a str) -> Option<&'a str> {
+    match pattern.split_once('*') {
+        Some((prefix, suffix)) => {
+            if name.len() >= prefix.len() + suffix.len()
+                && name.starts_with(prefix)
+                && name.ends_with(suffix)
+            {
+                Some(&name[prefix.len()..name.len() - suffix.len()])
+            } else {
+                None
+            }
+        }
+        None => (pattern == name).then_some(""),
+    }
+}
+
+/// Matches `name` against a pattern in which `*` matches any text.
+fn glob_matches(pattern: &str, name: &str) -> bool {
+    let mut parts = pattern.split('*');
+    let Some(first) = parts.next() else {
+        return name.is_empty();
+    };
+    let Some(mut rest) = name.strip_prefix(first) else {
+        return false;
+    };
+    let parts = parts.collect::<Vec<_>>();
+    let Some((last, middle)) = parts.split_last() else {
+        return rest.is_empty();
+    };
+    for part in middle {
+        match rest.find(part) {
+            Some(ix) => rest = &rest[ix + part.len()..],
+            None => return false,
+        }
+    }
+    rest.ends_with(last)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_nesting_patterns() {
+        assert_eq!(capture("*.ts", "foo.ts"), Some("foo"));
+        assert_eq!(capture("*.ts", "foo.js"), None);
+        assert_eq!(capture("Cargo.toml", "Cargo.toml"), Some(""));
+        assert_eq!(capture("a*a", "a"), None);
+
+        assert!(glob_matches("foo.js", "foo.js"));
+        assert!(glob_matches("foo.*.map", "foo.js.map"));
+        assert!(glob_matches("*", "anything"));
+        assert!(!glob_matches("foo.js", "foo.js.map"));
+        assert!(!glob_matches("foo.*.map", "foo.map"));
+
+        let rules = FileNestingRules::new(&FileNestingSettings {
+            enabled: true,
+            patterns: [
+                (
+                    "*.ts".to_string(),
+                    vec!["${capture}.js".to_string(), "${capture}.*.map".to_string()],
+                ),
+                ("Cargo.toml".to_string(), vec!["Cargo.lock".to_string()]),
+            ]
+            .into_iter()
+            .collect(),
+        })
+        .unwrap();
+        let siblings = ["Cargo.lock", "Cargo.toml", "foo.js", "foo.js.map", "foo.ts"];
+        assert_eq!(
+            rules.parent_indices(&siblings),
+            [Some(1), None, Some(4), Some(4), None]
+        );
+
+        // Siblings sharing a prefix are only nested under the file they were derived from.
+        let siblings = ["a.b.js", "a.b.ts", "a.ts", "b.js", "b.ts"];
+        assert_eq!(
+            rules.parent_indices(&siblings),
+            [Some(1), None, None, Some(4), None]
+        );
+    }
+}
@@ -0,0 +1,295 @@
+use anyhow::{Context as _, Result};
+use collections::HashSet;
+use futures::StreamExt as _;
+use gpui::{AsyncAppContext, Model};
+use project::{Entry, Fs};
+use std::{
+    ffi::OsStr,
+    path::{Path, PathBuf},
+    pin::Pin,
+};
+use worktree::{Snapshot, Worktree};
+
+/// The number of bytes sent in each message of a transfer, so that large files are neither
+/// buffered whole on either end nor shown without progress.
+pub(crate) const TRANSFER_CHUNK_SIZE: u64 = 1024 * 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum TransferDirection {
+    Upload,
+    Download,
+}
+
+/// A file or directory to copy between the local disk and a remote worktree.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct TransferEntry {
+    pub source: PathBuf,
+    pub target: PathBuf,
+    pub is_dir: bool,
+    pub size: u64,
+}
+
+/// The progress of a transfer, shown at the bottom of the project panel.
+#[derive(Debug)]
+pub(crate) struct FileTransfer {
+    pub direction: TransferDirection,
+    pub total_files: usize,
+    pub transferred_files: usize,
+    pub total_bytes: u64,
+    pub transferred_bytes: u64,
+}
+
+impl FileTransfer {
+    pub fn new(direction: TransferDirection, entries: &[TransferEntry]) -> Self {
+        let files = entries.iter().filter(|entry| !entry.is_dir);
+        Self {
+            direction,
+            total_files: files.clone().count(),
+            transferred_files: 0,
+            total_bytes: files.map(|entry| entry.size).sum(),
+            transferred_bytes: 0,
+        }
+    }
+
+    pub fn record_chunk(&mut self, len: u64) {
+        self.transferred_bytes += len;
+    }
+
+    pub fn record_entry(&mut self, entry: &TransferEntry) {
+        if !entry.is_dir {
+            self.transferred_files += 1;
+        }
+    }
+
+    pub fn label(&self) -> String {
+        let verb = match self.direction {
+            TransferDirection::Upload => "Uploading",
+            TransferDirection::Download => "Downloading",
+        };
+        let percentage = if self.total_bytes == 0 {
+            100
+        } else {
+            (self.transferred_bytes * 100 / self.total_bytes).min(100)
+        };
+        format!(
+            "{verb} {} of {} files ({percentage}%)",
+            self.transferred_files, self.total_files
+        )
+    }
+}
+
+/// Reads the chunk of a file that starts at `offset`, from the local disk when uploading and from
+/// the worktree when downloading.
+pub(crate) async fn read_chunk(
+    direction: TransferDirection,
+    fs: &dyn Fs,
+    worktree: &Model<Worktree>,
+    entry: &TransferEntry,
+    offset: u64,
+    cx: &mut AsyncAppContext,
+) -> Result<Vec<u8>> {
+    let range = offset..offset + TRANSFER_CHUNK_SIZE;
+    match direction {
+        TransferDirection::Upload => fs.load_bytes_range(&entry.source, range).await,
+        TransferDirection::Download => {
+            worktree
+                .update(cx, |worktree, cx| {
+                    worktree.read_file(&entry.source, range, cx)
+                })?
+                .await
+        }
+    }
+}
+
+/// Writes a chunk read by [`read_chunk`] to the target of the entry, creating the file with the
+/// first chunk and appending the following ones.
+pub(crate) async fn write_chunk(
+    direction: TransferDirection,
+    fs: &dyn Fs,
+    worktree: &Model<Worktree>,
+    entry: &TransferEntry,
+    offset: u64,
+    chunk: Vec<u8>,
+    cx: &mut AsyncAppContext,
+) -> Result<()> {
+    match direction {
+        TransferDirection::Upload if offset == 0 => {
+            worktree
+                .update(cx, |worktree, cx| {
+                    worktree.create_entry(entry.target.clone(), false, Some(chunk), cx)
+                })?
+                .await?;
+            Ok(())
+        }
+        TransferDirection::Upload => {
+            worktree
+                .update(cx, |worktree, cx| {
+                    worktree.append_to_file(&entry.target, chunk, cx)
+                })?
+                .await
+        }
+        TransferDirection::Download if offset == 0 => {
+            fs.create_file_with(&entry.target, Pin::new(&mut chunk.as_slice()))
+                .await
+        }
+        TransferDirection::Download => fs.append_bytes(&entry.target, &chunk).await,
+    }
+}
+
+/// Lists the local files and directories to upload into `target_dir`, with every directory
+/// preceding its contents.
+pub(crate) async fn local_entries(
+    fs: &dyn Fs,
+    paths: &[PathBuf],
+    target_dir: &Path,
+) -> Result<Vec<TransferEntry>> {
+    let mut entries = Vec::new();
+    let mut pending = paths
+        .iter()
+        .filter_map(|path| Some((path.clone(), target_dir.join(path.file_name()?))))
+        .collect::<Vec<_>>();
+    while let Some((source, target)) = pending.pop() {
+        let metadata = fs
+            .metadata(&source)
+            .await?
+            .with_context(|| format!("{source:?} does not exist"))?;
+        if metadata.is_dir {
+            let mut children = fs.read_dir(&source).await?;
+            while let Some(child) = children.next().await {
+                let child = child?;
+                if let Some(name) = child.file_name() {
+                    pending.push((child.clone(), target.join(name)));
+                }
+            }
+        }
+        entries.push(TransferEntry {
+            source,
+            target,
+            is_dir: metadata.is_dir,
+            size: if metadata.is_dir { 0 } else { metadata.len },
+        });
+    }
+    entries.sort_by(|a, b| a.target.cmp(&b.target));
+    Ok(entries)
+}
+
+/// Loads the directories under `path` that the worktree hasn't scanned yet, such as ignored ones,
+/// so that their contents are listed in its snapshot.
+pub(crate) async fn load_unloaded_directories(
+    worktree: &Model<Worktree>,
+    path: &Path,
+    cx: &mut AsyncAppContext,
+) -> Result<()> {
+    let mut expanded_entries = HashSet::default();
+    loop {
+        let tasks = worktree.update(cx, |worktree, cx| {
+            let unloaded_entries = worktree
+                .entries(true, 0)
+                .filter(|entry| entry.kind.is_unloaded() && entry.path.starts_with(path))
+                .map(|entry| entry.id)
+                .filter(|entry_id| expanded_entries.insert(*entry_id))
+                .collect::<Vec<_>>();
+            unloaded_entries
+                .into_iter()
+                .filter_map(|entry_id| worktree.expand_entry(entry_id, cx))
+                .collect::<Vec<_>>()
+        })?;
+        if tasks.is_empty() {
+            return Ok(());
+        }
+        for task in tasks {
+            task.await?;
+        }
+    }
+}
+
+/// Lists the files and directories of a remote worktree to download into the local `target_dir`,
+/// with every directory preceding its contents.
+pub(crate) fn remote_entries(
+    snapshot: &Snapshot,
+    entry: &Entry,
+    target_dir: &Path,
+) -> Vec<TransferEntry> {
+    let name = entry
+        .path
+        .file_name()
+        .map(OsStr::to_os_string)
+        .unwrap_or_else(|| snapshot.root_name().into());
+    let target_root = target_dir.join(name);
+    snapshot
+        .entries(true, 0)
+        .filter(|child| child.path.starts_with(&entry.path))
+        .filter_map(|child| {
+            let relative_path = child.path.strip_prefix(&entry.path).ok()?;
+            Some(TransferEntry {
+                source: child.path.to_path_buf(),
+                target: if relative_path == Path::new("") {
+                    target_root.clone()
+                } else {
+                    target_root.join(relative_path)
+                },
+                is_dir: child.is_dir(),
+                size: child.size,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::TestAppContext;
+    use project::FakeFs;
+    use serde_json::json;
+
+    #[gpui::test]
+    async fn test_local_entries(cx: &mut TestAppContext) {
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            "/local",
+            json!({
+                "notes.txt": "hello",
+                "assets": {
+                    "logo.svg": "<svg/>",
+                    "fonts": { "mono.ttf": "font" },
+                },
+            }),
+        )
+        .await;
+
+        let entries = local_entries(
+            fs.as_ref(),
+            &[
+                PathBuf::from("/local/assets"),
+                PathBuf::from("/local/notes.txt"),
+            ],
+            Path::new("src"),
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            entries
+                .iter()
+                .map(|entry| (entry.target.to_str().unwrap(), entry.is_dir))
+                .collect::<Vec<_>>(),
+            [
+                ("src/assets", true),
+                ("src/assets/fonts", true),
+                ("src/assets/fonts/mono.ttf", false),
+                ("src/assets/logo.svg", false),
+                ("src/notes.txt", false),
+            ]
+        );
+
+        let mut transfer = FileTransfer::new(TransferDirection::Upload, &entries);
+        assert_eq!(transfer.label(), "Uploading 0 of 3 files (0%)");
+        for entry in &entries[..2] {
+            transfer.record_entry(entry);
+        }
+        transfer.record_chunk(2);
+        assert_eq!(transfer.label(), "Uploading 0 of 3 files (13%)");
+        transfer.record_chunk(2);
+        transfer.record_entry(&entries[2]);
+        assert_eq!(transfer.label(), "Uploading 1 of 3 files (26%)");
+    }
+}
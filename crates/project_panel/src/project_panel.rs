@@ -1,3 +1,5 @@
+mod file_nesting;
+mod file_transfer;
 mod project_panel_settings;
 mod utils;
 
@@ -15,6 +17,8 @@ use editor::{
     Editor, EditorEvent, EditorSettings, ShowScrollbar,
 };
 use file_icons::FileIcons;
+use file_nesting::{FileNestingRules, FileNests};
+use file_transfer::{FileTransfer, TransferDirection, TransferEntry, TRANSFER_CHUNK_SIZE};
 
 use anyhow::{anyhow, Context as _, Result};
 use collections::{hash_map, BTreeSet, HashMap};
@@ -25,15 +29,16 @@ use gpui::{
     AnyElement, AppContext, AssetSource, AsyncWindowContext, Bounds, ClipboardItem, DismissEvent,
     Div, DragMoveEvent, EventEmitter, ExternalPaths, FocusHandle, FocusableView, Hsla,
     InteractiveElement, KeyContext, ListHorizontalSizingBehavior, ListSizingBehavior, Model,
-    MouseButton, MouseDownEvent, ParentElement, Pixels, Point, PromptLevel, Render, ScrollStrategy,
-    Stateful, Styled, Subscription, Task, UniformListScrollHandle, View, ViewContext,
-    VisualContext as _, WeakView, WindowContext,
+    MouseButton, MouseDownEvent, ParentElement, PathPromptOptions, Pixels, Point, PromptLevel,
+    Render, ScrollStrategy, Stateful, Styled, Subscription, Task, UniformListScrollHandle, View,
+    ViewContext, VisualContext as _, WeakView, WindowContext,
 };
+use image_viewer::is_diffable_image;
 use indexmap::IndexMap;
 use menu::{Confirm, SelectFirst, SelectLast, SelectNext, SelectPrev};
 use project::{
-    relativize_path, Entry, EntryKind, Fs, Project, ProjectEntryId, ProjectPath, Worktree,
-    WorktreeId,
+    relativize_path, Entry, EntryKind, Fs, Project, ProjectEntryId, ProjectPath, RemoveOptions,
+    Worktree, WorktreeId,
 };
 use project_panel_settings::{
     ProjectPanelDockPosition, ProjectPanelSettings, ShowDiagnostics, ShowIndentGuides,
@@ -81,6 +86,9 @@ pub struct ProjectPanel {
     /// Relevant only for auto-fold dirs, where a single project panel entry may actually consist of several
     /// project entries (and all non-leaf nodes are guaranteed to be directories).
     ancestors: HashMap<ProjectEntryId, FoldedAncestors>,
+    /// Files nested under other files by the file nesting rules, for all visible worktrees.
+    file_nests: FileNests,
+    expanded_nest_ids: HashSet<ProjectEntryId>,
     last_worktree_root_id: Option<ProjectEntryId>,
     last_external_paths_drag_over_entry: Option<ProjectEntryId>,
     expanded_dir_ids: HashMap<WorktreeId, Vec<ProjectEntryId>>,
@@ -105,6 +113,7 @@ pub struct ProjectPanel {
     // We keep track of the mouse down state on entries so we don't flash the UI
     // in case a user clicks to open a file.
     mouse_down: bool,
+    file_transfer: Option<FileTransfer>,
 }
 
 #[derive(Clone, Debug)]
@@ -139,6 +148,8 @@ struct EntryDetails {
     kind: EntryKind,
     is_ignored: bool,
     is_expanded: bool,
+    /// Whether the files nested under this entry are shown, if it has any.
+    nested_files_expanded: Option<bool>,
     is_selected: bool,
     is_marked: bool,
     is_editing: bool,
@@ -181,6 +192,7 @@ actions!(
         RevealInFileManager,
         RemoveFromProject,
         OpenWithSystem,
+        OpenImageDiff,
         Cut,
         Paste,
         Rename,
@@ -197,6 +209,8 @@ actions!(
         SelectPrevDiagnostic,
         SelectNextDirectory,
         SelectPrevDirectory,
+        Upload,
+        Download,
     ]
 );
 
@@ -359,11 +373,14 @@ impl ProjectPanel {
             })
             .detach();
 
-            let mut project_panel_settings = *ProjectPanelSettings::get_global(cx);
+            let mut project_panel_settings = ProjectPanelSettings::get_global(cx).clone();
             cx.observe_global::<SettingsStore>(move |this, cx| {
-                let new_settings = *ProjectPanelSettings::get_global(cx);
-                if project_panel_settings != new_settings {
-                    project_panel_settings = new_settings;
+                let new_settings = ProjectPanelSettings::get_global(cx);
+                if project_panel_settings != *new_settings {
+                    if project_panel_settings.file_nesting != new_settings.file_nesting {
+                        this.update_visible_entries(None, cx);
+                    }
+                    project_panel_settings = ProjectPanelSettings::get_global(cx).clone();
                     this.update_diagnostics(cx);
                     cx.notify();
                 }
@@ -378,6 +395,8 @@ impl ProjectPanel {
                 focus_handle,
                 visible_entries: Default::default(),
                 ancestors: Default::default(),
+                file_nests: Default::default(),
+                expanded_nest_ids: Default::default(),
                 last_worktree_root_id: Default::default(),
                 last_external_paths_drag_over_entry: None,
                 expanded_dir_ids: Default::default(),
@@ -402,6 +421,7 @@ impl ProjectPanel {
                 diagnostics: Default::default(),
                 scroll_handle,
                 mouse_down: false,
+                file_transfer: None,
             };
             this.update_visible_entries(None, cx);
 
@@ -631,6 +651,9 @@ impl ProjectPanel {
             let is_read_only = project.is_read_only(cx);
             let is_remote = project.is_via_collab();
             let is_local = project.is_local();
+            let is_via_ssh = project.is_via_ssh();
+            let has_image_diff =
+                is_local && entry.git_status.is_some() && is_diffable_image(&entry.path);
 
             let context_menu = ContextMenu::build(cx, |menu, _| {
                 menu.context(self.focus_handle.clone()).map(|menu| {
@@ -652,6 +675,9 @@ impl ProjectPanel {
                                 menu.action("Open in Default App", Box::new(OpenWithSystem))
                             })
                             .action("Open in Terminal", Box::new(OpenInTerminal))
+                            .when(has_image_diff, |menu| {
+                                menu.action("Open Image Diff", Box::new(OpenImageDiff))
+                            })
                             .when(is_dir, |menu| {
                                 menu.separator()
                                     .action("Find in Folder…", Box::new(NewSearchInDirectory))
@@ -677,6 +703,11 @@ impl ProjectPanel {
                             .separator()
                             .action("Copy Path", Box::new(CopyPath))
                             .action("Copy Relative Path", Box::new(CopyRelativePath))
+                            .when(is_via_ssh, |menu| {
+                                menu.separator()
+                                    .action("Upload Files…", Box::new(Upload))
+                                    .action("Download…", Box::new(Download))
+                            })
                             .separator()
                             .action("Rename", Box::new(Rename))
                             .when(!is_root & !is_remote, |menu| {
@@ -745,6 +776,14 @@ impl ProjectPanel {
 
     fn expand_selected_entry(&mut self, _: &ExpandSelectedEntry, cx: &mut ViewContext<Self>) {
         if let Some((worktree, entry)) = self.selected_entry(cx) {
+            if self.file_nests.nested_files.contains_key(&entry.id) {
+                if self.expanded_nest_ids.contains(&entry.id) {
+                    self.select_next(&SelectNext, cx);
+                } else {
+                    self.toggle_nested_files(entry.id, cx);
+                }
+                return;
+            }
             if let Some(folded_ancestors) = self.ancestors.get_mut(&entry.id) {
                 if folded_ancestors.current_ancestor_depth > 0 {
                     folded_ancestors.current_ancestor_depth -= 1;
@@ -791,6 +830,14 @@ impl ProjectPanel {
         worktree: Model<Worktree>,
         cx: &mut ViewContext<Self>,
     ) {
+        if let Some(nest_parent_id) = self.file_nests.nest_parents.get(&entry.id).copied() {
+            self.toggle_nested_files(nest_parent_id, cx);
+            return;
+        }
+        if self.expanded_nest_ids.contains(&entry.id) {
+            self.toggle_nested_files(entry.id, cx);
+            return;
+        }
         let worktree = worktree.read(cx);
         if let Some(folded_ancestors) = self.ancestors.get_mut(&entry.id) {
             if folded_ancestors.current_ancestor_depth + 1 < folded_ancestors.max_ancestor_depth() {
@@ -860,6 +907,21 @@ impl ProjectPanel {
         }
     }
 
+    /// Shows or hides the files nested under the given file, selecting the file
+    /// itself if the selection would otherwise become hidden.
+    fn toggle_nested_files(&mut self, entry_id: ProjectEntryId, cx: &mut ViewContext<Self>) {
+        if !self.expanded_nest_ids.remove(&entry_id) {
+            self.expanded_nest_ids.insert(entry_id);
+        } else if let Some(selection) = &mut self.selection {
+            if self.file_nests.nest_parents.get(&selection.entry_id) == Some(&entry_id) {
+                selection.entry_id = entry_id;
+            }
+        }
+        self.update_visible_entries(None, cx);
+        cx.focus(&self.focus_handle);
+        cx.notify();
+    }
+
     fn select_prev(&mut self, _: &SelectPrev, cx: &mut ViewContext<Self>) {
         if let Some(edit_state) = &self.edit_state {
             if edit_state.processing_filename.is_none() {
@@ -1998,6 +2060,91 @@ impl ProjectPanel {
         }
     }
 
+    fn upload(&mut self, _: &Upload, cx: &mut ViewContext<Self>) {
+        let Some((_, entry)) = self.selected_sub_entry(cx) else {
+            return;
+        };
+        let entry_id = entry.id;
+        let paths = cx.prompt_for_paths(PathPromptOptions {
+            files: true,
+            directories: true,
+            multiple: true,
+        });
+        cx.spawn(|this, mut cx| async move {
+            let Some(paths) = paths.await?? else {
+                return Ok(());
+            };
+            this.update(&mut cx, |this, cx| {
+                this.upload_external_files(&paths, entry_id, cx)
+            })
+        })
+        .detach_and_prompt_err("Failed to upload files", cx, |_, _| None);
+    }
+
+    fn download(&mut self, _: &Download, cx: &mut ViewContext<Self>) {
+        let Some((worktree, entry)) = self.selected_sub_entry(cx) else {
+            return;
+        };
+        let entry = entry.clone();
+        let destination = cx.prompt_for_paths(PathPromptOptions {
+            files: false,
+            directories: true,
+            multiple: false,
+        });
+        let fs = self.fs.clone();
+        cx.spawn(|this, mut cx| async move {
+            let Some(destination) = destination
+                .await??
+                .and_then(|paths| paths.into_iter().next())
+            else {
+                return Ok(());
+            };
+            file_transfer::load_unloaded_directories(&worktree, &entry.path, &mut cx).await?;
+            let entries = worktree.update(&mut cx, |worktree, _| {
+                file_transfer::remote_entries(&worktree.snapshot(), &entry, &destination)
+            })?;
+            let Some(root) = entries.first() else {
+                return Ok(());
+            };
+            if let Some(metadata) = fs.metadata(&root.target).await? {
+                let filename = root.target.file_name().unwrap_or_default().to_string_lossy();
+                let answer = cx
+                    .prompt(
+                        PromptLevel::Info,
+                        format!("A file or folder with name {filename} already exists in the destination folder. Do you want to replace it?").as_str(),
+                        None,
+                        &["Replace", "Cancel"],
+                    )
+                    .await?;
+                if answer == 1 {
+                    return Ok(());
+                }
+                let options = RemoveOptions {
+                    recursive: true,
+                    ignore_if_not_exists: true,
+                };
+                if metadata.is_dir {
+                    fs.remove_dir(&root.target, options).await?;
+                } else {
+                    fs.remove_file(&root.target, options).await?;
+                }
+            }
+            this.update(&mut cx, |this, cx| {
+                this.transfer_files(TransferDirection::Download, entries, worktree, cx)
+            })?
+            .await
+        })
+        .detach_and_prompt_err("Failed to download files", cx, |_, _| None);
+    }
+
+    fn open_image_diff(&mut self, _: &OpenImageDiff, cx: &mut ViewContext<Self>) {
+        if let Some((worktree, entry)) = self.selected_sub_entry(cx) {
+            if let Ok(abs_path) = worktree.read(cx).absolutize(&entry.path) {
+                cx.dispatch_action(workspace::OpenImageDiff { abs_path }.boxed_clone())
+            }
+        }
+    }
+
     pub fn new_search_in_directory(
         &mut self,
         _: &NewSearchInDirectory,
@@ -2169,7 +2316,28 @@ impl ProjectPanel {
             }
         }
 
-        sanitized_entries
+        self.with_collapsed_nested_files(sanitized_entries)
+    }
+
+    /// Adds the files nested under any of the given entries whose nested files are hidden,
+    /// so that operations on a collapsed file apply to its nested files as well.
+    fn with_collapsed_nested_files(
+        &self,
+        entries: impl IntoIterator<Item = SelectedEntry>,
+    ) -> BTreeSet<SelectedEntry> {
+        let mut result = BTreeSet::new();
+        for entry in entries {
+            if !self.expanded_nest_ids.contains(&entry.entry_id) {
+                if let Some(nested) = self.file_nests.nested_files.get(&entry.entry_id) {
+                    result.extend(nested.children.iter().map(|&entry_id| SelectedEntry {
+                        entry_id,
+                        worktree_id: entry.worktree_id,
+                    }));
+                }
+            }
+            result.insert(entry);
+        }
+        result
     }
 
     // Returns the union of the currently selected entry and all marked entries.
@@ -2244,6 +2412,9 @@ impl ProjectPanel {
 
     fn expand_to_selection(&mut self, cx: &mut ViewContext<Self>) -> Option<()> {
         let (worktree, entry) = self.selected_entry(cx)?;
+        if let Some(nest_parent_id) = self.file_nests.nest_parents.get(&entry.id) {
+            self.expanded_nest_ids.insert(*nest_parent_id);
+        }
         let expanded_dir_ids = self.expanded_dir_ids.entry(worktree.id()).or_default();
 
         for path in entry.path.ancestors() {
@@ -2265,7 +2436,9 @@ impl ProjectPanel {
         new_selected_entry: Option<(WorktreeId, ProjectEntryId)>,
         cx: &mut ViewContext<Self>,
     ) {
-        let auto_collapse_dirs = ProjectPanelSettings::get_global(cx).auto_fold_dirs;
+        let settings = ProjectPanelSettings::get_global(cx);
+        let auto_collapse_dirs = settings.auto_fold_dirs;
+        let nesting_rules = FileNestingRules::new(&settings.file_nesting);
         let project = self.project.read(cx);
         self.last_worktree_root_id = project
             .visible_worktrees(cx)
@@ -2274,6 +2447,7 @@ impl ProjectPanel {
             .map(|entry| entry.id);
 
         let old_ancestors = std::mem::take(&mut self.ancestors);
+        self.file_nests = FileNests::default();
         self.visible_entries.clear();
         let mut max_width_item = None;
         for worktree in project.visible_worktrees(cx) {
@@ -2460,6 +2634,13 @@ impl ProjectPanel {
 
             snapshot.propagate_git_statuses(&mut visible_worktree_entries);
             project::sort_worktree_entries(&mut visible_worktree_entries);
+            if let Some(nesting_rules) = &nesting_rules {
+                nesting_rules.nest_entries(
+                    &mut visible_worktree_entries,
+                    &self.expanded_nest_ids,
+                    &mut self.file_nests,
+                );
+            }
             self.visible_entries
                 .push((worktree_id, visible_worktree_entries, OnceCell::new()));
         }
@@ -2494,6 +2675,9 @@ impl ProjectPanel {
         entry_id: ProjectEntryId,
         cx: &mut ViewContext<Self>,
     ) {
+        if let Some(nest_parent_id) = self.file_nests.nest_parents.get(&entry_id) {
+            self.expanded_nest_ids.insert(*nest_parent_id);
+        }
         self.project.update(cx, |project, cx| {
             if let Some((worktree, expanded_dir_ids)) = project
                 .worktree_for_id(worktree_id, cx)
@@ -2527,6 +2711,11 @@ impl ProjectPanel {
         entry_id: ProjectEntryId,
         cx: &mut ViewContext<Self>,
     ) {
+        if self.project.read(cx).is_via_ssh() {
+            self.upload_external_files(paths, entry_id, cx);
+            return;
+        }
+
         let mut paths: Vec<Arc<Path>> = paths.iter().map(|path| Arc::from(path.clone())).collect();
 
         let open_file_after_drop = paths.len() == 1 && paths[0].is_file();
@@ -2594,6 +2783,191 @@ impl ProjectPanel {
         .detach();
     }
 
+    /// Copies local files and folders into a remote worktree, through the project's connection.
+    fn upload_external_files(
+        &mut self,
+        paths: &[PathBuf],
+        entry_id: ProjectEntryId,
+        cx: &mut ViewContext<Self>,
+    ) {
+        let Some((worktree, target_directory)) = maybe!({
+            let worktree = self.project.read(cx).worktree_for_entry(entry_id, cx)?;
+            let entry = worktree.read(cx).entry_for_id(entry_id)?;
+            let target_directory: Arc<Path> = if entry.is_dir() {
+                entry.path.clone()
+            } else {
+                Arc::from(entry.path.parent()?)
+            };
+            Some((worktree, target_directory))
+        }) else {
+            return;
+        };
+
+        let fs = self.fs.clone();
+        let paths = paths.to_vec();
+        cx.spawn(|this, mut cx| async move {
+            let mut entries =
+                file_transfer::local_entries(fs.as_ref(), &paths, &target_directory).await?;
+            let existing_entries = worktree.update(&mut cx, |worktree, _| {
+                paths
+                    .iter()
+                    .filter_map(|path| {
+                        let target_path = target_directory.join(path.file_name()?);
+                        let entry_id = worktree.entry_for_path(&target_path)?.id;
+                        Some((target_path, entry_id))
+                    })
+                    .collect::<Vec<_>>()
+            })?;
+            let mut replaced_entries = Vec::new();
+            for (target_path, entry_id) in existing_entries {
+                let filename = target_path.file_name().unwrap_or_default().to_string_lossy();
+                let answer = cx
+                    .prompt(
+                        PromptLevel::Info,
+                        format!("A file or folder with name {filename} already exists in the destination folder. Do you want to replace it?").as_str(),
+                        None,
+                        &["Replace", "Cancel"],
+                    )
+                    .await?;
+                if answer == 1 {
+                    entries.retain(|entry| !entry.target.starts_with(&target_path));
+                } else {
+                    replaced_entries.push(entry_id);
+                }
+            }
+            if entries.is_empty() {
+                return Ok(());
+            }
+            for entry_id in replaced_entries {
+                if let Some(task) = worktree.update(&mut cx, |worktree, cx| {
+                    worktree.delete_entry(entry_id, false, cx)
+                })? {
+                    task.await?;
+                }
+            }
+
+            this.update(&mut cx, |this, cx| {
+                this.transfer_files(TransferDirection::Upload, entries, worktree, cx)
+            })?
+            .await
+        })
+        .detach_and_prompt_err("Failed to upload files", cx, |_, _| None);
+    }
+
+    /// Copies files between the local disk and a remote worktree one chunk at a time, showing the
+    /// progress at the bottom of the panel.
+    fn transfer_files(
+        &mut self,
+        direction: TransferDirection,
+        entries: Vec<TransferEntry>,
+        worktree: Model<Worktree>,
+        cx: &mut ViewContext<Self>,
+    ) -> Task<Result<()>> {
+        if self.file_transfer.is_some() {
+            return Task::ready(Err(anyhow!(
+                "Wait for the current file transfer to finish before starting another one"
+            )));
+        }
+        self.file_transfer = Some(FileTransfer::new(direction, &entries));
+        cx.notify();
+
+        let fs = self.fs.clone();
+        cx.spawn(|this, mut cx| async move {
+            let result = async {
+                for entry in &entries {
+                    if entry.is_dir {
+                        match direction {
+                            TransferDirection::Upload => {
+                                worktree
+                                    .update(&mut cx, |worktree, cx| {
+                                        worktree.create_entry(entry.target.clone(), true, None, cx)
+                                    })?
+                                    .await?;
+                            }
+                            TransferDirection::Download => fs.create_dir(&entry.target).await?,
+                        }
+                    } else {
+                        let mut offset = 0;
+                        loop {
+                            let chunk = file_transfer::read_chunk(
+                                direction,
+                                fs.as_ref(),
+                                &worktree,
+                                entry,
+                                offset,
+                                &mut cx,
+                            )
+                            .await?;
+                            let chunk_len = chunk.len() as u64;
+                            file_transfer::write_chunk(
+                                direction,
+                                fs.as_ref(),
+                                &worktree,
+                                entry,
+                                offset,
+                                chunk,
+                                &mut cx,
+                            )
+                            .await
+                            .with_context(|| format!("writing {:?}", entry.target))?;
+                            offset += chunk_len;
+                            this.update(&mut cx, |this, cx| {
+                                if let Some(file_transfer) = &mut this.file_transfer {
+                                    file_transfer.record_chunk(chunk_len);
+                                }
+                                cx.notify();
+                            })?;
+                            if chunk_len < TRANSFER_CHUNK_SIZE {
+                                break;
+                            }
+                        }
+                    }
+                    this.update(&mut cx, |this, cx| {
+                        if let Some(file_transfer) = &mut this.file_transfer {
+                            file_transfer.record_entry(entry);
+                        }
+                        cx.notify();
+                    })?;
+                }
+                anyhow::Ok(())
+            }
+            .await;
+
+            this.update(&mut cx, |this, cx| {
+                this.file_transfer = None;
+                cx.notify();
+            })?;
+            result
+        })
+    }
+
+    fn render_file_transfer(&self, cx: &mut ViewContext<Self>) -> Option<impl IntoElement> {
+        let file_transfer = self.file_transfer.as_ref()?;
+        let icon = match file_transfer.direction {
+            TransferDirection::Upload => IconName::ArrowUp,
+            TransferDirection::Download => IconName::Download,
+        };
+        Some(
+            h_flex()
+                .absolute()
+                .bottom_0()
+                .left_0()
+                .right_0()
+                .gap_1()
+                .px_2()
+                .py_1()
+                .bg(cx.theme().colors().panel_background)
+                .border_t_1()
+                .border_color(cx.theme().colors().border)
+                .child(Icon::new(icon).size(IconSize::Small).color(Color::Muted))
+                .child(
+                    Label::new(file_transfer.label())
+                        .size(LabelSize::Small)
+                        .color(Color::Muted),
+                ),
+        )
+    }
+
     fn drag_onto(
         &mut self,
         selections: &DraggedSelection,
@@ -2602,6 +2976,7 @@ impl ProjectPanel {
         cx: &mut ViewContext<Self>,
     ) {
         let should_copy = cx.modifiers().alt;
+        let dragged_entries = self.with_collapsed_nested_files(selections.items().copied());
         if should_copy {
             let _ = maybe!({
                 let project = self.project.read(cx);
@@ -2610,7 +2985,7 @@ impl ProjectPanel {
                     .read(cx)
                     .entry_for_id(target_entry_id)?
                     .clone();
-                for selection in selections.items() {
+                for selection in &dragged_entries {
                     let new_path = self.create_paste_path(
                         selection,
                         (target_worktree.clone(), &target_entry),
@@ -2626,7 +3001,7 @@ impl ProjectPanel {
                 Some(())
             });
         } else {
-            for selection in selections.items() {
+            for selection in &dragged_entries {
                 self.move_entry(selection.entry_id, target_entry_id, is_file, cx);
             }
         }
@@ -2743,7 +3118,16 @@ impl ProjectPanel {
                         .collect()
                 });
                 for entry in visible_worktree_entries[entry_range].iter() {
-                    let status = git_status_setting.then_some(entry.git_status).flatten();
+                    let nested_files = self.file_nests.nested_files.get(&entry.id);
+                    let nested_files_expanded =
+                        nested_files.map(|_| self.expanded_nest_ids.contains(&entry.id));
+                    let git_status = match nested_files {
+                        Some(nested_files) if nested_files_expanded == Some(false) => {
+                            nested_files.git_status
+                        }
+                        _ => entry.git_status,
+                    };
+                    let status = git_status_setting.then_some(git_status).flatten();
                     let is_expanded = expanded_entry_ids.binary_search(&entry.id).is_ok();
                     let icon = match entry.kind {
                         EntryKind::File => {
@@ -2755,15 +3139,18 @@ impl ProjectPanel {
                         }
                         _ => {
                             if show_folder_icons {
-                                FileIcons::get_folder_icon(is_expanded, cx)
+                                FileIcons::get_folder_icon(is_expanded, &entry.path, cx)
                             } else {
                                 FileIcons::get_chevron_icon(is_expanded, cx)
                             }
                         }
                     };
 
-                    let (depth, difference) =
+                    let (mut depth, difference) =
                         ProjectPanel::calculate_depth_and_difference(entry, entries);
+                    if self.file_nests.nest_parents.contains_key(&entry.id) {
+                        depth += 1;
+                    }
 
                     let filename = match difference {
                         diff if diff > 1 => entry
@@ -2803,6 +3190,7 @@ impl ProjectPanel {
                         kind: entry.kind,
                         is_ignored: entry.is_ignored,
                         is_expanded,
+                        nested_files_expanded,
                         is_selected: self.selection == Some(selection),
                         is_marked,
                         is_editing: false,
@@ -3185,9 +3573,13 @@ impl ProjectPanel {
         let path = details.path.clone();
 
         let depth = details.depth;
+        let nested_files_expanded = details.nested_files_expanded;
         let worktree_id = details.worktree_id;
         let selections = Arc::new(self.marked_entries.clone());
-        let is_local = self.project.read(cx).is_local();
+        let accepts_external_paths = {
+            let project = self.project.read(cx);
+            project.is_local() || project.is_via_ssh()
+        };
 
         let dragged_selection = DraggedSelection {
             active_selection: selection,
@@ -3227,7 +3619,7 @@ impl ProjectPanel {
             .when(!is_marked && !is_active, |div| {
                 div.hover(|style| style.bg(bg_hover_color))
             })
-            .when(is_local, |div| {
+            .when(accepts_external_paths, |div| {
                 div.on_drag_move::<ExternalPaths>(cx.listener(
                     move |this, event: &DragMoveEvent<ExternalPaths>, cx| {
                         if event.bounds.contains(&event.event.position) {
@@ -3243,8 +3635,7 @@ impl ProjectPanel {
                                     .read(cx)
                                     .worktree_for_id(selection.worktree_id, cx)?;
                                 let worktree = worktree.read(cx);
-                                let abs_path = worktree.absolutize(&path).log_err()?;
-                                let path = if abs_path.is_dir() {
+                                let path = if worktree.entry_for_path(&path)?.is_dir() {
                                     path.as_ref()
                                 } else {
                                     path.parent()?
@@ -3366,6 +3757,12 @@ impl ProjectPanel {
                 ListItem::new(entry_id.to_proto() as usize)
                     .indent_level(depth)
                     .indent_step_size(px(settings.indent_size))
+                    .when_some(nested_files_expanded, |this, is_expanded| {
+                        this.toggle(is_expanded)
+                            .on_toggle(cx.listener(move |this, _, cx| {
+                                this.toggle_nested_files(entry_id, cx);
+                            }))
+                    })
                     .selectable(false)
                     .when_some(canonical_path, |this, path| {
                         this.end_slot::<AnyElement>(
@@ -3966,9 +4363,12 @@ impl Render for ProjectPanel {
                     el.on_action(cx.listener(Self::reveal_in_finder))
                         .on_action(cx.listener(Self::open_system))
                         .on_action(cx.listener(Self::open_in_terminal))
+                        .on_action(cx.listener(Self::open_image_diff))
                 })
                 .when(project.is_via_ssh(), |el| {
                     el.on_action(cx.listener(Self::open_in_terminal))
+                        .on_action(cx.listener(Self::upload))
+                        .on_action(cx.listener(Self::download))
                 })
                 .on_mouse_down(
                     MouseButton::Right,
@@ -4000,10 +4400,13 @@ impl Render for ProjectPanel {
                                 |this, range, cx| {
                                     let mut items =
                                         SmallVec::with_capacity(range.end - range.start);
+                                    let nest_parents = &this.file_nests.nest_parents;
                                     this.iter_visible_entries(range, cx, |entry, entries, _| {
                                         let (depth, _) =
                                             Self::calculate_depth_and_difference(entry, entries);
-                                        items.push(depth);
+                                        let nesting_depth =
+                                            nest_parents.contains_key(&entry.id) as usize;
+                                        items.push(depth + nesting_depth);
                                     });
                                     items
                                 },
@@ -4095,6 +4498,7 @@ impl Render for ProjectPanel {
                     .track_scroll(self.scroll_handle.clone()),
                 )
                 .children(self.render_vertical_scrollbar(cx))
+                .children(self.render_file_transfer(cx))
                 .when_some(self.render_horizontal_scrollbar(cx), |this, scrollbar| {
                     this.pb_4().child(scrollbar)
                 })
@@ -4277,6 +4681,7 @@ impl ClipboardEntry {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::project_panel_settings::FileNestingSettingsContent;
     use collections::HashSet;
     use gpui::{Empty, TestAppContext, View, VisualTestContext, WindowHandle};
     use pretty_assertions::assert_eq;
@@ -7769,6 +8174,112 @@ mod tests {
         );
     }
 
+    #[gpui::test]
+    async fn test_file_nesting(cx: &mut gpui::TestAppContext) {
+        init_test(cx);
+        cx.update(|cx| {
+            cx.update_global::<SettingsStore, _>(|store, cx| {
+                store.update_user_settings::<ProjectPanelSettings>(cx, |project_panel_settings| {
+                    project_panel_settings.file_nesting = Some(FileNestingSettingsContent {
+                        enabled: Some(true),
+                        patterns: None,
+                    });
+                });
+            });
+        });
+
+        let fs = FakeFs::new(cx.executor().clone());
+        fs.insert_tree(
+            "/root",
+            json!({
+                "src": {
+                    "main.js": "",
+                    "main.js.map": "",
+                    "main.ts": "",
+                },
+                "Cargo.lock": "",
+                "Cargo.toml": "",
+                "README.md": "",
+            }),
+        )
+        .await;
+
+        let project = Project::test(fs.clone(), ["/root".as_ref()], cx).await;
+        let workspace = cx.add_window(|cx| Workspace::test_new(project.clone(), cx));
+        let cx = &mut VisualTestContext::from_window(*workspace, cx);
+        let panel = workspace.update(cx, ProjectPanel::new).unwrap();
+
+        toggle_expand_dir(&panel, "root/src", cx);
+        assert_eq!(
+            visible_entries_as_strings(&panel, 0..10, cx),
+            &[
+                "v root",
+                "    v src  <== selected",
+                "        > main.ts",
+                "    > Cargo.toml",
+                "      README.md",
+            ]
+        );
+
+        select_path(&panel, "root/src/main.ts", cx);
+        panel.update(cx, |panel, cx| {
+            panel.expand_selected_entry(&ExpandSelectedEntry, cx)
+        });
+        assert_eq!(
+            visible_entries_as_strings(&panel, 0..10, cx),
+            &[
+                "v root",
+                "    v src",
+                "        v main.ts  <== selected",
+                "              main.js",
+                "              main.js.map",
+                "    > Cargo.toml",
+                "      README.md",
+            ]
+        );
+
+        // Collapsing a nested file collapses its parent and selects it.
+        select_path(&panel, "root/src/main.js.map", cx);
+        panel.update(cx, |panel, cx| {
+            panel.collapse_selected_entry(&CollapseSelectedEntry, cx)
+        });
+        assert_eq!(
+            visible_entries_as_strings(&panel, 0..10, cx),
+            &[
+                "v root",
+                "    v src",
+                "        > main.ts  <== selected",
+                "    > Cargo.toml",
+                "      README.md",
+            ]
+        );
+
+        // Operations on a collapsed parent apply to its nested files as well.
+        select_path(&panel, "root/Cargo.toml", cx);
+        let cargo_lock = find_project_entry(&panel, "root/Cargo.lock", cx).unwrap();
+        panel.update(cx, |panel, cx| {
+            let entries = panel.disjoint_entries(cx);
+            assert_eq!(entries.len(), 2);
+            assert!(entries.iter().any(|entry| entry.entry_id == cargo_lock));
+        });
+
+        // Revealing a nested file expands its parent.
+        panel.update(cx, |panel, cx| {
+            panel.reveal_entry(project.clone(), cargo_lock, false, cx)
+        });
+        assert_eq!(
+            visible_entries_as_strings(&panel, 0..10, cx),
+            &[
+                "v root",
+                "    v src",
+                "        > main.ts",
+                "    v Cargo.toml",
+                "          Cargo.lock  <== selected",
+                "      README.md",
+            ]
+        );
+    }
+
     fn toggle_expand_dir(
         panel: &View<ProjectPanel>,
         path: impl AsRef<Path>,
@@ -7879,6 +8390,12 @@ mod tests {
                     } else {
                         "> "
                     }
+                } else if let Some(nested_files_expanded) = details.nested_files_expanded {
+                    if nested_files_expanded {
+                        "v "
+                    } else {
+                        "> "
+                    }
                 } else {
                     "  "
                 };
@@ -1,3 +1,4 @@
+use collections::BTreeMap;
 use editor::ShowScrollbar;
 use gpui::Pixels;
 use schemars::JsonSchema;
@@ -18,7 +19,7 @@ pub enum ShowIndentGuides {
     Never,
 }
 
-#[derive(Deserialize, Debug, Clone, Copy, PartialEq)]
+#[derive(Deserialize, Debug, Clone, PartialEq)]
 pub struct ProjectPanelSettings {
     pub button: bool,
     pub default_width: Pixels,
@@ -32,6 +33,28 @@ pub struct ProjectPanelSettings {
     pub auto_fold_dirs: bool,
     pub scrollbar: ScrollbarSettings,
     pub show_diagnostics: ShowDiagnostics,
+    pub file_nesting: FileNestingSettings,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+pub struct FileNestingSettings {
+    pub enabled: bool,
+    pub patterns: BTreeMap<String, Vec<String>>,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+pub struct FileNestingSettingsContent {
+    /// Whether to nest related files under a parent file in the project panel.
+    ///
+    /// Default: false
+    pub enabled: Option<bool>,
+    /// Nesting patterns, keyed by the name of the parent file.
+    ///
+    /// A `*` in the parent name matches any text, which child patterns can
+    /// refer to as `${capture}`. Child patterns may also contain `*` wildcards.
+    /// For example, `"*.ts": ["${capture}.js", "${capture}.js.map"]` nests
+    /// `foo.js` and `foo.js.map` under `foo.ts`.
+    pub patterns: Option<BTreeMap<String, Vec<String>>>,
 }
 
 #[derive(Copy, Clone, Debug, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
@@ -125,6 +148,8 @@ pub struct ProjectPanelSettingsContent {
     pub show_diagnostics: Option<ShowDiagnostics>,
     /// Settings related to indent guides in the project panel.
     pub indent_guides: Option<IndentGuidesSettingsContent>,
+    /// Settings related to nesting related files in the project panel.
+    pub file_nesting: Option<FileNestingSettingsContent>,
 }
 
 impl Settings for ProjectPanelSettings {
@@ -372,6 +372,26 @@ messages!(
     (SyncExtensionsResponse, Background),
     (InstallExtension, Background),
     (RegisterBufferWithLanguageServers, Background),
+    (ShareTerminal, Foreground),
+    (UnshareTerminal, Foreground),
+    (UpdateSharedTerminal, Foreground),
+    (SharedTerminalInput, Foreground),
+    (GetReviewThreads, Background),
+    (GetReviewThreadsResponse, Background),
+    (CreateReviewThread, Foreground),
+    (ReviewThreadResponse, Foreground),
+    (AddReviewComment, Foreground),
+    (ResolveReviewThread, Foreground),
+    (MoveReviewThread, Foreground),
+    (UpdateReviewThread, Foreground),
+    (ReadProjectFile, Background),
+    (ReadProjectFileResponse, Background),
+    (AppendToProjectFile, Background),
+    (ToggleChannelMessageReaction, Foreground),
+    (ToggleChannelMessageReactionResponse, Foreground),
+    (ChannelMessageReactionsUpdated, Foreground),
+    (SearchChannelMessages, Background),
+    (SearchChannelMessagesResponse, Background),
 );
 
 request_messages!(
@@ -498,6 +518,16 @@ request_messages!(
     (SyncExtensions, SyncExtensionsResponse),
     (InstallExtension, Ack),
     (RegisterBufferWithLanguageServers, Ack),
+    (SharedTerminalInput, Ack),
+    (GetReviewThreads, GetReviewThreadsResponse),
+    (CreateReviewThread, ReviewThreadResponse),
+    (AddReviewComment, ReviewThreadResponse),
+    (ResolveReviewThread, ReviewThreadResponse),
+    (MoveReviewThread, ReviewThreadResponse),
+    (ReadProjectFile, ReadProjectFileResponse),
+    (AppendToProjectFile, Ack),
+    (ToggleChannelMessageReaction, ToggleChannelMessageReactionResponse),
+    (SearchChannelMessages, SearchChannelMessagesResponse),
 );
 
 entity_messages!(
@@ -584,13 +614,27 @@ entity_messages!(
     GetPathMetadata,
     CancelLanguageServerWork,
     RegisterBufferWithLanguageServers,
+    ShareTerminal,
+    UnshareTerminal,
+    UpdateSharedTerminal,
+    SharedTerminalInput,
+    ReadProjectFile,
+    AppendToProjectFile,
+    GetReviewThreads,
+    CreateReviewThread,
+    AddReviewComment,
+    ResolveReviewThread,
+    MoveReviewThread,
+    UpdateReviewThread,
 );
 
 entity_messages!(
     {channel_id, Channel},
     ChannelMessageSent,
     ChannelMessageUpdate,
+    ChannelMessageReactionsUpdated,
     RemoveChannelMessage,
+    ToggleChannelMessageReaction,
     UpdateChannelMessage,
     UpdateChannelBuffer,
     UpdateChannelBufferCollaborators,
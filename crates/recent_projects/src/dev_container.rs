@@ -0,0 +1,54 @@
+use anyhow::Context as _;
+use gpui::ViewContext;
+use remote::{DevContainerConfig, DevContainerOptions};
+use workspace::{notifications::DetachAndPromptErr, OpenOptions, Workspace};
+use zed_actions::OpenDevContainer;
+
+use crate::open_ssh_project;
+
+pub fn register(workspace: &mut Workspace, _: &mut ViewContext<Workspace>) {
+    workspace.register_action(open_dev_container);
+}
+
+/// Reopens a local project in the dev container defined by its `devcontainer.json`.
+fn open_dev_container(
+    workspace: &mut Workspace,
+    _: &OpenDevContainer,
+    cx: &mut ViewContext<Workspace>,
+) {
+    let project = workspace.project().read(cx);
+    let options = if project.is_local() {
+        project
+            .visible_worktrees(cx)
+            .find_map(|worktree| DevContainerOptions::find(&worktree.read(cx).abs_path()))
+    } else {
+        None
+    };
+    let app_state = workspace.app_state().clone();
+    let replace_window = cx.window_handle().downcast::<Workspace>();
+
+    cx.spawn(move |_, mut cx| async move {
+        let options = options.context("No devcontainer.json found in the local project")?;
+        let (options, config) = cx
+            .background_executor()
+            .spawn(async move {
+                let config = DevContainerConfig::load(&options)?;
+                anyhow::Ok((options, config))
+            })
+            .await?;
+        let workspace_folder = config.workspace_folder(options.local_folder());
+
+        open_ssh_project(
+            options.connection_options(config.name),
+            vec![workspace_folder.into()],
+            app_state,
+            OpenOptions {
+                replace_window,
+                ..Default::default()
+            },
+            &mut cx,
+        )
+        .await
+    })
+    .detach_and_prompt_err("Failed to open dev container", cx, |_, _| None);
+}
@@ -1,3 +1,4 @@
+mod dev_container;
 pub mod disconnected_overlay;
 mod remote_servers;
 mod ssh_connections;
@@ -36,6 +37,7 @@ pub fn init(cx: &mut AppContext) {
     cx.observe_new_views(RemoteServerProjects::register)
         .detach();
     cx.observe_new_views(DisconnectedOverlay::register).detach();
+    cx.observe_new_views(dev_container::register).detach();
 }
 
 pub struct RecentProjects {
@@ -0,0 +1,833 @@
+use crate::ssh_session::{
+    multiplex, RemoteConnection, RemoteShell, SshClientDelegate, SshConnectionOptions,
+};
+use anyhow::{anyhow, Context as _, Result};
+use async_trait::async_trait;
+use collections::HashMap;
+use futures::{
+    channel::mpsc::{Sender, UnboundedReceiver, UnboundedSender},
+    future,
+};
+use gpui::{AppContext, AsyncAppContext, Task};
+use release_channel::{AppCommitSha, AppVersion, ReleaseChannel};
+use rpc::proto::Envelope;
+use serde::Deserialize;
+use smol::process::{self, Stdio};
+use std::{
+    collections::BTreeMap,
+    hash::{DefaultHasher, Hash, Hasher},
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, Ordering::SeqCst},
+        Arc,
+    },
+};
+
+/// Dev container connections are stored as ssh hosts with this prefix, followed by the path of
+/// their `devcontainer.json`, so that they are persisted and reopened like ssh projects.
+const HOST_PREFIX: &str = "devcontainer:";
+/// The label identifying the container created for a `devcontainer.json`.
+const CONFIG_LABEL: &str = "dev.zed.devcontainer.config";
+/// Keeps the container running in place of the command of its image, like other dev container
+/// tools do when `overrideCommand` is not disabled.
+const KEEP_ALIVE_SCRIPT: &str = "trap 'exit 0' TERM; while sleep 1000 & wait $!; do :; done";
+/// Runs a command from the home directory of the user, as ssh does.
+const FROM_HOME_SCRIPT: &str = "cd && exec \"$@\"";
+/// The directory of the container, relative to the home of the user, where the lifecycle commands
+/// that only run once per container record that they succeeded.
+const LIFECYCLE_MARKERS_DIR: &str = ".zed_devcontainer";
+
+/// A dev container (https://containers.dev) to connect to instead of an ssh host.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct DevContainerOptions {
+    /// The `devcontainer.json` defining the container, on the local machine.
+    pub config_path: PathBuf,
+}
+
+impl DevContainerOptions {
+    /// Looks for the `devcontainer.json` of a project where dev container tools look for it.
+    pub fn find(project_dir: &Path) -> Option<Self> {
+        [".devcontainer/devcontainer.json", ".devcontainer.json"]
+            .into_iter()
+            .map(|path| project_dir.join(path))
+            .find(|path| path.is_file())
+            .map(|config_path| Self { config_path })
+    }
+
+    pub fn from_host(host: &str) -> Option<Self> {
+        let config_path = host.strip_prefix(HOST_PREFIX)?;
+        Some(Self {
+            config_path: PathBuf::from(config_path),
+        })
+    }
+
+    pub fn connection_options(&self, name: Option<String>) -> SshConnectionOptions {
+        SshConnectionOptions {
+            host: format!("{HOST_PREFIX}{}", self.config_path.display()),
+            nickname: name,
+            ..Default::default()
+        }
+    }
+
+    /// The project folder the container is defined for, which is mounted into it.
+    pub fn local_folder(&self) -> &Path {
+        let config_dir = self.config_path.parent().unwrap_or(Path::new("/"));
+        if config_dir.file_name() == Some(".devcontainer".as_ref()) {
+            config_dir.parent().unwrap_or(config_dir)
+        } else {
+            config_dir
+        }
+    }
+}
+
+/// The subset of `devcontainer.json` needed to run a single container.
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DevContainerConfig {
+    pub name: Option<String>,
+    pub image: Option<String>,
+    pub build: Option<BuildConfig>,
+    /// The legacy spelling of `build.dockerfile`.
+    pub docker_file: Option<String>,
+    pub docker_compose_file: Option<serde_json::Value>,
+    #[serde(default)]
+    pub features: BTreeMap<String, serde_json::Value>,
+    #[serde(default)]
+    pub forward_ports: Vec<ForwardPort>,
+    #[serde(default)]
+    pub container_env: BTreeMap<String, String>,
+    #[serde(default)]
+    pub remote_env: BTreeMap<String, Option<String>>,
+    #[serde(default)]
+    pub mounts: Vec<Mount>,
+    pub workspace_folder: Option<String>,
+    pub workspace_mount: Option<String>,
+    pub container_user: Option<String>,
+    pub remote_user: Option<String>,
+    #[serde(default)]
+    pub run_args: Vec<String>,
+    pub override_command: Option<bool>,
+    pub on_create_command: Option<LifecycleCommand>,
+    pub post_create_command: Option<LifecycleCommand>,
+    pub post_start_command: Option<LifecycleCommand>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BuildConfig {
+    pub dockerfile: Option<String>,
+    pub context: Option<String>,
+    #[serde(default)]
+    pub args: BTreeMap<String, String>,
+    pub target: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+pub enum ForwardPort {
+    Port(u16),
+    /// A `host:port` address, where the host may be another service of a compose project.
+    Address(String),
+}
+
+impl ForwardPort {
+    /// The port of the container to publish on the local machine.
+    fn container_port(&self) -> Option<u16> {
+        match self {
+            ForwardPort::Port(port) => Some(*port),
+            ForwardPort::Address(address) => {
+                let (host, port) = address.rsplit_once(':')?;
+                if host == "localhost" || host == "127.0.0.1" {
+                    port.parse().ok()
+                } else {
+                    None
+                }
+            }
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+pub enum Mount {
+    /// A `--mount` argument, such as `type=volume,source=cache,target=/cache`.
+    Spec(String),
+    Object {
+        #[serde(rename = "type")]
+        kind: Option<String>,
+        source: Option<String>,
+        target: String,
+    },
+}
+
+impl Mount {
+    fn spec(&self) -> String {
+        match self {
+            Mount::Spec(spec) => spec.clone(),
+            Mount::Object {
+                kind,
+                source,
+                target,
+            } => {
+                let mut spec = format!("type={}", kind.as_deref().unwrap_or("bind"));
+                if let Some(source) = source {
+                    spec.push_str(&format!(",source={source}"));
+                }
+                spec.push_str(&format!(",target={target}"));
+                spec
+            }
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+pub enum LifecycleCommand {
+    /// A command run by a shell.
+    Shell(String),
+    /// A program and its arguments, run without a shell.
+    Exec(Vec<String>),
+    /// Named commands, which are run in parallel.
+    Parallel(BTreeMap<String, LifecycleCommand>),
+}
+
+impl LifecycleCommand {
+    fn commands(&self) -> Vec<Vec<String>> {
+        match self {
+            LifecycleCommand::Shell(command) => {
+                vec![vec!["/bin/sh".to_owned(), "-c".to_owned(), command.clone()]]
+            }
+            LifecycleCommand::Exec(command) => vec![command.clone()],
+            LifecycleCommand::Parallel(commands) => commands
+                .values()
+                .flat_map(LifecycleCommand::commands)
+                .collect(),
+        }
+    }
+}
+
+impl DevContainerConfig {
+    pub fn load(options: &DevContainerOptions) -> Result<Self> {
+        let contents = std::fs::read_to_string(&options.config_path)
+            .with_context(|| format!("failed to read {:?}", options.config_path))?;
+        Self::parse(&contents, options.local_folder())
+    }
+
+    fn parse(contents: &str, local_folder: &Path) -> Result<Self> {
+        let local_folder_name = folder_name(local_folder);
+        let contents = substitute_variables(contents, |variable| match variable {
+            "localWorkspaceFolder" => Some(local_folder.to_string_lossy().into_owned()),
+            "localWorkspaceFolderBasename" => Some(local_folder_name.clone()),
+            _ => {
+                let variable = variable.strip_prefix("localEnv:")?;
+                let (name, default) = variable.split_once(':').unwrap_or((variable, ""));
+                Some(std::env::var(name).unwrap_or_else(|_| default.to_owned()))
+            }
+        });
+        let config: Self =
+            serde_json_lenient::from_str(&contents).context("failed to parse devcontainer.json")?;
+
+        let workspace_folder = config.workspace_folder(local_folder);
+        let workspace_folder_name = folder_name(Path::new(&workspace_folder));
+        let contents = substitute_variables(&contents, |variable| match variable {
+            "containerWorkspaceFolder" => Some(workspace_folder.clone()),
+            "containerWorkspaceFolderBasename" => Some(workspace_folder_name.clone()),
+            _ => None,
+        });
+        serde_json_lenient::from_str(&contents).context("failed to parse devcontainer.json")
+    }
+
+    /// The folder of the container the project is mounted at.
+    pub fn workspace_folder(&self, local_folder: &Path) -> String {
+        self.workspace_folder
+            .clone()
+            .unwrap_or_else(|| format!("/workspaces/{}", folder_name(local_folder)))
+    }
+}
+
+fn folder_name(path: &Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
+/// Replaces the `${variable}`s of a `devcontainer.json` that `resolve` knows about, escaping
+/// their values as JSON string contents.
+fn substitute_variables(contents: &str, resolve: impl Fn(&str) -> Option<String>) -> String {
+    let mut result = String::with_capacity(contents.len());
+    let mut rest = contents;
+    while let Some(start) = rest.find("${") {
+        result.push_str(&rest[..start]);
+        let variable = &rest[start + 2..];
+        let Some(end) = variable.find('}') else {
+            rest = &rest[start..];
+            break;
+        };
+        match resolve(&variable[..end]) {
+            Some(value) => {
+                let escaped = serde_json::to_string(&value).unwrap_or_default();
+                result.push_str(&escaped[1..escaped.len() - 1]);
+            }
+            None => result.push_str(&rest[start..start + end + 3]),
+        }
+        rest = &variable[end + 1..];
+    }
+    result.push_str(rest);
+    result
+}
+
+async fn run(mut command: process::Command) -> Result<String> {
+    let output = command
+        .stdin(Stdio::null())
+        .output()
+        .await
+        .with_context(|| format!("failed to run {command:?}"))?;
+    if output.status.success() {
+        Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+    } else {
+        Err(anyhow!(
+            "{command:?} failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ))
+    }
+}
+
+/// A running dev container, and how commands are run in it.
+struct DevContainer {
+    cli: String,
+    id: String,
+    remote_user: Option<String>,
+    remote_env: Vec<(String, String)>,
+    workspace_folder: String,
+}
+
+impl DevContainer {
+    /// Starts the container of a `devcontainer.json`, creating it first if needed.
+    async fn up(
+        options: &DevContainerOptions,
+        config: &DevContainerConfig,
+        delegate: &Arc<dyn SshClientDelegate>,
+        cx: &mut AsyncAppContext,
+    ) -> Result<Self> {
+        let cli = ["docker", "podman"]
+            .into_iter()
+            .find(|cli| which::which(cli).is_ok())
+            .context("Cannot find docker or podman, which are required to run dev containers.")?
+            .to_owned();
+        let label = format!("{CONFIG_LABEL}={}", options.config_path.display());
+        let local_folder = options.local_folder();
+        let workspace_folder = config.workspace_folder(local_folder);
+
+        let mut command = util::command::new_smol_command(&cli);
+        command
+            .args(["ps", "--all", "--filter"])
+            .arg(format!("label={label}"));
+        command.args(["--format", "{{.ID}} {{.State}}"]);
+        let existing = run(command).await?;
+        let (id, started) = match existing
+            .lines()
+            .next()
+            .and_then(|line| line.split_once(' '))
+        {
+            Some((id, state)) => {
+                let running = state.trim().eq_ignore_ascii_case("running");
+                if !running {
+                    delegate.set_status(Some("Starting dev container"), cx);
+                    let mut command = util::command::new_smol_command(&cli);
+                    command.args(["start", id]);
+                    run(command).await?;
+                }
+                (id.to_owned(), !running)
+            }
+            None => {
+                let image = Self::image(&cli, options, config, delegate, cx).await?;
+                if config.features.is_empty() {
+                    delegate.set_status(Some("Creating dev container"), cx);
+                } else {
+                    let features = config.features.keys().cloned().collect::<Vec<_>>();
+                    log::warn!("ignoring unsupported dev container features: {features:?}");
+                    delegate.set_status(
+                        Some(&format!(
+                            "Creating dev container without its features, which are not supported yet: {}",
+                            features.join(", ")
+                        )),
+                        cx,
+                    );
+                }
+                let mut command = util::command::new_smol_command(&cli);
+                command.args(["run", "--detach", "--label", &label, "--mount"]);
+                command.arg(config.workspace_mount.clone().unwrap_or_else(|| {
+                    format!(
+                        "type=bind,source={},target={workspace_folder}",
+                        local_folder.display()
+                    )
+                }));
+                for mount in &config.mounts {
+                    command.arg("--mount").arg(mount.spec());
+                }
+                for (name, value) in &config.container_env {
+                    command.arg("--env").arg(format!("{name}={value}"));
+                }
+                for port in config
+                    .forward_ports
+                    .iter()
+                    .filter_map(ForwardPort::container_port)
+                {
+                    command
+                        .arg("--publish")
+                        .arg(format!("127.0.0.1:{port}:{port}"));
+                }
+                if let Some(user) = &config.container_user {
+                    command.args(["--user", user]);
+                }
+                command.args(&config.run_args);
+                if config.override_command != Some(false) {
+                    command.args(["--entrypoint", "/bin/sh", &image, "-c", KEEP_ALIVE_SCRIPT]);
+                } else {
+                    command.arg(&image);
+                }
+                let id = run(command).await?.trim().to_owned();
+                (id, true)
+            }
+        };
+
+        let mut container = Self {
+            cli,
+            id,
+            remote_user: config
+                .remote_user
+                .clone()
+                .or_else(|| config.container_user.clone()),
+            remote_env: Vec::new(),
+            workspace_folder,
+        };
+        container.remote_env = container.remote_env(config).await?;
+
+        // Commands that run once per container are rerun until they succeed, as a failure
+        // interrupts the connection and leaves the container set up only partially.
+        for (name, command) in [
+            ("onCreateCommand", &config.on_create_command),
+            ("postCreateCommand", &config.post_create_command),
+        ] {
+            let Some(command) = command else {
+                continue;
+            };
+            let marker = format!("{LIFECYCLE_MARKERS_DIR}/{name}");
+            if container
+                .run_command("test", &["-e", &marker])
+                .await
+                .is_ok()
+            {
+                continue;
+            }
+            container
+                .run_lifecycle_command(name, command, delegate, cx)
+                .await?;
+            container
+                .run_command("mkdir", &["-p", LIFECYCLE_MARKERS_DIR])
+                .await?;
+            container.run_command("touch", &[&marker]).await?;
+        }
+        if started {
+            if let Some(command) = &config.post_start_command {
+                container
+                    .run_lifecycle_command("postStartCommand", command, delegate, cx)
+                    .await?;
+            }
+        }
+
+        Ok(container)
+    }
+
+    async fn run_lifecycle_command(
+        &self,
+        name: &str,
+        command: &LifecycleCommand,
+        delegate: &Arc<dyn SshClientDelegate>,
+        cx: &mut AsyncAppContext,
+    ) -> Result<()> {
+        delegate.set_status(Some(&format!("Running {name}")), cx);
+        future::try_join_all(command.commands().into_iter().filter_map(|command| {
+            let (program, args) = command.split_first()?;
+            let mut exec = self.exec(false, Some(self.workspace_folder.as_str()));
+            exec.arg(program).args(args);
+            Some(run(exec))
+        }))
+        .await
+        .with_context(|| format!("{name} of the dev container failed"))?;
+        Ok(())
+    }
+
+    /// Returns the image of the container, building it if the container is defined by a
+    /// Dockerfile.
+    async fn image(
+        cli: &str,
+        options: &DevContainerOptions,
+        config: &DevContainerConfig,
+        delegate: &Arc<dyn SshClientDelegate>,
+        cx: &mut AsyncAppContext,
+    ) -> Result<String> {
+        if config.docker_compose_file.is_some() {
+            anyhow::bail!("Dev containers defined with Docker Compose are not supported yet.");
+        }
+        let build = config.build.as_ref();
+        let Some(dockerfile) = build
+            .and_then(|build| build.dockerfile.as_ref())
+            .or(config.docker_file.as_ref())
+        else {
+            return config
+                .image
+                .clone()
+                .context("devcontainer.json must specify an `image` or a `build.dockerfile`");
+        };
+
+        delegate.set_status(Some("Building dev container image"), cx);
+        let config_dir = options.config_path.parent().unwrap_or(Path::new("/"));
+        let context = config_dir.join(
+            build
+                .and_then(|build| build.context.as_deref())
+                .unwrap_or("."),
+        );
+        let mut hasher = DefaultHasher::new();
+        options.config_path.hash(&mut hasher);
+        let tag = format!("zed-devcontainer-{:x}", hasher.finish());
+
+        let mut command = util::command::new_smol_command(cli);
+        command
+            .arg("build")
+            .arg("--file")
+            .arg(config_dir.join(dockerfile))
+            .args(["--tag", &tag]);
+        if let Some(build) = build {
+            for (name, value) in &build.args {
+                command.arg("--build-arg").arg(format!("{name}={value}"));
+            }
+            if let Some(target) = &build.target {
+                command.args(["--target", target]);
+            }
+        }
+        command.arg(context);
+        run(command).await?;
+        Ok(tag)
+    }
+
+    /// Resolves `remoteEnv`, whose values may refer to the environment of the container.
+    async fn remote_env(&self, config: &DevContainerConfig) -> Result<Vec<(String, String)>> {
+        let needs_container_env = config
+            .remote_env
+            .values()
+            .flatten()
+            .any(|value| value.contains("${containerEnv:"));
+        let mut container_env = HashMap::default();
+        if needs_container_env {
+            let mut command = self.exec(false, None);
+            command.arg("env");
+            for line in run(command).await?.lines() {
+                if let Some((name, value)) = line.split_once('=') {
+                    container_env.insert(name.to_owned(), value.to_owned());
+                }
+            }
+        }
+
+        Ok(config
+            .remote_env
+            .iter()
+            .filter_map(|(name, value)| {
+                let value = substitute_variables(value.as_ref()?, |variable| {
+                    let (name, default) = variable
+                        .strip_prefix("containerEnv:")
+                        .map(|variable| variable.split_once(':').unwrap_or((variable, "")))?;
+                    Some(
+                        container_env
+                            .get(name)
+                            .cloned()
+                            .unwrap_or_else(|| default.to_owned()),
+                    )
+                });
+                Some((name.clone(), value))
+            })
+            .collect())
+    }
+
+    fn exec_args(&self, terminal: bool, working_dir: Option<&str>) -> Vec<String> {
+        let mut args = vec![
+            "exec".to_owned(),
+            if terminal { "-it" } else { "-i" }.to_owned(),
+        ];
+        if let Some(user) = &self.remote_user {
+            args.extend(["--user".to_owned(), user.clone()]);
+        }
+        for (name, value) in &self.remote_env {
+            args.extend(["--env".to_owned(), format!("{name}={value}")]);
+        }
+        if let Some(working_dir) = working_dir {
+            args.extend(["--workdir".to_owned(), working_dir.to_owned()]);
+        }
+        args.push(self.id.clone());
+        args
+    }
+
+    fn exec(&self, terminal: bool, working_dir: Option<&str>) -> process::Command {
+        let mut command = util::command::new_smol_command(&self.cli);
+        command.args(self.exec_args(terminal, working_dir));
+        command
+    }
+
+    /// Runs a program from the home directory of the user, as ssh would.
+    fn exec_from_home(&self, program: &str, args: &[&str]) -> process::Command {
+        let mut command = self.exec(false, None);
+        command
+            .args(["sh", "-c", FROM_HOME_SCRIPT, "sh", program])
+            .args(args);
+        command
+    }
+}
+
+#[async_trait(?Send)]
+impl RemoteShell for DevContainer {
+    async fn run_command(&self, program: &str, args: &[&str]) -> Result<String> {
+        run(self.exec_from_home(program, args)).await
+    }
+
+    async fn upload_file(&self, src_path: &Path, dest_path: &Path) -> Result<()> {
+        log::debug!("uploading file {:?} to {:?}", src_path, dest_path);
+        let dest_path = if dest_path.is_relative() {
+            let home = self.run_command("pwd", &[]).await?;
+            Path::new(home.trim()).join(dest_path)
+        } else {
+            dest_path.to_path_buf()
+        };
+        let mut command = util::command::new_smol_command(&self.cli);
+        command
+            .arg("cp")
+            .arg(src_path)
+            .arg(format!("{}:{}", self.id, dest_path.display()));
+        run(command).await.map(|_| ())
+    }
+}
+
+/// A connection to the remote server running in a dev container, over the stdio of
+/// `docker exec`.
+pub(crate) struct DevContainerConnection {
+    container: DevContainer,
+    connection_options: SshConnectionOptions,
+    remote_binary_path: PathBuf,
+    killed: AtomicBool,
+}
+
+impl DevContainerConnection {
+    pub(crate) async fn new(
+        options: DevContainerOptions,
+        connection_options: SshConnectionOptions,
+        delegate: Arc<dyn SshClientDelegate>,
+        cx: &mut AsyncAppContext,
+    ) -> Result<Self> {
+        delegate.set_status(Some("Reading devcontainer.json"), cx);
+        let config = DevContainerConfig::load(&options)?;
+        let container = DevContainer::up(&options, &config, &delegate, cx).await?;
+
+        let (release_channel, version, commit) = cx.update(|cx| {
+            (
+                ReleaseChannel::global(cx),
+                AppVersion::global(cx),
+                AppCommitSha::try_global(cx),
+            )
+        })?;
+        // Containers often lack curl or network access, so the server is always uploaded.
+        let remote_binary_path = container
+            .ensure_server_binary(true, &delegate, release_channel, version, commit, cx)
+            .await?;
+
+        Ok(Self {
+            container,
+            connection_options,
+            remote_binary_path,
+            killed: AtomicBool::new(false),
+        })
+    }
+}
+
+#[async_trait(?Send)]
+impl RemoteConnection for DevContainerConnection {
+    fn start_proxy(
+        &self,
+        unique_identifier: String,
+        reconnect: bool,
+        incoming_tx: UnboundedSender<Envelope>,
+        outgoing_rx: UnboundedReceiver<Envelope>,
+        connection_activity_tx: Sender<()>,
+        delegate: Arc<dyn SshClientDelegate>,
+        cx: &mut AsyncAppContext,
+    ) -> Task<Result<i32>> {
+        delegate.set_status(Some("Starting proxy"), cx);
+
+        let remote_binary_path = self.remote_binary_path.to_string_lossy();
+        let mut args = vec!["proxy", "--identifier", &unique_identifier];
+        if reconnect {
+            args.push("--reconnect");
+        }
+        let mut env = Vec::new();
+        for name in ["RUST_LOG", "RUST_BACKTRACE"] {
+            if let Ok(value) = std::env::var(name) {
+                env.push(format!("{name}={value}"));
+            }
+        }
+        let mut command = self.container.exec(false, None);
+        command
+            .arg("env")
+            .args(env)
+            .args(["sh", "-c", FROM_HOME_SCRIPT, "sh", &*remote_binary_path])
+            .args(args);
+        let proxy_process = match command
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            // IMPORTANT: we kill this process when we drop the task that uses it.
+            .kill_on_drop(true)
+            .spawn()
+        {
+            Ok(process) => process,
+            Err(error) => {
+                return Task::ready(Err(anyhow!("failed to spawn remote server: {}", error)))
+            }
+        };
+
+        multiplex(
+            proxy_process,
+            incoming_tx,
+            outgoing_rx,
+            connection_activity_tx,
+            cx,
+        )
+    }
+
+    fn upload_directory(
+        &self,
+        src_path: PathBuf,
+        dest_path: PathBuf,
+        cx: &AppContext,
+    ) -> Task<Result<()>> {
+        let mut command = util::command::new_smol_command(&self.container.cli);
+        command.arg("cp").arg(&src_path).arg(format!(
+            "{}:{}",
+            self.container.id,
+            dest_path.display()
+        ));
+        cx.background_executor()
+            .spawn(async move { run(command).await.map(|_| ()) })
+    }
+
+    async fn kill(&self) -> Result<()> {
+        // Like other dev container tools, the container is left running for the next connection.
+        self.killed.store(true, SeqCst);
+        Ok(())
+    }
+
+    fn has_been_killed(&self) -> bool {
+        self.killed.load(SeqCst)
+    }
+
+    fn ssh_args(&self) -> Vec<String> {
+        Vec::new()
+    }
+
+    fn exec_command(&self) -> Option<(String, Vec<String>)> {
+        Some((
+            self.container.cli.clone(),
+            self.container
+                .exec_args(true, Some(self.container.workspace_folder.as_str())),
+        ))
+    }
+
+    fn connection_options(&self) -> SshConnectionOptions {
+        self.connection_options.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_config() {
+        std::env::set_var("ZED_DEV_CONTAINER_TEST_TOKEN", "secret");
+        let config = DevContainerConfig::parse(
+            r#"{
+                // Comments and trailing commas are allowed.
+                "name": "api",
+                "build": { "dockerfile": "Dockerfile", "context": "..", "args": { "VARIANT": "3.12" } },
+                "features": { "ghcr.io/devcontainers/features/node:1": { "version": "lts" } },
+                "forwardPorts": [8000, "localhost:5432", "db:6379"],
+                "containerEnv": {
+                    "TOKEN": "${localEnv:ZED_DEV_CONTAINER_TEST_TOKEN}",
+                    "MISSING": "${localEnv:ZED_DEV_CONTAINER_TEST_MISSING:fallback}",
+                },
+                "remoteEnv": { "PATH": "${containerEnv:PATH}:${containerWorkspaceFolder}/bin", "UNSET": null },
+                "mounts": [
+                    "type=volume,source=${localWorkspaceFolderBasename}-cache,target=/cache",
+                    { "source": "${localWorkspaceFolder}/data", "target": "/data", "type": "bind" },
+                ],
+                "postCreateCommand": { "deps": "pip install -r requirements.txt", "hooks": ["pre-commit", "install"] },
+            }"#,
+            Path::new("/home/me/api"),
+        )
+        .unwrap();
+
+        assert_eq!(config.name.as_deref(), Some("api"));
+        assert_eq!(
+            config.workspace_folder(Path::new("/home/me/api")),
+            "/workspaces/api"
+        );
+        assert_eq!(
+            config
+                .forward_ports
+                .iter()
+                .filter_map(ForwardPort::container_port)
+                .collect::<Vec<_>>(),
+            [8000, 5432]
+        );
+        assert_eq!(
+            config.features.keys().collect::<Vec<_>>(),
+            ["ghcr.io/devcontainers/features/node:1"]
+        );
+        assert_eq!(config.container_env["TOKEN"], "secret");
+        assert_eq!(config.container_env["MISSING"], "fallback");
+        assert_eq!(
+            config.remote_env["PATH"].as_deref(),
+            Some("${containerEnv:PATH}:/workspaces/api/bin")
+        );
+        assert_eq!(config.remote_env["UNSET"], None);
+        assert_eq!(
+            config.mounts.iter().map(Mount::spec).collect::<Vec<_>>(),
+            [
+                "type=volume,source=api-cache,target=/cache",
+                "type=bind,source=/home/me/api/data,target=/data",
+            ]
+        );
+        assert_eq!(
+            config.post_create_command.unwrap().commands(),
+            [
+                vec!["/bin/sh", "-c", "pip install -r requirements.txt"],
+                vec!["pre-commit", "install"],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_options_round_trip_through_host() {
+        let options = DevContainerOptions {
+            config_path: PathBuf::from("/home/me/api/.devcontainer/devcontainer.json"),
+        };
+        assert_eq!(options.local_folder(), Path::new("/home/me/api"));
+        let connection_options = options.connection_options(Some("api".to_owned()));
+        assert_eq!(connection_options.dev_container(), Some(options));
+        assert_eq!(
+            SshConnectionOptions {
+                host: "example.com".to_owned(),
+                ..Default::default()
+            }
+            .dev_container(),
+            None
+        );
+    }
+}
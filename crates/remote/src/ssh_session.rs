@@ -1,4 +1,5 @@
 use crate::{
+    dev_container::{DevContainerConnection, DevContainerOptions},
     json_log::LogRecord,
     protocol::{
         message_len_from_buffer, read_message_with_len, write_message, MessageId, MESSAGE_LEN_SIZE,
@@ -191,6 +192,11 @@ impl SshConnectionOptions {
         }
     }
 
+    /// The dev container to connect to instead of an ssh host, if any.
+    pub fn dev_container(&self) -> Option<DevContainerOptions> {
+        DevContainerOptions::from_host(&self.host)
+    }
+
     pub fn connection_string(&self) -> String {
         let host = if let Some(username) = &self.username {
             format!("{}@{}", username, self.host)
@@ -274,18 +280,6 @@ impl SshSocket {
         command
     }
 
-    async fn run_command(&self, program: &str, args: &[&str]) -> Result<String> {
-        let output = self.ssh_command(program, args).output().await?;
-        if output.status.success() {
-            Ok(String::from_utf8_lossy(&output.stdout).to_string())
-        } else {
-            Err(anyhow!(
-                "failed to run command: {}",
-                String::from_utf8_lossy(&output.stderr)
-            ))
-        }
-    }
-
     fn ssh_options<'a>(&self, command: &'a mut process::Command) -> &'a mut process::Command {
         command
             .stdin(Stdio::piped())
@@ -990,6 +984,16 @@ impl SshRemoteClient {
             .map(|ssh_connection| ssh_connection.ssh_args())
     }
 
+    /// The program and arguments that run a command on the remote, for connections that
+    /// do not go through ssh.
+    pub fn exec_command(&self) -> Option<(String, Vec<String>)> {
+        self.state
+            .lock()
+            .as_ref()
+            .and_then(|state| state.ssh_connection())
+            .and_then(|connection| connection.exec_command())
+    }
+
     pub fn upload_directory(
         &self,
         src_path: PathBuf,
@@ -1155,9 +1159,19 @@ impl ConnectionPool {
                 let opts = opts.clone();
                 let delegate = delegate.clone();
                 |mut cx| async move {
-                    let connection = SshRemoteConnection::new(opts.clone(), delegate, &mut cx)
+                    let connection = match opts.dev_container() {
+                        Some(dev_container) => DevContainerConnection::new(
+                            dev_container,
+                            opts.clone(),
+                            delegate,
+                            &mut cx,
+                        )
                         .await
-                        .map(|connection| Arc::new(connection) as Arc<dyn RemoteConnection>);
+                        .map(|connection| Arc::new(connection) as Arc<dyn RemoteConnection>),
+                        None => SshRemoteConnection::new(opts.clone(), delegate, &mut cx)
+                            .await
+                            .map(|connection| Arc::new(connection) as Arc<dyn RemoteConnection>),
+                    };
 
                     cx.update_global(|pool: &mut Self, _| {
                         debug_assert!(matches!(
@@ -1195,7 +1209,7 @@ impl From<SshRemoteClient> for AnyProtoClient {
 }
 
 #[async_trait(?Send)]
-trait RemoteConnection: Send + Sync {
+pub(crate) trait RemoteConnection: Send + Sync {
     #[allow(clippy::too_many_arguments)]
     fn start_proxy(
         &self,
@@ -1216,6 +1230,9 @@ trait RemoteConnection: Send + Sync {
     async fn kill(&self) -> Result<()>;
     fn has_been_killed(&self) -> bool;
     fn ssh_args(&self) -> Vec<String>;
+    /// The program and arguments that run a command on the remote machine without going
+    /// through ssh, such as `docker exec` for dev containers.
+    fn exec_command(&self) -> Option<(String, Vec<String>)>;
     fn connection_options(&self) -> SshConnectionOptions;
 
     #[cfg(any(test, feature = "test-support"))]
@@ -1248,6 +1265,10 @@ impl RemoteConnection for SshRemoteConnection {
         self.socket.ssh_args()
     }
 
+    fn exec_command(&self) -> Option<(String, Vec<String>)> {
+        None
+    }
+
     fn connection_options(&self) -> SshConnectionOptions {
         self.socket.connection_options.clone()
     }
@@ -1348,7 +1369,7 @@ impl RemoteConnection for SshRemoteConnection {
             }
         };
 
-        Self::multiplex(
+        multiplex(
             ssh_proxy_process,
             incoming_tx,
             outgoing_rx,
@@ -1545,16 +1566,136 @@ impl SshRemoteConnection {
                 AppCommitSha::try_global(cx),
             )
         })?;
+        this.socket.connection_options.upload_binary_over_ssh = true;
         this.remote_binary_path = Some(
-            this.ensure_server_binary(&delegate, release_channel, version, commit, cx)
+            this.socket
+                .ensure_server_binary(
+                    this.socket.connection_options.upload_binary_over_ssh,
+                    &delegate,
+                    release_channel,
+                    version,
+                    commit,
+                    cx,
+                )
                 .await?,
         );
 
         Ok(this)
     }
+}
+
+/// Exchanges messages with a remote server proxy over the stdio of its process.
+pub(crate) fn multiplex(
+    mut ssh_proxy_process: Child,
+    incoming_tx: UnboundedSender<Envelope>,
+    mut outgoing_rx: UnboundedReceiver<Envelope>,
+    mut connection_activity_tx: Sender<()>,
+    cx: &AsyncAppContext,
+) -> Task<Result<i32>> {
+    let mut child_stderr = ssh_proxy_process.stderr.take().unwrap();
+    let mut child_stdout = ssh_proxy_process.stdout.take().unwrap();
+    let mut child_stdin = ssh_proxy_process.stdin.take().unwrap();
+
+    let mut stdin_buffer = Vec::new();
+    let mut stdout_buffer = Vec::new();
+    let mut stderr_buffer = Vec::new();
+    let mut stderr_offset = 0;
+
+    let stdin_task = cx.background_executor().spawn(async move {
+        while let Some(outgoing) = outgoing_rx.next().await {
+            write_message(&mut child_stdin, &mut stdin_buffer, outgoing).await?;
+        }
+        anyhow::Ok(())
+    });
+
+    let stdout_task = cx.background_executor().spawn({
+        let mut connection_activity_tx = connection_activity_tx.clone();
+        async move {
+            loop {
+                stdout_buffer.resize(MESSAGE_LEN_SIZE, 0);
+                let len = child_stdout.read(&mut stdout_buffer).await?;
+
+                if len == 0 {
+                    return anyhow::Ok(());
+                }
+
+                if len < MESSAGE_LEN_SIZE {
+                    child_stdout.read_exact(&mut stdout_buffer[len..]).await?;
+                }
+
+                let message_len = message_len_from_buffer(&stdout_buffer);
+                let envelope =
+                    read_message_with_len(&mut child_stdout, &mut stdout_buffer, message_len)
+                        .await?;
+                connection_activity_tx.try_send(()).ok();
+                incoming_tx.unbounded_send(envelope).ok();
+            }
+        }
+    });
+
+    let stderr_task: Task<anyhow::Result<()>> = cx.background_executor().spawn(async move {
+        loop {
+            stderr_buffer.resize(stderr_offset + 1024, 0);
+
+            let len = child_stderr
+                .read(&mut stderr_buffer[stderr_offset..])
+                .await?;
+            if len == 0 {
+                return anyhow::Ok(());
+            }
+
+            stderr_offset += len;
+            let mut start_ix = 0;
+            while let Some(ix) = stderr_buffer[start_ix..stderr_offset]
+                .iter()
+                .position(|b| b == &b'\n')
+            {
+                let line_ix = start_ix + ix;
+                let content = &stderr_buffer[start_ix..line_ix];
+                start_ix = line_ix + 1;
+                if let Ok(record) = serde_json::from_slice::<LogRecord>(content) {
+                    record.log(log::logger())
+                } else {
+                    eprintln!("(remote) {}", String::from_utf8_lossy(content));
+                }
+            }
+            stderr_buffer.drain(0..start_ix);
+            stderr_offset -= start_ix;
+
+            connection_activity_tx.try_send(()).ok();
+        }
+    });
+
+    cx.spawn(|_| async move {
+        let result = futures::select! {
+            result = stdin_task.fuse() => {
+                result.context("stdin")
+            }
+            result = stdout_task.fuse() => {
+                result.context("stdout")
+            }
+            result = stderr_task.fuse() => {
+                result.context("stderr")
+            }
+        };
+
+        let status = ssh_proxy_process.status().await?.code().unwrap_or(1);
+        match result {
+            Ok(_) => Ok(status),
+            Err(error) => Err(error),
+        }
+    })
+}
+
+/// Runs commands on the remote machine and uploads files to it, which is all that is needed to
+/// install the remote server there.
+#[async_trait(?Send)]
+pub(crate) trait RemoteShell {
+    async fn run_command(&self, program: &str, args: &[&str]) -> Result<String>;
+    async fn upload_file(&self, src_path: &Path, dest_path: &Path) -> Result<()>;
 
     async fn platform(&self) -> Result<SshPlatform> {
-        let uname = self.socket.run_command("uname", &["-sm"]).await?;
+        let uname = self.run_command("uname", &["-sm"]).await?;
         let Some((os, arch)) = uname.split_once(" ") else {
             Err(anyhow!("unknown uname: {uname:?}"))?
         };
@@ -1584,111 +1725,10 @@ impl SshRemoteConnection {
         Ok(SshPlatform { os, arch })
     }
 
-    fn multiplex(
-        mut ssh_proxy_process: Child,
-        incoming_tx: UnboundedSender<Envelope>,
-        mut outgoing_rx: UnboundedReceiver<Envelope>,
-        mut connection_activity_tx: Sender<()>,
-        cx: &AsyncAppContext,
-    ) -> Task<Result<i32>> {
-        let mut child_stderr = ssh_proxy_process.stderr.take().unwrap();
-        let mut child_stdout = ssh_proxy_process.stdout.take().unwrap();
-        let mut child_stdin = ssh_proxy_process.stdin.take().unwrap();
-
-        let mut stdin_buffer = Vec::new();
-        let mut stdout_buffer = Vec::new();
-        let mut stderr_buffer = Vec::new();
-        let mut stderr_offset = 0;
-
-        let stdin_task = cx.background_executor().spawn(async move {
-            while let Some(outgoing) = outgoing_rx.next().await {
-                write_message(&mut child_stdin, &mut stdin_buffer, outgoing).await?;
-            }
-            anyhow::Ok(())
-        });
-
-        let stdout_task = cx.background_executor().spawn({
-            let mut connection_activity_tx = connection_activity_tx.clone();
-            async move {
-                loop {
-                    stdout_buffer.resize(MESSAGE_LEN_SIZE, 0);
-                    let len = child_stdout.read(&mut stdout_buffer).await?;
-
-                    if len == 0 {
-                        return anyhow::Ok(());
-                    }
-
-                    if len < MESSAGE_LEN_SIZE {
-                        child_stdout.read_exact(&mut stdout_buffer[len..]).await?;
-                    }
-
-                    let message_len = message_len_from_buffer(&stdout_buffer);
-                    let envelope =
-                        read_message_with_len(&mut child_stdout, &mut stdout_buffer, message_len)
-                            .await?;
-                    connection_activity_tx.try_send(()).ok();
-                    incoming_tx.unbounded_send(envelope).ok();
-                }
-            }
-        });
-
-        let stderr_task: Task<anyhow::Result<()>> = cx.background_executor().spawn(async move {
-            loop {
-                stderr_buffer.resize(stderr_offset + 1024, 0);
-
-                let len = child_stderr
-                    .read(&mut stderr_buffer[stderr_offset..])
-                    .await?;
-                if len == 0 {
-                    return anyhow::Ok(());
-                }
-
-                stderr_offset += len;
-                let mut start_ix = 0;
-                while let Some(ix) = stderr_buffer[start_ix..stderr_offset]
-                    .iter()
-                    .position(|b| b == &b'\n')
-                {
-                    let line_ix = start_ix + ix;
-                    let content = &stderr_buffer[start_ix..line_ix];
-                    start_ix = line_ix + 1;
-                    if let Ok(record) = serde_json::from_slice::<LogRecord>(content) {
-                        record.log(log::logger())
-                    } else {
-                        eprintln!("(remote) {}", String::from_utf8_lossy(content));
-                    }
-                }
-                stderr_buffer.drain(0..start_ix);
-                stderr_offset -= start_ix;
-
-                connection_activity_tx.try_send(()).ok();
-            }
-        });
-
-        cx.spawn(|_| async move {
-            let result = futures::select! {
-                result = stdin_task.fuse() => {
-                    result.context("stdin")
-                }
-                result = stdout_task.fuse() => {
-                    result.context("stdout")
-                }
-                result = stderr_task.fuse() => {
-                    result.context("stderr")
-                }
-            };
-
-            let status = ssh_proxy_process.status().await?.code().unwrap_or(1);
-            match result {
-                Ok(_) => Ok(status),
-                Err(error) => Err(error),
-            }
-        })
-    }
-
     #[allow(unused)]
     async fn ensure_server_binary(
-        &mut self,
+        &self,
+        upload_binary_over_ssh: bool,
         delegate: &Arc<dyn SshClientDelegate>,
         release_channel: ReleaseChannel,
         version: SemanticVersion,
@@ -1729,7 +1769,6 @@ impl SshRemoteConnection {
         }
 
         if self
-            .socket
             .run_command(&dst_path.to_string_lossy(), &["version"])
             .await
             .is_ok()
@@ -1750,9 +1789,7 @@ impl SshRemoteConnection {
 
         let platform = self.platform().await?;
 
-        self.socket.connection_options.upload_binary_over_ssh = true;
-
-        if !self.socket.connection_options.upload_binary_over_ssh {
+        if !upload_binary_over_ssh {
             if let Some((url, body)) = delegate
                 .get_download_params(platform, release_channel, wanted_version, cx)
                 .await?
@@ -1795,15 +1832,13 @@ impl SshRemoteConnection {
         cx: &mut AsyncAppContext,
     ) -> Result<()> {
         if let Some(parent) = tmp_path_gz.parent() {
-            self.socket
-                .run_command("mkdir", &["-p", &parent.to_string_lossy()])
+            self.run_command("mkdir", &["-p", &parent.to_string_lossy()])
                 .await?;
         }
 
         delegate.set_status(Some("Downloading remote development server on host"), cx);
 
         match self
-            .socket
             .run_command(
                 "curl",
                 &[
@@ -1824,7 +1859,7 @@ impl SshRemoteConnection {
         {
             Ok(_) => {}
             Err(e) => {
-                if self.socket.run_command("which", &["curl"]).await.is_ok() {
+                if self.run_command("which", &["curl"]).await.is_ok() {
                     return Err(e);
                 }
 
@@ -1847,7 +1882,7 @@ impl SshRemoteConnection {
                 {
                     Ok(_) => {}
                     Err(e) => {
-                        if self.socket.run_command("which", &["wget"]).await.is_ok() {
+                        if self.run_command("which", &["wget"]).await.is_ok() {
                             return Err(e);
                         } else {
                             anyhow::bail!("Neither curl nor wget is available");
@@ -1868,8 +1903,7 @@ impl SshRemoteConnection {
         cx: &mut AsyncAppContext,
     ) -> Result<()> {
         if let Some(parent) = tmp_path_gz.parent() {
-            self.socket
-                .run_command("mkdir", &["-p", &parent.to_string_lossy()])
+            self.run_command("mkdir", &["-p", &parent.to_string_lossy()])
                 .await?;
         }
 
@@ -1907,44 +1941,10 @@ impl SshRemoteConnection {
             server_mode = &format!("{:o}", server_mode),
             dst_path = &dst_path.to_string_lossy()
         );
-        self.socket.run_command("sh", &["-c", &script]).await?;
+        self.run_command("sh", &["-c", &script]).await?;
         Ok(())
     }
 
-    async fn upload_file(&self, src_path: &Path, dest_path: &Path) -> Result<()> {
-        log::debug!("uploading file {:?} to {:?}", src_path, dest_path);
-        let mut command = util::command::new_smol_command("scp");
-        let output = self
-            .socket
-            .ssh_options(&mut command)
-            .args(
-                self.socket
-                    .connection_options
-                    .port
-                    .map(|port| vec!["-P".to_string(), port.to_string()])
-                    .unwrap_or_default(),
-            )
-            .arg(src_path)
-            .arg(format!(
-                "{}:{}",
-                self.socket.connection_options.scp_url(),
-                dest_path.display()
-            ))
-            .output()
-            .await?;
-
-        if output.status.success() {
-            Ok(())
-        } else {
-            Err(anyhow!(
-                "failed to upload file {} -> {}: {}",
-                src_path.display(),
-                dest_path.display(),
-                String::from_utf8_lossy(&output.stderr)
-            ))
-        }
-    }
-
     #[cfg(debug_assertions)]
     async fn build_local(
         &self,
@@ -2053,6 +2053,53 @@ impl SshRemoteConnection {
     }
 }
 
+#[async_trait(?Send)]
+impl RemoteShell for SshSocket {
+    async fn run_command(&self, program: &str, args: &[&str]) -> Result<String> {
+        let output = self.ssh_command(program, args).output().await?;
+        if output.status.success() {
+            Ok(String::from_utf8_lossy(&output.stdout).to_string())
+        } else {
+            Err(anyhow!(
+                "failed to run command: {}",
+                String::from_utf8_lossy(&output.stderr)
+            ))
+        }
+    }
+
+    async fn upload_file(&self, src_path: &Path, dest_path: &Path) -> Result<()> {
+        log::debug!("uploading file {:?} to {:?}", src_path, dest_path);
+        let mut command = util::command::new_smol_command("scp");
+        let output = self
+            .ssh_options(&mut command)
+            .args(
+                self.connection_options
+                    .port
+                    .map(|port| vec!["-P".to_string(), port.to_string()])
+                    .unwrap_or_default(),
+            )
+            .arg(src_path)
+            .arg(format!(
+                "{}:{}",
+                self.connection_options.scp_url(),
+                dest_path.display()
+            ))
+            .output()
+            .await?;
+
+        if output.status.success() {
+            Ok(())
+        } else {
+            Err(anyhow!(
+                "failed to upload file {} -> {}: {}",
+                src_path.display(),
+                dest_path.display(),
+                String::from_utf8_lossy(&output.stderr)
+            ))
+        }
+    }
+}
+
 type ResponseChannels = Mutex<HashMap<MessageId, oneshot::Sender<(Envelope, oneshot::Sender<()>)>>>;
 
 pub struct ChannelClient {
@@ -2416,6 +2463,9 @@ mod fake {
         fn ssh_args(&self) -> Vec<String> {
             Vec::new()
         }
+        fn exec_command(&self) -> Option<(String, Vec<String>)> {
+            None
+        }
         fn upload_directory(
             &self,
             _src_path: PathBuf,
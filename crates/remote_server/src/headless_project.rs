@@ -82,21 +82,22 @@ impl HeadlessProject {
             buffer_store.shared(SSH_PROJECT_ID, session.clone().into(), cx);
             buffer_store
         });
-        let prettier_store = cx.new_model(|cx| {
-            PrettierStore::new(
-                node_runtime.clone(),
-                fs.clone(),
+        let environment = project::ProjectEnvironment::new(&worktree_store, None, cx);
+        let toolchain_store = cx.new_model(|cx| {
+            ToolchainStore::local(
                 languages.clone(),
                 worktree_store.clone(),
+                environment.clone(),
                 cx,
             )
         });
-        let environment = project::ProjectEnvironment::new(&worktree_store, None, cx);
-        let toolchain_store = cx.new_model(|cx| {
-            ToolchainStore::local(
+        let prettier_store = cx.new_model(|cx| {
+            PrettierStore::new(
+                node_runtime.clone(),
+                fs.clone(),
                 languages.clone(),
                 worktree_store.clone(),
-                environment.clone(),
+                toolchain_store.read(cx).as_language_toolchain_store(),
                 cx,
             )
         });
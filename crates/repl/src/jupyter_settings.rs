@@ -1,10 +1,11 @@
 use std::collections::HashMap;
 
 use editor::EditorSettings;
-use gpui::AppContext;
+use gpui::{AppContext, Pixels};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use settings::{Settings, SettingsSources};
+use workspace::dock::DockPosition;
 
 #[derive(Debug, Default)]
 pub struct JupyterSettings {
@@ -61,3 +62,39 @@ impl Settings for JupyterSettings {
         Ok(settings)
     }
 }
+
+#[derive(Deserialize, Debug)]
+pub struct VariableExplorerSettings {
+    pub button: bool,
+    pub dock: DockPosition,
+    pub default_width: Pixels,
+}
+
+#[derive(Clone, Default, Serialize, Deserialize, JsonSchema, Debug)]
+pub struct VariableExplorerSettingsContent {
+    /// Whether to show the variable explorer button in the status bar.
+    ///
+    /// Default: true
+    pub button: Option<bool>,
+    /// Where to dock the variable explorer.
+    ///
+    /// Default: right
+    pub dock: Option<DockPosition>,
+    /// Default width of the variable explorer in pixels.
+    ///
+    /// Default: 360
+    pub default_width: Option<f32>,
+}
+
+impl Settings for VariableExplorerSettings {
+    const KEY: Option<&'static str> = Some("variable_explorer");
+
+    type FileContent = VariableExplorerSettingsContent;
+
+    fn load(
+        sources: SettingsSources<Self::FileContent>,
+        _cx: &mut gpui::AppContext,
+    ) -> anyhow::Result<Self> {
+        sources.json_merge()
+    }
+}
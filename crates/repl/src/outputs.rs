@@ -50,7 +50,7 @@ use image::ImageView;
 mod markdown;
 use markdown::MarkdownView;
 
-mod table;
+pub(crate) mod table;
 use table::TableView;
 
 pub mod plain;
@@ -7,6 +7,7 @@ mod repl_editor;
 mod repl_sessions_ui;
 mod repl_store;
 mod session;
+mod variable_explorer;
 
 use std::{sync::Arc, time::Duration};
 
@@ -24,6 +25,7 @@ pub use crate::repl_sessions_ui::{
 };
 use crate::repl_store::ReplStore;
 pub use crate::session::Session;
+pub use crate::variable_explorer::{RefreshVariables, ToggleVariableExplorer, VariableExplorer};
 use client::telemetry::Telemetry;
 
 pub const KERNEL_DOCS_URL: &str = "https://zed.dev/docs/repl#changing-kernels";
@@ -33,6 +35,7 @@ pub fn init(fs: Arc<dyn Fs>, telemetry: Arc<Telemetry>, cx: &mut AppContext) {
     JupyterSettings::register(cx);
     ::editor::init_settings(cx);
     repl_sessions_ui::init(cx);
+    variable_explorer::init(cx);
     ReplStore::init(fs, telemetry, cx);
 }
 
@@ -59,6 +59,7 @@ pub fn assign_kernelspec(
                             store.remove_session(shutdown_event.entity_id());
                         });
                     }
+                    SessionEvent::ExecutionFinished => {}
                 }
             })
             .detach();
@@ -126,6 +127,7 @@ pub fn run(editor: WeakView<Editor>, move_down: bool, cx: &mut WindowContext) ->
                                 store.remove_session(shutdown_event.entity_id());
                             });
                         }
+                        SessionEvent::ExecutionFinished => {}
                     }
                 })
                 .detach();
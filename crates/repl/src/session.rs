@@ -16,7 +16,7 @@ use editor::{
     scroll::Autoscroll,
     Anchor, AnchorRangeExt as _, Editor, MultiBuffer, ToPoint,
 };
-use futures::FutureExt as _;
+use futures::{channel::oneshot, FutureExt as _};
 use gpui::{
     div, prelude::*, EventEmitter, Model, Render, Subscription, Task, View, ViewContext, WeakView,
 };
@@ -24,7 +24,7 @@ use language::Point;
 use project::Fs;
 use runtimelib::{
     ExecuteRequest, ExecutionState, InterruptRequest, JupyterMessage, JupyterMessageContent,
-    ShutdownRequest,
+    ShutdownRequest, Stdio,
 };
 use std::{env::temp_dir, ops::Range, sync::Arc, time::Duration};
 use theme::ActiveTheme;
@@ -36,6 +36,7 @@ pub struct Session {
     editor: WeakView<Editor>,
     pub kernel: Kernel,
     blocks: HashMap<String, EditorBlock>,
+    inspections: HashMap<String, PendingInspection>,
     pub kernel_specification: KernelSpecification,
     telemetry: Arc<Telemetry>,
     _buffer_subscription: Subscription,
@@ -48,6 +49,14 @@ struct EditorBlock {
     execution_view: View<ExecutionView>,
 }
 
+/// Code run in the kernel on behalf of the editor, such as the variable explorer, rather than by
+/// the user.
+struct PendingInspection {
+    stdout: String,
+    error: Option<String>,
+    tx: oneshot::Sender<anyhow::Result<String>>,
+}
+
 type CloseBlockFn =
     Arc<dyn for<'a> Fn(CustomBlockId, &'a mut WindowContext) + Send + Sync + 'static>;
 
@@ -219,6 +228,7 @@ impl Session {
             editor,
             kernel: Kernel::StartingKernel(Task::ready(()).shared()),
             blocks: HashMap::default(),
+            inspections: HashMap::default(),
             kernel_specification,
             _buffer_subscription: subscription,
             telemetry,
@@ -478,16 +488,93 @@ impl Session {
         }
     }
 
+    /// Runs code in the kernel without storing it in the kernel's history or showing its outputs
+    /// in the editor, and returns what it printed to stdout once it has finished.
+    pub fn inspect(
+        &mut self,
+        code: String,
+        cx: &mut ViewContext<Self>,
+    ) -> Task<anyhow::Result<String>> {
+        if !matches!(self.kernel, Kernel::RunningKernel(_)) {
+            return Task::ready(Err(anyhow::anyhow!("kernel is not running")));
+        }
+
+        // Silent requests don't broadcast their outputs on IOPub, which is where stdout is read
+        // from, so the request only opts out of the history.
+        let message: JupyterMessage = ExecuteRequest {
+            code,
+            silent: false,
+            store_history: false,
+            ..ExecuteRequest::default()
+        }
+        .into();
+
+        let (tx, rx) = oneshot::channel();
+        self.inspections.insert(
+            message.header.msg_id.clone(),
+            PendingInspection {
+                stdout: String::new(),
+                error: None,
+                tx,
+            },
+        );
+        self.send(message, cx).ok();
+
+        cx.background_executor().spawn(async move { rx.await? })
+    }
+
+    fn route_inspection(&mut self, parent_message_id: &str, message: &JupyterMessage) -> bool {
+        let Some(inspection) = self.inspections.get_mut(parent_message_id) else {
+            return false;
+        };
+
+        match &message.content {
+            JupyterMessageContent::StreamContent(stream) => {
+                if matches!(stream.name, Stdio::Stdout) {
+                    inspection.stdout.push_str(&stream.text);
+                }
+            }
+            JupyterMessageContent::ErrorOutput(error) => {
+                inspection.error = Some(format!("{}: {}", error.ename, error.evalue));
+            }
+            JupyterMessageContent::Status(status) => {
+                self.kernel.set_execution_state(&status.execution_state);
+                if matches!(status.execution_state, ExecutionState::Idle) {
+                    if let Some(inspection) = self.inspections.remove(parent_message_id) {
+                        let result = match inspection.error {
+                            Some(error) => Err(anyhow::anyhow!(error)),
+                            None => Ok(inspection.stdout),
+                        };
+                        inspection.tx.send(result).ok();
+                    }
+                }
+            }
+            _ => {}
+        }
+        true
+    }
+
     pub fn route(&mut self, message: &JupyterMessage, cx: &mut ViewContext<Self>) {
         let parent_message_id = match message.parent_header.as_ref() {
             Some(header) => &header.msg_id,
             None => return,
         };
 
+        if self.route_inspection(parent_message_id, message) {
+            cx.notify();
+            return;
+        }
+
         match &message.content {
             JupyterMessageContent::Status(status) => {
                 self.kernel.set_execution_state(&status.execution_state);
 
+                if matches!(status.execution_state, ExecutionState::Idle)
+                    && self.blocks.contains_key(parent_message_id)
+                {
+                    cx.emit(SessionEvent::ExecutionFinished);
+                }
+
                 self.telemetry.report_repl_event(
                     self.kernel_specification.language().into(),
                     KernelStatus::from(&self.kernel).to_string(),
@@ -539,6 +626,9 @@ impl Session {
             cx.emit(SessionEvent::Shutdown(self.editor.clone()));
         }
 
+        // Any code that was being inspected won't finish on a kernel that's been replaced.
+        self.inspections.clear();
+
         let kernel_status = KernelStatus::from(&kernel).to_string();
         let kernel_language = self.kernel_specification.language().into();
 
@@ -630,6 +720,8 @@ impl Session {
 
 pub enum SessionEvent {
     Shutdown(WeakView<Editor>),
+    /// Code run from the editor has finished executing.
+    ExecutionFinished,
 }
 
 impl EventEmitter<SessionEvent> for Session {}
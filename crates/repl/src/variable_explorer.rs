@@ -0,0 +1,418 @@
+mod inspection;
+mod variable_table;
+
+use anyhow::Result;
+use db::kvp::KEY_VALUE_STORE;
+use editor::Editor;
+use gpui::{
+    actions, AppContext, AsyncWindowContext, EventEmitter, FocusHandle, FocusableView, Render,
+    Subscription, Task, View, ViewContext, WeakView,
+};
+use project::Fs;
+use serde::{Deserialize, Serialize};
+use settings::{Settings, SettingsStore};
+use std::sync::Arc;
+use ui::{prelude::*, Tooltip};
+use util::{ResultExt as _, TryFutureExt as _};
+use workspace::{
+    dock::{DockPosition, Panel, PanelEvent},
+    Workspace,
+};
+
+use crate::jupyter_settings::VariableExplorerSettings;
+use crate::repl_store::ReplStore;
+use crate::session::{Session, SessionEvent};
+use crate::{JupyterSettings, Kernel};
+use inspection::Variable;
+use variable_table::VariableTable;
+
+const VARIABLE_EXPLORER_KEY: &str = "VariableExplorer";
+
+actions!(repl, [ToggleVariableExplorer, RefreshVariables]);
+
+pub fn init(cx: &mut AppContext) {
+    VariableExplorerSettings::register(cx);
+
+    cx.observe_new_views(|workspace: &mut Workspace, _| {
+        workspace.register_action(|workspace, _: &ToggleVariableExplorer, cx| {
+            workspace.toggle_panel_focus::<VariableExplorer>(cx);
+        });
+    })
+    .detach();
+}
+
+/// Lists the variables of the REPL session of the active editor, along with their types,
+/// shapes and previews of their values.
+pub struct VariableExplorer {
+    fs: Arc<dyn Fs>,
+    workspace: WeakView<Workspace>,
+    width: Option<Pixels>,
+    focus_handle: FocusHandle,
+    pending_serialization: Task<Option<()>>,
+    session: Option<View<Session>>,
+    variables: Vec<Variable>,
+    error: Option<SharedString>,
+    refresh_task: Task<()>,
+    _session_subscriptions: Vec<Subscription>,
+    _subscriptions: Vec<Subscription>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct SerializedVariableExplorer {
+    width: Option<Pixels>,
+}
+
+impl VariableExplorer {
+    pub fn new(workspace: &mut Workspace, cx: &mut ViewContext<Workspace>) -> View<Self> {
+        let fs = workspace.app_state().fs.clone();
+        let workspace_view = cx.view().clone();
+
+        cx.new_view(|cx: &mut ViewContext<Self>| {
+            let subscriptions = vec![
+                cx.subscribe(&workspace_view, |this, _, event: &workspace::Event, cx| {
+                    if let workspace::Event::ActiveItemChanged = event {
+                        this.update_session(cx);
+                    }
+                }),
+                cx.observe(&ReplStore::global(cx), |this, _, cx| {
+                    this.update_session(cx)
+                }),
+                cx.observe_global::<SettingsStore>(|_: &mut Self, cx| cx.notify()),
+            ];
+
+            let this = Self {
+                fs,
+                workspace: workspace_view.downgrade(),
+                width: None,
+                focus_handle: cx.focus_handle(),
+                pending_serialization: Task::ready(None),
+                session: None,
+                variables: Vec::new(),
+                error: None,
+                refresh_task: Task::ready(()),
+                _session_subscriptions: Vec::new(),
+                _subscriptions: subscriptions,
+            };
+            cx.defer(|this, cx| this.update_session(cx));
+            this
+        })
+    }
+
+    pub fn load(
+        workspace: WeakView<Workspace>,
+        cx: AsyncWindowContext,
+    ) -> Task<Result<View<Self>>> {
+        cx.spawn(|mut cx| async move {
+            let serialized_panel = if let Some(panel) = cx
+                .background_executor()
+                .spawn(async move { KEY_VALUE_STORE.read_kvp(VARIABLE_EXPLORER_KEY) })
+                .await
+                .log_err()
+                .flatten()
+            {
+                Some(serde_json::from_str::<SerializedVariableExplorer>(&panel)?)
+            } else {
+                None
+            };
+
+            workspace.update(&mut cx, |workspace, cx| {
+                let panel = Self::new(workspace, cx);
+                if let Some(serialized_panel) = serialized_panel {
+                    panel.update(cx, |panel, cx| {
+                        panel.width = serialized_panel.width.map(|w| w.round());
+                        cx.notify();
+                    });
+                }
+                panel
+            })
+        })
+    }
+
+    fn serialize(&mut self, cx: &mut ViewContext<Self>) {
+        let width = self.width;
+        self.pending_serialization = cx.background_executor().spawn(
+            async move {
+                KEY_VALUE_STORE
+                    .write_kvp(
+                        VARIABLE_EXPLORER_KEY.into(),
+                        serde_json::to_string(&SerializedVariableExplorer { width })?,
+                    )
+                    .await?;
+                anyhow::Ok(())
+            }
+            .log_err(),
+        );
+    }
+
+    /// Switches to the session of the active editor. While other items are active, the last
+    /// session stays selected until it's shut down.
+    fn update_session(&mut self, cx: &mut ViewContext<Self>) {
+        let Some(workspace) = self.workspace.upgrade() else {
+            return;
+        };
+        let store = ReplStore::global(cx);
+        let store = store.read(cx);
+        let session = workspace
+            .read(cx)
+            .active_item_as::<Editor>(cx)
+            .and_then(|editor| store.get_session(editor.entity_id()).cloned())
+            .or_else(|| {
+                self.session
+                    .clone()
+                    .filter(|session| store.sessions().any(|other| other == session))
+            });
+        if session == self.session {
+            return;
+        }
+
+        self._session_subscriptions = session
+            .iter()
+            .flat_map(|session| {
+                [
+                    cx.subscribe(session, |this, _, event, cx| match event {
+                        SessionEvent::ExecutionFinished => this.refresh(cx),
+                        SessionEvent::Shutdown(_) => {
+                            this.session = None;
+                            this.refresh(cx);
+                        }
+                    }),
+                    cx.observe(session, |_, _, cx| cx.notify()),
+                ]
+            })
+            .collect();
+        self.session = session;
+        self.variables.clear();
+        self.refresh(cx);
+    }
+
+    fn refresh(&mut self, cx: &mut ViewContext<Self>) {
+        let code = self.session.as_ref().and_then(|session| {
+            let session = session.read(cx);
+            if matches!(session.kernel, Kernel::RunningKernel(_)) {
+                inspection::list_variables_code(&session.kernel_specification.language())
+            } else {
+                None
+            }
+        });
+        let (Some(session), Some(code)) = (self.session.clone(), code) else {
+            self.variables.clear();
+            self.error = None;
+            self.refresh_task = Task::ready(());
+            cx.notify();
+            return;
+        };
+
+        let inspection = session.update(cx, |session, cx| session.inspect(code, cx));
+        self.refresh_task = cx.spawn(|this, mut cx| async move {
+            let variables = inspection
+                .await
+                .and_then(|stdout| inspection::parse_variables(&stdout));
+            this.update(&mut cx, |this, cx| {
+                match variables {
+                    Ok(variables) => {
+                        this.variables = variables;
+                        this.error = None;
+                    }
+                    Err(error) => this.error = Some(error.to_string().into()),
+                }
+                cx.notify();
+            })
+            .ok();
+        });
+    }
+
+    fn open_table(&mut self, name: String, cx: &mut ViewContext<Self>) {
+        let Some(session) = self.session.clone() else {
+            return;
+        };
+        let language = session.read(cx).kernel_specification.language();
+        let Some(code) = inspection::variable_table_code(&language, &name) else {
+            return;
+        };
+
+        let inspection = session.update(cx, |session, cx| session.inspect(code, cx));
+        let workspace = self.workspace.clone();
+        cx.spawn(|this, mut cx| async move {
+            let table = inspection
+                .await
+                .and_then(|stdout| inspection::parse_table(&stdout));
+            match table {
+                Ok(table) => workspace.update(&mut cx, |workspace, cx| {
+                    let table = cx.new_view(|cx| VariableTable::new(name, table, cx));
+                    workspace.add_item_to_active_pane(Box::new(table), None, true, cx);
+                }),
+                Err(error) => this.update(&mut cx, |this, cx| {
+                    this.error = Some(error.to_string().into());
+                    cx.notify();
+                }),
+            }
+        })
+        .detach_and_log_err(cx);
+    }
+
+    fn render_variable(&self, ix: usize, variable: &Variable, cx: &mut ViewContext<Self>) -> Div {
+        let name = variable.name.clone();
+        v_flex()
+            .px_2()
+            .py_1()
+            .border_b_1()
+            .border_color(cx.theme().colors().border_variant)
+            .child(
+                h_flex()
+                    .gap_2()
+                    .child(Label::new(variable.name.clone()))
+                    .child(
+                        Label::new(variable.type_name.clone())
+                            .size(LabelSize::Small)
+                            .color(Color::Muted),
+                    )
+                    .children(
+                        variable.shape.clone().map(|shape| {
+                            Label::new(shape).size(LabelSize::Small).color(Color::Muted)
+                        }),
+                    )
+                    .child(div().flex_1())
+                    .when(variable.tabular, |el| {
+                        el.child(
+                            IconButton::new(("open-variable-table", ix), IconName::Maximize)
+                                .icon_size(IconSize::Small)
+                                .tooltip(|cx| Tooltip::text("Open in Table Viewer", cx))
+                                .on_click(cx.listener(move |this, _, cx| {
+                                    this.open_table(name.clone(), cx)
+                                })),
+                        )
+                    }),
+            )
+            .child(
+                Label::new(variable.preview.clone())
+                    .size(LabelSize::Small)
+                    .color(Color::Muted)
+                    .single_line(),
+            )
+    }
+}
+
+impl Render for VariableExplorer {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let session = self.session.as_ref().map(|session| session.read(cx));
+        let title = session.map(|session| session.kernel_specification.name());
+        let message = match session {
+            None => Some("Run some code in an editor to start a REPL session.".into()),
+            Some(session) => {
+                let language = session.kernel_specification.language();
+                if !inspection::is_language_supported(&language) {
+                    Some(format!(
+                        "Variables can't be listed for {language} kernels yet."
+                    ))
+                } else if !matches!(session.kernel, Kernel::RunningKernel(_)) {
+                    Some("The kernel isn't running.".into())
+                } else if self.variables.is_empty() && self.error.is_none() {
+                    Some("No variables have been defined yet.".into())
+                } else {
+                    None
+                }
+            }
+        };
+
+        v_flex()
+            .key_context("VariableExplorer")
+            .track_focus(&self.focus_handle)
+            .on_action(cx.listener(|this, _: &RefreshVariables, cx| this.refresh(cx)))
+            .size_full()
+            .child(
+                h_flex()
+                    .justify_between()
+                    .px_2()
+                    .py_1()
+                    .border_b_1()
+                    .border_color(cx.theme().colors().border)
+                    .child(Label::new(title.unwrap_or_else(|| "Variables".into())))
+                    .child(
+                        IconButton::new("refresh-variables", IconName::RotateCw)
+                            .icon_size(IconSize::Small)
+                            .disabled(self.session.is_none())
+                            .tooltip(|cx| Tooltip::for_action("Refresh", &RefreshVariables, cx))
+                            .on_click(cx.listener(|this, _, cx| this.refresh(cx))),
+                    ),
+            )
+            .children(self.error.clone().map(|error| {
+                div()
+                    .p_2()
+                    .child(Label::new(error).size(LabelSize::Small).color(Color::Error))
+            }))
+            .children(message.map(|message| {
+                div().p_4().child(
+                    Label::new(message)
+                        .size(LabelSize::Small)
+                        .color(Color::Muted),
+                )
+            }))
+            .child(
+                v_flex()
+                    .id("variables")
+                    .flex_1()
+                    .overflow_y_scroll()
+                    .children(
+                        self.variables
+                            .iter()
+                            .enumerate()
+                            .map(|(ix, variable)| self.render_variable(ix, variable, cx))
+                            .collect::<Vec<_>>(),
+                    ),
+            )
+    }
+}
+
+impl FocusableView for VariableExplorer {
+    fn focus_handle(&self, _: &AppContext) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl EventEmitter<PanelEvent> for VariableExplorer {}
+
+impl Panel for VariableExplorer {
+    fn persistent_name() -> &'static str {
+        "VariableExplorer"
+    }
+
+    fn position(&self, cx: &WindowContext) -> DockPosition {
+        VariableExplorerSettings::get_global(cx).dock
+    }
+
+    fn position_is_valid(&self, position: DockPosition) -> bool {
+        matches!(position, DockPosition::Left | DockPosition::Right)
+    }
+
+    fn set_position(&mut self, position: DockPosition, cx: &mut ViewContext<Self>) {
+        settings::update_settings_file::<VariableExplorerSettings>(
+            self.fs.clone(),
+            cx,
+            move |settings, _| settings.dock = Some(position),
+        );
+    }
+
+    fn size(&self, cx: &WindowContext) -> Pixels {
+        self.width
+            .unwrap_or_else(|| VariableExplorerSettings::get_global(cx).default_width)
+    }
+
+    fn set_size(&mut self, size: Option<Pixels>, cx: &mut ViewContext<Self>) {
+        self.width = size;
+        self.serialize(cx);
+        cx.notify();
+    }
+
+    fn icon(&self, cx: &WindowContext) -> Option<IconName> {
+        (JupyterSettings::enabled(cx) && VariableExplorerSettings::get_global(cx).button)
+            .then_some(IconName::Code)
+    }
+
+    fn icon_tooltip(&self, _cx: &WindowContext) -> Option<&'static str> {
+        Some("Variable Explorer")
+    }
+
+    fn toggle_action(&self) -> Box<dyn gpui::Action> {
+        Box::new(ToggleVariableExplorer)
+    }
+}
@@ -0,0 +1,217 @@
+//! Code the variable explorer runs in a kernel to find out about the variables in its namespace.
+//!
+//! Jupyter's messaging protocol has no request for listing variables, so for each kernel language
+//! we run a snippet outside of the editor that prints what we need as JSON on stdout.
+
+use anyhow::{Context as _, Result};
+use runtimelib::media::datatable::TabularDataResource;
+use serde::Deserialize;
+
+/// The number of rows of a DataFrame or array that are shown in the table viewer.
+const TABLE_ROW_LIMIT: usize = 1000;
+
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+pub struct Variable {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub type_name: String,
+    /// The dimensions of an array or DataFrame, or the length of other collections.
+    pub shape: Option<String>,
+    pub preview: String,
+    /// Whether the variable can be opened in the table viewer.
+    #[serde(default)]
+    pub tabular: bool,
+}
+
+const PYTHON_LIST_VARIABLES: &str = r#"
+def __zed_list_variables():
+    import inspect
+    import json
+    import reprlib
+
+    try:
+        shell = get_ipython()
+        namespace, hidden = shell.user_ns, shell.user_ns_hidden
+    except NameError:
+        namespace, hidden = globals(), {}
+
+    short_repr = reprlib.Repr()
+    short_repr.maxstring = 80
+    short_repr.maxother = 80
+
+    variables = []
+    for name, value in list(namespace.items()):
+        if name.startswith("_") or name in hidden:
+            continue
+        if inspect.ismodule(value) or inspect.isclass(value) or inspect.isroutine(value):
+            continue
+
+        value_type = type(value)
+        module = value_type.__module__.split(".")[0]
+        shape = getattr(value, "shape", None)
+        if isinstance(shape, tuple):
+            shape = " × ".join(str(dimension) for dimension in shape)
+        elif not isinstance(value, str) and hasattr(value, "__len__"):
+            try:
+                shape = str(len(value))
+            except Exception:
+                shape = None
+        else:
+            shape = None
+
+        try:
+            preview = short_repr.repr(value)
+        except Exception:
+            preview = "<unavailable>"
+
+        tabular = (
+            module in ("pandas", "polars") and value_type.__name__ in ("DataFrame", "Series")
+        ) or (
+            module == "numpy" and value_type.__name__ == "ndarray" and value.ndim in (1, 2)
+        )
+
+        variables.append({
+            "name": name,
+            "type": value_type.__name__,
+            "shape": shape,
+            "preview": preview,
+            "tabular": tabular,
+        })
+
+    print(json.dumps(variables))
+
+try:
+    __zed_list_variables()
+finally:
+    del __zed_list_variables
+"#;
+
+const PYTHON_VARIABLE_TABLE: &str = r#"
+def __zed_variable_table(name, limit):
+    import json
+    import math
+
+    try:
+        namespace = get_ipython().user_ns
+    except NameError:
+        namespace = globals()
+
+    value = namespace[name]
+    module = type(value).__module__.split(".")[0]
+    if module == "pandas":
+        print(value.head(limit).to_json(orient="table", default_handler=str))
+        return
+
+    if module == "polars":
+        frame = value.head(limit)
+        if hasattr(frame, "to_frame"):
+            frame = frame.to_frame()
+        columns = [str(column) for column in frame.columns]
+        rows = [list(row) for row in frame.rows()]
+    else:
+        rows = value[:limit].tolist()
+        if value.ndim == 1:
+            rows = [[cell] for cell in rows]
+        columns = [str(column) for column in range(value.shape[1] if value.ndim == 2 else 1)]
+
+    def cell(value):
+        if isinstance(value, float) and not math.isfinite(value):
+            return None
+        return value
+
+    fields = [{"name": "index", "type": "integer"}]
+    fields += [{"name": column, "type": "any"} for column in columns]
+    data = [
+        dict(zip(["index"] + columns, [index] + [cell(value) for value in row]))
+        for index, row in enumerate(rows)
+    ]
+    print(json.dumps({"schema": {"fields": fields, "primaryKey": ["index"]}, "data": data}, default=str))
+"#;
+
+pub fn is_language_supported(language: &str) -> bool {
+    language.eq_ignore_ascii_case("python")
+}
+
+/// Returns the code that prints the variables in the kernel's namespace.
+pub fn list_variables_code(language: &str) -> Option<String> {
+    is_language_supported(language).then(|| PYTHON_LIST_VARIABLES.to_string())
+}
+
+/// Returns the code that prints the first rows of the given variable as a tabular data resource.
+pub fn variable_table_code(language: &str, name: &str) -> Option<String> {
+    if !is_language_supported(language) {
+        return None;
+    }
+    // A JSON string is also a valid Python string literal.
+    let name = serde_json::to_string(name).ok()?;
+    Some(format!(
+        "{PYTHON_VARIABLE_TABLE}
+try:
+    __zed_variable_table({name}, {TABLE_ROW_LIMIT})
+finally:
+    del __zed_variable_table
+"
+    ))
+}
+
+pub fn parse_variables(stdout: &str) -> Result<Vec<Variable>> {
+    serde_json::from_str(last_line(stdout)?).context("failed to parse variables")
+}
+
+pub fn parse_table(stdout: &str) -> Result<TabularDataResource> {
+    serde_json::from_str(last_line(stdout)?).context("failed to parse table")
+}
+
+/// The JSON is printed last, after anything printed while the values were inspected.
+fn last_line(stdout: &str) -> Result<&str> {
+    stdout
+        .lines()
+        .rev()
+        .find(|line| !line.trim().is_empty())
+        .context("kernel printed nothing")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_variables() {
+        let stdout = concat!(
+            "some warning\n",
+            r#"[{"name": "df", "type": "DataFrame", "shape": "3 × 2", "preview": "   a  b", "tabular": true}, "#,
+            r#"{"name": "x", "type": "int", "shape": null, "preview": "1", "tabular": false}]"#,
+            "\n"
+        );
+        assert_eq!(
+            parse_variables(stdout).unwrap(),
+            [
+                Variable {
+                    name: "df".into(),
+                    type_name: "DataFrame".into(),
+                    shape: Some("3 × 2".into()),
+                    preview: "   a  b".into(),
+                    tabular: true,
+                },
+                Variable {
+                    name: "x".into(),
+                    type_name: "int".into(),
+                    shape: None,
+                    preview: "1".into(),
+                    tabular: false,
+                },
+            ]
+        );
+        assert!(parse_variables("").is_err());
+    }
+
+    #[test]
+    fn test_variable_table_code() {
+        let code = variable_table_code("python", "df").unwrap();
+        assert!(code.contains(r#"__zed_variable_table("df", 1000)"#));
+        // Names are inserted as they are, even when they contain words of the snippet.
+        let code = variable_table_code("python", "RATE_LIMIT").unwrap();
+        assert!(code.contains(r#"__zed_variable_table("RATE_LIMIT", 1000)"#));
+        assert_eq!(variable_table_code("typescript", "df"), None);
+    }
+}
@@ -0,0 +1,80 @@
+use gpui::{AppContext, EventEmitter, FocusHandle, FocusableView, View};
+use runtimelib::media::datatable::TabularDataResource;
+use ui::prelude::*;
+use workspace::item::{Item, ItemEvent};
+use workspace::WorkspaceId;
+
+use crate::outputs::table::TableView;
+
+/// Shows the first rows of a DataFrame or array from a kernel session in a tab.
+pub struct VariableTable {
+    name: SharedString,
+    table: View<TableView>,
+    focus_handle: FocusHandle,
+}
+
+impl VariableTable {
+    pub fn new(
+        name: impl Into<SharedString>,
+        table: TabularDataResource,
+        cx: &mut ViewContext<Self>,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            table: cx.new_view(|cx| TableView::new(&table, cx)),
+            focus_handle: cx.focus_handle(),
+        }
+    }
+}
+
+impl EventEmitter<ItemEvent> for VariableTable {}
+
+impl FocusableView for VariableTable {
+    fn focus_handle(&self, _cx: &AppContext) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Item for VariableTable {
+    type Event = ItemEvent;
+
+    fn tab_content_text(&self, _cx: &WindowContext) -> Option<SharedString> {
+        Some(self.name.clone())
+    }
+
+    fn tab_icon(&self, _cx: &WindowContext) -> Option<Icon> {
+        Some(Icon::new(IconName::Code))
+    }
+
+    fn telemetry_event_text(&self) -> Option<&'static str> {
+        Some("repl variable table")
+    }
+
+    fn show_toolbar(&self) -> bool {
+        false
+    }
+
+    fn clone_on_split(
+        &self,
+        _workspace_id: Option<WorkspaceId>,
+        _: &mut ViewContext<Self>,
+    ) -> Option<View<Self>> {
+        None
+    }
+
+    fn to_item_events(event: &Self::Event, mut f: impl FnMut(ItemEvent)) {
+        f(*event)
+    }
+}
+
+impl Render for VariableTable {
+    fn render(&mut self, _cx: &mut ViewContext<Self>) -> impl IntoElement {
+        v_flex()
+            .id("variable-table")
+            .track_focus(&self.focus_handle)
+            .size_full()
+            .p_2()
+            .overflow_y_scroll()
+            .child(self.table.clone())
+    }
+}
@@ -23,6 +23,9 @@ pub type EditorconfigProperties = ec4rs::Properties;
 
 use crate::{SettingsJsonSchemaParams, WorktreeId};
 
+/// The key in the user settings under which named settings profiles are defined.
+const PROFILES_KEY: &str = "profiles";
+
 /// A value that can be defined as a user setting.
 ///
 /// Settings can be loaded from a combination of multiple JSON files.
@@ -114,6 +117,8 @@ pub struct SettingsSources<'a, T> {
     pub user: Option<&'a T>,
     /// The user settings for the current release channel.
     pub release_channel: Option<&'a T>,
+    /// The user settings for the active settings profile.
+    pub profile: Option<&'a T>,
     /// The server's settings.
     pub server: Option<&'a T>,
     /// The project settings, ordered from least specific to most specific.
@@ -132,6 +137,7 @@ impl<'a, T: Serialize> SettingsSources<'a, T> {
             .into_iter()
             .chain(self.user)
             .chain(self.release_channel)
+            .chain(self.profile)
             .chain(self.server)
             .chain(self.project.iter().copied())
     }
@@ -169,6 +175,7 @@ pub struct SettingsStore {
     setting_values: HashMap<TypeId, Box<dyn AnySettingValue>>,
     raw_default_settings: serde_json::Value,
     raw_user_settings: serde_json::Value,
+    active_profile: Option<String>,
     raw_server_settings: Option<serde_json::Value>,
     raw_extension_settings: serde_json::Value,
     raw_local_settings: BTreeMap<(WorktreeId, Arc<Path>), serde_json::Value>,
@@ -247,6 +254,7 @@ impl SettingsStore {
             setting_values: Default::default(),
             raw_default_settings: serde_json::json!({}),
             raw_user_settings: serde_json::json!({}),
+            active_profile: None,
             raw_server_settings: None,
             raw_extension_settings: serde_json::json!({}),
             raw_local_settings: Default::default(),
@@ -300,6 +308,16 @@ impl SettingsStore {
                     .log_err();
             }
 
+            let profile_value = self
+                .active_profile
+                .as_ref()
+                .and_then(|profile| self.raw_user_settings.get(PROFILES_KEY)?.get(profile))
+                .and_then(|profile_settings| {
+                    setting_value
+                        .deserialize_setting(profile_settings)
+                        .log_err()
+                });
+
             let server_value = self
                 .raw_server_settings
                 .as_ref()
@@ -318,6 +336,7 @@ impl SettingsStore {
                         extensions: extension_value.as_ref(),
                         user: user_value.as_ref(),
                         release_channel: release_channel_value.as_ref(),
+                        profile: profile_value.as_ref(),
                         server: server_value.as_ref(),
                         project: &[],
                     },
@@ -344,6 +363,14 @@ impl SettingsStore {
             .expect("no default value for setting type")
     }
 
+    /// Get the value of a setting, if its type has been registered.
+    pub fn try_get<T: Settings>(&self, path: Option<SettingsLocation>) -> Option<&T> {
+        self.setting_values
+            .get(&TypeId::of::<T>())
+            .map(|value| value.value_for_path(path))
+            .and_then(|value| value.downcast_ref::<T>())
+    }
+
     /// Override the global value for a setting.
     ///
     /// The given value will be overwritten if the user settings file changes.
@@ -354,6 +381,40 @@ impl SettingsStore {
             .set_global_value(Box::new(value))
     }
 
+    /// Returns the names of the settings profiles defined in the user settings,
+    /// in the order they appear in the file.
+    pub fn profile_names(&self) -> Vec<String> {
+        self.raw_user_settings
+            .get(PROFILES_KEY)
+            .and_then(|profiles| profiles.as_object())
+            .map(|profiles| profiles.keys().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    /// Returns the name of the settings profile that is currently layered on
+    /// top of the user settings, if any.
+    pub fn active_profile(&self) -> Option<&str> {
+        self.active_profile.as_deref()
+    }
+
+    /// Activates the settings profile with the given name, or deactivates the
+    /// current profile when `None` is given, and recomputes all settings.
+    ///
+    /// A profile that isn't defined in the user settings is still remembered,
+    /// and takes effect once the user settings define it.
+    pub fn set_active_profile(
+        &mut self,
+        profile: Option<String>,
+        cx: &mut AppContext,
+    ) -> Result<()> {
+        if self.active_profile == profile {
+            return Ok(());
+        }
+        self.active_profile = profile;
+        self.recompute_values(None, cx)?;
+        Ok(())
+    }
+
     /// Get the user's settings as a raw JSON value.
     ///
     /// For user-facing functionality use the typed setting interface.
@@ -830,15 +891,23 @@ impl SettingsStore {
             }
         }
 
+        let overlay_schema = combined_schema.schema.clone();
         for release_stage in ["dev", "nightly", "stable", "preview"] {
-            let schema = combined_schema.schema.clone();
             combined_schema
                 .schema
                 .object()
                 .properties
-                .insert(release_stage.to_string(), schema.into());
+                .insert(release_stage.to_string(), overlay_schema.clone().into());
         }
 
+        let mut profiles_schema = SchemaObject::default();
+        profiles_schema.object().additional_properties = Some(Box::new(overlay_schema.into()));
+        combined_schema
+            .schema
+            .object()
+            .properties
+            .insert(PROFILES_KEY.to_string(), profiles_schema.into());
+
         serde_json::to_value(&combined_schema).unwrap()
     }
 
@@ -888,6 +957,12 @@ impl SettingsStore {
                 }
             }
 
+            let profile_settings = self
+                .active_profile
+                .as_ref()
+                .and_then(|profile| self.raw_user_settings.get(PROFILES_KEY)?.get(profile))
+                .and_then(|settings| setting_value.deserialize_setting(settings).log_err());
+
             // If the global settings file changed, reload the global value for the field.
             if changed_local_path.is_none() {
                 if let Some(value) = setting_value
@@ -897,6 +972,7 @@ impl SettingsStore {
                             extensions: extension_settings.as_ref(),
                             user: user_settings.as_ref(),
                             release_channel: release_channel_settings.as_ref(),
+                            profile: profile_settings.as_ref(),
                             server: server_settings.as_ref(),
                             project: &[],
                         },
@@ -948,6 +1024,7 @@ impl SettingsStore {
                                     extensions: extension_settings.as_ref(),
                                     user: user_settings.as_ref(),
                                     release_channel: release_channel_settings.as_ref(),
+                                    profile: profile_settings.as_ref(),
                                     server: server_settings.as_ref(),
                                     project: &project_settings_stack.iter().collect::<Vec<_>>(),
                                 },
@@ -1068,6 +1145,9 @@ impl<T: Settings> AnySettingValue for SettingValue<T> {
                 release_channel: values
                     .release_channel
                     .map(|value| value.0.downcast_ref::<T::FileContent>().unwrap()),
+                profile: values
+                    .profile
+                    .map(|value| value.0.downcast_ref::<T::FileContent>().unwrap()),
                 server: values
                     .server
                     .map(|value| value.0.downcast_ref::<T::FileContent>().unwrap()),
@@ -1526,6 +1606,98 @@ mod tests {
         );
     }
 
+    #[gpui::test]
+    fn test_settings_profiles(cx: &mut AppContext) {
+        let mut store = SettingsStore::new(cx);
+        store.register_setting::<UserSettings>(cx);
+        store.register_setting::<TurboSetting>(cx);
+        store
+            .set_default_settings(
+                r#"{
+                    "turbo": false,
+                    "user": {
+                        "name": "John Doe",
+                        "age": 30,
+                        "staff": false
+                    }
+                }"#,
+                cx,
+            )
+            .unwrap();
+        store
+            .set_user_settings(
+                r#"{
+                    "user": { "age": 31 },
+                    "profiles": {
+                        "presentation": { "turbo": true },
+                        "pairing": { "user": { "staff": true } }
+                    }
+                }"#,
+                cx,
+            )
+            .unwrap();
+
+        assert_eq!(store.profile_names(), vec!["presentation", "pairing"]);
+        assert_eq!(store.active_profile(), None);
+        assert_eq!(store.get::<TurboSetting>(None), &TurboSetting(false));
+
+        store
+            .set_active_profile(Some("presentation".into()), cx)
+            .unwrap();
+        assert_eq!(store.active_profile(), Some("presentation"));
+        assert_eq!(store.get::<TurboSetting>(None), &TurboSetting(true));
+
+        store
+            .set_active_profile(Some("pairing".into()), cx)
+            .unwrap();
+        assert_eq!(store.get::<TurboSetting>(None), &TurboSetting(false));
+        assert_eq!(
+            store.get::<UserSettings>(None),
+            &UserSettings {
+                name: "John Doe".to_string(),
+                age: 31,
+                staff: true,
+            }
+        );
+
+        // Project settings still take precedence over the active profile.
+        store
+            .set_local_settings(
+                WorktreeId::from_usize(1),
+                Path::new("/root1").into(),
+                LocalSettingsKind::Settings,
+                Some(r#"{ "user": { "staff": false } }"#),
+                cx,
+            )
+            .unwrap();
+        assert!(
+            !store
+                .get::<UserSettings>(Some(SettingsLocation {
+                    worktree_id: WorktreeId::from_usize(1),
+                    path: Path::new("/root1/something"),
+                }))
+                .staff
+        );
+
+        store.set_active_profile(None, cx).unwrap();
+        assert!(!store.get::<UserSettings>(None).staff);
+
+        store.set_active_profile(Some("focus".into()), cx).unwrap();
+        assert!(!store.get::<UserSettings>(None).staff);
+        store
+            .set_user_settings(
+                r#"{
+                    "profiles": {
+                        "focus": { "user": { "staff": true } }
+                    }
+                }"#,
+                cx,
+            )
+            .unwrap();
+        assert_eq!(store.active_profile(), Some("focus"));
+        assert!(store.get::<UserSettings>(None).staff);
+    }
+
     #[gpui::test]
     fn test_setting_store_update(cx: &mut AppContext) {
         let mut store = SettingsStore::new(cx);
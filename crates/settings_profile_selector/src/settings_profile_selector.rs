@@ -0,0 +1,215 @@
+use fuzzy::{match_strings, StringMatch, StringMatchCandidate};
+use gpui::{
+    actions, AppContext, DismissEvent, EventEmitter, FocusHandle, FocusableView, Render, Task,
+    View, ViewContext, VisualContext, WeakView,
+};
+use picker::{Picker, PickerDelegate};
+use settings::SettingsStore;
+use std::sync::Arc;
+use ui::{prelude::*, HighlightedLabel, ListItem, ListItemSpacing};
+use util::ResultExt;
+use workspace::{ModalView, Workspace};
+
+actions!(settings_profile_selector, [Toggle]);
+
+pub fn init(cx: &mut AppContext) {
+    cx.observe_new_views(SettingsProfileSelector::register)
+        .detach();
+}
+
+const DEFAULT_PROFILE_LABEL: &str = "Default";
+
+pub struct SettingsProfileSelector {
+    picker: View<Picker<SettingsProfileSelectorDelegate>>,
+}
+
+impl SettingsProfileSelector {
+    fn register(workspace: &mut Workspace, _: &mut ViewContext<Workspace>) {
+        workspace.register_action(|workspace, _: &Toggle, cx| {
+            let weak_workspace = cx.view().downgrade();
+            let active_profile = workspace.settings_profile().map(ToString::to_string);
+            workspace.toggle_modal(cx, |cx| Self::new(weak_workspace, active_profile, cx));
+        });
+    }
+
+    fn new(
+        workspace: WeakView<Workspace>,
+        active_profile: Option<String>,
+        cx: &mut ViewContext<Self>,
+    ) -> Self {
+        let profiles = cx.global::<SettingsStore>().profile_names();
+        let delegate = SettingsProfileSelectorDelegate::new(
+            workspace,
+            profiles,
+            active_profile,
+            cx.view().downgrade(),
+        );
+        let picker = cx.new_view(|cx| Picker::uniform_list(delegate, cx));
+        Self { picker }
+    }
+}
+
+impl ModalView for SettingsProfileSelector {}
+
+impl EventEmitter<DismissEvent> for SettingsProfileSelector {}
+
+impl FocusableView for SettingsProfileSelector {
+    fn focus_handle(&self, cx: &AppContext) -> FocusHandle {
+        self.picker.focus_handle(cx)
+    }
+}
+
+impl Render for SettingsProfileSelector {
+    fn render(&mut self, _: &mut ViewContext<Self>) -> impl IntoElement {
+        v_flex().w(rems(34.)).child(self.picker.clone())
+    }
+}
+
+pub struct SettingsProfileSelectorDelegate {
+    selector: WeakView<SettingsProfileSelector>,
+    workspace: WeakView<Workspace>,
+    /// The profiles defined in the user settings, preceded by `None` for the
+    /// default settings.
+    profiles: Vec<Option<String>>,
+    active_profile: Option<String>,
+    matches: Vec<StringMatch>,
+    selected_index: usize,
+}
+
+impl SettingsProfileSelectorDelegate {
+    fn new(
+        workspace: WeakView<Workspace>,
+        profile_names: Vec<String>,
+        active_profile: Option<String>,
+        selector: WeakView<SettingsProfileSelector>,
+    ) -> Self {
+        let profiles = std::iter::once(None)
+            .chain(profile_names.into_iter().map(Some))
+            .collect::<Vec<_>>();
+        let selected_index = profiles
+            .iter()
+            .position(|profile| *profile == active_profile)
+            .unwrap_or(0);
+        Self {
+            selector,
+            workspace,
+            profiles,
+            active_profile,
+            matches: Vec::new(),
+            selected_index,
+        }
+    }
+
+    fn profile_label(profile: &Option<String>) -> &str {
+        profile.as_deref().unwrap_or(DEFAULT_PROFILE_LABEL)
+    }
+}
+
+impl PickerDelegate for SettingsProfileSelectorDelegate {
+    type ListItem = ListItem;
+
+    fn placeholder_text(&self, _: &mut WindowContext) -> Arc<str> {
+        "Select a settings profile...".into()
+    }
+
+    fn match_count(&self) -> usize {
+        self.matches.len()
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(&mut self, ix: usize, _: &mut ViewContext<Picker<Self>>) {
+        self.selected_index = ix;
+    }
+
+    fn update_matches(&mut self, query: String, cx: &mut ViewContext<Picker<Self>>) -> Task<()> {
+        let candidates = self
+            .profiles
+            .iter()
+            .enumerate()
+            .map(|(id, profile)| StringMatchCandidate::new(id, Self::profile_label(profile)))
+            .collect::<Vec<_>>();
+
+        cx.spawn(|picker, mut cx| async move {
+            let matches = if query.is_empty() {
+                candidates
+                    .into_iter()
+                    .map(|candidate| StringMatch {
+                        candidate_id: candidate.id,
+                        string: candidate.string,
+                        positions: Vec::new(),
+                        score: 0.0,
+                    })
+                    .collect()
+            } else {
+                match_strings(
+                    &candidates,
+                    &query,
+                    false,
+                    100,
+                    &Default::default(),
+                    cx.background_executor().clone(),
+                )
+                .await
+            };
+
+            picker
+                .update(&mut cx, |picker, _| {
+                    let delegate = &mut picker.delegate;
+                    delegate.matches = matches;
+                    delegate.selected_index = delegate
+                        .selected_index
+                        .min(delegate.matches.len().saturating_sub(1));
+                })
+                .log_err();
+        })
+    }
+
+    fn confirm(&mut self, _: bool, cx: &mut ViewContext<Picker<Self>>) {
+        let Some(profile_match) = self.matches.get(self.selected_index) else {
+            return;
+        };
+        let profile = self.profiles[profile_match.candidate_id].clone();
+        self.workspace
+            .update(cx, |workspace, cx| {
+                workspace.set_settings_profile(profile, cx)
+            })
+            .log_err();
+        self.dismissed(cx);
+    }
+
+    fn dismissed(&mut self, cx: &mut ViewContext<Picker<Self>>) {
+        self.selector
+            .update(cx, |_, cx| cx.emit(DismissEvent))
+            .log_err();
+    }
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        _: &mut ViewContext<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        let profile_match = self.matches.get(ix)?;
+        let is_active = self.profiles[profile_match.candidate_id] == self.active_profile;
+        Some(
+            ListItem::new(ix)
+                .inset(true)
+                .spacing(ListItemSpacing::Sparse)
+                .toggle_state(selected)
+                .child(HighlightedLabel::new(
+                    profile_match.string.clone(),
+                    profile_match.positions.clone(),
+                ))
+                .when(is_active, |item| {
+                    item.end_slot(
+                        Icon::new(IconName::Check)
+                            .size(IconSize::Small)
+                            .color(Color::Accent),
+                    )
+                }),
+        )
+    }
+}
@@ -1,4 +1,5 @@
 use anyhow::{anyhow, Context, Result};
+use regex::{Captures, Regex, RegexBuilder};
 use smallvec::SmallVec;
 use std::{collections::BTreeMap, ops::Range};
 
@@ -12,15 +13,188 @@ pub struct Snippet {
 pub struct TabStop {
     pub ranges: SmallVec<[Range<isize>; 2]>,
     pub choices: Option<Vec<String>>,
+    /// Ranges that mirror this tabstop through a transform, such as `${1/(.*)/${1:/upcase}/}`.
+    /// Their text is recomputed from the tabstop's text when the tabstop is left.
+    pub transformed_ranges: Vec<(Range<isize>, Transform)>,
+}
+
+/// A `/regex/format/flags` transform applied to a variable or a tabstop's text.
+#[derive(Clone, Debug)]
+pub struct Transform {
+    regex: Regex,
+    format: Vec<FormatItem>,
+    global: bool,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum FormatItem {
+    Text(String),
+    Group(usize),
+    Case(usize, CaseChange),
+    Conditional {
+        group: usize,
+        if_text: String,
+        else_text: String,
+    },
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum CaseChange {
+    Upcase,
+    Downcase,
+    Capitalize,
+    CamelCase,
+    PascalCase,
+}
+
+impl PartialEq for Transform {
+    fn eq(&self, other: &Self) -> bool {
+        self.regex.as_str() == other.regex.as_str()
+            && self.format == other.format
+            && self.global == other.global
+    }
+}
+
+impl Transform {
+    /// Replaces the first match of the transform's regex in `text`, or every match
+    /// when the `g` flag is given, with the transform's format.
+    pub fn apply(&self, text: &str) -> String {
+        let limit = if self.global { 0 } else { 1 };
+        self.regex
+            .replacen(text, limit, |captures: &Captures| self.format(captures))
+            .into_owned()
+    }
+
+    fn format(&self, captures: &Captures) -> String {
+        let group = |index: usize| captures.get(index).map_or("", |group| group.as_str());
+        let mut result = String::new();
+        for item in &self.format {
+            match item {
+                FormatItem::Text(text) => result.push_str(text),
+                FormatItem::Group(index) => result.push_str(group(*index)),
+                FormatItem::Case(index, case_change) => {
+                    result.push_str(&case_change.apply(group(*index)))
+                }
+                FormatItem::Conditional {
+                    group: index,
+                    if_text,
+                    else_text,
+                } => {
+                    if group(*index).is_empty() {
+                        result.push_str(else_text);
+                    } else {
+                        result.push_str(if_text);
+                    }
+                }
+            }
+        }
+        result
+    }
+}
+
+impl CaseChange {
+    fn apply(self, text: &str) -> String {
+        match self {
+            CaseChange::Upcase => text.to_uppercase(),
+            CaseChange::Downcase => text.to_lowercase(),
+            CaseChange::Capitalize => {
+                let mut chars = text.chars();
+                match chars.next() {
+                    Some(first) => first.to_uppercase().chain(chars).collect(),
+                    None => String::new(),
+                }
+            }
+            CaseChange::CamelCase | CaseChange::PascalCase => {
+                let mut result = String::new();
+                for (ix, word) in text
+                    .split(|c: char| !c.is_alphanumeric())
+                    .filter(|word| !word.is_empty())
+                    .enumerate()
+                {
+                    if ix == 0 && self == CaseChange::CamelCase {
+                        result.push_str(&word.to_lowercase());
+                    } else {
+                        result.push_str(&CaseChange::Capitalize.apply(&word.to_lowercase()));
+                    }
+                }
+                result
+            }
+        }
+    }
+}
+
+struct ParseContext<'a> {
+    variables: &'a dyn Fn(&str) -> Option<String>,
+    /// The initial text of every tabstop, used to fill in transformed mirrors.
+    tabstop_texts: BTreeMap<usize, String>,
+    /// The value of every variable in the snippet, in the order they appear, so that variables
+    /// such as `$UUID` keep their value when the snippet is parsed again.
+    variable_values: Vec<Option<String>>,
+    variable_count: usize,
+}
+
+impl ParseContext<'_> {
+    fn resolve_variable(&mut self, name: &str) -> Option<String> {
+        if self.variable_count == self.variable_values.len() {
+            self.variable_values.push((self.variables)(name));
+        }
+        self.variable_count += 1;
+        self.variable_values[self.variable_count - 1].clone()
+    }
 }
 
 impl Snippet {
     pub fn parse(source: &str) -> Result<Self> {
+        Self::parse_with_variables(source, &|_| None)
+    }
+
+    /// Parses a snippet, resolving variables such as `$TM_FILENAME` with the given function.
+    ///
+    /// Variables for which `variables` returns `None` are unknown, and are inserted by name.
+    /// Known variables with an empty value are replaced by their default, if any.
+    pub fn parse_with_variables(
+        source: &str,
+        variables: &dyn Fn(&str) -> Option<String>,
+    ) -> Result<Self> {
+        let mut cx = ParseContext {
+            variables,
+            tabstop_texts: BTreeMap::new(),
+            variable_values: Vec::new(),
+            variable_count: 0,
+        };
         let mut text = String::with_capacity(source.len());
         let mut tabstops = BTreeMap::new();
-        parse_snippet(source, false, &mut text, &mut tabstops)
+        parse_snippet(source, false, &mut cx, &mut text, &mut tabstops)
             .context("failed to parse snippet")?;
 
+        // Transformed mirrors depend on the text of their tabstop, which may only be
+        // defined later in the snippet, so parse again once all tabstops are known.
+        if tabstops
+            .values()
+            .any(|tabstop: &TabStop| !tabstop.transformed_ranges.is_empty())
+        {
+            cx.tabstop_texts = tabstops
+                .iter()
+                .filter_map(|(index, tabstop)| {
+                    let range = tabstop
+                        .ranges
+                        .iter()
+                        .find(|range| !range.is_empty())
+                        .or(tabstop.ranges.first())?;
+                    Some((
+                        *index,
+                        text[range.start as usize..range.end as usize].to_string(),
+                    ))
+                })
+                .collect();
+            cx.variable_count = 0;
+            text.clear();
+            tabstops.clear();
+            parse_snippet(source, false, &mut cx, &mut text, &mut tabstops)
+                .context("failed to parse snippet")?;
+        }
+        tabstops.retain(|_, tabstop| !tabstop.ranges.is_empty());
+
         let len = text.len() as isize;
         let final_tabstop = tabstops.remove(&0);
         let mut tabstops = tabstops.into_values().collect::<Vec<_>>();
@@ -31,6 +205,7 @@ impl Snippet {
             let end_tabstop = TabStop {
                 ranges: [len..len].into_iter().collect(),
                 choices: None,
+                transformed_ranges: Vec::new(),
             };
 
             if !tabstops.last().map_or(false, |t| *t == end_tabstop) {
@@ -45,6 +220,7 @@ impl Snippet {
 fn parse_snippet<'a>(
     mut source: &'a str,
     nested: bool,
+    cx: &mut ParseContext,
     text: &mut String,
     tabstops: &mut BTreeMap<usize, TabStop>,
 ) -> Result<&'a str> {
@@ -52,7 +228,13 @@ fn parse_snippet<'a>(
         match source.chars().next() {
             None => return Ok(""),
             Some('$') => {
-                source = parse_tabstop(&source[1..], text, tabstops)?;
+                if source[1..].starts_with(is_variable_start)
+                    || source[1..].starts_with('{') && source[2..].starts_with(is_variable_start)
+                {
+                    source = parse_variable(&source[1..], cx, text, tabstops)?;
+                } else {
+                    source = parse_tabstop(&source[1..], cx, text, tabstops)?;
+                }
             }
             Some('\\') => {
                 // As specified in the LSP spec (`Grammar` section),
@@ -91,12 +273,14 @@ fn parse_snippet<'a>(
 
 fn parse_tabstop<'a>(
     mut source: &'a str,
+    cx: &mut ParseContext,
     text: &mut String,
     tabstops: &mut BTreeMap<usize, TabStop>,
 ) -> Result<&'a str> {
     let tabstop_start = text.len();
     let tabstop_index;
     let mut choices = None;
+    let mut transform = None;
 
     if source.starts_with('{') {
         let (index, rest) = parse_int(&source[1..])?;
@@ -108,7 +292,14 @@ fn parse_tabstop<'a>(
         }
 
         if source.starts_with(':') {
-            source = parse_snippet(&source[1..], true, text, tabstops)?;
+            source = parse_snippet(&source[1..], true, cx, text, tabstops)?;
+        } else if source.starts_with('/') {
+            let parsed_transform;
+            (parsed_transform, source) = parse_transform(&source[1..])?;
+            if let Some(tabstop_text) = cx.tabstop_texts.get(&tabstop_index) {
+                text.push_str(&parsed_transform.apply(tabstop_text));
+            }
+            transform = Some(parsed_transform);
         }
 
         if source.starts_with('}') {
@@ -122,17 +313,255 @@ fn parse_tabstop<'a>(
         source = rest;
     }
 
-    tabstops
-        .entry(tabstop_index)
-        .or_insert_with(|| TabStop {
-            ranges: Default::default(),
-            choices,
-        })
-        .ranges
-        .push(tabstop_start as isize..text.len() as isize);
+    let tabstop = tabstops.entry(tabstop_index).or_default();
+    if tabstop.choices.is_none() {
+        tabstop.choices = choices;
+    }
+    let range = tabstop_start as isize..text.len() as isize;
+    if let Some(transform) = transform {
+        tabstop.transformed_ranges.push((range, transform));
+    } else {
+        tabstop.ranges.push(range);
+    }
     Ok(source)
 }
 
+fn is_variable_start(c: char) -> bool {
+    c == '_' || c.is_ascii_alphabetic()
+}
+
+fn parse_variable<'a>(
+    mut source: &'a str,
+    cx: &mut ParseContext,
+    text: &mut String,
+    tabstops: &mut BTreeMap<usize, TabStop>,
+) -> Result<&'a str> {
+    let braced = source.starts_with('{');
+    if braced {
+        source = &source[1..];
+    }
+    let name_len = source
+        .find(|c: char| c != '_' && !c.is_ascii_alphanumeric())
+        .unwrap_or(source.len());
+    let (name, rest) = source.split_at(name_len);
+    source = rest;
+    let value = cx.resolve_variable(name);
+
+    if !braced {
+        text.push_str(value.as_deref().unwrap_or(name));
+        return Ok(source);
+    }
+
+    if source.starts_with(':') {
+        let mut default_text = String::new();
+        let mut default_tabstops = BTreeMap::new();
+        source = parse_snippet(
+            &source[1..],
+            true,
+            cx,
+            &mut default_text,
+            &mut default_tabstops,
+        )?;
+        match value {
+            Some(value) if !value.is_empty() => text.push_str(&value),
+            _ => {
+                let offset = text.len() as isize;
+                text.push_str(&default_text);
+                for (index, default_tabstop) in default_tabstops {
+                    let tabstop = tabstops.entry(index).or_default();
+                    if tabstop.choices.is_none() {
+                        tabstop.choices = default_tabstop.choices;
+                    }
+                    tabstop.ranges.extend(
+                        default_tabstop
+                            .ranges
+                            .into_iter()
+                            .map(|range| range.start + offset..range.end + offset),
+                    );
+                    tabstop.transformed_ranges.extend(
+                        default_tabstop
+                            .transformed_ranges
+                            .into_iter()
+                            .map(|(range, transform)| {
+                                (range.start + offset..range.end + offset, transform)
+                            }),
+                    );
+                }
+            }
+        }
+    } else if source.starts_with('/') {
+        let transform;
+        (transform, source) = parse_transform(&source[1..])?;
+        text.push_str(&transform.apply(value.as_deref().unwrap_or(name)));
+    } else {
+        text.push_str(value.as_deref().unwrap_or(name));
+    }
+
+    source
+        .strip_prefix('}')
+        .ok_or_else(|| anyhow!("expected a closing brace"))
+}
+
+/// Parses the `regex/format/flags` part of a transform, up to the closing brace.
+fn parse_transform(source: &str) -> Result<(Transform, &str)> {
+    let (pattern, source) = take_until_slash(source)?;
+    let (format, source) = take_until_slash(source)?;
+    let flags_len = source.find('}').unwrap_or(source.len());
+    let (flags, source) = source.split_at(flags_len);
+
+    let mut builder = RegexBuilder::new(&pattern);
+    let mut global = false;
+    for flag in flags.chars() {
+        match flag {
+            'g' => global = true,
+            'i' => {
+                builder.case_insensitive(true);
+            }
+            'm' => {
+                builder.multi_line(true);
+            }
+            's' => {
+                builder.dot_matches_new_line(true);
+            }
+            _ => return Err(anyhow!("unsupported transform flag '{flag}'")),
+        }
+    }
+    let regex = builder.build().context("invalid transform regex")?;
+
+    Ok((
+        Transform {
+            regex,
+            format: parse_format(&format)?,
+            global,
+        },
+        source,
+    ))
+}
+
+/// Returns the text up to the next unescaped slash outside of a `${...}` format item,
+/// with escaped slashes unescaped.
+fn take_until_slash(source: &str) -> Result<(String, &str)> {
+    let mut result = String::new();
+    let mut depth = 0;
+    let mut chars = source.char_indices().peekable();
+    while let Some((ix, c)) = chars.next() {
+        match c {
+            '/' if depth == 0 => return Ok((result, &source[ix + 1..])),
+            '$' if chars.peek().map_or(false, |(_, c)| *c == '{') => {
+                depth += 1;
+                result.push(c);
+            }
+            '}' if depth > 0 => {
+                depth -= 1;
+                result.push(c);
+            }
+            '\\' => match chars.next() {
+                Some((_, '/')) => result.push('/'),
+                Some((_, c)) => {
+                    result.push('\\');
+                    result.push(c);
+                }
+                None => result.push('\\'),
+            },
+            c => result.push(c),
+        }
+    }
+    Err(anyhow!("expected a '/' in transform"))
+}
+
+fn parse_format(mut source: &str) -> Result<Vec<FormatItem>> {
+    let mut items = Vec::new();
+    let mut text = String::new();
+    while let Some(c) = source.chars().next() {
+        match c {
+            '\\' => {
+                source = &source[1..];
+                match source.chars().next() {
+                    Some(c @ ('$' | '\\' | '}' | '/')) => {
+                        text.push(c);
+                        source = &source[1..];
+                    }
+                    Some('n') => {
+                        text.push('\n');
+                        source = &source[1..];
+                    }
+                    Some('t') => {
+                        text.push('\t');
+                        source = &source[1..];
+                    }
+                    _ => text.push('\\'),
+                }
+            }
+            '$' => {
+                if !text.is_empty() {
+                    items.push(FormatItem::Text(std::mem::take(&mut text)));
+                }
+                let item;
+                (item, source) = parse_format_item(&source[1..])?;
+                items.push(item);
+            }
+            c => {
+                text.push(c);
+                source = &source[c.len_utf8()..];
+            }
+        }
+    }
+    if !text.is_empty() {
+        items.push(FormatItem::Text(text));
+    }
+    Ok(items)
+}
+
+fn parse_format_item(source: &str) -> Result<(FormatItem, &str)> {
+    let Some(source) = source.strip_prefix('{') else {
+        let (group, source) = parse_int(source)?;
+        return Ok((FormatItem::Group(group), source));
+    };
+    let (group, source) = parse_int(source)?;
+    if let Some(source) = source.strip_prefix('}') {
+        return Ok((FormatItem::Group(group), source));
+    }
+    let source = source
+        .strip_prefix(':')
+        .ok_or_else(|| anyhow!("expected ':' in format"))?;
+    let end = source
+        .find('}')
+        .ok_or_else(|| anyhow!("expected a closing brace in format"))?;
+    let (body, source) = (&source[..end], &source[end + 1..]);
+
+    let item = if let Some(case_change) = body.strip_prefix('/') {
+        let case_change = match case_change {
+            "upcase" => CaseChange::Upcase,
+            "downcase" => CaseChange::Downcase,
+            "capitalize" => CaseChange::Capitalize,
+            "camelcase" => CaseChange::CamelCase,
+            "pascalcase" => CaseChange::PascalCase,
+            _ => return Err(anyhow!("unsupported case change '{case_change}'")),
+        };
+        FormatItem::Case(group, case_change)
+    } else if let Some(if_text) = body.strip_prefix('+') {
+        FormatItem::Conditional {
+            group,
+            if_text: if_text.to_string(),
+            else_text: String::new(),
+        }
+    } else if let Some(branches) = body.strip_prefix('?') {
+        let (if_text, else_text) = branches.split_once(':').unwrap_or((branches, ""));
+        FormatItem::Conditional {
+            group,
+            if_text: if_text.to_string(),
+            else_text: else_text.to_string(),
+        }
+    } else {
+        FormatItem::Conditional {
+            group,
+            if_text: String::new(),
+            else_text: body.strip_prefix('-').unwrap_or(body).to_string(),
+        }
+    };
+    Ok((item, source))
+}
+
 fn parse_int(source: &str) -> Result<(usize, &str)> {
     let len = source
         .find(|c: char| !c.is_ascii_digit())
@@ -327,6 +756,96 @@ mod tests {
         assert_eq!(tabstops(&snippet), &[vec![4..4], vec![7..7]]);
     }
 
+    #[test]
+    fn test_snippet_with_variables() {
+        let variables = |name: &str| match name {
+            "TM_FILENAME" => Some("main.rs".to_string()),
+            "TM_SELECTED_TEXT" => Some(String::new()),
+            _ => None,
+        };
+
+        let snippet = Snippet::parse_with_variables("// $TM_FILENAME", &variables).unwrap();
+        assert_eq!(snippet.text, "// main.rs");
+        assert_eq!(tabstops(&snippet), &[vec![10..10]]);
+
+        // Empty variables fall back to their default, which may contain tabstops.
+        let snippet =
+            Snippet::parse_with_variables("${TM_SELECTED_TEXT:${1:default}}", &variables).unwrap();
+        assert_eq!(snippet.text, "default");
+        assert_eq!(tabstops(&snippet), &[vec![0..7], vec![7..7]]);
+
+        let snippet =
+            Snippet::parse_with_variables("${TM_FILENAME:${1:default}}$2", &variables).unwrap();
+        assert_eq!(snippet.text, "main.rs");
+        assert_eq!(tabstops(&snippet), &[vec![7..7]]);
+
+        // Unknown variables are inserted by name.
+        let snippet = Snippet::parse_with_variables("$UNKNOWN ${OTHER}", &variables).unwrap();
+        assert_eq!(snippet.text, "UNKNOWN OTHER");
+
+        let snippet =
+            Snippet::parse_with_variables(r"${TM_FILENAME/(.*)\.rs/$1/}", &variables).unwrap();
+        assert_eq!(snippet.text, "main");
+    }
+
+    #[test]
+    fn test_snippet_variables_are_resolved_once() {
+        let resolved_count = std::cell::Cell::new(0);
+        let variables = |name: &str| {
+            (name == "RANDOM").then(|| {
+                resolved_count.set(resolved_count.get() + 1);
+                resolved_count.get().to_string()
+            })
+        };
+
+        // Snippets with transforms are parsed twice, but keep the values of the first parse.
+        let snippet =
+            Snippet::parse_with_variables("$RANDOM ${1:a} ${1/(.*)/$1/} $RANDOM", &variables)
+                .unwrap();
+        assert_eq!(snippet.text, "1 a a 2");
+        assert_eq!(resolved_count.get(), 2);
+    }
+
+    #[test]
+    fn test_snippet_with_transformed_tabstops() {
+        let snippet = Snippet::parse("${1:foo_bar} ${1/(.*)/${1:/upcase}/}").unwrap();
+        assert_eq!(snippet.text, "foo_bar FOO_BAR");
+        assert_eq!(tabstops(&snippet), &[vec![0..7], vec![15..15]]);
+        let (range, transform) = &snippet.tabstops[0].transformed_ranges[0];
+        assert_eq!(*range, 8..15);
+        assert_eq!(transform.apply("baz"), "BAZ");
+
+        // The transformed tabstop may appear before the tabstop it mirrors.
+        let snippet = Snippet::parse("${1/(.)/$1$1/} ${1:x}").unwrap();
+        assert_eq!(snippet.text, "xx x");
+        assert_eq!(tabstops(&snippet), &[vec![3..4], vec![4..4]]);
+        assert_eq!(snippet.tabstops[0].transformed_ranges[0].0, 0..2);
+    }
+
+    #[test]
+    fn test_transforms() {
+        assert_eq!(transform("(a)|b/${1:?yes:no}/g}").apply("abc"), "yesnoc");
+        assert_eq!(transform("(a)|b/${1:?yes:no}/}").apply("abc"), "yesbc");
+        assert_eq!(transform("A/x/gi}").apply("aAb"), "xxb");
+        assert_eq!(
+            transform("(.*)/${1:/camelcase}/}").apply("foo_bar-baz"),
+            "fooBarBaz"
+        );
+        assert_eq!(
+            transform("(.*)/${1:/pascalcase}/}").apply("foo_bar"),
+            "FooBar"
+        );
+        assert_eq!(transform("(.*)/${1:/capitalize}/}").apply("foo"), "Foo");
+        assert_eq!(transform("(x)?y/${1:+has x}/}").apply("y"), "");
+        assert_eq!(transform("(x)?y/${1:-none}/}").apply("y"), "none");
+        assert_eq!(transform(r"\//_/g}").apply("a/b/c"), "a_b_c");
+        assert!(parse_transform("a/b/z}").is_err());
+    }
+
+    fn transform(source: &str) -> Transform {
+        parse_transform(source).unwrap().0
+    }
+
     fn tabstops(snippet: &Snippet) -> Vec<Vec<Range<isize>>> {
         snippet.tabstops.iter().map(|t| t.ranges.to_vec()).collect()
     }
@@ -13,14 +13,15 @@ use alacritty_terminal::{
     selection::{Selection, SelectionRange, SelectionType},
     sync::FairMutex,
     term::{
-        cell::Cell,
+        cell::{Cell, Flags},
         search::{Match, RegexIter, RegexSearch},
         Config, RenderableCursor, TermMode,
     },
     tty::{self},
     vi_mode::{ViModeCursor, ViMotion},
     vte::ansi::{
-        ClearMode, CursorStyle as AlacCursorStyle, Handler, NamedPrivateMode, PrivateMode,
+        ClearMode, Color as AnsiColor, CursorStyle as AlacCursorStyle, Handler, NamedColor,
+        NamedPrivateMode, PrivateMode,
     },
     Term,
 };
@@ -597,6 +598,24 @@ impl Default for TerminalContent {
     }
 }
 
+/// A copy of the lines of a terminal, see [`Terminal::grid_snapshot`].
+#[derive(Clone, Debug, Default)]
+pub struct GridSnapshot {
+    /// The cells of each line, without trailing blank cells.
+    pub lines: Vec<Vec<Cell>>,
+    /// The line and column of the cursor within `lines`, if the cursor is shown.
+    pub cursor: Option<(usize, usize)>,
+}
+
+fn is_blank_cell(cell: &Cell) -> bool {
+    cell.c == ' '
+        && cell.bg == AnsiColor::Named(NamedColor::Background)
+        && !cell
+            .flags
+            .intersects(Flags::INVERSE | Flags::ALL_UNDERLINES | Flags::STRIKEOUT)
+        && cell.zerowidth().is_none()
+}
+
 #[derive(PartialEq, Eq)]
 pub enum SelectionPhase {
     Selecting,
@@ -1361,6 +1380,35 @@ impl Terminal {
         lines
     }
 
+    /// Returns a copy of the terminal's screen, preceded by at most `max_scrollback_lines`
+    /// lines of its scrollback, so that the terminal can be mirrored elsewhere.
+    pub fn grid_snapshot(&self, max_scrollback_lines: usize) -> GridSnapshot {
+        let term = self.term.clone();
+        let terminal = term.lock_unfair();
+        let grid = terminal.grid();
+
+        let scrollback_lines = grid.history_size().min(max_scrollback_lines);
+        let lines = (-(scrollback_lines as i32)..=terminal.bottommost_line().0)
+            .map(|line| {
+                let row = &grid[Line(line)];
+                let mut cells = row.into_iter().cloned().collect::<Vec<_>>();
+                while cells.last().map_or(false, is_blank_cell) {
+                    cells.pop();
+                }
+                cells
+            })
+            .collect();
+        let cursor = terminal.mode().contains(TermMode::SHOW_CURSOR).then(|| {
+            let point = grid.cursor.point;
+            (
+                (point.line.0 + scrollback_lines as i32) as usize,
+                point.column.0,
+            )
+        });
+
+        GridSnapshot { lines, cursor }
+    }
+
     pub fn focus_in(&self) {
         if self.last_content.mode.contains(TermMode::FOCUS_IN_OUT) {
             self.write_to_pty("\x1b[I".to_string());
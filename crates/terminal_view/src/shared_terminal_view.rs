@@ -0,0 +1,338 @@
+use gpui::{
+    uniform_list, AnyElement, AppContext, EventEmitter, FocusHandle, FocusableView, Font,
+    FontStyle, FontWeight, KeyDownEvent, Model, Pixels, Render, ScrollStrategy, StrikethroughStyle,
+    StyledText, Subscription, TextRun, TextStyle, UnderlineStyle, UniformListScrollHandle,
+    WhiteSpace,
+};
+use project::terminal_sharing::{SharedTerminal, SharedTerminalEvent, SharedTerminalRun};
+use settings::Settings;
+use terminal::{
+    alacritty_terminal::{
+        term::{cell::Flags, TermMode},
+        vte::ansi::{Color as AnsiColor, NamedColor},
+    },
+    mappings::keys::to_esc_str,
+    terminal_settings::TerminalSettings,
+};
+use theme::{Theme, ThemeSettings};
+use ui::prelude::*;
+use util::ResultExt;
+use workspace::item::{Item, ItemEvent, TabContentParams};
+
+use crate::terminal_element::convert_color;
+
+/// A view of a terminal that the host of a project shared with its guests.
+/// It is read-only, unless the host allowed the local participant to type into it.
+pub struct SharedTerminalView {
+    terminal: Model<SharedTerminal>,
+    focus_handle: FocusHandle,
+    scroll_handle: UniformListScrollHandle,
+    rendered_line_count: usize,
+    _subscription: Subscription,
+}
+
+impl SharedTerminalView {
+    pub fn new(terminal: Model<SharedTerminal>, cx: &mut ViewContext<Self>) -> Self {
+        let subscription = cx.subscribe(&terminal, |this, terminal, event, cx| {
+            match event {
+                SharedTerminalEvent::Updated => {
+                    // Keep following the output, unless the user scrolled up to read it.
+                    if this.is_scrolled_to_bottom() {
+                        let line_count = terminal.read(cx).lines().len();
+                        if line_count > 0 {
+                            this.scroll_handle
+                                .scroll_to_item(line_count - 1, ScrollStrategy::Top);
+                        }
+                    }
+                    cx.emit(ItemEvent::UpdateTab);
+                }
+                SharedTerminalEvent::Closed => cx.emit(ItemEvent::UpdateTab),
+            }
+            cx.notify();
+        });
+
+        Self {
+            terminal,
+            focus_handle: cx.focus_handle(),
+            scroll_handle: UniformListScrollHandle::new(),
+            rendered_line_count: 0,
+            _subscription: subscription,
+        }
+    }
+
+    pub fn terminal(&self) -> &Model<SharedTerminal> {
+        &self.terminal
+    }
+
+    fn is_scrolled_to_bottom(&self) -> bool {
+        let state = self.scroll_handle.0.borrow();
+        let Some(item_size) = state.last_item_size else {
+            return true;
+        };
+        let line_height = item_size.item.height;
+        let content_height = line_height * self.rendered_line_count as f32;
+        let viewport_height = state.base_handle.bounds().size.height;
+        -state.base_handle.offset().y + viewport_height >= content_height - line_height
+    }
+
+    fn key_down(&mut self, event: &KeyDownEvent, cx: &mut ViewContext<Self>) {
+        let terminal = self.terminal.read(cx);
+        if !terminal.can_write() {
+            return;
+        }
+
+        let alt_is_meta = TerminalSettings::get_global(cx).option_as_meta;
+        let input = to_esc_str(&event.keystroke, &TermMode::NONE, alt_is_meta).or_else(|| {
+            event
+                .keystroke
+                .key_char
+                .clone()
+                .filter(|_| !event.keystroke.modifiers.platform)
+        });
+        if let Some(input) = input {
+            terminal
+                .input(input.into_bytes(), cx)
+                .detach_and_log_err(cx);
+            cx.stop_propagation();
+        }
+    }
+
+    fn render_line(
+        &self,
+        ix: usize,
+        text_style: &TextStyle,
+        cell_width: Pixels,
+        line_height: Pixels,
+        cx: &ViewContext<Self>,
+    ) -> AnyElement {
+        let terminal = self.terminal.read(cx);
+        let theme = cx.theme();
+        let line = terminal
+            .lines()
+            .get(ix)
+            .map_or(&[][..], |line| line.as_slice());
+
+        let mut text = String::new();
+        let mut runs = Vec::with_capacity(line.len());
+        for run in line {
+            text.push_str(&run.text);
+            runs.push(text_run(run, text_style, theme));
+        }
+
+        let cursor = terminal
+            .cursor()
+            .filter(|(cursor_line, _)| *cursor_line == ix)
+            .map(|(_, column)| {
+                div()
+                    .absolute()
+                    .top_0()
+                    .left(cell_width * column as f32)
+                    .w(cell_width)
+                    .h(line_height)
+                    .border_1()
+                    .border_color(theme.players().local().cursor)
+            });
+
+        div()
+            .relative()
+            .h(line_height)
+            .child(StyledText::new(text).with_runs(runs))
+            .children(cursor)
+            .into_any_element()
+    }
+}
+
+fn text_run(run: &SharedTerminalRun, text_style: &TextStyle, theme: &Theme) -> TextRun {
+    let mut color = convert_color(&run.foreground, theme);
+    if run.flags.contains(Flags::DIM) {
+        color.a *= 0.7;
+    }
+    let background_color = match run.background {
+        AnsiColor::Named(NamedColor::Background) => None,
+        background => Some(convert_color(&background, theme)),
+    };
+
+    TextRun {
+        len: run.text.len(),
+        color,
+        background_color,
+        font: Font {
+            weight: if run.flags.contains(Flags::BOLD) {
+                FontWeight::BOLD
+            } else {
+                text_style.font_weight
+            },
+            style: if run.flags.contains(Flags::ITALIC) {
+                FontStyle::Italic
+            } else {
+                FontStyle::Normal
+            },
+            ..text_style.font()
+        },
+        underline: run
+            .flags
+            .contains(Flags::UNDERLINE)
+            .then(|| UnderlineStyle {
+                color: Some(color),
+                thickness: px(1.),
+                wavy: false,
+            }),
+        strikethrough: run
+            .flags
+            .contains(Flags::STRIKEOUT)
+            .then(|| StrikethroughStyle {
+                color: Some(color),
+                thickness: px(1.),
+            }),
+    }
+}
+
+/// The text style used to render shared terminals, along with their line height and cell width.
+fn terminal_text_style(cx: &WindowContext) -> (TextStyle, Pixels, Pixels) {
+    let settings = ThemeSettings::get_global(cx);
+    let terminal_settings = TerminalSettings::get_global(cx);
+    let font_size = terminal_settings
+        .font_size
+        .map_or(settings.buffer_font_size(cx), |size| {
+            theme::adjusted_font_size(size, cx)
+        });
+    let line_height = terminal_settings.line_height.value();
+
+    let text_style = TextStyle {
+        font_family: terminal_settings
+            .font_family
+            .clone()
+            .unwrap_or_else(|| settings.buffer_font.family.clone()),
+        font_features: terminal_settings
+            .font_features
+            .clone()
+            .unwrap_or_else(|| settings.buffer_font.features.clone()),
+        font_fallbacks: terminal_settings
+            .font_fallbacks
+            .clone()
+            .or_else(|| settings.buffer_font.fallbacks.clone()),
+        font_weight: terminal_settings.font_weight.unwrap_or_default(),
+        font_size: font_size.into(),
+        line_height: line_height.into(),
+        white_space: WhiteSpace::Nowrap,
+        color: cx.theme().colors().terminal_foreground,
+        ..Default::default()
+    };
+
+    let rem_size = cx.rem_size();
+    let font_pixels = text_style.font_size.to_pixels(rem_size);
+    let line_height = font_pixels * line_height.to_pixels(rem_size);
+    let font_id = cx.text_system().resolve_font(&text_style.font());
+    let cell_width = cx
+        .text_system()
+        .advance(font_id, font_pixels, 'm')
+        .log_err()
+        .map_or(font_pixels * 0.6, |advance| advance.width);
+
+    (text_style, line_height, cell_width)
+}
+
+impl EventEmitter<ItemEvent> for SharedTerminalView {}
+
+impl FocusableView for SharedTerminalView {
+    fn focus_handle(&self, _cx: &AppContext) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Render for SharedTerminalView {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let terminal = self.terminal.read(cx);
+        let line_count = terminal.lines().len();
+        self.rendered_line_count = line_count;
+
+        let status = if terminal.is_closed() {
+            "The host stopped sharing this terminal"
+        } else if terminal.can_write() {
+            "The host allowed you to type into this terminal"
+        } else {
+            "Read-only: ask the host for permission to type into this terminal"
+        };
+        let (text_style, line_height, cell_width) = terminal_text_style(cx);
+
+        v_flex()
+            .id("shared-terminal")
+            .size_full()
+            .track_focus(&self.focus_handle)
+            .on_key_down(cx.listener(Self::key_down))
+            .bg(cx.theme().colors().terminal_background)
+            .child(
+                h_flex()
+                    .px_2()
+                    .py_0p5()
+                    .gap_2()
+                    .border_b_1()
+                    .border_color(cx.theme().colors().border_variant)
+                    .child(
+                        Icon::new(IconName::Terminal)
+                            .size(IconSize::Small)
+                            .color(Color::Muted),
+                    )
+                    .child(
+                        Label::new(status)
+                            .size(LabelSize::Small)
+                            .color(Color::Muted),
+                    ),
+            )
+            .child(
+                uniform_list(
+                    cx.view().clone(),
+                    "shared-terminal-lines",
+                    line_count,
+                    move |this, range, cx| {
+                        range
+                            .map(|ix| {
+                                this.render_line(ix, &text_style, cell_width, line_height, cx)
+                            })
+                            .collect()
+                    },
+                )
+                .track_scroll(self.scroll_handle.clone())
+                .flex_grow()
+                .px_2(),
+            )
+    }
+}
+
+impl Item for SharedTerminalView {
+    type Event = ItemEvent;
+
+    fn to_item_events(event: &Self::Event, mut f: impl FnMut(ItemEvent)) {
+        f(*event)
+    }
+
+    fn tab_content(&self, params: TabContentParams, cx: &WindowContext) -> AnyElement {
+        let terminal = self.terminal.read(cx);
+        h_flex()
+            .gap_1()
+            .child(
+                Icon::new(IconName::Terminal)
+                    .size(IconSize::Small)
+                    .color(params.text_color()),
+            )
+            .child(
+                Label::new(terminal.title().to_string())
+                    .color(params.text_color())
+                    .strikethrough(terminal.is_closed()),
+            )
+            .into_any_element()
+    }
+
+    fn tab_tooltip_text(&self, cx: &AppContext) -> Option<SharedString> {
+        let terminal = self.terminal.read(cx);
+        Some(format!("{} (shared by the host)", terminal.title()).into())
+    }
+
+    fn telemetry_event_text(&self) -> Option<&'static str> {
+        Some("Shared Terminal Opened")
+    }
+
+    fn is_dirty(&self, _: &AppContext) -> bool {
+        false
+    }
+}
@@ -1,4 +1,5 @@
 mod persistence;
+pub mod shared_terminal_view;
 pub mod terminal_element;
 pub mod terminal_panel;
 
@@ -13,7 +14,11 @@ use gpui::{
 };
 use language::Bias;
 use persistence::TERMINAL_DB;
-use project::{search::SearchQuery, terminals::TerminalKind, Fs, Metadata, Project};
+use project::{
+    search::SearchQuery, terminal_sharing::SharedTerminal, terminals::TerminalKind, Fs, Metadata,
+    Project,
+};
+use shared_terminal_view::SharedTerminalView;
 use terminal::{
     alacritty_terminal::{
         index::Point,
@@ -30,10 +35,11 @@ use ui::{h_flex, prelude::*, ContextMenu, Icon, IconName, Label, Tooltip};
 use util::{paths::PathWithPosition, ResultExt};
 use workspace::{
     item::{BreadcrumbText, Item, ItemEvent, SerializableItem, TabContentParams},
+    notifications::NotificationId,
     register_serializable_item,
     searchable::{SearchEvent, SearchOptions, SearchableItem, SearchableItemHandle},
-    CloseActiveItem, NewCenterTerminal, NewTerminal, OpenVisible, ToolbarItemLocation, Workspace,
-    WorkspaceId,
+    CloseActiveItem, NewCenterTerminal, NewTerminal, OpenVisible, Toast, ToolbarItemLocation,
+    Workspace, WorkspaceId,
 };
 
 use anyhow::Context;
@@ -77,12 +83,49 @@ pub fn init(cx: &mut AppContext) {
 
     register_serializable_item::<TerminalView>(cx);
 
-    cx.observe_new_views(|workspace: &mut Workspace, _cx| {
+    cx.observe_new_views(|workspace: &mut Workspace, cx| {
         workspace.register_action(TerminalView::deploy);
+
+        let project = workspace.project().clone();
+        cx.subscribe(&project, |workspace, _, event, cx| {
+            if let project::Event::TerminalShared(terminal) = event {
+                notify_terminal_shared(workspace, terminal.clone(), cx);
+            }
+        })
+        .detach();
     })
     .detach();
 }
 
+struct TerminalSharedToast;
+
+fn notify_terminal_shared(
+    workspace: &mut Workspace,
+    terminal: Model<SharedTerminal>,
+    cx: &mut ViewContext<Workspace>,
+) {
+    let terminal_id = terminal.read(cx).id();
+    let message = format!(
+        "The host shared the terminal “{}”",
+        terminal.read(cx).title()
+    );
+    let workspace_handle = cx.view().downgrade();
+    let toast = Toast::new(
+        NotificationId::composite::<TerminalSharedToast>(("shared-terminal", terminal_id)),
+        message,
+    )
+    .on_click("Open", move |cx| {
+        let terminal = terminal.clone();
+        workspace_handle
+            .update(cx, |workspace, cx| {
+                let view = cx.new_view(|cx| SharedTerminalView::new(terminal, cx));
+                workspace.add_item_to_active_pane(Box::new(view), None, true, cx);
+            })
+            .log_err();
+    });
+    workspace.show_toast(toast, cx);
+}
+
 pub struct BlockProperties {
     pub height: u8,
     pub render: Box<dyn Send + Fn(&mut BlockContext) -> AnyElement>,
@@ -207,6 +250,7 @@ impl TerminalView {
             .map_or(false, |terminal_panel| {
                 terminal_panel.read(cx).assistant_enabled()
             });
+        let terminal_sharing = self.terminal_sharing_menu_entries(cx);
         let context_menu = ContextMenu::build(cx, |menu, _| {
             menu.context(self.focus_handle.clone())
                 .action("New Terminal", Box::new(NewTerminal))
@@ -219,6 +263,13 @@ impl TerminalView {
                     menu.separator()
                         .action("Inline Assist", Box::new(InlineAssist::default()))
                 })
+                .when(!terminal_sharing.is_empty(), |mut menu| {
+                    menu = menu.separator();
+                    for (label, handler) in terminal_sharing {
+                        menu = menu.entry(label, None, handler);
+                    }
+                    menu
+                })
                 .separator()
                 .action("Close", Box::new(CloseActiveItem { save_intent: None }))
         });
@@ -238,6 +289,73 @@ impl TerminalView {
         self.context_menu = Some((context_menu, position, subscription));
     }
 
+    /// Context menu entries to share this terminal with the guests of the project,
+    /// and to control which of them can type into it.
+    fn terminal_sharing_menu_entries(
+        &self,
+        cx: &AppContext,
+    ) -> Vec<(SharedString, Box<dyn Fn(&mut WindowContext)>)> {
+        let Some(workspace) = self.workspace.upgrade() else {
+            return Vec::new();
+        };
+        let project = workspace.read(cx).project().clone();
+        let project_ref = project.read(cx);
+        if !project_ref.is_local() || !project_ref.is_shared() {
+            return Vec::new();
+        }
+
+        let terminal = self.terminal.clone();
+        let Some(terminal_id) = project_ref.shared_terminal_id(&terminal) else {
+            return vec![(
+                "Share Terminal".into(),
+                Box::new(move |cx| {
+                    project.update(cx, |project, cx| {
+                        project.share_terminal(&terminal, cx).log_err();
+                    });
+                }),
+            )];
+        };
+
+        let mut entries: Vec<(SharedString, Box<dyn Fn(&mut WindowContext)>)> = vec![(
+            "Stop Sharing Terminal".into(),
+            Box::new({
+                let project = project.clone();
+                move |cx| {
+                    project.update(cx, |project, cx| project.unshare_terminal(terminal_id, cx));
+                }
+            }),
+        )];
+        let user_store = project_ref.user_store().read(cx);
+        let mut collaborators = project_ref
+            .collaborators()
+            .values()
+            .filter(|collaborator| !collaborator.is_host)
+            .filter_map(|collaborator| {
+                let user = user_store.get_cached_user(collaborator.user_id)?;
+                Some((user.github_login.clone(), collaborator.peer_id))
+            })
+            .collect::<Vec<_>>();
+        collaborators.sort();
+        for (login, peer_id) in collaborators {
+            let can_write = project_ref.is_shared_terminal_writer(terminal_id, peer_id);
+            let label = if can_write {
+                format!("Disallow @{login} to Type")
+            } else {
+                format!("Allow @{login} to Type")
+            };
+            let project = project.clone();
+            entries.push((
+                label.into(),
+                Box::new(move |cx| {
+                    project.update(cx, |project, cx| {
+                        project.set_shared_terminal_writer(terminal_id, peer_id, !can_write, cx)
+                    });
+                }),
+            ));
+        }
+        entries
+    }
+
     fn settings_changed(&mut self, cx: &mut ViewContext<Self>) {
         let settings = TerminalSettings::get_global(cx);
         self.show_breadcrumbs = settings.toolbar.breadcrumbs;
@@ -1537,7 +1655,11 @@ mod tests {
             .unwrap();
 
         let entry = cx
-            .update(|cx| wt.update(cx, |wt, cx| wt.create_entry(Path::new(""), is_dir, cx)))
+            .update(|cx| {
+                wt.update(cx, |wt, cx| {
+                    wt.create_entry(Path::new(""), is_dir, None, cx)
+                })
+            })
             .await
             .unwrap()
             .to_included()
@@ -0,0 +1,161 @@
+use std::path::Path;
+
+use collections::HashMap;
+use gpui::SharedString;
+use util::paths::PathExt;
+
+use crate::{DirectoryIconsContent, IconThemeContent};
+
+/// The name of the icon theme that uses the file icons bundled with Zed.
+pub const DEFAULT_ICON_THEME_NAME: &str = "Zed (Default)";
+
+/// The icons to use for a directory or chevron, depending on whether it is expanded.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DirectoryIcons {
+    /// The icon to use when the directory is collapsed.
+    pub collapsed: Option<SharedString>,
+    /// The icon to use when the directory is expanded.
+    pub expanded: Option<SharedString>,
+}
+
+impl DirectoryIcons {
+    fn icon(&self, expanded: bool) -> Option<SharedString> {
+        if expanded {
+            self.expanded.clone()
+        } else {
+            self.collapsed.clone()
+        }
+    }
+}
+
+/// An icon theme, mapping files and directories to the icons shown for them.
+///
+/// All of the icon paths in an icon theme are absolute paths to SVG files.
+#[derive(Debug, Clone, PartialEq)]
+pub struct IconTheme {
+    /// The name of the icon theme.
+    pub name: SharedString,
+    /// The icons to use for directories.
+    pub directory_icons: DirectoryIcons,
+    /// The icons to use for directories with a specific name, such as `.git` or `src`.
+    pub named_directory_icons: HashMap<String, DirectoryIcons>,
+    /// The icons to use for the chevrons next to expandable entries.
+    pub chevron_icons: DirectoryIcons,
+    /// A mapping of exact file names, such as `Cargo.toml`, to icon keys.
+    pub file_names: HashMap<String, String>,
+    /// A mapping of file suffixes, such as `rs`, to icon keys.
+    pub file_suffixes: HashMap<String, String>,
+    /// A mapping of icon keys to the icons they refer to.
+    pub file_icons: HashMap<String, SharedString>,
+}
+
+impl IconTheme {
+    /// Creates an [`IconTheme`] from its content, resolving its icon paths relative to `icons_root_dir`.
+    pub fn from_content(content: IconThemeContent, icons_root_dir: &Path) -> Self {
+        let resolve_path = |path: String| {
+            SharedString::from(icons_root_dir.join(path).to_string_lossy().to_string())
+        };
+        let resolve_directory_icons = |icons: DirectoryIconsContent| DirectoryIcons {
+            collapsed: icons.collapsed.map(resolve_path),
+            expanded: icons.expanded.map(resolve_path),
+        };
+
+        Self {
+            name: content.name.into(),
+            directory_icons: resolve_directory_icons(content.directory_icons),
+            named_directory_icons: content
+                .named_directory_icons
+                .into_iter()
+                .map(|(name, icons)| (name, resolve_directory_icons(icons)))
+                .collect(),
+            chevron_icons: resolve_directory_icons(content.chevron_icons),
+            file_names: content.file_names,
+            file_suffixes: content.file_suffixes,
+            file_icons: content
+                .file_icons
+                .into_iter()
+                .map(|(key, icon)| (key, resolve_path(icon.path)))
+                .collect(),
+        }
+    }
+
+    /// Returns the icon for the file at the given path, if this icon theme has one.
+    pub fn file_icon(&self, path: &Path) -> Option<SharedString> {
+        let file_name = path.file_name()?.to_str()?;
+        let key = self.file_names.get(file_name).or_else(|| {
+            let suffix = path.icon_stem_or_suffix()?;
+            self.file_suffixes.get(suffix)
+        })?;
+        self.file_icons.get(key).cloned()
+    }
+
+    /// Returns the icon for the directory at the given path, if this icon theme has one.
+    pub fn directory_icon(&self, path: &Path, expanded: bool) -> Option<SharedString> {
+        path.file_name()
+            .and_then(|name| name.to_str())
+            .and_then(|name| self.named_directory_icons.get(name))
+            .and_then(|icons| icons.icon(expanded))
+            .or_else(|| self.directory_icons.icon(expanded))
+    }
+
+    /// Returns the chevron icon, if this icon theme has one.
+    pub fn chevron_icon(&self, expanded: bool) -> Option<SharedString> {
+        self.chevron_icons.icon(expanded)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_icon_theme_lookup() {
+        let content: IconThemeContent = serde_json::from_value(json!({
+            "name": "Test Icons",
+            "directory_icons": {
+                "collapsed": "icons/folder.svg",
+                "expanded": "icons/folder-open.svg"
+            },
+            "named_directory_icons": {
+                ".git": { "collapsed": "icons/git.svg" }
+            },
+            "file_names": { "Cargo.toml": "cargo" },
+            "file_suffixes": { "rs": "rust", "toml": "toml" },
+            "file_icons": {
+                "cargo": { "path": "icons/cargo.svg" },
+                "rust": { "path": "icons/rust.svg" }
+            }
+        }))
+        .unwrap();
+        let root = Path::new("/extensions/test-icons");
+        let icon_theme = IconTheme::from_content(content, root);
+        let icon = |path: &str| root.join(path).to_string_lossy().to_string();
+
+        assert_eq!(
+            icon_theme.file_icon(Path::new("src/main.rs")),
+            Some(icon("icons/rust.svg").into())
+        );
+        assert_eq!(
+            icon_theme.file_icon(Path::new("Cargo.toml")),
+            Some(icon("icons/cargo.svg").into())
+        );
+        // Suffixes without a matching icon definition fall back to the default icons.
+        assert_eq!(icon_theme.file_icon(Path::new("rustfmt.toml")), None);
+        assert_eq!(icon_theme.file_icon(Path::new("README.md")), None);
+
+        assert_eq!(
+            icon_theme.directory_icon(Path::new("src"), true),
+            Some(icon("icons/folder-open.svg").into())
+        );
+        assert_eq!(
+            icon_theme.directory_icon(Path::new(".git"), false),
+            Some(icon("icons/git.svg").into())
+        );
+        assert_eq!(
+            icon_theme.directory_icon(Path::new(".git"), true),
+            Some(icon("icons/folder-open.svg").into())
+        );
+        assert_eq!(icon_theme.chevron_icon(true), None);
+    }
+}
@@ -0,0 +1,42 @@
+#![allow(missing_docs)]
+
+use collections::HashMap;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// The content of a serialized icon theme family.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct IconThemeFamilyContent {
+    pub name: String,
+    pub author: String,
+    pub themes: Vec<IconThemeContent>,
+}
+
+/// The content of a serialized icon theme.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct IconThemeContent {
+    pub name: String,
+    #[serde(default)]
+    pub directory_icons: DirectoryIconsContent,
+    #[serde(default)]
+    pub named_directory_icons: HashMap<String, DirectoryIconsContent>,
+    #[serde(default)]
+    pub chevron_icons: DirectoryIconsContent,
+    #[serde(default)]
+    pub file_names: HashMap<String, String>,
+    #[serde(default)]
+    pub file_suffixes: HashMap<String, String>,
+    #[serde(default)]
+    pub file_icons: HashMap<String, IconDefinitionContent>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct DirectoryIconsContent {
+    pub collapsed: Option<String>,
+    pub expanded: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct IconDefinitionContent {
+    pub path: String,
+}
@@ -11,7 +11,8 @@ use parking_lot::RwLock;
 use util::ResultExt;
 
 use crate::{
-    read_user_theme, refine_theme_family, Appearance, Theme, ThemeFamily, ThemeFamilyContent,
+    read_icon_theme, read_user_theme, refine_theme_family, Appearance, IconTheme, Theme,
+    ThemeFamily, ThemeFamilyContent,
 };
 
 /// The metadata for a theme.
@@ -36,6 +37,7 @@ impl Global for GlobalThemeRegistry {}
 
 struct ThemeRegistryState {
     themes: HashMap<SharedString, Arc<Theme>>,
+    icon_themes: HashMap<SharedString, Arc<IconTheme>>,
 }
 
 /// The registry for themes.
@@ -67,6 +69,7 @@ impl ThemeRegistry {
         let registry = Self {
             state: RwLock::new(ThemeRegistryState {
                 themes: HashMap::default(),
+                icon_themes: HashMap::default(),
             }),
             assets,
         };
@@ -196,6 +199,59 @@ impl ThemeRegistry {
 
         Ok(())
     }
+
+    /// Returns the names of all icon themes in the registry.
+    pub fn list_icon_themes(&self) -> Vec<SharedString> {
+        let mut names = self
+            .state
+            .read()
+            .icon_themes
+            .keys()
+            .cloned()
+            .collect::<Vec<_>>();
+        names.sort();
+        names
+    }
+
+    /// Returns the icon theme with the given name.
+    pub fn get_icon_theme(&self, name: &str) -> Result<Arc<IconTheme>> {
+        self.state
+            .read()
+            .icon_themes
+            .get(name)
+            .ok_or_else(|| anyhow!("icon theme not found: {}", name))
+            .cloned()
+    }
+
+    /// Removes the icon themes with the given names from the registry.
+    pub fn remove_icon_themes(&self, icon_themes_to_remove: &[SharedString]) {
+        self.state
+            .write()
+            .icon_themes
+            .retain(|name, _| !icon_themes_to_remove.contains(name))
+    }
+
+    /// Loads the icon theme from the specified path and adds it to the registry.
+    ///
+    /// The icon paths in the icon theme are resolved relative to `icons_root_dir`.
+    pub async fn load_icon_theme(
+        &self,
+        icon_theme_path: &Path,
+        icons_root_dir: &Path,
+        fs: Arc<dyn Fs>,
+    ) -> Result<()> {
+        let icon_theme_family = read_icon_theme(icon_theme_path, fs).await?;
+
+        let mut state = self.state.write();
+        for icon_theme in icon_theme_family.themes {
+            let icon_theme = IconTheme::from_content(icon_theme, icons_root_dir);
+            state
+                .icon_themes
+                .insert(icon_theme.name.clone(), Arc::new(icon_theme));
+        }
+
+        Ok(())
+    }
 }
 
 impl Default for ThemeRegistry {
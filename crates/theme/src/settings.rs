@@ -1,10 +1,13 @@
 use crate::fallback_themes::zed_default_dark;
-use crate::{Appearance, SyntaxTheme, Theme, ThemeRegistry, ThemeStyleContent};
+use crate::{
+    Appearance, IconTheme, SyntaxTheme, Theme, ThemeRegistry, ThemeStyleContent,
+    DEFAULT_ICON_THEME_NAME,
+};
 use anyhow::Result;
 use derive_more::{Deref, DerefMut};
 use gpui::{
     px, AppContext, Font, FontFallbacks, FontFeatures, FontStyle, FontWeight, Global, Pixels,
-    Subscription, ViewContext, WindowContext,
+    SharedString, Subscription, ViewContext, WindowContext,
 };
 use refineable::Refineable;
 use schemars::{
@@ -118,6 +121,12 @@ pub struct ThemeSettings {
     ///
     /// Note: This setting is still experimental. See [this tracking issue](https://github.com/zed-industries/zed/issues/18078)
     pub theme_overrides: Option<ThemeStyleContent>,
+    /// The name of the selected icon theme.
+    pub icon_theme_selection: Option<String>,
+    /// The active icon theme.
+    ///
+    /// When this is `None`, the file icons bundled with Zed are used.
+    pub active_icon_theme: Option<Arc<IconTheme>>,
     /// The density of the UI.
     /// Note: This setting is still experimental. See [this tracking issue](
     pub ui_density: UiDensity,
@@ -160,6 +169,31 @@ impl ThemeSettings {
             }
         }
     }
+
+    /// Reloads the current icon theme.
+    ///
+    /// Reads the [`ThemeSettings`] to know which icon theme should be loaded.
+    pub fn reload_current_icon_theme(cx: &mut AppContext) {
+        let mut theme_settings = ThemeSettings::get_global(cx).clone();
+
+        if let Some(icon_theme_name) = theme_settings.icon_theme_selection.clone() {
+            let icon_theme = ThemeRegistry::global(cx)
+                .get_icon_theme(&icon_theme_name)
+                .ok();
+            if theme_settings.active_icon_theme != icon_theme {
+                theme_settings.active_icon_theme = icon_theme;
+                ThemeSettings::override_global(theme_settings, cx);
+            }
+        }
+    }
+
+    fn icon_theme(themes: &ThemeRegistry, icon_theme_name: &str) -> Option<Arc<IconTheme>> {
+        if icon_theme_name == DEFAULT_ICON_THEME_NAME {
+            return None;
+        }
+
+        themes.get_icon_theme(icon_theme_name).log_err()
+    }
 }
 
 /// The appearance of the system.
@@ -324,6 +358,9 @@ pub struct ThemeSettingsContent {
     /// The name of the Zed theme to use.
     #[serde(default)]
     pub theme: Option<ThemeSelection>,
+    /// The name of the icon theme to use.
+    #[serde(default)]
+    pub icon_theme: Option<String>,
 
     /// UNSTABLE: Expect many elements to be broken.
     ///
@@ -372,6 +409,11 @@ impl ThemeSettingsContent {
         }
     }
 
+    /// Sets the icon theme to the icon theme with the specified name.
+    pub fn set_icon_theme(&mut self, icon_theme_name: String) {
+        self.icon_theme = Some(icon_theme_name);
+    }
+
     /// Sets the mode for the theme.
     pub fn set_mode(&mut self, mode: ThemeMode) {
         if let Some(selection) = self.theme.as_mut() {
@@ -632,6 +674,11 @@ impl settings::Settings for ThemeSettings {
                 .or(themes.get(&zed_default_dark().name))
                 .unwrap(),
             theme_overrides: None,
+            icon_theme_selection: defaults.icon_theme.clone(),
+            active_icon_theme: defaults
+                .icon_theme
+                .as_ref()
+                .and_then(|name| Self::icon_theme(&themes, name)),
             ui_density: defaults.ui_density.unwrap_or(UiDensity::Default),
             unnecessary_code_fade: defaults.unnecessary_code_fade.unwrap_or(0.0),
         };
@@ -640,6 +687,7 @@ impl settings::Settings for ThemeSettings {
             .user
             .into_iter()
             .chain(sources.release_channel)
+            .chain(sources.profile)
             .chain(sources.server)
         {
             if let Some(value) = value.ui_density {
@@ -682,6 +730,11 @@ impl settings::Settings for ThemeSettings {
                 }
             }
 
+            if let Some(value) = &value.icon_theme {
+                this.icon_theme_selection = Some(value.clone());
+                this.active_icon_theme = Self::icon_theme(&themes, value);
+            }
+
             this.theme_overrides.clone_from(&value.theme_overrides);
             this.apply_theme_overrides();
 
@@ -722,8 +775,20 @@ impl settings::Settings for ThemeSettings {
             ..Default::default()
         };
 
+        let icon_theme_names = std::iter::once(DEFAULT_ICON_THEME_NAME.into())
+            .chain(ThemeRegistry::global(cx).list_icon_themes())
+            .map(|icon_theme_name: SharedString| Value::String(icon_theme_name.to_string()))
+            .collect();
+
+        let icon_theme_name_schema = SchemaObject {
+            instance_type: Some(InstanceType::String.into()),
+            enum_values: Some(icon_theme_names),
+            ..Default::default()
+        };
+
         root_schema.definitions.extend([
             ("ThemeName".into(), theme_name_schema.into()),
+            ("IconThemeName".into(), icon_theme_name_schema.into()),
             ("FontFamilies".into(), params.font_family_schema()),
             ("FontFallbacks".into(), params.font_fallback_schema()),
         ]);
@@ -732,6 +797,7 @@ impl settings::Settings for ThemeSettings {
             &mut root_schema,
             &[
                 ("buffer_font_family", "#/definitions/FontFamilies"),
+                ("icon_theme", "#/definitions/IconThemeName"),
                 ("buffer_font_fallbacks", "#/definitions/FontFallbacks"),
                 ("ui_font_family", "#/definitions/FontFamilies"),
                 ("ui_font_fallbacks", "#/definitions/FontFallbacks"),
@@ -0,0 +1,283 @@
+use std::sync::Arc;
+
+use client::telemetry::Telemetry;
+use fs::Fs;
+use fuzzy::{match_strings, StringMatch, StringMatchCandidate};
+use gpui::{
+    AppContext, DismissEvent, EventEmitter, FocusableView, Render, SharedString, UpdateGlobal,
+    View, ViewContext, VisualContext, WeakView,
+};
+use picker::{Picker, PickerDelegate};
+use settings::{update_settings_file, Settings as _, SettingsStore};
+use theme::{IconTheme, ThemeRegistry, ThemeSettings, DEFAULT_ICON_THEME_NAME};
+use ui::{prelude::*, v_flex, ListItem, ListItemSpacing};
+use util::ResultExt;
+use workspace::{ui::HighlightedLabel, ModalView, Workspace};
+use zed_actions::icon_theme_selector::Toggle;
+
+pub fn toggle(workspace: &mut Workspace, toggle: &Toggle, cx: &mut ViewContext<Workspace>) {
+    let fs = workspace.app_state().fs.clone();
+    let telemetry = workspace.client().telemetry().clone();
+    workspace.toggle_modal(cx, |cx| {
+        let delegate = IconThemeSelectorDelegate::new(
+            cx.view().downgrade(),
+            fs,
+            telemetry,
+            toggle.themes_filter.as_ref(),
+            cx,
+        );
+        IconThemeSelector::new(delegate, cx)
+    });
+}
+
+impl ModalView for IconThemeSelector {}
+
+pub struct IconThemeSelector {
+    picker: View<Picker<IconThemeSelectorDelegate>>,
+}
+
+impl EventEmitter<DismissEvent> for IconThemeSelector {}
+
+impl FocusableView for IconThemeSelector {
+    fn focus_handle(&self, cx: &AppContext) -> gpui::FocusHandle {
+        self.picker.focus_handle(cx)
+    }
+}
+
+impl Render for IconThemeSelector {
+    fn render(&mut self, _cx: &mut ViewContext<Self>) -> impl IntoElement {
+        v_flex().w(rems(34.)).child(self.picker.clone())
+    }
+}
+
+impl IconThemeSelector {
+    pub fn new(delegate: IconThemeSelectorDelegate, cx: &mut ViewContext<Self>) -> Self {
+        let picker = cx.new_view(|cx| Picker::uniform_list(delegate, cx));
+        Self { picker }
+    }
+}
+
+pub struct IconThemeSelectorDelegate {
+    fs: Arc<dyn Fs>,
+    themes: Vec<SharedString>,
+    matches: Vec<StringMatch>,
+    original_theme: Option<Arc<IconTheme>>,
+    selection_completed: bool,
+    selected_index: usize,
+    telemetry: Arc<Telemetry>,
+    view: WeakView<IconThemeSelector>,
+}
+
+impl IconThemeSelectorDelegate {
+    fn new(
+        weak_view: WeakView<IconThemeSelector>,
+        fs: Arc<dyn Fs>,
+        telemetry: Arc<Telemetry>,
+        themes_filter: Option<&Vec<String>>,
+        cx: &mut ViewContext<IconThemeSelector>,
+    ) -> Self {
+        let original_theme = ThemeSettings::get_global(cx).active_icon_theme.clone();
+
+        let registry = ThemeRegistry::global(cx);
+        let themes = std::iter::once(SharedString::from(DEFAULT_ICON_THEME_NAME))
+            .chain(registry.list_icon_themes())
+            .filter(|name| {
+                if let Some(theme_filter) = themes_filter {
+                    theme_filter.contains(&name.to_string())
+                } else {
+                    true
+                }
+            })
+            .collect::<Vec<_>>();
+
+        let matches = themes
+            .iter()
+            .map(|name| StringMatch {
+                candidate_id: 0,
+                score: 0.0,
+                positions: Default::default(),
+                string: name.to_string(),
+            })
+            .collect();
+        let mut this = Self {
+            fs,
+            themes,
+            matches,
+            original_theme: original_theme.clone(),
+            selected_index: 0,
+            selection_completed: false,
+            telemetry,
+            view: weak_view,
+        };
+
+        let original_theme_name = original_theme
+            .map(|theme| theme.name.clone())
+            .unwrap_or_else(|| DEFAULT_ICON_THEME_NAME.into());
+        this.select_if_matching(&original_theme_name);
+        this
+    }
+
+    fn show_selected_theme(&mut self, cx: &mut ViewContext<Picker<IconThemeSelectorDelegate>>) {
+        if let Some(mat) = self.matches.get(self.selected_index) {
+            if mat.string == DEFAULT_ICON_THEME_NAME {
+                Self::set_icon_theme(None, cx);
+                return;
+            }
+
+            let registry = ThemeRegistry::global(cx);
+            match registry.get_icon_theme(&mat.string) {
+                Ok(theme) => {
+                    Self::set_icon_theme(Some(theme), cx);
+                }
+                Err(error) => {
+                    log::error!("error loading icon theme {}: {}", mat.string, error)
+                }
+            }
+        }
+    }
+
+    fn select_if_matching(&mut self, theme_name: &str) {
+        self.selected_index = self
+            .matches
+            .iter()
+            .position(|mat| mat.string == theme_name)
+            .unwrap_or(self.selected_index);
+    }
+
+    fn set_icon_theme(theme: Option<Arc<IconTheme>>, cx: &mut AppContext) {
+        SettingsStore::update_global(cx, |store, cx| {
+            let mut theme_settings = store.get::<ThemeSettings>(None).clone();
+            theme_settings.active_icon_theme = theme;
+            store.override_global(theme_settings);
+            cx.refresh();
+        });
+    }
+}
+
+impl PickerDelegate for IconThemeSelectorDelegate {
+    type ListItem = ui::ListItem;
+
+    fn placeholder_text(&self, _cx: &mut WindowContext) -> Arc<str> {
+        "Select Icon Theme...".into()
+    }
+
+    fn match_count(&self) -> usize {
+        self.matches.len()
+    }
+
+    fn confirm(&mut self, _: bool, cx: &mut ViewContext<Picker<IconThemeSelectorDelegate>>) {
+        self.selection_completed = true;
+
+        let theme_name = ThemeSettings::get_global(cx)
+            .active_icon_theme
+            .as_ref()
+            .map(|theme| theme.name.clone())
+            .unwrap_or_else(|| DEFAULT_ICON_THEME_NAME.into());
+
+        self.telemetry
+            .report_setting_event("icon_theme", theme_name.to_string());
+
+        update_settings_file::<ThemeSettings>(self.fs.clone(), cx, move |settings, _| {
+            settings.set_icon_theme(theme_name.to_string());
+        });
+
+        self.view
+            .update(cx, |_, cx| {
+                cx.emit(DismissEvent);
+            })
+            .ok();
+    }
+
+    fn dismissed(&mut self, cx: &mut ViewContext<Picker<IconThemeSelectorDelegate>>) {
+        if !self.selection_completed {
+            Self::set_icon_theme(self.original_theme.clone(), cx);
+            self.selection_completed = true;
+        }
+
+        self.view
+            .update(cx, |_, cx| cx.emit(DismissEvent))
+            .log_err();
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(
+        &mut self,
+        ix: usize,
+        cx: &mut ViewContext<Picker<IconThemeSelectorDelegate>>,
+    ) {
+        self.selected_index = ix;
+        self.show_selected_theme(cx);
+    }
+
+    fn update_matches(
+        &mut self,
+        query: String,
+        cx: &mut ViewContext<Picker<IconThemeSelectorDelegate>>,
+    ) -> gpui::Task<()> {
+        let background = cx.background_executor().clone();
+        let candidates = self
+            .themes
+            .iter()
+            .enumerate()
+            .map(|(id, name)| StringMatchCandidate::new(id, name))
+            .collect::<Vec<_>>();
+
+        cx.spawn(|this, mut cx| async move {
+            let matches = if query.is_empty() {
+                candidates
+                    .into_iter()
+                    .enumerate()
+                    .map(|(index, candidate)| StringMatch {
+                        candidate_id: index,
+                        string: candidate.string,
+                        positions: Vec::new(),
+                        score: 0.0,
+                    })
+                    .collect()
+            } else {
+                match_strings(
+                    &candidates,
+                    &query,
+                    false,
+                    100,
+                    &Default::default(),
+                    background,
+                )
+                .await
+            };
+
+            this.update(&mut cx, |this, cx| {
+                this.delegate.matches = matches;
+                this.delegate.selected_index = this
+                    .delegate
+                    .selected_index
+                    .min(this.delegate.matches.len().saturating_sub(1));
+                this.delegate.show_selected_theme(cx);
+            })
+            .log_err();
+        })
+    }
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        _cx: &mut ViewContext<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        let theme_match = &self.matches[ix];
+
+        Some(
+            ListItem::new(ix)
+                .inset(true)
+                .spacing(ListItemSpacing::Sparse)
+                .toggle_state(selected)
+                .child(HighlightedLabel::new(
+                    theme_match.string.clone(),
+                    theme_match.positions.clone(),
+                )),
+        )
+    }
+}
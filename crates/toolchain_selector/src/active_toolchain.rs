@@ -122,8 +122,12 @@ impl ActiveToolchain {
                     })
                     .ok()?
                     .await?;
-                if let Some(toolchain) = toolchains.toolchains.first() {
-                    // Since we don't have a selected toolchain, pick one for user here.
+                // Since we don't have a selected toolchain, pick one for user here, preferring
+                // the one the project asks for.
+                let toolchain = toolchains
+                    .default_toolchain()
+                    .or_else(|| toolchains.toolchains.first().cloned());
+                if let Some(toolchain) = toolchain.as_ref() {
                     workspace::WORKSPACE_DB
                         .set_toolchain(workspace_id, worktree_id, toolchain.clone())
                         .await
@@ -136,7 +140,7 @@ impl ActiveToolchain {
                         .await;
                 }
 
-                toolchains.toolchains.first().cloned()
+                toolchain
             }
         })
     }
@@ -377,11 +377,17 @@ impl ButtonCommon for Button {
         self.base = self.base.layer(elevation);
         self
     }
+
+    /// Sets the name assistive technologies announce for the button, which defaults to its label.
+    fn accessibility_name(mut self, name: impl Into<SharedString>) -> Self {
+        self.base = self.base.accessibility_name(name);
+        self
+    }
 }
 
 impl RenderOnce for Button {
     #[allow(refining_impl_trait)]
-    fn render(self, cx: &mut WindowContext) -> ButtonLike {
+    fn render(mut self, cx: &mut WindowContext) -> ButtonLike {
         let is_disabled = self.base.disabled;
         let is_selected = self.base.selected;
 
@@ -389,6 +395,9 @@ impl RenderOnce for Button {
             .selected_label
             .filter(|_| is_selected)
             .unwrap_or(self.label);
+        self.base
+            .accessibility_name
+            .get_or_insert_with(|| label.clone());
 
         let label_color = if is_disabled {
             Color::Disabled
@@ -1,5 +1,5 @@
 #![allow(missing_docs)]
-use gpui::{relative, CursorStyle, DefiniteLength, MouseButton};
+use gpui::{relative, AccessibilityRole, CursorStyle, DefiniteLength, MouseButton};
 use gpui::{transparent_black, AnyElement, AnyView, ClickEvent, Hsla, Rems};
 use smallvec::SmallVec;
 
@@ -36,6 +36,11 @@ pub trait ButtonCommon: Clickable + Disableable {
     fn tooltip(self, tooltip: impl Fn(&mut WindowContext) -> AnyView + 'static) -> Self;
 
     fn layer(self, elevation: ElevationIndex) -> Self;
+
+    /// The name assistive technologies announce for the button.
+    ///
+    /// Buttons with a label are named after it, so this is mostly useful for icon buttons.
+    fn accessibility_name(self, name: impl Into<SharedString>) -> Self;
 }
 
 #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy, Default)]
@@ -351,6 +356,8 @@ pub struct ButtonLike {
     cursor_style: CursorStyle,
     on_click: Option<Box<dyn Fn(&ClickEvent, &mut WindowContext) + 'static>>,
     children: SmallVec<[AnyElement; 2]>,
+    pub(super) accessibility_role: AccessibilityRole,
+    pub(super) accessibility_name: Option<SharedString>,
 }
 
 impl ButtonLike {
@@ -371,6 +378,8 @@ impl ButtonLike {
             cursor_style: CursorStyle::PointingHand,
             on_click: None,
             layer: None,
+            accessibility_role: AccessibilityRole::Button,
+            accessibility_name: None,
         }
     }
 
@@ -462,6 +471,11 @@ impl ButtonCommon for ButtonLike {
         self.layer = Some(elevation);
         self
     }
+
+    fn accessibility_name(mut self, name: impl Into<SharedString>) -> Self {
+        self.accessibility_name = Some(name.into());
+        self
+    }
 }
 
 impl VisibleOnHover for ButtonLike {
@@ -487,6 +501,15 @@ impl RenderOnce for ButtonLike {
         self.base
             .h_flex()
             .id(self.id.clone())
+            .accessibility_role(self.accessibility_role)
+            .when_some(self.accessibility_name, |this, name| {
+                this.accessibility_name(name)
+            })
+            .when(
+                self.accessibility_role == AccessibilityRole::ToggleButton,
+                |this| this.accessibility_selected(self.selected),
+            )
+            .accessibility_disabled(self.disabled)
             .group("")
             .flex_none()
             .h(self.height.unwrap_or(self.size.rems().into()))
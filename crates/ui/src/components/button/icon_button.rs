@@ -131,6 +131,11 @@ impl ButtonCommon for IconButton {
         self.base = self.base.layer(elevation);
         self
     }
+
+    fn accessibility_name(mut self, name: impl Into<SharedString>) -> Self {
+        self.base = self.base.accessibility_name(name);
+        self
+    }
 }
 
 impl VisibleOnHover for IconButton {
@@ -141,10 +146,15 @@ impl VisibleOnHover for IconButton {
 }
 
 impl RenderOnce for IconButton {
-    fn render(self, cx: &mut WindowContext) -> impl IntoElement {
+    fn render(mut self, cx: &mut WindowContext) -> impl IntoElement {
         let is_disabled = self.base.disabled;
         let is_selected = self.base.selected;
         let selected_style = self.base.selected_style;
+        // Icon buttons have no label, so fall back to naming them after their icon.
+        let icon = self.icon;
+        self.base
+            .accessibility_name
+            .get_or_insert_with(|| <&'static str>::from(icon).replace('_', " ").into());
 
         self.base
             .map(|this| match self.shape {
@@ -1,5 +1,5 @@
 #![allow(missing_docs)]
-use gpui::{AnyView, ClickEvent};
+use gpui::{AccessibilityRole, AnyView, ClickEvent};
 
 use crate::{prelude::*, ButtonLike, ButtonLikeRounding, ElevationIndex};
 
@@ -26,8 +26,10 @@ pub struct ToggleButton {
 
 impl ToggleButton {
     pub fn new(id: impl Into<ElementId>, label: impl Into<SharedString>) -> Self {
+        let mut base = ButtonLike::new(id);
+        base.accessibility_role = AccessibilityRole::ToggleButton;
         Self {
-            base: ButtonLike::new(id),
+            base,
             position_in_group: None,
             label: label.into(),
             label_color: None,
@@ -114,10 +116,15 @@ impl ButtonCommon for ToggleButton {
         self.base = self.base.layer(elevation);
         self
     }
+
+    fn accessibility_name(mut self, name: impl Into<SharedString>) -> Self {
+        self.base = self.base.accessibility_name(name);
+        self
+    }
 }
 
 impl RenderOnce for ToggleButton {
-    fn render(self, _cx: &mut WindowContext) -> impl IntoElement {
+    fn render(mut self, _cx: &mut WindowContext) -> impl IntoElement {
         let is_disabled = self.base.disabled;
         let is_selected = self.base.selected;
 
@@ -128,6 +135,9 @@ impl RenderOnce for ToggleButton {
         } else {
             self.label_color.unwrap_or_default()
         };
+        self.base
+            .accessibility_name
+            .get_or_insert_with(|| self.label.clone());
 
         self.base
             .when_some(self.position_in_group, |this, position| match position {
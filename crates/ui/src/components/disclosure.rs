@@ -66,6 +66,7 @@ impl RenderOnce for Disclosure {
         .icon_color(Color::Muted)
         .icon_size(IconSize::Small)
         .toggle_state(self.selected)
+        .accessibility_name(if self.is_open { "Collapse" } else { "Expand" })
         .when_some(self.on_toggle, move |this, on_toggle| {
             this.on_click(move |event, cx| on_toggle(event, cx))
         })
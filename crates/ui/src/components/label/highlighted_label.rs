@@ -118,6 +118,7 @@ impl RenderOnce for HighlightedLabel {
         text_style.color = self.base.color.color(cx);
 
         self.base
+            .accessibility_text(self.label.clone())
             .child(StyledText::new(self.label).with_highlights(&text_style, highlights))
     }
 }
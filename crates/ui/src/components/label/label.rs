@@ -178,6 +178,8 @@ impl RenderOnce for Label {
         } else {
             self.label
         };
-        self.base.child(target_label)
+        self.base
+            .accessibility_text(target_label.clone())
+            .child(target_label)
     }
 }
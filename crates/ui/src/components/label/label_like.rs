@@ -1,6 +1,8 @@
 #![allow(missing_docs)]
 
-use gpui::{relative, AnyElement, FontWeight, StyleRefinement, Styled, UnderlineStyle};
+use gpui::{
+    relative, AccessibilityRole, AnyElement, FontWeight, StyleRefinement, Styled, UnderlineStyle,
+};
 use settings::Settings;
 use smallvec::SmallVec;
 use theme::ThemeSettings;
@@ -91,6 +93,15 @@ impl LabelLike {
             single_line: false,
         }
     }
+
+    /// Exposes the label to assistive technologies with the given text.
+    pub(super) fn accessibility_text(mut self, text: SharedString) -> Self {
+        self.base = self
+            .base
+            .accessibility_role(AccessibilityRole::Label)
+            .accessibility_name(text);
+        self
+    }
 }
 
 // Style methods.
@@ -2,7 +2,9 @@
 
 use std::sync::Arc;
 
-use gpui::{px, AnyElement, AnyView, ClickEvent, MouseButton, MouseDownEvent, Pixels};
+use gpui::{
+    px, AccessibilityRole, AnyElement, AnyView, ClickEvent, MouseButton, MouseDownEvent, Pixels,
+};
 use smallvec::SmallVec;
 
 use crate::{prelude::*, Disclosure};
@@ -40,6 +42,7 @@ pub struct ListItem {
     selectable: bool,
     overflow_x: bool,
     focused: Option<bool>,
+    accessibility_name: Option<SharedString>,
 }
 
 impl ListItem {
@@ -64,6 +67,7 @@ impl ListItem {
             selectable: true,
             overflow_x: false,
             focused: None,
+            accessibility_name: None,
         }
     }
 
@@ -147,6 +151,12 @@ impl ListItem {
         self.focused = Some(focused);
         self
     }
+
+    /// Sets the name assistive technologies announce for the item, such as a file name.
+    pub fn accessibility_name(mut self, name: impl Into<SharedString>) -> Self {
+        self.accessibility_name = Some(name.into());
+        self
+    }
 }
 
 impl Disableable for ListItem {
@@ -203,6 +213,17 @@ impl RenderOnce for ListItem {
             .child(
                 h_flex()
                     .id("inner_list_item")
+                    .accessibility_role(AccessibilityRole::ListItem)
+                    .when_some(self.accessibility_name, |this, name| {
+                        this.accessibility_name(name)
+                    })
+                    .when(self.selectable, |this| {
+                        this.accessibility_selected(self.selected)
+                    })
+                    .when_some(self.toggle, |this, is_open| {
+                        this.accessibility_expanded(is_open)
+                    })
+                    .accessibility_disabled(self.disabled)
                     .w_full()
                     .relative()
                     .items_center()
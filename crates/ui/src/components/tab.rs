@@ -1,7 +1,7 @@
 #![allow(missing_docs)]
 use std::cmp::Ordering;
 
-use gpui::{AnyElement, IntoElement, Stateful};
+use gpui::{AccessibilityRole, AnyElement, IntoElement, Stateful};
 use smallvec::SmallVec;
 
 use crate::prelude::*;
@@ -134,6 +134,8 @@ impl RenderOnce for Tab {
         };
 
         self.div
+            .accessibility_role(AccessibilityRole::Tab)
+            .accessibility_selected(self.selected)
             .h(Tab::container_height(cx))
             .bg(tab_bg)
             .border_color(cx.theme().colors().border)
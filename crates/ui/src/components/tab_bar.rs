@@ -1,5 +1,5 @@
 #![allow(missing_docs)]
-use gpui::{AnyElement, ScrollHandle};
+use gpui::{AccessibilityRole, AnyElement, ScrollHandle};
 use smallvec::SmallVec;
 
 use crate::prelude::*;
@@ -94,6 +94,7 @@ impl RenderOnce for TabBar {
     fn render(self, cx: &mut WindowContext) -> impl IntoElement {
         div()
             .id(self.id)
+            .accessibility_role(AccessibilityRole::TabList)
             .group("tab_bar")
             .flex()
             .flex_none()
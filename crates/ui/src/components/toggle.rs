@@ -1,6 +1,6 @@
 #![allow(missing_docs)]
 
-use gpui::{div, prelude::*, ElementId, IntoElement, Styled, WindowContext};
+use gpui::{div, prelude::*, AccessibilityRole, ElementId, IntoElement, Styled, WindowContext};
 use std::sync::Arc;
 
 use crate::prelude::*;
@@ -98,6 +98,9 @@ impl RenderOnce for Checkbox {
 
         h_flex()
             .id(self.id)
+            .accessibility_role(AccessibilityRole::Checkbox)
+            .accessibility_selected(selected)
+            .accessibility_disabled(self.disabled)
             .justify_center()
             .items_center()
             .size(DynamicSpacing::Base20.rems(cx))
@@ -244,6 +247,9 @@ impl RenderOnce for Switch {
 
         h_flex()
             .id(self.id)
+            .accessibility_role(AccessibilityRole::ToggleButton)
+            .accessibility_selected(is_on)
+            .accessibility_disabled(self.disabled)
             .items_center()
             .w(DynamicSpacing::Base32.rems(cx))
             .h(DynamicSpacing::Base20.rems(cx))
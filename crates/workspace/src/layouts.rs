@@ -0,0 +1,643 @@
+use std::{
+    collections::BTreeMap,
+    mem,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use anyhow::{Context as _, Result};
+use collections::HashMap;
+use gpui::{px, Model, SharedString, Task, View, ViewContext, WindowContext};
+use project::{Project, ProjectPath};
+use serde::Deserialize;
+use util::ResultExt;
+
+use crate::{
+    dock::Dock,
+    item::ItemHandle,
+    persistence::{
+        model::{
+            SerializedLayout, SerializedLayoutDock, SerializedLayoutDocks, SerializedLayoutGroup,
+        },
+        DB,
+    },
+    Member, Pane, PaneAxis, PaneGroup, Workspace,
+};
+
+/// Saves the current arrangement of panes and docks under the given name.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+pub struct SaveLayout {
+    pub name: String,
+    /// Whether to write the layout to the project's `.zed/layouts.json`, rather than
+    /// keeping it on this machine only.
+    #[serde(default)]
+    pub shared: bool,
+}
+
+/// Switches to the layout with the given name.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+pub struct ApplyLayout {
+    pub name: String,
+}
+
+gpui::impl_actions!(workspace, [SaveLayout, ApplyLayout]);
+
+/// Where a [`NamedLayout`] is stored.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LayoutSource {
+    /// The workspace database on this machine.
+    Local,
+    /// A `.zed/layouts.json` file inside the project, shared with anyone who opens it.
+    Project,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct NamedLayout {
+    pub name: SharedString,
+    pub source: LayoutSource,
+    pub layout: SerializedLayout,
+}
+
+impl Workspace {
+    /// Returns the layouts available in this workspace, sorted by name.
+    ///
+    /// Layouts saved locally take precedence over project layouts with the same name.
+    pub fn layouts(&self, cx: &mut ViewContext<Self>) -> Task<Result<Vec<NamedLayout>>> {
+        let database_id = self.database_id();
+        let fs = self.app_state.fs.clone();
+        let layout_files = project_layout_files(&self.project, cx);
+        cx.background_executor().spawn(async move {
+            let mut layouts = BTreeMap::new();
+            for path in layout_files {
+                if !fs.is_file(&path).await {
+                    continue;
+                }
+                let Some(project_layouts) = fs
+                    .load(&path)
+                    .await
+                    .and_then(|contents| {
+                        settings::parse_json_with_comments::<BTreeMap<String, SerializedLayout>>(
+                            &contents,
+                        )
+                    })
+                    .with_context(|| format!("loading layouts from {path:?}"))
+                    .log_err()
+                else {
+                    continue;
+                };
+                for (name, layout) in project_layouts {
+                    layouts
+                        .entry(name)
+                        .or_insert((LayoutSource::Project, layout));
+                }
+            }
+
+            if let Some(database_id) = database_id {
+                for (name, layout) in DB.layouts(database_id)? {
+                    layouts.insert(name, (LayoutSource::Local, layout));
+                }
+            }
+
+            Ok(layouts
+                .into_iter()
+                .map(|(name, (source, layout))| NamedLayout {
+                    name: name.into(),
+                    source,
+                    layout,
+                })
+                .collect())
+        })
+    }
+
+    /// Captures the current arrangement of the center panes and docks.
+    ///
+    /// Items that don't correspond to a file in the project, such as terminals, are omitted.
+    pub fn capture_layout(&self, cx: &WindowContext) -> SerializedLayout {
+        SerializedLayout {
+            center: self.capture_layout_member(&self.center.root, cx),
+            docks: SerializedLayoutDocks {
+                left: capture_layout_dock(&self.left_dock, cx),
+                right: capture_layout_dock(&self.right_dock, cx),
+                bottom: capture_layout_dock(&self.bottom_dock, cx),
+            },
+        }
+    }
+
+    fn capture_layout_member(&self, member: &Member, cx: &WindowContext) -> SerializedLayoutGroup {
+        match member {
+            Member::Axis(axis) => SerializedLayoutGroup::Group {
+                axis: axis.axis,
+                flexes: Some(axis.flexes.lock().clone()),
+                children: axis
+                    .members
+                    .iter()
+                    .map(|member| self.capture_layout_member(member, cx))
+                    .collect(),
+            },
+            Member::Pane(pane) => {
+                let pane_state = pane.read(cx);
+                let active_item_id = pane_state.active_item().map(|item| item.item_id());
+                let mut items = Vec::new();
+                let mut active_item = None;
+                for item in pane_state.items() {
+                    let Some(project_path) = item.project_path(cx) else {
+                        continue;
+                    };
+                    if Some(item.item_id()) == active_item_id {
+                        active_item = Some(items.len());
+                    }
+                    items.push(project_path.path.to_path_buf());
+                }
+                SerializedLayoutGroup::Pane {
+                    items,
+                    active_item,
+                    active: pane == &self.active_pane,
+                }
+            }
+        }
+    }
+
+    /// Saves the current layout under the given name, either in the workspace database
+    /// or, when `shared` is true, in the `.zed/layouts.json` file of the first worktree.
+    pub fn save_layout(
+        &mut self,
+        name: impl Into<String>,
+        shared: bool,
+        cx: &mut ViewContext<Self>,
+    ) -> Task<Result<()>> {
+        let name = name.into();
+        let layout = self.capture_layout(cx);
+        if shared {
+            return self.share_layout(name, layout, cx);
+        }
+
+        let Some(database_id) = self.database_id() else {
+            return Task::ready(Err(anyhow::anyhow!(
+                "layouts can only be saved for workspaces with a project"
+            )));
+        };
+        cx.background_executor()
+            .spawn(DB.save_layout(database_id, name, layout))
+    }
+
+    /// Writes a layout into the `.zed/layouts.json` file of the first worktree, replacing
+    /// any layout with the same name and keeping the others.
+    pub fn share_layout(
+        &mut self,
+        name: String,
+        layout: SerializedLayout,
+        cx: &mut ViewContext<Self>,
+    ) -> Task<Result<()>> {
+        let fs = self.app_state.fs.clone();
+        let Some(path) = project_layout_files(&self.project, cx).into_iter().next() else {
+            return Task::ready(Err(anyhow::anyhow!(
+                "layouts can only be shared from local projects"
+            )));
+        };
+        cx.background_executor().spawn(async move {
+            let mut layouts = if fs.is_file(&path).await {
+                let contents = fs.load(&path).await?;
+                settings::parse_json_with_comments::<BTreeMap<String, SerializedLayout>>(&contents)
+                    .with_context(|| format!("parsing layouts in {path:?}"))?
+            } else {
+                BTreeMap::default()
+            };
+            layouts.insert(name, layout);
+
+            if let Some(parent) = path.parent() {
+                fs.create_dir(parent).await?;
+            }
+            fs.atomic_write(path, serde_json::to_string_pretty(&layouts)?)
+                .await
+        })
+    }
+
+    /// Deletes a layout saved in the workspace database.
+    pub fn delete_layout(&mut self, name: String, cx: &mut ViewContext<Self>) -> Task<Result<()>> {
+        let Some(database_id) = self.database_id() else {
+            return Task::ready(Ok(()));
+        };
+        cx.background_executor()
+            .spawn(DB.delete_layout(database_id, name))
+    }
+
+    /// Looks up a layout by name and switches to it.
+    pub fn apply_named_layout(
+        &mut self,
+        name: SharedString,
+        cx: &mut ViewContext<Self>,
+    ) -> Task<Result<()>> {
+        let layouts = self.layouts(cx);
+        cx.spawn(|this, mut cx| async move {
+            let layout = layouts
+                .await?
+                .into_iter()
+                .find(|layout| layout.name == name)
+                .with_context(|| format!("no layout named {name:?}"))?;
+            this.update(&mut cx, |this, cx| this.apply_layout(layout.layout, cx))?
+                .await
+        })
+    }
+
+    /// Rearranges the center panes and docks to match the given layout.
+    ///
+    /// Items that are already open are moved into their new panes, and the remaining files are
+    /// opened. Open items that aren't part of the layout are closed, unless they have unsaved
+    /// changes, in which case they're kept in the first pane.
+    pub fn apply_layout(
+        &mut self,
+        layout: SerializedLayout,
+        cx: &mut ViewContext<Self>,
+    ) -> Task<Result<()>> {
+        self.dismiss_zoomed_items_to_reveal(None, cx);
+
+        let mut open_items =
+            HashMap::<ProjectPath, Vec<(View<Pane>, Box<dyn ItemHandle>)>>::default();
+        for pane in self.center.panes() {
+            for item in pane.read(cx).items() {
+                if let Some(project_path) = item.project_path(cx) {
+                    open_items
+                        .entry(project_path)
+                        .or_default()
+                        .push((pane.clone(), item.boxed_clone()));
+                }
+            }
+        }
+
+        let mut panes = Vec::new();
+        let mut active_pane = None;
+        let root = self.build_layout_member(
+            &layout.center,
+            &mut open_items,
+            &mut panes,
+            &mut active_pane,
+            cx,
+        );
+        let old_center = mem::replace(&mut self.center, PaneGroup::with_root(root));
+
+        let first_pane = self.center.first_pane();
+        for pane in old_center.panes() {
+            let dirty_items = pane
+                .read(cx)
+                .items()
+                .filter(|item| item.is_dirty(cx))
+                .map(|item| item.boxed_clone())
+                .collect::<Vec<_>>();
+            for item in dirty_items {
+                pane.update(cx, |pane, cx| {
+                    pane.remove_item(item.item_id(), false, false, cx)
+                });
+                first_pane.update(cx, |pane, cx| pane.add_item(item, false, false, None, cx));
+            }
+        }
+        self.remove_panes(old_center.root, cx);
+
+        let active_pane = active_pane.unwrap_or(first_pane);
+        self.set_active_pane(&active_pane, cx);
+        active_pane.update(cx, |pane, cx| pane.focus(cx));
+
+        for (dock, layout_dock) in [
+            (self.left_dock.clone(), &layout.docks.left),
+            (self.right_dock.clone(), &layout.docks.right),
+            (self.bottom_dock.clone(), &layout.docks.bottom),
+        ] {
+            dock.update(cx, |dock, cx| apply_layout_dock(dock, layout_dock, cx));
+        }
+
+        cx.notify();
+        self.serialize_workspace(cx);
+
+        let mut open_tasks = Vec::new();
+        for (pane, project_paths, _) in &panes {
+            for project_path in project_paths {
+                let already_open = pane
+                    .read(cx)
+                    .items()
+                    .any(|item| item.project_path(cx).as_ref() == Some(project_path));
+                if !already_open {
+                    open_tasks.push(self.open_path(
+                        project_path.clone(),
+                        Some(pane.downgrade()),
+                        false,
+                        cx,
+                    ));
+                }
+            }
+        }
+
+        cx.spawn(|_, mut cx| async move {
+            for result in futures::future::join_all(open_tasks).await {
+                result.log_err();
+            }
+
+            // Items open at different speeds, so restore the order from the layout once
+            // they're all in place.
+            cx.update(|cx| {
+                for (pane, paths, active_path) in panes {
+                    pane.update(cx, |pane, cx| {
+                        for (index, project_path) in paths.iter().enumerate() {
+                            let item = pane
+                                .items()
+                                .find(|item| item.project_path(cx).as_ref() == Some(project_path))
+                                .map(|item| item.boxed_clone());
+                            if let Some(item) = item {
+                                pane.add_item(item, false, false, Some(index), cx);
+                            }
+                        }
+                        let active_index = active_path.and_then(|active_path| {
+                            pane.items()
+                                .position(|item| item.project_path(cx) == Some(active_path.clone()))
+                        });
+                        if let Some(index) = active_index {
+                            pane.activate_item(index, false, false, cx);
+                        }
+                    });
+                }
+            })
+        })
+    }
+
+    fn build_layout_member(
+        &mut self,
+        group: &SerializedLayoutGroup,
+        open_items: &mut HashMap<ProjectPath, Vec<(View<Pane>, Box<dyn ItemHandle>)>>,
+        panes: &mut Vec<(View<Pane>, Vec<ProjectPath>, Option<ProjectPath>)>,
+        active_pane: &mut Option<View<Pane>>,
+        cx: &mut ViewContext<Self>,
+    ) -> Member {
+        match group {
+            SerializedLayoutGroup::Group {
+                axis,
+                flexes,
+                children,
+            } if !children.is_empty() => {
+                let mut members = children
+                    .iter()
+                    .map(|child| {
+                        self.build_layout_member(child, open_items, panes, active_pane, cx)
+                    })
+                    .collect::<Vec<_>>();
+                if members.len() == 1 {
+                    return members.remove(0);
+                }
+                let flexes = flexes
+                    .clone()
+                    .filter(|flexes| flexes.len() == members.len());
+                Member::Axis(PaneAxis::load(*axis, members, flexes))
+            }
+            SerializedLayoutGroup::Group { .. } => {
+                let empty_pane = SerializedLayoutGroup::Pane {
+                    items: Vec::new(),
+                    active_item: None,
+                    active: false,
+                };
+                self.build_layout_member(&empty_pane, open_items, panes, active_pane, cx)
+            }
+            SerializedLayoutGroup::Pane {
+                items,
+                active_item,
+                active,
+            } => {
+                let pane = self.add_pane(cx);
+                let mut project_paths = Vec::new();
+                let mut active_path = None;
+                for (index, path) in items.iter().enumerate() {
+                    let Some(project_path) = layout_project_path(&self.project, path, cx) else {
+                        continue;
+                    };
+                    if let Some((source, item)) = open_items
+                        .get_mut(&project_path)
+                        .and_then(|items| items.pop())
+                    {
+                        source.update(cx, |source, cx| {
+                            source.remove_item(item.item_id(), false, false, cx)
+                        });
+                        pane.update(cx, |pane, cx| pane.add_item(item, false, false, None, cx));
+                    }
+                    if Some(index) == *active_item {
+                        active_path = Some(project_path.clone());
+                    }
+                    project_paths.push(project_path);
+                }
+                if *active && active_pane.is_none() {
+                    *active_pane = Some(pane.clone());
+                }
+                panes.push((pane.clone(), project_paths, active_path));
+                Member::Pane(pane)
+            }
+        }
+    }
+
+    pub(crate) fn save_layout_action(&mut self, action: &SaveLayout, cx: &mut ViewContext<Self>) {
+        self.save_layout(action.name.clone(), action.shared, cx)
+            .detach_and_log_err(cx);
+    }
+
+    pub(crate) fn apply_layout_action(&mut self, action: &ApplyLayout, cx: &mut ViewContext<Self>) {
+        self.apply_named_layout(action.name.clone().into(), cx)
+            .detach_and_log_err(cx);
+    }
+}
+
+fn capture_layout_dock(dock: &View<Dock>, cx: &WindowContext) -> SerializedLayoutDock {
+    let dock = dock.read(cx);
+    let active_panel = dock.active_panel();
+    SerializedLayoutDock {
+        visible: dock.is_open(),
+        active_panel: active_panel.map(|panel| panel.persistent_name().to_string()),
+        zoom: active_panel.map_or(false, |panel| panel.is_zoomed(cx)),
+        size: active_panel
+            .and_then(|panel| dock.panel_size(panel.as_ref(), cx))
+            .map(|size| size.0),
+    }
+}
+
+fn apply_layout_dock(dock: &mut Dock, layout: &SerializedLayoutDock, cx: &mut ViewContext<Dock>) {
+    if let Some(active_panel) = &layout.active_panel {
+        if let Some(index) = dock.panel_index_for_persistent_name(active_panel, cx) {
+            dock.activate_panel(index, cx);
+        }
+    }
+    if let Some(size) = layout.size {
+        dock.resize_active_panel(Some(px(size)), cx);
+    }
+    dock.set_open(layout.visible, cx);
+    if layout.visible && layout.zoom {
+        if let Some(panel) = dock.active_panel() {
+            panel.set_zoomed(true, cx);
+        }
+    }
+}
+
+/// Resolves a path stored in a layout against the project's visible worktrees.
+fn layout_project_path(
+    project: &Model<Project>,
+    path: &Path,
+    cx: &WindowContext,
+) -> Option<ProjectPath> {
+    project.read(cx).visible_worktrees(cx).find_map(|worktree| {
+        let worktree = worktree.read(cx);
+        worktree.entry_for_path(path)?;
+        Some(ProjectPath {
+            worktree_id: worktree.id(),
+            path: Arc::from(path),
+        })
+    })
+}
+
+/// Returns the `.zed/layouts.json` paths of the project's visible worktrees. Layouts are only
+/// shared through files in local projects.
+fn project_layout_files(project: &Model<Project>, cx: &WindowContext) -> Vec<PathBuf> {
+    let project = project.read(cx);
+    if !project.is_local() {
+        return Vec::new();
+    }
+    project
+        .visible_worktrees(cx)
+        .map(|worktree| {
+            worktree
+                .read(cx)
+                .abs_path()
+                .join(paths::local_layouts_file_relative_path())
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        item::test::{TestItem, TestProjectItem},
+        tests::init_test,
+        SplitDirection,
+    };
+    use fs::FakeFs;
+    use gpui::{Axis, TestAppContext, VisualTestContext};
+    use project::WorktreeId;
+    use serde_json::json;
+
+    #[gpui::test]
+    async fn test_capture_and_apply_layout(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree("/root", json!({ "a.txt": "", "b.txt": "", "c.txt": "" }))
+            .await;
+        let project = Project::test(fs, ["/root".as_ref()], cx).await;
+        let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project.clone(), cx));
+        let worktree_id = project.update(cx, |project, cx| {
+            project.worktrees(cx).next().unwrap().read(cx).id()
+        });
+
+        let item_a = add_item(&workspace, worktree_id, "a.txt", None, cx);
+        add_item(&workspace, worktree_id, "b.txt", None, cx);
+        let right_pane = workspace.update(cx, |workspace, cx| {
+            workspace.split_pane(workspace.active_pane().clone(), SplitDirection::Right, cx)
+        });
+        let item_c = add_item(&workspace, worktree_id, "c.txt", Some(&right_pane), cx);
+        workspace.update(cx, |workspace, cx| {
+            workspace.set_active_pane(&right_pane, cx)
+        });
+
+        let layout = workspace.update(cx, |workspace, cx| workspace.capture_layout(cx));
+        assert_eq!(
+            layout.center,
+            SerializedLayoutGroup::Group {
+                axis: Axis::Horizontal,
+                flexes: Some(vec![1.0, 1.0]),
+                children: vec![
+                    SerializedLayoutGroup::Pane {
+                        items: vec!["a.txt".into(), "b.txt".into()],
+                        active_item: Some(1),
+                        active: false,
+                    },
+                    SerializedLayoutGroup::Pane {
+                        items: vec!["c.txt".into()],
+                        active_item: Some(0),
+                        active: true,
+                    },
+                ],
+            }
+        );
+
+        // Switching to a single pane keeps the items it mentions, closes the others, and keeps
+        // items with unsaved changes around.
+        item_a.update(cx, |item, _| item.is_dirty = true);
+        workspace
+            .update(cx, |workspace, cx| {
+                workspace.apply_layout(
+                    SerializedLayout {
+                        center: SerializedLayoutGroup::Pane {
+                            items: vec!["c.txt".into(), "missing.txt".into()],
+                            active_item: Some(0),
+                            active: true,
+                        },
+                        docks: Default::default(),
+                    },
+                    cx,
+                )
+            })
+            .await
+            .unwrap();
+        workspace.update(cx, |workspace, cx| {
+            assert_eq!(workspace.center.panes().len(), 1);
+            let pane = workspace.active_pane().read(cx);
+            let item_ids = pane.items().map(|item| item.item_id()).collect::<Vec<_>>();
+            assert_eq!(item_ids, vec![item_c.item_id(), item_a.item_id()]);
+            assert_eq!(
+                pane.active_item().map(|item| item.item_id()),
+                Some(item_c.item_id())
+            );
+        });
+
+        // Restoring the captured layout brings back the split.
+        workspace
+            .update(cx, |workspace, cx| {
+                workspace.apply_layout(layout.clone(), cx)
+            })
+            .await
+            .ok();
+        workspace.update(cx, |workspace, cx| {
+            let panes = workspace.center.panes();
+            assert_eq!(panes.len(), 2);
+            assert_eq!(
+                panes[1]
+                    .read(cx)
+                    .items()
+                    .map(|item| item.item_id())
+                    .collect::<Vec<_>>(),
+                vec![item_c.item_id()]
+            );
+            assert_eq!(workspace.active_pane(), panes[1]);
+        });
+    }
+
+    fn add_item(
+        workspace: &View<Workspace>,
+        worktree_id: WorktreeId,
+        path: &str,
+        pane: Option<&View<Pane>>,
+        cx: &mut VisualTestContext,
+    ) -> View<TestItem> {
+        let project_item = cx.update(|cx| {
+            let project_item = TestProjectItem::new(0, path, cx);
+            project_item.update(cx, |project_item, _| {
+                project_item.project_path = Some(ProjectPath {
+                    worktree_id,
+                    path: Path::new(path).into(),
+                });
+            });
+            project_item
+        });
+        let item = cx.new_view(|cx| TestItem::new(cx).with_project_items(&[project_item]));
+        workspace.update(cx, |workspace, cx| {
+            let pane = pane
+                .cloned()
+                .unwrap_or_else(|| workspace.active_pane().clone());
+            pane.update(cx, |pane, cx| {
+                pane.add_item(Box::new(item.clone()), true, true, None, cx)
+            });
+        });
+        item
+    }
+}
@@ -22,8 +22,8 @@ use uuid::Uuid;
 use crate::WorkspaceId;
 
 use model::{
-    GroupId, LocalPaths, PaneId, SerializedItem, SerializedPane, SerializedPaneGroup,
-    SerializedSshProject, SerializedWorkspace,
+    GroupId, LocalPaths, PaneId, SerializedItem, SerializedLayout, SerializedPane,
+    SerializedPaneGroup, SerializedSshProject, SerializedWorkspace,
 };
 
 use self::model::{DockStructure, LocalPathsOrder, SerializedWorkspaceLocation};
@@ -383,6 +383,19 @@ define_connection! {
     sql!(
         ALTER TABLE toolchains ADD COLUMN raw_json TEXT DEFAULT "{}";
     ),
+    sql!(
+        CREATE TABLE workspace_layouts (
+            workspace_id INTEGER NOT NULL,
+            name TEXT NOT NULL,
+            layout TEXT NOT NULL,
+            PRIMARY KEY (workspace_id, name),
+            FOREIGN KEY(workspace_id) REFERENCES workspaces(workspace_id)
+            ON DELETE CASCADE
+        ) STRICT;
+    ),
+    sql!(
+        ALTER TABLE workspaces ADD COLUMN settings_profile TEXT DEFAULT NULL;
+    ),
     ];
 }
 
@@ -1074,6 +1087,47 @@ impl WorkspaceDb {
         }
     }
 
+    query! {
+        pub(crate) async fn set_settings_profile(workspace_id: WorkspaceId, settings_profile: Option<String>) -> Result<()> {
+            UPDATE workspaces
+            SET settings_profile = ?2
+            WHERE workspace_id = ?1
+        }
+    }
+
+    query! {
+        pub(crate) fn settings_profile(workspace_id: WorkspaceId) -> Result<Option<String>> {
+            SELECT settings_profile
+            FROM workspaces
+            WHERE workspace_id = ? AND settings_profile IS NOT NULL
+        }
+    }
+
+    query! {
+        pub(crate) async fn save_layout(workspace_id: WorkspaceId, name: String, layout: SerializedLayout) -> Result<()> {
+            INSERT INTO workspace_layouts(workspace_id, name, layout)
+            VALUES (?1, ?2, ?3)
+            ON CONFLICT DO
+            UPDATE SET layout = ?3
+        }
+    }
+
+    query! {
+        pub(crate) fn layouts(workspace_id: WorkspaceId) -> Result<Vec<(String, SerializedLayout)>> {
+            SELECT name, layout
+            FROM workspace_layouts
+            WHERE workspace_id = ?
+            ORDER BY name
+        }
+    }
+
+    query! {
+        pub(crate) async fn delete_layout(workspace_id: WorkspaceId, name: String) -> Result<()> {
+            DELETE FROM workspace_layouts
+            WHERE workspace_id = ?1 AND name = ?2
+        }
+    }
+
     pub async fn toolchain(
         &self,
         workspace_id: WorkspaceId,
@@ -1158,7 +1212,9 @@ impl WorkspaceDb {
 mod tests {
     use super::*;
     use crate::persistence::model::SerializedWorkspace;
-    use crate::persistence::model::{SerializedItem, SerializedPane, SerializedPaneGroup};
+    use crate::persistence::model::{
+        SerializedItem, SerializedLayoutGroup, SerializedPane, SerializedPaneGroup,
+    };
     use db::open_test_db;
     use gpui::{self};
 
@@ -1894,6 +1950,64 @@ mod tests {
         assert_eq!(stored_projects.len(), projects.len());
     }
 
+    #[gpui::test]
+    async fn test_workspace_layouts() {
+        env_logger::try_init().ok();
+
+        let db = WorkspaceDb(open_test_db("test_workspace_layouts").await);
+        let workspace_id = db.next_id().await.unwrap();
+        let other_workspace_id = db.next_id().await.unwrap();
+
+        let review = SerializedLayout {
+            center: SerializedLayoutGroup::Group {
+                axis: Axis::Horizontal,
+                flexes: Some(vec![1.0, 1.0]),
+                children: vec![
+                    SerializedLayoutGroup::Pane {
+                        items: vec!["a.rs".into()],
+                        active_item: Some(0),
+                        active: true,
+                    },
+                    SerializedLayoutGroup::Pane {
+                        items: vec!["b.rs".into()],
+                        active_item: Some(0),
+                        active: false,
+                    },
+                ],
+            },
+            docks: Default::default(),
+        };
+        let writing = SerializedLayout::default();
+
+        db.save_layout(workspace_id, "writing".into(), writing.clone())
+            .await
+            .unwrap();
+        db.save_layout(workspace_id, "review".into(), SerializedLayout::default())
+            .await
+            .unwrap();
+        // Saving a layout under an existing name replaces it.
+        db.save_layout(workspace_id, "review".into(), review.clone())
+            .await
+            .unwrap();
+
+        assert_eq!(
+            db.layouts(workspace_id).unwrap(),
+            vec![
+                ("review".to_string(), review.clone()),
+                ("writing".to_string(), writing.clone())
+            ]
+        );
+        assert_eq!(db.layouts(other_workspace_id).unwrap(), Vec::new());
+
+        db.delete_layout(workspace_id, "writing".into())
+            .await
+            .unwrap();
+        assert_eq!(
+            db.layouts(workspace_id).unwrap(),
+            vec![("review".to_string(), review)]
+        );
+    }
+
     #[gpui::test]
     async fn test_simple_split() {
         env_logger::try_init().ok();
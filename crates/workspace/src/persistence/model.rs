@@ -547,6 +547,86 @@ impl Column for SerializedItem {
     }
 }
 
+/// A named arrangement of the center panes and docks that can be switched to on demand.
+///
+/// Unlike [`SerializedWorkspace`], layouts refer to items by their path within the project
+/// rather than by item id, which lets them be shared with others through `.zed/layouts.json`.
+#[derive(Debug, PartialEq, Clone, Default, Deserialize, Serialize)]
+pub struct SerializedLayout {
+    #[serde(default)]
+    pub center: SerializedLayoutGroup,
+    #[serde(default)]
+    pub docks: SerializedLayoutDocks,
+}
+
+#[derive(Debug, PartialEq, Clone, Deserialize, Serialize)]
+#[serde(untagged)]
+pub enum SerializedLayoutGroup {
+    Group {
+        axis: gpui::Axis,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        flexes: Option<Vec<f32>>,
+        children: Vec<SerializedLayoutGroup>,
+    },
+    Pane {
+        /// Paths of the pane's items, relative to the root of the worktree that contains them.
+        #[serde(default)]
+        items: Vec<PathBuf>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        active_item: Option<usize>,
+        #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+        active: bool,
+    },
+}
+
+impl Default for SerializedLayoutGroup {
+    fn default() -> Self {
+        Self::Pane {
+            items: Vec::new(),
+            active_item: None,
+            active: true,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Default, Deserialize, Serialize)]
+pub struct SerializedLayoutDocks {
+    #[serde(default)]
+    pub left: SerializedLayoutDock,
+    #[serde(default)]
+    pub right: SerializedLayoutDock,
+    #[serde(default)]
+    pub bottom: SerializedLayoutDock,
+}
+
+#[derive(Debug, PartialEq, Clone, Default, Deserialize, Serialize)]
+pub struct SerializedLayoutDock {
+    #[serde(default)]
+    pub visible: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub active_panel: Option<String>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub zoom: bool,
+    /// The size of the active panel, in pixels.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub size: Option<f32>,
+}
+
+impl StaticColumnCount for SerializedLayout {}
+impl Bind for SerializedLayout {
+    fn bind(&self, statement: &Statement, start_index: i32) -> Result<i32> {
+        statement.bind(&serde_json::to_string(self)?, start_index)
+    }
+}
+
+impl Column for SerializedLayout {
+    fn column(statement: &mut Statement, start_index: i32) -> Result<(Self, i32)> {
+        let raw_layout = statement.column_text(start_index)?;
+        let layout = serde_json::from_str(raw_layout).context("deserializing workspace layout")?;
+        Ok((layout, start_index + 1))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -564,4 +644,59 @@ mod tests {
             )
         );
     }
+
+    #[test]
+    fn test_layout_json() {
+        let layout: SerializedLayout = serde_json::from_str(
+            r#"{
+                "center": {
+                    "axis": "Horizontal",
+                    "flexes": [2.0, 1.0],
+                    "children": [
+                        { "items": ["src/main.rs", "src/lib.rs"], "active_item": 1, "active": true },
+                        { "items": ["README.md"] }
+                    ]
+                },
+                "docks": {
+                    "left": { "visible": true, "active_panel": "ProjectPanel", "size": 240.0 }
+                }
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            layout,
+            SerializedLayout {
+                center: SerializedLayoutGroup::Group {
+                    axis: gpui::Axis::Horizontal,
+                    flexes: Some(vec![2.0, 1.0]),
+                    children: vec![
+                        SerializedLayoutGroup::Pane {
+                            items: vec!["src/main.rs".into(), "src/lib.rs".into()],
+                            active_item: Some(1),
+                            active: true,
+                        },
+                        SerializedLayoutGroup::Pane {
+                            items: vec!["README.md".into()],
+                            active_item: None,
+                            active: false,
+                        },
+                    ],
+                },
+                docks: SerializedLayoutDocks {
+                    left: SerializedLayoutDock {
+                        visible: true,
+                        active_panel: Some("ProjectPanel".into()),
+                        zoom: false,
+                        size: Some(240.0),
+                    },
+                    ..Default::default()
+                },
+            }
+        );
+
+        let round_trip: SerializedLayout =
+            serde_json::from_str(&serde_json::to_string(&layout).unwrap()).unwrap();
+        assert_eq!(round_trip, layout);
+    }
 }
@@ -1,5 +1,6 @@
 pub mod dock;
 pub mod item;
+mod layouts;
 mod modal_layer;
 pub mod notifications;
 pub mod pane;
@@ -45,6 +46,7 @@ pub use item::{
 };
 use itertools::Itertools;
 use language::{LanguageRegistry, Rope};
+pub use layouts::{ApplyLayout, LayoutSource, NamedLayout, SaveLayout};
 pub use modal_layer::*;
 use node_runtime::NodeRuntime;
 use notifications::{
@@ -53,7 +55,10 @@ use notifications::{
 pub use pane::*;
 pub use pane_group::*;
 pub use persistence::{
-    model::{ItemId, LocalPaths, SerializedWorkspaceLocation},
+    model::{
+        ItemId, LocalPaths, SerializedLayout, SerializedLayoutDock, SerializedLayoutDocks,
+        SerializedLayoutGroup, SerializedWorkspaceLocation,
+    },
     WorkspaceDb, DB as WORKSPACE_DB,
 };
 use persistence::{
@@ -67,7 +72,7 @@ use project::{
 use remote::{ssh_session::ConnectionIdentifier, SshClientDelegate, SshConnectionOptions};
 use serde::Deserialize;
 use session::AppSession;
-use settings::Settings;
+use settings::{Settings, SettingsStore};
 use shared_screen::SharedScreen;
 use sqlez::{
     bindable::{Bind, Column, StaticColumnCount},
@@ -222,6 +227,7 @@ impl_actions!(
         ActivatePaneInDirection,
         CloseAllItemsAndPanes,
         CloseInactiveTabsAndPanes,
+        OpenImageDiff,
         OpenTerminal,
         Reload,
         Save,
@@ -287,6 +293,12 @@ pub struct OpenTerminal {
     pub working_directory: PathBuf,
 }
 
+/// Compares the image at the given path with its version in git.
+#[derive(Debug, Default, Clone, Deserialize, PartialEq)]
+pub struct OpenImageDiff {
+    pub abs_path: PathBuf,
+}
+
 #[derive(Clone, Copy, Debug, Default, Hash, PartialEq, Eq, PartialOrd, Ord)]
 pub struct WorkspaceId(i64);
 
@@ -757,6 +769,7 @@ pub struct Workspace {
     pane_history_timestamp: Arc<AtomicUsize>,
     bounds: Bounds<Pixels>,
     centered_layout: bool,
+    settings_profile: Option<String>,
     bounds_save_task_queued: Option<Task<()>>,
     on_prompt_for_new_path: Option<PromptForNewPath>,
     on_prompt_for_open_path: Option<PromptForOpenPath>,
@@ -1062,6 +1075,8 @@ impl Workspace {
             // This data will be incorrect, but it will be overwritten by the time it needs to be used.
             bounds: Default::default(),
             centered_layout: false,
+            settings_profile: workspace_id
+                .and_then(|workspace_id| DB.settings_profile(workspace_id).log_err().flatten()),
             bounds_save_task_queued: None,
             on_prompt_for_new_path: None,
             on_prompt_for_open_path: None,
@@ -3976,6 +3991,7 @@ impl Workspace {
     pub fn on_window_activation_changed(&mut self, cx: &mut ViewContext<Self>) {
         if cx.is_window_active() {
             self.update_active_view_for_followers(cx);
+            self.activate_settings_profile(cx);
 
             if let Some(database_id) = self.database_id {
                 cx.background_executor()
@@ -4408,6 +4424,8 @@ impl Workspace {
             .on_action(cx.listener(Self::follow_next_collaborator))
             .on_action(cx.listener(Self::close_window))
             .on_action(cx.listener(Self::activate_pane_at_index))
+            .on_action(cx.listener(Self::save_layout_action))
+            .on_action(cx.listener(Self::apply_layout_action))
             .on_action(cx.listener(|workspace, _: &Unfollow, cx| {
                 let pane = workspace.active_pane().clone();
                 workspace.unfollow_in_pane(&pane, cx);
@@ -4546,6 +4564,31 @@ impl Workspace {
         cx.notify();
     }
 
+    /// The settings profile that is activated whenever this workspace's window is.
+    pub fn settings_profile(&self) -> Option<&str> {
+        self.settings_profile.as_deref()
+    }
+
+    pub fn set_settings_profile(&mut self, profile: Option<String>, cx: &mut ViewContext<Self>) {
+        self.settings_profile = profile;
+        self.activate_settings_profile(cx);
+        if let Some(database_id) = self.database_id() {
+            cx.background_executor()
+                .spawn(DB.set_settings_profile(database_id, self.settings_profile.clone()))
+                .detach_and_log_err(cx);
+        }
+        cx.notify();
+    }
+
+    fn activate_settings_profile(&self, cx: &mut ViewContext<Self>) {
+        if cx.global::<SettingsStore>().active_profile() == self.settings_profile() {
+            return;
+        }
+        let profile = self.settings_profile.clone();
+        SettingsStore::update_global(cx, |store, cx| store.set_active_profile(profile, cx))
+            .log_err();
+    }
+
     fn adjust_padding(padding: Option<f32>) -> f32 {
         padding
             .unwrap_or(Self::DEFAULT_PADDING)
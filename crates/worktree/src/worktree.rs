@@ -53,8 +53,9 @@ use std::{
     ffi::OsStr,
     fmt,
     future::Future,
+    io::{self, Read as _},
     mem,
-    ops::{AddAssign, Deref, DerefMut, Sub},
+    ops::{AddAssign, Deref, DerefMut, Range, Sub},
     path::{Path, PathBuf},
     pin::Pin,
     sync::{
@@ -103,7 +104,11 @@ pub enum CreatedEntry {
 
 pub struct LoadedFile {
     pub file: Arc<File>,
-    pub text: String,
+    /// The file's contents, with their line endings normalized.
+    pub text: Rope,
+    pub line_ending: LineEnding,
+    /// Whether the file exceeds the `large_file_threshold_mb` setting.
+    pub is_large: bool,
 }
 
 pub struct LoadedBinaryFile {
@@ -710,6 +715,33 @@ impl Worktree {
     }
 
     pub fn load_staged_file(&self, path: &Path, cx: &AppContext) -> Task<Result<Option<String>>> {
+        self.load_from_git_repository(path, cx, |repo, repo_path| repo.load_index_text(repo_path))
+    }
+
+    /// Loads the contents of the file at the given path as it is staged in the index.
+    pub fn load_staged_binary_file(
+        &self,
+        path: &Path,
+        cx: &AppContext,
+    ) -> Task<Result<Option<Vec<u8>>>> {
+        self.load_from_git_repository(path, cx, |repo, repo_path| repo.load_index_bytes(repo_path))
+    }
+
+    /// Loads the contents of the file at the given path as of the repository's HEAD commit.
+    pub fn load_committed_binary_file(
+        &self,
+        path: &Path,
+        cx: &AppContext,
+    ) -> Task<Result<Option<Vec<u8>>>> {
+        self.load_from_git_repository(path, cx, |repo, repo_path| repo.load_head_bytes(repo_path))
+    }
+
+    fn load_from_git_repository<T: Send + 'static>(
+        &self,
+        path: &Path,
+        cx: &AppContext,
+        load: impl FnOnce(&dyn GitRepository, &RepoPath) -> Option<T> + Send + 'static,
+    ) -> Task<Result<Option<T>>> {
         match self {
             Worktree::Local(this) => {
                 let path = Arc::from(path);
@@ -720,16 +752,16 @@ impl Worktree {
                             if let Some(git_repo) =
                                 snapshot.git_repositories.get(&*repo.work_directory)
                             {
-                                return Ok(git_repo.repo_ptr.load_index_text(&repo_path));
+                                return Ok(load(git_repo.repo_ptr.as_ref(), &repo_path));
                             }
                         }
                     }
                     Ok(None)
                 })
             }
-            Worktree::Remote(_) => {
-                Task::ready(Err(anyhow!("remote worktrees can't yet load staged files")))
-            }
+            Worktree::Remote(_) => Task::ready(Err(anyhow!(
+                "remote worktrees can't yet load files from git"
+            ))),
         }
     }
 
@@ -746,6 +778,66 @@ impl Worktree {
         }
     }
 
+    /// Reads the raw bytes of a file in the given range, or fewer if the file ends before it,
+    /// whether or not the file is in the worktree's snapshot.
+    pub fn read_file(
+        &self,
+        path: &Path,
+        range: Range<u64>,
+        cx: &ModelContext<Worktree>,
+    ) -> Task<Result<Vec<u8>>> {
+        let worktree_id = self.id();
+        match self {
+            Worktree::Local(this) => {
+                let abs_path = this.absolutize(path);
+                let fs = this.fs.clone();
+                cx.background_executor()
+                    .spawn(async move { fs.load_bytes_range(&abs_path?, range).await })
+            }
+            Worktree::Remote(this) => {
+                let request = this.client.request(proto::ReadProjectFile {
+                    project_id: this.project_id,
+                    worktree_id: worktree_id.to_proto(),
+                    path: path.to_string_lossy().into(),
+                    offset: range.start,
+                    length: range.end.saturating_sub(range.start),
+                });
+                cx.background_executor()
+                    .spawn(async move { Ok(request.await?.content) })
+            }
+        }
+    }
+
+    /// Appends bytes to the end of a file, so that large files can be written in chunks.
+    pub fn append_to_file(
+        &self,
+        path: &Path,
+        content: Vec<u8>,
+        cx: &ModelContext<Worktree>,
+    ) -> Task<Result<()>> {
+        let worktree_id = self.id();
+        match self {
+            Worktree::Local(this) => {
+                let abs_path = this.absolutize(path);
+                let fs = this.fs.clone();
+                cx.background_executor()
+                    .spawn(async move { fs.append_bytes(&abs_path?, &content).await })
+            }
+            Worktree::Remote(this) => {
+                let request = this.client.request(proto::AppendToProjectFile {
+                    project_id: this.project_id,
+                    worktree_id: worktree_id.to_proto(),
+                    path: path.to_string_lossy().into(),
+                    content,
+                });
+                cx.background_executor().spawn(async move {
+                    request.await?;
+                    Ok(())
+                })
+            }
+        }
+    }
+
     pub fn write_file(
         &self,
         path: &Path,
@@ -761,16 +853,18 @@ impl Worktree {
         }
     }
 
+    /// Creates a file or directory at the given path. Files are empty, unless `content` is given.
     pub fn create_entry(
         &mut self,
         path: impl Into<Arc<Path>>,
         is_directory: bool,
+        content: Option<Vec<u8>>,
         cx: &ModelContext<Worktree>,
     ) -> Task<Result<CreatedEntry>> {
         let path = path.into();
         let worktree_id = self.id();
         match self {
-            Worktree::Local(this) => this.create_entry(path, is_directory, cx),
+            Worktree::Local(this) => this.create_entry(path, is_directory, content, cx),
             Worktree::Remote(this) => {
                 let project_id = this.project_id;
                 let request = this.client.request(proto::CreateProjectEntry {
@@ -778,6 +872,7 @@ impl Worktree {
                     project_id,
                     path: path.to_string_lossy().into(),
                     is_directory,
+                    content,
                 });
                 cx.spawn(move |this, mut cx| async move {
                     let response = request.await?;
@@ -948,7 +1043,12 @@ impl Worktree {
         let (scan_id, entry) = this.update(&mut cx, |this, cx| {
             (
                 this.scan_id(),
-                this.create_entry(PathBuf::from(request.path), request.is_directory, cx),
+                this.create_entry(
+                    PathBuf::from(request.path),
+                    request.is_directory,
+                    request.content,
+                    cx,
+                ),
             )
         })?;
         Ok(proto::ProjectEntryResponse {
@@ -960,6 +1060,32 @@ impl Worktree {
         })
     }
 
+    pub async fn handle_read_file(
+        this: Model<Self>,
+        request: proto::ReadProjectFile,
+        mut cx: AsyncAppContext,
+    ) -> Result<proto::ReadProjectFileResponse> {
+        let range = request.offset..request.offset.saturating_add(request.length);
+        let content = this
+            .update(&mut cx, |this, cx| {
+                this.read_file(Path::new(&request.path), range, cx)
+            })?
+            .await?;
+        Ok(proto::ReadProjectFileResponse { content })
+    }
+
+    pub async fn handle_append_to_file(
+        this: Model<Self>,
+        request: proto::AppendToProjectFile,
+        mut cx: AsyncAppContext,
+    ) -> Result<proto::Ack> {
+        this.update(&mut cx, |this, cx| {
+            this.append_to_file(Path::new(&request.path), request.content, cx)
+        })?
+        .await?;
+        Ok(proto::Ack {})
+    }
+
     pub async fn handle_delete_entry(
         this: Model<Self>,
         request: proto::DeleteProjectEntry,
@@ -1387,10 +1513,29 @@ impl LocalWorktree {
         let fs = self.fs.clone();
         let entry = self.refresh_entry(path.clone(), None, cx);
         let is_private = self.is_path_private(path.as_ref());
+        let settings = self.settings.clone();
 
-        cx.spawn(|this, _cx| async move {
+        cx.spawn(|this, cx| async move {
             let abs_path = abs_path?;
-            let text = fs.load(&abs_path).await?;
+            let is_large = fs
+                .metadata(&abs_path)
+                .await?
+                .map_or(false, |metadata| settings.is_large_file(metadata.len));
+            let (text, line_ending) = if is_large {
+                let reader = fs.open_sync(&abs_path).await?;
+                cx.background_executor()
+                    .spawn(async move { read_text_in_pages(reader) })
+                    .await?
+            } else {
+                let mut text = fs.load(&abs_path).await?;
+                cx.background_executor()
+                    .spawn(async move {
+                        let line_ending = LineEnding::detect(&text);
+                        LineEnding::normalize(&mut text);
+                        (Rope::from(text), line_ending)
+                    })
+                    .await
+            };
 
             let worktree = this
                 .upgrade()
@@ -1420,7 +1565,12 @@ impl LocalWorktree {
                 }
             };
 
-            Ok(LoadedFile { file, text })
+            Ok(LoadedFile {
+                file,
+                text,
+                line_ending,
+                is_large,
+            })
         })
     }
 
@@ -1441,6 +1591,7 @@ impl LocalWorktree {
         &self,
         path: impl Into<Arc<Path>>,
         is_dir: bool,
+        content: Option<Vec<u8>>,
         cx: &ModelContext<Worktree>,
     ) -> Task<Result<CreatedEntry>> {
         let path = path.into();
@@ -1456,6 +1607,15 @@ impl LocalWorktree {
                 fs.create_dir(&task_abs_path)
                     .await
                     .with_context(|| format!("creating directory {task_abs_path:?}"))
+            } else if let Some(content) = content {
+                if let Some(parent) = task_abs_path.parent() {
+                    fs.create_dir(parent)
+                        .await
+                        .with_context(|| format!("creating directory {parent:?}"))?;
+                }
+                fs.create_file_with(&task_abs_path, Pin::new(&mut content.as_slice()))
+                    .await
+                    .with_context(|| format!("creating file {task_abs_path:?}"))
             } else {
                 fs.save(&task_abs_path, &Rope::default(), LineEnding::default())
                     .await
@@ -5110,6 +5270,45 @@ fn char_bag_for_path(root_char_bag: CharBag, path: &Path) -> CharBag {
     result
 }
 
+/// Reads a large file into a rope one page at a time, normalizing its line endings, so that the
+/// file's contents are never held in memory both as a string and as a rope.
+///
+/// The whole file still ends up in memory: buffers are backed by a [`Rope`], which can't page its
+/// contents from disk, so this only bounds the peak memory used while loading.
+fn read_text_in_pages(mut reader: impl io::Read) -> Result<(Rope, LineEnding)> {
+    const PAGE_SIZE: u64 = 1024 * 1024;
+
+    let mut text = Rope::new();
+    let mut line_ending = None;
+    let mut carried_over = Vec::new();
+    loop {
+        let mut page = mem::take(&mut carried_over);
+        let read = (&mut reader).take(PAGE_SIZE).read_to_end(&mut page)?;
+        if read > 0 {
+            // Leave an incomplete character, or a `\r` that may be followed by a `\n`, for the
+            // next page.
+            let mut end = match std::str::from_utf8(&page) {
+                Err(error) if error.error_len().is_none() => error.valid_up_to(),
+                _ => page.len(),
+            };
+            if page[..end].ends_with(b"\r") {
+                end -= 1;
+            }
+            carried_over = page.split_off(end);
+        }
+
+        let mut page = String::from_utf8(page)?;
+        line_ending.get_or_insert_with(|| LineEnding::detect(&page));
+        LineEnding::normalize(&mut page);
+        text.push(&page);
+
+        if read == 0 {
+            break;
+        }
+    }
+    Ok((text, line_ending.unwrap_or_default()))
+}
+
 struct RepoPaths {
     repo: Arc<dyn GitRepository>,
     relative_paths: Vec<Arc<Path>>,
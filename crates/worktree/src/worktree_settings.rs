@@ -7,11 +7,16 @@ use serde::{Deserialize, Serialize};
 use settings::{Settings, SettingsSources};
 use util::paths::PathMatcher;
 
+/// The default for the `large_file_threshold_mb` setting.
+const DEFAULT_LARGE_FILE_THRESHOLD_MB: u64 = 50;
+
 #[derive(Clone, PartialEq, Eq)]
 pub struct WorktreeSettings {
     pub file_scan_inclusions: PathMatcher,
     pub file_scan_exclusions: PathMatcher,
     pub private_files: PathMatcher,
+    /// The size in bytes above which files are opened in large-file mode.
+    pub large_file_threshold: u64,
 }
 
 impl WorktreeSettings {
@@ -29,6 +34,10 @@ impl WorktreeSettings {
         path.ancestors()
             .any(|ancestor| self.file_scan_inclusions.is_match(&ancestor))
     }
+
+    pub fn is_large_file(&self, size: u64) -> bool {
+        size > self.large_file_threshold
+    }
 }
 
 #[derive(Clone, Default, Serialize, Deserialize, JsonSchema)]
@@ -62,6 +71,13 @@ pub struct WorktreeSettingsContent {
     /// Treat the files matching these globs as `.env` files.
     /// Default: [ "**/.env*" ]
     pub private_files: Option<Vec<String>>,
+
+    /// Open files larger than this many megabytes in large-file mode, where they are
+    /// read-only, and syntax highlighting, language servers, git diffs and inlay
+    /// hints are disabled until full editing is explicitly enabled.
+    ///
+    /// Default: 50
+    pub large_file_threshold_mb: Option<u64>,
 }
 
 impl Settings for WorktreeSettings {
@@ -97,6 +113,10 @@ impl Settings for WorktreeSettings {
                 &parsed_file_scan_inclusions,
                 "file_scan_inclusions",
             )?,
+            large_file_threshold: result
+                .large_file_threshold_mb
+                .unwrap_or(DEFAULT_LARGE_FILE_THRESHOLD_MB)
+                .saturating_mul(1024 * 1024),
         })
     }
 }
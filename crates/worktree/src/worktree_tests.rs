@@ -19,6 +19,7 @@ use std::{
     path::{Path, PathBuf},
     sync::Arc,
 };
+use text::LineEnding;
 use util::{test::temp_tree, ResultExt};
 
 #[gpui::test]
@@ -1417,7 +1418,7 @@ async fn test_create_directory_during_initial_scan(cx: &mut TestAppContext) {
         .update(cx, |tree, cx| {
             tree.as_local_mut()
                 .unwrap()
-                .create_entry("a/e".as_ref(), true, cx)
+                .create_entry("a/e".as_ref(), true, None, cx)
         })
         .await
         .unwrap()
@@ -1536,7 +1537,7 @@ async fn test_create_dir_all_on_create_entry(cx: &mut TestAppContext) {
         .update(cx, |tree, cx| {
             tree.as_local_mut()
                 .unwrap()
-                .create_entry("a/b/c/d.txt".as_ref(), false, cx)
+                .create_entry("a/b/c/d.txt".as_ref(), false, None, cx)
         })
         .await
         .unwrap()
@@ -1570,7 +1571,7 @@ async fn test_create_dir_all_on_create_entry(cx: &mut TestAppContext) {
         .update(cx, |tree, cx| {
             tree.as_local_mut()
                 .unwrap()
-                .create_entry("a/b/c/d.txt".as_ref(), false, cx)
+                .create_entry("a/b/c/d.txt".as_ref(), false, None, cx)
         })
         .await
         .unwrap()
@@ -1590,7 +1591,7 @@ async fn test_create_dir_all_on_create_entry(cx: &mut TestAppContext) {
         .update(cx, |tree, cx| {
             tree.as_local_mut()
                 .unwrap()
-                .create_entry("a/b/c/e.txt".as_ref(), false, cx)
+                .create_entry("a/b/c/e.txt".as_ref(), false, None, cx)
         })
         .await
         .unwrap()
@@ -1608,7 +1609,7 @@ async fn test_create_dir_all_on_create_entry(cx: &mut TestAppContext) {
         .update(cx, |tree, cx| {
             tree.as_local_mut()
                 .unwrap()
-                .create_entry("d/e/f/g.txt".as_ref(), false, cx)
+                .create_entry("d/e/f/g.txt".as_ref(), false, None, cx)
         })
         .await
         .unwrap()
@@ -1625,6 +1626,78 @@ async fn test_create_dir_all_on_create_entry(cx: &mut TestAppContext) {
     });
 }
 
+#[gpui::test]
+async fn test_create_entry_with_content(cx: &mut TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.background_executor.clone());
+    fs.insert_tree("/root", json!({ "a": {} })).await;
+
+    let tree = Worktree::local(
+        "/root".as_ref(),
+        true,
+        fs.clone(),
+        Default::default(),
+        &mut cx.to_async(),
+    )
+    .await
+    .unwrap();
+
+    let content = vec![0, 159, 146, 150, b'\n'];
+    let entry = tree
+        .update(cx, |tree, cx| {
+            tree.create_entry(Path::new("a/b/image.bin"), false, Some(content.clone()), cx)
+        })
+        .await
+        .unwrap()
+        .to_included()
+        .unwrap();
+    assert!(entry.is_file());
+    assert_eq!(
+        fs.load_bytes("/root/a/b/image.bin".as_ref()).await.unwrap(),
+        content
+    );
+
+    let read = tree
+        .update(cx, |tree, cx| {
+            tree.read_file(Path::new("a/b/image.bin"), 0..1024, cx)
+        })
+        .await
+        .unwrap();
+    assert_eq!(read, content);
+
+    // Files can be read and written in chunks.
+    tree.update(cx, |tree, cx| {
+        tree.append_to_file(Path::new("a/b/image.bin"), b"more".to_vec(), cx)
+    })
+    .await
+    .unwrap();
+    let read = tree
+        .update(cx, |tree, cx| {
+            tree.read_file(Path::new("a/b/image.bin"), 3..7, cx)
+        })
+        .await
+        .unwrap();
+    assert_eq!(read, [150, b'\n', b'm', b'o']);
+    let read = tree
+        .update(cx, |tree, cx| {
+            tree.read_file(Path::new("a/b/image.bin"), 7..1024, cx)
+        })
+        .await
+        .unwrap();
+    assert_eq!(read, b"re");
+
+    // Paths outside of the worktree can't be read.
+    assert!(tree
+        .update(cx, |tree, cx| tree.read_file(
+            Path::new("../root"),
+            0..1024,
+            cx
+        ))
+        .await
+        .is_err());
+}
+
 #[gpui::test(iterations = 100)]
 async fn test_random_worktree_operations_during_initial_scan(
     cx: &mut TestAppContext,
@@ -1956,7 +2029,7 @@ fn randomly_mutate_worktree(
                     if is_dir { "dir" } else { "file" },
                     child_path,
                 );
-                let task = worktree.create_entry(child_path, is_dir, cx);
+                let task = worktree.create_entry(child_path, is_dir, None, cx);
                 cx.background_executor().spawn(async move {
                     task.await?;
                     Ok(())
@@ -2612,6 +2685,68 @@ async fn test_repository_subfolder_git_status(cx: &mut TestAppContext) {
     });
 }
 
+#[gpui::test]
+async fn test_load_binary_files_from_git(cx: &mut TestAppContext) {
+    init_test(cx);
+    let fs = FakeFs::new(cx.background_executor.clone());
+    fs.insert_tree(
+        "/root",
+        json!({
+            ".git": {},
+            "a.png": "working",
+            "b.png": "added",
+        }),
+    )
+    .await;
+    fs.set_head_for_repo(
+        Path::new("/root/.git"),
+        &[(Path::new("a.png"), "committed".into())],
+    );
+    fs.set_index_for_repo(
+        Path::new("/root/.git"),
+        &[
+            (Path::new("a.png"), "staged".into()),
+            (Path::new("b.png"), "added".into()),
+        ],
+    );
+
+    let tree = Worktree::local(
+        Path::new("/root"),
+        true,
+        fs.clone(),
+        Default::default(),
+        &mut cx.to_async(),
+    )
+    .await
+    .unwrap();
+    cx.executor().run_until_parked();
+
+    let committed = tree
+        .read_with(cx, |tree, cx| {
+            tree.load_committed_binary_file(Path::new("a.png"), cx)
+        })
+        .await
+        .unwrap();
+    assert_eq!(committed, Some(b"committed".to_vec()));
+
+    // Files that were added since the last commit have no committed contents.
+    let committed = tree
+        .read_with(cx, |tree, cx| {
+            tree.load_committed_binary_file(Path::new("b.png"), cx)
+        })
+        .await
+        .unwrap();
+    assert_eq!(committed, None);
+
+    let staged = tree
+        .read_with(cx, |tree, cx| {
+            tree.load_staged_binary_file(Path::new("a.png"), cx)
+        })
+        .await
+        .unwrap();
+    assert_eq!(staged, Some(b"staged".to_vec()));
+}
+
 #[gpui::test]
 async fn test_propagate_git_statuses(cx: &mut TestAppContext) {
     init_test(cx);
@@ -2754,6 +2889,24 @@ fn check_propagated_statuses(
     );
 }
 
+#[test]
+fn test_read_text_in_pages() {
+    const PAGE_SIZE: usize = 1024 * 1024;
+
+    // A multi-byte character spans the first two pages, and a `\r\n` the next two.
+    let mut text = String::from("one\r\n");
+    text.push_str(&"a".repeat(PAGE_SIZE - text.len() - 1));
+    text.push('é');
+    text.push_str(&"b".repeat(2 * PAGE_SIZE - text.len() - 1));
+    text.push_str("\r\ntwo\r\n");
+
+    let (rope, line_ending) = crate::read_text_in_pages(text.as_bytes()).unwrap();
+    assert_eq!(line_ending, LineEnding::Windows);
+    assert_eq!(rope.to_string(), text.replace("\r\n", "\n"));
+
+    assert!(crate::read_text_in_pages(&b"\xff\xfe"[..]).is_err());
+}
+
 #[track_caller]
 fn git_init(path: &Path) -> git2::Repository {
     git2::Repository::init(path).expect("Failed to initialize git repository")
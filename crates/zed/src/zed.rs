@@ -17,8 +17,8 @@ use breadcrumbs::Breadcrumbs;
 use client::{zed_urls, ZED_URL_SCHEME};
 use collections::VecDeque;
 use command_palette_hooks::CommandPaletteFilter;
-use editor::ProposedChangesEditorToolbar;
 use editor::{scroll::Autoscroll, Editor, MultiBuffer};
+use editor::{LargeFileBanner, ProposedChangesEditorToolbar};
 use feature_flags::FeatureFlagAppExt;
 use futures::{channel::mpsc, select_biased, StreamExt};
 use gpui::{
@@ -249,6 +249,10 @@ pub fn initialize_workspace(
                 workspace_handle.clone(),
                 cx.clone(),
             );
+            let review_panel =
+                collab_ui::review_panel::ReviewPanel::load(workspace_handle.clone(), cx.clone());
+            let variable_explorer =
+                repl::VariableExplorer::load(workspace_handle.clone(), cx.clone());
 
             let (
                 project_panel,
@@ -257,6 +261,8 @@ pub fn initialize_workspace(
                 channels_panel,
                 chat_panel,
                 notification_panel,
+                review_panel,
+                variable_explorer,
             ) = futures::try_join!(
                 project_panel,
                 outline_panel,
@@ -264,6 +270,8 @@ pub fn initialize_workspace(
                 channels_panel,
                 chat_panel,
                 notification_panel,
+                review_panel,
+                variable_explorer,
             )?;
 
             workspace_handle.update(&mut cx, |workspace, cx| {
@@ -273,6 +281,8 @@ pub fn initialize_workspace(
                 workspace.add_panel(channels_panel, cx);
                 workspace.add_panel(chat_panel, cx);
                 workspace.add_panel(notification_panel, cx);
+                workspace.add_panel(review_panel, cx);
+                workspace.add_panel(variable_explorer, cx);
             })?;
             let git_ui_enabled = git_ui_feature_flag.await || is_staff;
 
@@ -569,6 +579,13 @@ pub fn initialize_workspace(
                         .toggle_panel_focus::<collab_ui::notification_panel::NotificationPanel>(cx);
                 },
             )
+            .register_action(
+                |workspace: &mut Workspace,
+                 _: &collab_ui::review_panel::ToggleFocus,
+                 cx: &mut ViewContext<Workspace>| {
+                    workspace.toggle_panel_focus::<collab_ui::review_panel::ReviewPanel>(cx);
+                },
+            )
             .register_action(
                 |workspace: &mut Workspace,
                  _: &terminal_panel::ToggleFocus,
@@ -653,6 +670,8 @@ fn initialize_pane(workspace: &Workspace, pane: &View<Pane>, cx: &mut ViewContex
             toolbar.add_item(multibuffer_hint, cx);
             let breadcrumbs = cx.new_view(|_| Breadcrumbs::new());
             toolbar.add_item(breadcrumbs, cx);
+            let large_file_banner = cx.new_view(|_| LargeFileBanner::new());
+            toolbar.add_item(large_file_banner, cx);
             let buffer_search_bar = cx.new_view(search::BufferSearchBar::new);
             toolbar.add_item(buffer_search_bar.clone(), cx);
 
@@ -76,6 +76,19 @@ pub mod theme_selector {
     impl_actions!(theme_selector, [Toggle]);
 }
 
+pub mod icon_theme_selector {
+    use gpui::impl_actions;
+    use serde::Deserialize;
+
+    #[derive(PartialEq, Clone, Default, Debug, Deserialize)]
+    pub struct Toggle {
+        /// A list of icon theme names to filter the icon theme selector down to.
+        pub themes_filter: Option<Vec<String>>,
+    }
+
+    impl_actions!(icon_theme_selector, [Toggle]);
+}
+
 #[derive(Clone, Default, Deserialize, PartialEq)]
 pub struct InlineAssist {
     pub prompt: Option<String>,
@@ -89,7 +102,7 @@ pub struct OpenRecent {
     pub create_new_window: bool,
 }
 gpui::impl_actions!(projects, [OpenRecent]);
-gpui::actions!(projects, [OpenRemote]);
+gpui::actions!(projects, [OpenRemote, OpenDevContainer]);
 
 /// Where to spawn the task in the UI.
 #[derive(Default, Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
@@ -138,6 +138,43 @@ impl zed::Extension for TestExtension {
             code,
         })
     }
+
+    fn language_task_templates(&self, language_name: String) -> Result<Vec<zed::TaskTemplate>> {
+        if language_name != "Gleam" {
+            return Ok(Vec::new());
+        }
+
+        Ok(vec![zed::TaskTemplate {
+            label: "gleam run $ZED_CUSTOM_MODULE".into(),
+            command: "gleam".into(),
+            args: vec!["run".into(), "--module".into(), "$ZED_CUSTOM_MODULE".into()],
+            env: Vec::new(),
+            cwd: None,
+            tags: Vec::new(),
+        }])
+    }
+
+    fn language_task_variables(
+        &self,
+        _language_name: String,
+        variables: zed::EnvVars,
+    ) -> Result<zed::EnvVars> {
+        let module = variables
+            .into_iter()
+            .find_map(|(name, value)| (name == "ZED_STEM").then_some(value))
+            .unwrap_or_default();
+        Ok(vec![("MODULE".into(), module)])
+    }
+
+    fn run_tool(&self, tool: zed::Tool, input: String, project: &zed::Project) -> Result<String> {
+        match tool.name.as_str() {
+            "echo" => Ok(format!(
+                "{input} ({} worktrees)",
+                project.worktree_ids().len()
+            )),
+            name => Err(format!("unknown tool {name:?}")),
+        }
+    }
 }
 
 zed::register_extension!(TestExtension);